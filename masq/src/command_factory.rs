@@ -1,6 +1,7 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 use crate::command_factory::CommandFactoryError::{CommandSyntax, UnrecognizedSubcommand};
+use crate::commands::banned_command::BannedCommand;
 use crate::commands::change_password_command::ChangePasswordCommand;
 use crate::commands::check_password_command::CheckPasswordCommand;
 use crate::commands::commands_common::Command;
@@ -8,9 +9,15 @@ use crate::commands::configuration_command::ConfigurationCommand;
 use crate::commands::connection_status_command::ConnectionStatusCommand;
 use crate::commands::crash_command::CrashCommand;
 use crate::commands::descriptor_command::DescriptorCommand;
+use crate::commands::excluded_nodes_command::ExcludedNodesCommand;
+use crate::commands::exit_locations_command::ExitLocationsCommand;
 use crate::commands::financials_command::FinancialsCommand;
 use crate::commands::generate_wallets_command::GenerateWalletsCommand;
+use crate::commands::neighborhood_command::NeighborhoodCommand;
+use crate::commands::preferred_exits_command::PreferredExitsCommand;
 use crate::commands::recover_wallets_command::RecoverWalletsCommand;
+use crate::commands::rotate_keys_command::RotateKeysCommand;
+use crate::commands::route_command::RouteCommand;
 use crate::commands::scan_command::ScanCommand;
 use crate::commands::set_configuration_command::SetConfigurationCommand;
 use crate::commands::setup_command::SetupCommand;
@@ -34,6 +41,10 @@ pub struct CommandFactoryReal;
 impl CommandFactory for CommandFactoryReal {
     fn make(&self, pieces: &[String]) -> Result<Box<dyn Command>, CommandFactoryError> {
         let boxed_command: Box<dyn Command> = match pieces[0].as_str() {
+            "banned" => match BannedCommand::new(pieces) {
+                Ok(command) => Box::new(command),
+                Err(msg) => return Err(CommandSyntax(msg)),
+            },
             "change-password" => match ChangePasswordCommand::new_change(pieces) {
                 Ok(command) => Box::new(command),
                 Err(msg) => return Err(CommandSyntax(msg)),
@@ -52,6 +63,11 @@ impl CommandFactory for CommandFactoryReal {
                 Err(msg) => return Err(CommandSyntax(msg)),
             },
             "descriptor" => Box::new(DescriptorCommand::new()),
+            "excluded-nodes" => match ExcludedNodesCommand::new(pieces) {
+                Ok(command) => Box::new(command),
+                Err(msg) => return Err(CommandSyntax(msg)),
+            },
+            "exit-locations" => Box::new(ExitLocationsCommand::new()),
             "financials" => match FinancialsCommand::new(pieces) {
                 Ok(command) => Box::new(command),
                 Err(msg) => return Err(CommandSyntax(msg)),
@@ -60,10 +76,23 @@ impl CommandFactory for CommandFactoryReal {
                 Ok(command) => Box::new(command),
                 Err(msg) => return Err(CommandSyntax(msg)),
             },
+            "neighborhood" => match NeighborhoodCommand::new(pieces) {
+                Ok(command) => Box::new(command),
+                Err(msg) => return Err(CommandSyntax(msg)),
+            },
+            "preferred-exits" => match PreferredExitsCommand::new(pieces) {
+                Ok(command) => Box::new(command),
+                Err(msg) => return Err(CommandSyntax(msg)),
+            },
             "recover-wallets" => match RecoverWalletsCommand::new(pieces) {
                 Ok(command) => Box::new(command),
                 Err(msg) => return Err(CommandSyntax(msg)),
             },
+            "rotate-keys" => Box::new(RotateKeysCommand::new()),
+            "route" => match RouteCommand::new(pieces) {
+                Ok(command) => Box::new(command),
+                Err(msg) => return Err(CommandSyntax(msg)),
+            },
             "scan" => match ScanCommand::new(pieces) {
                 Ok(command) => Box::new(command),
                 Err(msg) => return Err(CommandSyntax(msg)),
@@ -103,6 +132,7 @@ impl CommandFactoryReal {
 mod tests {
     use super::*;
     use crate::command_factory::CommandFactoryError::UnrecognizedSubcommand;
+    use masq_lib::messages::UiNeighborhoodGraphFormat;
 
     #[test]
     fn complains_about_unrecognized_subcommand() {
@@ -214,6 +244,234 @@ mod tests {
         assert_eq!(connnection_status_command, &ConnectionStatusCommand {});
     }
 
+    #[test]
+    fn factory_produces_banned() {
+        let subject = CommandFactoryReal::new();
+
+        let command = subject
+            .make(&[
+                "banned".to_string(),
+                "--unban".to_string(),
+                "0x0000000000000000000000000000000000000001".to_string(),
+                "--note".to_string(),
+                "Forgiven".to_string(),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            command.as_any().downcast_ref::<BannedCommand>().unwrap(),
+            &BannedCommand {
+                ban: vec![],
+                unban: vec!["0x0000000000000000000000000000000000000001".to_string()],
+                note_opt: Some("Forgiven".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn complains_about_banned_command_with_bad_syntax() {
+        let subject = CommandFactoryReal::new();
+
+        let result = subject
+            .make(&["banned".to_string(), "--ban".to_string()])
+            .err()
+            .unwrap();
+
+        let msg = match result {
+            CommandSyntax(msg) => msg,
+            x => panic!("Expected syntax error, got {:?}", x),
+        };
+        assert!(msg.contains("--ban"), "{}", msg);
+    }
+
+    #[test]
+    fn factory_produces_excluded_nodes() {
+        let subject = CommandFactoryReal::new();
+
+        let command = subject
+            .make(&[
+                "excluded-nodes".to_string(),
+                "--add".to_string(),
+                "AQID".to_string(),
+                "--remove".to_string(),
+                "BAUG".to_string(),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            command
+                .as_any()
+                .downcast_ref::<ExcludedNodesCommand>()
+                .unwrap(),
+            &ExcludedNodesCommand {
+                add: vec!["AQID".to_string()],
+                remove: vec!["BAUG".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn complains_about_excluded_nodes_command_with_bad_syntax() {
+        let subject = CommandFactoryReal::new();
+
+        let result = subject
+            .make(&["excluded-nodes".to_string(), "--add".to_string()])
+            .err()
+            .unwrap();
+
+        let msg = match result {
+            CommandSyntax(msg) => msg,
+            x => panic!("Expected syntax error, got {:?}", x),
+        };
+        assert!(msg.contains("--add"), "{}", msg);
+    }
+
+    #[test]
+    fn factory_produces_exit_locations() {
+        let subject = CommandFactoryReal::new();
+
+        let command = subject.make(&["exit-locations".to_string()]).unwrap();
+
+        assert_eq!(
+            command
+                .as_any()
+                .downcast_ref::<ExitLocationsCommand>()
+                .unwrap(),
+            &ExitLocationsCommand {}
+        );
+    }
+
+    #[test]
+    fn factory_produces_neighborhood() {
+        let subject = CommandFactoryReal::new();
+
+        let command = subject
+            .make(&[
+                "neighborhood".to_string(),
+                "--format".to_string(),
+                "dot".to_string(),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            command
+                .as_any()
+                .downcast_ref::<NeighborhoodCommand>()
+                .unwrap(),
+            &NeighborhoodCommand {
+                format: UiNeighborhoodGraphFormat::Dot,
+                output_opt: None,
+            }
+        );
+    }
+
+    #[test]
+    fn complains_about_neighborhood_command_with_bad_syntax() {
+        let subject = CommandFactoryReal::new();
+
+        let result = subject
+            .make(&["neighborhood".to_string(), "--booga".to_string()])
+            .err()
+            .unwrap();
+
+        let msg = match result {
+            CommandSyntax(msg) => msg,
+            x => panic!("Expected syntax error, got {:?}", x),
+        };
+        assert!(msg.contains("Found argument"), "{}", msg);
+        assert!(msg.contains("--booga"), "{}", msg);
+    }
+
+    #[test]
+    fn factory_produces_preferred_exits() {
+        let subject = CommandFactoryReal::new();
+
+        let command = subject
+            .make(&[
+                "preferred-exits".to_string(),
+                "AQID".to_string(),
+                "--strict".to_string(),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            command
+                .as_any()
+                .downcast_ref::<PreferredExitsCommand>()
+                .unwrap(),
+            &PreferredExitsCommand {
+                public_keys_opt: Some(vec!["AQID".to_string()]),
+                strict: true,
+            }
+        );
+    }
+
+    #[test]
+    fn complains_about_preferred_exits_command_with_bad_syntax() {
+        let subject = CommandFactoryReal::new();
+
+        let result = subject
+            .make(&["preferred-exits".to_string(), "--booga".to_string()])
+            .err()
+            .unwrap();
+
+        let msg = match result {
+            CommandSyntax(msg) => msg,
+            x => panic!("Expected syntax error, got {:?}", x),
+        };
+        assert!(msg.contains("Found argument"), "{}", msg);
+        assert!(msg.contains("--booga"), "{}", msg);
+    }
+
+    #[test]
+    fn factory_produces_route() {
+        let subject = CommandFactoryReal::new();
+
+        let command = subject
+            .make(&[
+                "route".to_string(),
+                "booga.com".to_string(),
+                "--port".to_string(),
+                "8080".to_string(),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            command.as_any().downcast_ref::<RouteCommand>().unwrap(),
+            &RouteCommand {
+                hostname: "booga.com".to_string(),
+                port_opt: Some(8080),
+                payload_size: 1000,
+                response_size_opt: None,
+            }
+        );
+    }
+
+    #[test]
+    fn complains_about_route_command_with_bad_syntax() {
+        let subject = CommandFactoryReal::new();
+
+        let result = subject
+            .make(&[
+                "route".to_string(),
+                "booga.com".to_string(),
+                "--port".to_string(),
+                "0".to_string(),
+            ])
+            .err()
+            .unwrap();
+
+        let msg = match result {
+            CommandSyntax(msg) => msg,
+            x => panic!("Expected syntax error, got {:?}", x),
+        };
+        assert!(
+            msg.contains("Port must be an integer from 1 to 65535, not '0'"),
+            "{}",
+            msg
+        );
+    }
+
     #[test]
     fn factory_produces_set_password() {
         let subject = CommandFactoryReal::new();
@@ -0,0 +1,427 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::command_context::CommandContext;
+use crate::commands::commands_common::CommandError::Payload;
+use crate::commands::commands_common::{
+    transaction, Command, CommandError, STANDARD_COMMAND_TIMEOUT_MILLIS,
+};
+use clap::{App, Arg, SubCommand};
+use masq_lib::as_any_ref_in_trait_impl;
+use masq_lib::constants::{INVALID_WALLET_ERROR, NODE_NOT_RUNNING_ERROR};
+use masq_lib::messages::{UiBannedRequest, UiBannedResponse, UiBannedWallet};
+use masq_lib::short_writeln;
+use std::fmt::Debug;
+use std::io::Write;
+use thousands::Separable;
+use time::macros::format_description;
+use time::OffsetDateTime;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct BannedCommand {
+    pub ban: Vec<String>,
+    pub unban: Vec<String>,
+    pub note_opt: Option<String>,
+}
+
+const BANNED_SUBCOMMAND_ABOUT: &str =
+    "Refuses service to consuming wallets, by address. Without arguments, shows the wallets \
+     currently banned and why.";
+const BAN_ARG_HELP: &str =
+    "Wallet addresses to ban by hand. Paying their debts won't lift the ban.";
+const UNBAN_ARG_HELP: &str =
+    "Wallet addresses to forgive. A forgiven debtor isn't banned again until after paying.";
+const NOTE_ARG_HELP: &str = "Reason to record along with the bans and unbans.";
+
+pub fn banned_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("banned")
+        .about(BANNED_SUBCOMMAND_ABOUT)
+        .arg(
+            Arg::with_name("ban")
+                .help(BAN_ARG_HELP)
+                .long("ban")
+                .value_name("WALLET")
+                .takes_value(true)
+                .multiple(true)
+                .required(false),
+        )
+        .arg(
+            Arg::with_name("unban")
+                .help(UNBAN_ARG_HELP)
+                .long("unban")
+                .value_name("WALLET")
+                .takes_value(true)
+                .multiple(true)
+                .required(false),
+        )
+        .arg(
+            Arg::with_name("note")
+                .help(NOTE_ARG_HELP)
+                .long("note")
+                .value_name("TEXT")
+                .takes_value(true)
+                .required(false),
+        )
+}
+
+impl Command for BannedCommand {
+    fn execute(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        let input = UiBannedRequest {
+            ban: self.ban.clone(),
+            unban: self.unban.clone(),
+            note_opt: self.note_opt.clone(),
+        };
+        let output: Result<UiBannedResponse, CommandError> =
+            transaction(input, context, STANDARD_COMMAND_TIMEOUT_MILLIS);
+        match output {
+            Ok(response) => {
+                Self::dump_banned_wallets(&response, context.stdout());
+                Ok(())
+            }
+            Err(Payload(code, message)) if code == NODE_NOT_RUNNING_ERROR => {
+                short_writeln!(
+                    context.stderr(),
+                    "MASQNode is not running; therefore banned wallets cannot be shown or changed."
+                );
+                Err(Payload(code, message))
+            }
+            Err(Payload(code, message)) if code == INVALID_WALLET_ERROR => {
+                short_writeln!(
+                    context.stderr(),
+                    "Banned wallets were not changed: {}",
+                    message
+                );
+                Err(Payload(code, message))
+            }
+            Err(e) => {
+                short_writeln!(context.stderr(), "Banning wallets failed: {:?}", e);
+                Err(e)
+            }
+        }
+    }
+
+    as_any_ref_in_trait_impl!();
+}
+
+impl BannedCommand {
+    pub fn new(pieces: &[String]) -> Result<Self, String> {
+        let matches = match banned_subcommand().get_matches_from_safe(pieces) {
+            Ok(matches) => matches,
+            Err(e) => return Err(format!("{}", e)),
+        };
+        let values = |name: &str| -> Vec<String> {
+            matches
+                .values_of(name)
+                .map(|values| values.map(|value| value.to_string()).collect())
+                .unwrap_or_default()
+        };
+        Ok(Self {
+            ban: values("ban"),
+            unban: values("unban"),
+            note_opt: matches.value_of("note").map(|note| note.to_string()),
+        })
+    }
+
+    fn dump_banned_wallets(response: &UiBannedResponse, stdout: &mut dyn Write) {
+        if response.banned.is_empty() {
+            short_writeln!(stdout, "No wallets are banned.");
+        } else {
+            short_writeln!(stdout, "Banned wallets:");
+            response.banned.iter().for_each(|banned| {
+                short_writeln!(
+                    stdout,
+                    "    {} banned{} {}",
+                    banned.wallet,
+                    Self::when(banned),
+                    Self::why(banned)
+                )
+            });
+        }
+    }
+
+    fn when(banned: &UiBannedWallet) -> String {
+        match banned
+            .banned_at_sec_opt
+            .and_then(|sec| OffsetDateTime::from_unix_timestamp(sec).ok())
+        {
+            Some(timestamp) => format!(
+                " {} UTC",
+                timestamp
+                    .format(format_description!(
+                        "[year]-[month]-[day] [hour]:[minute]:[second]"
+                    ))
+                    .expect("Formatting of a valid timestamp failed")
+            ),
+            None => String::new(),
+        }
+    }
+
+    fn why(banned: &UiBannedWallet) -> String {
+        if banned.manual {
+            match &banned.note_opt {
+                Some(note) => format!("by hand: {}", note),
+                None => "by hand".to_string(),
+            }
+        } else {
+            match (banned.balance_gwei_opt, banned.debt_age_sec_opt) {
+                (Some(balance_gwei), Some(debt_age_sec)) => format!(
+                    "for delinquency (balance: {} gwei, age: {} sec)",
+                    balance_gwei.separate_with_commas(),
+                    debt_age_sec.separate_with_commas()
+                ),
+                _ => "for delinquency".to_string(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_context::ContextError;
+    use crate::command_context::ContextError::ConnectionDropped;
+    use crate::commands::commands_common::CommandError::ConnectionProblem;
+    use crate::test_utils::mocks::CommandContextMock;
+    use masq_lib::messages::ToMessageBody;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(
+            BANNED_SUBCOMMAND_ABOUT,
+            "Refuses service to consuming wallets, by address. Without arguments, shows the wallets \
+             currently banned and why."
+        );
+        assert_eq!(
+            BAN_ARG_HELP,
+            "Wallet addresses to ban by hand. Paying their debts won't lift the ban."
+        );
+        assert_eq!(
+            UNBAN_ARG_HELP,
+            "Wallet addresses to forgive. A forgiven debtor isn't banned again until after paying."
+        );
+        assert_eq!(
+            NOTE_ARG_HELP,
+            "Reason to record along with the bans and unbans."
+        );
+    }
+
+    #[test]
+    fn new_without_arguments_only_asks() {
+        let result = BannedCommand::new(&["banned".to_string()]);
+
+        assert_eq!(
+            result,
+            Ok(BannedCommand {
+                ban: vec![],
+                unban: vec![],
+                note_opt: None,
+            })
+        );
+    }
+
+    #[test]
+    fn new_takes_wallets_to_ban_and_unban_with_a_note() {
+        let result = BannedCommand::new(&[
+            "banned".to_string(),
+            "--ban".to_string(),
+            "0x0000000000000000000000000000000000000001".to_string(),
+            "0x0000000000000000000000000000000000000002".to_string(),
+            "--unban".to_string(),
+            "0x0000000000000000000000000000000000000003".to_string(),
+            "--note".to_string(),
+            "Settled off-chain".to_string(),
+        ]);
+
+        assert_eq!(
+            result,
+            Ok(BannedCommand {
+                ban: vec![
+                    "0x0000000000000000000000000000000000000001".to_string(),
+                    "0x0000000000000000000000000000000000000002".to_string()
+                ],
+                unban: vec!["0x0000000000000000000000000000000000000003".to_string()],
+                note_opt: Some("Settled off-chain".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn banned_command_happy_path() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let response = UiBannedResponse {
+            banned: vec![
+                UiBannedWallet {
+                    wallet: "0x0000000000000000000000000000000000000001".to_string(),
+                    banned_at_sec_opt: None,
+                    manual: false,
+                    note_opt: None,
+                    balance_gwei_opt: None,
+                    debt_age_sec_opt: None,
+                },
+                UiBannedWallet {
+                    wallet: "0x0000000000000000000000000000000000000002".to_string(),
+                    banned_at_sec_opt: Some(1_600_000_000),
+                    manual: false,
+                    note_opt: None,
+                    balance_gwei_opt: Some(12_345_678),
+                    debt_age_sec_opt: Some(700_000),
+                },
+                UiBannedWallet {
+                    wallet: "0x0000000000000000000000000000000000000003".to_string(),
+                    banned_at_sec_opt: Some(1_600_000_500),
+                    manual: true,
+                    note_opt: Some("Abusive traffic".to_string()),
+                    balance_gwei_opt: None,
+                    debt_age_sec_opt: None,
+                },
+                UiBannedWallet {
+                    wallet: "0x0000000000000000000000000000000000000004".to_string(),
+                    banned_at_sec_opt: Some(1_600_000_600),
+                    manual: true,
+                    note_opt: None,
+                    balance_gwei_opt: None,
+                    debt_age_sec_opt: None,
+                },
+            ],
+        };
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(response.tmb(42)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = BannedCommand {
+            ban: vec!["0x0000000000000000000000000000000000000003".to_string()],
+            unban: vec!["0x0000000000000000000000000000000000000005".to_string()],
+            note_opt: Some("Abusive traffic".to_string()),
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        let transact_params = transact_params_arc.lock().unwrap();
+        assert_eq!(
+            *transact_params,
+            vec![(
+                UiBannedRequest {
+                    ban: vec!["0x0000000000000000000000000000000000000003".to_string()],
+                    unban: vec!["0x0000000000000000000000000000000000000005".to_string()],
+                    note_opt: Some("Abusive traffic".to_string()),
+                }
+                .tmb(0),
+                STANDARD_COMMAND_TIMEOUT_MILLIS
+            )]
+        );
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "Banned wallets:\n\
+             \x20   0x0000000000000000000000000000000000000001 banned for delinquency\n\
+             \x20   0x0000000000000000000000000000000000000002 banned 2020-09-13 12:26:40 UTC \
+             for delinquency (balance: 12,345,678 gwei, age: 700,000 sec)\n\
+             \x20   0x0000000000000000000000000000000000000003 banned 2020-09-13 12:35:00 UTC \
+             by hand: Abusive traffic\n\
+             \x20   0x0000000000000000000000000000000000000004 banned 2020-09-13 12:36:40 UTC \
+             by hand\n"
+        );
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn banned_command_reports_when_nothing_is_banned() {
+        let response = UiBannedResponse { banned: vec![] };
+        let mut context = CommandContextMock::new().transact_result(Ok(response.tmb(42)));
+        let stdout_arc = context.stdout_arc();
+        let subject = BannedCommand {
+            ban: vec![],
+            unban: vec![],
+            note_opt: None,
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "No wallets are banned.\n"
+        );
+    }
+
+    #[test]
+    fn banned_command_reports_bad_wallet_addresses() {
+        let mut context =
+            CommandContextMock::new().transact_result(Err(ContextError::PayloadError(
+                INVALID_WALLET_ERROR,
+                "Bad wallet address: 'booga'".to_string(),
+            )));
+        let stderr_arc = context.stderr_arc();
+        let subject = BannedCommand {
+            ban: vec!["booga".to_string()],
+            unban: vec![],
+            note_opt: None,
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(
+            result,
+            Err(CommandError::Payload(
+                INVALID_WALLET_ERROR,
+                "Bad wallet address: 'booga'".to_string()
+            ))
+        );
+        assert_eq!(
+            stderr_arc.lock().unwrap().get_string(),
+            "Banned wallets were not changed: Bad wallet address: 'booga'\n"
+        );
+    }
+
+    #[test]
+    fn banned_command_doesnt_work_if_node_is_not_running() {
+        let mut context = CommandContextMock::new().transact_result(Err(
+            ContextError::PayloadError(NODE_NOT_RUNNING_ERROR, "irrelevant".to_string()),
+        ));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = BannedCommand {
+            ban: vec![],
+            unban: vec![],
+            note_opt: None,
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(
+            result,
+            Err(CommandError::Payload(
+                NODE_NOT_RUNNING_ERROR,
+                "irrelevant".to_string()
+            ))
+        );
+        assert_eq!(
+            stderr_arc.lock().unwrap().get_string(),
+            "MASQNode is not running; therefore banned wallets cannot be shown or changed.\n"
+        );
+        assert_eq!(stdout_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn banned_command_sad_path() {
+        let mut context =
+            CommandContextMock::new().transact_result(Err(ConnectionDropped("Booga".to_string())));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = BannedCommand {
+            ban: vec![],
+            unban: vec![],
+            note_opt: None,
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Err(ConnectionProblem("Booga".to_string())));
+        assert_eq!(stdout_arc.lock().unwrap().get_string(), String::new());
+        assert_eq!(
+            stderr_arc.lock().unwrap().get_string(),
+            "Banning wallets failed: ConnectionProblem(\"Booga\")\n"
+        );
+    }
+}
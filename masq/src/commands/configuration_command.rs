@@ -172,6 +172,25 @@ impl ConfigurationCommand {
             ]
         });
         Self::dump_value_list(stream, "Scan intervals:", &scan_intervals);
+        let transaction_fees = match &configuration.transaction_fees_opt {
+            None => vec![],
+            Some(t_f) => match &t_f.max_priority_fee_per_gas_wei_opt {
+                None => Self::preprocess_combined_parameters(&[(
+                    "Gas price:",
+                    &t_f.max_fee_per_gas_wei,
+                    "wei",
+                )]),
+                Some(max_priority_fee_per_gas_wei) => Self::preprocess_combined_parameters(&[
+                    ("Max fee per gas:", &t_f.max_fee_per_gas_wei, "wei"),
+                    (
+                        "Max priority fee per gas:",
+                        max_priority_fee_per_gas_wei,
+                        "wei",
+                    ),
+                ]),
+            },
+        };
+        Self::dump_value_list(stream, "Transaction fees:", &transaction_fees);
     }
 
     fn dump_value_list(stream: &mut dyn Write, name: &str, values: &[String]) {
@@ -228,6 +247,7 @@ mod tests {
     use masq_lib::constants::NODE_NOT_RUNNING_ERROR;
     use masq_lib::messages::{
         ToMessageBody, UiConfigurationResponse, UiPaymentThresholds, UiRatePack, UiScanIntervals,
+        UiTransactionFees,
     };
     use masq_lib::utils::AutomapProtocol;
     use std::sync::{Arc, Mutex};
@@ -342,6 +362,10 @@ mod tests {
                 payable_sec: 155000,
                 receivable_sec: 250666,
             },
+            transaction_fees_opt: Some(UiTransactionFees {
+                max_fee_per_gas_wei: 2_500_000_000,
+                max_priority_fee_per_gas_wei_opt: Some(100_000_000),
+            }),
         };
         let mut context = CommandContextMock::new()
             .transact_params(&transact_params_arc)
@@ -399,7 +423,10 @@ mod tests {
 |Scan intervals:                   \n\
 |                                  Pending payable:                  150,500 s\n\
 |                                  Payable:                          155,000 s\n\
-|                                  Receivable:                       250,666 s\n"
+|                                  Receivable:                       250,666 s\n\
+|Transaction fees:                 \n\
+|                                  Max fee per gas:                  2,500,000,000 wei\n\
+|                                  Max priority fee per gas:         100,000,000 wei\n"
             )
             .replace('|', "")
         );
@@ -442,6 +469,7 @@ mod tests {
                 payable_sec: 1000,
                 receivable_sec: 1000,
             },
+            transaction_fees_opt: None,
         };
         let mut context = CommandContextMock::new()
             .transact_params(&transact_params_arc)
@@ -496,7 +524,8 @@ mod tests {
 |Scan intervals:                   \n\
 |                                  Pending payable:                  1,000 s\n\
 |                                  Payable:                          1,000 s\n\
-|                                  Receivable:                       1,000 s\n",
+|                                  Receivable:                       1,000 s\n\
+|Transaction fees:                 [?]\n",
             )
             .replace('|', "")
         );
@@ -0,0 +1,315 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::command_context::CommandContext;
+use crate::commands::commands_common::CommandError::Payload;
+use crate::commands::commands_common::{
+    transaction, Command, CommandError, STANDARD_COMMAND_TIMEOUT_MILLIS,
+};
+use clap::{App, Arg, SubCommand};
+use masq_lib::as_any_ref_in_trait_impl;
+use masq_lib::constants::{INVALID_PUBLIC_KEY_ERROR, NODE_NOT_RUNNING_ERROR};
+use masq_lib::messages::{UiExcludedNodesRequest, UiExcludedNodesResponse};
+use masq_lib::short_writeln;
+use std::fmt::Debug;
+use std::io::Write;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ExcludedNodesCommand {
+    pub add: Vec<String>,
+    pub remove: Vec<String>,
+}
+
+const EXCLUDED_NODES_SUBCOMMAND_ABOUT: &str =
+    "Keeps Nodes out of your Node's routes altogether, by public key. Without arguments, shows \
+     the Nodes currently excluded.";
+const ADD_ARG_HELP: &str = "Public keys of Nodes never to route through or exit from.";
+const REMOVE_ARG_HELP: &str = "Public keys of excluded Nodes to allow into routes again.";
+
+pub fn excluded_nodes_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("excluded-nodes")
+        .about(EXCLUDED_NODES_SUBCOMMAND_ABOUT)
+        .arg(
+            Arg::with_name("add")
+                .help(ADD_ARG_HELP)
+                .long("add")
+                .value_name("PUBLIC-KEY")
+                .takes_value(true)
+                .multiple(true)
+                .required(false),
+        )
+        .arg(
+            Arg::with_name("remove")
+                .help(REMOVE_ARG_HELP)
+                .long("remove")
+                .value_name("PUBLIC-KEY")
+                .takes_value(true)
+                .multiple(true)
+                .required(false),
+        )
+}
+
+impl Command for ExcludedNodesCommand {
+    fn execute(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        let input = UiExcludedNodesRequest {
+            add: self.add.clone(),
+            remove: self.remove.clone(),
+        };
+        let output: Result<UiExcludedNodesResponse, CommandError> =
+            transaction(input, context, STANDARD_COMMAND_TIMEOUT_MILLIS);
+        match output {
+            Ok(response) => {
+                Self::dump_excluded_nodes(&response, context.stdout());
+                Ok(())
+            }
+            Err(Payload(code, message)) if code == NODE_NOT_RUNNING_ERROR => {
+                short_writeln!(
+                    context.stderr(),
+                    "MASQNode is not running; therefore excluded Nodes cannot be shown or changed."
+                );
+                Err(Payload(code, message))
+            }
+            Err(Payload(code, message)) if code == INVALID_PUBLIC_KEY_ERROR => {
+                short_writeln!(
+                    context.stderr(),
+                    "Excluded Nodes were not changed: {}",
+                    message
+                );
+                Err(Payload(code, message))
+            }
+            Err(e) => {
+                short_writeln!(context.stderr(), "Excluding Nodes failed: {:?}", e);
+                Err(e)
+            }
+        }
+    }
+
+    as_any_ref_in_trait_impl!();
+}
+
+impl ExcludedNodesCommand {
+    pub fn new(pieces: &[String]) -> Result<Self, String> {
+        let matches = match excluded_nodes_subcommand().get_matches_from_safe(pieces) {
+            Ok(matches) => matches,
+            Err(e) => return Err(format!("{}", e)),
+        };
+        let values = |name: &str| -> Vec<String> {
+            matches
+                .values_of(name)
+                .map(|values| values.map(|value| value.to_string()).collect())
+                .unwrap_or_default()
+        };
+        Ok(Self {
+            add: values("add"),
+            remove: values("remove"),
+        })
+    }
+
+    fn dump_excluded_nodes(response: &UiExcludedNodesResponse, stdout: &mut dyn Write) {
+        if response.public_keys.is_empty() {
+            short_writeln!(stdout, "No Nodes are excluded from routes.");
+        } else {
+            short_writeln!(stdout, "Excluded Nodes:");
+            response
+                .public_keys
+                .iter()
+                .for_each(|public_key| short_writeln!(stdout, "    {}", public_key));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_context::ContextError;
+    use crate::command_context::ContextError::ConnectionDropped;
+    use crate::commands::commands_common::CommandError::ConnectionProblem;
+    use crate::test_utils::mocks::CommandContextMock;
+    use masq_lib::messages::ToMessageBody;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(
+            EXCLUDED_NODES_SUBCOMMAND_ABOUT,
+            "Keeps Nodes out of your Node's routes altogether, by public key. Without arguments, shows \
+             the Nodes currently excluded."
+        );
+        assert_eq!(
+            ADD_ARG_HELP,
+            "Public keys of Nodes never to route through or exit from."
+        );
+        assert_eq!(
+            REMOVE_ARG_HELP,
+            "Public keys of excluded Nodes to allow into routes again."
+        );
+    }
+
+    #[test]
+    fn new_without_arguments_only_asks() {
+        let result = ExcludedNodesCommand::new(&["excluded-nodes".to_string()]);
+
+        assert_eq!(
+            result,
+            Ok(ExcludedNodesCommand {
+                add: vec![],
+                remove: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn new_takes_keys_to_add_and_remove() {
+        let result = ExcludedNodesCommand::new(&[
+            "excluded-nodes".to_string(),
+            "--add".to_string(),
+            "AQID".to_string(),
+            "BAUG".to_string(),
+            "--remove".to_string(),
+            "BwgJ".to_string(),
+        ]);
+
+        assert_eq!(
+            result,
+            Ok(ExcludedNodesCommand {
+                add: vec!["AQID".to_string(), "BAUG".to_string()],
+                remove: vec!["BwgJ".to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn excluded_nodes_command_happy_path() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let response = UiExcludedNodesResponse {
+            public_keys: vec!["AQID".to_string(), "BAUG".to_string()],
+        };
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(response.tmb(42)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = ExcludedNodesCommand {
+            add: vec!["BAUG".to_string()],
+            remove: vec!["BwgJ".to_string()],
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        let transact_params = transact_params_arc.lock().unwrap();
+        assert_eq!(
+            *transact_params,
+            vec![(
+                UiExcludedNodesRequest {
+                    add: vec!["BAUG".to_string()],
+                    remove: vec!["BwgJ".to_string()],
+                }
+                .tmb(0),
+                STANDARD_COMMAND_TIMEOUT_MILLIS
+            )]
+        );
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "Excluded Nodes:\n    AQID\n    BAUG\n"
+        );
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn excluded_nodes_command_reports_when_nothing_is_excluded() {
+        let response = UiExcludedNodesResponse {
+            public_keys: vec![],
+        };
+        let mut context = CommandContextMock::new().transact_result(Ok(response.tmb(42)));
+        let stdout_arc = context.stdout_arc();
+        let subject = ExcludedNodesCommand {
+            add: vec![],
+            remove: vec![],
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "No Nodes are excluded from routes.\n"
+        );
+    }
+
+    #[test]
+    fn excluded_nodes_command_reports_bad_public_keys() {
+        let mut context =
+            CommandContextMock::new().transact_result(Err(ContextError::PayloadError(
+                INVALID_PUBLIC_KEY_ERROR,
+                "Bad public key: 'booga'".to_string(),
+            )));
+        let stderr_arc = context.stderr_arc();
+        let subject = ExcludedNodesCommand {
+            add: vec!["booga".to_string()],
+            remove: vec![],
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(
+            result,
+            Err(CommandError::Payload(
+                INVALID_PUBLIC_KEY_ERROR,
+                "Bad public key: 'booga'".to_string()
+            ))
+        );
+        assert_eq!(
+            stderr_arc.lock().unwrap().get_string(),
+            "Excluded Nodes were not changed: Bad public key: 'booga'\n"
+        );
+    }
+
+    #[test]
+    fn excluded_nodes_command_doesnt_work_if_node_is_not_running() {
+        let mut context = CommandContextMock::new().transact_result(Err(
+            ContextError::PayloadError(NODE_NOT_RUNNING_ERROR, "irrelevant".to_string()),
+        ));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = ExcludedNodesCommand {
+            add: vec![],
+            remove: vec![],
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(
+            result,
+            Err(CommandError::Payload(
+                NODE_NOT_RUNNING_ERROR,
+                "irrelevant".to_string()
+            ))
+        );
+        assert_eq!(
+            stderr_arc.lock().unwrap().get_string(),
+            "MASQNode is not running; therefore excluded Nodes cannot be shown or changed.\n"
+        );
+        assert_eq!(stdout_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn excluded_nodes_command_sad_path() {
+        let mut context =
+            CommandContextMock::new().transact_result(Err(ConnectionDropped("Booga".to_string())));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = ExcludedNodesCommand {
+            add: vec![],
+            remove: vec![],
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Err(ConnectionProblem("Booga".to_string())));
+        assert_eq!(stdout_arc.lock().unwrap().get_string(), String::new());
+        assert_eq!(
+            stderr_arc.lock().unwrap().get_string(),
+            "Excluding Nodes failed: ConnectionProblem(\"Booga\")\n"
+        );
+    }
+}
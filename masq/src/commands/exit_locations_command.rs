@@ -0,0 +1,213 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::command_context::CommandContext;
+use crate::commands::commands_common::CommandError::Payload;
+use crate::commands::commands_common::{
+    transaction, Command, CommandError, STANDARD_COMMAND_TIMEOUT_MILLIS,
+};
+use clap::{App, SubCommand};
+use masq_lib::as_any_ref_in_trait_impl;
+use masq_lib::constants::NODE_NOT_RUNNING_ERROR;
+use masq_lib::messages::{UiExitLocationsRequest, UiExitLocationsResponse};
+use masq_lib::short_writeln;
+use std::fmt::Debug;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ExitLocationsCommand {}
+
+const EXIT_LOCATIONS_SUBCOMMAND_ABOUT: &str =
+    "Lists the countries that exit Nodes currently known to your Node declare, with the number \
+     of Nodes in each, along with the --exit-location preference your Node is using.";
+
+pub fn exit_locations_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("exit-locations").about(EXIT_LOCATIONS_SUBCOMMAND_ABOUT)
+}
+
+impl Command for ExitLocationsCommand {
+    fn execute(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        let input = UiExitLocationsRequest {};
+        let output: Result<UiExitLocationsResponse, CommandError> =
+            transaction(input, context, STANDARD_COMMAND_TIMEOUT_MILLIS);
+        match output {
+            Ok(response) => {
+                Self::dump_exit_locations(&response, context.stdout());
+                Ok(())
+            }
+            Err(Payload(code, message)) if code == NODE_NOT_RUNNING_ERROR => {
+                short_writeln!(
+                    context.stderr(),
+                    "MASQNode is not running; therefore exit locations cannot be displayed."
+                );
+                Err(Payload(code, message))
+            }
+            Err(e) => {
+                short_writeln!(context.stderr(), "Exit locations retrieval failed: {:?}", e);
+                Err(e)
+            }
+        }
+    }
+
+    as_any_ref_in_trait_impl!();
+}
+
+impl ExitLocationsCommand {
+    pub fn new() -> Self {
+        ExitLocationsCommand {}
+    }
+
+    fn dump_exit_locations(response: &UiExitLocationsResponse, stdout: &mut dyn std::io::Write) {
+        if response.exit_countries.is_empty() {
+            short_writeln!(
+                stdout,
+                "No Node known to yours declares the country it exits from."
+            );
+        } else {
+            short_writeln!(stdout, "{:<8}{:>6}", "Country", "Nodes");
+            response.exit_countries.iter().for_each(|exit_country| {
+                short_writeln!(
+                    stdout,
+                    "{:<8}{:>6}",
+                    exit_country.country_code,
+                    exit_country.node_count
+                )
+            });
+        }
+        match &response.exit_location_opt {
+            Some(exit_location) => short_writeln!(stdout, "\nExit location: {}", exit_location),
+            None => short_writeln!(stdout, "\nExit location: any"),
+        }
+    }
+}
+
+impl Default for ExitLocationsCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_context::ContextError;
+    use crate::command_context::ContextError::ConnectionDropped;
+    use crate::commands::commands_common::CommandError::ConnectionProblem;
+    use crate::test_utils::mocks::CommandContextMock;
+    use masq_lib::messages::{ToMessageBody, UiExitCountry};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(
+            EXIT_LOCATIONS_SUBCOMMAND_ABOUT,
+            "Lists the countries that exit Nodes currently known to your Node declare, with the number \
+             of Nodes in each, along with the --exit-location preference your Node is using."
+        );
+    }
+
+    #[test]
+    fn exit_locations_command_happy_path() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let response = UiExitLocationsResponse {
+            exit_countries: vec![
+                UiExitCountry {
+                    country_code: "CZ".to_string(),
+                    node_count: 3,
+                },
+                UiExitCountry {
+                    country_code: "SK".to_string(),
+                    node_count: 12,
+                },
+            ],
+            exit_location_opt: Some("strict:CZ,SK".to_string()),
+        };
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(response.tmb(42)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = ExitLocationsCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        let transact_params = transact_params_arc.lock().unwrap();
+        assert_eq!(
+            *transact_params,
+            vec![(
+                UiExitLocationsRequest {}.tmb(0),
+                STANDARD_COMMAND_TIMEOUT_MILLIS
+            )]
+        );
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "Country  Nodes\n\
+             CZ           3\n\
+             SK          12\n\
+             \n\
+             Exit location: strict:CZ,SK\n"
+        );
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn exit_locations_command_reports_when_no_country_is_known() {
+        let response = UiExitLocationsResponse {
+            exit_countries: vec![],
+            exit_location_opt: None,
+        };
+        let mut context = CommandContextMock::new().transact_result(Ok(response.tmb(42)));
+        let stdout_arc = context.stdout_arc();
+        let subject = ExitLocationsCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "No Node known to yours declares the country it exits from.\n\nExit location: any\n"
+        );
+    }
+
+    #[test]
+    fn exit_locations_command_doesnt_work_if_node_is_not_running() {
+        let mut context = CommandContextMock::new().transact_result(Err(
+            ContextError::PayloadError(NODE_NOT_RUNNING_ERROR, "irrelevant".to_string()),
+        ));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = ExitLocationsCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(
+            result,
+            Err(CommandError::Payload(
+                NODE_NOT_RUNNING_ERROR,
+                "irrelevant".to_string()
+            ))
+        );
+        assert_eq!(
+            stderr_arc.lock().unwrap().get_string(),
+            "MASQNode is not running; therefore exit locations cannot be displayed.\n"
+        );
+        assert_eq!(stdout_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn exit_locations_command_sad_path() {
+        let mut context =
+            CommandContextMock::new().transact_result(Err(ConnectionDropped("Booga".to_string())));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = ExitLocationsCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Err(ConnectionProblem("Booga".to_string())));
+        assert_eq!(stdout_arc.lock().unwrap().get_string(), String::new());
+        assert_eq!(
+            stderr_arc.lock().unwrap().get_string(),
+            "Exit locations retrieval failed: ConnectionProblem(\"Booga\")\n"
+        );
+    }
+}
@@ -1,5 +1,6 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+pub mod banned_command;
 pub mod change_password_command;
 pub mod check_password_command;
 pub mod commands_common;
@@ -7,9 +8,15 @@ pub mod configuration_command;
 pub mod connection_status_command;
 pub mod crash_command;
 pub mod descriptor_command;
+pub mod excluded_nodes_command;
+pub mod exit_locations_command;
 pub mod financials_command;
 pub mod generate_wallets_command;
+pub mod neighborhood_command;
+pub mod preferred_exits_command;
 pub mod recover_wallets_command;
+pub mod rotate_keys_command;
+pub mod route_command;
 pub mod scan_command;
 pub mod set_configuration_command;
 pub mod setup_command;
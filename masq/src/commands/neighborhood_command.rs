@@ -0,0 +1,384 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::command_context::CommandContext;
+use crate::commands::commands_common::CommandError::Payload;
+use crate::commands::commands_common::{
+    transaction, Command, CommandError, STANDARD_COMMAND_TIMEOUT_MILLIS,
+};
+use clap::{App, Arg, SubCommand};
+use masq_lib::as_any_ref_in_trait_impl;
+use masq_lib::constants::NODE_NOT_RUNNING_ERROR;
+use masq_lib::messages::{
+    UiNeighborhoodGraphFormat, UiNeighborhoodGraphRequest, UiNeighborhoodGraphResponse,
+};
+use masq_lib::short_writeln;
+use masq_lib::utils::to_string;
+use std::fmt::Debug;
+use std::path::PathBuf;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct NeighborhoodCommand {
+    pub format: UiNeighborhoodGraphFormat,
+    pub output_opt: Option<PathBuf>,
+}
+
+const NEIGHBORHOOD_SUBCOMMAND_ABOUT: &str =
+    "Exports the Nodes and neighborships your Node currently knows about, either as JSON or \
+     as a DOT graph that can be rendered with Graphviz.";
+const FORMAT_ARG_HELP: &str = "Format of the exported graph: json or dot.";
+const OUTPUT_ARG_HELP: &str =
+    "File to write the exported graph to. If this is omitted, the graph is written to the console.";
+
+const FORMAT_ARG_POSSIBLE_VALUES: [&str; 2] = ["json", "dot"];
+const FORMAT_ARG_DEFAULT_VALUE: &str = "json";
+
+pub fn neighborhood_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("neighborhood")
+        .about(NEIGHBORHOOD_SUBCOMMAND_ABOUT)
+        .arg(
+            Arg::with_name("format")
+                .help(FORMAT_ARG_HELP)
+                .long("format")
+                .value_name("FORMAT")
+                .required(false)
+                .default_value(FORMAT_ARG_DEFAULT_VALUE)
+                .takes_value(true)
+                .possible_values(&FORMAT_ARG_POSSIBLE_VALUES)
+                .case_insensitive(true),
+        )
+        .arg(
+            Arg::with_name("output")
+                .help(OUTPUT_ARG_HELP)
+                .long("output")
+                .value_name("FILE")
+                .required(false)
+                .takes_value(true),
+        )
+}
+
+impl Command for NeighborhoodCommand {
+    fn execute(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        let input = UiNeighborhoodGraphRequest {
+            format: self.format,
+        };
+        let output: Result<UiNeighborhoodGraphResponse, CommandError> =
+            transaction(input, context, STANDARD_COMMAND_TIMEOUT_MILLIS);
+        match output {
+            Ok(response) => self.export_graph(response, context),
+            Err(Payload(code, message)) if code == NODE_NOT_RUNNING_ERROR => {
+                short_writeln!(
+                    context.stderr(),
+                    "MASQNode is not running; therefore its neighborhood cannot be exported."
+                );
+                Err(Payload(code, message))
+            }
+            Err(e) => {
+                short_writeln!(context.stderr(), "Neighborhood export failed: {:?}", e);
+                Err(e)
+            }
+        }
+    }
+
+    as_any_ref_in_trait_impl!();
+}
+
+impl NeighborhoodCommand {
+    pub fn new(pieces: &[String]) -> Result<Self, String> {
+        let matches = match neighborhood_subcommand().get_matches_from_safe(pieces) {
+            Ok(matches) => matches,
+            Err(e) => return Err(format!("{}", e)),
+        };
+        let format = match matches
+            .value_of("format")
+            .expect("format parameter is not properly defaulted")
+            .to_lowercase()
+            .as_str()
+        {
+            "dot" => UiNeighborhoodGraphFormat::Dot,
+            _ => UiNeighborhoodGraphFormat::Json,
+        };
+        Ok(Self {
+            format,
+            output_opt: matches.value_of("output").map(to_string).map(PathBuf::from),
+        })
+    }
+
+    fn export_graph(
+        &self,
+        response: UiNeighborhoodGraphResponse,
+        context: &mut dyn CommandContext,
+    ) -> Result<(), CommandError> {
+        let graph = match response.dot_graph_opt {
+            Some(dot_graph) => dot_graph,
+            None => serde_json::to_string_pretty(&response)
+                .expect("Neighborhood graph could not be serialized"),
+        };
+        match &self.output_opt {
+            None => {
+                short_writeln!(context.stdout(), "{}", graph);
+                Ok(())
+            }
+            Some(path) => match std::fs::write(path, format!("{}\n", graph)) {
+                Ok(()) => {
+                    short_writeln!(
+                        context.stdout(),
+                        "Neighborhood graph written to {}",
+                        path.display()
+                    );
+                    Ok(())
+                }
+                Err(e) => {
+                    let message = format!(
+                        "Could not write neighborhood graph to {}: {}",
+                        path.display(),
+                        e
+                    );
+                    short_writeln!(context.stderr(), "{}", message);
+                    Err(CommandError::Other(message))
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_context::ContextError;
+    use crate::command_context::ContextError::ConnectionDropped;
+    use crate::commands::commands_common::CommandError::ConnectionProblem;
+    use crate::test_utils::mocks::CommandContextMock;
+    use masq_lib::messages::{ToMessageBody, UiGraphEdge, UiGraphNode, UiRatePack};
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+    use std::sync::{Arc, Mutex};
+
+    fn make_response() -> UiNeighborhoodGraphResponse {
+        UiNeighborhoodGraphResponse {
+            nodes: vec![UiGraphNode {
+                public_key: "AQIDBA".to_string(),
+                node_addr_opt: Some("1.2.3.4:1234".to_string()),
+                version: 3,
+                is_root: true,
+                accepts_connections: true,
+                routes_data: true,
+                country_code_opt: Some("CZ".to_string()),
+                rate_pack: UiRatePack {
+                    routing_byte_rate: 1,
+                    routing_service_rate: 2,
+                    exit_byte_rate: 3,
+                    exit_service_rate: 4,
+                },
+            }],
+            edges: vec![UiGraphEdge {
+                from: "AQIDBA".to_string(),
+                to: "AgMEBQ".to_string(),
+            }],
+            dot_graph_opt: None,
+        }
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(
+            NEIGHBORHOOD_SUBCOMMAND_ABOUT,
+            "Exports the Nodes and neighborships your Node currently knows about, either as JSON or \
+             as a DOT graph that can be rendered with Graphviz."
+        );
+        assert_eq!(
+            FORMAT_ARG_HELP,
+            "Format of the exported graph: json or dot."
+        );
+        assert_eq!(
+            OUTPUT_ARG_HELP,
+            "File to write the exported graph to. If this is omitted, the graph is written to the console."
+        );
+        assert_eq!(FORMAT_ARG_POSSIBLE_VALUES, ["json", "dot"]);
+        assert_eq!(FORMAT_ARG_DEFAULT_VALUE, "json");
+    }
+
+    #[test]
+    fn new_defaults_to_json_on_the_console() {
+        let result = NeighborhoodCommand::new(&["neighborhood".to_string()]);
+
+        assert_eq!(
+            result,
+            Ok(NeighborhoodCommand {
+                format: UiNeighborhoodGraphFormat::Json,
+                output_opt: None,
+            })
+        );
+    }
+
+    #[test]
+    fn new_accepts_dot_format_and_output_file() {
+        let result = NeighborhoodCommand::new(&[
+            "neighborhood".to_string(),
+            "--format".to_string(),
+            "DOT".to_string(),
+            "--output".to_string(),
+            "graph.dot".to_string(),
+        ]);
+
+        assert_eq!(
+            result,
+            Ok(NeighborhoodCommand {
+                format: UiNeighborhoodGraphFormat::Dot,
+                output_opt: Some(PathBuf::from("graph.dot")),
+            })
+        );
+    }
+
+    #[test]
+    fn new_rejects_unknown_format() {
+        let result = NeighborhoodCommand::new(&[
+            "neighborhood".to_string(),
+            "--format".to_string(),
+            "png".to_string(),
+        ]);
+
+        let msg = result.unwrap_err();
+        assert!(
+            msg.contains("'png' isn't a valid value for '--format <FORMAT>'"),
+            "{}",
+            msg
+        );
+    }
+
+    #[test]
+    fn neighborhood_command_writes_json_to_the_console() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(make_response().tmb(42)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = NeighborhoodCommand::new(&["neighborhood".to_string()]).unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        let transact_params = transact_params_arc.lock().unwrap();
+        assert_eq!(
+            *transact_params,
+            vec![(
+                UiNeighborhoodGraphRequest {
+                    format: UiNeighborhoodGraphFormat::Json
+                }
+                .tmb(0),
+                STANDARD_COMMAND_TIMEOUT_MILLIS
+            )]
+        );
+        let stdout = stdout_arc.lock().unwrap().get_string();
+        let exported: UiNeighborhoodGraphResponse = serde_json::from_str(&stdout).unwrap();
+        assert_eq!(exported, make_response());
+        assert!(stdout.contains("\n  \"nodes\": ["), "{}", stdout);
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn neighborhood_command_writes_dot_graph_to_a_file() {
+        let home_dir = ensure_node_home_directory_exists(
+            "neighborhood_command",
+            "neighborhood_command_writes_dot_graph_to_a_file",
+        );
+        let path = home_dir.join("graph.dot");
+        let response = UiNeighborhoodGraphResponse {
+            nodes: vec![],
+            edges: vec![],
+            dot_graph_opt: Some("digraph db { \"AQIDBA\" -> \"AgMEBQ\"; }".to_string()),
+        };
+        let mut context = CommandContextMock::new().transact_result(Ok(response.tmb(42)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = NeighborhoodCommand {
+            format: UiNeighborhoodGraphFormat::Dot,
+            output_opt: Some(path.clone()),
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "digraph db { \"AQIDBA\" -> \"AgMEBQ\"; }\n"
+        );
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            format!("Neighborhood graph written to {}\n", path.display())
+        );
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn neighborhood_command_reports_file_that_cannot_be_written() {
+        let home_dir = ensure_node_home_directory_exists(
+            "neighborhood_command",
+            "neighborhood_command_reports_file_that_cannot_be_written",
+        );
+        let path = home_dir.join("nonexistent").join("graph.json");
+        let mut context = CommandContextMock::new().transact_result(Ok(make_response().tmb(42)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = NeighborhoodCommand {
+            format: UiNeighborhoodGraphFormat::Json,
+            output_opt: Some(path.clone()),
+        };
+
+        let result = subject.execute(&mut context);
+
+        let message = match result {
+            Err(CommandError::Other(message)) => message,
+            x => panic!("Expected CommandError::Other, got {:?}", x),
+        };
+        let prefix = format!("Could not write neighborhood graph to {}: ", path.display());
+        assert!(message.starts_with(&prefix), "{}", message);
+        assert_eq!(
+            stderr_arc.lock().unwrap().get_string(),
+            format!("{}\n", message)
+        );
+        assert_eq!(stdout_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn neighborhood_command_doesnt_work_if_node_is_not_running() {
+        let mut context = CommandContextMock::new().transact_result(Err(
+            ContextError::PayloadError(NODE_NOT_RUNNING_ERROR, "irrelevant".to_string()),
+        ));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = NeighborhoodCommand::new(&["neighborhood".to_string()]).unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(
+            result,
+            Err(CommandError::Payload(
+                NODE_NOT_RUNNING_ERROR,
+                "irrelevant".to_string()
+            ))
+        );
+        assert_eq!(
+            stderr_arc.lock().unwrap().get_string(),
+            "MASQNode is not running; therefore its neighborhood cannot be exported.\n"
+        );
+        assert_eq!(stdout_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn neighborhood_command_sad_path() {
+        let mut context =
+            CommandContextMock::new().transact_result(Err(ConnectionDropped("Booga".to_string())));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = NeighborhoodCommand::new(&["neighborhood".to_string()]).unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Err(ConnectionProblem("Booga".to_string())));
+        assert_eq!(stdout_arc.lock().unwrap().get_string(), String::new());
+        assert_eq!(
+            stderr_arc.lock().unwrap().get_string(),
+            "Neighborhood export failed: ConnectionProblem(\"Booga\")\n"
+        );
+    }
+}
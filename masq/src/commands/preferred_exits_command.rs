@@ -0,0 +1,378 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::command_context::CommandContext;
+use crate::commands::commands_common::CommandError::Payload;
+use crate::commands::commands_common::{
+    transaction, Command, CommandError, STANDARD_COMMAND_TIMEOUT_MILLIS,
+};
+use clap::{App, Arg, SubCommand};
+use masq_lib::as_any_ref_in_trait_impl;
+use masq_lib::constants::{INVALID_PUBLIC_KEY_ERROR, NODE_NOT_RUNNING_ERROR};
+use masq_lib::messages::{UiPreferredExitsRequest, UiPreferredExitsResponse};
+use masq_lib::short_writeln;
+use std::fmt::Debug;
+use std::io::Write;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct PreferredExitsCommand {
+    pub public_keys_opt: Option<Vec<String>>,
+    pub strict: bool,
+}
+
+const PREFERRED_EXITS_SUBCOMMAND_ABOUT: &str =
+    "Pins the exit Nodes your Node's routes should leave the MASQ Network through, by public key. \
+     Without arguments, shows the exits currently pinned.";
+const PUBLIC_KEY_ARG_HELP: &str =
+    "Public key of an exit Node to pin. Pinning replaces whatever exits were pinned before.";
+const STRICT_ARG_HELP: &str =
+    "Refuse every exit but the pinned ones, even if that means no route can be found. Without \
+     this, other exits are used only when no route through a pinned one can be built.";
+const CLEAR_ARG_HELP: &str = "Unpin all exits, so that routes may leave through any Node.";
+
+pub fn preferred_exits_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("preferred-exits")
+        .about(PREFERRED_EXITS_SUBCOMMAND_ABOUT)
+        .arg(
+            Arg::with_name("public-key")
+                .help(PUBLIC_KEY_ARG_HELP)
+                .value_name("PUBLIC-KEY")
+                .index(1)
+                .multiple(true)
+                .required(false),
+        )
+        .arg(
+            Arg::with_name("strict")
+                .help(STRICT_ARG_HELP)
+                .long("strict")
+                .takes_value(false)
+                .requires("public-key"),
+        )
+        .arg(
+            Arg::with_name("clear")
+                .help(CLEAR_ARG_HELP)
+                .long("clear")
+                .takes_value(false)
+                .conflicts_with_all(&["public-key", "strict"]),
+        )
+}
+
+impl Command for PreferredExitsCommand {
+    fn execute(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        let input = UiPreferredExitsRequest {
+            public_keys_opt: self.public_keys_opt.clone(),
+            strict: self.strict,
+        };
+        let output: Result<UiPreferredExitsResponse, CommandError> =
+            transaction(input, context, STANDARD_COMMAND_TIMEOUT_MILLIS);
+        match output {
+            Ok(response) => {
+                Self::dump_preferred_exits(&response, context.stdout());
+                Ok(())
+            }
+            Err(Payload(code, message)) if code == NODE_NOT_RUNNING_ERROR => {
+                short_writeln!(
+                    context.stderr(),
+                    "MASQNode is not running; therefore pinned exits cannot be shown or changed."
+                );
+                Err(Payload(code, message))
+            }
+            Err(Payload(code, message)) if code == INVALID_PUBLIC_KEY_ERROR => {
+                short_writeln!(
+                    context.stderr(),
+                    "Pinned exits were not changed: {}",
+                    message
+                );
+                Err(Payload(code, message))
+            }
+            Err(e) => {
+                short_writeln!(context.stderr(), "Pinning exits failed: {:?}", e);
+                Err(e)
+            }
+        }
+    }
+
+    as_any_ref_in_trait_impl!();
+}
+
+impl PreferredExitsCommand {
+    pub fn new(pieces: &[String]) -> Result<Self, String> {
+        let matches = match preferred_exits_subcommand().get_matches_from_safe(pieces) {
+            Ok(matches) => matches,
+            Err(e) => return Err(format!("{}", e)),
+        };
+        let public_keys_opt = if matches.is_present("clear") {
+            Some(vec![])
+        } else {
+            matches
+                .values_of("public-key")
+                .map(|values| values.map(|value| value.to_string()).collect())
+        };
+        Ok(Self {
+            public_keys_opt,
+            strict: matches.is_present("strict"),
+        })
+    }
+
+    fn dump_preferred_exits(response: &UiPreferredExitsResponse, stdout: &mut dyn Write) {
+        if response.public_keys.is_empty() {
+            short_writeln!(
+                stdout,
+                "No exits are pinned; routes may leave through any Node."
+            );
+        } else {
+            short_writeln!(
+                stdout,
+                "Pinned exits ({}):",
+                if response.strict {
+                    "strict"
+                } else {
+                    "preferred"
+                }
+            );
+            response
+                .public_keys
+                .iter()
+                .for_each(|public_key| short_writeln!(stdout, "    {}", public_key));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_context::ContextError;
+    use crate::command_context::ContextError::ConnectionDropped;
+    use crate::commands::commands_common::CommandError::ConnectionProblem;
+    use crate::test_utils::mocks::CommandContextMock;
+    use masq_lib::messages::ToMessageBody;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(
+            PREFERRED_EXITS_SUBCOMMAND_ABOUT,
+            "Pins the exit Nodes your Node's routes should leave the MASQ Network through, by public key. \
+             Without arguments, shows the exits currently pinned."
+        );
+        assert_eq!(
+            PUBLIC_KEY_ARG_HELP,
+            "Public key of an exit Node to pin. Pinning replaces whatever exits were pinned before."
+        );
+        assert_eq!(
+            STRICT_ARG_HELP,
+            "Refuse every exit but the pinned ones, even if that means no route can be found. Without \
+             this, other exits are used only when no route through a pinned one can be built."
+        );
+        assert_eq!(
+            CLEAR_ARG_HELP,
+            "Unpin all exits, so that routes may leave through any Node."
+        );
+    }
+
+    #[test]
+    fn new_without_arguments_only_asks() {
+        let result = PreferredExitsCommand::new(&["preferred-exits".to_string()]);
+
+        assert_eq!(
+            result,
+            Ok(PreferredExitsCommand {
+                public_keys_opt: None,
+                strict: false,
+            })
+        );
+    }
+
+    #[test]
+    fn new_takes_public_keys_and_strictness() {
+        let result = PreferredExitsCommand::new(&[
+            "preferred-exits".to_string(),
+            "AQID".to_string(),
+            "BAUG".to_string(),
+            "--strict".to_string(),
+        ]);
+
+        assert_eq!(
+            result,
+            Ok(PreferredExitsCommand {
+                public_keys_opt: Some(vec!["AQID".to_string(), "BAUG".to_string()]),
+                strict: true,
+            })
+        );
+    }
+
+    #[test]
+    fn new_clears_with_an_empty_list() {
+        let result =
+            PreferredExitsCommand::new(&["preferred-exits".to_string(), "--clear".to_string()]);
+
+        assert_eq!(
+            result,
+            Ok(PreferredExitsCommand {
+                public_keys_opt: Some(vec![]),
+                strict: false,
+            })
+        );
+    }
+
+    #[test]
+    fn new_rejects_clear_with_public_keys_and_strict_without_them() {
+        let clear_with_keys = PreferredExitsCommand::new(&[
+            "preferred-exits".to_string(),
+            "AQID".to_string(),
+            "--clear".to_string(),
+        ])
+        .unwrap_err();
+        let strict_alone =
+            PreferredExitsCommand::new(&["preferred-exits".to_string(), "--strict".to_string()])
+                .unwrap_err();
+
+        assert!(
+            clear_with_keys.contains("cannot be used with"),
+            "{}",
+            clear_with_keys
+        );
+        assert!(
+            strict_alone.contains("The following required arguments were not provided"),
+            "{}",
+            strict_alone
+        );
+    }
+
+    #[test]
+    fn preferred_exits_command_happy_path() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let response = UiPreferredExitsResponse {
+            public_keys: vec!["AQID".to_string(), "BAUG".to_string()],
+            strict: true,
+        };
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(response.tmb(42)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = PreferredExitsCommand {
+            public_keys_opt: Some(vec!["AQID".to_string(), "BAUG".to_string()]),
+            strict: true,
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        let transact_params = transact_params_arc.lock().unwrap();
+        assert_eq!(
+            *transact_params,
+            vec![(
+                UiPreferredExitsRequest {
+                    public_keys_opt: Some(vec!["AQID".to_string(), "BAUG".to_string()]),
+                    strict: true,
+                }
+                .tmb(0),
+                STANDARD_COMMAND_TIMEOUT_MILLIS
+            )]
+        );
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "Pinned exits (strict):\n    AQID\n    BAUG\n"
+        );
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn preferred_exits_command_reports_when_nothing_is_pinned() {
+        let response = UiPreferredExitsResponse {
+            public_keys: vec![],
+            strict: false,
+        };
+        let mut context = CommandContextMock::new().transact_result(Ok(response.tmb(42)));
+        let stdout_arc = context.stdout_arc();
+        let subject = PreferredExitsCommand {
+            public_keys_opt: None,
+            strict: false,
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "No exits are pinned; routes may leave through any Node.\n"
+        );
+    }
+
+    #[test]
+    fn preferred_exits_command_reports_bad_public_keys() {
+        let mut context =
+            CommandContextMock::new().transact_result(Err(ContextError::PayloadError(
+                INVALID_PUBLIC_KEY_ERROR,
+                "Bad public key: 'booga'".to_string(),
+            )));
+        let stderr_arc = context.stderr_arc();
+        let subject = PreferredExitsCommand {
+            public_keys_opt: Some(vec!["booga".to_string()]),
+            strict: false,
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(
+            result,
+            Err(CommandError::Payload(
+                INVALID_PUBLIC_KEY_ERROR,
+                "Bad public key: 'booga'".to_string()
+            ))
+        );
+        assert_eq!(
+            stderr_arc.lock().unwrap().get_string(),
+            "Pinned exits were not changed: Bad public key: 'booga'\n"
+        );
+    }
+
+    #[test]
+    fn preferred_exits_command_doesnt_work_if_node_is_not_running() {
+        let mut context = CommandContextMock::new().transact_result(Err(
+            ContextError::PayloadError(NODE_NOT_RUNNING_ERROR, "irrelevant".to_string()),
+        ));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = PreferredExitsCommand {
+            public_keys_opt: None,
+            strict: false,
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(
+            result,
+            Err(CommandError::Payload(
+                NODE_NOT_RUNNING_ERROR,
+                "irrelevant".to_string()
+            ))
+        );
+        assert_eq!(
+            stderr_arc.lock().unwrap().get_string(),
+            "MASQNode is not running; therefore pinned exits cannot be shown or changed.\n"
+        );
+        assert_eq!(stdout_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn preferred_exits_command_sad_path() {
+        let mut context =
+            CommandContextMock::new().transact_result(Err(ConnectionDropped("Booga".to_string())));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = PreferredExitsCommand {
+            public_keys_opt: None,
+            strict: false,
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Err(ConnectionProblem("Booga".to_string())));
+        assert_eq!(stdout_arc.lock().unwrap().get_string(), String::new());
+        assert_eq!(
+            stderr_arc.lock().unwrap().get_string(),
+            "Pinning exits failed: ConnectionProblem(\"Booga\")\n"
+        );
+    }
+}
@@ -0,0 +1,191 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::command_context::CommandContext;
+use crate::commands::commands_common::CommandError::Payload;
+use crate::commands::commands_common::{
+    transaction, Command, CommandError, STANDARD_COMMAND_TIMEOUT_MILLIS,
+};
+use clap::{App, SubCommand};
+use masq_lib::as_any_ref_in_trait_impl;
+use masq_lib::constants::NODE_NOT_RUNNING_ERROR;
+use masq_lib::messages::{UiRotateKeysRequest, UiRotateKeysResponse};
+use masq_lib::short_writeln;
+use std::fmt::Debug;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct RotateKeysCommand {}
+
+const ROTATE_KEYS_SUBCOMMAND_ABOUT: &str =
+    "Generates new keys for the running MASQNode and tells its neighbors about them. The new keys \
+     go into service the next time the Node starts. Only valid if Node is already running and was \
+     started with a database password.";
+
+pub fn rotate_keys_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("rotate-keys").about(ROTATE_KEYS_SUBCOMMAND_ABOUT)
+}
+
+impl Command for RotateKeysCommand {
+    fn execute(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        let input = UiRotateKeysRequest {};
+        let output: Result<UiRotateKeysResponse, CommandError> =
+            transaction(input, context, STANDARD_COMMAND_TIMEOUT_MILLIS);
+        match output {
+            Ok(response) => {
+                short_writeln!(
+                    context.stdout(),
+                    "Keys have been rotated. This Node is known as {} until it restarts, and as {} \
+                     afterward.",
+                    response.old_public_key,
+                    response.new_public_key
+                );
+                Ok(())
+            }
+            Err(Payload(code, message)) if code == NODE_NOT_RUNNING_ERROR => {
+                short_writeln!(
+                    context.stderr(),
+                    "MASQNode is not running; therefore its keys cannot be rotated."
+                );
+                Err(Payload(code, message))
+            }
+            Err(e) => {
+                short_writeln!(context.stderr(), "Key rotation failed: {:?}", e);
+                Err(e)
+            }
+        }
+    }
+
+    as_any_ref_in_trait_impl!();
+}
+
+impl Default for RotateKeysCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RotateKeysCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_context::ContextError;
+    use crate::command_factory::{CommandFactory, CommandFactoryReal};
+    use crate::test_utils::mocks::CommandContextMock;
+    use masq_lib::constants::KEY_ROTATION_ERROR;
+    use masq_lib::messages::ToMessageBody;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(
+            ROTATE_KEYS_SUBCOMMAND_ABOUT,
+            "Generates new keys for the running MASQNode and tells its neighbors about them. The \
+             new keys go into service the next time the Node starts. Only valid if Node is already \
+             running and was started with a database password."
+        );
+    }
+
+    #[test]
+    fn factory_produces_rotate_keys() {
+        let factory = CommandFactoryReal::new();
+
+        let command = factory.make(&["rotate-keys".to_string()]).unwrap();
+
+        assert_eq!(
+            command
+                .as_any()
+                .downcast_ref::<RotateKeysCommand>()
+                .unwrap(),
+            &RotateKeysCommand {}
+        );
+    }
+
+    #[test]
+    fn rotate_keys_command_reports_old_and_new_keys() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(UiRotateKeysResponse {
+                old_public_key: "AQIDBA".to_string(),
+                new_public_key: "BQYHCA".to_string(),
+            }
+            .tmb(42)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = RotateKeysCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        let transact_params = transact_params_arc.lock().unwrap();
+        assert_eq!(
+            *transact_params,
+            vec![(
+                UiRotateKeysRequest {}.tmb(0),
+                STANDARD_COMMAND_TIMEOUT_MILLIS
+            )]
+        );
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "Keys have been rotated. This Node is known as AQIDBA until it restarts, and as BQYHCA \
+             afterward.\n"
+        );
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn rotate_keys_command_reports_refusal() {
+        let mut context =
+            CommandContextMock::new().transact_result(Err(ContextError::PayloadError(
+                KEY_ROTATION_ERROR,
+                "Keys can't be rotated without a database password".to_string(),
+            )));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = RotateKeysCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(
+            result,
+            Err(Payload(
+                KEY_ROTATION_ERROR,
+                "Keys can't be rotated without a database password".to_string()
+            ))
+        );
+        assert_eq!(stdout_arc.lock().unwrap().get_string(), String::new());
+        assert_eq!(
+            stderr_arc.lock().unwrap().get_string(),
+            format!(
+                "Key rotation failed: Payload({}, \"Keys can't be rotated without a database password\")\n",
+                KEY_ROTATION_ERROR
+            )
+        );
+    }
+
+    #[test]
+    fn doesnt_work_if_node_is_not_running() {
+        let mut context = CommandContextMock::new().transact_result(Err(
+            ContextError::PayloadError(NODE_NOT_RUNNING_ERROR, "irrelevant".to_string()),
+        ));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = RotateKeysCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(
+            result,
+            Err(Payload(NODE_NOT_RUNNING_ERROR, "irrelevant".to_string()))
+        );
+        assert_eq!(stdout_arc.lock().unwrap().get_string(), String::new());
+        assert_eq!(
+            stderr_arc.lock().unwrap().get_string(),
+            "MASQNode is not running; therefore its keys cannot be rotated.\n"
+        );
+    }
+}
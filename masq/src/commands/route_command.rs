@@ -0,0 +1,499 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::command_context::CommandContext;
+use crate::commands::commands_common::CommandError::Payload;
+use crate::commands::commands_common::{
+    transaction, Command, CommandError, STANDARD_COMMAND_TIMEOUT_MILLIS,
+};
+use clap::{App, Arg, SubCommand};
+use masq_lib::as_any_ref_in_trait_impl;
+use masq_lib::constants::{
+    COMBINED_PARAMETERS_DELIMITER, NODE_NOT_RUNNING_ERROR, ROUTE_NOT_FOUND_ERROR,
+};
+use masq_lib::messages::{UiRouteHop, UiRouteRequest, UiRouteResponse, UiRouteService};
+use masq_lib::short_writeln;
+use std::fmt::Debug;
+use std::io::Write;
+use thousands::Separable;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct RouteCommand {
+    pub hostname: String,
+    pub port_opt: Option<u16>,
+    pub payload_size: u64,
+    pub response_size_opt: Option<u64>,
+}
+
+const ROUTE_SUBCOMMAND_ABOUT: &str =
+    "Shows the route your Node would choose to reach a host right now, with the services each Node \
+     on it would provide and what they would cost, without sending anything.";
+const HOSTNAME_ARG_HELP: &str = "Name of the host the route would lead to.";
+const PORT_ARG_HELP: &str = "Port on the host the route would lead to.";
+const PAYLOAD_SIZE_ARG_HELP: &str = "Size in bytes of the request whose cost is estimated.";
+const RESPONSE_SIZE_ARG_HELP: &str =
+    "Size in bytes of the response whose cost is estimated. If this is omitted, your Node \
+     estimates it from the size of the request.";
+
+const PAYLOAD_SIZE_ARG_DEFAULT_VALUE: &str = "1000";
+
+pub fn route_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("route")
+        .about(ROUTE_SUBCOMMAND_ABOUT)
+        .arg(
+            Arg::with_name("hostname")
+                .help(HOSTNAME_ARG_HELP)
+                .index(1)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("port")
+                .help(PORT_ARG_HELP)
+                .long("port")
+                .value_name("PORT")
+                .required(false)
+                .takes_value(true)
+                .validator(validate_port),
+        )
+        .arg(
+            Arg::with_name("payload-size")
+                .help(PAYLOAD_SIZE_ARG_HELP)
+                .long("payload-size")
+                .value_name("BYTES")
+                .required(false)
+                .default_value(PAYLOAD_SIZE_ARG_DEFAULT_VALUE)
+                .takes_value(true)
+                .validator(validate_size),
+        )
+        .arg(
+            Arg::with_name("response-size")
+                .help(RESPONSE_SIZE_ARG_HELP)
+                .long("response-size")
+                .value_name("BYTES")
+                .required(false)
+                .takes_value(true)
+                .validator(validate_size),
+        )
+}
+
+fn validate_port(port: String) -> Result<(), String> {
+    match port.parse::<u16>() {
+        Ok(port) if port > 0 => Ok(()),
+        _ => Err(format!(
+            "Port must be an integer from 1 to 65535, not '{}'",
+            port
+        )),
+    }
+}
+
+fn validate_size(size: String) -> Result<(), String> {
+    match size.parse::<u64>() {
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!(
+            "Size must be a nonnegative integer number of bytes, not '{}'",
+            size
+        )),
+    }
+}
+
+impl Command for RouteCommand {
+    fn execute(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        let input = UiRouteRequest {
+            hostname: self.hostname.clone(),
+            target_port_opt: self.port_opt,
+            payload_size: self.payload_size,
+            response_size_opt: self.response_size_opt,
+        };
+        let output: Result<UiRouteResponse, CommandError> =
+            transaction(input, context, STANDARD_COMMAND_TIMEOUT_MILLIS);
+        match output {
+            Ok(response) => {
+                self.dump_route(&response, context.stdout());
+                Ok(())
+            }
+            Err(Payload(code, message)) if code == NODE_NOT_RUNNING_ERROR => {
+                short_writeln!(
+                    context.stderr(),
+                    "MASQNode is not running; therefore no route can be shown."
+                );
+                Err(Payload(code, message))
+            }
+            Err(Payload(code, message)) if code == ROUTE_NOT_FOUND_ERROR => {
+                short_writeln!(
+                    context.stderr(),
+                    "No route to {} qualifies: {}",
+                    self.hostname,
+                    message
+                );
+                Err(Payload(code, message))
+            }
+            Err(e) => {
+                short_writeln!(context.stderr(), "Route retrieval failed: {:?}", e);
+                Err(e)
+            }
+        }
+    }
+
+    as_any_ref_in_trait_impl!();
+}
+
+impl RouteCommand {
+    pub fn new(pieces: &[String]) -> Result<Self, String> {
+        let matches = match route_subcommand().get_matches_from_safe(pieces) {
+            Ok(matches) => matches,
+            Err(e) => return Err(format!("{}", e)),
+        };
+        let parse_size = |value: &str| {
+            value
+                .parse::<u64>()
+                .expect("size parameter is not properly validated")
+        };
+        Ok(Self {
+            hostname: matches
+                .value_of("hostname")
+                .expect("hostname parameter is not properly required")
+                .to_string(),
+            port_opt: matches.value_of("port").map(|port| {
+                port.parse::<u16>()
+                    .expect("port parameter is not properly validated")
+            }),
+            payload_size: parse_size(
+                matches
+                    .value_of("payload-size")
+                    .expect("payload-size parameter is not properly defaulted"),
+            ),
+            response_size_opt: matches.value_of("response-size").map(parse_size),
+        })
+    }
+
+    fn dump_route(&self, response: &UiRouteResponse, stdout: &mut dyn Write) {
+        short_writeln!(
+            stdout,
+            "Route to {}{} with at least {} hops, for a {}-byte request and a {}-byte response:",
+            self.hostname,
+            match self.port_opt {
+                Some(port) => format!(":{}", port),
+                None => String::new(),
+            },
+            response.min_hops,
+            response.payload_size.separate_with_commas(),
+            response.response_size.separate_with_commas()
+        );
+        Self::dump_hops(stdout, "Over", &response.over);
+        Self::dump_hops(stdout, "Back", &response.back);
+        short_writeln!(
+            stdout,
+            "\nEstimated charge: {} wei",
+            response.total_charge_wei.separate_with_commas()
+        );
+    }
+
+    fn dump_hops(stdout: &mut dyn Write, title: &str, hops: &[UiRouteHop]) {
+        short_writeln!(stdout, "\n{}:", title);
+        short_writeln!(
+            stdout,
+            "{:<44} {:<8} {:<32} {:>20}",
+            "Public key",
+            "Service",
+            "Rate pack",
+            "Charge (wei)"
+        );
+        hops.iter().for_each(|hop| {
+            let service = match hop.service {
+                UiRouteService::Nothing => "-",
+                UiRouteService::Routing => "Routing",
+                UiRouteService::Exit => "Exit",
+            };
+            let rate_pack = match &hop.rate_pack_opt {
+                Some(rate_pack) => format!(
+                    "{}{d}{}{d}{}{d}{}",
+                    rate_pack.routing_byte_rate,
+                    rate_pack.routing_service_rate,
+                    rate_pack.exit_byte_rate,
+                    rate_pack.exit_service_rate,
+                    d = COMBINED_PARAMETERS_DELIMITER
+                ),
+                None => "-".to_string(),
+            };
+            short_writeln!(
+                stdout,
+                "{:<44} {:<8} {:<32} {:>20}",
+                hop.public_key,
+                service,
+                rate_pack,
+                hop.charge_wei.separate_with_commas()
+            )
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_context::ContextError;
+    use crate::command_context::ContextError::ConnectionDropped;
+    use crate::commands::commands_common::CommandError::ConnectionProblem;
+    use crate::test_utils::mocks::CommandContextMock;
+    use masq_lib::messages::{ToMessageBody, UiRatePack};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(
+            ROUTE_SUBCOMMAND_ABOUT,
+            "Shows the route your Node would choose to reach a host right now, with the services each Node \
+             on it would provide and what they would cost, without sending anything."
+        );
+        assert_eq!(
+            HOSTNAME_ARG_HELP,
+            "Name of the host the route would lead to."
+        );
+        assert_eq!(PORT_ARG_HELP, "Port on the host the route would lead to.");
+        assert_eq!(
+            PAYLOAD_SIZE_ARG_HELP,
+            "Size in bytes of the request whose cost is estimated."
+        );
+        assert_eq!(
+            RESPONSE_SIZE_ARG_HELP,
+            "Size in bytes of the response whose cost is estimated. If this is omitted, your Node \
+             estimates it from the size of the request."
+        );
+        assert_eq!(PAYLOAD_SIZE_ARG_DEFAULT_VALUE, "1000");
+    }
+
+    #[test]
+    fn new_requires_hostname_and_defaults_the_rest() {
+        let result = RouteCommand::new(&["route".to_string(), "booga.com".to_string()]);
+
+        assert_eq!(
+            result,
+            Ok(RouteCommand {
+                hostname: "booga.com".to_string(),
+                port_opt: None,
+                payload_size: 1000,
+                response_size_opt: None,
+            })
+        );
+        let missing = RouteCommand::new(&["route".to_string()]).unwrap_err();
+        assert!(
+            missing.contains("The following required arguments were not provided"),
+            "{}",
+            missing
+        );
+    }
+
+    #[test]
+    fn new_accepts_port_and_sizes() {
+        let result = RouteCommand::new(&[
+            "route".to_string(),
+            "booga.com".to_string(),
+            "--port".to_string(),
+            "443".to_string(),
+            "--payload-size".to_string(),
+            "2048".to_string(),
+            "--response-size".to_string(),
+            "65536".to_string(),
+        ]);
+
+        assert_eq!(
+            result,
+            Ok(RouteCommand {
+                hostname: "booga.com".to_string(),
+                port_opt: Some(443),
+                payload_size: 2048,
+                response_size_opt: Some(65536),
+            })
+        );
+    }
+
+    #[test]
+    fn validate_port_accepts_only_usable_ports() {
+        assert_eq!(validate_port("1".to_string()), Ok(()));
+        assert_eq!(validate_port("65535".to_string()), Ok(()));
+        assert_eq!(
+            validate_port("0".to_string()),
+            Err("Port must be an integer from 1 to 65535, not '0'".to_string())
+        );
+        assert_eq!(
+            validate_port("65536".to_string()),
+            Err("Port must be an integer from 1 to 65535, not '65536'".to_string())
+        );
+    }
+
+    #[test]
+    fn validate_size_accepts_only_nonnegative_integers() {
+        assert_eq!(validate_size("0".to_string()), Ok(()));
+        assert_eq!(
+            validate_size("-1".to_string()),
+            Err("Size must be a nonnegative integer number of bytes, not '-1'".to_string())
+        );
+        assert_eq!(
+            validate_size("big".to_string()),
+            Err("Size must be a nonnegative integer number of bytes, not 'big'".to_string())
+        );
+    }
+
+    #[test]
+    fn route_command_happy_path() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let rate_pack = UiRatePack {
+            routing_byte_rate: 1,
+            routing_service_rate: 20,
+            exit_byte_rate: 3,
+            exit_service_rate: 40,
+        };
+        let originator = UiRouteHop {
+            public_key: "AQIDBA".to_string(),
+            service: UiRouteService::Nothing,
+            earning_wallet_opt: None,
+            rate_pack_opt: None,
+            charge_wei: 0,
+        };
+        let relay = |charge_wei: u64| UiRouteHop {
+            public_key: "AgMEBQ".to_string(),
+            service: UiRouteService::Routing,
+            earning_wallet_opt: Some("0x0000000000000000000000000000000000000001".to_string()),
+            rate_pack_opt: Some(rate_pack.clone()),
+            charge_wei,
+        };
+        let exit = |charge_wei: u64| UiRouteHop {
+            public_key: "AwQFBg".to_string(),
+            service: UiRouteService::Exit,
+            earning_wallet_opt: Some("0x0000000000000000000000000000000000000002".to_string()),
+            rate_pack_opt: Some(rate_pack.clone()),
+            charge_wei,
+        };
+        let response = UiRouteResponse {
+            min_hops: 2,
+            payload_size: 1000,
+            response_size: 1_000_000,
+            over: vec![originator.clone(), relay(1020), exit(3040)],
+            back: vec![exit(3_000_040), relay(1_000_020), originator],
+            total_charge_wei: 4_004_120,
+        };
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(response.tmb(42)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = RouteCommand {
+            hostname: "booga.com".to_string(),
+            port_opt: Some(443),
+            payload_size: 1000,
+            response_size_opt: None,
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        let transact_params = transact_params_arc.lock().unwrap();
+        assert_eq!(
+            *transact_params,
+            vec![(
+                UiRouteRequest {
+                    hostname: "booga.com".to_string(),
+                    target_port_opt: Some(443),
+                    payload_size: 1000,
+                    response_size_opt: None,
+                }
+                .tmb(0),
+                STANDARD_COMMAND_TIMEOUT_MILLIS
+            )]
+        );
+        let line = |key: &str, service: &str, rate_pack: &str, charge: &str| {
+            format!(
+                "{:<44} {:<8} {:<32} {:>20}\n",
+                key, service, rate_pack, charge
+            )
+        };
+        let header = line("Public key", "Service", "Rate pack", "Charge (wei)");
+        let expected = format!(
+            "Route to booga.com:443 with at least 2 hops, for a 1,000-byte request and a 1,000,000-byte response:\n\
+             \n\
+             Over:\n\
+             {}{}{}{}\
+             \n\
+             Back:\n\
+             {}{}{}{}\
+             \n\
+             Estimated charge: 4,004,120 wei\n",
+            header,
+            line("AQIDBA", "-", "-", "0"),
+            line("AgMEBQ", "Routing", "1|20|3|40", "1,020"),
+            line("AwQFBg", "Exit", "1|20|3|40", "3,040"),
+            header,
+            line("AwQFBg", "Exit", "1|20|3|40", "3,000,040"),
+            line("AgMEBQ", "Routing", "1|20|3|40", "1,000,020"),
+            line("AQIDBA", "-", "-", "0"),
+        );
+        assert_eq!(stdout_arc.lock().unwrap().get_string(), expected);
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn route_command_explains_when_no_route_qualifies() {
+        let mut context = CommandContextMock::new().transact_result(Err(
+            ContextError::PayloadError(ROUTE_NOT_FOUND_ERROR, "Minimum hops: 3".to_string()),
+        ));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = RouteCommand::new(&["route".to_string(), "booga.com".to_string()]).unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(
+            result,
+            Err(CommandError::Payload(
+                ROUTE_NOT_FOUND_ERROR,
+                "Minimum hops: 3".to_string()
+            ))
+        );
+        assert_eq!(
+            stderr_arc.lock().unwrap().get_string(),
+            "No route to booga.com qualifies: Minimum hops: 3\n"
+        );
+        assert_eq!(stdout_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn route_command_doesnt_work_if_node_is_not_running() {
+        let mut context = CommandContextMock::new().transact_result(Err(
+            ContextError::PayloadError(NODE_NOT_RUNNING_ERROR, "irrelevant".to_string()),
+        ));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = RouteCommand::new(&["route".to_string(), "booga.com".to_string()]).unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(
+            result,
+            Err(CommandError::Payload(
+                NODE_NOT_RUNNING_ERROR,
+                "irrelevant".to_string()
+            ))
+        );
+        assert_eq!(
+            stderr_arc.lock().unwrap().get_string(),
+            "MASQNode is not running; therefore no route can be shown.\n"
+        );
+        assert_eq!(stdout_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn route_command_sad_path() {
+        let mut context =
+            CommandContextMock::new().transact_result(Err(ConnectionDropped("Booga".to_string())));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = RouteCommand::new(&["route".to_string(), "booga.com".to_string()]).unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Err(ConnectionProblem("Booga".to_string())));
+        assert_eq!(stdout_arc.lock().unwrap().get_string(), String::new());
+        assert_eq!(
+            stderr_arc.lock().unwrap().get_string(),
+            "Route retrieval failed: ConnectionProblem(\"Booga\")\n"
+        );
+    }
+}
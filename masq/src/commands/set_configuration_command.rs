@@ -3,6 +3,7 @@ use crate::commands::commands_common::{transaction, Command, CommandError};
 use clap::{App, Arg, ArgGroup, SubCommand};
 use masq_lib::as_any_ref_in_trait_impl;
 use masq_lib::messages::{UiSetConfigurationRequest, UiSetConfigurationResponse};
+use masq_lib::shared_schema::common_validators::validate_separate_u64_values;
 use masq_lib::shared_schema::gas_price_arg;
 use masq_lib::shared_schema::min_hops_arg;
 use masq_lib::short_writeln;
@@ -50,6 +51,20 @@ fn validate_start_block(start_block: String) -> Result<(), String> {
     }
 }
 
+fn validate_max_rate_pack(max_rate_pack: String) -> Result<(), String> {
+    if "none".eq_ignore_ascii_case(&max_rate_pack) {
+        return Ok(());
+    }
+    validate_separate_u64_values(max_rate_pack.clone())?;
+    match max_rate_pack.split('|').count() {
+        4 => Ok(()),
+        count => Err(format!(
+            "Supply four rates like routing_byte|routing_service|exit_byte|exit_service or 'none'; found {}",
+            count
+        )),
+    }
+}
+
 impl Command for SetConfigurationCommand {
     fn execute(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
         let input = UiSetConfigurationRequest {
@@ -67,6 +82,9 @@ impl Command for SetConfigurationCommand {
 
 const SET_CONFIGURATION_ABOUT: &str =
     "Sets Node configuration parameters being enabled for this operation when the Node is running.";
+const MAX_RATE_PACK_HELP: &str =
+    "Highest rates, in wei, that a Node on your routes may charge: routing_byte|routing_service|exit_byte|exit_service. \
+     Nodes charging more are left out of routes. Use 'none' to remove the limit.";
 const START_BLOCK_HELP: &str =
     "Ordinal number of the Ethereum block where scanning for transactions will start. Use 'latest' or 'none' for Latest block.";
 
@@ -78,6 +96,15 @@ pub fn set_configuration_subcommand() -> App<'static, 'static> {
     SubCommand::with_name("set-configuration")
         .about(SET_CONFIGURATION_ABOUT)
         .arg(set_configurationify(gas_price_arg()))
+        .arg(
+            Arg::with_name("max-rate-pack")
+                .help(MAX_RATE_PACK_HELP)
+                .long("max-rate-pack")
+                .value_name("MAX-RATE-PACK")
+                .takes_value(true)
+                .required(false)
+                .validator(validate_max_rate_pack),
+        )
         .arg(set_configurationify(min_hops_arg()))
         .arg(
             Arg::with_name("start-block")
@@ -90,7 +117,7 @@ pub fn set_configuration_subcommand() -> App<'static, 'static> {
         )
         .group(
             ArgGroup::with_name("parameter")
-                .args(&["gas-price", "min-hops", "start-block"])
+                .args(&["gas-price", "max-rate-pack", "min-hops", "start-block"])
                 .required(true),
         )
 }
@@ -110,6 +137,12 @@ mod tests {
             SET_CONFIGURATION_ABOUT,
             "Sets Node configuration parameters being enabled for this operation when the Node is running."
         );
+        assert_eq!(
+            MAX_RATE_PACK_HELP,
+            "Highest rates, in wei, that a Node on your routes may charge: \
+             routing_byte|routing_service|exit_byte|exit_service. Nodes charging more are left out \
+             of routes. Use 'none' to remove the limit."
+        );
         assert_eq!(
             START_BLOCK_HELP,
             "Ordinal number of the Ethereum block where scanning for transactions will start. Use 'latest' or 'none' for Latest block."
@@ -155,10 +188,29 @@ mod tests {
         assert_eq!(validate_start_block("lATEst".to_string()), Ok(()));
     }
 
+    #[test]
+    fn validate_max_rate_pack_works() {
+        assert_eq!(validate_max_rate_pack("1|2|3|4".to_string()), Ok(()));
+        assert_eq!(validate_max_rate_pack("none".to_string()), Ok(()));
+        assert_eq!(validate_max_rate_pack("NoNe".to_string()), Ok(()));
+        assert_eq!(
+            validate_max_rate_pack("1|2|3".to_string()),
+            Err("Supply four rates like routing_byte|routing_service|exit_byte|exit_service or 'none'; found 3".to_string())
+        );
+        assert_eq!(
+            validate_max_rate_pack("1|2|booga|4".to_string()),
+            Err(
+                "Supply positive numeric values separated by vertical bars like 111|222|333|..."
+                    .to_string()
+            )
+        );
+    }
+
     #[test]
     fn command_execution_works_all_fine() {
         test_command_execution("--start-block", "123456");
         test_command_execution("--gas-price", "123456");
+        test_command_execution("--max-rate-pack", "1|2|3|4");
         test_command_execution("--min-hops", "6");
     }
 
@@ -166,6 +218,7 @@ mod tests {
     fn set_configuration_command_throws_err_for_missing_values() {
         set_configuration_command_throws_err_for_missing_value("--start-block");
         set_configuration_command_throws_err_for_missing_value("--gas-price");
+        set_configuration_command_throws_err_for_missing_value("--max-rate-pack");
         set_configuration_command_throws_err_for_missing_value("--min-hops");
     }
 
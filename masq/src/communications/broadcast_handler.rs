@@ -7,7 +7,8 @@ use crate::terminal::terminal_interface::TerminalWrapper;
 use crossbeam_channel::{unbounded, RecvError, Sender};
 use masq_lib::messages::{
     FromMessageBody, UiConnectionChangeBroadcast, UiLogBroadcast, UiNewPasswordBroadcast,
-    UiNodeCrashedBroadcast, UiSetupBroadcast, UiUndeliveredFireAndForget,
+    UiNodeCrashedBroadcast, UiRoutesPricedOutBroadcast, UiSetupBroadcast,
+    UiUndeliveredFireAndForget,
 };
 use masq_lib::ui_gateway::MessageBody;
 use masq_lib::utils::ExpectValue;
@@ -17,6 +18,7 @@ use std::io::Write;
 use std::thread;
 
 use crate::notifications::connection_change_notification::ConnectionChangeNotification;
+use crate::notifications::routes_priced_out_notification::RoutesPricedOutNotification;
 
 pub trait BroadcastHandle: Send {
     fn send(&self, message_body: MessageBody);
@@ -111,6 +113,9 @@ impl BroadcastHandlerReal {
                         stdout,
                         terminal_interface,
                     );
+                } else if let Ok((body, _)) = UiRoutesPricedOutBroadcast::fmb(message_body.clone())
+                {
+                    RoutesPricedOutNotification::handle_broadcast(body, stdout, terminal_interface);
                 } else {
                     handle_unrecognized_broadcast(message_body, stderr, terminal_interface)
                 }
@@ -194,7 +199,7 @@ mod tests {
     use masq_lib::messages::UiSetupResponseValueStatus::{Configured, Default};
     use masq_lib::messages::{
         CrashReason, SerializableLogLevel, ToMessageBody, UiConnectionChangeBroadcast,
-        UiConnectionStage, UiLogBroadcast, UiNodeCrashedBroadcast,
+        UiConnectionStage, UiLogBroadcast, UiNodeCrashedBroadcast, UiRatePack,
     };
     use masq_lib::messages::{UiSetupBroadcast, UiSetupResponseValue, UiSetupResponseValueStatus};
     use masq_lib::ui_gateway::MessagePath;
@@ -378,6 +383,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn ui_routes_priced_out_broadcast_is_handled_properly() {
+        let (factory, handle) = TestStreamFactory::new();
+        let (mut stdout, mut stderr) = factory.make();
+        let terminal_interface = TerminalWrapper::new(Arc::new(TerminalPassiveMock::new()));
+
+        let message_body = UiRoutesPricedOutBroadcast {
+            min_hops: 3,
+            max_rate_pack: UiRatePack {
+                routing_byte_rate: 1,
+                routing_service_rate: 2,
+                exit_byte_rate: 3,
+                exit_service_rate: 4,
+            },
+        }
+        .tmb(0);
+
+        let result = BroadcastHandlerReal::handle_message_body(
+            Ok(message_body),
+            &mut stdout,
+            &mut stderr,
+            &terminal_interface,
+        );
+
+        assert!(result);
+        let stdout = handle.stdout_so_far();
+        assert!(
+            stdout.starts_with("\nRoutesPricedOut: No 3-hop route can be built"),
+            "stdout: '{}'",
+            stdout
+        );
+        assert_eq!(
+            handle.stderr_so_far(),
+            "".to_string(),
+            "stderr: '{}'",
+            stdout
+        );
+    }
+
     #[test]
     fn unexpected_broadcasts_are_ineffectual_but_dont_kill_the_handler() {
         let (factory, handle) = TestStreamFactory::new();
@@ -2,3 +2,4 @@
 
 pub mod connection_change_notification;
 pub mod crashed_notification;
+pub mod routes_priced_out_notification;
@@ -0,0 +1,68 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::terminal::terminal_interface::TerminalWrapper;
+use masq_lib::messages::UiRoutesPricedOutBroadcast;
+use masq_lib::short_writeln;
+use std::io::Write;
+
+pub struct RoutesPricedOutNotification {}
+
+impl RoutesPricedOutNotification {
+    pub fn handle_broadcast(
+        response: UiRoutesPricedOutBroadcast,
+        stdout: &mut dyn Write,
+        term_interface: &TerminalWrapper,
+    ) {
+        let _lock = term_interface.lock();
+        let max_rate_pack = response.max_rate_pack;
+        short_writeln!(
+            stdout,
+            "\nRoutesPricedOut: No {}-hop route can be built from Nodes charging no more than \
+             {}|{}|{}|{}. Raise the limit with 'set-configuration --max-rate-pack', or remove it \
+             with 'set-configuration --max-rate-pack none'.\n",
+            response.min_hops,
+            max_rate_pack.routing_byte_rate,
+            max_rate_pack.routing_service_rate,
+            max_rate_pack.exit_byte_rate,
+            max_rate_pack.exit_service_rate
+        );
+        stdout.flush().expect("flush failed");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::mocks::TerminalPassiveMock;
+    use masq_lib::messages::UiRatePack;
+    use masq_lib::test_utils::fake_stream_holder::ByteArrayWriter;
+    use masq_lib::utils::running_test;
+    use std::sync::Arc;
+
+    #[test]
+    fn broadcasts_routes_priced_out() {
+        running_test();
+        let mut stdout = ByteArrayWriter::new();
+        let stderr = ByteArrayWriter::new();
+        let msg = UiRoutesPricedOutBroadcast {
+            min_hops: 3,
+            max_rate_pack: UiRatePack {
+                routing_byte_rate: 1,
+                routing_service_rate: 2,
+                exit_byte_rate: 3,
+                exit_service_rate: 4,
+            },
+        };
+        let term_interface = TerminalWrapper::new(Arc::new(TerminalPassiveMock::new()));
+
+        RoutesPricedOutNotification::handle_broadcast(msg, &mut stdout, &term_interface);
+
+        assert_eq!(
+            stdout.get_string(),
+            "\nRoutesPricedOut: No 3-hop route can be built from Nodes charging no more than \
+             1|2|3|4. Raise the limit with 'set-configuration --max-rate-pack', or remove it \
+             with 'set-configuration --max-rate-pack none'.\n\n"
+        );
+        assert_eq!(stderr.get_string(), "".to_string());
+    }
+}
@@ -1,5 +1,6 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+use crate::commands::banned_command::banned_subcommand;
 use crate::commands::change_password_command::{
     change_password_subcommand, set_password_subcommand,
 };
@@ -8,9 +9,15 @@ use crate::commands::configuration_command::configuration_subcommand;
 use crate::commands::connection_status_command::connection_status_subcommand;
 use crate::commands::crash_command::crash_subcommand;
 use crate::commands::descriptor_command::descriptor_subcommand;
+use crate::commands::excluded_nodes_command::excluded_nodes_subcommand;
+use crate::commands::exit_locations_command::exit_locations_subcommand;
 use crate::commands::financials_command::args_validation::financials_subcommand;
 use crate::commands::generate_wallets_command::generate_wallets_subcommand;
+use crate::commands::neighborhood_command::neighborhood_subcommand;
+use crate::commands::preferred_exits_command::preferred_exits_subcommand;
 use crate::commands::recover_wallets_command::recover_wallets_subcommand;
+use crate::commands::rotate_keys_command::rotate_keys_subcommand;
+use crate::commands::route_command::route_subcommand;
 use crate::commands::scan_command::scan_subcommand;
 use crate::commands::set_configuration_command::set_configuration_subcommand;
 use crate::commands::setup_command::setup_subcommand;
@@ -61,15 +68,22 @@ pub fn app() -> App<'static, 'static> {
                 .validator(validate_ui_port)
                 .help(UI_PORT_HELP.as_str()),
         )
+        .subcommand(banned_subcommand())
         .subcommand(change_password_subcommand())
         .subcommand(check_password_subcommand())
         .subcommand(crash_subcommand())
         .subcommand(configuration_subcommand())
         .subcommand(connection_status_subcommand())
         .subcommand(descriptor_subcommand())
+        .subcommand(excluded_nodes_subcommand())
+        .subcommand(exit_locations_subcommand())
         .subcommand(financials_subcommand())
         .subcommand(generate_wallets_subcommand())
+        .subcommand(neighborhood_subcommand())
+        .subcommand(preferred_exits_subcommand())
         .subcommand(recover_wallets_subcommand())
+        .subcommand(rotate_keys_subcommand())
+        .subcommand(route_subcommand())
         .subcommand(scan_subcommand())
         .subcommand(set_configuration_subcommand())
         .subcommand(set_password_subcommand())
@@ -5,11 +5,12 @@ use crate::data_version::DataVersion;
 use const_format::concatcp;
 
 pub const DEFAULT_CHAIN: Chain = Chain::PolyMainnet;
-pub const CURRENT_SCHEMA_VERSION: usize = 10;
+pub const CURRENT_SCHEMA_VERSION: usize = 19;
 
 pub const HIGHEST_RANDOM_CLANDESTINE_PORT: u16 = 9999;
 pub const HTTP_PORT: u16 = 80;
 pub const TLS_PORT: u16 = 443;
+pub const SOCKS_PORT: u16 = 1080;
 pub const LOWEST_USABLE_INSECURE_PORT: u16 = 1025;
 pub const HIGHEST_USABLE_PORT: u16 = 65535;
 pub const DEFAULT_UI_PORT: u16 = 5333;
@@ -41,10 +42,12 @@ pub const MULTINODE_TESTNET_CONTRACT_CREATION_BLOCK: u64 = 0;
 // please add it to the test: check_limits_of_data_versions_const()
 pub const CLIENT_REQUEST_PAYLOAD_CURRENT_VERSION: DataVersion = DataVersion { major: 0, minor: 1 };
 pub const CLIENT_RESPONSE_PAYLOAD_CURRENT_VERSION: DataVersion = DataVersion { major: 0, minor: 1 };
+pub const COVER_TRAFFIC_CURRENT_VERSION: DataVersion = DataVersion { major: 0, minor: 1 };
 pub const DNS_RESOLVER_FAILURE_CURRENT_VERSION: DataVersion = DataVersion { major: 0, minor: 1 };
 pub const GOSSIP_CURRENT_VERSION: DataVersion = DataVersion { major: 0, minor: 1 };
 pub const GOSSIP_FAILURE_CURRENT_VERSION: DataVersion = DataVersion { major: 0, minor: 1 };
 pub const NODE_RECORD_INNER_CURRENT_VERSION: DataVersion = DataVersion { major: 0, minor: 1 };
+pub const SERVICE_REFUSED_CURRENT_VERSION: DataVersion = DataVersion { major: 0, minor: 1 };
 
 //error codes
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -81,6 +84,14 @@ pub const ACCOUNTANT_PREFIX: u64 = 0x0040_0000_0000_0000;
 pub const REQUEST_WITH_NO_VALUES: u64 = ACCOUNTANT_PREFIX | 1;
 pub const REQUEST_WITH_MUTUALLY_EXCLUSIVE_PARAMS: u64 = ACCOUNTANT_PREFIX | 2;
 pub const VALUE_EXCEEDS_ALLOWED_LIMIT: u64 = ACCOUNTANT_PREFIX | 3;
+pub const INVALID_WALLET_ERROR: u64 = ACCOUNTANT_PREFIX | 4;
+
+//neighborhood
+pub const NEIGHBORHOOD_PREFIX: u64 = 0x0020_0000_0000_0000;
+pub const ROUTE_NOT_FOUND_ERROR: u64 = NEIGHBORHOOD_PREFIX | 1;
+pub const INVALID_PUBLIC_KEY_ERROR: u64 = NEIGHBORHOOD_PREFIX | 2;
+pub const ROUTING_PREFERENCES_WRITE_ERROR: u64 = NEIGHBORHOOD_PREFIX | 3;
+pub const KEY_ROTATION_ERROR: u64 = NEIGHBORHOOD_PREFIX | 4;
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
@@ -114,6 +125,7 @@ mod tests {
         assert_eq!(HIGHEST_RANDOM_CLANDESTINE_PORT, 9999);
         assert_eq!(HTTP_PORT, 80);
         assert_eq!(TLS_PORT, 443);
+        assert_eq!(SOCKS_PORT, 1080);
         assert_eq!(LOWEST_USABLE_INSECURE_PORT, 1025);
         assert_eq!(HIGHEST_USABLE_PORT, 65535);
         assert_eq!(DEFAULT_UI_PORT, 5333);
@@ -164,6 +176,12 @@ mod tests {
             ACCOUNTANT_PREFIX | 2
         );
         assert_eq!(VALUE_EXCEEDS_ALLOWED_LIMIT, ACCOUNTANT_PREFIX | 3);
+        assert_eq!(INVALID_WALLET_ERROR, ACCOUNTANT_PREFIX | 4);
+        assert_eq!(NEIGHBORHOOD_PREFIX, 0x0020_0000_0000_0000);
+        assert_eq!(ROUTE_NOT_FOUND_ERROR, NEIGHBORHOOD_PREFIX | 1);
+        assert_eq!(INVALID_PUBLIC_KEY_ERROR, NEIGHBORHOOD_PREFIX | 2);
+        assert_eq!(ROUTING_PREFERENCES_WRITE_ERROR, NEIGHBORHOOD_PREFIX | 3);
+        assert_eq!(KEY_ROTATION_ERROR, NEIGHBORHOOD_PREFIX | 4);
         assert_eq!(CENTRAL_DELIMITER, '@');
         assert_eq!(CHAIN_IDENTIFIER_DELIMITER, ':');
         assert_eq!(POLYGON_FAMILY, "polygon");
@@ -185,6 +203,10 @@ mod tests {
             CLIENT_RESPONSE_PAYLOAD_CURRENT_VERSION,
             DataVersion { major: 0, minor: 1 }
         );
+        assert_eq!(
+            COVER_TRAFFIC_CURRENT_VERSION,
+            DataVersion { major: 0, minor: 1 }
+        );
         assert_eq!(
             DNS_RESOLVER_FAILURE_CURRENT_VERSION,
             DataVersion { major: 0, minor: 1 }
@@ -198,6 +220,10 @@ mod tests {
             NODE_RECORD_INNER_CURRENT_VERSION,
             DataVersion { major: 0, minor: 1 }
         );
+        assert_eq!(
+            SERVICE_REFUSED_CURRENT_VERSION,
+            DataVersion { major: 0, minor: 1 }
+        );
     }
 
     #[test]
@@ -205,10 +231,12 @@ mod tests {
         [
             CLIENT_REQUEST_PAYLOAD_CURRENT_VERSION,
             CLIENT_RESPONSE_PAYLOAD_CURRENT_VERSION,
+            COVER_TRAFFIC_CURRENT_VERSION,
             DNS_RESOLVER_FAILURE_CURRENT_VERSION,
             GOSSIP_CURRENT_VERSION,
             GOSSIP_FAILURE_CURRENT_VERSION,
             NODE_RECORD_INNER_CURRENT_VERSION,
+            SERVICE_REFUSED_CURRENT_VERSION,
         ]
         .into_iter()
         .for_each(|item| {
@@ -434,6 +434,35 @@ fire_and_forget_message!(UiUnmarshalError, "unmarshalError");
 // These messages are sent to or by the Node only
 ///////////////////////////////////////////////////////////////////
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiBannedRequest {
+    pub ban: Vec<String>,
+    pub unban: Vec<String>,
+    #[serde(rename = "noteOpt")]
+    pub note_opt: Option<String>,
+}
+conversation_message!(UiBannedRequest, "banned");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiBannedWallet {
+    pub wallet: String,
+    #[serde(rename = "bannedAtSecOpt")]
+    pub banned_at_sec_opt: Option<i64>,
+    pub manual: bool,
+    #[serde(rename = "noteOpt")]
+    pub note_opt: Option<String>,
+    #[serde(rename = "balanceGweiOpt")]
+    pub balance_gwei_opt: Option<i64>,
+    #[serde(rename = "debtAgeSecOpt")]
+    pub debt_age_sec_opt: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiBannedResponse {
+    pub banned: Vec<UiBannedWallet>,
+}
+conversation_message!(UiBannedResponse, "banned");
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct UiChangePasswordRequest {
     #[serde(rename = "oldPasswordOpt")]
@@ -507,6 +536,9 @@ pub struct UiConfigurationResponse {
     pub rate_pack: UiRatePack,
     #[serde(rename = "scanIntervals")]
     pub scan_intervals: UiScanIntervals,
+    // Fees per unit of gas agreed for the latest payments; None until the Node has paid anything.
+    #[serde(rename = "transactionFeesOpt")]
+    pub transaction_fees_opt: Option<UiTransactionFees>,
 }
 
 conversation_message!(UiConfigurationResponse, "configuration");
@@ -533,6 +565,17 @@ pub struct UiScanIntervals {
     pub receivable_sec: u64,
 }
 
+// A legacy transaction pays its gas price, which is reported here as the maximum fee, and has
+// no priority fee. An EIP-1559 transaction pays the base fee plus the priority fee, never more
+// than the maximum fee.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiTransactionFees {
+    #[serde(rename = "maxFeePerGasWei")]
+    pub max_fee_per_gas_wei: u64,
+    #[serde(rename = "maxPriorityFeePerGasWeiOpt")]
+    pub max_priority_fee_per_gas_wei_opt: Option<u64>,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct UiPaymentThresholds {
     #[serde(rename = "thresholdIntervalSec")]
@@ -562,6 +605,15 @@ pub struct UiConnectionChangeBroadcast {
 }
 fire_and_forget_message!(UiConnectionChangeBroadcast, "connectionChange");
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiRoutesPricedOutBroadcast {
+    #[serde(rename = "minHops")]
+    pub min_hops: u8,
+    #[serde(rename = "maxRatePack")]
+    pub max_rate_pack: UiRatePack,
+}
+fire_and_forget_message!(UiRoutesPricedOutBroadcast, "routesPricedOut");
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct UiConnectionStatusRequest {}
 
@@ -585,6 +637,41 @@ pub struct UiDescriptorResponse {
 }
 conversation_message!(UiDescriptorResponse, "descriptor");
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiExcludedNodesRequest {
+    pub add: Vec<String>,
+    pub remove: Vec<String>,
+}
+conversation_message!(UiExcludedNodesRequest, "excludedNodes");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiExcludedNodesResponse {
+    #[serde(rename = "publicKeys")]
+    pub public_keys: Vec<String>,
+}
+conversation_message!(UiExcludedNodesResponse, "excludedNodes");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiExitLocationsRequest {}
+conversation_message!(UiExitLocationsRequest, "exitLocations");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiExitCountry {
+    #[serde(rename = "countryCode")]
+    pub country_code: String,
+    #[serde(rename = "nodeCount")]
+    pub node_count: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiExitLocationsResponse {
+    #[serde(rename = "exitCountries")]
+    pub exit_countries: Vec<UiExitCountry>,
+    #[serde(rename = "exitLocationOpt")]
+    pub exit_location_opt: Option<String>,
+}
+conversation_message!(UiExitLocationsResponse, "exitLocations");
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct UiFinancialsRequest {
     #[serde(rename = "statsRequired")]
@@ -743,10 +830,72 @@ pub enum SerializableLogLevel {
     Info,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UiNeighborhoodGraphFormat {
+    Json,
+    Dot,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiNeighborhoodGraphRequest {
+    pub format: UiNeighborhoodGraphFormat,
+}
+conversation_message!(UiNeighborhoodGraphRequest, "neighborhoodGraph");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiGraphNode {
+    #[serde(rename = "publicKey")]
+    pub public_key: String,
+    #[serde(rename = "nodeAddrOpt")]
+    pub node_addr_opt: Option<String>,
+    pub version: u32,
+    #[serde(rename = "isRoot")]
+    pub is_root: bool,
+    #[serde(rename = "acceptsConnections")]
+    pub accepts_connections: bool,
+    #[serde(rename = "routesData")]
+    pub routes_data: bool,
+    #[serde(rename = "countryCodeOpt")]
+    pub country_code_opt: Option<String>,
+    #[serde(rename = "ratePack")]
+    pub rate_pack: UiRatePack,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiGraphEdge {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiNeighborhoodGraphResponse {
+    pub nodes: Vec<UiGraphNode>,
+    pub edges: Vec<UiGraphEdge>,
+    #[serde(rename = "dotGraphOpt")]
+    pub dot_graph_opt: Option<String>,
+}
+conversation_message!(UiNeighborhoodGraphResponse, "neighborhoodGraph");
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct UiNewPasswordBroadcast {}
 fire_and_forget_message!(UiNewPasswordBroadcast, "newPassword");
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiPreferredExitsRequest {
+    #[serde(rename = "publicKeysOpt")]
+    pub public_keys_opt: Option<Vec<String>>,
+    pub strict: bool,
+}
+conversation_message!(UiPreferredExitsRequest, "preferredExits");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiPreferredExitsResponse {
+    #[serde(rename = "publicKeys")]
+    pub public_keys: Vec<String>,
+    pub strict: bool,
+}
+conversation_message!(UiPreferredExitsResponse, "preferredExits");
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct UiRecoverSeedSpec {
     #[serde(rename = "mnemonicPhrase")]
@@ -778,6 +927,93 @@ conversation_message!(UiRecoverWalletsRequest, "recoverWallets");
 pub struct UiRecoverWalletsResponse {}
 conversation_message!(UiRecoverWalletsResponse, "recoverWallets");
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiRelayReputationsRequest {}
+conversation_message!(UiRelayReputationsRequest, "relayReputations");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiRelayReputation {
+    #[serde(rename = "publicKey")]
+    pub public_key: String,
+    pub penalty: u64,
+    #[serde(rename = "lastFailureSec")]
+    pub last_failure_sec: u32,
+    #[serde(rename = "connectionFailures")]
+    pub connection_failures: u32,
+    #[serde(rename = "streamDrops")]
+    pub stream_drops: u32,
+    #[serde(rename = "dnsFailures")]
+    pub dns_failures: u32,
+    #[serde(rename = "serviceRefusals")]
+    pub service_refusals: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiRelayReputationsResponse {
+    pub reputations: Vec<UiRelayReputation>,
+}
+conversation_message!(UiRelayReputationsResponse, "relayReputations");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiRotateKeysRequest {}
+conversation_message!(UiRotateKeysRequest, "rotateKeys");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiRotateKeysResponse {
+    #[serde(rename = "oldPublicKey")]
+    pub old_public_key: String,
+    #[serde(rename = "newPublicKey")]
+    pub new_public_key: String,
+}
+conversation_message!(UiRotateKeysResponse, "rotateKeys");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiRouteRequest {
+    pub hostname: String,
+    #[serde(rename = "targetPortOpt")]
+    pub target_port_opt: Option<u16>,
+    #[serde(rename = "payloadSize")]
+    pub payload_size: u64,
+    #[serde(rename = "responseSizeOpt")]
+    pub response_size_opt: Option<u64>,
+}
+conversation_message!(UiRouteRequest, "route");
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UiRouteService {
+    Nothing,
+    Routing,
+    Exit,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiRouteHop {
+    #[serde(rename = "publicKey")]
+    pub public_key: String,
+    pub service: UiRouteService,
+    #[serde(rename = "earningWalletOpt")]
+    pub earning_wallet_opt: Option<String>,
+    #[serde(rename = "ratePackOpt")]
+    pub rate_pack_opt: Option<UiRatePack>,
+    #[serde(rename = "chargeWei")]
+    pub charge_wei: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiRouteResponse {
+    #[serde(rename = "minHops")]
+    pub min_hops: u8,
+    #[serde(rename = "payloadSize")]
+    pub payload_size: u64,
+    #[serde(rename = "responseSize")]
+    pub response_size: u64,
+    pub over: Vec<UiRouteHop>,
+    pub back: Vec<UiRouteHop>,
+    #[serde(rename = "totalChargeWei")]
+    pub total_charge_wei: u64,
+}
+conversation_message!(UiRouteResponse, "route");
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum ScanType {
     Payables,
@@ -10,6 +10,11 @@ use crate::crash_point::CrashPoint;
 use clap::{App, Arg};
 use lazy_static::lazy_static;
 
+pub const BINARY_MASQUERADE_HELP: &str =
+    "If 'on', your Node offers its neighbors a binary masquerade: CORES packages framed with a short \
+     header and no disguise at all. It's cheaper to relay than the masquerades that make your traffic look like \
+     TLS, HTTP or JSON, but anyone watching your connections can see that they carry MASQ traffic. A neighbor \
+     uses it only if it offers it too and neither of you has a lookalike masquerade in common. [default: off]";
 pub const BLOCKCHAIN_SERVICE_HELP: &str =
     "The Ethereum client you wish to use to provide Blockchain \
      exit services from your MASQ Node (e.g. http://localhost:8545, \
@@ -26,6 +31,10 @@ pub const CONFIG_FILE_HELP: &str =
      look for your config file starting in the --data-directory. If you specify an absolute path, \
      --data-directory will be ignored when searching for the config file. A few parameters \
      (such as --config-file, --generate-wallet, and --recover-wallet) must not be specified in a config file.";
+pub const CONNECT_PORTS_HELP: &str =
+    "Comma-separated list of destination ports to which the forward-proxy listener will open \
+     CONNECT tunnels; CONNECT requests to any other port are refused. Used only if --forward-proxy-port \
+     is specified. [default: 443]";
 pub const CONSUMING_PRIVATE_KEY_HELP: &str = "The private key for the Ethereum wallet from which you wish to pay \
      other Nodes for routing and exit services. Mostly this is used for testing; be careful using it for real \
      traffic, because this value is very sensitive: anyone who sees it can use it to drain your consuming wallet. \
@@ -33,6 +42,15 @@ pub const CONSUMING_PRIVATE_KEY_HELP: &str = "The private key for the Ethereum w
      make sure you haven't already set up a consuming wallet with a derivation path, and make sure that you always \
      supply exactly the same private key every time you run the Node. A consuming private key is 64 case-insensitive \
      hexadecimal digits.";
+pub const COUNTRY_CODE_HELP: &str =
+    "The two-letter ISO 3166-1 country code (e.g. CZ) of the jurisdiction in which your Node \
+     lets traffic out onto the Internet when it acts as an exit. It's advertised to other Nodes so that \
+     consumers can choose exits by location; nobody verifies it, so please be honest. [default: none]";
+pub const COVER_TRAFFIC_INTERVAL_HELP: &str =
+    "How many milliseconds your Node waits between rounds of cover traffic: dummy CORES packages it \
+     sends to each of its neighbors so that someone watching its connections can't tell when it's really \
+     carrying data. Neighbors throw the dummies away on arrival, and nobody is charged for them. Values range \
+     from 1 to 65535. Leave this out to send no cover traffic. [default: none]";
 pub const DATA_DIRECTORY_HELP: &str =
     "Directory in which the Node will store its persistent state, including at least its database \
     and by default its configuration file as well.\nNote: any existing database in the data directory \
@@ -50,11 +68,28 @@ pub const EARNING_WALLET_HELP: &str =
      (case-insensitive). If you already have a derivation-path earning wallet, don't supply this. \
      If you have supplied an earning wallet address before, either don't supply it again or be \
      careful to supply exactly the same one you supplied before.";
+pub const EXIT_LOCATION_HELP: &str =
+    "Comma-separated list of two-letter ISO 3166-1 country codes (e.g. CZ,SK) where you'd like \
+     your traffic to leave the MASQ Network. Prefix the list with 'strict:' to use only exits that declare one \
+     of those countries, even if that means no route can be found, or with 'preferred:' (the default) to \
+     favor them while still using other exits when necessary. [default: none]";
+pub const EXIT_POLICY_HELP: &str =
+    "Comma-separated list of destinations to which your Node will refuse to open streams when it \
+     acts as an exit for other Nodes. Each entry is a port (25), a port range (6881-6889), an IP address \
+     or network (10.0.0.0/8), a host name (example.com, which covers its subdomains as well), or the word \
+     'private', which stands for all loopback, link-local and private networks. Your exit policy is \
+     advertised to other Nodes, so they won't route such traffic through you. [default: none]";
 pub const IP_ADDRESS_HELP: &str = "The public IP address of your MASQ Node: that is, the IPv4 \
      address at which other Nodes can contact yours. If you're running your Node behind \
      a router, this will be the IP address of the router. If this IP address starts with 192.168 or 10.0, \
      it's a local address rather than a public address, and other Nodes won't be able to see yours. \
      --ip is meaningless except in --neighborhood-mode standard.";
+pub const LATENCY_WEIGHT_HELP: &str =
+    "How much each millisecond of round-trip time between two neighboring Nodes adds to the \
+     undesirability of a route through them, in the same units (wei) as the routing and exit charges \
+     the route is chosen to minimize. Your Node measures the round-trip time to its own neighbors and \
+     learns the others from Gossip. Set this to 0 to ignore latency when choosing routes. \
+     [default: 10000000000]";
 pub const LOG_LEVEL_HELP: &str =
     "The minimum severity of the logs that should appear in the Node's logfile. You should probably not specify \
      a level lower than the default unless you're doing testing or forensics: a Node at the 'trace' log level \
@@ -111,6 +146,12 @@ pub const MAPPING_PROTOCOL_HELP: &str =
     public IP address with the --ip parameter. If the Node communicates successfully with your router, \
     it will remember the protocol it used, and on its next run it will try that protocol first, unless \
     you specify a different protocol on the command line.";
+pub const MULTIPATH_ROUTES_HELP: &str =
+    "How many routes your Node spreads each of its connections across. With a value above 1, once your \
+     Node has a route for a connection it looks for up to that many routes in all to the same exit, none of \
+     which share a relay with another, and takes turns sending the connection's data down each of them, so \
+     that no single slow relay caps the connection's speed. Each route is paid for separately. The exit puts \
+     the data back in order, so the site can't tell the difference. Values range from 1 to 4. [default: 1]";
 pub const MIN_HOPS_HELP: &str =
     "The Node is a system that routes data through multiple Nodes to enhance security and privacy. \
     However, the level of anonymity and security provided depends on the number of hops specified \
@@ -144,6 +185,21 @@ pub const SCANS_HELP: &str =
     started when the Node starts, and will have to be triggered later manually and individually with the \
     MASQNode-UIv2 'scan' command. (If you don't, you'll most likely be delinquency-banned by all your neighbors.) \
     This parameter is most useful for testing.";
+pub const STICKY_ROUTE_TTL_HELP: &str =
+    "How many seconds your Node keeps reusing the route it found for a destination hostname, so that new \
+     connections to the same site leave the MASQ Network through the same exit and the site sees the same IP \
+     address throughout your session. A route is forgotten once it has gone unused for this long, or as soon \
+     as it fails. Set this to 0 to find a new route for every connection. [default: 300]";
+pub const PACKET_PADDING_HELP: &str =
+    "If 'on', your Node pads every CORES package it sends to one of a few fixed sizes before encrypting \
+     it, so that someone watching its connections can't work out from the sizes of the packages how much data \
+     they carry. Padding costs extra bandwidth, most of all for small packages. [default: off]";
+pub const PENDING_SCANS_BEFORE_REPLACEMENT_HELP: &str =
+    "How many pending payable scans a payment you sent may go unconfirmed before your Node sends it \
+     again under the same nonce with higher fees, so that a transaction priced out by a surge in fees doesn't \
+     hold up your payments for hours. Only one of the two can ever be mined, and whichever it is settles the \
+     debt. A payment is replaced only once, and never with fees beyond those allowed by --gas-price. Set this \
+     to 0 to leave stuck transactions alone. [default: 6]";
 pub const RATE_PACK_HELP: &str = "\
      These four parameters specify your rates that your Node will use for charging other Nodes for your provided \
      services. These are ever present values, defaulted if left unspecified. The parameters must be always supplied \
@@ -216,6 +272,20 @@ lazy_static! {
          Must be between {} and {} [default: last used port]",
         LOWEST_USABLE_INSECURE_PORT, HIGHEST_USABLE_PORT
     );
+    pub static ref SOCKS_PORT_HELP: String = format!(
+        "The local port at which the Node will accept SOCKS5 CONNECT requests from applications that \
+         are configured to use it as a SOCKS proxy. This lets you send individual applications through \
+         MASQ without subverting your system's DNS. If you don't specify a SOCKS port, no SOCKS listener \
+         is started. Must be between {} and {}.",
+        LOWEST_USABLE_INSECURE_PORT, HIGHEST_USABLE_PORT
+    );
+    pub static ref FORWARD_PROXY_PORT_HELP: String = format!(
+        "The local port at which the Node will act as an explicit HTTP proxy: it accepts absolute-URI \
+         requests and CONNECT requests from browsers that are configured to use it, and serves a proxy \
+         auto-configuration (PAC) file at http://127.0.0.1:<port>/proxy.pac. If you don't specify a \
+         forward-proxy port, no forward-proxy listener is started. Must be between {} and {}.",
+        LOWEST_USABLE_INSECURE_PORT, HIGHEST_USABLE_PORT
+    );
     pub static ref GAS_PRICE_HELP: String = format!(
        "The Gas Price is the amount of gwei you will pay per unit of gas used in a transaction. \
        If left unspecified, MASQ Node will use the previously stored value (Default {}).",
@@ -357,6 +427,14 @@ fn common_parameter_with_separate_u64_values<'a>(name: &'a str, help: &'a str) -
 
 pub fn shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
     head.arg(
+        Arg::with_name("binary-masquerade")
+            .long("binary-masquerade")
+            .value_name("BINARY-MASQUERADE")
+            .takes_value(true)
+            .possible_values(&["on", "off"])
+            .help(BINARY_MASQUERADE_HELP),
+    )
+    .arg(
         Arg::with_name("blockchain-service-url")
             .long("blockchain-service-url")
             .value_name("URL")
@@ -374,6 +452,15 @@ pub fn shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
             .help(&CLANDESTINE_PORT_HELP),
     )
     .arg(config_file_arg())
+    .arg(
+        Arg::with_name("connect-ports")
+            .long("connect-ports")
+            .value_name("CONNECT-PORTS")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_connect_ports)
+            .help(CONNECT_PORTS_HELP),
+    )
     .arg(
         Arg::with_name("consuming-private-key")
             .long("consuming-private-key")
@@ -383,6 +470,24 @@ pub fn shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
             .validator(common_validators::validate_private_key)
             .help(CONSUMING_PRIVATE_KEY_HELP),
     )
+    .arg(
+        Arg::with_name("country-code")
+            .long("country-code")
+            .value_name("COUNTRY-CODE")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_country_code)
+            .help(COUNTRY_CODE_HELP),
+    )
+    .arg(
+        Arg::with_name("cover-traffic-interval")
+            .long("cover-traffic-interval")
+            .value_name("MILLISECONDS")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_non_zero_u16)
+            .help(COVER_TRAFFIC_INTERVAL_HELP),
+    )
     .arg(
         Arg::with_name("crash-point")
             .long("crash-point")
@@ -408,6 +513,24 @@ pub fn shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
         EARNING_WALLET_HELP,
         common_validators::validate_ethereum_address,
     ))
+    .arg(
+        Arg::with_name("exit-location")
+            .long("exit-location")
+            .value_name("EXIT-LOCATION")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_exit_location)
+            .help(EXIT_LOCATION_HELP),
+    )
+    .arg(
+        Arg::with_name("exit-policy")
+            .long("exit-policy")
+            .value_name("EXIT-POLICY")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_exit_policy)
+            .help(EXIT_POLICY_HELP),
+    )
     .arg(
         Arg::with_name("fake-public-key")
             .long("fake-public-key")
@@ -416,6 +539,15 @@ pub fn shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
             .max_values(1)
             .hidden(true),
     )
+    .arg(
+        Arg::with_name("forward-proxy-port")
+            .long("forward-proxy-port")
+            .value_name("FORWARD-PROXY-PORT")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_forward_proxy_port)
+            .help(&FORWARD_PROXY_PORT_HELP),
+    )
     .arg(gas_price_arg())
     .arg(
         Arg::with_name("ip")
@@ -426,6 +558,15 @@ pub fn shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
             .validator(common_validators::validate_ip_address)
             .help(IP_ADDRESS_HELP),
     )
+    .arg(
+        Arg::with_name("latency-weight")
+            .long("latency-weight")
+            .value_name("LATENCY-WEIGHT")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_latency_weight)
+            .help(LATENCY_WEIGHT_HELP),
+    )
     .arg(
         Arg::with_name("log-level")
             .long("log-level")
@@ -447,6 +588,15 @@ pub fn shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
             .help(MAPPING_PROTOCOL_HELP),
     )
     .arg(min_hops_arg())
+    .arg(
+        Arg::with_name("multipath-routes")
+            .long("multipath-routes")
+            .value_name("MULTIPATH-ROUTES")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_multipath_routes)
+            .help(MULTIPATH_ROUTES_HELP),
+    )
     .arg(
         Arg::with_name("neighborhood-mode")
             .long("neighborhood-mode")
@@ -464,6 +614,23 @@ pub fn shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
             .min_values(0)
             .help(NEIGHBORS_HELP),
     )
+    .arg(
+        Arg::with_name("packet-padding")
+            .long("packet-padding")
+            .value_name("PACKET-PADDING")
+            .takes_value(true)
+            .possible_values(&["on", "off"])
+            .help(PACKET_PADDING_HELP),
+    )
+    .arg(
+        Arg::with_name("pending-scans-before-replacement")
+            .long("pending-scans-before-replacement")
+            .value_name("PENDING-SCANS-BEFORE-REPLACEMENT")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_pending_scans_before_replacement)
+            .help(PENDING_SCANS_BEFORE_REPLACEMENT_HELP),
+    )
     .arg(real_user_arg())
     .arg(
         Arg::with_name("scans")
@@ -473,6 +640,24 @@ pub fn shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
             .possible_values(&["on", "off"])
             .help(SCANS_HELP),
     )
+    .arg(
+        Arg::with_name("socks-port")
+            .long("socks-port")
+            .value_name("SOCKS-PORT")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_socks_port)
+            .help(&SOCKS_PORT_HELP),
+    )
+    .arg(
+        Arg::with_name("sticky-route-ttl")
+            .long("sticky-route-ttl")
+            .value_name("STICKY-ROUTE-TTL")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_sticky_route_ttl)
+            .help(STICKY_ROUTE_TTL_HELP),
+    )
     .arg(common_parameter_with_separate_u64_values(
         "scan-intervals",
         SCAN_INTERVALS_HELP,
@@ -604,6 +789,100 @@ pub mod common_validators {
         }
     }
 
+    pub fn validate_socks_port(port: String) -> Result<(), String> {
+        match str::parse::<u16>(&port) {
+            Ok(port_number) if port_number < LOWEST_USABLE_INSECURE_PORT => Err(port),
+            Ok(_) => Ok(()),
+            Err(_) => Err(port),
+        }
+    }
+
+    pub fn validate_forward_proxy_port(port: String) -> Result<(), String> {
+        match str::parse::<u16>(&port) {
+            Ok(port_number) if port_number < LOWEST_USABLE_INSECURE_PORT => Err(port),
+            Ok(_) => Ok(()),
+            Err(_) => Err(port),
+        }
+    }
+
+    pub fn validate_connect_ports(ports: String) -> Result<(), String> {
+        if ports
+            .split(',')
+            .all(|port| matches!(str::parse::<u16>(port), Ok(port_number) if port_number > 0))
+        {
+            Ok(())
+        } else {
+            Err(ports)
+        }
+    }
+
+    pub fn validate_country_code(country_code: String) -> Result<(), String> {
+        let trimmed = country_code.trim();
+        if trimmed.len() == 2 && trimmed.chars().all(|c| c.is_ascii_alphabetic()) {
+            Ok(())
+        } else {
+            Err(country_code)
+        }
+    }
+
+    pub fn validate_exit_location(location: String) -> Result<(), String> {
+        let country_codes = location
+            .trim()
+            .strip_prefix("strict:")
+            .or_else(|| location.trim().strip_prefix("preferred:"))
+            .unwrap_or(&location);
+        if country_codes
+            .split(',')
+            .all(|country_code| validate_country_code(country_code.to_string()).is_ok())
+        {
+            Ok(())
+        } else {
+            Err(location)
+        }
+    }
+
+    pub fn validate_exit_policy(policy: String) -> Result<(), String> {
+        let port_range = Regex::new(r"^(\d+)(?:-(\d+))?$").expect("Bad regex");
+        let host_name = Regex::new(r"^[A-Za-z0-9-]+(\.[A-Za-z0-9-]+)*$").expect("Bad regex");
+        let is_valid_rule = |rule: &str| {
+            if rule == "private" {
+                return true;
+            }
+            if let Some(captures) = port_range.captures(rule) {
+                let low = captures[1].parse::<u16>().ok();
+                let high = captures
+                    .get(2)
+                    .map_or(low, |high| high.as_str().parse::<u16>().ok());
+                return matches!((low, high), (Some(low), Some(high)) if low > 0 && low <= high);
+            }
+            let (address, prefix_len_opt) = match rule.split_once('/') {
+                Some((address, prefix_len)) => (address, Some(prefix_len)),
+                None => (rule, None),
+            };
+            match (IpAddr::from_str(address), prefix_len_opt) {
+                (Ok(_), None) => true,
+                (Ok(ip_addr), Some(prefix_len)) => {
+                    let max_prefix_len = if ip_addr.is_ipv4() { 32 } else { 128 };
+                    matches!(prefix_len.parse::<u8>(), Ok(len) if len <= max_prefix_len)
+                }
+                (Err(_), None) => host_name.is_match(rule),
+                (Err(_), Some(_)) => false,
+            }
+        };
+        if policy.split(',').all(|rule| is_valid_rule(rule.trim())) {
+            Ok(())
+        } else {
+            Err(policy)
+        }
+    }
+
+    pub fn validate_latency_weight(weight: String) -> Result<(), String> {
+        match str::parse::<u64>(&weight) {
+            Ok(weight_value) if weight_value <= i64::MAX as u64 => Ok(()),
+            _ => Err(weight),
+        }
+    }
+
     pub fn validate_non_zero_u16(str: String) -> Result<(), String> {
         match str::parse::<u16>(&str) {
             Ok(num) if num > 0 => Ok(()),
@@ -611,6 +890,27 @@ pub mod common_validators {
         }
     }
 
+    pub fn validate_multipath_routes(routes: String) -> Result<(), String> {
+        match str::parse::<usize>(&routes) {
+            Ok(count) if (1..=4).contains(&count) => Ok(()),
+            _ => Err(routes),
+        }
+    }
+
+    pub fn validate_sticky_route_ttl(ttl: String) -> Result<(), String> {
+        match str::parse::<u64>(&ttl) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(ttl),
+        }
+    }
+
+    pub fn validate_pending_scans_before_replacement(scans: String) -> Result<(), String> {
+        match str::parse::<u16>(&scans) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(scans),
+        }
+    }
+
     pub fn validate_separate_u64_values(values_with_delimiters: String) -> Result<(), String> {
         values_with_delimiters.split('|').try_for_each(|segment| {
             segment
@@ -683,6 +983,13 @@ mod tests {
 
     #[test]
     fn constants_have_correct_values() {
+        assert_eq!(
+            BINARY_MASQUERADE_HELP,
+            "If 'on', your Node offers its neighbors a binary masquerade: CORES packages framed with a short \
+             header and no disguise at all. It's cheaper to relay than the masquerades that make your traffic look like \
+             TLS, HTTP or JSON, but anyone watching your connections can see that they carry MASQ traffic. A neighbor \
+             uses it only if it offers it too and neither of you has a lookalike masquerade in common. [default: off]"
+        );
         assert_eq!(
             BLOCKCHAIN_SERVICE_HELP,
             "The Ethereum client you wish to use to provide Blockchain \
@@ -705,6 +1012,12 @@ mod tests {
              --data-directory will be ignored when searching for the config file. A few parameters \
              (such as --config-file, --generate-wallet, and --recover-wallet) must not be specified in a config file."
         );
+        assert_eq!(
+            CONNECT_PORTS_HELP,
+            "Comma-separated list of destination ports to which the forward-proxy listener will open \
+             CONNECT tunnels; CONNECT requests to any other port are refused. Used only if --forward-proxy-port \
+             is specified. [default: 443]"
+        );
         assert_eq!(
             CONSUMING_PRIVATE_KEY_HELP,
             "The private key for the Ethereum wallet from which you wish to pay \
@@ -715,6 +1028,19 @@ mod tests {
              supply exactly the same private key every time you run the Node. A consuming private key is 64 case-insensitive \
              hexadecimal digits."
         );
+        assert_eq!(
+            COUNTRY_CODE_HELP,
+            "The two-letter ISO 3166-1 country code (e.g. CZ) of the jurisdiction in which your Node \
+             lets traffic out onto the Internet when it acts as an exit. It's advertised to other Nodes so that \
+             consumers can choose exits by location; nobody verifies it, so please be honest. [default: none]"
+        );
+        assert_eq!(
+            COVER_TRAFFIC_INTERVAL_HELP,
+            "How many milliseconds your Node waits between rounds of cover traffic: dummy CORES packages it \
+             sends to each of its neighbors so that someone watching its connections can't tell when it's really \
+             carrying data. Neighbors throw the dummies away on arrival, and nobody is charged for them. Values range \
+             from 1 to 65535. Leave this out to send no cover traffic. [default: none]"
+        );
         assert_eq!(
             DATA_DIRECTORY_HELP,
             "Directory in which the Node will store its persistent state, including at \
@@ -741,6 +1067,21 @@ mod tests {
              If you have supplied an earning wallet address before, either don't supply it again or be \
              careful to supply exactly the same one you supplied before."
         );
+        assert_eq!(
+            EXIT_LOCATION_HELP,
+            "Comma-separated list of two-letter ISO 3166-1 country codes (e.g. CZ,SK) where you'd like \
+             your traffic to leave the MASQ Network. Prefix the list with 'strict:' to use only exits that declare one \
+             of those countries, even if that means no route can be found, or with 'preferred:' (the default) to \
+             favor them while still using other exits when necessary. [default: none]"
+        );
+        assert_eq!(
+            EXIT_POLICY_HELP,
+            "Comma-separated list of destinations to which your Node will refuse to open streams when it \
+             acts as an exit for other Nodes. Each entry is a port (25), a port range (6881-6889), an IP address \
+             or network (10.0.0.0/8), a host name (example.com, which covers its subdomains as well), or the word \
+             'private', which stands for all loopback, link-local and private networks. Your exit policy is \
+             advertised to other Nodes, so they won't route such traffic through you. [default: none]"
+        );
         assert_eq!(
             IP_ADDRESS_HELP,
             "The public IP address of your MASQ Node: that is, the IPv4 \
@@ -749,6 +1090,14 @@ mod tests {
              it's a local address rather than a public address, and other Nodes won't be able to see yours. \
              --ip is meaningless except in --neighborhood-mode standard."
         );
+        assert_eq!(
+            LATENCY_WEIGHT_HELP,
+            "How much each millisecond of round-trip time between two neighboring Nodes adds to the \
+             undesirability of a route through them, in the same units (wei) as the routing and exit charges \
+             the route is chosen to minimize. Your Node measures the round-trip time to its own neighbors and \
+             learns the others from Gossip. Set this to 0 to ignore latency when choosing routes. \
+             [default: 10000000000]"
+        );
         assert_eq!(
             LOG_LEVEL_HELP,
             "The minimum severity of the logs that should appear in the Node's logfile. You should probably not specify \
@@ -862,6 +1211,55 @@ mod tests {
                 LOWEST_USABLE_INSECURE_PORT, HIGHEST_USABLE_PORT
             )
         );
+        assert_eq!(
+            SOCKS_PORT_HELP.to_string(),
+            format!(
+                "The local port at which the Node will accept SOCKS5 CONNECT requests from applications that \
+                 are configured to use it as a SOCKS proxy. This lets you send individual applications through \
+                 MASQ without subverting your system's DNS. If you don't specify a SOCKS port, no SOCKS listener \
+                 is started. Must be between {} and {}.",
+                LOWEST_USABLE_INSECURE_PORT, HIGHEST_USABLE_PORT
+            )
+        );
+        assert_eq!(
+            FORWARD_PROXY_PORT_HELP.to_string(),
+            format!(
+                "The local port at which the Node will act as an explicit HTTP proxy: it accepts absolute-URI \
+                 requests and CONNECT requests from browsers that are configured to use it, and serves a proxy \
+                 auto-configuration (PAC) file at http://127.0.0.1:<port>/proxy.pac. If you don't specify a \
+                 forward-proxy port, no forward-proxy listener is started. Must be between {} and {}.",
+                LOWEST_USABLE_INSECURE_PORT, HIGHEST_USABLE_PORT
+            )
+        );
+        assert_eq!(
+            PACKET_PADDING_HELP,
+            "If 'on', your Node pads every CORES package it sends to one of a few fixed sizes before encrypting \
+             it, so that someone watching its connections can't work out from the sizes of the packages how much data \
+             they carry. Padding costs extra bandwidth, most of all for small packages. [default: off]"
+        );
+        assert_eq!(
+            MULTIPATH_ROUTES_HELP,
+            "How many routes your Node spreads each of its connections across. With a value above 1, once your \
+             Node has a route for a connection it looks for up to that many routes in all to the same exit, none of \
+             which share a relay with another, and takes turns sending the connection's data down each of them, so \
+             that no single slow relay caps the connection's speed. Each route is paid for separately. The exit puts \
+             the data back in order, so the site can't tell the difference. Values range from 1 to 4. [default: 1]"
+        );
+        assert_eq!(
+            STICKY_ROUTE_TTL_HELP,
+            "How many seconds your Node keeps reusing the route it found for a destination hostname, so that new \
+             connections to the same site leave the MASQ Network through the same exit and the site sees the same IP \
+             address throughout your session. A route is forgotten once it has gone unused for this long, or as soon \
+             as it fails. Set this to 0 to find a new route for every connection. [default: 300]"
+        );
+        assert_eq!(
+            PENDING_SCANS_BEFORE_REPLACEMENT_HELP,
+            "How many pending payable scans a payment you sent may go unconfirmed before your Node sends it \
+             again under the same nonce with higher fees, so that a transaction priced out by a surge in fees doesn't \
+             hold up your payments for hours. Only one of the two can ever be mined, and whichever it is settles the \
+             debt. A payment is replaced only once, and never with fees beyond those allowed by --gas-price. Set this \
+             to 0 to leave stuck transactions alone. [default: 6]"
+        );
         assert_eq!(
             GAS_PRICE_HELP.to_string(),
             format!(
@@ -1032,6 +1430,132 @@ mod tests {
         assert_eq!(result, Ok(()));
     }
 
+    #[test]
+    fn validate_socks_port_rejects_badly_formatted_port_number() {
+        let result = common_validators::validate_socks_port(String::from("booga"));
+
+        assert_eq!(result, Err(String::from("booga")));
+    }
+
+    #[test]
+    fn validate_socks_port_rejects_port_number_too_low() {
+        let result = common_validators::validate_socks_port(String::from("1024"));
+
+        assert_eq!(result, Err(String::from("1024")));
+    }
+
+    #[test]
+    fn validate_socks_port_accepts_port_if_provided() {
+        let result = common_validators::validate_socks_port(String::from("1080"));
+
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn validate_forward_proxy_port_rejects_port_number_too_low() {
+        let result = common_validators::validate_forward_proxy_port(String::from("80"));
+
+        assert_eq!(result, Err(String::from("80")));
+    }
+
+    #[test]
+    fn validate_forward_proxy_port_accepts_port_if_provided() {
+        let result = common_validators::validate_forward_proxy_port(String::from("8080"));
+
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn validate_connect_ports_accepts_list_of_ports() {
+        let result = common_validators::validate_connect_ports(String::from("443,22,8443"));
+
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn validate_connect_ports_rejects_zero_and_garbage() {
+        vec!["443,0", "443,booga", "443,,22", "", "65536"]
+            .into_iter()
+            .for_each(|ports| {
+                let result = common_validators::validate_connect_ports(ports.to_string());
+
+                assert_eq!(result, Err(ports.to_string()), "{}", ports);
+            });
+    }
+
+    #[test]
+    fn validate_country_code_accepts_two_letters_in_either_case() {
+        vec!["CZ", "sk", "De"].into_iter().for_each(|country_code| {
+            let result = common_validators::validate_country_code(country_code.to_string());
+
+            assert_eq!(result, Ok(()), "{}", country_code);
+        });
+    }
+
+    #[test]
+    fn validate_country_code_rejects_anything_else() {
+        vec!["", "C", "CZE", "C1", "Czechia"]
+            .into_iter()
+            .for_each(|country_code| {
+                let result = common_validators::validate_country_code(country_code.to_string());
+
+                assert_eq!(result, Err(country_code.to_string()), "{}", country_code);
+            });
+    }
+
+    #[test]
+    fn validate_exit_location_accepts_lists_with_and_without_mode() {
+        vec!["CZ", "cz,sk", "strict:CH,IS", "preferred:DE"]
+            .into_iter()
+            .for_each(|location| {
+                let result = common_validators::validate_exit_location(location.to_string());
+
+                assert_eq!(result, Ok(()), "{}", location);
+            });
+    }
+
+    #[test]
+    fn validate_exit_location_rejects_malformed_lists() {
+        vec!["", "strict:", "CZ,", "sometimes:CZ", "CZ;SK", "strict:CZE"]
+            .into_iter()
+            .for_each(|location| {
+                let result = common_validators::validate_exit_location(location.to_string());
+
+                assert_eq!(result, Err(location.to_string()), "{}", location);
+            });
+    }
+
+    #[test]
+    fn validate_exit_policy_accepts_every_kind_of_rule() {
+        let result = common_validators::validate_exit_policy(String::from(
+            "25,6881-6889,10.0.0.0/8,1.2.3.4,fc00::/7,example.com,private",
+        ));
+
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn validate_exit_policy_rejects_malformed_rules() {
+        vec![
+            "",
+            "25,",
+            "0",
+            "6889-6881",
+            "65536",
+            "10.0.0.0/33",
+            "::1/129",
+            "booga.com/8",
+            ".booga.com",
+            "boo ga.com",
+        ]
+        .into_iter()
+        .for_each(|policy| {
+            let result = common_validators::validate_exit_policy(policy.to_string());
+
+            assert_eq!(result, Err(policy.to_string()), "{}", policy);
+        });
+    }
+
     #[test]
     fn validate_gas_price_zero() {
         let result = common_validators::validate_gas_price("0".to_string());
@@ -1111,6 +1635,92 @@ mod tests {
         )
     }
 
+    #[test]
+    fn validate_latency_weight_accepts_zero_through_i64_max() {
+        vec!["0", "1", "10000000000", "9223372036854775807"]
+            .into_iter()
+            .for_each(|weight| {
+                let result = common_validators::validate_latency_weight(weight.to_string());
+
+                assert_eq!(result, Ok(()), "{}", weight);
+            });
+    }
+
+    #[test]
+    fn validate_latency_weight_rejects_anything_else() {
+        vec!["", "-1", "9223372036854775808", "1.5", "booga"]
+            .into_iter()
+            .for_each(|weight| {
+                let result = common_validators::validate_latency_weight(weight.to_string());
+
+                assert_eq!(result, Err(weight.to_string()), "{}", weight);
+            });
+    }
+
+    #[test]
+    fn validate_multipath_routes_accepts_one_to_four_routes() {
+        vec!["1", "2", "3", "4"].into_iter().for_each(|routes| {
+            let result = common_validators::validate_multipath_routes(routes.to_string());
+
+            assert_eq!(result, Ok(()), "{}", routes);
+        });
+    }
+
+    #[test]
+    fn validate_multipath_routes_rejects_anything_else() {
+        vec!["", "0", "5", "-1", "1.5", "booga"]
+            .into_iter()
+            .for_each(|routes| {
+                let result = common_validators::validate_multipath_routes(routes.to_string());
+
+                assert_eq!(result, Err(routes.to_string()), "{}", routes);
+            });
+    }
+
+    #[test]
+    fn validate_sticky_route_ttl_accepts_any_number_of_seconds() {
+        vec!["0", "1", "300", "18446744073709551615"]
+            .into_iter()
+            .for_each(|ttl| {
+                let result = common_validators::validate_sticky_route_ttl(ttl.to_string());
+
+                assert_eq!(result, Ok(()), "{}", ttl);
+            });
+    }
+
+    #[test]
+    fn validate_sticky_route_ttl_rejects_anything_else() {
+        vec!["", "-1", "18446744073709551616", "1.5", "booga"]
+            .into_iter()
+            .for_each(|ttl| {
+                let result = common_validators::validate_sticky_route_ttl(ttl.to_string());
+
+                assert_eq!(result, Err(ttl.to_string()), "{}", ttl);
+            });
+    }
+
+    #[test]
+    fn validate_pending_scans_before_replacement_accepts_any_u16() {
+        vec!["0", "1", "6", "65535"].into_iter().for_each(|scans| {
+            let result =
+                common_validators::validate_pending_scans_before_replacement(scans.to_string());
+
+            assert_eq!(result, Ok(()), "{}", scans);
+        });
+    }
+
+    #[test]
+    fn validate_pending_scans_before_replacement_rejects_anything_else() {
+        vec!["", "-1", "65536", "1.5", "booga"]
+            .into_iter()
+            .for_each(|scans| {
+                let result =
+                    common_validators::validate_pending_scans_before_replacement(scans.to_string());
+
+                assert_eq!(result, Err(scans.to_string()), "{}", scans);
+            });
+    }
+
     #[test]
     fn validate_non_zero_u16_happy_path() {
         let result = validate_non_zero_u16("456".to_string());
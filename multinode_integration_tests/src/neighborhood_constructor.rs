@@ -259,6 +259,7 @@ fn from_masq_node_to_node_record(masq_node: &dyn MASQNode) -> NodeRecord {
             last_update: time_t_timestamp(),
             node_addr_opt: agr.node_addr_opt.clone(),
             unreachable_hosts: Default::default(),
+            latency_millis_opt: None,
         },
         signed_gossip: agr.signed_gossip.clone(),
         signature: agr.signature,
@@ -12,10 +12,12 @@ use node_lib::database::db_initializer::{
 };
 use node_lib::database::rusqlite_wrappers::ConnectionWrapper;
 use node_lib::db_config::config_dao::{ConfigDao, ConfigDaoReal};
+use node_lib::masquerader::MasqueraderKind;
 use node_lib::neighborhood::node_record::NodeRecordInner_0v1;
 use node_lib::neighborhood::AccessibleGossipRecord;
 use node_lib::sub_lib::cryptde::{CryptData, PlainData};
-use std::collections::BTreeSet;
+use node_lib::sub_lib::exit_policy::ExitPolicy;
+use std::collections::{BTreeMap, BTreeSet};
 use std::io::{ErrorKind, Read, Write};
 use std::net::TcpStream;
 use std::path::PathBuf;
@@ -138,6 +140,13 @@ impl From<&dyn MASQNode> for AccessibleGossipRecord {
                 accepts_connections: masq_node.accepts_connections(),
                 routes_data: masq_node.routes_data(),
                 version: 0,
+                masquerader_kinds: MasqueraderKind::supported(false),
+                exit_policy: ExitPolicy::default(),
+                country_code_opt: None,
+                neighbor_latencies: BTreeMap::new(),
+                key_transition_opt: None,
+                // Every Node accepts plain masked CORES packages; mock Nodes accept nothing else
+                link_encryption: false,
             },
             node_addr_opt: Some(masq_node.node_addr()),
             signed_gossip: PlainData::new(b""),
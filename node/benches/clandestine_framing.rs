@@ -0,0 +1,88 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+// Measures what it costs a Node to mask a CORES package, find it again in the incoming byte
+// stream, and unmask it, for the JSON masquerade and the binary masquerade.
+//
+//     cargo bench --bench clandestine_framing
+
+use node_lib::binary_framer::BinaryFramer;
+use node_lib::binary_masquerader::BinaryMasquerader;
+use node_lib::json_framer::JsonFramer;
+use node_lib::json_masquerader::JsonMasquerader;
+use node_lib::masquerader::Masquerader;
+use node_lib::sub_lib::framer::Framer;
+use rand::RngCore;
+use std::time::{Duration, Instant};
+
+const PAYLOAD_SIZES: [usize; 4] = [256, 1024, 16 * 1024, 64 * 1024];
+const BYTES_PER_RUN: usize = 64 * 1024 * 1024;
+
+struct Contestant {
+    name: &'static str,
+    masquerader: Box<dyn Masquerader>,
+    make_framer: fn() -> Box<dyn Framer>,
+}
+
+struct Measurement {
+    elapsed: Duration,
+    wire_bytes: usize,
+}
+
+fn main() {
+    let contestants = vec![
+        Contestant {
+            name: "json",
+            masquerader: Box::new(JsonMasquerader::new()),
+            make_framer: || Box::new(JsonFramer::new()),
+        },
+        Contestant {
+            name: "binary",
+            masquerader: Box::new(BinaryMasquerader::new()),
+            make_framer: || Box::new(BinaryFramer::new()),
+        },
+    ];
+    println!(
+        "{:>8} {:>8} {:>12} {:>12} {:>10}",
+        "kind", "payload", "ns/package", "MB/s", "overhead"
+    );
+    PAYLOAD_SIZES.iter().for_each(|payload_size| {
+        let payload = random_payload(*payload_size);
+        let iterations = (BYTES_PER_RUN / payload_size).max(1);
+        contestants.iter().for_each(|contestant| {
+            let measurement = measure(contestant, &payload, iterations);
+            let seconds = measurement.elapsed.as_secs_f64();
+            println!(
+                "{:>8} {:>8} {:>12.0} {:>12.1} {:>9.1}%",
+                contestant.name,
+                payload_size,
+                seconds * 1_000_000_000.0 / iterations as f64,
+                (payload_size * iterations) as f64 / seconds / 1_000_000.0,
+                (measurement.wire_bytes - payload_size) as f64 * 100.0 / *payload_size as f64,
+            );
+        });
+    });
+}
+
+fn measure(contestant: &Contestant, payload: &[u8], iterations: usize) -> Measurement {
+    let mut framer = (contestant.make_framer)();
+    let mut wire_bytes = 0;
+    let start = Instant::now();
+    for _ in 0..iterations {
+        let masked = contestant.masquerader.mask(payload).unwrap();
+        wire_bytes = masked.len();
+        framer.add_data(&masked);
+        let frame = framer.take_frame().expect("No frame");
+        let unmasked = contestant.masquerader.try_unmask(&frame.chunk).unwrap();
+        assert_eq!(unmasked.chunk.len(), payload.len(), "{}", contestant.name);
+    }
+    Measurement {
+        elapsed: start.elapsed(),
+        wire_bytes,
+    }
+}
+
+fn random_payload(size: usize) -> Vec<u8> {
+    let mut payload = vec![0u8; size];
+    rand::thread_rng().fill_bytes(&mut payload);
+    payload
+}
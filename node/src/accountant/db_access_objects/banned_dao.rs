@@ -1,12 +1,15 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
-use crate::accountant::db_access_objects::utils::{DaoFactoryReal, VigilantRusqliteFlatten};
+use crate::accountant::db_access_objects::utils::{
+    from_time_t, to_time_t, DaoFactoryReal, VigilantRusqliteFlatten,
+};
 use crate::database::rusqlite_wrappers::ConnectionWrapper;
 use crate::sub_lib::wallet::Wallet;
 use lazy_static::lazy_static;
-use rusqlite::{Error, ErrorCode, ToSql};
+use rusqlite::{Row, ToSql};
 use std::collections::HashSet;
 use std::sync::RwLock;
+use std::time::SystemTime;
 
 lazy_static! {
     pub static ref BAN_CACHE: BannedCache = BannedCache::default();
@@ -63,10 +66,31 @@ impl BannedCacheLoader for BannedCacheLoaderReal {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BanReason {
+    // The facts are missing for bans made before they were recorded
+    Delinquency {
+        balance_gwei_opt: Option<i64>,
+        debt_age_sec_opt: Option<u64>,
+    },
+    Manual {
+        note_opt: Option<String>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BannedAccount {
+    pub wallet: Wallet,
+    pub banned_at_opt: Option<SystemTime>,
+    pub reason: BanReason,
+}
+
 pub trait BannedDao: Send {
-    fn ban_list(&self) -> Vec<Wallet>;
-    fn ban(&self, wallet: &Wallet);
+    fn ban_list(&self) -> Vec<BannedAccount>;
+    fn ban(&self, wallet: &Wallet, reason: &BanReason, timestamp: SystemTime);
     fn unban(&self, wallet: &Wallet);
+    fn unban_by_hand(&self, wallet: &Wallet, note_opt: Option<&str>, timestamp: SystemTime)
+        -> bool;
 }
 
 pub trait BannedDaoFactory {
@@ -87,41 +111,102 @@ impl BannedDaoReal {
     pub fn new(conn: Box<dyn ConnectionWrapper>) -> Self {
         Self { conn }
     }
+
+    fn banned_account_from_row(row: &Row) -> rusqlite::Result<BannedAccount> {
+        let reason = match row.get::<usize, i64>(2)? {
+            0 => BanReason::Delinquency {
+                balance_gwei_opt: row.get(4)?,
+                debt_age_sec_opt: row.get::<usize, Option<i64>>(5)?.map(|age| age as u64),
+            },
+            _ => BanReason::Manual {
+                note_opt: row.get(3)?,
+            },
+        };
+        Ok(BannedAccount {
+            wallet: row.get(0)?,
+            banned_at_opt: row.get::<usize, Option<i64>>(1)?.map(from_time_t),
+            reason,
+        })
+    }
+
+    fn audit(&self, wallet: &Wallet, action: &str, note_opt: Option<&str>, timestamp: SystemTime) {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "insert into ban_audit (wallet_address, action, note, timestamp) values (?, ?, ?, ?)",
+            )
+            .expect("Failed to prepare a statement");
+        let params: &[&dyn ToSql] = &[&wallet, &action, &note_opt, &to_time_t(timestamp)];
+        if let Err(e) = stmt.execute(params) {
+            panic!(
+                "Could not audit {} of {} because of database corruption: {}",
+                action, wallet, e
+            )
+        }
+    }
 }
 
 impl BannedDao for BannedDaoReal {
-    fn ban_list(&self) -> Vec<Wallet> {
+    fn ban_list(&self) -> Vec<BannedAccount> {
         let mut stmt = self
             .conn
-            .prepare("select wallet_address from banned")
+            .prepare(
+                "select wallet_address, banned_at, manual, note, balance_gwei, debt_age_sec \
+                 from banned order by banned_at, wallet_address",
+            )
             .expect("Failed to prepare a statement");
-        stmt.query_map([], |row| row.get(0))
+        stmt.query_map([], Self::banned_account_from_row)
             .expect("Couldn't retrieve delinquency-ban list: database corrupt")
             .vigilant_flatten()
             .collect()
     }
 
-    fn ban(&self, wallet: &Wallet) {
-        if BAN_CACHE.is_banned(wallet) {
-            return;
-        }
+    fn ban(&self, wallet: &Wallet, reason: &BanReason, timestamp: SystemTime) {
+        let (manual, note_opt, balance_gwei_opt, debt_age_sec_opt) = match reason {
+            BanReason::Delinquency {
+                balance_gwei_opt,
+                debt_age_sec_opt,
+            } => {
+                if BAN_CACHE.is_banned(wallet) {
+                    return;
+                }
+                (
+                    false,
+                    None,
+                    *balance_gwei_opt,
+                    debt_age_sec_opt.map(|age| age as i64),
+                )
+            }
+            BanReason::Manual { note_opt } => (true, note_opt.as_deref(), None, None),
+        };
 
+        // A ban by hand takes over a ban for delinquency, so that paying up won't lift it
         let mut stmt = self
             .conn
-            .prepare("insert into banned (wallet_address) values (?)")
+            .prepare(
+                "insert into banned (wallet_address, banned_at, manual, note, balance_gwei, debt_age_sec) \
+                 values (?, ?, ?, ?, ?, ?) \
+                 on conflict (wallet_address) do update set manual = 1, note = excluded.note \
+                 where excluded.manual = 1",
+            )
             .expect("Failed to prepare a statement");
-        let params: &[&dyn ToSql] = &[&wallet];
+        let params: &[&dyn ToSql] = &[
+            &wallet,
+            &to_time_t(timestamp),
+            &manual,
+            &note_opt,
+            &balance_gwei_opt,
+            &debt_age_sec_opt,
+        ];
         match stmt.execute(params) {
             Ok(_) => BAN_CACHE.insert(wallet.clone()),
-            Err(e) => match e {
-                Error::SqliteFailure(e, _) if e.code == ErrorCode::ConstraintViolation => {
-                    BAN_CACHE.insert(wallet.clone())
-                }
-                _ => panic!(
-                    "Could not initiate delinquency ban for {} because of database corruption: {}",
-                    wallet, e
-                ),
-            },
+            Err(e) => panic!(
+                "Could not initiate delinquency ban for {} because of database corruption: {}",
+                wallet, e
+            ),
+        }
+        if manual {
+            self.audit(wallet, "ban", note_opt, timestamp)
         }
     }
 
@@ -143,6 +228,31 @@ impl BannedDao for BannedDaoReal {
             ),
         }
     }
+
+    fn unban_by_hand(
+        &self,
+        wallet: &Wallet,
+        note_opt: Option<&str>,
+        timestamp: SystemTime,
+    ) -> bool {
+        let mut stmt = self
+            .conn
+            .prepare("delete from banned where wallet_address = ?")
+            .expect("Failed to prepare a statement");
+        let params: &[&dyn ToSql] = &[&wallet];
+        match stmt.execute(params) {
+            Ok(0) => false,
+            Ok(_) => {
+                BAN_CACHE.remove(wallet);
+                self.audit(wallet, "unban", note_opt, timestamp);
+                true
+            }
+            Err(e) => panic!(
+                "Could not lift ban on {} because of database corruption: {}",
+                wallet, e
+            ),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -170,7 +280,11 @@ mod tests {
             BannedDaoReal::new(conn)
         };
 
-        subject.ban(&make_wallet("donalddrumph"));
+        subject.ban(
+            &make_wallet("donalddrumph"),
+            &delinquency(),
+            SystemTime::now(),
+        );
 
         let conn = db_initializer
             .initialize(&home_dir, DbInitializationConfig::test_default())
@@ -198,11 +312,18 @@ mod tests {
             BannedDaoReal::new(conn)
         };
 
-        subject.ban(&make_wallet("no_duplicate_wallets"));
-        subject.ban(&make_wallet("no_duplicate_wallets"));
+        let now = SystemTime::now();
+        subject.ban(&make_wallet("no_duplicate_wallets"), &delinquency(), now);
+        subject.ban(&make_wallet("no_duplicate_wallets"), &delinquency(), now);
 
         let ban_list = subject.ban_list();
-        assert_eq!(vec![make_wallet("no_duplicate_wallets")], ban_list);
+        assert_eq!(
+            ban_list
+                .into_iter()
+                .map(|account| account.wallet)
+                .collect::<Vec<Wallet>>(),
+            vec![make_wallet("no_duplicate_wallets")]
+        );
     }
 
     #[test]
@@ -217,7 +338,11 @@ mod tests {
             BannedDaoReal::new(conn)
         };
 
-        subject.ban(&make_wallet("forgot_to_init"));
+        subject.ban(
+            &make_wallet("forgot_to_init"),
+            &delinquency(),
+            SystemTime::now(),
+        );
     }
 
     #[test]
@@ -309,7 +434,7 @@ mod tests {
         let subject = BannedDaoReal::new(conn);
 
         let ban_me_baby = make_wallet("BAN_ME_BABY");
-        subject.ban(&ban_me_baby.clone());
+        subject.ban(&ban_me_baby.clone(), &delinquency(), SystemTime::now());
 
         assert!(BAN_CACHE.is_banned(&ban_me_baby))
     }
@@ -357,4 +482,201 @@ mod tests {
         assert!(!BAN_CACHE.is_banned(&already_banned_wallet));
         assert!(!BAN_CACHE.is_banned(&already_banned_address_wallet));
     }
+
+    #[test]
+    fn ban_list_reports_the_facts_behind_each_ban() {
+        let home_dir = ensure_node_home_directory_does_not_exist(
+            "banned_dao",
+            "ban_list_reports_the_facts_behind_each_ban",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        conn.prepare("insert into banned (wallet_address) values ('0x000000000000000000006f6c645f62616e6e6564')")
+            .unwrap()
+            .execute([])
+            .unwrap();
+        let subject = BannedDaoReal::new(conn);
+        let delinquent = make_wallet("ban_list_delinquent");
+        let abuser = make_wallet("ban_list_abuser");
+        let earlier = from_time_t(1_600_000_000);
+        let later = from_time_t(1_600_000_100);
+        let delinquency = BanReason::Delinquency {
+            balance_gwei_opt: Some(1_234_567),
+            debt_age_sec_opt: Some(86_400),
+        };
+        let manual = BanReason::Manual {
+            note_opt: Some("Abusive traffic".to_string()),
+        };
+
+        subject.ban(&abuser, &manual, later);
+        subject.ban(&delinquent, &delinquency, earlier);
+
+        let result = subject.ban_list();
+
+        assert_eq!(
+            result,
+            vec![
+                BannedAccount {
+                    wallet: make_wallet("old_banned"),
+                    banned_at_opt: None,
+                    reason: BanReason::Delinquency {
+                        balance_gwei_opt: None,
+                        debt_age_sec_opt: None
+                    }
+                },
+                BannedAccount {
+                    wallet: delinquent,
+                    banned_at_opt: Some(earlier),
+                    reason: delinquency
+                },
+                BannedAccount {
+                    wallet: abuser,
+                    banned_at_opt: Some(later),
+                    reason: manual
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn manual_ban_takes_over_a_delinquency_ban_and_is_audited() {
+        let home_dir = ensure_node_home_directory_does_not_exist(
+            "banned_dao",
+            "manual_ban_takes_over_a_delinquency_ban_and_is_audited",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let subject = BannedDaoReal::new(conn);
+        let wallet = make_wallet("manual_takes_over");
+        let banned_at = from_time_t(1_600_000_000);
+        let manual_at = from_time_t(1_600_000_500);
+        subject.ban(&wallet, &delinquency(), banned_at);
+
+        subject.ban(
+            &wallet,
+            &BanReason::Manual {
+                note_opt: Some("Never pays".to_string()),
+            },
+            manual_at,
+        );
+
+        assert_eq!(
+            subject.ban_list(),
+            vec![BannedAccount {
+                wallet: wallet.clone(),
+                banned_at_opt: Some(banned_at),
+                reason: BanReason::Manual {
+                    note_opt: Some("Never pays".to_string())
+                }
+            }]
+        );
+        assert_eq!(
+            audit_trail(&home_dir),
+            vec![(
+                wallet.to_string(),
+                "ban".to_string(),
+                Some("Never pays".to_string()),
+                1_600_000_500
+            )]
+        );
+    }
+
+    #[test]
+    fn delinquency_ban_does_not_override_a_manual_ban() {
+        let home_dir = ensure_node_home_directory_does_not_exist(
+            "banned_dao",
+            "delinquency_ban_does_not_override_a_manual_ban",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let subject = BannedDaoReal::new(conn);
+        let wallet = make_wallet("manual_stays_manual");
+        let manual = BanReason::Manual { note_opt: None };
+        let banned_at = from_time_t(1_600_000_000);
+        subject.ban(&wallet, &manual, banned_at);
+        BAN_CACHE.remove(&wallet);
+
+        subject.ban(&wallet, &delinquency(), from_time_t(1_600_000_500));
+
+        assert_eq!(
+            subject.ban_list(),
+            vec![BannedAccount {
+                wallet,
+                banned_at_opt: Some(banned_at),
+                reason: manual
+            }]
+        );
+    }
+
+    #[test]
+    fn unban_by_hand_lifts_the_ban_and_is_audited() {
+        let home_dir = ensure_node_home_directory_does_not_exist(
+            "banned_dao",
+            "unban_by_hand_lifts_the_ban_and_is_audited",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let subject = BannedDaoReal::new(conn);
+        let wallet = make_wallet("forgiven_debtor");
+        subject.ban(&wallet, &delinquency(), from_time_t(1_600_000_000));
+
+        let result =
+            subject.unban_by_hand(&wallet, Some("Paid in cash"), from_time_t(1_600_000_900));
+
+        assert_eq!(result, true);
+        assert!(subject.ban_list().is_empty());
+        assert!(!BAN_CACHE.is_banned(&wallet));
+        assert_eq!(
+            audit_trail(&home_dir),
+            vec![(
+                wallet.to_string(),
+                "unban".to_string(),
+                Some("Paid in cash".to_string()),
+                1_600_000_900
+            )]
+        );
+    }
+
+    #[test]
+    fn unban_by_hand_reports_and_does_not_audit_a_wallet_that_was_not_banned() {
+        let home_dir = ensure_node_home_directory_does_not_exist(
+            "banned_dao",
+            "unban_by_hand_reports_and_does_not_audit_a_wallet_that_was_not_banned",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let subject = BannedDaoReal::new(conn);
+
+        let result = subject.unban_by_hand(&make_wallet("never_banned"), None, SystemTime::now());
+
+        assert_eq!(result, false);
+        assert!(audit_trail(&home_dir).is_empty());
+    }
+
+    fn delinquency() -> BanReason {
+        BanReason::Delinquency {
+            balance_gwei_opt: Some(1_000_000),
+            debt_age_sec_opt: Some(10_000),
+        }
+    }
+
+    fn audit_trail(home_dir: &std::path::Path) -> Vec<(String, String, Option<String>, i64)> {
+        let conn = DbInitializerReal::default()
+            .initialize(home_dir, DbInitializationConfig::panic_on_migration())
+            .unwrap();
+        let mut stmt = conn
+            .prepare("select wallet_address, action, note, timestamp from ban_audit order by rowid")
+            .unwrap();
+        stmt.query_map([], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })
+        .unwrap()
+        .flatten()
+        .collect()
+    }
 }
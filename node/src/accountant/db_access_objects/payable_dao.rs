@@ -937,6 +937,7 @@ mod tests {
             attempt: 1,
             amount: balance_change_1,
             process_error: None,
+            replaceable_opt: None,
         };
         let fingerprint_2 = PendingPayableFingerprint {
             rowid: rowid_2,
@@ -945,6 +946,7 @@ mod tests {
             attempt: 1,
             amount: balance_change_2,
             process_error: None,
+            replaceable_opt: None,
         };
         let previous_timestamp_1 = from_time_t(previous_timestamp_1_s);
         let previous_timestamp_2 = from_time_t(previous_timestamp_2_s);
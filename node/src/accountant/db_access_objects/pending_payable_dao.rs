@@ -5,11 +5,14 @@ use crate::accountant::db_access_objects::utils::{
 };
 use crate::accountant::db_big_integer::big_int_divider::BigIntDivider;
 use crate::accountant::{checked_conversion, comma_joined_stringifiable};
-use crate::blockchain::blockchain_bridge::PendingPayableFingerprint;
+use crate::blockchain::blockchain_bridge::{
+    PendingPayableFingerprint, ReplaceableTransaction, TransactionReplacement,
+};
 use crate::database::rusqlite_wrappers::ConnectionWrapper;
+use crate::sub_lib::blockchain_bridge::TransactionFees;
 use crate::sub_lib::wallet::Wallet;
 use masq_lib::utils::ExpectValue;
-use rusqlite::Row;
+use rusqlite::{named_params, Row};
 use std::collections::HashSet;
 use std::fmt::Debug;
 use std::str::FromStr;
@@ -41,6 +44,14 @@ pub trait PendingPayableDao {
         hashes_and_amounts: &[(H256, u128)],
         batch_wide_timestamp: SystemTime,
     ) -> Result<(), PendingPayableDaoError>;
+    fn record_replaceable_transactions(
+        &self,
+        replaceable_transactions: &[(H256, ReplaceableTransaction)],
+    ) -> Result<(), PendingPayableDaoError>;
+    fn record_replacement(
+        &self,
+        replacement: &TransactionReplacement,
+    ) -> Result<(), PendingPayableDaoError>;
     fn delete_fingerprints(&self, ids: &[u64]) -> Result<(), PendingPayableDaoError>;
     fn increment_scan_attempts(&self, ids: &[u64]) -> Result<(), PendingPayableDaoError>;
     fn mark_failures(&self, ids: &[u64]) -> Result<(), PendingPayableDaoError>;
@@ -91,7 +102,8 @@ impl PendingPayableDao for PendingPayableDaoReal<'_> {
             .conn
             .prepare(
                 "select rowid, transaction_hash, amount_high_b, amount_low_b, \
-                 payable_timestamp, attempt from pending_payable where process_error is null",
+                 payable_timestamp, attempt, recipient_wallet, nonce, transaction_fees, \
+                 replacement_transaction_hashes from pending_payable where process_error is null",
             )
             .expect("Internal error");
         stm.query_map([], |row| {
@@ -101,21 +113,35 @@ impl PendingPayableDao for PendingPayableDaoReal<'_> {
             let amount_low_bytes: i64 = Self::get_with_expect(row, 3);
             let timestamp: i64 = Self::get_with_expect(row, 4);
             let attempt: u16 = Self::get_with_expect(row, 5);
+            let recipient_wallet_opt: Option<Wallet> = Self::get_with_expect(row, 6);
+            let nonce_opt: Option<i64> = Self::get_with_expect(row, 7);
+            let transaction_fees_opt: Option<String> = Self::get_with_expect(row, 8);
+            let replacement_hashes_opt: Option<String> = Self::get_with_expect(row, 9);
+            let replaceable_opt = match (recipient_wallet_opt, nonce_opt, transaction_fees_opt) {
+                (Some(recipient_wallet), Some(nonce), Some(transaction_fees)) => {
+                    Some(ReplaceableTransaction {
+                        recipient_wallet,
+                        nonce: checked_conversion::<i64, u64>(nonce),
+                        transaction_fees: TransactionFees::try_from(transaction_fees.as_str())
+                            .unwrap_or_else(|e| panic!("{} - database corrupt", e)),
+                        replacement_hashes: replacement_hashes_opt
+                            .map(|hashes| hashes.split(',').map(Self::parse_hash).collect())
+                            .unwrap_or_default(),
+                    })
+                }
+                _ => None,
+            };
             Ok(PendingPayableFingerprint {
                 rowid,
                 timestamp: from_time_t(timestamp),
-                hash: H256::from_str(&transaction_hash[2..]).unwrap_or_else(|e| {
-                    panic!(
-                        "Invalid hash format (\"{}\": {:?}) - database corrupt",
-                        transaction_hash, e
-                    )
-                }),
+                hash: Self::parse_hash(&transaction_hash),
                 attempt,
                 amount: checked_conversion::<i128, u128>(BigIntDivider::reconstitute(
                     amount_high_bytes,
                     amount_low_bytes,
                 )),
                 process_error: None,
+                replaceable_opt,
             })
         })
         .expect("rusqlite failure")
@@ -165,6 +191,66 @@ impl PendingPayableDao for PendingPayableDaoReal<'_> {
         }
     }
 
+    fn record_replaceable_transactions(
+        &self,
+        replaceable_transactions: &[(H256, ReplaceableTransaction)],
+    ) -> Result<(), PendingPayableDaoError> {
+        let mut stm = self
+            .conn
+            .prepare(
+                "update pending_payable set recipient_wallet = :recipient_wallet, nonce = :nonce, \
+                 transaction_fees = :transaction_fees where transaction_hash = :hash",
+            )
+            .expect("Internal error");
+        replaceable_transactions
+            .iter()
+            .try_for_each(|(hash, replaceable)| {
+                match stm.execute(named_params! {
+                    ":recipient_wallet": replaceable.recipient_wallet,
+                    ":nonce": checked_conversion::<u64, i64>(replaceable.nonce),
+                    ":transaction_fees": replaceable.transaction_fees.to_config_value(),
+                    ":hash": format!("{:?}", hash),
+                }) {
+                    Ok(1) => Ok(()),
+                    Ok(num) => panic!(
+                        "Database corrupt: recording signing details of {:?}: expected to update \
+                        1 row but did {}",
+                        hash, num
+                    ),
+                    Err(e) => Err(PendingPayableDaoError::UpdateFailed(e.to_string())),
+                }
+            })
+    }
+
+    fn record_replacement(
+        &self,
+        replacement: &TransactionReplacement,
+    ) -> Result<(), PendingPayableDaoError> {
+        let mut stm = self
+            .conn
+            .prepare(
+                "update pending_payable set replacement_transaction_hashes = \
+                 coalesce(replacement_transaction_hashes || ',', '') || :replacement_hash, \
+                 transaction_fees = :transaction_fees, payable_timestamp = :timestamp \
+                 where rowid = :rowid",
+            )
+            .expect("Internal error");
+        match stm.execute(named_params! {
+            ":replacement_hash": format!("{:?}", replacement.hash),
+            ":transaction_fees": replacement.transaction_fees.to_config_value(),
+            ":timestamp": to_time_t(replacement.timestamp),
+            ":rowid": checked_conversion::<u64, i64>(replacement.rowid),
+        }) {
+            Ok(1) => Ok(()),
+            Ok(num) => panic!(
+                "Database corrupt: recording replacement {:?} at rowid {}: expected to update \
+                1 row but did {}",
+                replacement.hash, replacement.rowid, num
+            ),
+            Err(e) => Err(PendingPayableDaoError::UpdateFailed(e.to_string())),
+        }
+    }
+
     fn delete_fingerprints(&self, ids: &[u64]) -> Result<(), PendingPayableDaoError> {
         let sql = format!(
             "delete from pending_payable where rowid in ({})",
@@ -253,6 +339,15 @@ impl<'a> PendingPayableDaoReal<'a> {
         row.get(index).expect("database is corrupt")
     }
 
+    fn parse_hash(transaction_hash: &str) -> H256 {
+        H256::from_str(&transaction_hash[2..]).unwrap_or_else(|e| {
+            panic!(
+                "Invalid hash format (\"{}\": {:?}) - database corrupt",
+                transaction_hash, e
+            )
+        })
+    }
+
     fn serialize_ids(ids: &[u64]) -> String {
         comma_joined_stringifiable(ids, |id| id.to_string())
     }
@@ -276,13 +371,17 @@ mod tests {
     };
     use crate::accountant::db_access_objects::utils::from_time_t;
     use crate::accountant::db_big_integer::big_int_divider::BigIntDivider;
-    use crate::blockchain::blockchain_bridge::PendingPayableFingerprint;
+    use crate::blockchain::blockchain_bridge::{
+        PendingPayableFingerprint, ReplaceableTransaction, TransactionReplacement,
+    };
     use crate::blockchain::test_utils::make_tx_hash;
     use crate::database::db_initializer::{
         DbInitializationConfig, DbInitializer, DbInitializerReal, DATABASE_FILE,
     };
     use crate::database::rusqlite_wrappers::ConnectionWrapperReal;
     use crate::database::test_utils::ConnectionWrapperMock;
+    use crate::sub_lib::blockchain_bridge::TransactionFees;
+    use crate::test_utils::make_wallet;
     use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
     use rusqlite::{Connection, OpenFlags};
     use std::str::FromStr;
@@ -322,7 +421,8 @@ mod tests {
                     hash: hash_1,
                     attempt: 1,
                     amount: amount_1,
-                    process_error: None
+                    process_error: None,
+                    replaceable_opt: None,
                 },
                 PendingPayableFingerprint {
                     rowid: 2,
@@ -330,7 +430,8 @@ mod tests {
                     hash: hash_2,
                     attempt: 1,
                     amount: amount_2,
-                    process_error: None
+                    process_error: None,
+                    replaceable_opt: None,
                 }
             ]
         )
@@ -500,7 +601,8 @@ mod tests {
                     hash: hash_1,
                     attempt: 1,
                     amount: amount_1,
-                    process_error: None
+                    process_error: None,
+                    replaceable_opt: None,
                 },
                 PendingPayableFingerprint {
                     rowid: 2,
@@ -508,7 +610,8 @@ mod tests {
                     hash: hash_2,
                     attempt: 1,
                     amount: amount_2,
-                    process_error: None
+                    process_error: None,
+                    replaceable_opt: None,
                 }
             ]
         )
@@ -544,7 +647,8 @@ mod tests {
                 hash,
                 attempt: 1,
                 amount,
-                process_error: None
+                process_error: None,
+                replaceable_opt: None,
             }]
         )
     }
@@ -575,6 +679,169 @@ mod tests {
         let _ = subject.return_all_errorless_fingerprints();
     }
 
+    #[test]
+    fn record_replaceable_transactions_works() {
+        let home_dir = ensure_node_home_directory_exists(
+            "pending_payable_dao",
+            "record_replaceable_transactions_works",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let subject = PendingPayableDaoReal::new(conn);
+        let timestamp = from_time_t(199_000_000);
+        let hash_1 = make_tx_hash(111);
+        let hash_2 = make_tx_hash(222);
+        let replaceable_transaction = ReplaceableTransaction {
+            recipient_wallet: make_wallet("creditor"),
+            nonce: 45,
+            transaction_fees: TransactionFees::Eip1559 {
+                max_fee_per_gas_wei: 2_000_000_000,
+                max_priority_fee_per_gas_wei: 150_000_000,
+            },
+            replacement_hashes: vec![],
+        };
+        {
+            subject
+                .insert_new_fingerprints(&[(hash_1, 1000), (hash_2, 2000)], timestamp)
+                .unwrap();
+        }
+
+        let result =
+            subject.record_replaceable_transactions(&[(hash_2, replaceable_transaction.clone())]);
+
+        assert_eq!(result, Ok(()));
+        let mut all_records = subject.return_all_errorless_fingerprints();
+        assert_eq!(all_records.len(), 2);
+        assert_eq!(all_records.remove(0).replaceable_opt, None);
+        assert_eq!(
+            all_records.remove(0).replaceable_opt,
+            Some(replaceable_transaction)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "expected to update 1 row but did 0")]
+    fn record_replaceable_transactions_panics_on_a_hash_without_fingerprint() {
+        let home_dir = ensure_node_home_directory_exists(
+            "pending_payable_dao",
+            "record_replaceable_transactions_panics_on_a_hash_without_fingerprint",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let subject = PendingPayableDaoReal::new(conn);
+
+        let _ = subject.record_replaceable_transactions(&[(
+            make_tx_hash(111),
+            ReplaceableTransaction {
+                recipient_wallet: make_wallet("creditor"),
+                nonce: 1,
+                transaction_fees: TransactionFees::Legacy { gas_price_wei: 1 },
+                replacement_hashes: vec![],
+            },
+        )]);
+    }
+
+    #[test]
+    fn record_replacement_works() {
+        let home_dir =
+            ensure_node_home_directory_exists("pending_payable_dao", "record_replacement_works");
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let subject = PendingPayableDaoReal::new(conn);
+        let hash = make_tx_hash(333);
+        let first_replacement_hash = make_tx_hash(444);
+        let second_replacement_hash = make_tx_hash(555);
+        let recipient_wallet = make_wallet("creditor");
+        let bumped_fees = TransactionFees::Legacy {
+            gas_price_wei: 1_265_625_000,
+        };
+        {
+            subject
+                .insert_new_fingerprints(&[(hash, 5000)], from_time_t(199_000_000))
+                .unwrap();
+            subject
+                .record_replaceable_transactions(&[(
+                    hash,
+                    ReplaceableTransaction {
+                        recipient_wallet: recipient_wallet.clone(),
+                        nonce: 8,
+                        transaction_fees: TransactionFees::Legacy {
+                            gas_price_wei: 1_000_000_000,
+                        },
+                        replacement_hashes: vec![],
+                    },
+                )])
+                .unwrap();
+        }
+
+        let first_result = subject.record_replacement(&TransactionReplacement {
+            rowid: 1,
+            hash: first_replacement_hash,
+            transaction_fees: TransactionFees::Legacy {
+                gas_price_wei: 1_125_000_000,
+            },
+            timestamp: from_time_t(199_001_000),
+        });
+        let second_result = subject.record_replacement(&TransactionReplacement {
+            rowid: 1,
+            hash: second_replacement_hash,
+            transaction_fees: bumped_fees,
+            timestamp: from_time_t(199_002_000),
+        });
+
+        assert_eq!(first_result, Ok(()));
+        assert_eq!(second_result, Ok(()));
+        let all_records = subject.return_all_errorless_fingerprints();
+        assert_eq!(all_records.len(), 1);
+        assert_eq!(all_records[0].hash, hash);
+        // The pending time starts over with each replacement
+        assert_eq!(all_records[0].timestamp, from_time_t(199_002_000));
+        assert_eq!(
+            all_records[0].replaceable_opt,
+            Some(ReplaceableTransaction {
+                recipient_wallet,
+                nonce: 8,
+                transaction_fees: bumped_fees,
+                replacement_hashes: vec![first_replacement_hash, second_replacement_hash],
+            })
+        );
+    }
+
+    #[test]
+    fn record_replacement_sad_path() {
+        let home_dir =
+            ensure_node_home_directory_exists("pending_payable_dao", "record_replacement_sad_path");
+        {
+            DbInitializerReal::default()
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap();
+        }
+        let conn_read_only = Connection::open_with_flags(
+            home_dir.join(DATABASE_FILE),
+            OpenFlags::SQLITE_OPEN_READ_ONLY,
+        )
+        .unwrap();
+        let wrapped_conn = ConnectionWrapperReal::new(conn_read_only);
+        let subject = PendingPayableDaoReal::new(Box::new(wrapped_conn));
+
+        let result = subject.record_replacement(&TransactionReplacement {
+            rowid: 1,
+            hash: make_tx_hash(444),
+            transaction_fees: TransactionFees::Legacy { gas_price_wei: 1 },
+            timestamp: from_time_t(199_000_000),
+        });
+
+        assert_eq!(
+            result,
+            Err(PendingPayableDaoError::UpdateFailed(
+                "attempt to write a readonly database".to_string()
+            ))
+        )
+    }
+
     #[test]
     fn delete_fingerprints_happy_path() {
         let home_dir = ensure_node_home_directory_exists(
@@ -792,6 +1059,7 @@ mod tests {
                         amount_low_b,
                     )),
                     process_error,
+                    replaceable_opt: None,
                 })
             })
             .unwrap()
@@ -806,7 +1074,8 @@ mod tests {
                     hash: hash_1,
                     attempt: 1,
                     amount: amount_1,
-                    process_error: None
+                    process_error: None,
+                    replaceable_opt: None,
                 },
                 PendingPayableFingerprint {
                     rowid: 2,
@@ -814,7 +1083,8 @@ mod tests {
                     hash: hash_2,
                     attempt: 1,
                     amount: amount_2,
-                    process_error: Some("ERROR".to_string())
+                    process_error: Some("ERROR".to_string()),
+                    replaceable_opt: None,
                 }
             ]
         )
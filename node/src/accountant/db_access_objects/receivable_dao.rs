@@ -207,6 +207,11 @@ impl ReceivableDao for ReceivableDaoReal {
                         and (r.balance_low_b > slope_drop_low_bytes(:debt_threshold, :slope, :sugg_and_grace - r.last_received_timestamp))))
                     and ((r.balance_high_b > :permanent_debt_allowed_high_b) or ((r.balance_high_b = 0) and (r.balance_low_b > :permanent_debt_allowed_low_b)))
                     and b.wallet_address is null
+                    and not exists (
+                        select 1 from ban_audit a
+                        where a.wallet_address = r.wallet_address and a.action = 'unban'
+                            and a.timestamp >= r.last_received_timestamp
+                    )
             "
         );
         self.conn
@@ -233,7 +238,8 @@ impl ReceivableDao for ReceivableDaoReal {
             select r.wallet_address, r.balance_high_b, r.balance_low_b, r.last_received_timestamp
             from receivable r inner join banned b on r.wallet_address = b.wallet_address
             where
-                (r.balance_high_b < :unban_balance_high_b) or ((balance_high_b = :unban_balance_high_b) and (balance_low_b <= :unban_balance_low_b))
+                b.manual = 0
+                and ((r.balance_high_b < :unban_balance_high_b) or ((balance_high_b = :unban_balance_high_b) and (balance_low_b <= :unban_balance_low_b)))
         "
         );
         let mut stmt = self.conn.prepare(sql).expect("Couldn't prepare statement");
@@ -1349,6 +1355,52 @@ mod tests {
         assert_eq!(result.len(), 1);
     }
 
+    #[test]
+    fn new_delinquencies_does_not_find_debtors_forgiven_since_their_last_payment() {
+        let payment_thresholds = PaymentThresholds {
+            maturity_threshold_sec: 25,
+            payment_grace_period_sec: 50,
+            permanent_debt_allowed_gwei: 100,
+            debt_threshold_gwei: 200,
+            threshold_interval_sec: 100,
+            unban_below_gwei: 0,
+        };
+        let now = now_time_t();
+        let last_received = payment_thresholds.sugg_and_grace(now) - 1;
+        let mut forgiven = make_receivable_account(1234, true);
+        forgiven.balance_wei = gwei_to_wei(250);
+        forgiven.last_received_timestamp = from_time_t(last_received);
+        let mut forgiven_before_last_payment = make_receivable_account(2345, true);
+        forgiven_before_last_payment.balance_wei = gwei_to_wei(250);
+        forgiven_before_last_payment.last_received_timestamp = from_time_t(last_received);
+        let mut banned_by_hand_once = make_receivable_account(3456, true);
+        banned_by_hand_once.balance_wei = gwei_to_wei(250);
+        banned_by_hand_once.last_received_timestamp = from_time_t(last_received);
+        let home_dir = ensure_node_home_directory_exists(
+            "receivable_dao",
+            "new_delinquencies_does_not_find_debtors_forgiven_since_their_last_payment",
+        );
+        let conn = make_connection_with_our_defined_sqlite_functions(&home_dir);
+        add_receivable_account(&conn, &forgiven);
+        add_receivable_account(&conn, &forgiven_before_last_payment);
+        add_receivable_account(&conn, &banned_by_hand_once);
+        add_ban_audit_entry(&conn, &forgiven, "unban", last_received + 10);
+        add_ban_audit_entry(
+            &conn,
+            &forgiven_before_last_payment,
+            "unban",
+            last_received - 10,
+        );
+        add_ban_audit_entry(&conn, &banned_by_hand_once, "ban", last_received + 10);
+        let subject = ReceivableDaoReal::new(conn);
+
+        let result = subject.new_delinquencies(from_time_t(now), &payment_thresholds);
+
+        assert_contains(&result, &forgiven_before_last_payment);
+        assert_contains(&result, &banned_by_hand_once);
+        assert_eq!(result.len(), 2);
+    }
+
     #[test]
     fn new_delinquencies_works_for_still_empty_tables() {
         let payment_thresholds = PaymentThresholds {
@@ -1440,6 +1492,41 @@ mod tests {
         assert_eq!(result.len(), 1);
     }
 
+    #[test]
+    fn paid_delinquencies_does_not_lift_bans_made_by_hand() {
+        let payment_thresholds = PaymentThresholds {
+            maturity_threshold_sec: 0,
+            payment_grace_period_sec: 0,
+            permanent_debt_allowed_gwei: 0,
+            debt_threshold_gwei: 0,
+            threshold_interval_sec: 0,
+            unban_below_gwei: 50,
+        };
+        let mut paid_delinquent = make_receivable_account(1234, false);
+        paid_delinquent.balance_wei = gwei_to_wei(25);
+        let mut paid_abuser = make_receivable_account(2345, false);
+        paid_abuser.balance_wei = gwei_to_wei(25);
+        let home_dir = ensure_node_home_directory_exists(
+            "receivable_dao",
+            "paid_delinquencies_does_not_lift_bans_made_by_hand",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        add_receivable_account(&conn, &paid_delinquent);
+        add_receivable_account(&conn, &paid_abuser);
+        add_banned_account(&conn, &paid_delinquent);
+        conn.prepare("insert into banned (wallet_address, manual) values (?, 1)")
+            .unwrap()
+            .execute(&[&paid_abuser.wallet])
+            .unwrap();
+        let subject = ReceivableDaoReal::new(conn);
+
+        let result = subject.paid_delinquencies(&payment_thresholds);
+
+        assert_eq!(result, vec![paid_delinquent]);
+    }
+
     #[test]
     fn paid_delinquencies_does_not_find_existing_nondelinquencies() {
         let payment_thresholds = PaymentThresholds {
@@ -1882,6 +1969,19 @@ mod tests {
         stmt.execute(&[&account.wallet]).unwrap();
     }
 
+    fn add_ban_audit_entry(
+        conn: &Box<dyn ConnectionWrapper>,
+        account: &ReceivableAccount,
+        action: &str,
+        timestamp: i64,
+    ) {
+        let params: &[&dyn ToSql] = &[&account.wallet, &action, &timestamp];
+        conn.prepare("insert into ban_audit (wallet_address, action, timestamp) values (?, ?, ?)")
+            .unwrap()
+            .execute(params)
+            .unwrap();
+    }
+
     fn receivable_read_only_conn(path: &Path) -> Connection {
         trick_rusqlite_with_read_only_conn(path, DbInitializerReal::create_receivable_table)
     }
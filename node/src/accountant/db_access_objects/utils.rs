@@ -1,5 +1,6 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+use crate::accountant::db_access_objects::banned_dao::{BanReason, BannedAccount};
 use crate::accountant::db_access_objects::payable_dao::PayableAccount;
 use crate::accountant::db_access_objects::receivable_dao::ReceivableAccount;
 use crate::accountant::db_big_integer::big_int_divider::BigIntDivider;
@@ -11,7 +12,8 @@ use crate::database::rusqlite_wrappers::ConnectionWrapper;
 use crate::sub_lib::accountant::PaymentThresholds;
 use masq_lib::constants::WEIS_IN_GWEI;
 use masq_lib::messages::{
-    RangeQuery, TopRecordsConfig, TopRecordsOrdering, UiPayableAccount, UiReceivableAccount,
+    RangeQuery, TopRecordsConfig, TopRecordsOrdering, UiBannedWallet, UiPayableAccount,
+    UiReceivableAccount,
 };
 use rusqlite::{Row, Statement, ToSql};
 use std::fmt::{Debug, Display};
@@ -298,6 +300,24 @@ pub fn remap_receivable_accounts(accounts: Vec<ReceivableAccount>) -> Vec<UiRece
         .collect()
 }
 
+pub fn remap_banned_account(account: BannedAccount) -> UiBannedWallet {
+    let (manual, note_opt, balance_gwei_opt, debt_age_sec_opt) = match account.reason {
+        BanReason::Delinquency {
+            balance_gwei_opt,
+            debt_age_sec_opt,
+        } => (false, None, balance_gwei_opt, debt_age_sec_opt),
+        BanReason::Manual { note_opt } => (true, note_opt, None, None),
+    };
+    UiBannedWallet {
+        wallet: account.wallet.to_string(),
+        banned_at_sec_opt: account.banned_at_opt.map(to_time_t),
+        manual,
+        note_opt,
+        balance_gwei_opt,
+        debt_age_sec_opt,
+    }
+}
+
 fn to_age(timestamp: SystemTime) -> u64 {
     (to_time_t(SystemTime::now()) - to_time_t(timestamp)) as u64
 }
@@ -10,14 +10,16 @@ pub mod scanners;
 pub mod test_utils;
 
 use core::fmt::Debug;
-use masq_lib::constants::{SCAN_ERROR, WEIS_IN_GWEI};
+use masq_lib::constants::{INVALID_WALLET_ERROR, SCAN_ERROR, WEIS_IN_GWEI};
 use std::cell::{Ref, RefCell};
 
+use crate::accountant::db_access_objects::banned_dao::{BanReason, BannedDao};
 use crate::accountant::db_access_objects::payable_dao::{PayableDao, PayableDaoError};
 use crate::accountant::db_access_objects::pending_payable_dao::PendingPayableDao;
 use crate::accountant::db_access_objects::receivable_dao::{ReceivableDao, ReceivableDaoError};
 use crate::accountant::db_access_objects::utils::{
-    remap_payable_accounts, remap_receivable_accounts, CustomQuery, DaoFactoryReal,
+    remap_banned_account, remap_payable_accounts, remap_receivable_accounts, CustomQuery,
+    DaoFactoryReal,
 };
 use crate::accountant::financials::visibility_restricted_module::{
     check_query_is_within_tech_limits, financials_entry_check,
@@ -27,7 +29,8 @@ use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::{
 };
 use crate::accountant::scanners::{BeginScanError, ScanSchedulers, Scanners};
 use crate::blockchain::blockchain_bridge::{
-    PendingPayableFingerprint, PendingPayableFingerprintSeeds, RetrieveTransactions,
+    PendingPayableFingerprint, PendingPayableFingerprintSeeds, ReplaceableTransaction,
+    RetrieveTransactions,
 };
 use crate::blockchain::blockchain_interface::data_structures::errors::PayableTransactionError;
 use crate::blockchain::blockchain_interface::data_structures::{
@@ -41,6 +44,9 @@ use crate::sub_lib::accountant::FinancialStatistics;
 use crate::sub_lib::accountant::ReportExitServiceProvidedMessage;
 use crate::sub_lib::accountant::ReportRoutingServiceProvidedMessage;
 use crate::sub_lib::accountant::ReportServicesConsumedMessage;
+use crate::sub_lib::accountant::{
+    CoverTrafficStatistics, PrioritizeCreditorMessage, ReportCoverTrafficMessage,
+};
 use crate::sub_lib::accountant::{MessageIdGenerator, MessageIdGeneratorReal};
 use crate::sub_lib::blockchain_bridge::OutboundPaymentsInstructions;
 use crate::sub_lib::neighborhood::{ConfigChange, ConfigChangeMsg};
@@ -64,22 +70,26 @@ use masq_lib::messages::{
     QueryResults, ScanType, UiFinancialStatistics, UiPayableAccount, UiReceivableAccount,
     UiScanRequest,
 };
+use masq_lib::messages::{UiBannedRequest, UiBannedResponse};
 use masq_lib::ui_gateway::MessageTarget::ClientId;
 use masq_lib::ui_gateway::{MessageBody, MessagePath};
 use masq_lib::ui_gateway::{NodeFromUiMessage, NodeToUiMessage};
 use masq_lib::utils::ExpectValue;
 use std::any::type_name;
+use std::collections::HashSet;
 #[cfg(test)]
 use std::default::Default;
 use std::fmt::Display;
 use std::ops::{Div, Mul};
 use std::path::Path;
 use std::rc::Rc;
+use std::str::FromStr;
 use std::time::SystemTime;
 use web3::types::{TransactionReceipt, H256};
 
 pub const CRASH_KEY: &str = "ACCOUNTANT";
 pub const DEFAULT_PENDING_TOO_LONG_SEC: u64 = 21_600; //6 hours
+pub const DEFAULT_PENDING_SCANS_BEFORE_REPLACEMENT: u16 = 6; //an hour at the default scan interval
 
 pub struct Accountant {
     suppress_initial_scans: bool,
@@ -88,10 +98,13 @@ pub struct Accountant {
     payable_dao: Box<dyn PayableDao>,
     receivable_dao: Box<dyn ReceivableDao>,
     pending_payable_dao: Box<dyn PendingPayableDao>,
+    banned_dao: Box<dyn BannedDao>,
     crashable: bool,
     scanners: Scanners,
     scan_schedulers: ScanSchedulers,
     financial_statistics: Rc<RefCell<FinancialStatistics>>,
+    cover_traffic_statistics: CoverTrafficStatistics,
+    prioritized_creditors: Rc<RefCell<HashSet<Wallet>>>,
     outbound_payments_instructions_sub_opt: Option<Recipient<OutboundPaymentsInstructions>>,
     qualified_payables_sub_opt: Option<Recipient<QualifiedPayablesMessage>>,
     retrieve_transactions_sub_opt: Option<Recipient<RetrieveTransactions>>,
@@ -220,9 +233,9 @@ impl Handler<BlockchainAgentWithContextMessage> for Accountant {
     fn handle(
         &mut self,
         msg: BlockchainAgentWithContextMessage,
-        _ctx: &mut Self::Context,
+        ctx: &mut Self::Context,
     ) -> Self::Result {
-        self.handle_payable_payment_setup(msg)
+        self.handle_payable_payment_setup(msg, ctx)
     }
 }
 
@@ -320,6 +333,22 @@ impl Handler<ReportRoutingServiceProvidedMessage> for Accountant {
     }
 }
 
+impl Handler<ReportCoverTrafficMessage> for Accountant {
+    type Result = ();
+
+    fn handle(&mut self, msg: ReportCoverTrafficMessage, _ctx: &mut Self::Context) -> Self::Result {
+        self.handle_report_cover_traffic_message(msg);
+    }
+}
+
+impl Handler<PrioritizeCreditorMessage> for Accountant {
+    type Result = ();
+
+    fn handle(&mut self, msg: PrioritizeCreditorMessage, _ctx: &mut Self::Context) -> Self::Result {
+        self.handle_prioritize_creditor_message(msg);
+    }
+}
+
 impl Handler<ReportExitServiceProvidedMessage> for Accountant {
     type Result = ();
 
@@ -351,9 +380,18 @@ pub trait SkeletonOptHolder {
 #[derive(Debug, PartialEq, Eq, Message, Clone)]
 pub struct RequestTransactionReceipts {
     pub pending_payable: Vec<PendingPayableFingerprint>,
+    pub stuck_transactions_opt: Option<StuckTransactions>,
     pub response_skeleton_opt: Option<ResponseSkeleton>,
 }
 
+// Pending payables that outstayed the configured number of scans, to be sent again with higher fees
+// unless their receipts come back this time
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct StuckTransactions {
+    pub consuming_wallet: Wallet,
+    pub rowids: Vec<u64>,
+}
+
 impl SkeletonOptHolder for RequestTransactionReceipts {
     fn skeleton_opt(&self) -> Option<ResponseSkeleton> {
         self.response_skeleton_opt
@@ -398,6 +436,8 @@ impl Handler<NodeFromUiMessage> for Accountant {
         let client_id = msg.client_id;
         if let Ok((request, context_id)) = UiFinancialsRequest::fmb(msg.body.clone()) {
             self.handle_financials(&request, client_id, context_id)
+        } else if let Ok((request, context_id)) = UiBannedRequest::fmb(msg.body.clone()) {
+            self.handle_banned(request, client_id, context_id)
         } else if let Ok((body, context_id)) = UiScanRequest::fmb(msg.body.clone()) {
             self.handle_externally_triggered_scan(
                 ctx,
@@ -419,14 +459,18 @@ impl Accountant {
         let scan_intervals = config.scan_intervals_opt.expectv("Scan Intervals");
         let earning_wallet = config.earning_wallet.clone();
         let financial_statistics = Rc::new(RefCell::new(FinancialStatistics::default()));
+        let prioritized_creditors = Rc::new(RefCell::new(HashSet::new()));
         let payable_dao = dao_factories.payable_dao_factory.make();
         let pending_payable_dao = dao_factories.pending_payable_dao_factory.make();
         let receivable_dao = dao_factories.receivable_dao_factory.make();
+        let banned_dao = dao_factories.banned_dao_factory.make();
         let scanners = Scanners::new(
             dao_factories,
             Rc::new(payment_thresholds),
             config.when_pending_too_long_sec,
+            config.pending_scans_before_replacement,
             Rc::clone(&financial_statistics),
+            Rc::clone(&prioritized_creditors),
         );
 
         Accountant {
@@ -436,10 +480,13 @@ impl Accountant {
             payable_dao,
             receivable_dao,
             pending_payable_dao,
+            banned_dao,
             scanners,
             crashable: config.crash_point == CrashPoint::Message,
             scan_schedulers: ScanSchedulers::new(scan_intervals),
             financial_statistics: Rc::clone(&financial_statistics),
+            cover_traffic_statistics: CoverTrafficStatistics::default(),
+            prioritized_creditors,
             outbound_payments_instructions_sub_opt: None,
             qualified_payables_sub_opt: None,
             report_sent_payables_sub_opt: None,
@@ -460,6 +507,8 @@ impl Accountant {
             report_routing_service_provided: recipient!(addr, ReportRoutingServiceProvidedMessage),
             report_exit_service_provided: recipient!(addr, ReportExitServiceProvidedMessage),
             report_services_consumed: recipient!(addr, ReportServicesConsumedMessage),
+            report_cover_traffic: recipient!(addr, ReportCoverTrafficMessage),
+            prioritize_creditor: recipient!(addr, PrioritizeCreditorMessage),
             report_payable_payments_setup: recipient!(addr, BlockchainAgentWithContextMessage),
             report_inbound_payments: recipient!(addr, ReceivedPayments),
             init_pending_payable_fingerprints: recipient!(addr, PendingPayableFingerprintSeeds),
@@ -618,6 +667,29 @@ impl Accountant {
         );
     }
 
+    fn handle_report_cover_traffic_message(&mut self, msg: ReportCoverTrafficMessage) {
+        let statistics = &mut self.cover_traffic_statistics;
+        statistics.bytes_sent = statistics.bytes_sent.saturating_add(msg.bytes_sent as u64);
+        statistics.bytes_received = statistics
+            .bytes_received
+            .saturating_add(msg.bytes_received as u64);
+        debug!(
+            self.logger,
+            "Cover traffic so far, charged to nobody: {} bytes sent, {} bytes received",
+            statistics.bytes_sent,
+            statistics.bytes_received
+        );
+    }
+
+    fn handle_prioritize_creditor_message(&mut self, msg: PrioritizeCreditorMessage) {
+        info!(
+            self.logger,
+            "Wallet {} refused us service; whatever we owe it will be paid on the next payable scan",
+            msg.wallet
+        );
+        self.prioritized_creditors.borrow_mut().insert(msg.wallet);
+    }
+
     fn handle_report_exit_service_provided_message(
         &mut self,
         msg: ReportExitServiceProvidedMessage,
@@ -681,7 +753,12 @@ impl Accountant {
         })
     }
 
-    fn handle_payable_payment_setup(&mut self, msg: BlockchainAgentWithContextMessage) {
+    fn handle_payable_payment_setup(
+        &mut self,
+        msg: BlockchainAgentWithContextMessage,
+        ctx: &mut Context<Self>,
+    ) {
+        let response_skeleton_opt = msg.response_skeleton_opt;
         let blockchain_bridge_instructions = match self
             .scanners
             .payable
@@ -693,15 +770,22 @@ impl Accountant {
                 self.scanners
                     .payable
                     .perform_payment_adjustment(unaccepted_msg, &self.logger)
+                    .instructions
+            }
+            Err(msg) => {
+                ctx.notify(ScanError {
+                    scan_type: ScanType::Payables,
+                    response_skeleton_opt,
+                    msg,
+                });
+                return;
             }
-            Err(_e) => todo!("be completed by GH-711"),
         };
         self.outbound_payments_instructions_sub_opt
             .as_ref()
             .expect("BlockchainBridge is unbound")
             .try_send(blockchain_bridge_instructions)
             .expect("BlockchainBridge is dead")
-        //TODO implement send point for ScanError; be completed by GH-711
     }
 
     fn handle_financials(&self, msg: &UiFinancialsRequest, client_id: u64, context_id: u64) {
@@ -716,6 +800,91 @@ impl Accountant {
             .expect("UiGateway is dead");
     }
 
+    fn handle_banned(&self, msg: UiBannedRequest, client_id: u64, context_id: u64) {
+        let body = self.compute_banned(msg, context_id);
+        self.ui_message_sub_opt
+            .as_ref()
+            .expect("UiGateway not bound")
+            .try_send(NodeToUiMessage {
+                target: ClientId(client_id),
+                body,
+            })
+            .expect("UiGateway is dead");
+    }
+
+    fn compute_banned(&self, msg: UiBannedRequest, context_id: u64) -> MessageBody {
+        let parse = |addresses: &[String]| -> Result<Vec<Wallet>, MessageBody> {
+            addresses
+                .iter()
+                .map(|address| {
+                    Wallet::from_str(address).map_err(|_| MessageBody {
+                        opcode: "banned".to_string(),
+                        path: MessagePath::Conversation(context_id),
+                        payload: Err((
+                            INVALID_WALLET_ERROR,
+                            format!("Bad wallet address: '{}'", address),
+                        )),
+                    })
+                })
+                .collect()
+        };
+        // Nothing changes unless every address in the request is valid
+        let (to_ban, to_unban) = match (parse(&msg.ban), parse(&msg.unban)) {
+            (Ok(to_ban), Ok(to_unban)) => (to_ban, to_unban),
+            (Err(body), _) | (_, Err(body)) => return body,
+        };
+        let now = SystemTime::now();
+        to_ban.iter().for_each(|wallet| {
+            self.banned_dao.ban(
+                wallet,
+                &BanReason::Manual {
+                    note_opt: msg.note_opt.clone(),
+                },
+                now,
+            );
+            info!(
+                self.logger,
+                "Wallet {} banned by hand{}",
+                wallet,
+                Self::note_suffix(&msg.note_opt)
+            )
+        });
+        to_unban.iter().for_each(|wallet| {
+            if self
+                .banned_dao
+                .unban_by_hand(wallet, msg.note_opt.as_deref(), now)
+            {
+                info!(
+                    self.logger,
+                    "Ban on wallet {} lifted by hand{}",
+                    wallet,
+                    Self::note_suffix(&msg.note_opt)
+                )
+            } else {
+                info!(
+                    self.logger,
+                    "Wallet {} was not banned; nothing to lift", wallet
+                )
+            }
+        });
+        UiBannedResponse {
+            banned: self
+                .banned_dao
+                .ban_list()
+                .into_iter()
+                .map(remap_banned_account)
+                .collect(),
+        }
+        .tmb(context_id)
+    }
+
+    fn note_suffix(note_opt: &Option<String>) -> String {
+        match note_opt {
+            Some(note) => format!(": {}", note),
+            None => String::new(),
+        }
+    }
+
     fn compute_financials(&self, msg: &UiFinancialsRequest, context_id: u64) -> MessageBody {
         if let Err(message_body) = financials_entry_check(msg, context_id) {
             return message_body;
@@ -868,7 +1037,7 @@ impl Accountant {
     ) {
         let result = match self.consuming_wallet_opt.clone() {
             Some(consuming_wallet) => self.scanners.pending_payable.begin_scan(
-                consuming_wallet, // This argument is not used and is therefore irrelevant
+                consuming_wallet,
                 SystemTime::now(),
                 response_skeleton_opt,
                 &self.logger,
@@ -941,11 +1110,14 @@ impl Accountant {
             .pending_payable_dao
             .insert_new_fingerprints(&msg.hashes_and_balances, msg.batch_wide_timestamp)
         {
-            Ok(_) => debug!(
-                self.logger,
-                "Saved new pending payable fingerprints for: {}",
-                serialize_hashes(&msg.hashes_and_balances)
-            ),
+            Ok(_) => {
+                debug!(
+                    self.logger,
+                    "Saved new pending payable fingerprints for: {}",
+                    serialize_hashes(&msg.hashes_and_balances)
+                );
+                self.record_replaceable_transactions(&msg.replaceable_transactions)
+            }
             Err(e) => error!(
                 self.logger,
                 "Failed to process new pending payable fingerprints due to '{:?}', \
@@ -956,6 +1128,30 @@ impl Accountant {
         }
     }
 
+    fn record_replaceable_transactions(
+        &self,
+        replaceable_transactions: &[(H256, ReplaceableTransaction)],
+    ) {
+        if replaceable_transactions.is_empty() {
+            return;
+        }
+        if let Err(e) = self
+            .pending_payable_dao
+            .record_replaceable_transactions(replaceable_transactions)
+        {
+            warning!(
+                self.logger,
+                "Failed to keep signing details due to '{:?}'; should these transactions get \
+                 stuck, they will not be replaced: {}",
+                e,
+                comma_joined_stringifiable(replaceable_transactions, |(hash, _)| format!(
+                    "{:?}",
+                    hash
+                ))
+            )
+        }
+    }
+
     fn financial_statistics(&self) -> Ref<'_, FinancialStatistics> {
         self.financial_statistics.borrow()
     }
@@ -1028,6 +1224,7 @@ pub fn wei_to_gwei<T: TryFrom<S>, S: Display + Copy + Div<Output = S> + From<u32
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::accountant::db_access_objects::banned_dao::BannedAccount;
     use crate::accountant::db_access_objects::payable_dao::{
         PayableAccount, PayableDaoError, PayableDaoFactory,
     };
@@ -1036,10 +1233,10 @@ mod tests {
     };
     use crate::accountant::db_access_objects::receivable_dao::ReceivableAccount;
     use crate::accountant::db_access_objects::utils::{from_time_t, to_time_t, CustomQuery};
-    use crate::accountant::payment_adjuster::Adjustment;
+    use crate::accountant::payment_adjuster::{AdjustedPayments, Adjustment, AnalysisError};
     use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::test_utils::BlockchainAgentMock;
     use crate::accountant::scanners::test_utils::protect_payables_in_test;
-    use crate::accountant::scanners::BeginScanError;
+    use crate::accountant::scanners::{BeginScanError, PayableScanner};
     use crate::accountant::test_utils::DaoWithDestination::{
         ForAccountantBody, ForPayableScanner, ForPendingPayableScanner, ForReceivableScanner,
     };
@@ -1063,7 +1260,9 @@ mod tests {
         ExitServiceConsumed, PaymentThresholds, RoutingServiceConsumed, ScanIntervals,
         DEFAULT_EARNING_WALLET, DEFAULT_PAYMENT_THRESHOLDS,
     };
-    use crate::sub_lib::blockchain_bridge::OutboundPaymentsInstructions;
+    use crate::sub_lib::blockchain_bridge::{
+        ConsumingWalletBalances, OutboundPaymentsInstructions, TransactionFees,
+    };
     use crate::sub_lib::neighborhood::ConfigChange;
     use crate::sub_lib::neighborhood::{Hops, WalletPair};
     use crate::test_utils::persistent_configuration_mock::PersistentConfigurationMock;
@@ -1090,8 +1289,9 @@ mod tests {
     };
     use masq_lib::messages::TopRecordsOrdering::{Age, Balance};
     use masq_lib::messages::{
-        CustomQueries, RangeQuery, ScanType, TopRecordsConfig, UiFinancialStatistics,
-        UiMessageError, UiPayableAccount, UiReceivableAccount, UiScanRequest, UiScanResponse,
+        CustomQueries, RangeQuery, ScanType, TopRecordsConfig, UiBannedWallet,
+        UiFinancialStatistics, UiMessageError, UiPayableAccount, UiReceivableAccount,
+        UiScanRequest, UiScanResponse,
     };
     use masq_lib::test_utils::logging::init_test_logging;
     use masq_lib::test_utils::logging::TestLogHandler;
@@ -1104,7 +1304,7 @@ mod tests {
     use std::sync::Mutex;
     use std::time::Duration;
     use std::vec;
-    use web3::types::TransactionReceipt;
+    use web3::types::{TransactionReceipt, U256};
 
     impl Handler<AssertionsMessage<Accountant>> for Accountant {
         type Result = ();
@@ -1122,6 +1322,7 @@ mod tests {
     fn constants_have_correct_values() {
         assert_eq!(CRASH_KEY, "ACCOUNTANT");
         assert_eq!(DEFAULT_PENDING_TOO_LONG_SEC, 21_600);
+        assert_eq!(DEFAULT_PENDING_SCANS_BEFORE_REPLACEMENT, 6);
     }
 
     #[test]
@@ -1148,6 +1349,7 @@ mod tests {
             .make_result(ReceivableDaoMock::new()); // For Receivable Scanner
         let banned_dao_factory = BannedDaoFactoryMock::new()
             .make_params(&banned_dao_factory_params_arc)
+            .make_result(BannedDaoMock::new()) // For Accountant
             .make_result(BannedDaoMock::new()); // For Receivable Scanner
         let config_dao_factory = ConfigDaoFactoryMock::new()
             .make_params(&config_dao_factory_params_arc)
@@ -1176,7 +1378,7 @@ mod tests {
             *receivable_dao_factory_params_arc.lock().unwrap(),
             vec![(), ()]
         );
-        assert_eq!(*banned_dao_factory_params_arc.lock().unwrap(), vec![()]);
+        assert_eq!(*banned_dao_factory_params_arc.lock().unwrap(), vec![(), ()]);
         assert_eq!(*config_dao_factory_params_arc.lock().unwrap(), vec![()]);
     }
 
@@ -1200,8 +1402,11 @@ mod tests {
                 .make_result(ReceivableDaoMock::new()) // For Accountant
                 .make_result(ReceivableDaoMock::new()), // For Scanner
         );
-        let banned_dao_factory =
-            Box::new(BannedDaoFactoryMock::new().make_result(BannedDaoMock::new()));
+        let banned_dao_factory = Box::new(
+            BannedDaoFactoryMock::new()
+                .make_result(BannedDaoMock::new()) // For Accountant
+                .make_result(BannedDaoMock::new()), // For Scanner
+        );
         let config_dao_factory =
             Box::new(ConfigDaoFactoryMock::new().make_result(ConfigDaoMock::new()));
 
@@ -1549,25 +1754,10 @@ mod tests {
 
         system.run();
         let mut is_adjustment_required_params = is_adjustment_required_params_arc.lock().unwrap();
-        let (blockchain_agent_with_context_msg_actual, logger_clone) =
+        let (qualified_payables_actual, agent_id_stamp_actual, logger_clone) =
             is_adjustment_required_params.remove(0);
-        assert_eq!(
-            blockchain_agent_with_context_msg_actual.protected_qualified_payables,
-            protect_payables_in_test(accounts.clone())
-        );
-        assert_eq!(
-            blockchain_agent_with_context_msg_actual.response_skeleton_opt,
-            Some(ResponseSkeleton {
-                client_id: 1234,
-                context_id: 4321,
-            })
-        );
-        assert_eq!(
-            blockchain_agent_with_context_msg_actual
-                .agent
-                .arbitrary_id_stamp(),
-            agent_id_stamp
-        );
+        assert_eq!(qualified_payables_actual, accounts);
+        assert_eq!(agent_id_stamp_actual, agent_id_stamp);
         assert!(is_adjustment_required_params.is_empty());
         let blockchain_bridge_recording = blockchain_bridge_recording_arc.lock().unwrap();
         let payments_instructions =
@@ -1645,15 +1835,18 @@ mod tests {
         let agent =
             BlockchainAgentMock::default().set_arbitrary_id_stamp(agent_id_stamp_second_phase);
         let affordable_accounts = vec![adjusted_account_1.clone(), adjusted_account_2.clone()];
-        let payments_instructions = OutboundPaymentsInstructions {
-            affordable_accounts: affordable_accounts.clone(),
-            agent: Box::new(agent),
-            response_skeleton_opt: Some(response_skeleton),
+        let adjusted_payments = AdjustedPayments {
+            instructions: OutboundPaymentsInstructions {
+                affordable_accounts: affordable_accounts.clone(),
+                agent: Box::new(agent),
+                response_skeleton_opt: Some(response_skeleton),
+            },
+            deferred: vec![],
         };
         let payment_adjuster = PaymentAdjusterMock::default()
             .is_adjustment_required_result(Ok(Some(Adjustment::MasqToken)))
             .adjust_payments_params(&adjust_payments_params_arc)
-            .adjust_payments_result(payments_instructions);
+            .adjust_payments_result(adjusted_payments);
         let payable_scanner = PayableScannerBuilder::new()
             .payment_adjuster(payment_adjuster)
             .build();
@@ -1712,6 +1905,73 @@ mod tests {
         test_use_of_the_same_logger(&logger_clone, test_name)
     }
 
+    #[test]
+    fn payables_our_wallet_cannot_pay_at_all_are_reported_as_scan_error_instead_of_forwarded() {
+        init_test_logging();
+        let test_name =
+            "payables_our_wallet_cannot_pay_at_all_are_reported_as_scan_error_instead_of_forwarded";
+        let (blockchain_bridge, _, blockchain_bridge_recording_arc) = make_recorder();
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let ui_gateway = ui_gateway.system_stop_conditions(match_every_type_id!(NodeToUiMessage));
+        let mut subject = AccountantBuilder::default()
+            .logger(Logger::new(test_name))
+            .build();
+        let analysis_error = AnalysisError::NotEnoughTransactionFeeBalanceForSingleTx {
+            number_of_accounts: 2,
+            per_transaction_requirement_minor: 70_000_000_000_000,
+            cw_transaction_fee_balance_minor: U256::from(50_000_000_000_000_u64),
+        };
+        let payment_adjuster = PaymentAdjusterMock::default()
+            .is_adjustment_required_result(Err(analysis_error.clone()));
+        let payable_scanner = PayableScannerBuilder::new()
+            .payment_adjuster(payment_adjuster)
+            .build();
+        subject.scanners.payable = Box::new(payable_scanner);
+        subject.scanners.payable.mark_as_started(SystemTime::now());
+        let response_skeleton = ResponseSkeleton {
+            client_id: 24,
+            context_id: 42,
+        };
+        let msg = BlockchainAgentWithContextMessage {
+            protected_qualified_payables: protect_payables_in_test(vec![
+                make_payable_account(111_111),
+                make_payable_account(222_222),
+            ]),
+            agent: Box::new(BlockchainAgentMock::default()),
+            response_skeleton_opt: Some(response_skeleton),
+        };
+        let subject_addr = subject.start();
+        let system = System::new(test_name);
+        let peer_actors = peer_actors_builder()
+            .blockchain_bridge(blockchain_bridge)
+            .ui_gateway(ui_gateway)
+            .build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr.try_send(msg).unwrap();
+
+        assert_eq!(system.run(), 0);
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(
+            ui_gateway_recording.get_record::<NodeToUiMessage>(0),
+            &NodeToUiMessage {
+                target: ClientId(response_skeleton.client_id),
+                body: MessageBody {
+                    opcode: "scan".to_string(),
+                    path: MessagePath::Conversation(response_skeleton.context_id),
+                    payload: Err((
+                        SCAN_ERROR,
+                        format!("Payables scan failed: '{}'", analysis_error)
+                    )),
+                },
+            }
+        );
+        let blockchain_bridge_recording = blockchain_bridge_recording_arc.lock().unwrap();
+        assert_eq!(blockchain_bridge_recording.len(), 0);
+        TestLogHandler::new()
+            .exists_log_containing(&format!("INFO: {}: The Payables scan ended in", test_name));
+    }
+
     #[test]
     fn scan_pending_payables_request() {
         let mut config = bc_from_earning_wallet(make_wallet("some_wallet_address"));
@@ -1728,6 +1988,7 @@ mod tests {
             attempt: 1,
             amount: 1_000_000,
             process_error: None,
+            replaceable_opt: None,
         };
         let pending_payable_dao = PendingPayableDaoMock::default()
             .return_all_errorless_fingerprints_result(vec![fingerprint.clone()]);
@@ -1760,6 +2021,7 @@ mod tests {
             blockchain_bridge_recording.get_record::<RequestTransactionReceipts>(0),
             &RequestTransactionReceipts {
                 pending_payable: vec![fingerprint],
+                stuck_transactions_opt: None,
                 response_skeleton_opt: Some(ResponseSkeleton {
                     client_id: 1234,
                     context_id: 4321,
@@ -1786,6 +2048,7 @@ mod tests {
             attempt: 1,
             amount: 1_000_000,
             process_error: None,
+            replaceable_opt: None,
         };
         let pending_payable_dao = PendingPayableDaoMock::default()
             .return_all_errorless_fingerprints_result(vec![fingerprint]);
@@ -2237,6 +2500,7 @@ mod tests {
             .begin_scan_result(Err(BeginScanError::NothingToProcess))
             .begin_scan_result(Ok(RequestTransactionReceipts {
                 pending_payable: vec![],
+                stuck_transactions_opt: None,
                 response_skeleton_opt: None,
             }))
             .stop_the_system_after_last_msg();
@@ -2743,6 +3007,7 @@ mod tests {
             attempt: 1,
             amount: 4444,
             process_error: None,
+            replaceable_opt: None,
         };
         let payable_fingerprint_2 = PendingPayableFingerprint {
             rowid: 550,
@@ -2751,6 +3016,7 @@ mod tests {
             attempt: 2,
             amount: 7999,
             process_error: None,
+            replaceable_opt: None,
         };
         let pending_payable_dao = PendingPayableDaoMock::default()
             .return_all_errorless_fingerprints_result(vec![
@@ -2782,6 +3048,7 @@ mod tests {
             received_msg,
             &RequestTransactionReceipts {
                 pending_payable: vec![payable_fingerprint_1, payable_fingerprint_2],
+                stuck_transactions_opt: None,
                 response_skeleton_opt: None,
             }
         );
@@ -2836,6 +3103,80 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn report_cover_traffic_message_is_tallied_apart_from_any_debts() {
+        init_test_logging();
+        let test_name = "report_cover_traffic_message_is_tallied_apart_from_any_debts";
+        let more_money_receivable_parameters_arc = Arc::new(Mutex::new(vec![]));
+        let more_money_payable_parameters_arc = Arc::new(Mutex::new(vec![]));
+        let payable_dao_mock = PayableDaoMock::new()
+            .more_money_payable_params(more_money_payable_parameters_arc.clone());
+        let receivable_dao_mock = ReceivableDaoMock::new()
+            .more_money_receivable_parameters(&more_money_receivable_parameters_arc);
+        let mut subject = AccountantBuilder::default()
+            .bootstrapper_config(bc_from_earning_wallet(make_wallet("hi")))
+            .payable_daos(vec![ForAccountantBody(payable_dao_mock)])
+            .receivable_daos(vec![ForAccountantBody(receivable_dao_mock)])
+            .logger(Logger::new(test_name))
+            .build();
+
+        subject.handle_report_cover_traffic_message(ReportCoverTrafficMessage {
+            bytes_sent: 1234,
+            bytes_received: 0,
+        });
+        subject.handle_report_cover_traffic_message(ReportCoverTrafficMessage {
+            bytes_sent: 0,
+            bytes_received: 4321,
+        });
+
+        assert_eq!(
+            subject.cover_traffic_statistics,
+            CoverTrafficStatistics {
+                bytes_sent: 1234,
+                bytes_received: 4321,
+            }
+        );
+        assert!(more_money_receivable_parameters_arc
+            .lock()
+            .unwrap()
+            .is_empty());
+        assert!(more_money_payable_parameters_arc.lock().unwrap().is_empty());
+        TestLogHandler::new().exists_log_containing(&format!(
+            "DEBUG: {}: Cover traffic so far, charged to nobody: 1234 bytes sent, 4321 bytes received",
+            test_name
+        ));
+    }
+
+    #[test]
+    fn prioritize_creditor_message_puts_the_creditor_before_the_payable_scanner() {
+        init_test_logging();
+        let test_name = "prioritize_creditor_message_puts_the_creditor_before_the_payable_scanner";
+        let mut subject = AccountantBuilder::default()
+            .bootstrapper_config(bc_from_earning_wallet(make_wallet("hi")))
+            .logger(Logger::new(test_name))
+            .build();
+        let creditor = make_wallet("creditor");
+
+        subject.handle_prioritize_creditor_message(PrioritizeCreditorMessage {
+            wallet: creditor.clone(),
+        });
+
+        let payable_scanner = subject
+            .scanners
+            .payable
+            .as_any()
+            .downcast_ref::<PayableScanner>()
+            .unwrap();
+        assert_eq!(
+            *payable_scanner.prioritized_creditors.borrow(),
+            HashSet::from([creditor.clone()])
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: {test_name}: Wallet {creditor} refused us service; whatever we owe it will be \
+             paid on the next payable scan"
+        ));
+    }
+
     #[test]
     fn report_routing_service_provided_message_is_received_from_our_consuming_wallet() {
         init_test_logging();
@@ -3459,7 +3800,15 @@ mod tests {
         let transaction_receipt_tx_2_third_round = TransactionReceipt::default();
         let mut transaction_receipt_tx_2_fourth_round = TransactionReceipt::default();
         transaction_receipt_tx_2_fourth_round.status = Some(U64::from(1)); // confirmed
-        let agent = BlockchainAgentMock::default();
+        let consuming_wallet_balances = ConsumingWalletBalances {
+            transaction_fee_balance_in_minor_units: U256::from(u128::MAX),
+            masq_token_balance_in_minor_units: U256::from(u128::MAX),
+        };
+        let agent = BlockchainAgentMock::default()
+            .estimated_transaction_fee_total_result(1)
+            .consuming_wallet_balances_result(consuming_wallet_balances.clone())
+            .consuming_wallet_balances_result(consuming_wallet_balances)
+            .agreed_transaction_fees_result(TransactionFees::Legacy { gas_price_wei: 1 });
         let blockchain_interface = BlockchainInterfaceMock::default()
             .build_blockchain_agent_params(&build_blockchain_agent_params)
             .build_blockchain_agent_result(Ok(Box::new(agent)))
@@ -3488,10 +3837,12 @@ mod tests {
         let system = System::new("pending_transaction");
         let persistent_config_id_stamp = ArbitraryIdStamp::new();
         let persistent_config = PersistentConfigurationMock::default()
-            .set_arbitrary_id_stamp(persistent_config_id_stamp);
+            .set_arbitrary_id_stamp(persistent_config_id_stamp)
+            .set_transaction_fees_result(Ok(()));
         let blockchain_bridge = BlockchainBridge::new(
             Box::new(blockchain_interface),
             Box::new(persistent_config),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         let account_1 = PayableAccount {
@@ -3528,6 +3879,7 @@ mod tests {
             attempt: 1,
             amount: payable_account_balance_1,
             process_error: None,
+            replaceable_opt: None,
         };
         let fingerprint_2_first_round = PendingPayableFingerprint {
             rowid: rowid_for_account_2,
@@ -3536,6 +3888,7 @@ mod tests {
             attempt: 1,
             amount: payable_account_balance_2,
             process_error: None,
+            replaceable_opt: None,
         };
         let fingerprint_1_second_round = PendingPayableFingerprint {
             attempt: 2,
@@ -3758,6 +4111,7 @@ mod tests {
             attempt: 2,
             amount: 444,
             process_error: None,
+            replaceable_opt: None,
         };
         let transaction_hash_2 = make_tx_hash(3333333);
         let mut transaction_receipt_2 = TransactionReceipt::default();
@@ -3770,6 +4124,7 @@ mod tests {
             attempt: 15,
             amount: 1212,
             process_error: None,
+            replaceable_opt: None,
         };
         let msg = ReportTransactionReceipts {
             fingerprints_with_receipts: vec![
@@ -3795,9 +4150,12 @@ mod tests {
     fn accountant_handles_inserting_new_fingerprints() {
         init_test_logging();
         let insert_fingerprint_params_arc = Arc::new(Mutex::new(vec![]));
+        let record_replaceable_transactions_params_arc = Arc::new(Mutex::new(vec![]));
         let pending_payable_dao = PendingPayableDaoMock::default()
             .insert_fingerprints_params(&insert_fingerprint_params_arc)
-            .insert_fingerprints_result(Ok(()));
+            .insert_fingerprints_result(Ok(()))
+            .record_replaceable_transactions_params(&record_replaceable_transactions_params_arc)
+            .record_replaceable_transactions_result(Ok(()));
         let subject = AccountantBuilder::default()
             .pending_payable_daos(vec![ForAccountantBody(pending_payable_dao)])
             .build();
@@ -3809,9 +4167,14 @@ mod tests {
         let hash_2 = make_tx_hash(0x1b207);
         let amount_2 = 87654;
         let init_params = vec![(hash_1, amount_1), (hash_2, amount_2)];
+        let replaceable_transactions = vec![
+            (hash_1, make_replaceable_transaction("creditor_1", 4)),
+            (hash_2, make_replaceable_transaction("creditor_2", 5)),
+        ];
         let init_fingerprints_msg = PendingPayableFingerprintSeeds {
             batch_wide_timestamp: timestamp,
             hashes_and_balances: init_params.clone(),
+            replaceable_transactions: replaceable_transactions.clone(),
         };
 
         let _ = accountant_subs
@@ -3827,12 +4190,61 @@ mod tests {
             *insert_fingerprint_params,
             vec![(vec![(hash_1, amount_1), (hash_2, amount_2)], timestamp)]
         );
+        let record_replaceable_transactions_params =
+            record_replaceable_transactions_params_arc.lock().unwrap();
+        assert_eq!(
+            *record_replaceable_transactions_params,
+            vec![replaceable_transactions]
+        );
         TestLogHandler::new().exists_log_containing(
             "DEBUG: Accountant: Saved new pending payable fingerprints for: \
              0x000000000000000000000000000000000000000000000000000000000006c81c, 0x000000000000000000000000000000000000000000000000000000000001b207",
         );
     }
 
+    fn make_replaceable_transaction(recipient: &str, nonce: u64) -> ReplaceableTransaction {
+        ReplaceableTransaction {
+            recipient_wallet: make_wallet(recipient),
+            nonce,
+            transaction_fees: TransactionFees::Legacy {
+                gas_price_wei: 1_000_000_000,
+            },
+            replacement_hashes: vec![],
+        }
+    }
+
+    #[test]
+    fn failure_to_keep_signing_details_is_logged_as_a_warning() {
+        init_test_logging();
+        let test_name = "failure_to_keep_signing_details_is_logged_as_a_warning";
+        let pending_payable_dao = PendingPayableDaoMock::default()
+            .insert_fingerprints_result(Ok(()))
+            .record_replaceable_transactions_result(Err(PendingPayableDaoError::UpdateFailed(
+                "Locked".to_string(),
+            )));
+        let mut subject = AccountantBuilder::default()
+            .pending_payable_daos(vec![ForAccountantBody(pending_payable_dao)])
+            .build();
+        subject.logger = Logger::new(test_name);
+        let transaction_hash = make_tx_hash(0x1c8);
+        let report_new_fingerprints = PendingPayableFingerprintSeeds {
+            batch_wide_timestamp: SystemTime::now(),
+            hashes_and_balances: vec![(transaction_hash, 2345)],
+            replaceable_transactions: vec![(
+                transaction_hash,
+                make_replaceable_transaction("creditor", 7),
+            )],
+        };
+
+        let _ = subject.handle_new_pending_payable_fingerprints(report_new_fingerprints);
+
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {test_name}: Failed to keep signing details due to 'UpdateFailed(\"Locked\")'; \
+             should these transactions get stuck, they will not be replaced: \
+             0x00000000000000000000000000000000000000000000000000000000000001c8"
+        ));
+    }
+
     #[test]
     fn payable_fingerprint_insertion_clearly_failed_and_we_log_it_at_least() {
         //despite it doesn't end so here this event would be a cause of a later panic
@@ -3852,6 +4264,10 @@ mod tests {
         let report_new_fingerprints = PendingPayableFingerprintSeeds {
             batch_wide_timestamp: timestamp,
             hashes_and_balances: vec![(transaction_hash, amount)],
+            replaceable_transactions: vec![(
+                transaction_hash,
+                make_replaceable_transaction("creditor", 7),
+            )],
         };
 
         let _ = subject.handle_new_pending_payable_fingerprints(report_new_fingerprints);
@@ -4072,6 +4488,172 @@ mod tests {
         )
     }
 
+    #[test]
+    fn banned_request_bans_and_unbans_by_hand_and_reports_the_ban_list() {
+        init_test_logging();
+        let test_name = "banned_request_bans_and_unbans_by_hand_and_reports_the_ban_list";
+        let ban_params_arc = Arc::new(Mutex::new(vec![]));
+        let unban_by_hand_params_arc = Arc::new(Mutex::new(vec![]));
+        let abuser = make_wallet("abuser");
+        let debtor = make_wallet("debtor");
+        let stranger = make_wallet("stranger");
+        let delinquent = make_wallet("delinquent");
+        let banned_dao = BannedDaoMock::new()
+            .ban_parameters(&ban_params_arc)
+            .unban_by_hand_parameters(&unban_by_hand_params_arc)
+            .unban_by_hand_result(true)
+            .unban_by_hand_result(false)
+            .ban_list_result(vec![
+                BannedAccount {
+                    wallet: delinquent.clone(),
+                    banned_at_opt: Some(from_time_t(1_600_000_000)),
+                    reason: BanReason::Delinquency {
+                        balance_gwei_opt: Some(12_345_678),
+                        debt_age_sec_opt: Some(700_000),
+                    },
+                },
+                BannedAccount {
+                    wallet: abuser.clone(),
+                    banned_at_opt: Some(from_time_t(1_600_000_500)),
+                    reason: BanReason::Manual {
+                        note_opt: Some("Operator's call".to_string()),
+                    },
+                },
+            ]);
+        let system = System::new(test_name);
+        let mut subject = AccountantBuilder::default()
+            .bootstrapper_config(make_bc_with_defaults())
+            .banned_daos(vec![ForAccountantBody(banned_dao)])
+            .build();
+        subject.logger = Logger::new(test_name);
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let subject_addr = subject.start();
+        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let before = SystemTime::now();
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: UiBannedRequest {
+                    ban: vec![abuser.to_string()],
+                    unban: vec![debtor.to_string(), stranger.to_string()],
+                    note_opt: Some("Operator's call".to_string()),
+                }
+                .tmb(4321),
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let after = SystemTime::now();
+        let ban_params = ban_params_arc.lock().unwrap();
+        assert_eq!(ban_params.len(), 1);
+        let (wallet, reason, timestamp) = &ban_params[0];
+        assert_eq!(wallet, &abuser);
+        assert_eq!(
+            reason,
+            &BanReason::Manual {
+                note_opt: Some("Operator's call".to_string())
+            }
+        );
+        assert!(before <= *timestamp && *timestamp <= after);
+        let unban_by_hand_params = unban_by_hand_params_arc.lock().unwrap();
+        assert_eq!(
+            *unban_by_hand_params,
+            vec![
+                (
+                    debtor.clone(),
+                    Some("Operator's call".to_string()),
+                    *timestamp
+                ),
+                (
+                    stranger.clone(),
+                    Some("Operator's call".to_string()),
+                    *timestamp
+                ),
+            ]
+        );
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let response = ui_gateway_recording.get_record::<NodeToUiMessage>(0);
+        assert_eq!(response.target, ClientId(1234));
+        let (body, context_id) = UiBannedResponse::fmb(response.body.clone()).unwrap();
+        assert_eq!(context_id, 4321);
+        assert_eq!(
+            body,
+            UiBannedResponse {
+                banned: vec![
+                    UiBannedWallet {
+                        wallet: delinquent.to_string(),
+                        banned_at_sec_opt: Some(1_600_000_000),
+                        manual: false,
+                        note_opt: None,
+                        balance_gwei_opt: Some(12_345_678),
+                        debt_age_sec_opt: Some(700_000),
+                    },
+                    UiBannedWallet {
+                        wallet: abuser.to_string(),
+                        banned_at_sec_opt: Some(1_600_000_500),
+                        manual: true,
+                        note_opt: Some("Operator's call".to_string()),
+                        balance_gwei_opt: None,
+                        debt_age_sec_opt: None,
+                    },
+                ]
+            }
+        );
+        let tlh = TestLogHandler::new();
+        tlh.exists_log_containing(&format!(
+            "INFO: {}: Wallet {} banned by hand: Operator's call",
+            test_name, abuser
+        ));
+        tlh.exists_log_containing(&format!(
+            "INFO: {}: Ban on wallet {} lifted by hand: Operator's call",
+            test_name, debtor
+        ));
+        tlh.exists_log_containing(&format!(
+            "INFO: {}: Wallet {} was not banned; nothing to lift",
+            test_name, stranger
+        ));
+    }
+
+    #[test]
+    fn compute_banned_rejects_a_bad_wallet_address_without_changing_anything() {
+        let ban_params_arc = Arc::new(Mutex::new(vec![]));
+        let unban_by_hand_params_arc = Arc::new(Mutex::new(vec![]));
+        let ban_list_params_arc = Arc::new(Mutex::new(vec![]));
+        let banned_dao = BannedDaoMock::new()
+            .ban_parameters(&ban_params_arc)
+            .unban_by_hand_parameters(&unban_by_hand_params_arc)
+            .ban_list_params(&ban_list_params_arc);
+        let subject = AccountantBuilder::default()
+            .bootstrapper_config(make_bc_with_defaults())
+            .banned_daos(vec![ForAccountantBody(banned_dao)])
+            .build();
+        let request = UiBannedRequest {
+            ban: vec![make_wallet("fine").to_string()],
+            unban: vec!["0xbooga".to_string()],
+            note_opt: None,
+        };
+
+        let result = subject.compute_banned(request, 2468);
+
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "banned".to_string(),
+                path: Conversation(2468),
+                payload: Err((
+                    INVALID_WALLET_ERROR,
+                    "Bad wallet address: '0xbooga'".to_string()
+                ))
+            }
+        );
+        assert!(ban_params_arc.lock().unwrap().is_empty());
+        assert!(unban_by_hand_params_arc.lock().unwrap().is_empty());
+        assert!(ban_list_params_arc.lock().unwrap().is_empty());
+    }
+
     #[test]
     fn compute_financials_processes_defaulted_request() {
         let payable_dao = PayableDaoMock::new().total_result(u64::MAX as u128 + 123456);
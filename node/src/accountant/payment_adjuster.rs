@@ -1,15 +1,24 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
-use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::BlockchainAgentWithContextMessage;
+use crate::accountant::db_access_objects::payable_dao::PayableAccount;
+use crate::accountant::gwei_to_wei;
+use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::PreparedAdjustment;
+use crate::sub_lib::accountant::PaymentThresholds;
 use crate::sub_lib::blockchain_bridge::OutboundPaymentsInstructions;
 use masq_lib::logger::Logger;
+use std::cmp::Reverse;
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
 use std::time::SystemTime;
+use thousands::Separable;
+use web3::types::U256;
 
 pub trait PaymentAdjuster {
     fn search_for_indispensable_adjustment(
         &self,
-        msg: &BlockchainAgentWithContextMessage,
+        qualified_payables: &[PayableAccount],
+        agent: &dyn BlockchainAgent,
         logger: &Logger,
     ) -> Result<Option<Adjustment>, AnalysisError>;
 
@@ -18,43 +27,347 @@ pub trait PaymentAdjuster {
         setup: PreparedAdjustment,
         now: SystemTime,
         logger: &Logger,
-    ) -> OutboundPaymentsInstructions;
+    ) -> AdjustedPayments;
 
     as_any_ref_in_trait!();
 }
 
-pub struct PaymentAdjusterReal {}
+// Both the age and the size of a debt are measured against the payment thresholds, in thousandths
+// of the respective threshold; the creditors waiting longest for the most money come first
+const WEIGHT_SCALE: u128 = 1_000;
+
+pub struct PaymentAdjusterReal {
+    payment_thresholds: Rc<PaymentThresholds>,
+}
 
 impl PaymentAdjuster for PaymentAdjusterReal {
     fn search_for_indispensable_adjustment(
         &self,
-        _msg: &BlockchainAgentWithContextMessage,
-        _logger: &Logger,
+        qualified_payables: &[PayableAccount],
+        agent: &dyn BlockchainAgent,
+        logger: &Logger,
     ) -> Result<Option<Adjustment>, AnalysisError> {
-        Ok(None)
+        let adjustment_opt = self.determine_adjustment(qualified_payables, agent)?;
+        if let Some(adjustment) = adjustment_opt {
+            warning!(
+                logger,
+                "Consuming wallet {} can't cover all {} qualified payables; adjusting payments by {:?}",
+                agent.consuming_wallet(),
+                qualified_payables.len(),
+                adjustment
+            )
+        }
+        Ok(adjustment_opt)
     }
 
     fn adjust_payments(
         &self,
-        _setup: PreparedAdjustment,
-        _now: SystemTime,
-        _logger: &Logger,
-    ) -> OutboundPaymentsInstructions {
-        todo!("this function is dead until the card GH-711 is played")
+        setup: PreparedAdjustment,
+        now: SystemTime,
+        logger: &Logger,
+    ) -> AdjustedPayments {
+        let msg = setup.original_setup_msg;
+        let qualified_payables: Vec<PayableAccount> =
+            msg.protected_qualified_payables.expose_vector();
+        let weighted_payables = self.weigh_and_sort(qualified_payables, now);
+
+        let transaction_limit = match setup.adjustment {
+            Adjustment::MasqToken => weighted_payables.len(),
+            Adjustment::TransactionFeeCurrency { limiting_count } => limiting_count as usize,
+            Adjustment::Both => {
+                Self::affordable_transaction_count(msg.agent.as_ref()).unwrap_or(u16::MAX) as usize
+            }
+        };
+        let (fee_affordable, deferred_for_fee) =
+            Self::limit_by_transaction_fee(weighted_payables, transaction_limit);
+        Self::log_deferred(logger, &deferred_for_fee, "transaction fee balance");
+
+        let (affordable_accounts, deferred_for_masq) = match setup.adjustment {
+            Adjustment::TransactionFeeCurrency { .. } => (
+                fee_affordable
+                    .into_iter()
+                    .map(|weighted| weighted.account)
+                    .collect(),
+                vec![],
+            ),
+            Adjustment::MasqToken | Adjustment::Both => {
+                let cw_masq_balance = u256_to_u128(
+                    msg.agent
+                        .consuming_wallet_balances()
+                        .masq_token_balance_in_minor_units,
+                );
+                self.adjust_by_masq_token_balance(fee_affordable, cw_masq_balance, logger)
+            }
+        };
+        Self::log_deferred(logger, &deferred_for_masq, "MASQ balance");
+
+        AdjustedPayments {
+            instructions: OutboundPaymentsInstructions::new(
+                affordable_accounts,
+                msg.agent,
+                msg.response_skeleton_opt,
+            ),
+            deferred: deferred_for_fee
+                .into_iter()
+                .chain(deferred_for_masq)
+                .collect(),
+        }
     }
 
     as_any_ref_in_trait_impl!();
 }
 
 impl PaymentAdjusterReal {
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(payment_thresholds: Rc<PaymentThresholds>) -> Self {
+        Self { payment_thresholds }
+    }
+
+    fn determine_adjustment(
+        &self,
+        qualified_payables: &[PayableAccount],
+        agent: &dyn BlockchainAgent,
+    ) -> Result<Option<Adjustment>, AnalysisError> {
+        let transaction_limit_opt =
+            Self::check_transaction_fee_balance(qualified_payables.len(), agent)?;
+        let masq_shortage = self.check_masq_token_balance(qualified_payables, agent)?;
+        Ok(match (transaction_limit_opt, masq_shortage) {
+            (None, false) => None,
+            (Some(limiting_count), false) => {
+                Some(Adjustment::TransactionFeeCurrency { limiting_count })
+            }
+            (None, true) => Some(Adjustment::MasqToken),
+            (Some(_), true) => Some(Adjustment::Both),
+        })
+    }
+
+    fn check_transaction_fee_balance(
+        number_of_accounts: usize,
+        agent: &dyn BlockchainAgent,
+    ) -> Result<Option<u16>, AnalysisError> {
+        match Self::affordable_transaction_count(agent) {
+            None => Ok(None),
+            Some(0) => Err(AnalysisError::NotEnoughTransactionFeeBalanceForSingleTx {
+                number_of_accounts,
+                per_transaction_requirement_minor: agent.estimated_transaction_fee_total(1),
+                cw_transaction_fee_balance_minor: agent
+                    .consuming_wallet_balances()
+                    .transaction_fee_balance_in_minor_units,
+            }),
+            Some(count) if (count as usize) < number_of_accounts => Ok(Some(count)),
+            Some(_) => Ok(None),
+        }
+    }
+
+    // None means there is no limit worth considering
+    fn affordable_transaction_count(agent: &dyn BlockchainAgent) -> Option<u16> {
+        let per_transaction_requirement = agent.estimated_transaction_fee_total(1);
+        if per_transaction_requirement == 0 {
+            return None;
+        }
+        let count = agent
+            .consuming_wallet_balances()
+            .transaction_fee_balance_in_minor_units
+            / U256::from(per_transaction_requirement);
+        if count > U256::from(u16::MAX) {
+            None
+        } else {
+            Some(count.as_u32() as u16)
+        }
+    }
+
+    fn check_masq_token_balance(
+        &self,
+        qualified_payables: &[PayableAccount],
+        agent: &dyn BlockchainAgent,
+    ) -> Result<bool, AnalysisError> {
+        let cw_masq_balance = agent
+            .consuming_wallet_balances()
+            .masq_token_balance_in_minor_units;
+        let required = qualified_payables.iter().fold(0_u128, |sum, account| {
+            sum.saturating_add(account.balance_wei)
+        });
+        if U256::from(required) <= cw_masq_balance {
+            return Ok(false);
+        }
+        let minimal_payment = self.minimal_worthwhile_payment();
+        let smallest_worthwhile_payment = qualified_payables
+            .iter()
+            .map(|account| account.balance_wei.min(minimal_payment))
+            .min()
+            .unwrap_or(0);
+        if cw_masq_balance < U256::from(smallest_worthwhile_payment) {
+            Err(AnalysisError::NotEnoughMasqBalanceForAnyPayment {
+                number_of_accounts: qualified_payables.len(),
+                smallest_worthwhile_payment_minor: smallest_worthwhile_payment,
+                cw_masq_token_balance_minor: cw_masq_balance,
+            })
+        } else {
+            Ok(true)
+        }
+    }
+
+    // Paying off less than the creditor tolerates indefinitely isn't worth the transaction fee
+    fn minimal_worthwhile_payment(&self) -> u128 {
+        gwei_to_wei::<u128, u64>(self.payment_thresholds.permanent_debt_allowed_gwei).max(1)
+    }
+
+    fn weigh_and_sort(
+        &self,
+        accounts: Vec<PayableAccount>,
+        now: SystemTime,
+    ) -> Vec<WeightedPayable> {
+        let mut weighted: Vec<WeightedPayable> = accounts
+            .into_iter()
+            .map(|account| WeightedPayable {
+                weight: self.weight(&account, now),
+                account,
+            })
+            .collect();
+        weighted.sort_by_key(|weighted_payable| Reverse(weighted_payable.weight));
+        weighted
+    }
+
+    fn weight(&self, account: &PayableAccount, now: SystemTime) -> u128 {
+        let thresholds = self.payment_thresholds.as_ref();
+        let age_sec = now
+            .duration_since(account.last_paid_timestamp)
+            .unwrap_or_default()
+            .as_secs() as u128;
+        let full_maturity_sec =
+            (thresholds.maturity_threshold_sec + thresholds.threshold_interval_sec).max(1) as u128;
+        let debt_threshold_wei = gwei_to_wei::<u128, u64>(thresholds.debt_threshold_gwei).max(1);
+        let age_weight = age_sec.saturating_mul(WEIGHT_SCALE) / full_maturity_sec;
+        let balance_weight = account.balance_wei.saturating_mul(WEIGHT_SCALE) / debt_threshold_wei;
+        // Nobody is weightless, or the money couldn't be shared out
+        age_weight.saturating_add(balance_weight).max(1)
+    }
+
+    fn limit_by_transaction_fee(
+        mut weighted_payables: Vec<WeightedPayable>,
+        transaction_limit: usize,
+    ) -> (Vec<WeightedPayable>, Vec<PayableAccount>) {
+        let deferred = if transaction_limit < weighted_payables.len() {
+            weighted_payables
+                .split_off(transaction_limit)
+                .into_iter()
+                .map(|weighted| weighted.account)
+                .collect()
+        } else {
+            vec![]
+        };
+        (weighted_payables, deferred)
+    }
+
+    fn adjust_by_masq_token_balance(
+        &self,
+        mut candidates: Vec<WeightedPayable>,
+        cw_masq_balance: u128,
+        logger: &Logger,
+    ) -> (Vec<PayableAccount>, Vec<PayableAccount>) {
+        let minimal_payment = self.minimal_worthwhile_payment();
+        let mut deferred = vec![];
+        loop {
+            let allocations = Self::allocate(&candidates, cw_masq_balance);
+            // Only the least important of the creditors whose shares came out too small is let
+            // go at a time; the others may get enough once its share is spread among them
+            let too_small_position_opt =
+                candidates
+                    .iter()
+                    .zip(allocations.iter())
+                    .rposition(|(weighted, allocation)| {
+                        *allocation < weighted.account.balance_wei && *allocation < minimal_payment
+                    });
+            match too_small_position_opt {
+                Some(position) => deferred.push(candidates.remove(position).account),
+                None => {
+                    let adjusted = candidates
+                        .into_iter()
+                        .zip(allocations)
+                        .map(|(weighted, allocation)| {
+                            Self::settle(weighted.account, allocation, logger)
+                        })
+                        .collect();
+                    return (adjusted, deferred);
+                }
+            }
+        }
+    }
+
+    // Shares out the balance in proportion to the weights; whoever would get more than they're
+    // owed is paid in full, and the rest of their share goes back to be shared among the others
+    fn allocate(candidates: &[WeightedPayable], cw_masq_balance: u128) -> Vec<u128> {
+        let mut allocations = vec![0_u128; candidates.len()];
+        let mut unsettled: Vec<usize> = (0..candidates.len()).collect();
+        let mut remaining_balance = cw_masq_balance;
+        while !unsettled.is_empty() {
+            let total_weight = unsettled.iter().fold(U256::zero(), |sum, idx| {
+                sum + U256::from(candidates[*idx].weight)
+            });
+            let share = |idx: usize| -> u128 {
+                (U256::from(remaining_balance) * U256::from(candidates[idx].weight) / total_weight)
+                    .as_u128()
+            };
+            let (fully_paid, partially_paid): (Vec<usize>, Vec<usize>) = unsettled
+                .iter()
+                .partition(|idx| share(**idx) >= candidates[**idx].account.balance_wei);
+            if fully_paid.is_empty() {
+                partially_paid
+                    .iter()
+                    .for_each(|idx| allocations[*idx] = share(*idx));
+                break;
+            }
+            fully_paid.iter().for_each(|idx| {
+                allocations[*idx] = candidates[*idx].account.balance_wei;
+                remaining_balance -= candidates[*idx].account.balance_wei;
+            });
+            unsettled = partially_paid;
+        }
+        allocations
+    }
+
+    fn settle(mut account: PayableAccount, allocation: u128, logger: &Logger) -> PayableAccount {
+        if allocation < account.balance_wei {
+            info!(
+                logger,
+                "Paying {} wei of the {} wei owed to {}; the rest is deferred for lack of MASQ",
+                allocation.separate_with_commas(),
+                account.balance_wei.separate_with_commas(),
+                account.wallet
+            );
+            account.balance_wei = allocation;
+        }
+        account
     }
+
+    fn log_deferred(logger: &Logger, deferred: &[PayableAccount], shortage: &str) {
+        deferred.iter().for_each(|account| {
+            warning!(
+                logger,
+                "Payment of {} wei to {} deferred for insufficient {}",
+                account.balance_wei.separate_with_commas(),
+                account.wallet,
+                shortage
+            )
+        })
+    }
+}
+
+pub struct AdjustedPayments {
+    pub instructions: OutboundPaymentsInstructions,
+    // Creditors left out of the payments altogether; they are owed the whole debt still
+    pub deferred: Vec<PayableAccount>,
 }
 
-impl Default for PaymentAdjusterReal {
-    fn default() -> Self {
-        Self::new()
+struct WeightedPayable {
+    weight: u128,
+    account: PayableAccount,
+}
+
+fn u256_to_u128(value: U256) -> u128 {
+    if value > U256::from(u128::MAX) {
+        u128::MAX
+    } else {
+        value.as_u128()
     }
 }
 
@@ -65,41 +378,541 @@ pub enum Adjustment {
     Both,
 }
 
-#[derive(Debug, PartialEq, Eq)]
-pub enum AnalysisError {}
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum AnalysisError {
+    NotEnoughTransactionFeeBalanceForSingleTx {
+        number_of_accounts: usize,
+        per_transaction_requirement_minor: u128,
+        cw_transaction_fee_balance_minor: U256,
+    },
+    NotEnoughMasqBalanceForAnyPayment {
+        number_of_accounts: usize,
+        smallest_worthwhile_payment_minor: u128,
+        cw_masq_token_balance_minor: U256,
+    },
+}
+
+impl Display for AnalysisError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnalysisError::NotEnoughTransactionFeeBalanceForSingleTx {
+                number_of_accounts,
+                per_transaction_requirement_minor,
+                cw_transaction_fee_balance_minor,
+            } => write!(
+                f,
+                "Found a transaction fee balance of {} wei, which can't pay for even one of {} \
+                 transactions at {} wei each",
+                cw_transaction_fee_balance_minor.separate_with_commas(),
+                number_of_accounts,
+                per_transaction_requirement_minor.separate_with_commas()
+            ),
+            AnalysisError::NotEnoughMasqBalanceForAnyPayment {
+                number_of_accounts,
+                smallest_worthwhile_payment_minor,
+                cw_masq_token_balance_minor,
+            } => write!(
+                f,
+                "Found a MASQ balance of {} wei, which can't make a worthwhile payment to any of \
+                 {} creditors; the smallest would be {} wei",
+                cw_masq_token_balance_minor.separate_with_commas(),
+                number_of_accounts,
+                smallest_worthwhile_payment_minor.separate_with_commas()
+            ),
+        }
+    }
+}
 
 #[cfg(test)]
 mod tests {
-    use crate::accountant::payment_adjuster::{PaymentAdjuster, PaymentAdjusterReal};
+    use crate::accountant::db_access_objects::payable_dao::PayableAccount;
+    use crate::accountant::db_access_objects::utils::{from_time_t, to_time_t};
+    use crate::accountant::gwei_to_wei;
+    use crate::accountant::payment_adjuster::{
+        Adjustment, AnalysisError, PaymentAdjuster, PaymentAdjusterReal,
+    };
     use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::BlockchainAgentWithContextMessage;
     use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::test_utils::BlockchainAgentMock;
+    use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::PreparedAdjustment;
     use crate::accountant::scanners::test_utils::protect_payables_in_test;
     use crate::accountant::test_utils::make_payable_account;
+    use crate::accountant::ResponseSkeleton;
+    use crate::sub_lib::accountant::PaymentThresholds;
+    use crate::sub_lib::blockchain_bridge::ConsumingWalletBalances;
+    use crate::test_utils::make_wallet;
+    use crate::test_utils::unshared_test_utils::arbitrary_id_stamp::ArbitraryIdStamp;
     use masq_lib::logger::Logger;
     use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+    use std::rc::Rc;
+    use std::sync::{Arc, Mutex};
+    use std::time::SystemTime;
+    use web3::types::U256;
 
-    #[test]
-    fn search_for_indispensable_adjustment_always_returns_none() {
-        init_test_logging();
-        let test_name = "is_adjustment_required_always_returns_none";
-        let mut payable = make_payable_account(111);
-        payable.balance_wei = 100_000_000;
-        let agent = BlockchainAgentMock::default();
-        let setup_msg = BlockchainAgentWithContextMessage {
-            protected_qualified_payables: protect_payables_in_test(vec![payable]),
+    const NOW: i64 = 1_700_000_000;
+    const DAY: i64 = 86_400;
+
+    fn make_payment_thresholds() -> PaymentThresholds {
+        PaymentThresholds {
+            debt_threshold_gwei: 1_000_000,
+            maturity_threshold_sec: 10 * DAY as u64,
+            payment_grace_period_sec: DAY as u64,
+            permanent_debt_allowed_gwei: 10_000,
+            threshold_interval_sec: 10 * DAY as u64,
+            unban_below_gwei: 10_000,
+        }
+    }
+
+    fn make_subject() -> PaymentAdjusterReal {
+        PaymentAdjusterReal::new(Rc::new(make_payment_thresholds()))
+    }
+
+    fn make_account(name: &str, balance_gwei: u64, age_days: i64) -> PayableAccount {
+        PayableAccount {
+            wallet: make_wallet(name),
+            balance_wei: gwei_to_wei(balance_gwei),
+            last_paid_timestamp: from_time_t(NOW - age_days * DAY),
+            pending_payable_opt: None,
+        }
+    }
+
+    fn make_balances(transaction_fee_wei: u128, masq_wei: u128) -> ConsumingWalletBalances {
+        ConsumingWalletBalances {
+            transaction_fee_balance_in_minor_units: U256::from(transaction_fee_wei),
+            masq_token_balance_in_minor_units: U256::from(masq_wei),
+        }
+    }
+
+    fn make_setup_msg(
+        accounts: Vec<PayableAccount>,
+        agent: BlockchainAgentMock,
+    ) -> BlockchainAgentWithContextMessage {
+        BlockchainAgentWithContextMessage {
+            protected_qualified_payables: protect_payables_in_test(accounts),
             agent: Box::new(agent),
             response_skeleton_opt: None,
-        };
-        let logger = Logger::new(test_name);
-        let subject = PaymentAdjusterReal::new();
+        }
+    }
+
+    fn sum_of(accounts: &[PayableAccount]) -> u128 {
+        accounts.iter().map(|account| account.balance_wei).sum()
+    }
 
-        let result = subject.search_for_indispensable_adjustment(&setup_msg, &logger);
+    #[test]
+    fn search_for_indispensable_adjustment_finds_none_needed_when_everything_is_affordable() {
+        init_test_logging();
+        let test_name =
+            "search_for_indispensable_adjustment_finds_none_needed_when_everything_is_affordable";
+        let accounts = vec![
+            make_account("abc", 100_000, 30),
+            make_account("def", 200_000, 25),
+        ];
+        let estimated_fee_params_arc = Arc::new(Mutex::new(vec![]));
+        let agent = BlockchainAgentMock::default()
+            .estimated_transaction_fee_total_params(&estimated_fee_params_arc)
+            .estimated_transaction_fee_total_result(1_000)
+            .consuming_wallet_balances_result(make_balances(2_000, sum_of(&accounts)))
+            .consuming_wallet_balances_result(make_balances(2_000, sum_of(&accounts)));
+
+        let result = make_subject().search_for_indispensable_adjustment(
+            &accounts,
+            &agent,
+            &Logger::new(test_name),
+        );
 
         assert_eq!(result, Ok(None));
-        TestLogHandler::default().exists_no_log_containing(test_name);
-        // Nobody in this test asked about the wallet balances and the transaction fee
-        // requirement, yet we got through with the final None.
-        // How do we know? The mock agent didn't blow up while missing these
-        // results
+        assert_eq!(*estimated_fee_params_arc.lock().unwrap(), vec![1]);
+        TestLogHandler::new().exists_no_log_containing(test_name);
+    }
+
+    #[test]
+    fn search_for_indispensable_adjustment_limits_the_count_of_transactions_by_their_fee() {
+        init_test_logging();
+        let test_name =
+            "search_for_indispensable_adjustment_limits_the_count_of_transactions_by_their_fee";
+        let accounts = vec![
+            make_account("abc", 100_000, 30),
+            make_account("def", 200_000, 25),
+            make_account("ghi", 300_000, 20),
+        ];
+        let agent = BlockchainAgentMock::default()
+            .estimated_transaction_fee_total_result(1_000)
+            .consuming_wallet_balances_result(make_balances(2_999, u128::MAX))
+            .consuming_wallet_balances_result(make_balances(2_999, u128::MAX))
+            .consuming_wallet_result(make_wallet("consuming"));
+
+        let result = make_subject().search_for_indispensable_adjustment(
+            &accounts,
+            &agent,
+            &Logger::new(test_name),
+        );
+
+        assert_eq!(
+            result,
+            Ok(Some(Adjustment::TransactionFeeCurrency {
+                limiting_count: 2
+            }))
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {}: Consuming wallet {} can't cover all 3 qualified payables; adjusting \
+             payments by TransactionFeeCurrency {{ limiting_count: 2 }}",
+            test_name,
+            make_wallet("consuming")
+        ));
+    }
+
+    #[test]
+    fn search_for_indispensable_adjustment_finds_masq_shortage() {
+        let accounts = vec![
+            make_account("abc", 100_000, 30),
+            make_account("def", 200_000, 25),
+        ];
+        let agent = BlockchainAgentMock::default()
+            .estimated_transaction_fee_total_result(1_000)
+            .consuming_wallet_balances_result(make_balances(2_000, sum_of(&accounts) - 1))
+            .consuming_wallet_balances_result(make_balances(2_000, sum_of(&accounts) - 1))
+            .consuming_wallet_result(make_wallet("consuming"));
+
+        let result = make_subject().search_for_indispensable_adjustment(
+            &accounts,
+            &agent,
+            &Logger::new("test"),
+        );
+
+        assert_eq!(result, Ok(Some(Adjustment::MasqToken)));
+    }
+
+    #[test]
+    fn search_for_indispensable_adjustment_finds_both_shortages() {
+        let accounts = vec![
+            make_account("abc", 100_000, 30),
+            make_account("def", 200_000, 25),
+        ];
+        let agent = BlockchainAgentMock::default()
+            .estimated_transaction_fee_total_result(1_000)
+            .consuming_wallet_balances_result(make_balances(1_000, gwei_to_wei(50_000_u64)))
+            .consuming_wallet_balances_result(make_balances(1_000, gwei_to_wei(50_000_u64)))
+            .consuming_wallet_result(make_wallet("consuming"));
+
+        let result = make_subject().search_for_indispensable_adjustment(
+            &accounts,
+            &agent,
+            &Logger::new("test"),
+        );
+
+        assert_eq!(result, Ok(Some(Adjustment::Both)));
+    }
+
+    #[test]
+    fn search_for_indispensable_adjustment_fails_if_not_even_one_transaction_fee_is_affordable() {
+        let accounts = vec![
+            make_account("abc", 100_000, 30),
+            make_account("def", 200_000, 25),
+        ];
+        let agent = BlockchainAgentMock::default()
+            .estimated_transaction_fee_total_result(1_000)
+            .estimated_transaction_fee_total_result(1_000)
+            .consuming_wallet_balances_result(make_balances(999, u128::MAX))
+            .consuming_wallet_balances_result(make_balances(999, u128::MAX));
+
+        let result = make_subject().search_for_indispensable_adjustment(
+            &accounts,
+            &agent,
+            &Logger::new("test"),
+        );
+
+        assert_eq!(
+            result,
+            Err(AnalysisError::NotEnoughTransactionFeeBalanceForSingleTx {
+                number_of_accounts: 2,
+                per_transaction_requirement_minor: 1_000,
+                cw_transaction_fee_balance_minor: U256::from(999)
+            })
+        );
+    }
+
+    #[test]
+    fn search_for_indispensable_adjustment_fails_if_no_payment_would_be_worthwhile() {
+        // The smallest debt is even smaller than the minimal worthwhile payment
+        let accounts = vec![
+            make_account("abc", 100_000, 30),
+            make_account("def", 5_000, 25),
+        ];
+        let agent = BlockchainAgentMock::default()
+            .estimated_transaction_fee_total_result(1_000)
+            .consuming_wallet_balances_result(make_balances(2_000, gwei_to_wei(4_999_u64)))
+            .consuming_wallet_balances_result(make_balances(2_000, gwei_to_wei(4_999_u64)));
+
+        let result = make_subject().search_for_indispensable_adjustment(
+            &accounts,
+            &agent,
+            &Logger::new("test"),
+        );
+
+        assert_eq!(
+            result,
+            Err(AnalysisError::NotEnoughMasqBalanceForAnyPayment {
+                number_of_accounts: 2,
+                smallest_worthwhile_payment_minor: gwei_to_wei(5_000_u64),
+                cw_masq_token_balance_minor: U256::from(gwei_to_wei::<u128, u64>(4_999))
+            })
+        );
+    }
+
+    #[test]
+    fn weight_grows_with_age_and_size_of_the_debt() {
+        let subject = make_subject();
+        let now = from_time_t(NOW);
+
+        let young_small = subject.weight(&make_account("a", 100_000, 5), now);
+        let old_small = subject.weight(&make_account("b", 100_000, 15), now);
+        let young_big = subject.weight(&make_account("c", 900_000, 5), now);
+
+        // 5 days of 20 is 250 per mille, 100_000 gwei of 1_000_000 is 100 per mille
+        assert_eq!(young_small, 250 + 100);
+        assert_eq!(old_small, 750 + 100);
+        assert_eq!(young_big, 250 + 900);
+    }
+
+    #[test]
+    fn weight_is_never_zero() {
+        let subject = make_subject();
+        let mut account = make_account("a", 0, 0);
+        account.last_paid_timestamp = SystemTime::now();
+
+        let result = subject.weight(&account, from_time_t(NOW));
+
+        assert_eq!(result, 1);
+    }
+
+    #[test]
+    fn adjust_payments_by_transaction_fee_keeps_the_most_pressing_debts() {
+        init_test_logging();
+        let test_name = "adjust_payments_by_transaction_fee_keeps_the_most_pressing_debts";
+        let young_small = make_account("young_small", 100_000, 2);
+        let old_big = make_account("old_big", 800_000, 18);
+        let middling = make_account("middling", 400_000, 10);
+        let agent_id_stamp = ArbitraryIdStamp::new();
+        let agent = BlockchainAgentMock::default().set_arbitrary_id_stamp(agent_id_stamp);
+        let response_skeleton = ResponseSkeleton {
+            client_id: 12,
+            context_id: 34,
+        };
+        let mut msg = make_setup_msg(
+            vec![young_small.clone(), old_big.clone(), middling.clone()],
+            agent,
+        );
+        msg.response_skeleton_opt = Some(response_skeleton);
+        let setup = PreparedAdjustment::new(
+            msg,
+            Adjustment::TransactionFeeCurrency { limiting_count: 2 },
+        );
+
+        let result =
+            make_subject().adjust_payments(setup, from_time_t(NOW), &Logger::new(test_name));
+
+        assert_eq!(
+            result.instructions.affordable_accounts,
+            vec![old_big, middling]
+        );
+        assert_eq!(result.deferred, vec![young_small.clone()]);
+        assert_eq!(
+            result.instructions.agent.arbitrary_id_stamp(),
+            agent_id_stamp
+        );
+        assert_eq!(
+            result.instructions.response_skeleton_opt,
+            Some(response_skeleton)
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {}: Payment of 100,000,000,000,000 wei to {} deferred for insufficient \
+             transaction fee balance",
+            test_name, young_small.wallet
+        ));
+    }
+
+    #[test]
+    fn adjust_payments_by_masq_shares_out_the_balance_by_weight_and_pays_partially() {
+        init_test_logging();
+        let test_name =
+            "adjust_payments_by_masq_shares_out_the_balance_by_weight_and_pays_partially";
+        // Weights: 250 + 100 = 350, 500 + 400 = 900, 750 + 500 = 1250
+        let small = make_account("small", 100_000, 5);
+        let medium = make_account("medium", 400_000, 10);
+        let large = make_account("large", 500_000, 15);
+        let cw_masq_balance = gwei_to_wei::<u128, u64>(750_000);
+        let agent = BlockchainAgentMock::default()
+            .consuming_wallet_balances_result(make_balances(u128::MAX, cw_masq_balance));
+        let msg = make_setup_msg(vec![small.clone(), medium.clone(), large.clone()], agent);
+        let setup = PreparedAdjustment::new(msg, Adjustment::MasqToken);
+
+        let result =
+            make_subject().adjust_payments(setup, from_time_t(NOW), &Logger::new(test_name));
+
+        // The small debt is covered by its proportional share (750,000 * 350 / 2500 = 105,000)
+        // and paid in full; the remaining 650,000 gwei is split 900:1250 between the others
+        let expected_medium = 650_000_000_000_000_u128 * 900 / 2150;
+        let expected_large = 650_000_000_000_000_u128 * 1250 / 2150;
+        assert_eq!(
+            result
+                .instructions
+                .affordable_accounts
+                .iter()
+                .map(|account| (account.wallet.clone(), account.balance_wei))
+                .collect::<Vec<_>>(),
+            vec![
+                (large.wallet.clone(), expected_large),
+                (medium.wallet.clone(), expected_medium),
+                (small.wallet.clone(), small.balance_wei),
+            ]
+        );
+        assert!(sum_of(&result.instructions.affordable_accounts) <= cw_masq_balance);
+        assert_eq!(result.deferred, vec![]);
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: {}: Paying {} wei of the 500,000,000,000,000 wei owed to {}; the rest is \
+             deferred for lack of MASQ",
+            test_name,
+            thousands::Separable::separate_with_commas(&expected_large),
+            large.wallet
+        ));
+    }
+
+    #[test]
+    fn adjust_payments_by_masq_defers_creditors_whose_shares_would_be_too_small() {
+        init_test_logging();
+        let test_name = "adjust_payments_by_masq_defers_creditors_whose_shares_would_be_too_small";
+        // Weights: 1000 + 900 = 1900 and 50 + 100 = 150
+        let pressing = make_account("pressing", 900_000, 20);
+        let negligible = make_account("negligible", 100_000, 1);
+        // The negligible creditor's share would be 100,000 * 150 / 2050 = 7,317 gwei, less
+        // than the minimal worthwhile payment of 10,000 gwei
+        let cw_masq_balance = gwei_to_wei::<u128, u64>(100_000);
+        let agent = BlockchainAgentMock::default()
+            .consuming_wallet_balances_result(make_balances(u128::MAX, cw_masq_balance));
+        let msg = make_setup_msg(vec![negligible.clone(), pressing.clone()], agent);
+        let setup = PreparedAdjustment::new(msg, Adjustment::MasqToken);
+
+        let result =
+            make_subject().adjust_payments(setup, from_time_t(NOW), &Logger::new(test_name));
+
+        let mut expected_pressing = pressing.clone();
+        expected_pressing.balance_wei = cw_masq_balance;
+        assert_eq!(
+            result.instructions.affordable_accounts,
+            vec![expected_pressing]
+        );
+        assert_eq!(result.deferred, vec![negligible.clone()]);
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {}: Payment of 100,000,000,000,000 wei to {} deferred for insufficient \
+             MASQ balance",
+            test_name, negligible.wallet
+        ));
+    }
+
+    #[test]
+    fn adjust_payments_by_both_limits_transactions_first_and_then_shares_out_masq() {
+        init_test_logging();
+        let test_name =
+            "adjust_payments_by_both_limits_transactions_first_and_then_shares_out_masq";
+        let first = make_account("first", 600_000, 20);
+        let second = make_account("second", 300_000, 15);
+        let third = make_account("third", 200_000, 1);
+        let cw_masq_balance = gwei_to_wei::<u128, u64>(800_000);
+        let agent = BlockchainAgentMock::default()
+            .estimated_transaction_fee_total_result(1_000)
+            .consuming_wallet_balances_result(make_balances(2_500, cw_masq_balance))
+            .consuming_wallet_balances_result(make_balances(2_500, cw_masq_balance));
+        let msg = make_setup_msg(vec![third.clone(), second.clone(), first.clone()], agent);
+        let setup = PreparedAdjustment::new(msg, Adjustment::Both);
+
+        let result =
+            make_subject().adjust_payments(setup, from_time_t(NOW), &Logger::new(test_name));
+
+        // Weights: 1000 + 600 = 1600 and 750 + 300 = 1050; the share of the second creditor,
+        // 800,000 * 1050 / 2650 = 316,981 gwei, covers its whole debt
+        let mut expected_first = first.clone();
+        expected_first.balance_wei = cw_masq_balance - second.balance_wei;
+        assert_eq!(
+            result.instructions.affordable_accounts,
+            vec![expected_first, second]
+        );
+        assert_eq!(result.deferred, vec![third.clone()]);
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {}: Payment of 200,000,000,000,000 wei to {} deferred for insufficient \
+             transaction fee balance",
+            test_name, third.wallet
+        ));
+    }
+
+    #[test]
+    fn adjust_payments_returns_creditors_deferred_for_either_shortage() {
+        // Weights: 1000 + 900 = 1900, 50 + 100 = 150 and 50 + 50 = 100
+        let pressing = make_account("pressing", 900_000, 20);
+        let negligible = make_account("negligible", 100_000, 1);
+        let least = make_account("least", 50_000, 1);
+        let cw_masq_balance = gwei_to_wei::<u128, u64>(100_000);
+        let agent = BlockchainAgentMock::default()
+            .estimated_transaction_fee_total_result(1_000)
+            .consuming_wallet_balances_result(make_balances(2_500, cw_masq_balance))
+            .consuming_wallet_balances_result(make_balances(2_500, cw_masq_balance));
+        let msg = make_setup_msg(
+            vec![least.clone(), negligible.clone(), pressing.clone()],
+            agent,
+        );
+        let setup = PreparedAdjustment::new(msg, Adjustment::Both);
+
+        let result = make_subject().adjust_payments(setup, from_time_t(NOW), &Logger::new("test"));
+
+        let mut expected_pressing = pressing;
+        expected_pressing.balance_wei = cw_masq_balance;
+        assert_eq!(
+            result.instructions.affordable_accounts,
+            vec![expected_pressing]
+        );
+        // The least weighty goes for the transaction fee, the next one for MASQ
+        assert_eq!(result.deferred, vec![least, negligible]);
+    }
+
+    #[test]
+    fn adjust_payments_by_masq_pays_everything_when_the_balance_turns_out_sufficient() {
+        let first = make_account("first", 600_000, 20);
+        let second = make_payable_account(1_234);
+        let cw_masq_balance = first.balance_wei + second.balance_wei;
+        let agent = BlockchainAgentMock::default()
+            .consuming_wallet_balances_result(make_balances(u128::MAX, cw_masq_balance));
+        let msg = make_setup_msg(vec![second.clone(), first.clone()], agent);
+        let setup = PreparedAdjustment::new(msg, Adjustment::MasqToken);
+
+        let result = make_subject().adjust_payments(
+            setup,
+            from_time_t(to_time_t(SystemTime::now())),
+            &Logger::new("test"),
+        );
+
+        assert_eq!(result.instructions.affordable_accounts, vec![first, second]);
+        assert_eq!(result.deferred, vec![]);
+    }
+
+    #[test]
+    fn analysis_errors_are_displayed_properly() {
+        assert_eq!(
+            AnalysisError::NotEnoughTransactionFeeBalanceForSingleTx {
+                number_of_accounts: 3,
+                per_transaction_requirement_minor: 55_000_000_000,
+                cw_transaction_fee_balance_minor: U256::from(54_000_000_000_u64),
+            }
+            .to_string(),
+            "Found a transaction fee balance of 54,000,000,000 wei, which can't pay for even one \
+             of 3 transactions at 55,000,000,000 wei each"
+        );
+        assert_eq!(
+            AnalysisError::NotEnoughMasqBalanceForAnyPayment {
+                number_of_accounts: 2,
+                smallest_worthwhile_payment_minor: 10_000_000_000_000,
+                cw_masq_token_balance_minor: U256::from(9_999_999_999_999_u64),
+            }
+            .to_string(),
+            "Found a MASQ balance of 9,999,999,999,999 wei, which can't make a worthwhile payment \
+             to any of 2 creditors; the smallest would be 10,000,000,000,000 wei"
+        );
     }
 }
@@ -2,7 +2,7 @@
 
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
 
-use crate::sub_lib::blockchain_bridge::ConsumingWalletBalances;
+use crate::sub_lib::blockchain_bridge::{ConsumingWalletBalances, TransactionFees};
 use crate::sub_lib::wallet::Wallet;
 use ethereum_types::U256;
 use masq_lib::logger::Logger;
@@ -27,9 +27,9 @@ impl BlockchainAgent for BlockchainAgentNull {
         }
     }
 
-    fn agreed_fee_per_computation_unit(&self) -> u64 {
-        self.log_function_call("agreed_fee_per_computation_unit()");
-        0
+    fn agreed_transaction_fees(&self) -> TransactionFees {
+        self.log_function_call("agreed_transaction_fees()");
+        TransactionFees::Legacy { gas_price_wei: 0 }
     }
 
     fn consuming_wallet(&self) -> &Wallet {
@@ -78,7 +78,7 @@ mod tests {
     use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::agent_null::BlockchainAgentNull;
     use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
 
-    use crate::sub_lib::blockchain_bridge::ConsumingWalletBalances;
+    use crate::sub_lib::blockchain_bridge::{ConsumingWalletBalances, TransactionFees};
     use crate::sub_lib::wallet::Wallet;
 
     use masq_lib::logger::Logger;
@@ -152,16 +152,16 @@ mod tests {
     }
 
     #[test]
-    fn null_agent_agreed_fee_per_computation_unit() {
+    fn null_agent_agreed_transaction_fees() {
         init_test_logging();
-        let test_name = "null_agent_agreed_fee_per_computation_unit";
+        let test_name = "null_agent_agreed_transaction_fees";
         let mut subject = BlockchainAgentNull::new();
         subject.logger = Logger::new(test_name);
 
-        let result = subject.agreed_fee_per_computation_unit();
+        let result = subject.agreed_transaction_fees();
 
-        assert_eq!(result, 0);
-        assert_error_log(test_name, "agreed_fee_per_computation_unit")
+        assert_eq!(result, TransactionFees::Legacy { gas_price_wei: 0 });
+        assert_error_log(test_name, "agreed_transaction_fees")
     }
 
     #[test]
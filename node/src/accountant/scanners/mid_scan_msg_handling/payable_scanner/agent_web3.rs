@@ -2,14 +2,14 @@
 
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
 
-use crate::sub_lib::blockchain_bridge::ConsumingWalletBalances;
+use crate::sub_lib::blockchain_bridge::{ConsumingWalletBalances, TransactionFees};
 use crate::sub_lib::wallet::Wallet;
 
 use web3::types::U256;
 
 #[derive(Debug, Clone)]
 pub struct BlockchainAgentWeb3 {
-    gas_price_gwei: u64,
+    transaction_fees: TransactionFees,
     gas_limit_const_part: u64,
     maximum_added_gas_margin: u64,
     consuming_wallet: Wallet,
@@ -19,17 +19,17 @@ pub struct BlockchainAgentWeb3 {
 
 impl BlockchainAgent for BlockchainAgentWeb3 {
     fn estimated_transaction_fee_total(&self, number_of_transactions: usize) -> u128 {
-        let gas_price = self.gas_price_gwei as u128;
+        let max_fee_per_gas = self.transaction_fees.max_fee_per_gas_wei();
         let max_gas_limit = (self.maximum_added_gas_margin + self.gas_limit_const_part) as u128;
-        number_of_transactions as u128 * gas_price * max_gas_limit
+        number_of_transactions as u128 * max_fee_per_gas * max_gas_limit
     }
 
     fn consuming_wallet_balances(&self) -> ConsumingWalletBalances {
         self.consuming_wallet_balances
     }
 
-    fn agreed_fee_per_computation_unit(&self) -> u64 {
-        self.gas_price_gwei
+    fn agreed_transaction_fees(&self) -> TransactionFees {
+        self.transaction_fees
     }
 
     fn consuming_wallet(&self) -> &Wallet {
@@ -47,14 +47,14 @@ pub const WEB3_MAXIMAL_GAS_LIMIT_MARGIN: u64 = 3328;
 
 impl BlockchainAgentWeb3 {
     pub fn new(
-        gas_price_gwei: u64,
+        transaction_fees: TransactionFees,
         gas_limit_const_part: u64,
         consuming_wallet: Wallet,
         consuming_wallet_balances: ConsumingWalletBalances,
         pending_transaction_id: U256,
     ) -> Self {
         Self {
-            gas_price_gwei,
+            transaction_fees,
             gas_limit_const_part,
             consuming_wallet,
             maximum_added_gas_margin: WEB3_MAXIMAL_GAS_LIMIT_MARGIN,
@@ -71,9 +71,10 @@ mod tests {
     };
     use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
 
-    use crate::sub_lib::blockchain_bridge::ConsumingWalletBalances;
+    use crate::sub_lib::blockchain_bridge::{ConsumingWalletBalances, TransactionFees};
     use crate::test_utils::make_wallet;
 
+    use masq_lib::constants::WEIS_IN_GWEI;
     use web3::types::U256;
 
     #[test]
@@ -83,7 +84,10 @@ mod tests {
 
     #[test]
     fn blockchain_agent_can_return_non_computed_input_values() {
-        let gas_price_gwei = 123;
+        let transaction_fees = TransactionFees::Eip1559 {
+            max_fee_per_gas_wei: 123_000_000_000,
+            max_priority_fee_per_gas_wei: 2_000_000_000,
+        };
         let gas_limit_const_part = 44_000;
         let consuming_wallet = make_wallet("abcde");
         let consuming_wallet_balances = ConsumingWalletBalances {
@@ -93,14 +97,14 @@ mod tests {
         let pending_transaction_id = U256::from(777);
 
         let subject = BlockchainAgentWeb3::new(
-            gas_price_gwei,
+            transaction_fees,
             gas_limit_const_part,
             consuming_wallet.clone(),
             consuming_wallet_balances,
             pending_transaction_id,
         );
 
-        assert_eq!(subject.agreed_fee_per_computation_unit(), gas_price_gwei);
+        assert_eq!(subject.agreed_transaction_fees(), transaction_fees);
         assert_eq!(subject.consuming_wallet(), &consuming_wallet);
         assert_eq!(
             subject.consuming_wallet_balances(),
@@ -118,7 +122,9 @@ mod tests {
         };
         let nonce = U256::from(55);
         let agent = BlockchainAgentWeb3::new(
-            444,
+            TransactionFees::Legacy {
+                gas_price_wei: 444 * WEIS_IN_GWEI as u128,
+            },
             77_777,
             consuming_wallet,
             consuming_wallet_balances,
@@ -134,7 +140,32 @@ mod tests {
         );
         assert_eq!(
             result,
-            (3 * (77_777 + WEB3_MAXIMAL_GAS_LIMIT_MARGIN)) as u128 * 444
+            (3 * (77_777 + WEB3_MAXIMAL_GAS_LIMIT_MARGIN)) as u128 * 444 * WEIS_IN_GWEI as u128
+        );
+    }
+
+    #[test]
+    fn estimated_transaction_fee_counts_with_the_max_fee_per_gas_for_eip1559_transactions() {
+        let consuming_wallet_balances = ConsumingWalletBalances {
+            transaction_fee_balance_in_minor_units: Default::default(),
+            masq_token_balance_in_minor_units: Default::default(),
+        };
+        let agent = BlockchainAgentWeb3::new(
+            TransactionFees::Eip1559 {
+                max_fee_per_gas_wei: 30_000_000,
+                max_priority_fee_per_gas_wei: 1_000_000,
+            },
+            70_000,
+            make_wallet("efg"),
+            consuming_wallet_balances,
+            U256::from(1),
+        );
+
+        let result = agent.estimated_transaction_fee_total(2);
+
+        assert_eq!(
+            result,
+            (2 * (70_000 + WEB3_MAXIMAL_GAS_LIMIT_MARGIN)) as u128 * 30_000_000
         );
     }
 }
@@ -1,7 +1,7 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 use crate::arbitrary_id_stamp_in_trait;
-use crate::sub_lib::blockchain_bridge::ConsumingWalletBalances;
+use crate::sub_lib::blockchain_bridge::{ConsumingWalletBalances, TransactionFees};
 use crate::sub_lib::wallet::Wallet;
 use web3::types::U256;
 
@@ -22,9 +22,10 @@ use web3::types::U256;
 //* defaulted limit
 
 pub trait BlockchainAgent: Send {
+    // In minor units, to be comparable with the consuming wallet balances
     fn estimated_transaction_fee_total(&self, number_of_transactions: usize) -> u128;
     fn consuming_wallet_balances(&self) -> ConsumingWalletBalances;
-    fn agreed_fee_per_computation_unit(&self) -> u64;
+    fn agreed_transaction_fees(&self) -> TransactionFees;
     fn consuming_wallet(&self) -> &Wallet;
     fn pending_transaction_id(&self) -> U256;
 
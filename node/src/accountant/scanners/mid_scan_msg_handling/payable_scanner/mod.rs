@@ -6,7 +6,7 @@ pub mod blockchain_agent;
 pub mod msgs;
 pub mod test_utils;
 
-use crate::accountant::payment_adjuster::Adjustment;
+use crate::accountant::payment_adjuster::{AdjustedPayments, Adjustment};
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::BlockchainAgentWithContextMessage;
 use crate::accountant::scanners::Scanner;
 use crate::sub_lib::blockchain_bridge::OutboundPaymentsInstructions;
@@ -33,7 +33,7 @@ pub trait SolvencySensitivePaymentInstructor {
         &self,
         setup: PreparedAdjustment,
         logger: &Logger,
-    ) -> OutboundPaymentsInstructions;
+    ) -> AdjustedPayments;
 }
 
 pub struct PreparedAdjustment {
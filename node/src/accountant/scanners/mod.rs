@@ -7,7 +7,7 @@ pub mod test_utils;
 use crate::accountant::db_access_objects::payable_dao::{PayableAccount, PayableDao};
 use crate::accountant::db_access_objects::pending_payable_dao::{PendingPayable, PendingPayableDao};
 use crate::accountant::db_access_objects::receivable_dao::ReceivableDao;
-use crate::accountant::payment_adjuster::{PaymentAdjuster, PaymentAdjusterReal};
+use crate::accountant::payment_adjuster::{AdjustedPayments, PaymentAdjuster, PaymentAdjusterReal};
 use crate::accountant::scanners::scanners_utils::payable_scanner_utils::PayableTransactingErrorEnum::{
     LocallyCausedError, RemotelyCausedErrors,
 };
@@ -26,9 +26,9 @@ use crate::accountant::PendingPayableId;
 use crate::accountant::{
     comma_joined_stringifiable, gwei_to_wei, Accountant, ReceivedPayments,
     ReportTransactionReceipts, RequestTransactionReceipts, ResponseSkeleton, ScanForPayables,
-    ScanForPendingPayables, ScanForReceivables, SentPayables,
+    ScanForPendingPayables, ScanForReceivables, SentPayables, StuckTransactions, wei_to_gwei,
 };
-use crate::accountant::db_access_objects::banned_dao::BannedDao;
+use crate::accountant::db_access_objects::banned_dao::{BanReason, BannedDao};
 use crate::blockchain::blockchain_bridge::{PendingPayableFingerprint, RetrieveTransactions};
 use crate::sub_lib::accountant::{
     DaoFactories, FinancialStatistics, PaymentThresholds, ScanIntervals,
@@ -69,13 +69,16 @@ impl Scanners {
         dao_factories: DaoFactories,
         payment_thresholds: Rc<PaymentThresholds>,
         when_pending_too_long_sec: u64,
+        pending_scans_before_replacement: u16,
         financial_statistics: Rc<RefCell<FinancialStatistics>>,
+        prioritized_creditors: Rc<RefCell<HashSet<Wallet>>>,
     ) -> Self {
         let payable = Box::new(PayableScanner::new(
             dao_factories.payable_dao_factory.make(),
             dao_factories.pending_payable_dao_factory.make(),
             Rc::clone(&payment_thresholds),
-            Box::new(PaymentAdjusterReal::new()),
+            Box::new(PaymentAdjusterReal::new(Rc::clone(&payment_thresholds))),
+            prioritized_creditors,
         ));
 
         let pending_payable = Box::new(PendingPayableScanner::new(
@@ -83,6 +86,7 @@ impl Scanners {
             dao_factories.pending_payable_dao_factory.make(),
             Rc::clone(&payment_thresholds),
             when_pending_too_long_sec,
+            pending_scans_before_replacement,
             Rc::clone(&financial_statistics),
         ));
 
@@ -187,6 +191,7 @@ pub struct PayableScanner {
     pub pending_payable_dao: Box<dyn PendingPayableDao>,
     pub payable_threshold_gauge: Box<dyn PayableThresholdsGauge>,
     pub payment_adjuster: Box<dyn PaymentAdjuster>,
+    pub prioritized_creditors: Rc<RefCell<HashSet<Wallet>>>,
 }
 
 impl Scanner<QualifiedPayablesMessage, SentPayables> for PayableScanner {
@@ -210,8 +215,12 @@ impl Scanner<QualifiedPayablesMessage, SentPayables> for PayableScanner {
             investigate_debt_extremes(timestamp, &all_non_pending_payables)
         );
 
-        let qualified_payables =
-            self.sniff_out_alarming_payables_and_maybe_log_them(all_non_pending_payables, logger);
+        let (prioritized_payables, other_payables) =
+            self.separate_prioritized_payables(all_non_pending_payables, logger);
+        let qualified_payables = prioritized_payables
+            .into_iter()
+            .chain(self.sniff_out_alarming_payables_and_maybe_log_them(other_payables, logger))
+            .collect::<Vec<PayableAccount>>();
 
         match qualified_payables.is_empty() {
             true => {
@@ -269,21 +278,26 @@ impl SolvencySensitivePaymentInstructor for PayableScanner {
         msg: BlockchainAgentWithContextMessage,
         logger: &Logger,
     ) -> Result<Either<OutboundPaymentsInstructions, PreparedAdjustment>, String> {
-        match self
-            .payment_adjuster
-            .search_for_indispensable_adjustment(&msg, logger)
-        {
-            Ok(None) => {
-                let protected = msg.protected_qualified_payables;
-                let unprotected = self.expose_payables(protected);
-                Ok(Either::Left(OutboundPaymentsInstructions::new(
-                    unprotected,
+        let qualified_payables = self.expose_payables(msg.protected_qualified_payables);
+        match self.payment_adjuster.search_for_indispensable_adjustment(
+            &qualified_payables,
+            msg.agent.as_ref(),
+            logger,
+        ) {
+            Ok(None) => Ok(Either::Left(OutboundPaymentsInstructions::new(
+                qualified_payables,
+                msg.agent,
+                msg.response_skeleton_opt,
+            ))),
+            Ok(Some(adjustment)) => {
+                let msg = BlockchainAgentWithContextMessage::new(
+                    self.protect_payables(qualified_payables),
                     msg.agent,
                     msg.response_skeleton_opt,
-                )))
+                );
+                Ok(Either::Right(PreparedAdjustment::new(msg, adjustment)))
             }
-            Ok(Some(adjustment)) => Ok(Either::Right(PreparedAdjustment::new(msg, adjustment))),
-            Err(_e) => todo!("be implemented with GH-711"),
+            Err(e) => Err(e.to_string()),
         }
     }
 
@@ -291,7 +305,7 @@ impl SolvencySensitivePaymentInstructor for PayableScanner {
         &self,
         setup: PreparedAdjustment,
         logger: &Logger,
-    ) -> OutboundPaymentsInstructions {
+    ) -> AdjustedPayments {
         let now = SystemTime::now();
         self.payment_adjuster.adjust_payments(setup, now, logger)
     }
@@ -305,6 +319,7 @@ impl PayableScanner {
         pending_payable_dao: Box<dyn PendingPayableDao>,
         payment_thresholds: Rc<PaymentThresholds>,
         payment_adjuster: Box<dyn PaymentAdjuster>,
+        prioritized_creditors: Rc<RefCell<HashSet<Wallet>>>,
     ) -> Self {
         Self {
             common: ScannerCommon::new(payment_thresholds),
@@ -312,9 +327,33 @@ impl PayableScanner {
             pending_payable_dao,
             payable_threshold_gauge: Box::new(PayableThresholdsGaugeReal::default()),
             payment_adjuster,
+            prioritized_creditors,
         }
     }
 
+    // A creditor that has refused us service won't serve us again until it's paid, so whatever
+    // we owe it is paid on this scan, whether or not the debt has crossed the thresholds yet.
+    fn separate_prioritized_payables(
+        &self,
+        non_pending_payables: Vec<PayableAccount>,
+        logger: &Logger,
+    ) -> (Vec<PayableAccount>, Vec<PayableAccount>) {
+        let prioritized_creditors = self.prioritized_creditors.replace(HashSet::new());
+        let (prioritized, others): (Vec<PayableAccount>, Vec<PayableAccount>) =
+            non_pending_payables.into_iter().partition(|account| {
+                account.balance_wei > 0 && prioritized_creditors.contains(&account.wallet)
+            });
+        prioritized.iter().for_each(|account| {
+            info!(
+                logger,
+                "Paying {} wei to {} ahead of the thresholds because it refused us service",
+                account.balance_wei,
+                account.wallet
+            )
+        });
+        (prioritized, others)
+    }
+
     fn sniff_out_alarming_payables_and_maybe_log_them(
         &self,
         non_pending_payables: Vec<PayableAccount>,
@@ -564,13 +603,14 @@ pub struct PendingPayableScanner {
     pub payable_dao: Box<dyn PayableDao>,
     pub pending_payable_dao: Box<dyn PendingPayableDao>,
     pub when_pending_too_long_sec: u64,
+    pub pending_scans_before_replacement: u16,
     pub financial_statistics: Rc<RefCell<FinancialStatistics>>,
 }
 
 impl Scanner<RequestTransactionReceipts, ReportTransactionReceipts> for PendingPayableScanner {
     fn begin_scan(
         &mut self,
-        _irrelevant_wallet: Wallet,
+        consuming_wallet: Wallet,
         timestamp: SystemTime,
         response_skeleton_opt: Option<ResponseSkeleton>,
         logger: &Logger,
@@ -592,8 +632,11 @@ impl Scanner<RequestTransactionReceipts, ReportTransactionReceipts> for PendingP
                     "Found {} pending payables to process",
                     filtered_pending_payable.len()
                 );
+                let stuck_transactions_opt =
+                    self.select_stuck_transactions(&filtered_pending_payable, consuming_wallet);
                 Ok(RequestTransactionReceipts {
                     pending_payable: filtered_pending_payable,
+                    stuck_transactions_opt,
                     response_skeleton_opt,
                 })
             }
@@ -638,6 +681,7 @@ impl PendingPayableScanner {
         pending_payable_dao: Box<dyn PendingPayableDao>,
         payment_thresholds: Rc<PaymentThresholds>,
         when_pending_too_long_sec: u64,
+        pending_scans_before_replacement: u16,
         financial_statistics: Rc<RefCell<FinancialStatistics>>,
     ) -> Self {
         Self {
@@ -645,10 +689,40 @@ impl PendingPayableScanner {
             payable_dao,
             pending_payable_dao,
             when_pending_too_long_sec,
+            pending_scans_before_replacement,
             financial_statistics,
         }
     }
 
+    fn select_stuck_transactions(
+        &self,
+        fingerprints: &[PendingPayableFingerprint],
+        consuming_wallet: Wallet,
+    ) -> Option<StuckTransactions> {
+        if self.pending_scans_before_replacement == 0 {
+            return None;
+        }
+        // Every replacement gets as many scans to be mined as the original got before it
+        let rowids = fingerprints
+            .iter()
+            .filter(|fingerprint| {
+                let replacements_so_far = fingerprint.replacement_hashes().len() as u32;
+                fingerprint.replaceable_opt.is_some()
+                    && u32::from(fingerprint.attempt)
+                        >= u32::from(self.pending_scans_before_replacement)
+                            * (replacements_so_far + 1)
+            })
+            .map(|fingerprint| fingerprint.rowid)
+            .collect::<Vec<u64>>();
+        match rowids.is_empty() {
+            true => None,
+            false => Some(StuckTransactions {
+                consuming_wallet,
+                rowids,
+            }),
+        }
+    }
+
     fn handle_receipts_for_pending_transactions(
         &self,
         msg: ReportTransactionReceipts,
@@ -708,7 +782,12 @@ impl PendingPayableScanner {
                 if code == WEB3_FAILURE {
                     handle_status_with_failure(scan_report, fingerprint, logger)
                 } else if code == WEB3_SUCCESS {
-                    handle_status_with_success(scan_report, fingerprint, logger)
+                    handle_status_with_success(
+                        scan_report,
+                        fingerprint,
+                        receipt.transaction_hash,
+                        logger,
+                    )
                 } else {
                     unreachable!(
                         "tx receipt for pending {:?}: status code other than 0 or 1 \
@@ -951,8 +1030,15 @@ impl ReceivableScanner {
             .new_delinquencies(timestamp, self.common.payment_thresholds.as_ref())
             .into_iter()
             .for_each(|account| {
-                self.banned_dao.ban(&account.wallet);
                 let (balance_str_wei, age) = balance_and_age(timestamp, &account);
+                self.banned_dao.ban(
+                    &account.wallet,
+                    &BanReason::Delinquency {
+                        balance_gwei_opt: Some(wei_to_gwei::<i64, i128>(account.balance_wei)),
+                        debt_age_sec_opt: Some(age.as_secs()),
+                    },
+                    timestamp,
+                );
                 info!(
                     logger,
                     "Wallet {} (balance: {} gwei, age: {} sec) banned for delinquency",
@@ -1099,10 +1185,13 @@ impl<T: Default + 'static> ScanScheduler for PeriodicalScanScheduler<T> {
 }
 #[cfg(test)]
 mod tests {
+    use crate::accountant::db_access_objects::banned_dao::BanReason;
     use crate::accountant::db_access_objects::payable_dao::{PayableAccount, PayableDaoError};
     use crate::accountant::db_access_objects::pending_payable_dao::{
-        PendingPayable, PendingPayableDaoError, TransactionHashes,
+        PendingPayable, PendingPayableDao, PendingPayableDaoError, PendingPayableDaoReal,
+        TransactionHashes,
     };
+    use crate::accountant::db_access_objects::receivable_dao::ReceivableAccount;
     use crate::accountant::db_access_objects::utils::{from_time_t, to_time_t};
     use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::QualifiedPayablesMessage;
     use crate::accountant::scanners::scanners_utils::payable_scanner_utils::PendingPayableMetadata;
@@ -1122,14 +1211,20 @@ mod tests {
     };
     use crate::accountant::{
         gwei_to_wei, PendingPayableId, ReceivedPayments, ReportTransactionReceipts,
-        RequestTransactionReceipts, SentPayables, DEFAULT_PENDING_TOO_LONG_SEC,
+        RequestTransactionReceipts, SentPayables, StuckTransactions, DEFAULT_PENDING_TOO_LONG_SEC,
+    };
+    use crate::blockchain::blockchain_bridge::{
+        PendingPayableFingerprint, ReplaceableTransaction, RetrieveTransactions,
+        TransactionReplacement,
     };
-    use crate::blockchain::blockchain_bridge::{PendingPayableFingerprint, RetrieveTransactions};
     use crate::blockchain::blockchain_interface::data_structures::errors::PayableTransactionError;
     use crate::blockchain::blockchain_interface::data_structures::{
         BlockchainTransaction, RpcPayablesFailure,
     };
     use crate::blockchain::test_utils::make_tx_hash;
+    use crate::database::db_initializer::{
+        DbInitializationConfig, DbInitializer, DbInitializerReal,
+    };
     use crate::database::rusqlite_wrappers::TransactionSafeWrapper;
     use crate::database::test_utils::transaction_wrapper_mock::TransactionInnerWrapperMockBuilder;
     use crate::db_config::mocks::ConfigDaoMock;
@@ -1138,6 +1233,7 @@ mod tests {
         DaoFactories, FinancialStatistics, PaymentThresholds, ScanIntervals,
         DEFAULT_PAYMENT_THRESHOLDS,
     };
+    use crate::sub_lib::blockchain_bridge::TransactionFees;
     use crate::test_utils::persistent_configuration_mock::PersistentConfigurationMock;
     use crate::test_utils::unshared_test_utils::arbitrary_id_stamp::ArbitraryIdStamp;
     use crate::test_utils::{make_paying_wallet, make_wallet};
@@ -1146,6 +1242,7 @@ mod tests {
     use masq_lib::logger::Logger;
     use masq_lib::messages::ScanType;
     use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
     use regex::Regex;
     use rusqlite::{ffi, ErrorCode};
     use std::cell::RefCell;
@@ -1175,6 +1272,7 @@ mod tests {
             .set_result(Ok(()));
         let config_dao_factory = ConfigDaoFactoryMock::new().make_result(config_dao_mock);
         let when_pending_too_long_sec = 1234;
+        let pending_scans_before_replacement = 9;
         let financial_statistics = FinancialStatistics {
             total_paid_payable_wei: 1,
             total_paid_receivable_wei: 2,
@@ -1193,7 +1291,9 @@ mod tests {
             },
             Rc::clone(&payment_thresholds_rc),
             when_pending_too_long_sec,
+            pending_scans_before_replacement,
             Rc::new(RefCell::new(financial_statistics.clone())),
+            Rc::new(RefCell::new(HashSet::new())),
         );
 
         let payable_scanner = scanners
@@ -1220,6 +1320,10 @@ mod tests {
             pending_payable_scanner.when_pending_too_long_sec,
             when_pending_too_long_sec
         );
+        assert_eq!(
+            pending_payable_scanner.pending_scans_before_replacement,
+            pending_scans_before_replacement
+        );
         assert_eq!(
             *pending_payable_scanner.financial_statistics.borrow(),
             financial_statistics
@@ -1252,7 +1356,7 @@ mod tests {
         );
         assert_eq!(
             Rc::strong_count(&payment_thresholds_rc),
-            initial_rc_count + 3
+            initial_rc_count + 4
         );
     }
 
@@ -1306,6 +1410,63 @@ mod tests {
         ])
     }
 
+    #[test]
+    fn payable_scanner_pays_prioritized_creditors_first_regardless_of_thresholds_and_only_once() {
+        init_test_logging();
+        let test_name = "payable_scanner_pays_prioritized_creditors_first_regardless_of_thresholds_and_only_once";
+        let consuming_wallet = make_paying_wallet(b"consuming wallet");
+        let now = SystemTime::now();
+        let (qualified_payable_accounts, unqualified_payable_accounts, _) =
+            make_payables(now, &PaymentThresholds::default());
+        let refusing_creditor = unqualified_payable_accounts[0].clone();
+        let all_non_pending_payables = qualified_payable_accounts
+            .iter()
+            .chain(unqualified_payable_accounts.iter())
+            .cloned()
+            .collect::<Vec<PayableAccount>>();
+        let payable_dao = PayableDaoMock::new()
+            .non_pending_payables_result(all_non_pending_payables.clone())
+            .non_pending_payables_result(all_non_pending_payables);
+        let prioritized_creditors = Rc::new(RefCell::new(HashSet::new()));
+        prioritized_creditors
+            .borrow_mut()
+            .insert(refusing_creditor.wallet.clone());
+        let mut subject = PayableScannerBuilder::new()
+            .payable_dao(payable_dao)
+            .prioritized_creditors(&prioritized_creditors)
+            .build();
+
+        let first_result =
+            subject.begin_scan(consuming_wallet.clone(), now, None, &Logger::new(test_name));
+        subject.mark_as_ended(&Logger::new(test_name));
+        let second_result =
+            subject.begin_scan(consuming_wallet.clone(), now, None, &Logger::new(test_name));
+
+        let mut expected_first_payables = vec![refusing_creditor.clone()];
+        expected_first_payables.extend(qualified_payable_accounts.clone());
+        assert_eq!(
+            first_result,
+            Ok(QualifiedPayablesMessage {
+                protected_qualified_payables: protect_payables_in_test(expected_first_payables),
+                consuming_wallet: consuming_wallet.clone(),
+                response_skeleton_opt: None,
+            })
+        );
+        assert_eq!(
+            second_result,
+            Ok(QualifiedPayablesMessage {
+                protected_qualified_payables: protect_payables_in_test(qualified_payable_accounts),
+                consuming_wallet,
+                response_skeleton_opt: None,
+            })
+        );
+        assert!(prioritized_creditors.borrow().is_empty());
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: {test_name}: Paying {} wei to {} ahead of the thresholds because it refused us service",
+            refusing_creditor.balance_wei, refusing_creditor.wallet
+        ));
+    }
+
     #[test]
     fn payable_scanner_throws_error_when_a_scan_is_already_running() {
         let consuming_wallet = make_paying_wallet(b"consuming wallet");
@@ -2227,6 +2388,7 @@ mod tests {
             attempt: 1,
             amount: 4444,
             process_error: None,
+            replaceable_opt: None,
         };
         let payable_fingerprint_2 = PendingPayableFingerprint {
             rowid: 550,
@@ -2235,6 +2397,7 @@ mod tests {
             attempt: 1,
             amount: 7999,
             process_error: None,
+            replaceable_opt: None,
         };
         let fingerprints = vec![payable_fingerprint_1, payable_fingerprint_2];
         let pending_payable_dao = PendingPayableDaoMock::new()
@@ -2257,6 +2420,7 @@ mod tests {
             result,
             Ok(RequestTransactionReceipts {
                 pending_payable: fingerprints,
+                stuck_transactions_opt: None,
                 response_skeleton_opt: None
             })
         );
@@ -2280,6 +2444,7 @@ mod tests {
                 attempt: 1,
                 amount: 1_000_000,
                 process_error: None,
+                replaceable_opt: None,
             }]);
         let mut subject = PendingPayableScannerBuilder::new()
             .pending_payable_dao(pending_payable_dao)
@@ -2312,6 +2477,102 @@ mod tests {
         assert_eq!(is_scan_running, false);
     }
 
+    fn make_replaceable_fingerprint(
+        rowid: u64,
+        attempt: u16,
+        replacement_hashes: Vec<H256>,
+    ) -> PendingPayableFingerprint {
+        PendingPayableFingerprint {
+            rowid,
+            timestamp: from_time_t(210_000_000),
+            hash: make_tx_hash(rowid as u32),
+            attempt,
+            amount: 5555,
+            process_error: None,
+            replaceable_opt: Some(ReplaceableTransaction {
+                recipient_wallet: make_wallet("creditor"),
+                nonce: rowid,
+                transaction_fees: TransactionFees::Legacy {
+                    gas_price_wei: 1_000_000_000,
+                },
+                replacement_hashes,
+            }),
+        }
+    }
+
+    #[test]
+    fn pending_payable_scanner_picks_transactions_pending_too_many_scans_for_replacement() {
+        let consuming_wallet = make_paying_wallet(b"consuming");
+        let too_young = make_replaceable_fingerprint(1, 2, vec![]);
+        let stuck_1 = make_replaceable_fingerprint(2, 3, vec![]);
+        let replaced_lately = make_replaceable_fingerprint(3, 5, vec![make_tx_hash(0x333)]);
+        let mut without_signing_details = make_replaceable_fingerprint(4, 5, vec![]);
+        without_signing_details.replaceable_opt = None;
+        let stuck_2 = make_replaceable_fingerprint(5, 4, vec![]);
+        let replaced_but_stuck_again =
+            make_replaceable_fingerprint(6, 6, vec![make_tx_hash(0x666)]);
+        let fingerprints = vec![
+            too_young,
+            stuck_1,
+            replaced_lately,
+            without_signing_details,
+            stuck_2,
+            replaced_but_stuck_again,
+        ];
+        let pending_payable_dao = PendingPayableDaoMock::new()
+            .return_all_errorless_fingerprints_result(fingerprints.clone());
+        let mut subject = PendingPayableScannerBuilder::new()
+            .pending_payable_dao(pending_payable_dao)
+            .pending_scans_before_replacement(3)
+            .build();
+
+        let result = subject.begin_scan(
+            consuming_wallet.clone(),
+            SystemTime::now(),
+            None,
+            &Logger::new("test"),
+        );
+
+        assert_eq!(
+            result,
+            Ok(RequestTransactionReceipts {
+                pending_payable: fingerprints,
+                stuck_transactions_opt: Some(StuckTransactions {
+                    consuming_wallet,
+                    rowids: vec![2, 5, 6]
+                }),
+                response_skeleton_opt: None
+            })
+        );
+    }
+
+    #[test]
+    fn pending_payable_scanner_picks_nothing_for_replacement_if_it_is_disabled() {
+        let fingerprints = vec![make_replaceable_fingerprint(1, 50, vec![])];
+        let pending_payable_dao = PendingPayableDaoMock::new()
+            .return_all_errorless_fingerprints_result(fingerprints.clone());
+        let mut subject = PendingPayableScannerBuilder::new()
+            .pending_payable_dao(pending_payable_dao)
+            .pending_scans_before_replacement(0)
+            .build();
+
+        let result = subject.begin_scan(
+            make_paying_wallet(b"consuming"),
+            SystemTime::now(),
+            None,
+            &Logger::new("test"),
+        );
+
+        assert_eq!(
+            result,
+            Ok(RequestTransactionReceipts {
+                pending_payable: fingerprints,
+                stuck_transactions_opt: None,
+                response_skeleton_opt: None
+            })
+        );
+    }
+
     fn assert_interpreting_none_status_for_pending_payable(
         test_name: &str,
         when_pending_too_long_sec: u64,
@@ -2332,6 +2593,7 @@ mod tests {
             attempt: 1,
             amount: 123,
             process_error: None,
+            replaceable_opt: None,
         };
         let logger = Logger::new(test_name);
         let scan_report = PendingPayableScanReport::default();
@@ -2464,6 +2726,72 @@ mod tests {
         ), elapsed_after_ms, capture_regex);
     }
 
+    #[test]
+    fn replacement_sent_shortly_before_the_original_would_time_out_gets_its_own_pending_time() {
+        let home_dir = ensure_node_home_directory_exists(
+            "pending_payable_scanner",
+            "replacement_sent_shortly_before_the_original_would_time_out_gets_its_own_pending_time",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let pending_payable_dao = PendingPayableDaoReal::new(conn);
+        let now = SystemTime::now();
+        let original_hash = make_tx_hash(0x456);
+        let replacement_hash = make_tx_hash(0x789);
+        pending_payable_dao
+            .insert_new_fingerprints(
+                &[(original_hash, 5555)],
+                now.sub(Duration::from_secs(DEFAULT_PENDING_TOO_LONG_SEC + 60)),
+            )
+            .unwrap();
+        pending_payable_dao
+            .record_replaceable_transactions(&[(
+                original_hash,
+                ReplaceableTransaction {
+                    recipient_wallet: make_wallet("creditor"),
+                    nonce: 4,
+                    transaction_fees: TransactionFees::Legacy {
+                        gas_price_wei: 1_000_000_000,
+                    },
+                    replacement_hashes: vec![],
+                },
+            )])
+            .unwrap();
+        pending_payable_dao
+            .record_replacement(&TransactionReplacement {
+                rowid: 1,
+                hash: replacement_hash,
+                transaction_fees: TransactionFees::Legacy {
+                    gas_price_wei: 1_125_000_000,
+                },
+                timestamp: now.sub(Duration::from_secs(120)),
+            })
+            .unwrap();
+        let fingerprint = pending_payable_dao
+            .return_all_errorless_fingerprints()
+            .remove(0);
+        let subject = PendingPayableScannerBuilder::new()
+            .when_pending_too_long_sec(DEFAULT_PENDING_TOO_LONG_SEC)
+            .build();
+
+        let result = subject.interpret_transaction_receipt(
+            PendingPayableScanReport::default(),
+            &TransactionReceipt::default(),
+            fingerprint,
+            &Logger::new("test"),
+        );
+
+        assert_eq!(
+            result,
+            PendingPayableScanReport {
+                still_pending: vec![PendingPayableId::new(1, original_hash)],
+                failures: vec![],
+                confirmed: vec![]
+            }
+        );
+    }
+
     #[test]
     #[should_panic(
         expected = "tx receipt for pending 0x000000000000000000000000000000000000000000000000000000000000007b: \
@@ -2497,6 +2825,7 @@ mod tests {
             attempt: 5,
             amount: 2222,
             process_error: None,
+            replaceable_opt: None,
         };
         let logger = Logger::new(test_name);
         let scan_report = PendingPayableScanReport::default();
@@ -2519,6 +2848,73 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn interpret_transaction_receipt_when_the_replacement_was_mined() {
+        init_test_logging();
+        let test_name = "interpret_transaction_receipt_when_the_replacement_was_mined";
+        let subject = PendingPayableScannerBuilder::new().build();
+        let replacement_hash = make_tx_hash(0x4d2);
+        let mut tx_receipt = TransactionReceipt::default();
+        tx_receipt.status = Some(U64::from(1)); //success
+        tx_receipt.transaction_hash = replacement_hash;
+        // Not the latest of the replacements, but any of them can be the one that gets mined
+        let fingerprint =
+            make_replaceable_fingerprint(0x10e1, 8, vec![replacement_hash, make_tx_hash(0x4d4)]);
+        let logger = Logger::new(test_name);
+        let scan_report = PendingPayableScanReport::default();
+
+        let result = subject.interpret_transaction_receipt(
+            scan_report,
+            &tx_receipt,
+            fingerprint.clone(),
+            &logger,
+        );
+
+        assert_eq!(
+            result,
+            PendingPayableScanReport {
+                still_pending: vec![],
+                failures: vec![],
+                confirmed: vec![PendingPayableFingerprint {
+                    hash: replacement_hash,
+                    ..fingerprint
+                }]
+            }
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: {test_name}: Transaction 0x0000000000000000000000000000000000000000000000000000\
+            0000000004d2, replacing the stuck 0x000000000000000000000000000000000000000000000000000\
+            00000000010e1, has been added to the blockchain; detected locally at attempt 8"
+        ));
+    }
+
+    #[test]
+    fn interpret_transaction_receipt_when_the_original_was_mined_despite_a_replacement() {
+        let subject = PendingPayableScannerBuilder::new().build();
+        let original_hash = make_tx_hash(0x10e2);
+        let mut tx_receipt = TransactionReceipt::default();
+        tx_receipt.status = Some(U64::from(1)); //success
+        tx_receipt.transaction_hash = original_hash;
+        let fingerprint = make_replaceable_fingerprint(0x10e2, 8, vec![make_tx_hash(0x4d3)]);
+        let scan_report = PendingPayableScanReport::default();
+
+        let result = subject.interpret_transaction_receipt(
+            scan_report,
+            &tx_receipt,
+            fingerprint.clone(),
+            &Logger::new("test"),
+        );
+
+        assert_eq!(
+            result,
+            PendingPayableScanReport {
+                still_pending: vec![],
+                failures: vec![],
+                confirmed: vec![fingerprint]
+            }
+        );
+    }
+
     #[test]
     fn handle_pending_txs_with_receipts_handles_none_for_receipt() {
         init_test_logging();
@@ -2534,6 +2930,7 @@ mod tests {
             attempt: 3,
             amount: 111,
             process_error: None,
+            replaceable_opt: None,
         };
         let msg = ReportTransactionReceipts {
             fingerprints_with_receipts: vec![(tx_receipt_opt, fingerprint.clone())],
@@ -2734,6 +3131,7 @@ mod tests {
             attempt: 1,
             amount: 4567,
             process_error: None,
+            replaceable_opt: None,
         };
         let pending_payable_fingerprint_2 = PendingPayableFingerprint {
             rowid: rowid_2,
@@ -2742,6 +3140,7 @@ mod tests {
             attempt: 1,
             amount: 5555,
             process_error: None,
+            replaceable_opt: None,
         };
 
         subject.confirm_transactions(
@@ -2811,6 +3210,7 @@ mod tests {
             attempt: 1,
             amount: 5478,
             process_error: None,
+            replaceable_opt: None,
         };
         let fingerprint_2 = PendingPayableFingerprint {
             rowid: 6,
@@ -2819,6 +3219,7 @@ mod tests {
             attempt: 1,
             amount: 6543,
             process_error: None,
+            replaceable_opt: None,
         };
         let payable_dao = PayableDaoMock::default().transactions_confirmed_result(Ok(()));
         let pending_payable_dao =
@@ -2864,6 +3265,7 @@ mod tests {
             attempt: 2,
             amount: 444,
             process_error: None,
+            replaceable_opt: None,
         };
         let transaction_hash_2 = make_tx_hash(1234);
         let mut transaction_receipt_2 = TransactionReceipt::default();
@@ -2876,6 +3278,7 @@ mod tests {
             attempt: 15,
             amount: 1212,
             process_error: None,
+            replaceable_opt: None,
         };
         let msg = ReportTransactionReceipts {
             fingerprints_with_receipts: vec![
@@ -3037,8 +3440,22 @@ mod tests {
         assert_eq!(paid_delinquencies_parameters.len(), 1);
         assert_eq!(payment_thresholds, paid_delinquencies_parameters[0]);
         let ban_parameters = ban_parameters_arc.lock().unwrap();
-        assert!(ban_parameters.contains(&newly_banned_1.wallet));
-        assert!(ban_parameters.contains(&newly_banned_2.wallet));
+        let expected_ban = |account: &ReceivableAccount, balance_gwei: i64| {
+            (
+                account.wallet.clone(),
+                BanReason::Delinquency {
+                    balance_gwei_opt: Some(balance_gwei),
+                    debt_age_sec_opt: Some(
+                        now.duration_since(account.last_received_timestamp)
+                            .unwrap()
+                            .as_secs(),
+                    ),
+                },
+                now,
+            )
+        };
+        assert!(ban_parameters.contains(&expected_ban(&newly_banned_1, 1234)));
+        assert!(ban_parameters.contains(&expected_ban(&newly_banned_2, 2345)));
         assert_eq!(2, ban_parameters.len());
         let unban_parameters = unban_parameters_arc.lock().unwrap();
         assert!(unban_parameters.contains(&newly_unbanned_1.wallet));
@@ -311,6 +311,7 @@ pub mod pending_payable_scanner_utils {
     use crate::blockchain::blockchain_bridge::PendingPayableFingerprint;
     use masq_lib::logger::Logger;
     use std::time::SystemTime;
+    use web3::types::H256;
 
     #[derive(Debug, Default, PartialEq, Eq, Clone)]
     pub struct PendingPayableScanReport {
@@ -366,17 +367,32 @@ pub mod pending_payable_scanner_utils {
 
     pub fn handle_status_with_success(
         mut scan_report: PendingPayableScanReport,
-        fingerprint: PendingPayableFingerprint,
+        mut fingerprint: PendingPayableFingerprint,
+        mined_hash: H256,
         logger: &Logger,
     ) -> PendingPayableScanReport {
-        info!(
-            logger,
-            "Transaction {:?} has been added to the blockchain; detected locally at attempt \
-            {} at {}ms after its sending",
-            fingerprint.hash,
-            fingerprint.attempt,
-            elapsed_in_ms(fingerprint.timestamp)
-        );
+        if fingerprint.replacement_hashes().contains(&mined_hash) {
+            info!(
+                logger,
+                "Transaction {:?}, replacing the stuck {:?}, has been added to the blockchain; \
+                detected locally at attempt {} at {}ms after the latest sending",
+                mined_hash,
+                fingerprint.hash,
+                fingerprint.attempt,
+                elapsed_in_ms(fingerprint.timestamp)
+            );
+            // the payable is settled by the rowid, no matter which of them got mined
+            fingerprint.hash = mined_hash
+        } else {
+            info!(
+                logger,
+                "Transaction {:?} has been added to the blockchain; detected locally at attempt \
+                {} at {}ms after its sending",
+                fingerprint.hash,
+                fingerprint.attempt,
+                elapsed_in_ms(fingerprint.timestamp)
+            );
+        }
         scan_report.confirmed.push(fingerprint);
         scan_report
     }
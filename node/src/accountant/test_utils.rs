@@ -2,7 +2,9 @@
 
 #![cfg(test)]
 
-use crate::accountant::db_access_objects::banned_dao::{BannedDao, BannedDaoFactory};
+use crate::accountant::db_access_objects::banned_dao::{
+    BanReason, BannedAccount, BannedDao, BannedDaoFactory,
+};
 use crate::accountant::db_access_objects::payable_dao::{
     PayableAccount, PayableDao, PayableDaoError, PayableDaoFactory,
 };
@@ -13,7 +15,10 @@ use crate::accountant::db_access_objects::receivable_dao::{
     ReceivableAccount, ReceivableDao, ReceivableDaoError, ReceivableDaoFactory,
 };
 use crate::accountant::db_access_objects::utils::{from_time_t, to_time_t, CustomQuery};
-use crate::accountant::payment_adjuster::{Adjustment, AnalysisError, PaymentAdjuster};
+use crate::accountant::payment_adjuster::{
+    AdjustedPayments, Adjustment, AnalysisError, PaymentAdjuster,
+};
+use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::{
     BlockchainAgentWithContextMessage, QualifiedPayablesMessage,
 };
@@ -26,9 +31,12 @@ use crate::accountant::scanners::{
     ReceivableScanner, ScanSchedulers, Scanner,
 };
 use crate::accountant::{
-    gwei_to_wei, Accountant, ResponseSkeleton, SentPayables, DEFAULT_PENDING_TOO_LONG_SEC,
+    gwei_to_wei, Accountant, ResponseSkeleton, SentPayables,
+    DEFAULT_PENDING_SCANS_BEFORE_REPLACEMENT, DEFAULT_PENDING_TOO_LONG_SEC,
+};
+use crate::blockchain::blockchain_bridge::{
+    PendingPayableFingerprint, ReplaceableTransaction, TransactionReplacement,
 };
-use crate::blockchain::blockchain_bridge::PendingPayableFingerprint;
 use crate::blockchain::blockchain_interface::data_structures::BlockchainTransaction;
 use crate::blockchain::test_utils::make_tx_hash;
 use crate::bootstrapper::BootstrapperConfig;
@@ -42,6 +50,7 @@ use crate::sub_lib::utils::NotifyLaterHandle;
 use crate::sub_lib::wallet::Wallet;
 use crate::test_utils::make_wallet;
 use crate::test_utils::persistent_configuration_mock::PersistentConfigurationMock;
+use crate::test_utils::unshared_test_utils::arbitrary_id_stamp::ArbitraryIdStamp;
 use crate::test_utils::unshared_test_utils::make_bc_with_defaults;
 use actix::{Message, System};
 use ethereum_types::H256;
@@ -52,6 +61,7 @@ use masq_lib::ui_gateway::NodeToUiMessage;
 use rusqlite::{Connection, OpenFlags, Row};
 use std::any::type_name;
 use std::cell::RefCell;
+use std::collections::HashSet;
 use std::fmt::Debug;
 use std::path::Path;
 use std::rc::Rc;
@@ -262,6 +272,11 @@ const RECEIVABLE_DAOS_ACCOUNTANT_INITIALIZATION_ORDER: [DestinationMarker; 2] =
     DestinationMarker::ReceivableScanner,
 ];
 
+const BANNED_DAOS_ACCOUNTANT_INITIALIZATION_ORDER: [DestinationMarker; 2] = [
+    DestinationMarker::AccountantBody,
+    DestinationMarker::ReceivableScanner,
+];
+
 impl AccountantBuilder {
     pub fn bootstrapper_config(mut self, config: BootstrapperConfig) -> Self {
         self.config_opt = Some(config);
@@ -320,18 +335,18 @@ impl AccountantBuilder {
         )
     }
 
-    //TODO this method seems to be never used?
-    pub fn banned_dao(mut self, banned_dao: BannedDaoMock) -> Self {
-        match self.banned_dao_factory_opt {
-            None => {
-                self.banned_dao_factory_opt =
-                    Some(BannedDaoFactoryMock::new().make_result(banned_dao))
-            }
-            Some(banned_dao_factory) => {
-                self.banned_dao_factory_opt = Some(banned_dao_factory.make_result(banned_dao))
-            }
-        }
-        self
+    pub fn banned_daos(
+        mut self,
+        specially_configured_daos: Vec<DaoWithDestination<BannedDaoMock>>,
+    ) -> Self {
+        create_or_update_factory!(
+            specially_configured_daos,
+            BANNED_DAOS_ACCOUNTANT_INITIALIZATION_ORDER,
+            banned_dao_factory_opt,
+            BannedDaoFactoryMock,
+            BannedDao,
+            self
+        )
     }
 
     pub fn config_dao(mut self, config_dao: ConfigDaoMock) -> Self {
@@ -358,9 +373,11 @@ impl AccountantBuilder {
                 .make_result(PendingPayableDaoMock::new())
                 .make_result(PendingPayableDaoMock::new()),
         );
-        let banned_dao_factory = self
-            .banned_dao_factory_opt
-            .unwrap_or(BannedDaoFactoryMock::new().make_result(BannedDaoMock::new()));
+        let banned_dao_factory = self.banned_dao_factory_opt.unwrap_or(
+            BannedDaoFactoryMock::new()
+                .make_result(BannedDaoMock::new())
+                .make_result(BannedDaoMock::new()),
+        );
         let config_dao_factory = self
             .config_dao_factory_opt
             .unwrap_or(ConfigDaoFactoryMock::new().make_result(ConfigDaoMock::new()));
@@ -826,24 +843,43 @@ impl ReceivableDaoMock {
 #[derive(Debug, Default)]
 pub struct BannedDaoMock {
     ban_list_parameters: Arc<Mutex<Vec<()>>>,
-    ban_list_results: RefCell<Vec<Vec<Wallet>>>,
-    ban_parameters: Arc<Mutex<Vec<Wallet>>>,
+    ban_list_results: RefCell<Vec<Vec<BannedAccount>>>,
+    ban_parameters: Arc<Mutex<Vec<(Wallet, BanReason, SystemTime)>>>,
     unban_parameters: Arc<Mutex<Vec<Wallet>>>,
+    unban_by_hand_parameters: Arc<Mutex<Vec<(Wallet, Option<String>, SystemTime)>>>,
+    unban_by_hand_results: RefCell<Vec<bool>>,
 }
 
 impl BannedDao for BannedDaoMock {
-    fn ban_list(&self) -> Vec<Wallet> {
+    fn ban_list(&self) -> Vec<BannedAccount> {
         self.ban_list_parameters.lock().unwrap().push(());
         self.ban_list_results.borrow_mut().remove(0)
     }
 
-    fn ban(&self, wallet: &Wallet) {
-        self.ban_parameters.lock().unwrap().push(wallet.clone());
+    fn ban(&self, wallet: &Wallet, reason: &BanReason, timestamp: SystemTime) {
+        self.ban_parameters
+            .lock()
+            .unwrap()
+            .push((wallet.clone(), reason.clone(), timestamp));
     }
 
     fn unban(&self, wallet: &Wallet) {
         self.unban_parameters.lock().unwrap().push(wallet.clone());
     }
+
+    fn unban_by_hand(
+        &self,
+        wallet: &Wallet,
+        note_opt: Option<&str>,
+        timestamp: SystemTime,
+    ) -> bool {
+        self.unban_by_hand_parameters.lock().unwrap().push((
+            wallet.clone(),
+            note_opt.map(|note| note.to_string()),
+            timestamp,
+        ));
+        self.unban_by_hand_results.borrow_mut().remove(0)
+    }
 }
 
 impl BannedDaoMock {
@@ -853,15 +889,25 @@ impl BannedDaoMock {
             ban_list_results: RefCell::new(vec![]),
             ban_parameters: Arc::new(Mutex::new(vec![])),
             unban_parameters: Arc::new(Mutex::new(vec![])),
+            unban_by_hand_parameters: Arc::new(Mutex::new(vec![])),
+            unban_by_hand_results: RefCell::new(vec![]),
         }
     }
 
-    pub fn ban_list_result(self, result: Vec<Wallet>) -> Self {
+    pub fn ban_list_params(mut self, params: &Arc<Mutex<Vec<()>>>) -> Self {
+        self.ban_list_parameters = params.clone();
+        self
+    }
+
+    pub fn ban_list_result(self, result: Vec<BannedAccount>) -> Self {
         self.ban_list_results.borrow_mut().push(result);
         self
     }
 
-    pub fn ban_parameters(mut self, parameters: &Arc<Mutex<Vec<Wallet>>>) -> Self {
+    pub fn ban_parameters(
+        mut self,
+        parameters: &Arc<Mutex<Vec<(Wallet, BanReason, SystemTime)>>>,
+    ) -> Self {
         self.ban_parameters = parameters.clone();
         self
     }
@@ -870,6 +916,19 @@ impl BannedDaoMock {
         self.unban_parameters = parameters.clone();
         self
     }
+
+    pub fn unban_by_hand_parameters(
+        mut self,
+        parameters: &Arc<Mutex<Vec<(Wallet, Option<String>, SystemTime)>>>,
+    ) -> Self {
+        self.unban_by_hand_parameters = parameters.clone();
+        self
+    }
+
+    pub fn unban_by_hand_result(self, result: bool) -> Self {
+        self.unban_by_hand_results.borrow_mut().push(result);
+        self
+    }
 }
 
 pub fn bc_from_earning_wallet(earning_wallet: Wallet) -> BootstrapperConfig {
@@ -893,6 +952,10 @@ pub struct PendingPayableDaoMock {
     delete_fingerprints_results: RefCell<Vec<Result<(), PendingPayableDaoError>>>,
     insert_new_fingerprints_params: Arc<Mutex<Vec<(Vec<(H256, u128)>, SystemTime)>>>,
     insert_new_fingerprints_results: RefCell<Vec<Result<(), PendingPayableDaoError>>>,
+    record_replaceable_transactions_params: Arc<Mutex<Vec<Vec<(H256, ReplaceableTransaction)>>>>,
+    record_replaceable_transactions_results: RefCell<Vec<Result<(), PendingPayableDaoError>>>,
+    record_replacement_params: Arc<Mutex<Vec<TransactionReplacement>>>,
+    record_replacement_results: RefCell<Vec<Result<(), PendingPayableDaoError>>>,
     increment_scan_attempts_params: Arc<Mutex<Vec<Vec<u64>>>>,
     increment_scan_attempts_result: RefCell<Vec<Result<(), PendingPayableDaoError>>>,
     mark_failures_params: Arc<Mutex<Vec<Vec<u64>>>>,
@@ -942,6 +1005,30 @@ impl PendingPayableDao for PendingPayableDaoMock {
         self.insert_new_fingerprints_results.borrow_mut().remove(0)
     }
 
+    fn record_replaceable_transactions(
+        &self,
+        replaceable_transactions: &[(H256, ReplaceableTransaction)],
+    ) -> Result<(), PendingPayableDaoError> {
+        self.record_replaceable_transactions_params
+            .lock()
+            .unwrap()
+            .push(replaceable_transactions.to_vec());
+        self.record_replaceable_transactions_results
+            .borrow_mut()
+            .remove(0)
+    }
+
+    fn record_replacement(
+        &self,
+        replacement: &TransactionReplacement,
+    ) -> Result<(), PendingPayableDaoError> {
+        self.record_replacement_params
+            .lock()
+            .unwrap()
+            .push(replacement.clone());
+        self.record_replacement_results.borrow_mut().remove(0)
+    }
+
     fn delete_fingerprints(&self, ids: &[u64]) -> Result<(), PendingPayableDaoError> {
         self.delete_fingerprints_params
             .lock()
@@ -994,6 +1081,37 @@ impl PendingPayableDaoMock {
         self
     }
 
+    pub fn record_replaceable_transactions_params(
+        mut self,
+        params: &Arc<Mutex<Vec<Vec<(H256, ReplaceableTransaction)>>>>,
+    ) -> Self {
+        self.record_replaceable_transactions_params = params.clone();
+        self
+    }
+
+    pub fn record_replaceable_transactions_result(
+        self,
+        result: Result<(), PendingPayableDaoError>,
+    ) -> Self {
+        self.record_replaceable_transactions_results
+            .borrow_mut()
+            .push(result);
+        self
+    }
+
+    pub fn record_replacement_params(
+        mut self,
+        params: &Arc<Mutex<Vec<TransactionReplacement>>>,
+    ) -> Self {
+        self.record_replacement_params = params.clone();
+        self
+    }
+
+    pub fn record_replacement_result(self, result: Result<(), PendingPayableDaoError>) -> Self {
+        self.record_replacement_results.borrow_mut().push(result);
+        self
+    }
+
     pub fn delete_fingerprints_params(mut self, params: &Arc<Mutex<Vec<Vec<u64>>>>) -> Self {
         self.delete_fingerprints_params = params.clone();
         self
@@ -1089,6 +1207,7 @@ pub struct PayableScannerBuilder {
     pending_payable_dao: PendingPayableDaoMock,
     payment_thresholds: PaymentThresholds,
     payment_adjuster: PaymentAdjusterMock,
+    prioritized_creditors: Rc<RefCell<HashSet<Wallet>>>,
 }
 
 impl PayableScannerBuilder {
@@ -1098,9 +1217,18 @@ impl PayableScannerBuilder {
             pending_payable_dao: PendingPayableDaoMock::new(),
             payment_thresholds: PaymentThresholds::default(),
             payment_adjuster: PaymentAdjusterMock::default(),
+            prioritized_creditors: Rc::new(RefCell::new(HashSet::new())),
         }
     }
 
+    pub fn prioritized_creditors(
+        mut self,
+        prioritized_creditors: &Rc<RefCell<HashSet<Wallet>>>,
+    ) -> PayableScannerBuilder {
+        self.prioritized_creditors = Rc::clone(prioritized_creditors);
+        self
+    }
+
     pub fn payable_dao(mut self, payable_dao: PayableDaoMock) -> PayableScannerBuilder {
         self.payable_dao = payable_dao;
         self
@@ -1133,6 +1261,7 @@ impl PayableScannerBuilder {
             Box::new(self.pending_payable_dao),
             Rc::new(self.payment_thresholds),
             Box::new(self.payment_adjuster),
+            self.prioritized_creditors,
         )
     }
 }
@@ -1142,6 +1271,7 @@ pub struct PendingPayableScannerBuilder {
     pending_payable_dao: PendingPayableDaoMock,
     payment_thresholds: PaymentThresholds,
     when_pending_too_long_sec: u64,
+    pending_scans_before_replacement: u16,
     financial_statistics: FinancialStatistics,
 }
 
@@ -1152,6 +1282,7 @@ impl PendingPayableScannerBuilder {
             pending_payable_dao: PendingPayableDaoMock::new(),
             payment_thresholds: PaymentThresholds::default(),
             when_pending_too_long_sec: DEFAULT_PENDING_TOO_LONG_SEC,
+            pending_scans_before_replacement: DEFAULT_PENDING_SCANS_BEFORE_REPLACEMENT,
             financial_statistics: FinancialStatistics::default(),
         }
     }
@@ -1171,12 +1302,18 @@ impl PendingPayableScannerBuilder {
         self
     }
 
+    pub fn pending_scans_before_replacement(mut self, scans: u16) -> Self {
+        self.pending_scans_before_replacement = scans;
+        self
+    }
+
     pub fn build(self) -> PendingPayableScanner {
         PendingPayableScanner::new(
             Box::new(self.payable_dao),
             Box::new(self.pending_payable_dao),
             Rc::new(self.payment_thresholds),
             self.when_pending_too_long_sec,
+            self.pending_scans_before_replacement,
             Rc::new(RefCell::new(self.financial_statistics)),
         )
     }
@@ -1254,6 +1391,7 @@ pub fn make_pending_payable_fingerprint() -> PendingPayableFingerprint {
         attempt: 1,
         amount: 12345,
         process_error: None,
+        replaceable_opt: None,
     }
 }
 
@@ -1439,23 +1577,28 @@ pub fn trick_rusqlite_with_read_only_conn(
 #[derive(Default)]
 pub struct PaymentAdjusterMock {
     search_for_indispensable_adjustment_params:
-        Arc<Mutex<Vec<(BlockchainAgentWithContextMessage, Logger)>>>,
+        Arc<Mutex<Vec<(Vec<PayableAccount>, ArbitraryIdStamp, Logger)>>>,
     search_for_indispensable_adjustment_results:
         RefCell<Vec<Result<Option<Adjustment>, AnalysisError>>>,
     adjust_payments_params: Arc<Mutex<Vec<(PreparedAdjustment, SystemTime, Logger)>>>,
-    adjust_payments_results: RefCell<Vec<OutboundPaymentsInstructions>>,
+    adjust_payments_results: RefCell<Vec<AdjustedPayments>>,
 }
 
 impl PaymentAdjuster for PaymentAdjusterMock {
     fn search_for_indispensable_adjustment(
         &self,
-        msg: &BlockchainAgentWithContextMessage,
+        qualified_payables: &[PayableAccount],
+        agent: &dyn BlockchainAgent,
         logger: &Logger,
     ) -> Result<Option<Adjustment>, AnalysisError> {
         self.search_for_indispensable_adjustment_params
             .lock()
             .unwrap()
-            .push((msg.clone(), logger.clone()));
+            .push((
+                qualified_payables.to_vec(),
+                agent.arbitrary_id_stamp(),
+                logger.clone(),
+            ));
         self.search_for_indispensable_adjustment_results
             .borrow_mut()
             .remove(0)
@@ -1466,7 +1609,7 @@ impl PaymentAdjuster for PaymentAdjusterMock {
         setup: PreparedAdjustment,
         now: SystemTime,
         logger: &Logger,
-    ) -> OutboundPaymentsInstructions {
+    ) -> AdjustedPayments {
         self.adjust_payments_params
             .lock()
             .unwrap()
@@ -1478,7 +1621,7 @@ impl PaymentAdjuster for PaymentAdjusterMock {
 impl PaymentAdjusterMock {
     pub fn is_adjustment_required_params(
         mut self,
-        params: &Arc<Mutex<Vec<(BlockchainAgentWithContextMessage, Logger)>>>,
+        params: &Arc<Mutex<Vec<(Vec<PayableAccount>, ArbitraryIdStamp, Logger)>>>,
     ) -> Self {
         self.search_for_indispensable_adjustment_params = params.clone();
         self
@@ -1502,7 +1645,7 @@ impl PaymentAdjusterMock {
         self
     }
 
-    pub fn adjust_payments_result(self, result: OutboundPaymentsInstructions) -> Self {
+    pub fn adjust_payments_result(self, result: AdjustedPayments) -> Self {
         self.adjust_payments_results.borrow_mut().push(result);
         self
     }
@@ -1525,7 +1668,7 @@ macro_rules! formal_traits_for_payable_mid_scan_msg_handling {
                 &self,
                 _setup: PreparedAdjustment,
                 _logger: &Logger,
-            ) -> OutboundPaymentsInstructions {
+            ) -> AdjustedPayments {
                 intentionally_blank!()
             }
         }
@@ -16,6 +16,7 @@ use crate::bootstrapper::CryptDEPair;
 use crate::database::db_initializer::DbInitializationConfig;
 use crate::database::db_initializer::{connection_or_panic, DbInitializer, DbInitializerReal};
 use crate::db_config::persistent_configuration::PersistentConfiguration;
+use crate::masquerader::MasqueraderKind;
 use crate::node_configurator::configurator::Configurator;
 use crate::sub_lib::accountant::{AccountantSubs, AccountantSubsFactoryReal, DaoFactories};
 use crate::sub_lib::blockchain_bridge::BlockchainBridgeSubs;
@@ -128,6 +129,7 @@ impl ActorSystemFactoryTools for ActorSystemFactoryToolsReal {
                     exit_byte_rate: config.neighborhood_config.mode.rate_pack().exit_byte_rate,
                     is_decentralized: config.neighborhood_config.mode.is_decentralized(),
                     crashable: is_crashable(&config),
+                    exit_policy: config.exit_policy.clone(),
                 }),
             )
         } else {
@@ -146,6 +148,7 @@ impl ActorSystemFactoryTools for ActorSystemFactoryToolsReal {
                 .rate_pack()
                 .routing_byte_rate,
             is_decentralized: config.neighborhood_config.mode.is_decentralized(),
+            packet_padding: config.packet_padding,
             crashable: is_crashable(&config),
         });
         let blockchain_bridge_subs = actor_factory
@@ -158,7 +161,8 @@ impl ActorSystemFactoryTools for ActorSystemFactoryToolsReal {
             &AccountantSubsFactoryReal {},
         );
         let ui_gateway_subs = actor_factory.make_and_start_ui_gateway(&config);
-        let stream_handler_pool_subs = actor_factory.make_and_start_stream_handler_pool(&config);
+        let stream_handler_pool_subs =
+            actor_factory.make_and_start_stream_handler_pool(cryptdes.main, &config);
         let configurator_subs = actor_factory.make_and_start_configurator(&config);
 
         // collect all the subs
@@ -367,6 +371,7 @@ pub trait ActorFactory {
     fn make_and_start_ui_gateway(&self, config: &BootstrapperConfig) -> UiGatewaySubs;
     fn make_and_start_stream_handler_pool(
         &self,
+        cryptde: &'static dyn CryptDE,
         config: &BootstrapperConfig,
     ) -> StreamHandlerPoolSubs;
     fn make_and_start_proxy_client(&self, config: ProxyClientConfig) -> ProxyClientSubs;
@@ -407,6 +412,10 @@ impl ActorFactory for ActorFactoryReal {
         } else {
             None
         };
+        let socks_port_opt = config.socks_port_opt;
+        let forward_proxy_config_opt = config.forward_proxy_config_opt.clone();
+        let sticky_route_ttl = config.sticky_route_ttl;
+        let multipath_routes = config.multipath_routes;
         let crashable = is_crashable(config);
         let arbiter = Arbiter::builder().stop_system_on_panic(true);
         let addr: Addr<ProxyServer> = arbiter.start(move |_| {
@@ -415,6 +424,10 @@ impl ActorFactory for ActorFactoryReal {
                 cryptdes.alias,
                 is_decentralized,
                 consuming_wallet_balance,
+                socks_port_opt,
+                forward_proxy_config_opt,
+                sticky_route_ttl,
+                multipath_routes,
                 crashable,
             )
         });
@@ -479,14 +492,22 @@ impl ActorFactory for ActorFactoryReal {
 
     fn make_and_start_stream_handler_pool(
         &self,
+        cryptde: &'static dyn CryptDE,
         config: &BootstrapperConfig,
     ) -> StreamHandlerPoolSubs {
         let clandestine_discriminator_factories =
             config.clandestine_discriminator_factories.clone();
+        let masquerader_kinds = MasqueraderKind::supported(config.binary_masquerade);
         let crashable = is_crashable(config);
         let arbiter = Arbiter::builder().stop_system_on_panic(true);
-        let addr: Addr<StreamHandlerPool> = arbiter
-            .start(move |_| StreamHandlerPool::new(clandestine_discriminator_factories, crashable));
+        let addr: Addr<StreamHandlerPool> = arbiter.start(move |_| {
+            StreamHandlerPool::new(
+                clandestine_discriminator_factories,
+                masquerader_kinds,
+                crashable,
+                cryptde,
+            )
+        });
         StreamHandlerPool::make_subs_from(&addr)
     }
 
@@ -516,7 +537,14 @@ impl ActorFactory for ActorFactoryReal {
             );
             let persistent_config =
                 BlockchainBridge::initialize_persistent_configuration(&data_directory);
-            BlockchainBridge::new(blockchain_interface, persistent_config, crashable)
+            let pending_payable_dao =
+                BlockchainBridge::initialize_pending_payable_dao(&data_directory);
+            BlockchainBridge::new(
+                blockchain_interface,
+                persistent_config,
+                pending_payable_dao,
+                crashable,
+            )
         });
         subs_factory.make(&addr)
     }
@@ -623,17 +651,22 @@ where
 mod tests {
     use super::*;
     use crate::accountant::exportable_test_parts::test_accountant_is_constructed_with_upgraded_db_connection_recognizing_our_extra_sqlite_functions;
-    use crate::accountant::DEFAULT_PENDING_TOO_LONG_SEC;
+    use crate::accountant::{
+        DEFAULT_PENDING_SCANS_BEFORE_REPLACEMENT, DEFAULT_PENDING_TOO_LONG_SEC,
+    };
     use crate::blockchain::blockchain_bridge::exportable_test_parts::test_blockchain_bridge_is_constructed_with_correctly_functioning_connections;
     use crate::bootstrapper::{Bootstrapper, RealUser};
+    use crate::neighborhood::DEFAULT_LATENCY_WEIGHT;
     use crate::node_test_utils::{
         make_stream_handler_pool_subs_from_recorder, start_recorder_refcell_opt,
     };
+    use crate::proxy_server::{DEFAULT_MULTIPATH_ROUTES, DEFAULT_STICKY_ROUTE_TTL};
     use crate::sub_lib::accountant::{PaymentThresholds, ScanIntervals};
     use crate::sub_lib::blockchain_bridge::BlockchainBridgeConfig;
     use crate::sub_lib::cryptde::{PlainData, PublicKey};
     use crate::sub_lib::cryptde_null::CryptDENull;
     use crate::sub_lib::dispatcher::{InboundClientData, StreamShutdownMsg};
+    use crate::sub_lib::exit_policy::ExitPolicy;
     use crate::sub_lib::neighborhood::NeighborhoodMode;
     use crate::sub_lib::neighborhood::NodeDescriptor;
     use crate::sub_lib::neighborhood::{NeighborhoodConfig, DEFAULT_RATE_PACK};
@@ -897,6 +930,7 @@ mod tests {
 
         fn make_and_start_stream_handler_pool(
             &self,
+            _: &'static dyn CryptDE,
             _: &BootstrapperConfig,
         ) -> StreamHandlerPoolSubs {
             let addr = start_recorder_refcell_opt(&self.stream_handler_pool);
@@ -1156,6 +1190,17 @@ mod tests {
                 gas_price: 1,
             },
             port_configurations: HashMap::new(),
+            socks_port_opt: None,
+            forward_proxy_config_opt: None,
+            sticky_route_ttl: DEFAULT_STICKY_ROUTE_TTL,
+            multipath_routes: DEFAULT_MULTIPATH_ROUTES,
+            packet_padding: false,
+            binary_masquerade: false,
+            cover_traffic_interval_opt: None,
+            exit_policy: ExitPolicy::default(),
+            country_code_opt: None,
+            exit_location_opt: None,
+            latency_weight: DEFAULT_LATENCY_WEIGHT,
             db_password_opt: None,
             clandestine_port_opt: None,
             earning_wallet: make_wallet("earning"),
@@ -1176,6 +1221,7 @@ mod tests {
             },
             payment_thresholds_opt: Some(PaymentThresholds::default()),
             when_pending_too_long_sec: DEFAULT_PENDING_TOO_LONG_SEC,
+            pending_scans_before_replacement: DEFAULT_PENDING_SCANS_BEFORE_REPLACEMENT,
         };
         let persistent_config = PersistentConfigurationMock::default()
             .chain_name_result("eth-ropsten".to_string())
@@ -1231,6 +1277,17 @@ mod tests {
                 gas_price: 1,
             },
             port_configurations: HashMap::new(),
+            socks_port_opt: None,
+            forward_proxy_config_opt: None,
+            sticky_route_ttl: DEFAULT_STICKY_ROUTE_TTL,
+            multipath_routes: DEFAULT_MULTIPATH_ROUTES,
+            packet_padding: true,
+            binary_masquerade: false,
+            cover_traffic_interval_opt: None,
+            exit_policy: ExitPolicy::default(),
+            country_code_opt: None,
+            exit_location_opt: None,
+            latency_weight: DEFAULT_LATENCY_WEIGHT,
             db_password_opt: None,
             clandestine_port_opt: None,
             earning_wallet: make_wallet("earning"),
@@ -1250,7 +1307,8 @@ mod tests {
                 min_hops: MIN_HOPS_FOR_TEST,
             },
             payment_thresholds_opt: Default::default(),
-            when_pending_too_long_sec: DEFAULT_PENDING_TOO_LONG_SEC
+            when_pending_too_long_sec: DEFAULT_PENDING_TOO_LONG_SEC,
+            pending_scans_before_replacement: DEFAULT_PENDING_SCANS_BEFORE_REPLACEMENT
         };
         let add_mapping_params_arc = Arc::new(Mutex::new(vec![]));
         let mut subject = make_subject_with_null_setter();
@@ -1297,6 +1355,7 @@ mod tests {
         check_cryptde(hopper_config.cryptdes.main);
         assert_eq!(hopper_config.per_routing_service, 300);
         assert_eq!(hopper_config.per_routing_byte, 101);
+        assert_eq!(hopper_config.packet_padding, true);
         let proxy_client_config = Parameters::get(parameters.proxy_client_params);
         check_cryptde(proxy_client_config.cryptde);
         assert_eq!(proxy_client_config.exit_service_rate, 500);
@@ -1534,6 +1593,17 @@ mod tests {
                 gas_price: 1,
             },
             port_configurations: HashMap::new(),
+            socks_port_opt: None,
+            forward_proxy_config_opt: None,
+            sticky_route_ttl: DEFAULT_STICKY_ROUTE_TTL,
+            multipath_routes: DEFAULT_MULTIPATH_ROUTES,
+            packet_padding: false,
+            binary_masquerade: false,
+            cover_traffic_interval_opt: None,
+            exit_policy: ExitPolicy::default(),
+            country_code_opt: None,
+            exit_location_opt: None,
+            latency_weight: DEFAULT_LATENCY_WEIGHT,
             db_password_opt: None,
             clandestine_port_opt: None,
             earning_wallet: make_wallet("earning"),
@@ -1549,7 +1619,8 @@ mod tests {
                 min_hops: MIN_HOPS_FOR_TEST,
             },
             payment_thresholds_opt: Default::default(),
-            when_pending_too_long_sec: DEFAULT_PENDING_TOO_LONG_SEC
+            when_pending_too_long_sec: DEFAULT_PENDING_TOO_LONG_SEC,
+            pending_scans_before_replacement: DEFAULT_PENDING_SCANS_BEFORE_REPLACEMENT
         };
         let system = System::new("MASQNode");
         let mut subject = make_subject_with_null_setter();
@@ -1720,6 +1791,17 @@ mod tests {
                 gas_price: 1,
             },
             port_configurations: HashMap::new(),
+            socks_port_opt: None,
+            forward_proxy_config_opt: None,
+            sticky_route_ttl: DEFAULT_STICKY_ROUTE_TTL,
+            multipath_routes: DEFAULT_MULTIPATH_ROUTES,
+            packet_padding: false,
+            binary_masquerade: false,
+            cover_traffic_interval_opt: None,
+            exit_policy: ExitPolicy::default(),
+            country_code_opt: None,
+            exit_location_opt: None,
+            latency_weight: DEFAULT_LATENCY_WEIGHT,
             db_password_opt: None,
             clandestine_port_opt: None,
             consuming_wallet_opt: None,
@@ -1740,6 +1822,7 @@ mod tests {
             node_descriptor: Default::default(),
             payment_thresholds_opt: Default::default(),
             when_pending_too_long_sec: DEFAULT_PENDING_TOO_LONG_SEC,
+            pending_scans_before_replacement: DEFAULT_PENDING_SCANS_BEFORE_REPLACEMENT,
         };
         let subject = make_subject_with_null_setter();
         let system = System::new("MASQNode");
@@ -1782,6 +1865,7 @@ mod tests {
                 is_decentralized: true,
                 crashable: true,
                 exit_byte_rate: 50,
+                exit_policy: ExitPolicy::default(),
             };
             let subscribers = ActorFactoryReal {}.make_and_start_proxy_client(proxy_cl_config);
             subscribers.node_from_ui
@@ -1798,6 +1882,7 @@ mod tests {
                 per_routing_service: 100,
                 per_routing_byte: 50,
                 is_decentralized: false,
+                packet_padding: false,
                 crashable: true,
             };
             let subscribers = ActorFactoryReal {}.make_and_start_hopper(hopper_config);
@@ -1824,8 +1909,8 @@ mod tests {
         let closure = || {
             let mut bootstrapper_config = BootstrapperConfig::default();
             bootstrapper_config.crash_point = CrashPoint::Message;
-            let subscribers =
-                ActorFactoryReal {}.make_and_start_stream_handler_pool(&bootstrapper_config);
+            let subscribers = ActorFactoryReal {}
+                .make_and_start_stream_handler_pool(main_cryptde(), &bootstrapper_config);
             subscribers.node_from_ui_sub
         };
 
@@ -0,0 +1,133 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::sub_lib::framer::FramedChunk;
+use crate::sub_lib::framer::Framer;
+
+pub const BINARY_MASQUERADE_MARKER: u8 = 0xB1;
+pub const BINARY_HEADER_LEN: usize = 5;
+pub const BINARY_MAX_PAYLOAD_LEN: usize = 0x0100_0000;
+
+// A binary masquerade is a marker byte, a four-byte big-endian payload length, and the payload.
+#[derive(Default)]
+pub struct BinaryFramer {
+    data_so_far: Vec<u8>,
+}
+
+impl Framer for BinaryFramer {
+    fn add_data(&mut self, data: &[u8]) {
+        self.data_so_far.extend(data);
+    }
+
+    fn take_frame(&mut self) -> Option<FramedChunk> {
+        match self
+            .data_so_far
+            .iter()
+            .position(|byte| *byte == BINARY_MASQUERADE_MARKER)
+        {
+            Some(start) => {
+                self.data_so_far.drain(0..start);
+            }
+            None => {
+                self.data_so_far.clear();
+                return None;
+            }
+        }
+        let frame_length = BinaryFramer::frame_length(&self.data_so_far)?;
+        let leftovers = self.data_so_far.split_off(frame_length);
+        Some(FramedChunk {
+            chunk: std::mem::replace(&mut self.data_so_far, leftovers),
+            last_chunk: true,
+        })
+    }
+}
+
+impl BinaryFramer {
+    pub fn new() -> BinaryFramer {
+        Self::default()
+    }
+
+    // None means more data is needed before the end of the masquerade can be found. A length too
+    // large to be believed ends the masquerade right after its header; unmasking will complain.
+    pub fn frame_length(data: &[u8]) -> Option<usize> {
+        let header = data.get(0..BINARY_HEADER_LEN)?;
+        let payload_len = BinaryFramer::payload_len(header);
+        if payload_len > BINARY_MAX_PAYLOAD_LEN {
+            return Some(BINARY_HEADER_LEN);
+        }
+        let frame_length = BINARY_HEADER_LEN + payload_len;
+        if frame_length <= data.len() {
+            Some(frame_length)
+        } else {
+            None
+        }
+    }
+
+    pub fn payload_len(header: &[u8]) -> usize {
+        u32::from_be_bytes([header[1], header[2], header[3], header[4]]) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(BINARY_MASQUERADE_MARKER, 0xB1);
+        assert_eq!(BINARY_HEADER_LEN, 5);
+        assert_eq!(BINARY_MAX_PAYLOAD_LEN, 16 * 1024 * 1024);
+    }
+
+    #[test]
+    fn binary_framer_picks_packets_out_of_trash() {
+        let mut subject = BinaryFramer::new();
+
+        subject.add_data(&[
+            0x01, 0x02, 0xB1, 0x00, 0x00, 0x00, 0x02, b'h', b'i', 0xB1, 0x00, 0x00, 0x00, 0x00,
+            0x03,
+        ]);
+
+        assert_eq!(
+            subject.take_frame(),
+            Some(FramedChunk {
+                chunk: vec![0xB1, 0x00, 0x00, 0x00, 0x02, b'h', b'i'],
+                last_chunk: true
+            })
+        );
+        assert_eq!(
+            subject.take_frame(),
+            Some(FramedChunk {
+                chunk: vec![0xB1, 0x00, 0x00, 0x00, 0x00],
+                last_chunk: true
+            })
+        );
+        assert_eq!(subject.take_frame(), None);
+        assert!(subject.data_so_far.is_empty());
+    }
+
+    #[test]
+    fn binary_framer_handles_badly_fragmented_input() {
+        let mut subject = BinaryFramer::new();
+
+        subject.add_data(&[0xB1, 0x00, 0x00]);
+        assert_eq!(subject.take_frame(), None);
+        subject.add_data(&[0x00, 0x03, b'a']);
+        assert_eq!(subject.take_frame(), None);
+        subject.add_data(&[b'b', b'c', 0xB1]);
+        assert_eq!(
+            subject.take_frame(),
+            Some(FramedChunk {
+                chunk: vec![0xB1, 0x00, 0x00, 0x00, 0x03, b'a', b'b', b'c'],
+                last_chunk: true
+            })
+        );
+        assert_eq!(subject.take_frame(), None);
+        assert_eq!(subject.data_so_far, vec![0xB1]);
+    }
+
+    #[test]
+    fn frame_length_gives_up_on_unbelievable_length() {
+        let result = BinaryFramer::frame_length(&[0xB1, 0x01, 0x00, 0x00, 0x01, 0x00]);
+
+        assert_eq!(result, Some(BINARY_HEADER_LEN));
+    }
+}
@@ -0,0 +1,154 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::binary_framer::{
+    BinaryFramer, BINARY_HEADER_LEN, BINARY_MASQUERADE_MARKER, BINARY_MAX_PAYLOAD_LEN,
+};
+use crate::discriminator::UnmaskedChunk;
+use crate::masquerader::MasqueradeError;
+use crate::masquerader::Masquerader;
+use masq_lib::logger::Logger;
+
+// No disguise at all: just enough framing to find the data again, so that relaying it costs
+// five bytes and a copy instead of a Base64 encoding and a JSON parse.
+pub struct BinaryMasquerader {
+    logger: Logger,
+}
+
+impl Masquerader for BinaryMasquerader {
+    fn try_unmask(&self, item: &[u8]) -> Result<UnmaskedChunk, MasqueradeError> {
+        if item.first() != Some(&BINARY_MASQUERADE_MARKER) {
+            return Err(MasqueradeError::NotThisMasquerader);
+        }
+        match Self::unmask(item) {
+            Ok(chunk) => Ok(chunk),
+            Err(err) => {
+                error!(self.logger, "{}", err);
+                Err(err)
+            }
+        }
+    }
+
+    fn mask(&self, data: &[u8]) -> Result<Vec<u8>, MasqueradeError> {
+        if data.len() > BINARY_MAX_PAYLOAD_LEN {
+            return Err(MasqueradeError::HighLevelDataError(format!(
+                "Can't mask {} bytes; the limit is {}",
+                data.len(),
+                BINARY_MAX_PAYLOAD_LEN
+            )));
+        }
+        let mut masked = Vec::with_capacity(BINARY_HEADER_LEN + data.len());
+        masked.push(BINARY_MASQUERADE_MARKER);
+        masked.extend((data.len() as u32).to_be_bytes());
+        masked.extend(data);
+        Ok(masked)
+    }
+}
+
+impl Default for BinaryMasquerader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BinaryMasquerader {
+    pub fn new() -> BinaryMasquerader {
+        BinaryMasquerader {
+            logger: Logger::new("BinaryMasquerader"),
+        }
+    }
+
+    fn unmask(data: &[u8]) -> Result<UnmaskedChunk, MasqueradeError> {
+        if data.len() < BINARY_HEADER_LEN {
+            return Err(MasqueradeError::LowLevelDataError(format!(
+                "Binary header truncated after {} bytes",
+                data.len()
+            )));
+        }
+        let (header, payload) = data.split_at(BINARY_HEADER_LEN);
+        let payload_len = BinaryFramer::payload_len(header);
+        if payload_len != payload.len() {
+            return Err(MasqueradeError::LowLevelDataError(format!(
+                "Binary header claims {} bytes but payload contains {}",
+                payload_len,
+                payload.len()
+            )));
+        }
+        Ok(UnmaskedChunk::new(payload.to_vec(), true, false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+
+    #[test]
+    fn binary_masquerader_can_mask_and_unmask_non_utf8_data() {
+        let data = [0x7B, 0xC0, 0x7D, 0xC1];
+        let subject = BinaryMasquerader::new();
+
+        let masked = subject.mask(&data).unwrap();
+        let unmasked = subject.try_unmask(&masked).unwrap();
+
+        assert_eq!(
+            masked,
+            vec![0xB1, 0x00, 0x00, 0x00, 0x04, 0x7B, 0xC0, 0x7D, 0xC1]
+        );
+        assert_eq!(unmasked, UnmaskedChunk::new(data.to_vec(), true, false));
+    }
+
+    #[test]
+    fn binary_masquerader_refuses_to_mask_too_much_data() {
+        let data = vec![0u8; BINARY_MAX_PAYLOAD_LEN + 1];
+        let subject = BinaryMasquerader::new();
+
+        let result = subject.mask(&data);
+
+        assert_eq!(
+            result,
+            Err(MasqueradeError::HighLevelDataError(
+                "Can't mask 16777217 bytes; the limit is 16777216".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn try_unmask_rejects_data_for_other_masqueraders() {
+        let subject = BinaryMasquerader::new();
+
+        let result = subject.try_unmask(b"{\"bodyText\": \"booga\"}");
+
+        assert_eq!(result, Err(MasqueradeError::NotThisMasquerader));
+    }
+
+    #[test]
+    fn try_unmask_complains_about_truncated_header() {
+        init_test_logging();
+        let subject = BinaryMasquerader::new();
+
+        let result = subject.try_unmask(&[0xB1, 0x00, 0x00]);
+
+        assert_eq!(
+            result,
+            Err(MasqueradeError::LowLevelDataError(
+                "Binary header truncated after 3 bytes".to_string()
+            ))
+        );
+        TestLogHandler::new().exists_log_containing(
+            "ERROR: BinaryMasquerader: Low-level data error: Binary header truncated after 3 bytes",
+        );
+    }
+
+    #[test]
+    fn try_unmask_complains_about_payload_of_wrong_length() {
+        let subject = BinaryMasquerader::new();
+
+        let result = subject.try_unmask(&[0xB1, 0x00, 0x00, 0x00, 0x03, b'h', b'i']);
+
+        assert_eq!(
+            result,
+            Err(MasqueradeError::LowLevelDataError(
+                "Binary header claims 3 bytes but payload contains 2".to_string()
+            ))
+        );
+    }
+}
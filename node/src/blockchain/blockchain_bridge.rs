@@ -1,6 +1,10 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 use crate::accountant::db_access_objects::payable_dao::PayableAccount;
+use crate::accountant::db_access_objects::pending_payable_dao::{
+    PendingPayableDao, PendingPayableDaoFactory,
+};
+use crate::accountant::db_access_objects::utils::DaoFactoryReal;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::{
     BlockchainAgentWithContextMessage, QualifiedPayablesMessage,
@@ -8,7 +12,7 @@ use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::{
 use crate::accountant::{
     ReceivedPayments, ResponseSkeleton, ScanError, SentPayables, SkeletonOptHolder,
 };
-use crate::accountant::{ReportTransactionReceipts, RequestTransactionReceipts};
+use crate::accountant::{ReportTransactionReceipts, RequestTransactionReceipts, StuckTransactions};
 use crate::actor_system_factory::SubsFactory;
 use crate::blockchain::blockchain_interface::blockchain_interface_null::BlockchainInterfaceNull;
 use crate::blockchain::blockchain_interface::data_structures::errors::{
@@ -22,7 +26,9 @@ use crate::db_config::config_dao::ConfigDaoReal;
 use crate::db_config::persistent_configuration::{
     PersistentConfiguration, PersistentConfigurationReal,
 };
-use crate::sub_lib::blockchain_bridge::{BlockchainBridgeSubs, OutboundPaymentsInstructions};
+use crate::sub_lib::blockchain_bridge::{
+    BlockchainBridgeSubs, OutboundPaymentsInstructions, TransactionFees,
+};
 use crate::sub_lib::peer_actors::BindMessage;
 use crate::sub_lib::utils::{db_connection_launch_panic, handle_ui_crash_request};
 use crate::sub_lib::wallet::Wallet;
@@ -39,6 +45,7 @@ use masq_lib::messages::ScanType;
 use masq_lib::ui_gateway::NodeFromUiMessage;
 use masq_lib::utils::to_string;
 use regex::Regex;
+use std::iter::once;
 use std::path::Path;
 use std::time::SystemTime;
 use web3::types::{BlockNumber, TransactionReceipt, H256};
@@ -49,6 +56,7 @@ pub struct BlockchainBridge {
     blockchain_interface: Box<dyn BlockchainInterface>,
     logger: Logger,
     persistent_config: Box<dyn PersistentConfiguration>,
+    pending_payable_dao: Box<dyn PendingPayableDao>,
     sent_payable_subs_opt: Option<Recipient<SentPayables>>,
     payable_payments_setup_subs_opt: Option<Recipient<BlockchainAgentWithContextMessage>>,
     received_payments_subs_opt: Option<Recipient<ReceivedPayments>>,
@@ -150,6 +158,7 @@ impl Handler<OutboundPaymentsInstructions> for BlockchainBridge {
 pub struct PendingPayableFingerprintSeeds {
     pub batch_wide_timestamp: SystemTime,
     pub hashes_and_balances: Vec<(H256, u128)>,
+    pub replaceable_transactions: Vec<(H256, ReplaceableTransaction)>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -162,6 +171,37 @@ pub struct PendingPayableFingerprint {
     pub attempt: u16,
     pub amount: u128,
     pub process_error: Option<String>,
+    // Fingerprints of transactions sent before the signing details were kept are left with None
+    pub replaceable_opt: Option<ReplaceableTransaction>,
+}
+
+impl PendingPayableFingerprint {
+    pub fn replacement_hashes(&self) -> &[H256] {
+        self.replaceable_opt
+            .as_ref()
+            .map(|replaceable| replaceable.replacement_hashes.as_slice())
+            .unwrap_or(&[])
+    }
+}
+
+// What it takes to sign the same payment again under the same nonce
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ReplaceableTransaction {
+    pub recipient_wallet: Wallet,
+    pub nonce: u64,
+    // Those of the latest of the transactions sharing the nonce
+    pub transaction_fees: TransactionFees,
+    // Any of them may be the one that gets mined, so none is forgotten; the oldest comes first
+    pub replacement_hashes: Vec<H256>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TransactionReplacement {
+    pub rowid: u64,
+    pub hash: H256,
+    pub transaction_fees: TransactionFees,
+    // The replacement gets its own full pending time, counted from here
+    pub timestamp: SystemTime,
 }
 
 impl Handler<NodeFromUiMessage> for BlockchainBridge {
@@ -176,11 +216,13 @@ impl BlockchainBridge {
     pub fn new(
         blockchain_interface: Box<dyn BlockchainInterface>,
         persistent_config: Box<dyn PersistentConfiguration>,
+        pending_payable_dao: Box<dyn PendingPayableDao>,
         crashable: bool,
     ) -> BlockchainBridge {
         BlockchainBridge {
             blockchain_interface,
             persistent_config,
+            pending_payable_dao,
             sent_payable_subs_opt: None,
             payable_payments_setup_subs_opt: None,
             received_payments_subs_opt: None,
@@ -205,6 +247,10 @@ impl BlockchainBridge {
         Box::new(PersistentConfigurationReal::new(config_dao))
     }
 
+    pub fn initialize_pending_payable_dao(data_directory: &Path) -> Box<dyn PendingPayableDao> {
+        DaoFactoryReal::new(data_directory, DbInitializationConfig::panic_on_migration()).make()
+    }
+
     pub fn initialize_blockchain_interface(
         blockchain_service_url_opt: Option<String>,
         chain: Chain,
@@ -239,6 +285,17 @@ impl BlockchainBridge {
             .build_blockchain_agent(&incoming_message.consuming_wallet, &*self.persistent_config)
             .map_err(to_string)?;
 
+        if let Err(e) = self
+            .persistent_config
+            .set_transaction_fees(agent.agreed_transaction_fees())
+        {
+            warning!(
+                self.logger,
+                "Failed to record the agreed transaction fees: {:?}",
+                e
+            )
+        }
+
         let outgoing_message = BlockchainAgentWithContextMessage::new(
             incoming_message.protected_qualified_payables,
             agent,
@@ -390,15 +447,12 @@ impl BlockchainBridge {
         let (vector_of_results, error_opt) = msg.pending_payable.iter().fold(
             init,
             |(mut ok_receipts, err_opt), current_fingerprint| match err_opt {
-                None => match self
-                    .blockchain_interface
-                    .get_transaction_receipt(current_fingerprint.hash)
-                {
+                None => match self.request_receipt(current_fingerprint) {
                     Ok(receipt_opt) => {
                         ok_receipts.push(receipt_opt);
                         (ok_receipts, None)
                     }
-                    Err(e) => (ok_receipts, Some((e, current_fingerprint.hash))),
+                    Err(e) => (ok_receipts, Some(e)),
                 },
                 _ => (ok_receipts, err_opt),
             },
@@ -407,6 +461,9 @@ impl BlockchainBridge {
             .into_iter()
             .zip(msg.pending_payable.into_iter())
             .collect_vec();
+        if let (None, Some(stuck_transactions)) = (&error_opt, &msg.stuck_transactions_opt) {
+            self.replace_stuck_transactions(stuck_transactions, &pairs)
+        }
         self.pending_payable_confirmation
             .report_transaction_receipts_sub_opt
             .as_ref()
@@ -426,6 +483,55 @@ impl BlockchainBridge {
         Ok(())
     }
 
+    fn request_receipt(
+        &self,
+        fingerprint: &PendingPayableFingerprint,
+    ) -> Result<Option<TransactionReceipt>, (BlockchainError, H256)> {
+        // Only one of the transactions sharing the nonce can ever be mined
+        once(&fingerprint.hash)
+            .chain(fingerprint.replacement_hashes())
+            .try_fold(None, |receipt_opt, hash| match receipt_opt {
+                None => self
+                    .blockchain_interface
+                    .get_transaction_receipt(*hash)
+                    .map_err(|e| (e, *hash)),
+                found => Ok(found),
+            })
+    }
+
+    fn replace_stuck_transactions(
+        &self,
+        stuck_transactions: &StuckTransactions,
+        fingerprints_with_receipts: &[(Option<TransactionReceipt>, PendingPayableFingerprint)],
+    ) {
+        fingerprints_with_receipts
+            .iter()
+            .filter(|(receipt_opt, fingerprint)| {
+                receipt_opt.is_none() && stuck_transactions.rowids.contains(&fingerprint.rowid)
+            })
+            .for_each(|(_, fingerprint)| {
+                match self.blockchain_interface.replace_transaction(
+                    &stuck_transactions.consuming_wallet,
+                    fingerprint,
+                    &*self.persistent_config,
+                    &*self.pending_payable_dao,
+                ) {
+                    Ok(replacement) => debug!(
+                        self.logger,
+                        "Stuck transaction {:?} was replaced by {:?}",
+                        fingerprint.hash,
+                        replacement.hash
+                    ),
+                    Err(e) => warning!(
+                        self.logger,
+                        "Failed to replace the stuck transaction {:?}: {}",
+                        fingerprint.hash,
+                        e
+                    ),
+                }
+            })
+    }
+
     fn handle_scan<M, F>(&mut self, handler: F, scan_type: ScanType, msg: M)
     where
         F: FnOnce(&mut BlockchainBridge, M) -> Result<(), String>,
@@ -516,7 +622,7 @@ mod tests {
     use crate::accountant::db_access_objects::utils::from_time_t;
     use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::test_utils::BlockchainAgentMock;
     use crate::accountant::scanners::test_utils::protect_payables_in_test;
-    use crate::accountant::test_utils::make_pending_payable_fingerprint;
+    use crate::accountant::test_utils::{make_pending_payable_fingerprint, PendingPayableDaoMock};
     use crate::blockchain::blockchain_interface::blockchain_interface_null::BlockchainInterfaceNull;
     use crate::blockchain::blockchain_interface::data_structures::errors::{
         BlockchainAgentBuildError, PayableTransactionError,
@@ -530,6 +636,7 @@ mod tests {
     use crate::db_config::persistent_configuration::PersistentConfigError;
     use crate::match_every_type_id;
     use crate::node_test_utils::check_timestamp;
+    use crate::sub_lib::blockchain_bridge::TransactionFees;
     use crate::test_utils::persistent_configuration_mock::PersistentConfigurationMock;
     use crate::test_utils::recorder::{make_recorder, peer_actors_builder};
     use crate::test_utils::recorder_stop_conditions::StopCondition;
@@ -587,16 +694,25 @@ mod tests {
             "qualified_payables_msg_is_handled_and_new_msg_with_an_added_blockchain_agent_returns_to_accountant",
         );
         let build_blockchain_agent_params_arc = Arc::new(Mutex::new(vec![]));
+        let set_transaction_fees_params_arc = Arc::new(Mutex::new(vec![]));
         let (accountant, _, accountant_recording_arc) = make_recorder();
         let agent_id_stamp = ArbitraryIdStamp::new();
-        let agent = BlockchainAgentMock::default().set_arbitrary_id_stamp(agent_id_stamp);
+        let transaction_fees = TransactionFees::Eip1559 {
+            max_fee_per_gas_wei: 2_500_000_000,
+            max_priority_fee_per_gas_wei: 100_000_000,
+        };
+        let agent = BlockchainAgentMock::default()
+            .set_arbitrary_id_stamp(agent_id_stamp)
+            .agreed_transaction_fees_result(transaction_fees);
         let blockchain_interface = BlockchainInterfaceMock::default()
             .build_blockchain_agent_params(&build_blockchain_agent_params_arc)
             .build_blockchain_agent_result(Ok(Box::new(agent)));
         let consuming_wallet = make_paying_wallet(b"somewallet");
         let persistent_config_id_stamp = ArbitraryIdStamp::new();
         let persistent_configuration = PersistentConfigurationMock::default()
-            .set_arbitrary_id_stamp(persistent_config_id_stamp);
+            .set_arbitrary_id_stamp(persistent_config_id_stamp)
+            .set_transaction_fees_params(&set_transaction_fees_params_arc)
+            .set_transaction_fees_result(Ok(()));
         let wallet_1 = make_wallet("booga");
         let wallet_2 = make_wallet("gulp");
         let qualified_payables = vec![
@@ -620,6 +736,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(blockchain_interface),
             Box::new(persistent_configuration),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         let addr = subject.start();
@@ -646,6 +763,8 @@ mod tests {
             *build_blockchain_agent_params,
             vec![(consuming_wallet.clone(), persistent_config_id_stamp)]
         );
+        let set_transaction_fees_params = set_transaction_fees_params_arc.lock().unwrap();
+        assert_eq!(*set_transaction_fees_params, vec![transaction_fees]);
         let accountant_received_payment = accountant_recording_arc.lock().unwrap();
         let blockchain_agent_with_context_msg_actual: &BlockchainAgentWithContextMessage =
             accountant_received_payment.get_record(0);
@@ -662,6 +781,56 @@ mod tests {
         assert_eq!(accountant_received_payment.len(), 1);
     }
 
+    #[test]
+    fn failure_to_record_agreed_transaction_fees_is_logged_but_does_not_stop_the_payments() {
+        init_test_logging();
+        let test_name =
+            "failure_to_record_agreed_transaction_fees_is_logged_but_does_not_stop_the_payments";
+        let system = System::new(test_name);
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let agent = BlockchainAgentMock::default().agreed_transaction_fees_result(
+            TransactionFees::Legacy {
+                gas_price_wei: 123_000_000_000,
+            },
+        );
+        let blockchain_interface =
+            BlockchainInterfaceMock::default().build_blockchain_agent_result(Ok(Box::new(agent)));
+        let persistent_configuration = PersistentConfigurationMock::default()
+            .set_transaction_fees_result(Err(PersistentConfigError::NotPresent));
+        let mut subject = BlockchainBridge::new(
+            Box::new(blockchain_interface),
+            Box::new(persistent_configuration),
+            Box::new(PendingPayableDaoMock::new()),
+            false,
+        );
+        subject.logger = Logger::new(test_name);
+        let addr = subject.start();
+        let subject_subs = BlockchainBridge::make_subs_from(&addr);
+        let peer_actors = peer_actors_builder().accountant(accountant).build();
+        send_bind_message!(subject_subs, peer_actors);
+        let qualified_payables_msg = QualifiedPayablesMessage {
+            protected_qualified_payables: protect_payables_in_test(vec![PayableAccount {
+                wallet: make_wallet("blah"),
+                balance_wei: 42,
+                last_paid_timestamp: SystemTime::now(),
+                pending_payable_opt: None,
+            }]),
+            consuming_wallet: make_paying_wallet(b"somewallet"),
+            response_skeleton_opt: None,
+        };
+
+        addr.try_send(qualified_payables_msg).unwrap();
+
+        System::current().stop();
+        system.run();
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        let _: &BlockchainAgentWithContextMessage = accountant_recording.get_record(0);
+        assert_eq!(accountant_recording.len(), 1);
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {test_name}: Failed to record the agreed transaction fees: NotPresent"
+        ));
+    }
+
     #[test]
     fn build_of_blockchain_agent_throws_err_out_and_ends_handling_qualified_payables_message() {
         init_test_logging();
@@ -681,6 +850,7 @@ mod tests {
         let mut subject = BlockchainBridge::new(
             Box::new(blockchain_interface),
             Box::new(persistent_configuration),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         subject.logger = Logger::new(test_name);
@@ -754,6 +924,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(blockchain_interface_mock),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         let addr = subject.start();
@@ -841,6 +1012,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(blockchain_interface_mock),
             Box::new(persistent_configuration_mock),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         let addr = subject.start();
@@ -911,6 +1083,7 @@ mod tests {
         let mut subject = BlockchainBridge::new(
             Box::new(blockchain_interface_mock),
             Box::new(persistent_configuration_mock),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         let checked_accounts = vec![PayableAccount {
@@ -951,6 +1124,7 @@ mod tests {
             attempt: 3,
             amount: 4565,
             process_error: None,
+            replaceable_opt: None,
         };
         let blockchain_interface_mock = BlockchainInterfaceMock::default()
             .get_transaction_receipt_params(&get_transaction_receipt_params_arc)
@@ -959,6 +1133,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(blockchain_interface_mock),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         let addr = subject.start();
@@ -970,6 +1145,7 @@ mod tests {
                 pending_payable_fingerprint_1.clone(),
                 pending_payable_fingerprint_2.clone(),
             ],
+            stuck_transactions_opt: None,
             response_skeleton_opt: Some(ResponseSkeleton {
                 client_id: 1234,
                 context_id: 4321,
@@ -1004,6 +1180,183 @@ mod tests {
         assert_eq!(*get_transaction_receipt_params, vec![hash_1, hash_2])
     }
 
+    fn make_stuck_fingerprint(rowid: u64, nonce: u64) -> PendingPayableFingerprint {
+        PendingPayableFingerprint {
+            rowid,
+            timestamp: from_time_t(200_000_000),
+            hash: make_tx_hash(rowid as u32),
+            attempt: 6,
+            amount: 1_000_000 + u128::from(nonce),
+            process_error: None,
+            replaceable_opt: Some(ReplaceableTransaction {
+                recipient_wallet: make_wallet("creditor"),
+                nonce,
+                transaction_fees: TransactionFees::Legacy {
+                    gas_price_wei: 1_000_000_000,
+                },
+                replacement_hashes: vec![],
+            }),
+        }
+    }
+
+    #[test]
+    fn blockchain_bridge_replaces_stuck_transactions_still_without_receipts() {
+        init_test_logging();
+        let test_name = "blockchain_bridge_replaces_stuck_transactions_still_without_receipts";
+        let replace_transaction_params_arc = Arc::new(Mutex::new(vec![]));
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let consuming_wallet = make_paying_wallet(b"consuming_wallet");
+        let stuck_fingerprint_1 = make_stuck_fingerprint(1, 10);
+        let mined_fingerprint = make_stuck_fingerprint(2, 11);
+        let stuck_fingerprint_2 = make_stuck_fingerprint(3, 12);
+        let young_fingerprint = make_stuck_fingerprint(4, 13);
+        let replacement = TransactionReplacement {
+            rowid: 1,
+            hash: make_tx_hash(0x1111),
+            transaction_fees: TransactionFees::Legacy {
+                gas_price_wei: 1_125_000_000,
+            },
+            timestamp: SystemTime::now(),
+        };
+        let blockchain_interface_mock = BlockchainInterfaceMock::default()
+            .get_transaction_receipt_result(Ok(None))
+            .get_transaction_receipt_result(Ok(Some(TransactionReceipt::default())))
+            .get_transaction_receipt_result(Ok(None))
+            .get_transaction_receipt_result(Ok(None))
+            .replace_transaction_params(&replace_transaction_params_arc)
+            .replace_transaction_result(Ok(replacement))
+            .replace_transaction_result(Err(PayableTransactionError::Unreplaceable(
+                "fees are sky high".to_string(),
+            )));
+        let mut subject = BlockchainBridge::new(
+            Box::new(blockchain_interface_mock),
+            Box::new(PersistentConfigurationMock::default()),
+            Box::new(PendingPayableDaoMock::new()),
+            false,
+        );
+        subject.logger = Logger::new(test_name);
+        let addr = subject.start();
+        let subject_subs = BlockchainBridge::make_subs_from(&addr);
+        let peer_actors = peer_actors_builder().accountant(accountant).build();
+        send_bind_message!(subject_subs, peer_actors);
+        let msg = RequestTransactionReceipts {
+            pending_payable: vec![
+                stuck_fingerprint_1.clone(),
+                mined_fingerprint.clone(),
+                stuck_fingerprint_2.clone(),
+                young_fingerprint.clone(),
+            ],
+            stuck_transactions_opt: Some(StuckTransactions {
+                consuming_wallet: consuming_wallet.clone(),
+                rowids: vec![1, 2, 3],
+            }),
+            response_skeleton_opt: None,
+        };
+
+        let _ = addr.try_send(msg).unwrap();
+
+        let system = System::new(test_name);
+        System::current().stop();
+        system.run();
+        let replace_transaction_params = replace_transaction_params_arc.lock().unwrap();
+        assert_eq!(
+            *replace_transaction_params,
+            vec![
+                (consuming_wallet.clone(), stuck_fingerprint_1.clone()),
+                (consuming_wallet, stuck_fingerprint_2.clone())
+            ]
+        );
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        assert_eq!(
+            accountant_recording.get_record::<ReportTransactionReceipts>(0),
+            &ReportTransactionReceipts {
+                fingerprints_with_receipts: vec![
+                    (None, stuck_fingerprint_1),
+                    (Some(TransactionReceipt::default()), mined_fingerprint),
+                    (None, stuck_fingerprint_2),
+                    (None, young_fingerprint),
+                ],
+                response_skeleton_opt: None,
+            }
+        );
+        let log_handler = TestLogHandler::new();
+        log_handler.exists_log_containing(&format!(
+            "DEBUG: {test_name}: Stuck transaction \
+            0x0000000000000000000000000000000000000000000000000000000000000001 was replaced by \
+            0x0000000000000000000000000000000000000000000000000000000000001111"
+        ));
+        log_handler.exists_log_containing(&format!(
+            "WARN: {test_name}: Failed to replace the stuck transaction \
+            0x0000000000000000000000000000000000000000000000000000000000000003: \
+            Unreplaceable transaction: \"fees are sky high\""
+        ));
+    }
+
+    #[test]
+    fn blockchain_bridge_asks_for_the_receipts_of_the_replacements_until_one_is_found() {
+        let get_transaction_receipt_params_arc = Arc::new(Mutex::new(vec![]));
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let first_replacement_hash = make_tx_hash(0x2222);
+        let second_replacement_hash = make_tx_hash(0x3333);
+        let mut fingerprint = make_stuck_fingerprint(7, 20);
+        fingerprint
+            .replaceable_opt
+            .as_mut()
+            .unwrap()
+            .replacement_hashes = vec![
+            first_replacement_hash,
+            second_replacement_hash,
+            make_tx_hash(0x4444),
+        ];
+        let original_hash = fingerprint.hash;
+        let mut receipt = TransactionReceipt::default();
+        receipt.transaction_hash = second_replacement_hash;
+        receipt.status = Some(U64::from(1));
+        let blockchain_interface_mock = BlockchainInterfaceMock::default()
+            .get_transaction_receipt_params(&get_transaction_receipt_params_arc)
+            .get_transaction_receipt_result(Ok(None))
+            .get_transaction_receipt_result(Ok(None))
+            .get_transaction_receipt_result(Ok(Some(receipt.clone())));
+        let subject = BlockchainBridge::new(
+            Box::new(blockchain_interface_mock),
+            Box::new(PersistentConfigurationMock::default()),
+            Box::new(PendingPayableDaoMock::new()),
+            false,
+        );
+        let addr = subject.start();
+        let subject_subs = BlockchainBridge::make_subs_from(&addr);
+        let peer_actors = peer_actors_builder().accountant(accountant).build();
+        send_bind_message!(subject_subs, peer_actors);
+        let msg = RequestTransactionReceipts {
+            pending_payable: vec![fingerprint.clone()],
+            stuck_transactions_opt: None,
+            response_skeleton_opt: None,
+        };
+
+        let _ = addr.try_send(msg).unwrap();
+
+        let system = System::new("replacement receipt");
+        System::current().stop();
+        system.run();
+        let get_transaction_receipt_params = get_transaction_receipt_params_arc.lock().unwrap();
+        assert_eq!(
+            *get_transaction_receipt_params,
+            vec![
+                original_hash,
+                first_replacement_hash,
+                second_replacement_hash
+            ]
+        );
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        assert_eq!(
+            accountant_recording.get_record::<ReportTransactionReceipts>(0),
+            &ReportTransactionReceipts {
+                fingerprints_with_receipts: vec![(Some(receipt), fingerprint)],
+                response_skeleton_opt: None,
+            }
+        );
+    }
+
     #[test]
     fn blockchain_bridge_logs_error_from_retrieving_received_payments() {
         init_test_logging();
@@ -1025,6 +1378,7 @@ mod tests {
         let mut subject = BlockchainBridge::new(
             Box::new(blockchain_interface),
             Box::new(persistent_config),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         subject.scan_error_subs_opt = Some(scan_error_recipient);
@@ -1080,6 +1434,7 @@ mod tests {
             attempt: 3,
             amount: 3333,
             process_error: None,
+            replaceable_opt: None,
         };
         let fingerprint_3 = PendingPayableFingerprint {
             rowid: 456,
@@ -1088,6 +1443,7 @@ mod tests {
             attempt: 3,
             amount: 4565,
             process_error: None,
+            replaceable_opt: None,
         };
         let fingerprint_4 = PendingPayableFingerprint {
             rowid: 450,
@@ -1096,6 +1452,7 @@ mod tests {
             attempt: 1,
             amount: 7879,
             process_error: None,
+            replaceable_opt: None,
         };
         let mut transaction_receipt = TransactionReceipt::default();
         transaction_receipt.block_number = Some(U64::from(4545454));
@@ -1111,6 +1468,7 @@ mod tests {
         let mut subject = BlockchainBridge::new(
             Box::new(blockchain_interface_mock),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         subject
@@ -1124,6 +1482,7 @@ mod tests {
                 fingerprint_3,
                 fingerprint_4,
             ],
+            stuck_transactions_opt: None,
             response_skeleton_opt: Some(ResponseSkeleton {
                 client_id: 1234,
                 context_id: 4321,
@@ -1173,6 +1532,7 @@ mod tests {
         let mut subject = BlockchainBridge::new(
             Box::new(BlockchainInterfaceMock::default()),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         subject
@@ -1180,6 +1540,7 @@ mod tests {
             .report_transaction_receipts_sub_opt = Some(recipient);
         let msg = RequestTransactionReceipts {
             pending_payable: vec![],
+            stuck_transactions_opt: None,
             response_skeleton_opt: None,
         };
         let system = System::new(
@@ -1218,6 +1579,7 @@ mod tests {
             attempt: 3,
             amount: 3333,
             process_error: None,
+            replaceable_opt: None,
         };
         let fingerprint_2 = PendingPayableFingerprint {
             rowid: 456,
@@ -1226,6 +1588,7 @@ mod tests {
             attempt: 3,
             amount: 4565,
             process_error: None,
+            replaceable_opt: None,
         };
         let blockchain_interface_mock = BlockchainInterfaceMock::default()
             .get_transaction_receipt_params(&get_transaction_receipt_params_arc)
@@ -1233,6 +1596,7 @@ mod tests {
         let mut subject = BlockchainBridge::new(
             Box::new(blockchain_interface_mock),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         subject
@@ -1242,6 +1606,7 @@ mod tests {
         subject.scan_error_subs_opt = Some(scan_error_recipient);
         let msg = RequestTransactionReceipts {
             pending_payable: vec![fingerprint_1, fingerprint_2],
+            stuck_transactions_opt: None,
             response_skeleton_opt: None,
         };
         let system = System::new("test");
@@ -1318,6 +1683,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(blockchain_interface_mock),
             Box::new(persistent_config),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         let addr = subject.start();
@@ -1407,6 +1773,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(blockchain_interface_mock),
             Box::new(persistent_config),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         let addr = subject.start();
@@ -1492,6 +1859,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(blockchain_interface),
             Box::new(persistent_config),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         let addr = subject.start();
@@ -1572,6 +1940,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(blockchain_interface_mock),
             Box::new(persistent_config),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         let addr = subject.start();
@@ -1640,6 +2009,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(blockchain_interface_mock),
             Box::new(persistent_config),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         let addr = subject.start();
@@ -1693,6 +2063,7 @@ mod tests {
         let mut subject = BlockchainBridge::new(
             Box::new(blockchain_interface),
             Box::new(persistent_config),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         let retrieve_transactions = RetrieveTransactions {
@@ -1723,6 +2094,7 @@ mod tests {
         let mut subject = BlockchainBridge::new(
             Box::new(BlockchainInterfaceMock::default()),
             Box::new(PersistentConfigurationMock::new()),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         let system = System::new("test");
@@ -1754,6 +2126,7 @@ mod tests {
         let mut subject = BlockchainBridge::new(
             Box::new(BlockchainInterfaceMock::default()),
             Box::new(PersistentConfigurationMock::new()),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         let system = System::new("test");
@@ -1792,6 +2165,7 @@ mod tests {
         let mut subject = BlockchainBridge::new(
             Box::new(BlockchainInterfaceMock::default()),
             Box::new(PersistentConfigurationMock::new()),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         let system = System::new("test");
@@ -1836,6 +2210,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(BlockchainInterfaceMock::default()),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(PendingPayableDaoMock::new()),
             crashable,
         );
 
@@ -1848,6 +2223,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(BlockchainInterfaceMock::default()),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         let max_block_count = subject.extract_max_block_count(result);
@@ -1861,6 +2237,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(BlockchainInterfaceMock::default()),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         let max_block_count = subject.extract_max_block_count(result);
@@ -1881,6 +2258,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(BlockchainInterfaceMock::default()),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         let max_block_count = subject.extract_max_block_count(result);
@@ -1898,6 +2276,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(BlockchainInterfaceMock::default()),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         let max_block_count = subject.extract_max_block_count(result);
@@ -1915,6 +2294,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(BlockchainInterfaceMock::default()),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         let max_block_count = subject.extract_max_block_count(result);
@@ -1934,6 +2314,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(BlockchainInterfaceMock::default()),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         let max_block_count = subject.extract_max_block_count(result);
@@ -1947,6 +2328,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(BlockchainInterfaceMock::default()),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         let max_block_count = subject.extract_max_block_count(result);
@@ -1962,6 +2344,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(BlockchainInterfaceMock::default()),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         let max_block_count = subject.extract_max_block_count(result);
@@ -1982,6 +2365,20 @@ mod tests {
 
         assert_on_initialization_with_panic_on_migration(&data_dir, &act);
     }
+
+    #[test]
+    fn pending_payable_dao_connection_implements_panic_on_migration() {
+        let data_dir = ensure_node_home_directory_exists(
+            "blockchain_bridge",
+            "pending_payable_dao_connection_implements_panic_on_migration",
+        );
+
+        let act = |data_dir: &Path| {
+            BlockchainBridge::initialize_pending_payable_dao(data_dir);
+        };
+
+        assert_on_initialization_with_panic_on_migration(&data_dir, &act);
+    }
 }
 
 #[cfg(test)]
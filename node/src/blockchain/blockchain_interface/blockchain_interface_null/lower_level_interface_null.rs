@@ -2,7 +2,7 @@
 
 use crate::blockchain::blockchain_interface::data_structures::errors::BlockchainError;
 use crate::blockchain::blockchain_interface::lower_level_interface::{
-    LatestBlockNumber, LowBlockchainInt, ResultForBalance, ResultForNonce,
+    LatestBlockNumber, LowBlockchainInt, ResultForBalance, ResultForFeeHistory, ResultForNonce,
 };
 use crate::sub_lib::wallet::Wallet;
 use masq_lib::logger::Logger;
@@ -27,6 +27,10 @@ impl LowBlockchainInt for LowBlockChainIntNull {
     fn get_transaction_id(&self, _wallet: &Wallet) -> ResultForNonce {
         Err(self.handle_null_call("transaction id"))
     }
+
+    fn get_fee_history(&self, _block_count: u64, _reward_percentile: u8) -> ResultForFeeHistory {
+        Err(self.handle_null_call("fee history"))
+    }
 }
 
 impl LowBlockChainIntNull {
@@ -90,6 +94,15 @@ mod tests {
         test_null_method(test_name, act, "transaction id");
     }
 
+    #[test]
+    fn low_bci_null_gets_no_fee_history() {
+        let test_name = "low_bci_null_gets_no_fee_history";
+        let act =
+            |subject: &LowBlockChainIntNull, _wallet: &Wallet| subject.get_fee_history(20, 50);
+
+        test_null_method(test_name, act, "fee history");
+    }
+
     fn test_null_method<T: Debug + PartialEq>(
         test_name: &str,
         act: fn(&LowBlockChainIntNull, &Wallet) -> Result<T, BlockchainError>,
@@ -3,8 +3,9 @@
 pub mod lower_level_interface_null;
 
 use crate::accountant::db_access_objects::payable_dao::PayableAccount;
+use crate::accountant::db_access_objects::pending_payable_dao::PendingPayableDao;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
-use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
+use crate::blockchain::blockchain_bridge::{PendingPayableFingerprint, PendingPayableFingerprintSeeds, TransactionReplacement};
 use crate::blockchain::blockchain_interface::blockchain_interface_null::lower_level_interface_null::LowBlockChainIntNull;
 use crate::blockchain::blockchain_interface::lower_level_interface::LowBlockchainInt;
 use crate::db_config::persistent_configuration::PersistentConfiguration;
@@ -57,6 +58,16 @@ impl BlockchainInterface for BlockchainInterfaceNull {
         self.handle_uninitialized_interface("get transaction receipt")
     }
 
+    fn replace_transaction(
+        &self,
+        _consuming_wallet: &Wallet,
+        _stuck_transaction: &PendingPayableFingerprint,
+        _persistent_config: &dyn PersistentConfiguration,
+        _pending_payable_dao: &dyn PendingPayableDao,
+    ) -> Result<TransactionReplacement, PayableTransactionError> {
+        self.handle_uninitialized_interface("replace transaction")
+    }
+
     fn lower_interface(&self) -> &dyn LowBlockchainInt {
         error!(
             self.logger,
@@ -131,7 +142,9 @@ impl BlockchainInterfaceNull {
 #[cfg(test)]
 mod tests {
     use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::agent_null::BlockchainAgentNull;
-    use crate::accountant::test_utils::make_payable_account;
+    use crate::accountant::test_utils::{
+        make_payable_account, make_pending_payable_fingerprint, PendingPayableDaoMock,
+    };
     use crate::blockchain::blockchain_interface::blockchain_interface_null::lower_level_interface_null::LowBlockChainIntNull;
     use crate::blockchain::blockchain_interface::blockchain_interface_null::{
         BlockchainInterfaceNull, BlockchainInterfaceUninitializedError,
@@ -252,6 +265,31 @@ mod tests {
         TestLogHandler::new().exists_log_containing(expected_log_msg.as_str());
     }
 
+    #[test]
+    fn blockchain_interface_null_cannot_replace_transaction() {
+        init_test_logging();
+        let test_name = "blockchain_interface_null_cannot_replace_transaction";
+        let wallet = make_wallet("blah");
+        let persistent_config = PersistentConfigurationMock::new();
+
+        let result = make_subject(test_name).replace_transaction(
+            &wallet,
+            &make_pending_payable_fingerprint(),
+            &persistent_config,
+            &PendingPayableDaoMock::new(),
+        );
+
+        assert_eq!(
+            result,
+            Err(PayableTransactionError::UninitializedBlockchainInterface)
+        );
+        let expected_log_msg = format!(
+            "ERROR: {test_name}: Failed to replace transaction with uninitialized blockchain \
+            interface. Parameter blockchain-service-url is missing."
+        );
+        TestLogHandler::new().exists_log_containing(expected_log_msg.as_str());
+    }
+
     #[test]
     fn blockchain_interface_null_gives_null_lower_interface() {
         init_test_logging();
@@ -1,16 +1,89 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
-use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
+use crate::blockchain::blockchain_bridge::{
+    PendingPayableFingerprintSeeds, ReplaceableTransaction,
+};
 use actix::Recipient;
+use ethsign_crypto::Keccak256;
 use futures::Future;
+use rlp::RlpStream;
+use secp256k1secrets::key::SecretKey;
+use secp256k1secrets::{Message, Secp256k1};
 use serde_json::Value;
 use std::fmt::Debug;
 use std::marker::PhantomData;
 use std::time::SystemTime;
 use web3::transports::Batch;
-use web3::types::{Bytes, SignedTransaction, TransactionParameters, H256};
+use web3::types::{Address, Bytes, SignedTransaction, TransactionParameters, H256, U256};
 use web3::{BatchTransport, Error as Web3Error, Web3};
 
+const EIP1559_TRANSACTION_TYPE: u8 = 0x02;
+
+// web3 of this version can sign only legacy transactions; this carries what a type-2 one needs
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Eip1559TransactionParameters {
+    pub chain_id: u64,
+    pub nonce: U256,
+    pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub gas: U256,
+    pub to: Address,
+    pub value: U256,
+    pub data: Bytes,
+}
+
+impl Eip1559TransactionParameters {
+    fn rlp_append_unsigned(&self, stream: &mut RlpStream) {
+        stream.append(&self.chain_id);
+        stream.append(&self.nonce);
+        stream.append(&self.max_priority_fee_per_gas);
+        stream.append(&self.max_fee_per_gas);
+        stream.append(&self.gas);
+        stream.append(&self.to);
+        stream.append(&self.value);
+        stream.append(&self.data.0);
+        // An empty access list
+        stream.begin_list(0);
+    }
+
+    fn typed_payload(rlp_stream: RlpStream) -> Vec<u8> {
+        let mut payload = vec![EIP1559_TRANSACTION_TYPE];
+        payload.extend(rlp_stream.out());
+        payload
+    }
+
+    pub fn sign(&self, key: &SecretKey) -> Result<SignedTransaction, Web3Error> {
+        let mut unsigned = RlpStream::new_list(9);
+        self.rlp_append_unsigned(&mut unsigned);
+        let message_hash = H256(Self::typed_payload(unsigned).keccak256());
+
+        let message = Message::from_slice(&message_hash.0).map_err(Web3Error::Signing)?;
+        let (recovery_id, signature) = Secp256k1::signing_only()
+            .sign_recoverable(&message, key)
+            .serialize_compact();
+        let y_parity = recovery_id.to_i32() as u64;
+        let r = H256::from_slice(&signature[..32]);
+        let s = H256::from_slice(&signature[32..]);
+
+        let mut signed = RlpStream::new_list(12);
+        self.rlp_append_unsigned(&mut signed);
+        signed.append(&y_parity);
+        signed.append(&U256::from_big_endian(&r.0));
+        signed.append(&U256::from_big_endian(&s.0));
+        let raw_transaction = Self::typed_payload(signed);
+        let transaction_hash = H256(raw_transaction.keccak256());
+
+        Ok(SignedTransaction {
+            message_hash,
+            v: y_parity,
+            r,
+            s,
+            raw_transaction: Bytes(raw_transaction),
+            transaction_hash,
+        })
+    }
+}
+
 pub trait BatchPayableTools<T>
 where
     T: BatchTransport,
@@ -21,6 +94,11 @@ where
         web3: &Web3<Batch<T>>,
         key: &secp256k1secrets::key::SecretKey,
     ) -> Result<SignedTransaction, Web3Error>;
+    fn sign_eip1559_transaction(
+        &self,
+        transaction_params: Eip1559TransactionParameters,
+        key: &secp256k1secrets::key::SecretKey,
+    ) -> Result<SignedTransaction, Web3Error>;
     fn append_transaction_to_batch(&self, signed_transaction: Bytes, web3: &Web3<Batch<T>>);
     fn batch_wide_timestamp(&self) -> SystemTime;
     fn send_new_payable_fingerprints_seeds(
@@ -28,6 +106,7 @@ where
         batch_wide_timestamp: SystemTime,
         new_pp_fingerprints_sub: &Recipient<PendingPayableFingerprintSeeds>,
         hashes_and_balances: &[(H256, u128)],
+        replaceable_transactions: &[(H256, ReplaceableTransaction)],
     );
     fn submit_batch(
         &self,
@@ -60,6 +139,14 @@ impl<T: BatchTransport + Debug> BatchPayableTools<T> for BatchPayableToolsReal<T
             .wait()
     }
 
+    fn sign_eip1559_transaction(
+        &self,
+        transaction_params: Eip1559TransactionParameters,
+        key: &secp256k1secrets::key::SecretKey,
+    ) -> Result<SignedTransaction, Web3Error> {
+        transaction_params.sign(key)
+    }
+
     fn append_transaction_to_batch(&self, signed_transaction: Bytes, web3: &Web3<Batch<T>>) {
         let _ = web3.eth().send_raw_transaction(signed_transaction);
     }
@@ -73,11 +160,13 @@ impl<T: BatchTransport + Debug> BatchPayableTools<T> for BatchPayableToolsReal<T
         batch_wide_timestamp: SystemTime,
         pp_fingerprint_sub: &Recipient<PendingPayableFingerprintSeeds>,
         hashes_and_balances: &[(H256, u128)],
+        replaceable_transactions: &[(H256, ReplaceableTransaction)],
     ) {
         pp_fingerprint_sub
             .try_send(PendingPayableFingerprintSeeds {
                 batch_wide_timestamp,
                 hashes_and_balances: hashes_and_balances.to_vec(),
+                replaceable_transactions: replaceable_transactions.to_vec(),
             })
             .expect("Accountant is dead");
     }
@@ -92,14 +181,25 @@ impl<T: BatchTransport + Debug> BatchPayableTools<T> for BatchPayableToolsReal<T
 
 #[cfg(test)]
 mod tests {
-    use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
+    use crate::blockchain::bip32::Bip32EncryptionKeyProvider;
+    use crate::blockchain::blockchain_bridge::{
+        PendingPayableFingerprintSeeds, ReplaceableTransaction,
+    };
     use crate::blockchain::blockchain_interface::blockchain_interface_web3::batch_payable_tools::{
-        BatchPayableTools, BatchPayableToolsReal,
+        BatchPayableTools, BatchPayableToolsReal, Eip1559TransactionParameters,
     };
     use crate::blockchain::test_utils::{make_tx_hash, TestTransport};
+    use crate::sub_lib::blockchain_bridge::TransactionFees;
+    use crate::sub_lib::wallet::Wallet;
+    use crate::test_utils::make_wallet;
     use crate::test_utils::recorder::make_recorder;
     use actix::{Actor, System};
+    use ethsign_crypto::Keccak256;
+    use rlp::Rlp;
+    use secp256k1secrets::recovery::{RecoverableSignature, RecoveryId};
+    use secp256k1secrets::{Message, Secp256k1};
     use std::time::SystemTime;
+    use web3::types::{Address, Bytes, H256, U256};
 
     #[test]
     fn request_new_payable_fingerprints_works() {
@@ -107,9 +207,25 @@ mod tests {
         let recipient = accountant.start().recipient();
         let timestamp = SystemTime::now();
         let hashes_and_balances = vec![(make_tx_hash(123), 5), (make_tx_hash(45466), 444444)];
+        let replaceable_transactions = vec![(
+            make_tx_hash(123),
+            ReplaceableTransaction {
+                recipient_wallet: make_wallet("creditor"),
+                nonce: 7,
+                transaction_fees: TransactionFees::Legacy {
+                    gas_price_wei: 1_000_000_000,
+                },
+                replacement_hashes: vec![],
+            },
+        )];
 
         let _ = BatchPayableToolsReal::<TestTransport>::default()
-            .send_new_payable_fingerprints_seeds(timestamp, &recipient, &hashes_and_balances);
+            .send_new_payable_fingerprints_seeds(
+                timestamp,
+                &recipient,
+                &hashes_and_balances,
+                &replaceable_transactions,
+            );
 
         let system = System::new("new fingerprints");
         System::current().stop();
@@ -120,7 +236,8 @@ mod tests {
             message,
             &PendingPayableFingerprintSeeds {
                 batch_wide_timestamp: timestamp,
-                hashes_and_balances
+                hashes_and_balances,
+                replaceable_transactions
             }
         )
     }
@@ -141,4 +258,68 @@ mod tests {
             after
         )
     }
+
+    #[test]
+    fn eip1559_transaction_is_typed_and_signed_by_the_consuming_wallet() {
+        let consuming_wallet =
+            Wallet::from(Bip32EncryptionKeyProvider::from_raw_secret(&[0x46; 32]).unwrap());
+        let key = consuming_wallet.prepare_secp256k1_secret().unwrap();
+        let params = Eip1559TransactionParameters {
+            chain_id: 8453,
+            nonce: U256::from(9),
+            max_priority_fee_per_gas: U256::from(1_000_000),
+            max_fee_per_gas: U256::from(30_000_000),
+            gas: U256::from(72_000),
+            to: Address::from_low_u64_be(0x1234),
+            value: U256::zero(),
+            data: Bytes(vec![0xa9, 0x05, 0x9c, 0xbb, 0x00, 0x01]),
+        };
+
+        let result = BatchPayableToolsReal::<TestTransport>::default()
+            .sign_eip1559_transaction(params.clone(), &key)
+            .unwrap();
+
+        let raw = &result.raw_transaction.0;
+        assert_eq!(raw[0], 0x02);
+        assert_eq!(result.transaction_hash, H256(raw.keccak256()));
+        let decoded = Rlp::new(&raw[1..]);
+        assert_eq!(decoded.item_count().unwrap(), 12);
+        assert_eq!(decoded.val_at::<u64>(0).unwrap(), 8453);
+        assert_eq!(decoded.val_at::<U256>(1).unwrap(), params.nonce);
+        assert_eq!(
+            decoded.val_at::<U256>(2).unwrap(),
+            params.max_priority_fee_per_gas
+        );
+        assert_eq!(decoded.val_at::<U256>(3).unwrap(), params.max_fee_per_gas);
+        assert_eq!(decoded.val_at::<U256>(4).unwrap(), params.gas);
+        assert_eq!(decoded.val_at::<Address>(5).unwrap(), params.to);
+        assert_eq!(decoded.val_at::<U256>(6).unwrap(), params.value);
+        assert_eq!(decoded.val_at::<Vec<u8>>(7).unwrap(), params.data.0);
+        assert_eq!(decoded.at(8).unwrap().item_count().unwrap(), 0);
+        assert_eq!(decoded.val_at::<u64>(9).unwrap(), result.v);
+        assert_eq!(
+            decoded.val_at::<U256>(10).unwrap(),
+            U256::from_big_endian(&result.r.0)
+        );
+        assert_eq!(
+            decoded.val_at::<U256>(11).unwrap(),
+            U256::from_big_endian(&result.s.0)
+        );
+        let mut compact_signature = result.r.0.to_vec();
+        compact_signature.extend_from_slice(&result.s.0);
+        let signature = RecoverableSignature::from_compact(
+            &compact_signature,
+            RecoveryId::from_i32(result.v as i32).unwrap(),
+        )
+        .unwrap();
+        let public_key = Secp256k1::verification_only()
+            .recover(
+                &Message::from_slice(&result.message_hash.0).unwrap(),
+                &signature,
+            )
+            .unwrap();
+        let public_key_bytes = public_key.serialize_uncompressed()[1..].to_vec();
+        let signer = Address::from_slice(&public_key_bytes.keccak256()[12..]);
+        assert_eq!(signer, consuming_wallet.address())
+    }
 }
@@ -1,11 +1,13 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 use crate::blockchain::blockchain_interface::data_structures::errors::BlockchainError;
+use crate::blockchain::blockchain_interface::data_structures::FeeHistory;
 use crate::blockchain::blockchain_interface::lower_level_interface::{
-    LatestBlockNumber, LowBlockchainInt, ResultForBalance, ResultForNonce,
+    LatestBlockNumber, LowBlockchainInt, ResultForBalance, ResultForFeeHistory, ResultForNonce,
 };
 use crate::sub_lib::wallet::Wallet;
 use futures::Future;
+use serde_json::json;
 use std::rc::Rc;
 use web3::contract::{Contract, Options};
 use web3::transports::Batch;
@@ -62,6 +64,24 @@ where
             .map_err(|e| BlockchainError::QueryFailed(format!("{} for wallet {}", e, wallet)))
             .wait()
     }
+
+    fn get_fee_history(&self, block_count: u64, reward_percentile: u8) -> ResultForFeeHistory {
+        // web3 of this version doesn't know eth_feeHistory yet
+        let params = vec![
+            json!(format!("{:#x}", block_count)),
+            json!("latest"),
+            json!([reward_percentile]),
+        ];
+        self.web3
+            .transport()
+            .execute("eth_feeHistory", params)
+            .map_err(|e| BlockchainError::QueryFailed(e.to_string()))
+            .wait()
+            .and_then(|value| {
+                serde_json::from_value::<FeeHistory>(value)
+                    .map_err(|e| BlockchainError::QueryFailed(format!("Decoder error: {}", e)))
+            })
+    }
 }
 
 impl<T> LowBlockchainIntWeb3<T>
@@ -85,6 +105,7 @@ mod tests {
     };
     use crate::blockchain::blockchain_interface::lower_level_interface::{LowBlockchainInt, ResultForBalance};
     use crate::blockchain::blockchain_interface::BlockchainError;
+    use crate::blockchain::blockchain_interface::data_structures::FeeHistory;
     use crate::sub_lib::wallet::Wallet;
     use crate::test_utils::http_test_server::TestServer;
     use crate::test_utils::make_paying_wallet;
@@ -345,6 +366,60 @@ mod tests {
         assert_error_from_unintelligible_response(act, "invalid hex character")
     }
 
+    #[test]
+    fn low_interface_web3_get_fee_history_works() {
+        let prepare_params_arc = Arc::new(Mutex::new(vec![]));
+        let transport = TestTransport::default()
+            .prepare_params(&prepare_params_arc)
+            .send_result(json!({
+                "oldestBlock": "0x1e37064",
+                "baseFeePerGas": ["0x3b9aca00", "0x3b9aca01", "0x3b9aca02"],
+                "gasUsedRatio": [0.5, 0.6],
+                "reward": [["0x5f5e100"], ["0x77359400"]]
+            }));
+        let subject = make_subject(transport, TEST_DEFAULT_CHAIN);
+
+        let result = subject.get_fee_history(2, 50);
+
+        assert_eq!(
+            result,
+            Ok(FeeHistory {
+                base_fee_per_gas: vec![
+                    U256::from(1_000_000_000),
+                    U256::from(1_000_000_001),
+                    U256::from(1_000_000_002)
+                ],
+                reward: vec![
+                    vec![U256::from(100_000_000)],
+                    vec![U256::from(2_000_000_000)]
+                ]
+            })
+        );
+        let mut prepare_params = prepare_params_arc.lock().unwrap();
+        let (method_name, actual_arguments) = prepare_params.remove(0);
+        assert!(prepare_params.is_empty());
+        assert_eq!(method_name, "eth_feeHistory".to_string());
+        assert_eq!(
+            actual_arguments,
+            vec![json!("0x2"), json!("latest"), json!([50])]
+        );
+    }
+
+    #[test]
+    fn low_interface_web3_get_fee_history_handles_unintelligible_response() {
+        let transport = TestTransport::default().send_result(json!({"gasUsedRatio": [0.5]}));
+        let subject = make_subject(transport, TEST_DEFAULT_CHAIN);
+
+        let result = subject.get_fee_history(1, 50);
+
+        assert_eq!(
+            result,
+            Err(BlockchainError::QueryFailed(
+                "Decoder error: missing field `baseFeePerGas`".to_string()
+            ))
+        );
+    }
+
     fn assert_error_from_unintelligible_response<F>(act: F, expected_err_fragment: &str)
     where
         F: FnOnce(&LowBlockchainIntWeb3<Http>, &Wallet) -> ResultForBalance,
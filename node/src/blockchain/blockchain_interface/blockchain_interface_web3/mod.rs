@@ -8,16 +8,16 @@ use crate::accountant::db_access_objects::payable_dao::{PayableAccount};
 use crate::accountant::{gwei_to_wei};
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::agent_web3::BlockchainAgentWeb3;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
-use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
+use crate::blockchain::blockchain_bridge::{PendingPayableFingerprint, PendingPayableFingerprintSeeds, ReplaceableTransaction, TransactionReplacement};
 use crate::blockchain::blockchain_interface::blockchain_interface_web3::batch_payable_tools::{
-    BatchPayableTools, BatchPayableToolsReal,
+    BatchPayableTools, BatchPayableToolsReal, Eip1559TransactionParameters,
 };
 use crate::blockchain::blockchain_interface::blockchain_interface_web3::lower_level_interface_web3::LowBlockchainIntWeb3;
 use crate::blockchain::blockchain_interface::lower_level_interface::LowBlockchainInt;
 use crate::blockchain::blockchain_interface::{BlockchainAgentBuildError, BlockchainError, BlockchainInterface, PayableTransactionError, ResultForReceipt, RetrievedBlockchainTransactions};
 use crate::db_config::persistent_configuration::PersistentConfiguration;
 use crate::masq_lib::utils::ExpectValue;
-use crate::sub_lib::blockchain_bridge::ConsumingWalletBalances;
+use crate::sub_lib::blockchain_bridge::{ConsumingWalletBalances, TransactionFees};
 use crate::sub_lib::wallet::Wallet;
 use actix::Recipient;
 use futures::Future;
@@ -28,6 +28,7 @@ use serde_json::Value;
 use std::fmt::Debug;
 use std::iter::once;
 use std::rc::Rc;
+use std::time::SystemTime;
 use thousands::Separable;
 use web3::contract::Contract;
 use web3::transports::{Batch, EventLoopHandle};
@@ -36,8 +37,8 @@ use web3::types::{
     H160, H256, U256,
 };
 use web3::{BatchTransport, Error, Web3};
-use crate::accountant::db_access_objects::pending_payable_dao::PendingPayable;
-use crate::blockchain::blockchain_interface::data_structures::{BlockchainTransaction, ProcessedPayableFallible, RpcPayablesFailure};
+use crate::accountant::db_access_objects::pending_payable_dao::{PendingPayable, PendingPayableDao};
+use crate::blockchain::blockchain_interface::data_structures::{BlockchainTransaction, FeeHistory, ProcessedPayableFallible, RpcPayablesFailure};
 
 const CONTRACT_ABI: &str = indoc!(
     r#"[{
@@ -68,6 +69,13 @@ const TRANSFER_METHOD_ID: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
 
 pub const REQUESTS_IN_PARALLEL: usize = 1;
 
+pub const FEE_HISTORY_BLOCK_COUNT: u64 = 10;
+pub const FEE_HISTORY_REWARD_PERCENTILE: u8 = 50;
+
+// Transactions are signed at the configured gas price at most, but a stuck one may be outbid up to
+// this multiple of it
+pub const REPLACEMENT_FEE_CEILING_MULTIPLE: u128 = 2;
+
 pub struct BlockchainInterfaceWeb3<T>
 where
     T: 'static + BatchTransport + Debug,
@@ -209,7 +217,7 @@ where
         consuming_wallet: &Wallet,
         persistent_config: &dyn PersistentConfiguration,
     ) -> Result<Box<dyn BlockchainAgent>, BlockchainAgentBuildError> {
-        let gas_price_gwei = match persistent_config.gas_price() {
+        let gas_price_cap_gwei = match persistent_config.gas_price() {
             Ok(price) => price,
             Err(e) => return Err(BlockchainAgentBuildError::GasPrice(e)),
         };
@@ -251,6 +259,8 @@ where
             }
         };
 
+        let transaction_fees = self.compute_transaction_fees(gas_price_cap_gwei);
+
         let consuming_wallet_balances = ConsumingWalletBalances {
             transaction_fee_balance_in_minor_units: transaction_fee_balance,
             masq_token_balance_in_minor_units: masq_token_balance,
@@ -258,7 +268,7 @@ where
         let consuming_wallet = consuming_wallet.clone();
 
         Ok(Box::new(BlockchainAgentWeb3::new(
-            gas_price_gwei,
+            transaction_fees,
             self.gas_limit_const_part,
             consuming_wallet,
             consuming_wallet_balances,
@@ -273,36 +283,43 @@ where
         accounts: &[PayableAccount],
     ) -> Result<Vec<ProcessedPayableFallible>, PayableTransactionError> {
         let consuming_wallet = agent.consuming_wallet();
-        let gas_price = agent.agreed_fee_per_computation_unit();
+        let transaction_fees = agent.agreed_transaction_fees();
         let pending_nonce = agent.pending_transaction_id();
 
         debug!(
             self.logger,
-            "Common attributes of payables to be transacted: sender wallet: {}, contract: {:?}, chain_id: {}, gas_price: {}",
+            "Common attributes of payables to be transacted: sender wallet: {}, contract: {:?}, chain_id: {}, fees: {}",
             consuming_wallet,
             self.chain.rec().contract,
             self.chain.rec().num_chain_id,
-            gas_price
+            transaction_fees
         );
 
         let hashes_and_paid_amounts = self.sign_and_append_multiple_payments(
             consuming_wallet,
-            gas_price,
+            transaction_fees,
             pending_nonce,
             accounts,
         )?;
+        let replaceable_transactions = Self::replaceable_transactions(
+            &hashes_and_paid_amounts,
+            accounts,
+            pending_nonce,
+            transaction_fees,
+        );
         let timestamp = self.batch_payable_tools.batch_wide_timestamp();
         self.batch_payable_tools
             .send_new_payable_fingerprints_seeds(
                 timestamp,
                 new_fingerprints_recipient,
                 &hashes_and_paid_amounts,
+                &replaceable_transactions,
             );
 
         info!(
             self.logger,
             "{}",
-            self.transmission_log(accounts, gas_price)
+            self.transmission_log(accounts, transaction_fees)
         );
 
         match self.batch_payable_tools.submit_batch(&self.web3_batch) {
@@ -323,6 +340,96 @@ where
             .wait()
     }
 
+    fn replace_transaction(
+        &self,
+        consuming_wallet: &Wallet,
+        stuck_transaction: &PendingPayableFingerprint,
+        persistent_config: &dyn PersistentConfiguration,
+        pending_payable_dao: &dyn PendingPayableDao,
+    ) -> Result<TransactionReplacement, PayableTransactionError> {
+        let replaceable = match stuck_transaction.replaceable_opt.as_ref() {
+            Some(replaceable) => replaceable,
+            None => {
+                return Err(PayableTransactionError::Unreplaceable(format!(
+                    "signing details of {:?} were not kept",
+                    stuck_transaction.hash
+                )))
+            }
+        };
+        let gas_price_cap_gwei = persistent_config
+            .gas_price()
+            .map_err(|e| PayableTransactionError::GasPriceQueryFailed(format!("{:?}", e)))?;
+        let transaction_fees = replaceable
+            .transaction_fees
+            .outbidding(self.compute_transaction_fees(gas_price_cap_gwei));
+        let replacement_ceiling_wei =
+            gwei_to_wei::<u128, _>(gas_price_cap_gwei) * REPLACEMENT_FEE_CEILING_MULTIPLE;
+        if transaction_fees.max_fee_per_gas_wei() > replacement_ceiling_wei {
+            return Err(PayableTransactionError::Unreplaceable(format!(
+                "outbidding {:?} takes {}, more than the replacement ceiling of {} times the \
+                configured gas price of {} gwei allows",
+                stuck_transaction.hash,
+                transaction_fees,
+                REPLACEMENT_FEE_CEILING_MULTIPLE,
+                gas_price_cap_gwei
+            )));
+        }
+
+        let signed_tx = self.sign_transaction(
+            &replaceable.recipient_wallet,
+            consuming_wallet,
+            stuck_transaction.amount,
+            U256::from(replaceable.nonce),
+            transaction_fees,
+        )?;
+        let replacement = TransactionReplacement {
+            rowid: stuck_transaction.rowid,
+            hash: signed_tx.transaction_hash,
+            transaction_fees,
+            timestamp: SystemTime::now(),
+        };
+        pending_payable_dao
+            .record_replacement(&replacement)
+            .map_err(|e| {
+                PayableTransactionError::Unreplaceable(format!(
+                    "recording the replacement of {:?} failed: {:?}",
+                    stuck_transaction.hash, e
+                ))
+            })?;
+        let hash = replacement.hash;
+        self.batch_payable_tools
+            .append_transaction_to_batch(signed_tx.raw_transaction, &self.web3_batch);
+
+        info!(
+            self.logger,
+            "Replacing stuck transaction {:?} with nonce {} by {:?}; fees: {}",
+            stuck_transaction.hash,
+            replaceable.nonce,
+            hash,
+            transaction_fees
+        );
+
+        match self
+            .batch_payable_tools
+            .submit_batch(&self.web3_batch)
+            .map(|responses| responses.into_iter().next())
+        {
+            Ok(Some(Ok(_))) => Ok(replacement),
+            Ok(Some(Err(rpc_error))) => Err(PayableTransactionError::Sending {
+                msg: rpc_error.to_string(),
+                hashes: vec![hash],
+            }),
+            Ok(None) => Err(PayableTransactionError::Sending {
+                msg: "No response to the replacement".to_string(),
+                hashes: vec![hash],
+            }),
+            Err(e) => Err(Self::error_with_hashes(
+                e,
+                vec![(hash, stuck_transaction.amount)],
+            )),
+        }
+    }
+
     fn lower_interface(&self) -> &dyn LowBlockchainInt {
         &*self.lower_interface
     }
@@ -361,7 +468,7 @@ where
     fn sign_and_append_multiple_payments(
         &self,
         consuming_wallet: &Wallet,
-        gas_price: u64,
+        transaction_fees: TransactionFees,
         pending_nonce: U256,
         accounts: &[PayableAccount],
     ) -> HashAndAmountResult {
@@ -376,7 +483,7 @@ where
                         pending_nonce_opt,
                         hashes_and_amounts,
                         consuming_wallet,
-                        gas_price,
+                        transaction_fees,
                         account,
                     )
                 } else {
@@ -392,7 +499,7 @@ where
         pending_nonce_opt: Option<U256>,
         hashes_and_amounts: Vec<(H256, u128)>,
         consuming_wallet: &Wallet,
-        gas_price: u64,
+        transaction_fees: TransactionFees,
         account: &PayableAccount,
     ) -> (HashAndAmountResult, Option<U256>) {
         let nonce = pending_nonce_opt.expectv("pending nonce");
@@ -400,7 +507,7 @@ where
             hashes_and_amounts,
             consuming_wallet,
             nonce,
-            gas_price,
+            transaction_fees,
             account,
         );
         let advanced_nonce = Self::advance_used_nonce(nonce);
@@ -415,7 +522,7 @@ where
         mut hashes_and_amounts: Vec<(H256, u128)>,
         consuming_wallet: &Wallet,
         nonce: U256,
-        gas_price: u64,
+        transaction_fees: TransactionFees,
         account: &PayableAccount,
     ) -> HashAndAmountResult {
         debug!(
@@ -431,7 +538,7 @@ where
             consuming_wallet,
             account.balance_wei,
             nonce,
-            gas_price,
+            transaction_fees,
         ) {
             Ok(new_hash) => {
                 hashes_and_amounts.push((new_hash, account.balance_wei));
@@ -441,6 +548,31 @@ where
         }
     }
 
+    // Transactions of the batch were signed one after another, each with the next nonce
+    fn replaceable_transactions(
+        hashes_and_paid_amounts: &[(H256, u128)],
+        accounts: &[PayableAccount],
+        pending_nonce: U256,
+        transaction_fees: TransactionFees,
+    ) -> Vec<(H256, ReplaceableTransaction)> {
+        hashes_and_paid_amounts
+            .iter()
+            .zip(accounts.iter())
+            .enumerate()
+            .map(|(idx, ((hash, _), account))| {
+                (
+                    *hash,
+                    ReplaceableTransaction {
+                        recipient_wallet: account.wallet.clone(),
+                        nonce: pending_nonce.as_u64() + idx as u64,
+                        transaction_fees,
+                        replacement_hashes: vec![],
+                    },
+                )
+            })
+            .collect()
+    }
+
     fn advance_used_nonce(current_nonce: U256) -> U256 {
         current_nonce
             .checked_add(U256::one())
@@ -491,10 +623,10 @@ where
         consuming_wallet: &'a Wallet,
         amount: u128,
         nonce: U256,
-        gas_price: u64,
+        transaction_fees: TransactionFees,
     ) -> Result<H256, PayableTransactionError> {
         let signed_tx =
-            self.sign_transaction(recipient, consuming_wallet, amount, nonce, gas_price)?;
+            self.sign_transaction(recipient, consuming_wallet, amount, nonce, transaction_fees)?;
         self.batch_payable_tools
             .append_transaction_to_batch(signed_tx.raw_transaction, &self.web3_batch);
         Ok(signed_tx.transaction_hash)
@@ -506,32 +638,128 @@ where
         consuming_wallet: &'a Wallet,
         amount: u128,
         nonce: U256,
-        gas_price: u64,
+        transaction_fees: TransactionFees,
     ) -> Result<SignedTransaction, PayableTransactionError> {
         let data = Self::transaction_data(recipient, amount);
         let gas_limit = self.compute_gas_limit(data.as_slice());
-        let gas_price = gwei_to_wei::<U256, _>(gas_price);
-        let transaction_parameters = TransactionParameters {
-            nonce: Some(nonce),
-            to: Some(H160(self.contract_address().0)),
-            gas: gas_limit,
-            gas_price: Some(gas_price),
-            value: ethereum_types::U256::zero(),
-            data: Bytes(data.to_vec()),
-            chain_id: Some(self.chain.rec().num_chain_id),
-        };
 
         let key = match consuming_wallet.prepare_secp256k1_secret() {
             Ok(secret) => secret,
             Err(e) => return Err(PayableTransactionError::UnusableWallet(e.to_string())),
         };
 
-        self.batch_payable_tools
-            .sign_transaction(transaction_parameters, &self.web3_batch, &key)
-            .map_err(|e| PayableTransactionError::Signing(e.to_string()))
+        match transaction_fees {
+            TransactionFees::Legacy { gas_price_wei } => {
+                let transaction_parameters = TransactionParameters {
+                    nonce: Some(nonce),
+                    to: Some(H160(self.contract_address().0)),
+                    gas: gas_limit,
+                    gas_price: Some(U256::from(gas_price_wei)),
+                    value: ethereum_types::U256::zero(),
+                    data: Bytes(data.to_vec()),
+                    chain_id: Some(self.chain.rec().num_chain_id),
+                };
+                self.batch_payable_tools.sign_transaction(
+                    transaction_parameters,
+                    &self.web3_batch,
+                    &key,
+                )
+            }
+            TransactionFees::Eip1559 {
+                max_fee_per_gas_wei,
+                max_priority_fee_per_gas_wei,
+            } => {
+                let transaction_parameters = Eip1559TransactionParameters {
+                    chain_id: self.chain.rec().num_chain_id,
+                    nonce,
+                    max_priority_fee_per_gas: U256::from(max_priority_fee_per_gas_wei),
+                    max_fee_per_gas: U256::from(max_fee_per_gas_wei),
+                    gas: gas_limit,
+                    to: H160(self.contract_address().0),
+                    value: ethereum_types::U256::zero(),
+                    data: Bytes(data.to_vec()),
+                };
+                self.batch_payable_tools
+                    .sign_eip1559_transaction(transaction_parameters, &key)
+            }
+        }
+        .map_err(|e| PayableTransactionError::Signing(e.to_string()))
     }
 
-    fn transmission_log(&self, accounts: &[PayableAccount], gas_price: u64) -> String {
+    fn compute_transaction_fees(&self, gas_price_cap_gwei: u64) -> TransactionFees {
+        let gas_price_cap_wei = gwei_to_wei::<u128, _>(gas_price_cap_gwei);
+        let legacy_fees = TransactionFees::Legacy {
+            gas_price_wei: gas_price_cap_wei,
+        };
+        match self
+            .lower_interface
+            .get_fee_history(FEE_HISTORY_BLOCK_COUNT, FEE_HISTORY_REWARD_PERCENTILE)
+        {
+            Ok(fee_history) => match Self::eip1559_fees(&fee_history, gas_price_cap_wei) {
+                Some(fees) => {
+                    if fees.max_fee_per_gas_wei() == gas_price_cap_wei {
+                        warning!(
+                            self.logger,
+                            "Fees suggested by the fee history are limited by the configured \
+                            gas price of {} gwei; transactions may take long to get mined",
+                            gas_price_cap_gwei
+                        )
+                    }
+                    fees
+                }
+                None => {
+                    debug!(
+                        self.logger,
+                        "Fee history shows no base fee; paying by legacy transactions"
+                    );
+                    legacy_fees
+                }
+            },
+            Err(e) => {
+                warning!(
+                    self.logger,
+                    "Unable to fetch the fee history ({:?}); falling back to legacy \
+                    transactions with the configured gas price of {} gwei",
+                    e,
+                    gas_price_cap_gwei
+                );
+                legacy_fees
+            }
+        }
+    }
+
+    fn eip1559_fees(fee_history: &FeeHistory, max_fee_cap_wei: u128) -> Option<TransactionFees> {
+        let next_base_fee = fee_history
+            .base_fee_per_gas
+            .last()
+            .filter(|base_fee| !base_fee.is_zero())?;
+        let mut rewards = fee_history
+            .reward
+            .iter()
+            .flat_map(|block_rewards| block_rewards.first())
+            .collect::<Vec<&U256>>();
+        rewards.sort_unstable();
+        let cap = U256::from(max_fee_cap_wei);
+        let max_priority_fee_per_gas = rewards
+            .get(rewards.len() / 2)
+            .map(|reward| (**reward).min(cap))
+            .unwrap_or_else(U256::zero);
+        // Twice the base fee survives six full blocks in a row
+        let max_fee_per_gas = next_base_fee
+            .saturating_mul(U256::from(2))
+            .saturating_add(max_priority_fee_per_gas)
+            .min(cap);
+        Some(TransactionFees::Eip1559 {
+            max_fee_per_gas_wei: max_fee_per_gas.as_u128(),
+            max_priority_fee_per_gas_wei: max_priority_fee_per_gas.as_u128(),
+        })
+    }
+
+    fn transmission_log(
+        &self,
+        accounts: &[PayableAccount],
+        transaction_fees: TransactionFees,
+    ) -> String {
         let chain_name = self
             .chain
             .rec()
@@ -545,11 +773,11 @@ where
         Paying to creditors...\n\
         Transactions in the batch:\n\
         \n\
-        gas price:                                   {} gwei\n\
+        fees:                                        {}\n\
         chain:                                       {}\n\
         \n\
         [wallet address]                             [payment in wei]\n",
-            gas_price, chain_name
+            transaction_fees, chain_name
         ));
         let body = accounts.iter().map(|account| {
             format!(
@@ -631,13 +859,19 @@ mod tests {
     use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
     use crate::accountant::test_utils::{
         make_payable_account, make_payable_account_with_wallet_and_balance_and_timestamp_opt,
+        make_pending_payable_fingerprint, PendingPayableDaoMock,
     };
     use crate::blockchain::bip32::Bip32EncryptionKeyProvider;
-    use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
+    use crate::blockchain::blockchain_bridge::{
+        PendingPayableFingerprint, PendingPayableFingerprintSeeds, ReplaceableTransaction,
+        TransactionReplacement,
+    };
 
+    use crate::blockchain::blockchain_interface::blockchain_interface_web3::batch_payable_tools::Eip1559TransactionParameters;
     use crate::blockchain::blockchain_interface::blockchain_interface_web3::{
-        BlockchainInterfaceWeb3, CONTRACT_ABI, REQUESTS_IN_PARALLEL, TRANSACTION_LITERAL,
-        TRANSFER_METHOD_ID,
+        BlockchainInterfaceWeb3, CONTRACT_ABI, FEE_HISTORY_BLOCK_COUNT,
+        FEE_HISTORY_REWARD_PERCENTILE, REPLACEMENT_FEE_CEILING_MULTIPLE, REQUESTS_IN_PARALLEL,
+        TRANSACTION_LITERAL, TRANSFER_METHOD_ID,
     };
     use crate::blockchain::blockchain_interface::test_utils::{
         test_blockchain_interface_is_connected_and_functioning, LowBlockchainIntMock,
@@ -650,7 +884,7 @@ mod tests {
         all_chains, make_fake_event_loop_handle, make_tx_hash, TestTransport,
     };
     use crate::db_config::persistent_configuration::PersistentConfigError;
-    use crate::sub_lib::blockchain_bridge::ConsumingWalletBalances;
+    use crate::sub_lib::blockchain_bridge::{ConsumingWalletBalances, TransactionFees};
     use crate::sub_lib::wallet::Wallet;
     use crate::test_utils::assert_string_contains;
     use crate::test_utils::http_test_server::TestServer;
@@ -674,13 +908,15 @@ mod tests {
     use std::net::Ipv4Addr;
 
     use crate::accountant::db_access_objects::payable_dao::PayableAccount;
-    use crate::accountant::db_access_objects::pending_payable_dao::PendingPayable;
+    use crate::accountant::db_access_objects::pending_payable_dao::{
+        PendingPayable, PendingPayableDaoError,
+    };
     use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::test_utils::BlockchainAgentMock;
     use crate::blockchain::blockchain_interface::blockchain_interface_web3::test_utils::{
         make_default_signed_transaction, BatchPayableToolsMock,
     };
     use crate::blockchain::blockchain_interface::data_structures::{
-        BlockchainTransaction, RpcPayablesFailure,
+        BlockchainTransaction, FeeHistory, RpcPayablesFailure,
     };
     use indoc::indoc;
     use sodiumoxide::hex;
@@ -726,6 +962,9 @@ mod tests {
         assert_eq!(TRANSACTION_LITERAL, transaction_literal_expected);
         assert_eq!(TRANSFER_METHOD_ID, [0xa9, 0x05, 0x9c, 0xbb]);
         assert_eq!(REQUESTS_IN_PARALLEL, 1);
+        assert_eq!(FEE_HISTORY_BLOCK_COUNT, 10);
+        assert_eq!(FEE_HISTORY_REWARD_PERCENTILE, 50);
+        assert_eq!(REPLACEMENT_FEE_CEILING_MULTIPLE, 2);
     }
 
     #[test]
@@ -1094,6 +1333,7 @@ mod tests {
         let get_transaction_fee_balance_params_arc = Arc::new(Mutex::new(vec![]));
         let get_masq_balance_params_arc = Arc::new(Mutex::new(vec![]));
         let get_transactions_id_params_arc = Arc::new(Mutex::new(vec![]));
+        let get_fee_history_params_arc = Arc::new(Mutex::new(vec![]));
         let chain = Chain::PolyMainnet;
         let wallet = make_wallet("abc");
         let persistent_config = PersistentConfigurationMock::new().gas_price_result(Ok(50));
@@ -1111,7 +1351,15 @@ mod tests {
             .get_masq_balance_params(&get_masq_balance_params_arc)
             .get_masq_balance_result(Ok(masq_balance))
             .get_transaction_id_params(&get_transactions_id_params_arc)
-            .get_transaction_id_result(Ok(transaction_id));
+            .get_transaction_id_result(Ok(transaction_id))
+            .get_fee_history_params(&get_fee_history_params_arc)
+            .get_fee_history_result(Ok(FeeHistory {
+                base_fee_per_gas: vec![
+                    U256::from(9_000_000_000_u64),
+                    U256::from(10_000_000_000_u64),
+                ],
+                reward: vec![vec![U256::from(2_000_000_000)]],
+            }));
         subject.lower_interface = Box::new(lower_blockchain_interface);
 
         let result = subject
@@ -1125,6 +1373,11 @@ mod tests {
         assert_eq!(*get_masq_balance_params, vec![wallet.clone()]);
         let get_transaction_id_params = get_transactions_id_params_arc.lock().unwrap();
         assert_eq!(*get_transaction_id_params, vec![wallet.clone()]);
+        let get_fee_history_params = get_fee_history_params_arc.lock().unwrap();
+        assert_eq!(
+            *get_fee_history_params,
+            vec![(FEE_HISTORY_BLOCK_COUNT, FEE_HISTORY_REWARD_PERCENTILE)]
+        );
         assert_eq!(result.consuming_wallet(), &wallet);
         assert_eq!(result.pending_transaction_id(), transaction_id);
         assert_eq!(
@@ -1134,17 +1387,154 @@ mod tests {
                 masq_token_balance_in_minor_units: masq_balance
             }
         );
-        assert_eq!(result.agreed_fee_per_computation_unit(), 50);
+        assert_eq!(
+            result.agreed_transaction_fees(),
+            TransactionFees::Eip1559 {
+                max_fee_per_gas_wei: 22_000_000_000,
+                max_priority_fee_per_gas_wei: 2_000_000_000
+            }
+        );
         let expected_fee_estimation = (3
             * (BlockchainInterfaceWeb3::<Http>::web3_gas_limit_const_part(chain)
-                + WEB3_MAXIMAL_GAS_LIMIT_MARGIN)
-            * 50) as u128;
+                + WEB3_MAXIMAL_GAS_LIMIT_MARGIN)) as u128
+            * 22_000_000_000;
         assert_eq!(
             result.estimated_transaction_fee_total(3),
             expected_fee_estimation
         )
     }
 
+    #[test]
+    fn blockchain_agent_is_built_with_legacy_fees_if_fee_history_is_unavailable() {
+        init_test_logging();
+        let test_name = "blockchain_agent_is_built_with_legacy_fees_if_fee_history_is_unavailable";
+        let persistent_config = PersistentConfigurationMock::new().gas_price_result(Ok(50));
+        let mut subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            Chain::PolyAmoy,
+        );
+        subject.logger = Logger::new(test_name);
+        let lower_blockchain_interface = LowBlockchainIntMock::default()
+            .get_transaction_fee_balance_result(Ok(U256::from(123_456_789)))
+            .get_masq_balance_result(Ok(U256::from(444_444_444)))
+            .get_transaction_id_result(Ok(U256::from(23)))
+            .get_fee_history_result(Err(BlockchainError::QueryFailed(
+                "the method eth_feeHistory does not exist".to_string(),
+            )));
+        subject.lower_interface = Box::new(lower_blockchain_interface);
+
+        let result = subject
+            .build_blockchain_agent(&make_wallet("abc"), &persistent_config)
+            .unwrap();
+
+        assert_eq!(
+            result.agreed_transaction_fees(),
+            TransactionFees::Legacy {
+                gas_price_wei: 50_000_000_000
+            }
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {test_name}: Unable to fetch the fee history (QueryFailed(\"the method \
+            eth_feeHistory does not exist\")); falling back to legacy transactions with \
+            the configured gas price of 50 gwei"
+        ));
+    }
+
+    #[test]
+    fn eip1559_fees_take_the_median_reward_and_twice_the_next_base_fee() {
+        let fee_history = FeeHistory {
+            base_fee_per_gas: vec![U256::from(900), U256::from(1_100), U256::from(1_000)],
+            reward: vec![
+                vec![U256::from(300)],
+                vec![U256::from(100)],
+                vec![],
+                vec![U256::from(200)],
+            ],
+        };
+
+        let result =
+            BlockchainInterfaceWeb3::<TestTransport>::eip1559_fees(&fee_history, 1_000_000);
+
+        assert_eq!(
+            result,
+            Some(TransactionFees::Eip1559 {
+                max_fee_per_gas_wei: 2_200,
+                max_priority_fee_per_gas_wei: 200
+            })
+        )
+    }
+
+    #[test]
+    fn eip1559_fees_never_exceed_the_configured_gas_price() {
+        let fee_history = FeeHistory {
+            base_fee_per_gas: vec![U256::from(5_000)],
+            reward: vec![vec![U256::from(3_000)]],
+        };
+
+        let result = BlockchainInterfaceWeb3::<TestTransport>::eip1559_fees(&fee_history, 2_500);
+
+        assert_eq!(
+            result,
+            Some(TransactionFees::Eip1559 {
+                max_fee_per_gas_wei: 2_500,
+                max_priority_fee_per_gas_wei: 2_500
+            })
+        )
+    }
+
+    #[test]
+    fn eip1559_fees_are_not_offered_by_a_chain_without_base_fee() {
+        let zero_base_fee = FeeHistory {
+            base_fee_per_gas: vec![U256::zero(), U256::zero()],
+            reward: vec![vec![U256::from(3_000)]],
+        };
+        let no_base_fee = FeeHistory {
+            base_fee_per_gas: vec![],
+            reward: vec![],
+        };
+
+        let result_1 =
+            BlockchainInterfaceWeb3::<TestTransport>::eip1559_fees(&zero_base_fee, 1_000_000);
+        let result_2 =
+            BlockchainInterfaceWeb3::<TestTransport>::eip1559_fees(&no_base_fee, 1_000_000);
+
+        assert_eq!(result_1, None);
+        assert_eq!(result_2, None)
+    }
+
+    #[test]
+    fn compute_transaction_fees_warns_when_limited_by_the_configured_gas_price() {
+        init_test_logging();
+        let test_name = "compute_transaction_fees_warns_when_limited_by_the_configured_gas_price";
+        let mut subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            Chain::EthMainnet,
+        );
+        subject.logger = Logger::new(test_name);
+        subject.lower_interface = Box::new(LowBlockchainIntMock::default().get_fee_history_result(
+            Ok(FeeHistory {
+                base_fee_per_gas: vec![U256::from(40_000_000_000_u64)],
+                reward: vec![vec![U256::from(1_000_000_000)]],
+            }),
+        ));
+
+        let result = subject.compute_transaction_fees(60);
+
+        assert_eq!(
+            result,
+            TransactionFees::Eip1559 {
+                max_fee_per_gas_wei: 60_000_000_000,
+                max_priority_fee_per_gas_wei: 1_000_000_000
+            }
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {test_name}: Fees suggested by the fee history are limited by the \
+            configured gas price of 60 gwei; transactions may take long to get mined"
+        ));
+    }
+
     #[test]
     fn build_of_the_blockchain_agent_fails_on_fetching_gas_price() {
         let chain = Chain::PolyAmoy;
@@ -1430,13 +1820,42 @@ mod tests {
                     (expected_hash_1, gwei_to_wei(900_000_000_u64)),
                     (expected_hash_2, 123_456_789),
                     (expected_hash_3, gwei_to_wei(33_355_666_u64))
+                ],
+                replaceable_transactions: vec![
+                    (
+                        expected_hash_1,
+                        ReplaceableTransaction {
+                            recipient_wallet: make_wallet("w123"),
+                            nonce: 6,
+                            transaction_fees: legacy_fees(120),
+                            replacement_hashes: vec![]
+                        }
+                    ),
+                    (
+                        expected_hash_2,
+                        ReplaceableTransaction {
+                            recipient_wallet: make_wallet("w555"),
+                            nonce: 7,
+                            transaction_fees: legacy_fees(120),
+                            replacement_hashes: vec![]
+                        }
+                    ),
+                    (
+                        expected_hash_3,
+                        ReplaceableTransaction {
+                            recipient_wallet: make_wallet("w987"),
+                            nonce: 8,
+                            transaction_fees: legacy_fees(120),
+                            replacement_hashes: vec![]
+                        }
+                    )
                 ]
             }
         );
         let log_handler = TestLogHandler::new();
         log_handler.exists_log_containing("DEBUG: sending_batch_payments: \
         Common attributes of payables to be transacted: sender wallet: 0x5c361ba8d82fcf0e5538b2a823e9d457a2296725, contract: \
-          0x384dec25e03f94931767ce4c3556168468ba24c3, chain_id: 3, gas_price: 120");
+          0x384dec25e03f94931767ce4c3556168468ba24c3, chain_id: 3, fees: gas price 120,000,000,000 wei");
         log_handler.exists_log_containing(
             "DEBUG: sending_batch_payments: Preparing payment of 900,000,000,000,000,000 wei \
         to 0x0000000000000000000000000000000077313233 with nonce 6",
@@ -1453,7 +1872,7 @@ mod tests {
             "INFO: sending_batch_payments: Paying to creditors...\n\
         Transactions in the batch:\n\
         \n\
-        gas price:                                   120 gwei\n\
+        fees:                                        gas price 120,000,000,000 wei\n\
         chain:                                       ropsten\n\
         \n\
         [wallet address]                             [payment in wei]\n\
@@ -1608,7 +2027,7 @@ mod tests {
         );
         assert!(sign_transaction_params.is_empty());
         let new_payable_fingerprint_params = new_payable_fingerprint_params_arc.lock().unwrap();
-        let (batch_wide_timestamp, recipient, actual_pending_payables) =
+        let (batch_wide_timestamp, recipient, actual_pending_payables, replaceable_transactions) =
             &new_payable_fingerprint_params[0];
         assert_eq!(batch_wide_timestamp, &batch_wide_timestamp_expected);
         assert_eq!(
@@ -1618,6 +2037,29 @@ mod tests {
                 (second_hash, second_payment_amount)
             ]
         );
+        assert_eq!(
+            replaceable_transactions,
+            &vec![
+                (
+                    first_hash,
+                    ReplaceableTransaction {
+                        recipient_wallet: first_creditor_wallet,
+                        nonce: 4,
+                        transaction_fees: legacy_fees(123),
+                        replacement_hashes: vec![]
+                    }
+                ),
+                (
+                    second_hash,
+                    ReplaceableTransaction {
+                        recipient_wallet: second_creditor_wallet,
+                        nonce: 5,
+                        transaction_fees: legacy_fees(123),
+                        replacement_hashes: vec![]
+                    }
+                )
+            ]
+        );
         let mut append_transaction_to_batch_params =
             append_transaction_to_batch_params_arc.lock().unwrap();
         let (bytes_first_payment, web3_from_ertb_call_1) =
@@ -1645,6 +2087,7 @@ mod tests {
         let probe_message = PendingPayableFingerprintSeeds {
             batch_wide_timestamp: SystemTime::now(),
             hashes_and_balances: vec![],
+            replaceable_transactions: vec![],
         };
         recipient.try_send(probe_message).unwrap();
         System::current().stop();
@@ -1708,7 +2151,7 @@ mod tests {
             &consuming_wallet,
             1_000_000_000,
             nonce,
-            gas_price,
+            legacy_fees(gas_price),
         );
 
         let mut sign_transaction_params = sign_transaction_params_arc.lock().unwrap();
@@ -1829,6 +2272,68 @@ mod tests {
         );
     }
 
+    #[test]
+    fn sign_transaction_signs_eip1559_transaction_with_fees_from_the_agent() {
+        let sign_eip1559_transaction_params_arc = Arc::new(Mutex::new(vec![]));
+        let transport = TestTransport::default();
+        let signed_transaction = make_default_signed_transaction();
+        let batch_payable_tools = BatchPayableToolsMock::<TestTransport>::default()
+            .sign_eip1559_transaction_params(&sign_eip1559_transaction_params_arc)
+            .sign_eip1559_transaction_result(Ok(signed_transaction.clone()));
+        let chain = Chain::BaseMainnet;
+        let mut subject =
+            BlockchainInterfaceWeb3::new(transport, make_fake_event_loop_handle(), chain);
+        subject.batch_payable_tools = Box::new(batch_payable_tools);
+        let consuming_wallet_secret_raw_bytes = b"okay-wallet";
+        let consuming_wallet = make_paying_wallet(consuming_wallet_secret_raw_bytes);
+        let recipient = make_wallet("creditor321");
+        let transaction_fees = TransactionFees::Eip1559 {
+            max_fee_per_gas_wei: 12_000_000,
+            max_priority_fee_per_gas_wei: 1_000_000,
+        };
+
+        let result = subject.sign_transaction(
+            &recipient,
+            &consuming_wallet,
+            333_222_111_000,
+            U256::from(4),
+            transaction_fees,
+        );
+
+        assert_eq!(result, Ok(signed_transaction));
+        let mut sign_eip1559_transaction_params =
+            sign_eip1559_transaction_params_arc.lock().unwrap();
+        let (transaction_params, secret) = sign_eip1559_transaction_params.remove(0);
+        assert!(sign_eip1559_transaction_params.is_empty());
+        assert_eq!(
+            transaction_params,
+            Eip1559TransactionParameters {
+                chain_id: chain.rec().num_chain_id,
+                nonce: U256::from(4),
+                max_priority_fee_per_gas: U256::from(1_000_000),
+                max_fee_per_gas: U256::from(12_000_000),
+                gas: U256::from(71_552),
+                to: subject.contract_address(),
+                value: U256::zero(),
+                data: Bytes(
+                    BlockchainInterfaceWeb3::<TestTransport>::transaction_data(
+                        &recipient,
+                        333_222_111_000
+                    )
+                    .to_vec()
+                ),
+            }
+        );
+        assert_eq!(
+            secret,
+            (&Bip32EncryptionKeyProvider::from_raw_secret(
+                &consuming_wallet_secret_raw_bytes.keccak256()
+            )
+            .unwrap())
+                .into()
+        );
+    }
+
     #[test]
     fn sign_transaction_fails_on_signing_itself() {
         let transport = TestTransport::default();
@@ -1846,8 +2351,13 @@ mod tests {
         let gas_price = 123;
         let nonce = U256::from(1);
 
-        let result =
-            subject.sign_transaction(&recipient, &consuming_wallet, 444444, nonce, gas_price);
+        let result = subject.sign_transaction(
+            &recipient,
+            &consuming_wallet,
+            444444,
+            nonce,
+            legacy_fees(gas_price),
+        );
 
         assert_eq!(
             result,
@@ -1857,6 +2367,344 @@ mod tests {
         );
     }
 
+    #[test]
+    fn replace_transaction_signs_the_same_payment_under_the_same_nonce_with_outbidding_fees() {
+        init_test_logging();
+        let test_name =
+            "replace_transaction_signs_the_same_payment_under_the_same_nonce_with_outbidding_fees";
+        let sign_eip1559_transaction_params_arc = Arc::new(Mutex::new(vec![]));
+        let append_transaction_to_batch_params_arc = Arc::new(Mutex::new(vec![]));
+        let submit_batch_params_arc: Arc<Mutex<Vec<Web3<Batch<TestTransport>>>>> =
+            Arc::new(Mutex::new(vec![]));
+        let record_replacement_params_arc = Arc::new(Mutex::new(vec![]));
+        let replacement_hash = make_tx_hash(0xabc);
+        let mut signed_transaction = make_default_signed_transaction();
+        signed_transaction.transaction_hash = replacement_hash;
+        signed_transaction.raw_transaction = Bytes(vec![2, 4, 6]);
+        let batch_payable_tools = BatchPayableToolsMock::<TestTransport>::default()
+            .sign_eip1559_transaction_params(&sign_eip1559_transaction_params_arc)
+            .sign_eip1559_transaction_result(Ok(signed_transaction))
+            .append_transaction_to_batch_params(&append_transaction_to_batch_params_arc)
+            .submit_batch_params(&submit_batch_params_arc)
+            .submit_batch_result(Ok(vec![Ok(json!(format!("{:?}", replacement_hash)))]));
+        let lower_interface =
+            LowBlockchainIntMock::default().get_fee_history_result(Ok(FeeHistory {
+                base_fee_per_gas: vec![U256::from(1_000_000_000)],
+                reward: vec![vec![U256::from(100_000_000)]],
+            }));
+        let persistent_config = PersistentConfigurationMock::new().gas_price_result(Ok(50));
+        let pending_payable_dao = PendingPayableDaoMock::new()
+            .record_replacement_params(&record_replacement_params_arc)
+            .record_replacement_result(Ok(()));
+        let chain = Chain::BaseMainnet;
+        let mut subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            chain,
+        );
+        subject.logger = Logger::new(test_name);
+        subject.batch_payable_tools = Box::new(batch_payable_tools);
+        subject.lower_interface = Box::new(lower_interface);
+        let consuming_wallet = make_paying_wallet(b"okay-wallet");
+        let recipient_wallet = make_wallet("creditor");
+        let stuck_transaction = PendingPayableFingerprint {
+            rowid: 7,
+            timestamp: from_time_t(200_000_000),
+            hash: make_tx_hash(0x123),
+            attempt: 5,
+            amount: 44_555_666,
+            process_error: None,
+            replaceable_opt: Some(ReplaceableTransaction {
+                recipient_wallet: recipient_wallet.clone(),
+                nonce: 12,
+                transaction_fees: TransactionFees::Eip1559 {
+                    max_fee_per_gas_wei: 2_400_000_000,
+                    max_priority_fee_per_gas_wei: 100_000_000,
+                },
+                replacement_hashes: vec![],
+            }),
+        };
+
+        let before = SystemTime::now();
+        let result = subject.replace_transaction(
+            &consuming_wallet,
+            &stuck_transaction,
+            &persistent_config,
+            &pending_payable_dao,
+        );
+        let after = SystemTime::now();
+
+        let timestamp = result.as_ref().unwrap().timestamp;
+        assert!(before <= timestamp && timestamp <= after);
+        let expected_replacement = TransactionReplacement {
+            rowid: 7,
+            hash: replacement_hash,
+            transaction_fees: TransactionFees::Eip1559 {
+                max_fee_per_gas_wei: 2_700_000_000,
+                max_priority_fee_per_gas_wei: 112_500_000,
+            },
+            timestamp,
+        };
+        assert_eq!(result, Ok(expected_replacement.clone()));
+        let record_replacement_params = record_replacement_params_arc.lock().unwrap();
+        assert_eq!(*record_replacement_params, vec![expected_replacement]);
+        let mut sign_eip1559_transaction_params =
+            sign_eip1559_transaction_params_arc.lock().unwrap();
+        let (transaction_params, _) = sign_eip1559_transaction_params.remove(0);
+        assert!(sign_eip1559_transaction_params.is_empty());
+        let data = BlockchainInterfaceWeb3::<TestTransport>::transaction_data(
+            &recipient_wallet,
+            44_555_666,
+        );
+        assert_eq!(
+            transaction_params,
+            Eip1559TransactionParameters {
+                chain_id: chain.rec().num_chain_id,
+                nonce: U256::from(12),
+                max_priority_fee_per_gas: U256::from(112_500_000),
+                max_fee_per_gas: U256::from(2_700_000_000_u64),
+                gas: subject.compute_gas_limit(data.as_slice()),
+                to: subject.contract_address(),
+                value: U256::zero(),
+                data: Bytes(data.to_vec()),
+            }
+        );
+        let append_transaction_to_batch_params =
+            append_transaction_to_batch_params_arc.lock().unwrap();
+        assert_eq!(append_transaction_to_batch_params.len(), 1);
+        assert_eq!(
+            append_transaction_to_batch_params[0].0,
+            Bytes(vec![2, 4, 6])
+        );
+        assert_eq!(submit_batch_params_arc.lock().unwrap().len(), 1);
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: {test_name}: Replacing stuck transaction 0x0000000000000000000000000000000000000000\
+            000000000000000000000123 with nonce 12 by 0x000000000000000000000000000000000000000000000\
+            0000000000000000abc; fees: max fee per gas 2,700,000,000 wei, max priority fee per gas \
+            112,500,000 wei"
+        ));
+    }
+
+    #[test]
+    fn replace_transaction_outbids_a_transaction_signed_at_the_configured_gas_price() {
+        let record_replacement_params_arc = Arc::new(Mutex::new(vec![]));
+        let replacement_hash = make_tx_hash(0xabc);
+        let mut signed_transaction = make_default_signed_transaction();
+        signed_transaction.transaction_hash = replacement_hash;
+        let batch_payable_tools = BatchPayableToolsMock::<TestTransport>::default()
+            .sign_transaction_result(Ok(signed_transaction))
+            .submit_batch_result(Ok(vec![Ok(json!(format!("{:?}", replacement_hash)))]));
+        let persistent_config = PersistentConfigurationMock::new().gas_price_result(Ok(50));
+        let pending_payable_dao = PendingPayableDaoMock::new()
+            .record_replacement_params(&record_replacement_params_arc)
+            .record_replacement_result(Ok(()));
+        let lower_interface = LowBlockchainIntMock::default()
+            .get_fee_history_result(Err(BlockchainError::QueryFailed("Nope".to_string())));
+        let mut subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            Chain::PolyMainnet,
+        );
+        subject.batch_payable_tools = Box::new(batch_payable_tools);
+        subject.lower_interface = Box::new(lower_interface);
+        let mut stuck_transaction = make_pending_payable_fingerprint();
+        stuck_transaction.replaceable_opt = Some(ReplaceableTransaction {
+            recipient_wallet: make_wallet("creditor"),
+            nonce: 3,
+            transaction_fees: legacy_fees(50),
+            replacement_hashes: vec![make_tx_hash(0x567)],
+        });
+
+        let result = subject.replace_transaction(
+            &make_paying_wallet(b"okay-wallet"),
+            &stuck_transaction,
+            &persistent_config,
+            &pending_payable_dao,
+        );
+
+        let expected_replacement = TransactionReplacement {
+            rowid: stuck_transaction.rowid,
+            hash: replacement_hash,
+            transaction_fees: TransactionFees::Legacy {
+                gas_price_wei: 56_250_000_000,
+            },
+            timestamp: result.as_ref().unwrap().timestamp,
+        };
+        assert_eq!(result, Ok(expected_replacement.clone()));
+        let record_replacement_params = record_replacement_params_arc.lock().unwrap();
+        assert_eq!(*record_replacement_params, vec![expected_replacement]);
+    }
+
+    #[test]
+    fn replace_transaction_refuses_fees_beyond_the_replacement_ceiling() {
+        let persistent_config = PersistentConfigurationMock::new().gas_price_result(Ok(50));
+        let lower_interface = LowBlockchainIntMock::default()
+            .get_fee_history_result(Err(BlockchainError::QueryFailed("Nope".to_string())));
+        let mut subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            Chain::PolyMainnet,
+        );
+        subject.batch_payable_tools = Box::new(BatchPayableToolsMock::default());
+        subject.lower_interface = Box::new(lower_interface);
+        let mut stuck_transaction = make_pending_payable_fingerprint();
+        stuck_transaction.replaceable_opt = Some(ReplaceableTransaction {
+            recipient_wallet: make_wallet("creditor"),
+            nonce: 3,
+            transaction_fees: legacy_fees(90),
+            replacement_hashes: vec![make_tx_hash(0x567), make_tx_hash(0x678)],
+        });
+
+        let result = subject.replace_transaction(
+            &make_paying_wallet(b"okay-wallet"),
+            &stuck_transaction,
+            &persistent_config,
+            &PendingPayableDaoMock::new(),
+        );
+
+        assert_eq!(
+            result,
+            Err(PayableTransactionError::Unreplaceable(
+                "outbidding 0x00000000000000000000000000000000000000000000000000000000000001c8 \
+                takes gas price 101,250,000,000 wei, more than the replacement ceiling of 2 times \
+                the configured gas price of 50 gwei allows"
+                    .to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn replace_transaction_refuses_a_transaction_without_signing_details() {
+        let persistent_config = PersistentConfigurationMock::new();
+        let subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            Chain::PolyMainnet,
+        );
+        let stuck_transaction = make_pending_payable_fingerprint();
+
+        let result = subject.replace_transaction(
+            &make_paying_wallet(b"okay-wallet"),
+            &stuck_transaction,
+            &persistent_config,
+            &PendingPayableDaoMock::new(),
+        );
+
+        assert_eq!(
+            result,
+            Err(PayableTransactionError::Unreplaceable(
+                "signing details of 0x00000000000000000000000000000000000000000000000000000000000001c8 \
+                were not kept"
+                    .to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn replace_transaction_sends_nothing_if_the_replacement_cannot_be_recorded() {
+        let append_transaction_to_batch_params_arc = Arc::new(Mutex::new(vec![]));
+        let submit_batch_params_arc: Arc<Mutex<Vec<Web3<Batch<TestTransport>>>>> =
+            Arc::new(Mutex::new(vec![]));
+        let batch_payable_tools = BatchPayableToolsMock::<TestTransport>::default()
+            .sign_transaction_result(Ok(make_default_signed_transaction()))
+            .append_transaction_to_batch_params(&append_transaction_to_batch_params_arc)
+            .submit_batch_params(&submit_batch_params_arc);
+        let persistent_config = PersistentConfigurationMock::new().gas_price_result(Ok(100));
+        let pending_payable_dao = PendingPayableDaoMock::new().record_replacement_result(Err(
+            PendingPayableDaoError::UpdateFailed("Locked".to_string()),
+        ));
+        let lower_interface = LowBlockchainIntMock::default()
+            .get_fee_history_result(Err(BlockchainError::QueryFailed("Nope".to_string())));
+        let mut subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            Chain::PolyMainnet,
+        );
+        subject.batch_payable_tools = Box::new(batch_payable_tools);
+        subject.lower_interface = Box::new(lower_interface);
+        let mut stuck_transaction = make_pending_payable_fingerprint();
+        stuck_transaction.replaceable_opt = Some(ReplaceableTransaction {
+            recipient_wallet: make_wallet("creditor"),
+            nonce: 3,
+            transaction_fees: legacy_fees(50),
+            replacement_hashes: vec![],
+        });
+
+        let result = subject.replace_transaction(
+            &make_paying_wallet(b"okay-wallet"),
+            &stuck_transaction,
+            &persistent_config,
+            &pending_payable_dao,
+        );
+
+        assert_eq!(
+            result,
+            Err(PayableTransactionError::Unreplaceable(
+                "recording the replacement of \
+                0x00000000000000000000000000000000000000000000000000000000000001c8 failed: \
+                UpdateFailed(\"Locked\")"
+                    .to_string()
+            ))
+        );
+        assert!(append_transaction_to_batch_params_arc
+            .lock()
+            .unwrap()
+            .is_empty());
+        assert!(submit_batch_params_arc.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn replace_transaction_fails_on_rpc_error() {
+        let replacement_hash = make_tx_hash(0xabc);
+        let mut signed_transaction = make_default_signed_transaction();
+        signed_transaction.transaction_hash = replacement_hash;
+        let batch_payable_tools = BatchPayableToolsMock::<TestTransport>::default()
+            .sign_transaction_result(Ok(signed_transaction))
+            .submit_batch_result(Ok(vec![Err(web3::Error::Rpc(RPCError {
+                code: ErrorCode::ServerError(-32000),
+                message: "replacement transaction underpriced".to_string(),
+                data: None,
+            }))]));
+        let lower_interface =
+            LowBlockchainIntMock::default().get_fee_history_result(Ok(FeeHistory {
+                base_fee_per_gas: vec![U256::zero()],
+                reward: vec![],
+            }));
+        let persistent_config = PersistentConfigurationMock::new().gas_price_result(Ok(100));
+        let pending_payable_dao = PendingPayableDaoMock::new().record_replacement_result(Ok(()));
+        let mut subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            Chain::PolyMainnet,
+        );
+        subject.batch_payable_tools = Box::new(batch_payable_tools);
+        subject.lower_interface = Box::new(lower_interface);
+        let mut stuck_transaction = make_pending_payable_fingerprint();
+        stuck_transaction.replaceable_opt = Some(ReplaceableTransaction {
+            recipient_wallet: make_wallet("creditor"),
+            nonce: 3,
+            transaction_fees: legacy_fees(50),
+            replacement_hashes: vec![],
+        });
+
+        let result = subject.replace_transaction(
+            &make_paying_wallet(b"okay-wallet"),
+            &stuck_transaction,
+            &persistent_config,
+            &pending_payable_dao,
+        );
+
+        assert_eq!(
+            result,
+            Err(PayableTransactionError::Sending {
+                msg: "RPC error: Error { code: ServerError(-32000), message: \"replacement \
+                transaction underpriced\", data: None }"
+                    .to_string(),
+                hashes: vec![replacement_hash]
+            })
+        );
+    }
+
     fn test_consuming_wallet_with_secret() -> Wallet {
         let key_pair = Bip32EncryptionKeyProvider::from_raw_secret(
             &decode_hex("97923d8fd8de4a00f912bfb77ef483141dec551bd73ea59343ef5c4aac965d04")
@@ -1903,7 +2751,7 @@ mod tests {
                 &consuming_wallet,
                 payable_account.balance_wei,
                 nonce_correct_type,
-                gas_price,
+                legacy_fees(gas_price),
             )
             .unwrap();
 
@@ -2299,11 +3147,17 @@ mod tests {
         Box::new(
             BlockchainAgentMock::default()
                 .consuming_wallet_result(consuming_wallet)
-                .agreed_fee_per_computation_unit_result(gas_price_gwei)
+                .agreed_transaction_fees_result(legacy_fees(gas_price_gwei))
                 .pending_transaction_id_result(nonce),
         )
     }
 
+    fn legacy_fees(gas_price_gwei: u64) -> TransactionFees {
+        TransactionFees::Legacy {
+            gas_price_wei: gwei_to_wei(gas_price_gwei),
+        }
+    }
+
     #[test]
     fn hash_the_smart_contract_transfer_function_signature() {
         assert_eq!(
@@ -2,8 +2,12 @@
 
 #![cfg(test)]
 
-use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
-use crate::blockchain::blockchain_interface::blockchain_interface_web3::batch_payable_tools::BatchPayableTools;
+use crate::blockchain::blockchain_bridge::{
+    PendingPayableFingerprintSeeds, ReplaceableTransaction,
+};
+use crate::blockchain::blockchain_interface::blockchain_interface_web3::batch_payable_tools::{
+    BatchPayableTools, Eip1559TransactionParameters,
+};
 use actix::Recipient;
 use jsonrpc_core as rpc;
 use std::cell::RefCell;
@@ -25,6 +29,15 @@ pub struct BatchPayableToolsMock<T: BatchTransport> {
         >,
     >,
     sign_transaction_results: RefCell<Vec<Result<SignedTransaction, Web3Error>>>,
+    sign_eip1559_transaction_params: Arc<
+        Mutex<
+            Vec<(
+                Eip1559TransactionParameters,
+                secp256k1secrets::key::SecretKey,
+            )>,
+        >,
+    >,
+    sign_eip1559_transaction_results: RefCell<Vec<Result<SignedTransaction, Web3Error>>>,
     append_transaction_to_batch_params: Arc<Mutex<Vec<(Bytes, Web3<Batch<T>>)>>>,
     //append_transaction_to_batch returns just the unit type
     //batch_wide_timestamp doesn't have params
@@ -35,6 +48,7 @@ pub struct BatchPayableToolsMock<T: BatchTransport> {
                 SystemTime,
                 Recipient<PendingPayableFingerprintSeeds>,
                 Vec<(H256, u128)>,
+                Vec<(H256, ReplaceableTransaction)>,
             )>,
         >,
     >,
@@ -59,6 +73,18 @@ impl<T: BatchTransport> BatchPayableTools<T> for BatchPayableToolsMock<T> {
         self.sign_transaction_results.borrow_mut().remove(0)
     }
 
+    fn sign_eip1559_transaction(
+        &self,
+        transaction_params: Eip1559TransactionParameters,
+        key: &secp256k1secrets::key::SecretKey,
+    ) -> Result<SignedTransaction, Web3Error> {
+        self.sign_eip1559_transaction_params
+            .lock()
+            .unwrap()
+            .push((transaction_params, key.clone()));
+        self.sign_eip1559_transaction_results.borrow_mut().remove(0)
+    }
+
     fn append_transaction_to_batch(&self, signed_transaction: Bytes, web3: &Web3<Batch<T>>) {
         self.append_transaction_to_batch_params
             .lock()
@@ -75,6 +101,7 @@ impl<T: BatchTransport> BatchPayableTools<T> for BatchPayableToolsMock<T> {
         batch_wide_timestamp: SystemTime,
         pp_fingerprint_sub: &Recipient<PendingPayableFingerprintSeeds>,
         hashes_and_balances: &[(H256, u128)],
+        replaceable_transactions: &[(H256, ReplaceableTransaction)],
     ) {
         self.send_new_payable_fingerprints_seeds_params
             .lock()
@@ -83,6 +110,7 @@ impl<T: BatchTransport> BatchPayableTools<T> for BatchPayableToolsMock<T> {
                 batch_wide_timestamp,
                 (*pp_fingerprint_sub).clone(),
                 hashes_and_balances.to_vec(),
+                replaceable_transactions.to_vec(),
             ));
     }
 
@@ -117,6 +145,31 @@ impl<T: BatchTransport> BatchPayableToolsMock<T> {
         self
     }
 
+    pub fn sign_eip1559_transaction_params(
+        mut self,
+        params: &Arc<
+            Mutex<
+                Vec<(
+                    Eip1559TransactionParameters,
+                    secp256k1secrets::key::SecretKey,
+                )>,
+            >,
+        >,
+    ) -> Self {
+        self.sign_eip1559_transaction_params = params.clone();
+        self
+    }
+
+    pub fn sign_eip1559_transaction_result(
+        self,
+        result: Result<SignedTransaction, Web3Error>,
+    ) -> Self {
+        self.sign_eip1559_transaction_results
+            .borrow_mut()
+            .push(result);
+        self
+    }
+
     pub fn batch_wide_timestamp_result(self, result: SystemTime) -> Self {
         self.batch_wide_timestamp_results.borrow_mut().push(result);
         self
@@ -130,6 +183,7 @@ impl<T: BatchTransport> BatchPayableToolsMock<T> {
                     SystemTime,
                     Recipient<PendingPayableFingerprintSeeds>,
                     Vec<(H256, u128)>,
+                    Vec<(H256, ReplaceableTransaction)>,
                 )>,
             >,
         >,
@@ -47,6 +47,7 @@ pub enum PayableTransactionError {
     UnusableWallet(String),
     Signing(String),
     Sending { msg: String, hashes: Vec<H256> },
+    Unreplaceable(String),
     UninitializedBlockchainInterface,
 }
 
@@ -74,6 +75,7 @@ impl Display for PayableTransactionError {
                 msg,
                 comma_joined_stringifiable(hashes, |hash| format!("{:?}", hash))
             ),
+            Self::Unreplaceable(msg) => write!(f, "Unreplaceable transaction: \"{}\"", msg),
             Self::UninitializedBlockchainInterface => {
                 write!(f, "{}", BLOCKCHAIN_SERVICE_URL_NOT_SPECIFIED)
             }
@@ -194,6 +196,9 @@ mod tests {
                 msg: "Sending to cosmos belongs elsewhere".to_string(),
                 hashes: vec![make_tx_hash(0x6f), make_tx_hash(0xde)],
             },
+            PayableTransactionError::Unreplaceable(
+                "Doubling down on a losing bet is not an option".to_string(),
+            ),
             PayableTransactionError::UninitializedBlockchainInterface,
         ];
 
@@ -216,6 +221,7 @@ mod tests {
                 "Sending phase: \"Sending to cosmos belongs elsewhere\". Signed and hashed \
                 transactions: 0x000000000000000000000000000000000000000000000000000000000000006f, \
                 0x00000000000000000000000000000000000000000000000000000000000000de",
+                "Unreplaceable transaction: \"Doubling down on a losing bet is not an option\"",
                 BLOCKCHAIN_SERVICE_URL_NOT_SPECIFIED
             ])
         )
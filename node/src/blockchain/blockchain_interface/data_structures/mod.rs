@@ -3,7 +3,8 @@
 pub mod errors;
 use crate::accountant::db_access_objects::pending_payable_dao::PendingPayable;
 use crate::sub_lib::wallet::Wallet;
-use web3::types::{BlockNumber, H256};
+use serde_derive::Deserialize;
+use web3::types::{BlockNumber, H256, U256};
 use web3::Error;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -19,6 +20,15 @@ pub struct RetrievedBlockchainTransactions {
     pub transactions: Vec<BlockchainTransaction>,
 }
 
+// Answer to eth_feeHistory; the base fees include one for the block yet to come
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct FeeHistory {
+    #[serde(rename = "baseFeePerGas")]
+    pub base_fee_per_gas: Vec<U256>,
+    #[serde(default)]
+    pub reward: Vec<Vec<U256>>,
+}
+
 pub type ProcessedPayableFallible = Result<PendingPayable, RpcPayablesFailure>;
 
 #[derive(Debug, PartialEq, Clone)]
@@ -1,6 +1,7 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 use crate::blockchain::blockchain_interface::data_structures::errors::BlockchainResult;
+use crate::blockchain::blockchain_interface::data_structures::FeeHistory;
 use crate::sub_lib::wallet::Wallet;
 use ethereum_types::U64;
 use web3::types::U256;
@@ -13,9 +14,12 @@ pub trait LowBlockchainInt {
     fn get_block_number(&self) -> LatestBlockNumber;
 
     fn get_transaction_id(&self, wallet: &Wallet) -> ResultForNonce;
+
+    fn get_fee_history(&self, block_count: u64, reward_percentile: u8) -> ResultForFeeHistory;
 }
 
 pub type ResultForBalance = BlockchainResult<web3::types::U256>;
 pub type ResultForBothBalances = BlockchainResult<(web3::types::U256, web3::types::U256)>;
 pub type ResultForNonce = BlockchainResult<U256>;
 pub type LatestBlockNumber = BlockchainResult<U64>;
+pub type ResultForFeeHistory = BlockchainResult<FeeHistory>;
@@ -7,8 +7,11 @@ pub mod lower_level_interface;
 pub mod test_utils;
 
 use crate::accountant::db_access_objects::payable_dao::PayableAccount;
+use crate::accountant::db_access_objects::pending_payable_dao::PendingPayableDao;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
-use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
+use crate::blockchain::blockchain_bridge::{
+    PendingPayableFingerprint, PendingPayableFingerprintSeeds, TransactionReplacement,
+};
 use crate::blockchain::blockchain_interface::data_structures::errors::{
     BlockchainAgentBuildError, BlockchainError, PayableTransactionError, ResultForReceipt,
 };
@@ -46,6 +49,16 @@ pub trait BlockchainInterface {
 
     fn get_transaction_receipt(&self, hash: H256) -> ResultForReceipt;
 
+    // Sends the same payment again, under the same nonce but with higher fees. The replacement
+    // is recorded before it is sent so that its hash can never get lost
+    fn replace_transaction(
+        &self,
+        consuming_wallet: &Wallet,
+        stuck_transaction: &PendingPayableFingerprint,
+        persistent_config: &dyn PersistentConfiguration,
+        pending_payable_dao: &dyn PendingPayableDao,
+    ) -> Result<TransactionReplacement, PayableTransactionError>;
+
     fn lower_interface(&self) -> &dyn LowBlockchainInt;
 
     as_any_ref_in_trait!();
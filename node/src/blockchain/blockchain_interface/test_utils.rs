@@ -3,7 +3,7 @@
 #![cfg(test)]
 
 use crate::blockchain::blockchain_interface::lower_level_interface::{
-    LatestBlockNumber, LowBlockchainInt, ResultForBalance, ResultForNonce,
+    LatestBlockNumber, LowBlockchainInt, ResultForBalance, ResultForFeeHistory, ResultForNonce,
 };
 use crate::blockchain::blockchain_interface::BlockchainInterface;
 use crate::sub_lib::wallet::Wallet;
@@ -24,6 +24,8 @@ pub struct LowBlockchainIntMock {
     get_block_number_results: RefCell<Vec<LatestBlockNumber>>,
     get_transaction_id_params: Arc<Mutex<Vec<Wallet>>>,
     get_transaction_id_results: RefCell<Vec<ResultForNonce>>,
+    get_fee_history_params: Arc<Mutex<Vec<(u64, u8)>>>,
+    get_fee_history_results: RefCell<Vec<ResultForFeeHistory>>,
 }
 
 impl LowBlockchainInt for LowBlockchainIntMock {
@@ -56,6 +58,14 @@ impl LowBlockchainInt for LowBlockchainIntMock {
             .push(address.clone());
         self.get_transaction_id_results.borrow_mut().remove(0)
     }
+
+    fn get_fee_history(&self, block_count: u64, reward_percentile: u8) -> ResultForFeeHistory {
+        self.get_fee_history_params
+            .lock()
+            .unwrap()
+            .push((block_count, reward_percentile));
+        self.get_fee_history_results.borrow_mut().remove(0)
+    }
 }
 
 impl LowBlockchainIntMock {
@@ -95,6 +105,16 @@ impl LowBlockchainIntMock {
         self.get_transaction_id_results.borrow_mut().push(result);
         self
     }
+
+    pub fn get_fee_history_params(mut self, params: &Arc<Mutex<Vec<(u64, u8)>>>) -> Self {
+        self.get_fee_history_params = params.clone();
+        self
+    }
+
+    pub fn get_fee_history_result(self, result: ResultForFeeHistory) -> Self {
+        self.get_fee_history_results.borrow_mut().push(result);
+        self
+    }
 }
 
 pub fn test_blockchain_interface_is_connected_and_functioning<F>(subject_factory: F)
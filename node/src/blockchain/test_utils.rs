@@ -3,8 +3,11 @@
 #![cfg(test)]
 
 use crate::accountant::db_access_objects::payable_dao::PayableAccount;
+use crate::accountant::db_access_objects::pending_payable_dao::PendingPayableDao;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
-use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
+use crate::blockchain::blockchain_bridge::{
+    PendingPayableFingerprint, PendingPayableFingerprintSeeds, TransactionReplacement,
+};
 use crate::blockchain::blockchain_interface::blockchain_interface_web3::REQUESTS_IN_PARALLEL;
 use crate::blockchain::blockchain_interface::data_structures::errors::{
     BlockchainAgentBuildError, BlockchainError, PayableTransactionError, ResultForReceipt,
@@ -77,6 +80,9 @@ pub struct BlockchainInterfaceMock {
         RefCell<Vec<Result<Vec<ProcessedPayableFallible>, PayableTransactionError>>>,
     get_transaction_receipt_params: Arc<Mutex<Vec<H256>>>,
     get_transaction_receipt_results: RefCell<Vec<ResultForReceipt>>,
+    replace_transaction_params: Arc<Mutex<Vec<(Wallet, PendingPayableFingerprint)>>>,
+    replace_transaction_results:
+        RefCell<Vec<Result<TransactionReplacement, PayableTransactionError>>>,
     lower_interface_result: Option<Box<LowBlockchainIntMock>>,
     arbitrary_id_stamp_opt: Option<ArbitraryIdStamp>,
 }
@@ -134,6 +140,20 @@ impl BlockchainInterface for BlockchainInterfaceMock {
         self.get_transaction_receipt_results.borrow_mut().remove(0)
     }
 
+    fn replace_transaction(
+        &self,
+        consuming_wallet: &Wallet,
+        stuck_transaction: &PendingPayableFingerprint,
+        _persistent_config: &dyn PersistentConfiguration,
+        _pending_payable_dao: &dyn PendingPayableDao,
+    ) -> Result<TransactionReplacement, PayableTransactionError> {
+        self.replace_transaction_params
+            .lock()
+            .unwrap()
+            .push((consuming_wallet.clone(), stuck_transaction.clone()));
+        self.replace_transaction_results.borrow_mut().remove(0)
+    }
+
     fn lower_interface(&self) -> &dyn LowBlockchainInt {
         self.lower_interface_result.as_ref().unwrap().as_ref()
     }
@@ -212,6 +232,22 @@ impl BlockchainInterfaceMock {
         self
     }
 
+    pub fn replace_transaction_params(
+        mut self,
+        params: &Arc<Mutex<Vec<(Wallet, PendingPayableFingerprint)>>>,
+    ) -> Self {
+        self.replace_transaction_params = params.clone();
+        self
+    }
+
+    pub fn replace_transaction_result(
+        self,
+        result: Result<TransactionReplacement, PayableTransactionError>,
+    ) -> Self {
+        self.replace_transaction_results.borrow_mut().push(result);
+        self
+    }
+
     pub fn lower_interface_results(
         mut self,
         aggregated_results: Box<LowBlockchainIntMock>,
@@ -1,8 +1,9 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
-use crate::accountant::DEFAULT_PENDING_TOO_LONG_SEC;
+use crate::accountant::{DEFAULT_PENDING_SCANS_BEFORE_REPLACEMENT, DEFAULT_PENDING_TOO_LONG_SEC};
 use crate::actor_system_factory::ActorSystemFactory;
 use crate::actor_system_factory::ActorSystemFactoryReal;
 use crate::actor_system_factory::{ActorFactoryReal, ActorSystemFactoryToolsReal};
+use crate::clandestine_discriminator_factory::ClandestineDiscriminatorFactory;
 use crate::crash_test_dummy::CrashTestDummy;
 use crate::database::db_initializer::DbInitializationConfig;
 use crate::database::db_initializer::{DbInitializer, DbInitializerReal};
@@ -11,16 +12,17 @@ use crate::db_config::persistent_configuration::{
     PersistentConfiguration, PersistentConfigurationReal,
 };
 use crate::discriminator::DiscriminatorFactory;
-use crate::json_discriminator_factory::JsonDiscriminatorFactory;
 use crate::listener_handler::ListenerHandler;
 use crate::listener_handler::ListenerHandlerFactory;
 use crate::listener_handler::ListenerHandlerFactoryReal;
-use crate::neighborhood::DEFAULT_MIN_HOPS;
+use crate::masquerader::MasqueraderKind;
+use crate::neighborhood::{DEFAULT_LATENCY_WEIGHT, DEFAULT_MIN_HOPS};
 use crate::node_configurator::node_configurator_standard::{
     NodeConfiguratorStandardPrivileged, NodeConfiguratorStandardUnprivileged,
 };
 use crate::node_configurator::{initialize_database, DirsWrapper, NodeConfigurator};
 use crate::privilege_drop::{IdWrapper, IdWrapperReal};
+use crate::proxy_server::{DEFAULT_MULTIPATH_ROUTES, DEFAULT_STICKY_ROUTE_TTL};
 use crate::server_initializer::LoggerInitializerWrapper;
 use crate::stream_handler_pool::StreamHandlerPoolSubs;
 use crate::sub_lib::accountant;
@@ -29,9 +31,12 @@ use crate::sub_lib::blockchain_bridge::BlockchainBridgeConfig;
 use crate::sub_lib::cryptde::CryptDE;
 use crate::sub_lib::cryptde_null::CryptDENull;
 use crate::sub_lib::cryptde_real::CryptDEReal;
+use crate::sub_lib::exit_location::ExitLocation;
+use crate::sub_lib::exit_policy::ExitPolicy;
 use crate::sub_lib::neighborhood::NodeDescriptor;
 use crate::sub_lib::neighborhood::{NeighborhoodConfig, NeighborhoodMode};
 use crate::sub_lib::node_addr::NodeAddr;
+use crate::sub_lib::proxy_server::ForwardProxyConfig;
 use crate::sub_lib::socket_server::ConfiguredByPrivilege;
 use crate::sub_lib::ui_gateway::UiGatewayConfig;
 use crate::sub_lib::utils::db_connection_launch_panic;
@@ -55,6 +60,7 @@ use std::fmt::{Debug, Display, Error, Formatter};
 use std::net::{Ipv4Addr, SocketAddr};
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
 use std::vec::Vec;
 use tokio::prelude::stream::futures_unordered::FuturesUnordered;
 use tokio::prelude::Async;
@@ -337,11 +343,23 @@ pub struct BootstrapperConfig {
     pub scan_intervals_opt: Option<ScanIntervals>,
     pub suppress_initial_scans: bool,
     pub when_pending_too_long_sec: u64,
+    pub pending_scans_before_replacement: u16,
     pub crash_point: CrashPoint,
     pub clandestine_discriminator_factories: Vec<Box<dyn DiscriminatorFactory>>,
     pub ui_gateway_config: UiGatewayConfig,
     pub blockchain_bridge_config: BlockchainBridgeConfig,
     pub port_configurations: HashMap<u16, PortConfiguration>,
+    pub socks_port_opt: Option<u16>,
+    pub forward_proxy_config_opt: Option<ForwardProxyConfig>,
+    pub sticky_route_ttl: Duration,
+    pub multipath_routes: usize,
+    pub packet_padding: bool,
+    pub binary_masquerade: bool,
+    pub cover_traffic_interval_opt: Option<Duration>,
+    pub exit_policy: ExitPolicy,
+    pub country_code_opt: Option<String>,
+    pub exit_location_opt: Option<ExitLocation>,
+    pub latency_weight: u64,
     pub data_directory: PathBuf,
     pub node_descriptor: NodeDescriptor,
     pub main_cryptde_null_opt: Option<CryptDENull>,
@@ -383,6 +401,17 @@ impl BootstrapperConfig {
                 gas_price: 1,
             },
             port_configurations: HashMap::new(),
+            socks_port_opt: None,
+            forward_proxy_config_opt: None,
+            sticky_route_ttl: DEFAULT_STICKY_ROUTE_TTL,
+            multipath_routes: DEFAULT_MULTIPATH_ROUTES,
+            packet_padding: false,
+            binary_masquerade: false,
+            cover_traffic_interval_opt: None,
+            exit_policy: ExitPolicy::default(),
+            country_code_opt: None,
+            exit_location_opt: None,
+            latency_weight: DEFAULT_LATENCY_WEIGHT,
             data_directory: PathBuf::new(),
             node_descriptor: NodeDescriptor::default(),
             main_cryptde_null_opt: None,
@@ -401,6 +430,7 @@ impl BootstrapperConfig {
                 min_hops: DEFAULT_MIN_HOPS,
             },
             when_pending_too_long_sec: DEFAULT_PENDING_TOO_LONG_SEC,
+            pending_scans_before_replacement: DEFAULT_PENDING_SCANS_BEFORE_REPLACEMENT,
         }
     }
 
@@ -418,6 +448,7 @@ impl BootstrapperConfig {
         self.suppress_initial_scans = unprivileged.suppress_initial_scans;
         self.payment_thresholds_opt = unprivileged.payment_thresholds_opt;
         self.when_pending_too_long_sec = unprivileged.when_pending_too_long_sec;
+        self.pending_scans_before_replacement = unprivileged.pending_scans_before_replacement;
     }
 
     pub fn exit_service_rate(&self) -> u64 {
@@ -499,12 +530,7 @@ impl ConfiguredByPrivilege for Bootstrapper {
             NodeConfiguratorStandardUnprivileged::new(&self.config).configure(multi_config)?;
         self.config.merge_unprivileged(unprivileged_config);
         let _ = self.set_up_clandestine_port();
-        let (alias_cryptde_null_opt, main_cryptde_null_opt) = self.null_cryptdes_as_trait_objects();
-        let cryptdes = Bootstrapper::initialize_cryptdes(
-            &main_cryptde_null_opt,
-            &alias_cryptde_null_opt,
-            self.config.blockchain_bridge_config.chain,
-        );
+        let cryptdes = self.establish_cryptdes();
         let node_descriptor = Bootstrapper::make_local_descriptor(
             cryptdes.main,
             self.config.neighborhood_config.mode.node_addr_opt(),
@@ -553,6 +579,86 @@ impl Bootstrapper {
         )
     }
 
+    // Outside of --fake-public-key, a Node started with a database password keeps its keys, and
+    // so its identity, from one run to the next.
+    fn establish_cryptdes(&self) -> CryptDEPair {
+        let (alias_cryptde_null_opt, main_cryptde_null_opt) = self.null_cryptdes_as_trait_objects();
+        let chain = self.config.blockchain_bridge_config.chain;
+        match (
+            &main_cryptde_null_opt,
+            &alias_cryptde_null_opt,
+            &self.config.db_password_opt,
+        ) {
+            (None, None, Some(db_password)) => {
+                let conn = DbInitializerReal::default()
+                    .initialize(
+                        &self.config.data_directory,
+                        DbInitializationConfig::panic_on_migration(),
+                    )
+                    .unwrap_or_else(|err| {
+                        db_connection_launch_panic(err, &self.config.data_directory)
+                    });
+                let mut persistent_config = PersistentConfigurationReal::from(conn);
+                Self::initialize_persisted_cryptdes(&mut persistent_config, db_password, chain)
+            }
+            _ => Self::initialize_cryptdes(&main_cryptde_null_opt, &alias_cryptde_null_opt, chain),
+        }
+    }
+
+    fn initialize_persisted_cryptdes(
+        persistent_config: &mut dyn PersistentConfiguration,
+        db_password: &str,
+        chain: Chain,
+    ) -> CryptDEPair {
+        let logger = Logger::new("Bootstrapper");
+        match persistent_config.cryptde_keys(db_password) {
+            Ok(Some((main_key, alias_key))) => match (
+                CryptDEReal::from_private_key(&main_key, chain),
+                CryptDEReal::from_private_key(&alias_key, chain),
+            ) {
+                (Ok(main_cryptde), Ok(alias_cryptde)) => {
+                    unsafe {
+                        let _ = MAIN_CRYPTDE_BOX_OPT.replace(Box::new(main_cryptde));
+                        let _ = ALIAS_CRYPTDE_BOX_OPT.replace(Box::new(alias_cryptde));
+                    }
+                    CryptDEPair::default()
+                }
+                (Err(e), _) | (_, Err(e)) => {
+                    error!(
+                        logger,
+                        "Stored keys are unusable: {}; this run will use new keys that won't be saved",
+                        e
+                    );
+                    Self::initialize_cryptdes(&None, &None, chain)
+                }
+            },
+            Ok(None) => {
+                let cryptdes = Self::initialize_cryptdes(&None, &None, chain);
+                match persistent_config.set_cryptde_keys(
+                    cryptdes.main.private_key(),
+                    cryptdes.alias.private_key(),
+                    db_password,
+                ) {
+                    Ok(_) => info!(logger, "Saved new keys for this Node"),
+                    Err(e) => warning!(
+                        logger,
+                        "Could not save new keys: {:?}; this Node will have a different identity next time",
+                        e
+                    ),
+                }
+                cryptdes
+            }
+            Err(e) => {
+                warning!(
+                    logger,
+                    "Could not read stored keys: {:?}; this run will use new keys that won't be saved",
+                    e
+                );
+                Self::initialize_cryptdes(&None, &None, chain)
+            }
+        }
+    }
+
     fn initialize_cryptdes(
         main_cryptde_null_opt: &Option<&dyn CryptDE>,
         alias_cryptde_null_opt: &Option<&dyn CryptDE>,
@@ -648,9 +754,11 @@ impl Bootstrapper {
                     .bind_port_and_configuration(
                         clandestine_port,
                         PortConfiguration {
-                            discriminator_factories: vec![
-                                Box::new(JsonDiscriminatorFactory::new()),
-                            ],
+                            discriminator_factories: vec![Box::new(
+                                ClandestineDiscriminatorFactory::new(MasqueraderKind::supported(
+                                    self.config.binary_masquerade,
+                                )),
+                            )],
                             is_clandestine: true,
                         },
                     )
@@ -667,7 +775,9 @@ impl Bootstrapper {
             };
         self.config
             .clandestine_discriminator_factories
-            .push(Box::new(JsonDiscriminatorFactory::new()));
+            .push(Box::new(ClandestineDiscriminatorFactory::new(
+                MasqueraderKind::supported(self.config.binary_masquerade),
+            )));
         clandestine_port_opt
     }
 
@@ -724,6 +834,7 @@ mod tests {
     use crate::discriminator::Discriminator;
     use crate::discriminator::UnmaskedChunk;
     use crate::listener_handler::{ListenerHandler, ListenerHandlerFactory};
+    use crate::masquerader::MasqueraderKind;
     use crate::node_test_utils::{extract_log, DirsWrapperMock, IdWrapperMock};
     use crate::node_test_utils::{make_stream_handler_pool_subs_from_recorder, TestLogOwner};
     use crate::server_initializer::test_utils::LoggerInitializerWrapperMock;
@@ -732,8 +843,9 @@ mod tests {
     use crate::stream_messages::AddStreamMsg;
     use crate::sub_lib::accountant::ScanIntervals;
     use crate::sub_lib::cryptde::PublicKey;
-    use crate::sub_lib::cryptde::{CryptDE, PlainData};
+    use crate::sub_lib::cryptde::{CryptDE, PlainData, PrivateKey};
     use crate::sub_lib::cryptde_null::CryptDENull;
+    use crate::sub_lib::cryptde_real::CryptDEReal;
     use crate::sub_lib::neighborhood::{
         NeighborhoodConfig, NeighborhoodMode, NodeDescriptor, DEFAULT_RATE_PACK,
     };
@@ -1252,6 +1364,7 @@ mod tests {
         unprivileged_config.scan_intervals_opt = Some(ScanIntervals::default());
         unprivileged_config.suppress_initial_scans = false;
         unprivileged_config.when_pending_too_long_sec = DEFAULT_PENDING_TOO_LONG_SEC;
+        unprivileged_config.pending_scans_before_replacement = 2;
 
         privileged_config.merge_unprivileged(unprivileged_config);
 
@@ -1280,6 +1393,7 @@ mod tests {
             privileged_config.when_pending_too_long_sec,
             DEFAULT_PENDING_TOO_LONG_SEC
         );
+        assert_eq!(privileged_config.pending_scans_before_replacement, 2);
         //some values from the privileged config
         assert_eq!(privileged_config.log_level, Off);
         assert_eq!(
@@ -1551,6 +1665,106 @@ mod tests {
         assert_eq!(main_cryptde_ref().public_key(), cryptdes.main.public_key());
     }
 
+    #[test]
+    fn initialize_persisted_cryptdes_restores_stored_keys() {
+        let _lock = INITIALIZATION.lock();
+        let main_cryptde = CryptDEReal::new(TEST_DEFAULT_CHAIN);
+        let alias_cryptde = CryptDEReal::new(TEST_DEFAULT_CHAIN);
+        let cryptde_keys_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut persistent_config = PersistentConfigurationMock::new()
+            .cryptde_keys_params(&cryptde_keys_params_arc)
+            .cryptde_keys_result(Ok(Some((
+                main_cryptde.private_key().clone(),
+                alias_cryptde.private_key().clone(),
+            ))));
+
+        let cryptdes = Bootstrapper::initialize_persisted_cryptdes(
+            &mut persistent_config,
+            "password",
+            TEST_DEFAULT_CHAIN,
+        );
+
+        assert_eq!(cryptdes.main.public_key(), main_cryptde.public_key());
+        assert_eq!(cryptdes.alias.public_key(), alias_cryptde.public_key());
+        assert_eq!(main_cryptde_ref().public_key(), main_cryptde.public_key());
+        let cryptde_keys_params = cryptde_keys_params_arc.lock().unwrap();
+        assert_eq!(*cryptde_keys_params, vec!["password".to_string()]);
+    }
+
+    #[test]
+    fn initialize_persisted_cryptdes_stores_new_keys_when_there_are_none() {
+        let _lock = INITIALIZATION.lock();
+        init_test_logging();
+        let set_cryptde_keys_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut persistent_config = PersistentConfigurationMock::new()
+            .cryptde_keys_result(Ok(None))
+            .set_cryptde_keys_params(&set_cryptde_keys_params_arc)
+            .set_cryptde_keys_result(Ok(()));
+
+        let cryptdes = Bootstrapper::initialize_persisted_cryptdes(
+            &mut persistent_config,
+            "password",
+            TEST_DEFAULT_CHAIN,
+        );
+
+        let set_cryptde_keys_params = set_cryptde_keys_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_cryptde_keys_params,
+            vec![(
+                cryptdes.main.private_key().clone(),
+                cryptdes.alias.private_key().clone(),
+                "password".to_string()
+            )]
+        );
+        let restored =
+            CryptDEReal::from_private_key(&set_cryptde_keys_params[0].0, TEST_DEFAULT_CHAIN)
+                .unwrap();
+        assert_eq!(restored.public_key(), cryptdes.main.public_key());
+        TestLogHandler::new()
+            .exists_log_containing("INFO: Bootstrapper: Saved new keys for this Node");
+    }
+
+    #[test]
+    fn initialize_persisted_cryptdes_uses_unsaved_new_keys_when_stored_ones_cannot_be_read() {
+        let _lock = INITIALIZATION.lock();
+        init_test_logging();
+        let mut persistent_config = PersistentConfigurationMock::new()
+            .cryptde_keys_result(Err(PersistentConfigError::PasswordError));
+
+        let cryptdes = Bootstrapper::initialize_persisted_cryptdes(
+            &mut persistent_config,
+            "bad password",
+            TEST_DEFAULT_CHAIN,
+        );
+
+        assert_eq!(main_cryptde_ref().public_key(), cryptdes.main.public_key());
+        TestLogHandler::new().exists_log_containing(
+            "WARN: Bootstrapper: Could not read stored keys: PasswordError; this run will use new keys that won't be saved",
+        );
+    }
+
+    #[test]
+    fn initialize_persisted_cryptdes_does_not_replace_unusable_stored_keys() {
+        let _lock = INITIALIZATION.lock();
+        init_test_logging();
+        let mut persistent_config =
+            PersistentConfigurationMock::new().cryptde_keys_result(Ok(Some((
+                PrivateKey::new(&[1, 2, 3, 4]),
+                PrivateKey::new(&[5, 6, 7, 8]),
+            ))));
+
+        let cryptdes = Bootstrapper::initialize_persisted_cryptdes(
+            &mut persistent_config,
+            "password",
+            TEST_DEFAULT_CHAIN,
+        );
+
+        assert_eq!(main_cryptde_ref().public_key(), cryptdes.main.public_key());
+        TestLogHandler::new().exists_log_containing(
+            "ERROR: Bootstrapper: Stored keys are unusable: Private key must be 96 bytes long, not 4; this run will use new keys that won't be saved",
+        );
+    }
+
     #[test]
     fn initialize_cryptde_and_report_local_descriptor_with_ip_address() {
         let _lock = INITIALIZATION.lock();
@@ -1899,6 +2113,19 @@ mod tests {
             }),
             discriminator.take_chunk(),
         );
+        let tls_masquerade = MasqueraderKind::Tls
+            .make_masquerader(true)
+            .mask(b"Agoob")
+            .unwrap();
+        discriminator.add_data(&tls_masquerade);
+        assert_eq!(
+            Some(UnmaskedChunk {
+                chunk: b"Agoob".to_vec(),
+                last_chunk: true,
+                sequenced: false,
+            }),
+            discriminator.take_chunk(),
+        );
         assert_eq!(0, clandestine_discriminators.len()); // Used to be 1, now 0 after removal
     }
 
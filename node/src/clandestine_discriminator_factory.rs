@@ -0,0 +1,88 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::clandestine_framer::ClandestineFramer;
+use crate::discriminator::Discriminator;
+use crate::discriminator::DiscriminatorFactory;
+use crate::masquerader::MasqueraderKind;
+
+#[derive(Debug)]
+pub struct ClandestineDiscriminatorFactory {
+    masquerader_kinds: Vec<MasqueraderKind>,
+}
+
+impl DiscriminatorFactory for ClandestineDiscriminatorFactory {
+    fn make(&self) -> Discriminator {
+        Discriminator::new(
+            Box::new(ClandestineFramer::new(self.masquerader_kinds.clone())),
+            self.masquerader_kinds
+                .iter()
+                .map(|kind| kind.make_masquerader(false))
+                .collect(),
+        )
+    }
+
+    fn duplicate(&self) -> Box<dyn DiscriminatorFactory> {
+        Box::new(ClandestineDiscriminatorFactory::new(
+            self.masquerader_kinds.clone(),
+        ))
+    }
+}
+
+impl ClandestineDiscriminatorFactory {
+    pub fn new(masquerader_kinds: Vec<MasqueraderKind>) -> ClandestineDiscriminatorFactory {
+        ClandestineDiscriminatorFactory { masquerader_kinds }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discriminator::UnmaskedChunk;
+
+    #[test]
+    fn discriminator_factory_duplicate_works() {
+        let subject = ClandestineDiscriminatorFactory::new(MasqueraderKind::supported(true));
+
+        subject.duplicate();
+
+        // no panic; test passes
+    }
+
+    #[test]
+    fn factory_makes_discriminator_that_unmasks_every_supported_kind() {
+        let subject = ClandestineDiscriminatorFactory::new(MasqueraderKind::supported(true));
+        let mut discriminator = subject.make();
+
+        MasqueraderKind::supported(true)
+            .into_iter()
+            .for_each(|kind| {
+                let data = format!("I am contained in {:?}!", kind).into_bytes();
+                let masked = kind.make_masquerader(true).mask(&data).unwrap();
+
+                discriminator.add_data(&masked);
+                let result = discriminator.take_chunk();
+
+                assert_eq!(
+                    result,
+                    Some(UnmaskedChunk::new(data, true, false)),
+                    "{:?}",
+                    kind
+                );
+            });
+    }
+
+    #[test]
+    fn factory_makes_discriminator_that_ignores_unsupported_kinds() {
+        let data = b"I am contained in TLS!";
+        let masked = MasqueraderKind::Tls
+            .make_masquerader(true)
+            .mask(data)
+            .unwrap();
+        let subject = ClandestineDiscriminatorFactory::new(vec![MasqueraderKind::Json]);
+        let mut discriminator = subject.make();
+
+        discriminator.add_data(&masked);
+        let result = discriminator.take_chunk();
+
+        assert_eq!(result, None)
+    }
+}
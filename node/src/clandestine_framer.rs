@@ -0,0 +1,177 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::binary_framer::BinaryFramer;
+use crate::http_masquerader::HttpMasquerader;
+use crate::json_framer::JsonFramer;
+use crate::masquerader::MasqueraderKind;
+use crate::sub_lib::framer::FramedChunk;
+use crate::sub_lib::framer::Framer;
+use crate::tls_masquerader::TlsMasquerader;
+
+// Each Node picks the masquerade it sends to a neighbor, so the frames arriving on a single
+// clandestine stream may wear different masquerades. The first byte of each frame says which.
+pub struct ClandestineFramer {
+    masquerader_kinds: Vec<MasqueraderKind>,
+    data_so_far: Vec<u8>,
+}
+
+impl Framer for ClandestineFramer {
+    fn add_data(&mut self, data: &[u8]) {
+        self.data_so_far.extend(data);
+    }
+
+    fn take_frame(&mut self) -> Option<FramedChunk> {
+        let start = self
+            .data_so_far
+            .iter()
+            .position(|byte| self.recognize(*byte).is_some());
+        let kind = match start {
+            Some(start) => {
+                self.data_so_far.drain(0..start);
+                self.recognize(self.data_so_far[0])
+                    .expect("Kind disappeared")
+            }
+            None => {
+                self.data_so_far.clear();
+                return None;
+            }
+        };
+        let frame_length = Self::frame_length(kind, &self.data_so_far)?;
+        let leftovers = self.data_so_far.split_off(frame_length);
+        Some(FramedChunk {
+            chunk: std::mem::replace(&mut self.data_so_far, leftovers),
+            last_chunk: true,
+        })
+    }
+}
+
+impl ClandestineFramer {
+    pub fn new(masquerader_kinds: Vec<MasqueraderKind>) -> ClandestineFramer {
+        ClandestineFramer {
+            masquerader_kinds,
+            data_so_far: vec![],
+        }
+    }
+
+    fn recognize(&self, byte: u8) -> Option<MasqueraderKind> {
+        MasqueraderKind::recognize(byte).filter(|kind| self.masquerader_kinds.contains(kind))
+    }
+
+    fn frame_length(kind: MasqueraderKind, data: &[u8]) -> Option<usize> {
+        match kind {
+            MasqueraderKind::Json => {
+                let mut json_framer = JsonFramer::new();
+                json_framer.add_data(data);
+                json_framer.take_frame().map(|frame| frame.chunk.len())
+            }
+            MasqueraderKind::Http => HttpMasquerader::frame_length(data),
+            MasqueraderKind::Tls => TlsMasquerader::frame_length(data),
+            MasqueraderKind::Binary => BinaryFramer::frame_length(data),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn masked(kind: MasqueraderKind, data: &[u8]) -> Vec<u8> {
+        kind.make_masquerader(true).mask(data).unwrap()
+    }
+
+    #[test]
+    fn frames_each_kind_of_masquerade_on_the_same_stream() {
+        let json = masked(MasqueraderKind::Json, b"first");
+        let http = masked(MasqueraderKind::Http, b"second");
+        let tls = masked(MasqueraderKind::Tls, b"third");
+        let binary = masked(MasqueraderKind::Binary, b"fourth");
+        let mut all_data = json.clone();
+        all_data.extend(&http);
+        all_data.extend(&tls);
+        all_data.extend(&binary);
+        let mut subject = ClandestineFramer::new(MasqueraderKind::supported(true));
+
+        subject.add_data(&all_data);
+
+        assert_eq!(
+            subject.take_frame(),
+            Some(FramedChunk {
+                chunk: json,
+                last_chunk: true
+            })
+        );
+        assert_eq!(
+            subject.take_frame(),
+            Some(FramedChunk {
+                chunk: http,
+                last_chunk: true
+            })
+        );
+        assert_eq!(
+            subject.take_frame(),
+            Some(FramedChunk {
+                chunk: tls,
+                last_chunk: true
+            })
+        );
+        assert_eq!(
+            subject.take_frame(),
+            Some(FramedChunk {
+                chunk: binary,
+                last_chunk: true
+            })
+        );
+        assert_eq!(subject.take_frame(), None);
+    }
+
+    #[test]
+    fn waits_for_the_rest_of_a_partial_frame() {
+        let tls = masked(MasqueraderKind::Tls, b"booga");
+        let mut subject = ClandestineFramer::new(MasqueraderKind::supported(true));
+
+        subject.add_data(&tls[0..7]);
+        let first_result = subject.take_frame();
+        subject.add_data(&tls[7..]);
+        let second_result = subject.take_frame();
+
+        assert_eq!(first_result, None);
+        assert_eq!(
+            second_result,
+            Some(FramedChunk {
+                chunk: tls,
+                last_chunk: true
+            })
+        );
+    }
+
+    #[test]
+    fn skips_data_that_does_not_begin_a_supported_masquerade() {
+        let http = masked(MasqueraderKind::Http, b"booga");
+        let mut data = b"garbage".to_vec();
+        data.extend(masked(MasqueraderKind::Tls, b"unsupported"));
+        data.extend(&http);
+        let mut subject =
+            ClandestineFramer::new(vec![MasqueraderKind::Http, MasqueraderKind::Json]);
+
+        subject.add_data(&data);
+
+        assert_eq!(
+            subject.take_frame(),
+            Some(FramedChunk {
+                chunk: http,
+                last_chunk: true
+            })
+        );
+        assert_eq!(subject.take_frame(), None);
+    }
+
+    #[test]
+    fn discards_data_that_contains_no_masquerade() {
+        let mut subject = ClandestineFramer::new(vec![MasqueraderKind::Json]);
+
+        subject.add_data(b"no braces here");
+        let result = subject.take_frame();
+
+        assert_eq!(result, None);
+        assert!(subject.data_so_far.is_empty());
+    }
+}
@@ -138,6 +138,8 @@ impl DbInitializerReal {
         Self::create_pending_payable_table(conn);
         Self::create_receivable_table(conn);
         Self::create_banned_table(conn);
+        Self::create_node_records_table(conn);
+        Self::create_node_reputations_table(conn);
     }
 
     pub fn create_config_table(conn: &Connection) {
@@ -256,6 +258,48 @@ impl DbInitializerReal {
             "scan intervals",
         );
         Self::set_config_value(conn, "max_block_count", None, false, "maximum block count");
+        Self::set_config_value(
+            conn,
+            "max_rate_pack",
+            None,
+            false,
+            "highest rates a route may charge",
+        );
+        Self::set_config_value(
+            conn,
+            "preferred_exits",
+            None,
+            false,
+            "exit nodes pinned by public key",
+        );
+        Self::set_config_value(
+            conn,
+            "excluded_nodes",
+            None,
+            false,
+            "nodes never used in routes",
+        );
+        Self::set_config_value(
+            conn,
+            "main_cryptde_key",
+            None,
+            true,
+            "private key of the Node's public identity",
+        );
+        Self::set_config_value(
+            conn,
+            "alias_cryptde_key",
+            None,
+            true,
+            "private key used for return routes",
+        );
+        Self::set_config_value(
+            conn,
+            "transaction_fees",
+            None,
+            false,
+            "fees agreed for the latest payments",
+        );
     }
 
     pub fn create_pending_payable_table(conn: &Connection) {
@@ -267,7 +311,11 @@ impl DbInitializerReal {
                     amount_low_b integer not null,
                     payable_timestamp integer not null,
                     attempt integer not null,
-                    process_error text null
+                    process_error text null,
+                    recipient_wallet text null,
+                    nonce integer null,
+                    transaction_fees text null,
+                    replacement_transaction_hashes text null
             )",
             [],
         )
@@ -308,10 +356,57 @@ impl DbInitializerReal {
 
     pub fn create_banned_table(conn: &Connection) {
         conn.execute(
-            "create table banned ( wallet_address text primary key )",
+            "create table banned (
+                wallet_address text primary key,
+                banned_at integer,
+                manual integer not null default 0,
+                note text,
+                balance_gwei integer,
+                debt_age_sec integer
+            )",
             [],
         )
         .expect("Can't create banned table");
+        conn.execute(
+            "create table ban_audit (
+                wallet_address text not null,
+                action text not null,
+                note text,
+                timestamp integer not null
+            )",
+            [],
+        )
+        .expect("Can't create ban_audit table");
+    }
+
+    pub fn create_node_records_table(conn: &Connection) {
+        conn.execute(
+            "create table if not exists node_records (
+                public_key blob primary key,
+                signed_data blob not null,
+                signature blob not null,
+                node_addr text null,
+                last_update integer not null
+            )",
+            [],
+        )
+        .expect("Can't create node_records table");
+    }
+
+    pub fn create_node_reputations_table(conn: &Connection) {
+        conn.execute(
+            "create table if not exists node_reputations (
+                public_key blob primary key,
+                penalty integer not null,
+                last_failure integer not null,
+                connection_failures integer not null,
+                stream_drops integer not null,
+                dns_failures integer not null,
+                service_refusals integer not null default 0
+            )",
+            [],
+        )
+        .expect("Can't create node_reputations table");
     }
 
     fn extra_configuration(
@@ -652,7 +747,7 @@ mod tests {
     #[test]
     fn constants_have_correct_values() {
         assert_eq!(DATABASE_FILE, "node-data.db");
-        assert_eq!(CURRENT_SCHEMA_VERSION, 10);
+        assert_eq!(CURRENT_SCHEMA_VERSION, 19);
     }
 
     #[test]
@@ -692,7 +787,7 @@ mod tests {
             .initialize(&home_dir, DbInitializationConfig::test_default())
             .unwrap();
 
-        let mut stmt = conn.prepare("select rowid, transaction_hash, amount_high_b, amount_low_b, payable_timestamp, attempt, process_error from pending_payable").unwrap();
+        let mut stmt = conn.prepare("select rowid, transaction_hash, amount_high_b, amount_low_b, payable_timestamp, attempt, process_error, recipient_wallet, nonce, transaction_fees, replacement_transaction_hashes from pending_payable").unwrap();
         let mut payable_contents = stmt.query_map([], |_| Ok(42)).unwrap();
         assert!(payable_contents.next().is_none());
         let expected_key_words: &[&[&str]] = &[
@@ -703,6 +798,10 @@ mod tests {
             &["payable_timestamp", "integer", "not", "null"],
             &["attempt", "integer", "not", "null"],
             &["process_error", "text", "null"],
+            &["recipient_wallet", "text", "null"],
+            &["nonce", "integer", "null"],
+            &["transaction_fees", "text", "null"],
+            &["replacement_transaction_hashes", "text", "null"],
         ];
         assert_create_table_stm_contains_all_parts(&*conn, "pending_payable", expected_key_words);
         let expected_key_words: &[&[&str]] = &[&["transaction_hash"]];
@@ -784,9 +883,90 @@ mod tests {
         let mut stmt = conn.prepare("select wallet_address from banned").unwrap();
         let mut banned_contents = stmt.query_map([], |_| Ok(42)).unwrap();
         assert!(banned_contents.next().is_none());
-        let expected_key_words: &[&[&str]] = &[&["wallet_address", "text", "primary", "key"]];
+        let expected_key_words: &[&[&str]] = &[
+            &["wallet_address", "text", "primary", "key"],
+            &["banned_at", "integer"],
+            &["manual", "integer", "not", "null", "default", "0"],
+            &["note", "text"],
+            &["balance_gwei", "integer"],
+            &["debt_age_sec", "integer"],
+        ];
         assert_create_table_stm_contains_all_parts(conn.as_ref(), "banned", expected_key_words);
-        assert_no_index_exists_for_table(conn.as_ref(), "banned")
+        assert_no_index_exists_for_table(conn.as_ref(), "banned");
+        let expected_key_words: &[&[&str]] = &[
+            &["wallet_address", "text", "not", "null"],
+            &["action", "text", "not", "null"],
+            &["note", "text"],
+            &["timestamp", "integer", "not", "null"],
+        ];
+        assert_create_table_stm_contains_all_parts(conn.as_ref(), "ban_audit", expected_key_words);
+        assert_no_index_exists_for_table(conn.as_ref(), "ban_audit")
+    }
+
+    #[test]
+    fn db_initialize_creates_node_records_table() {
+        let home_dir = ensure_node_home_directory_does_not_exist(
+            "db_initializer",
+            "db_initialize_creates_node_records_table",
+        );
+        let subject = DbInitializerReal::default();
+
+        let conn = subject
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+
+        let mut stmt = conn
+            .prepare("select public_key, signed_data, signature, node_addr, last_update from node_records")
+            .unwrap();
+        let mut node_records_contents = stmt.query_map([], |_| Ok(42)).unwrap();
+        assert!(node_records_contents.next().is_none());
+        let expected_key_words: &[&[&str]] = &[
+            &["public_key", "blob", "primary", "key"],
+            &["signed_data", "blob", "not", "null"],
+            &["signature", "blob", "not", "null"],
+            &["node_addr", "text", "null"],
+            &["last_update", "integer", "not", "null"],
+        ];
+        assert_create_table_stm_contains_all_parts(
+            conn.as_ref(),
+            "node_records",
+            expected_key_words,
+        );
+        assert_no_index_exists_for_table(conn.as_ref(), "node_records")
+    }
+
+    #[test]
+    fn db_initialize_creates_node_reputations_table() {
+        let home_dir = ensure_node_home_directory_does_not_exist(
+            "db_initializer",
+            "db_initialize_creates_node_reputations_table",
+        );
+        let subject = DbInitializerReal::default();
+
+        let conn = subject
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+
+        let mut stmt = conn
+            .prepare("select public_key, penalty, last_failure, connection_failures, stream_drops, dns_failures, service_refusals from node_reputations")
+            .unwrap();
+        let mut node_reputations_contents = stmt.query_map([], |_| Ok(42)).unwrap();
+        assert!(node_reputations_contents.next().is_none());
+        let expected_key_words: &[&[&str]] = &[
+            &["public_key", "blob", "primary", "key"],
+            &["penalty", "integer", "not", "null"],
+            &["last_failure", "integer", "not", "null"],
+            &["connection_failures", "integer", "not", "null"],
+            &["stream_drops", "integer", "not", "null"],
+            &["dns_failures", "integer", "not", "null"],
+            &["service_refusals", "integer", "not", "null", "default", "0"],
+        ];
+        assert_create_table_stm_contains_all_parts(
+            conn.as_ref(),
+            "node_reputations",
+            expected_key_words,
+        );
+        assert_no_index_exists_for_table(conn.as_ref(), "node_reputations")
     }
 
     #[test]
@@ -906,6 +1086,7 @@ mod tests {
             assert_eq!(actual_encrypted, expected_encrypted);
             value
         };
+        verify(&mut config_vec, "alias_cryptde_key", None, true);
         verify(&mut config_vec, "blockchain_service_url", None, false);
         verify(
             &mut config_vec,
@@ -920,14 +1101,17 @@ mod tests {
         verify(&mut config_vec, "consuming_wallet_private_key", None, true);
         verify(&mut config_vec, "earning_wallet_address", None, false);
         verify(&mut config_vec, EXAMPLE_ENCRYPTED, None, true);
+        verify(&mut config_vec, "excluded_nodes", None, false);
         verify(
             &mut config_vec,
             "gas_price",
             Some(&DEFAULT_GAS_PRICE.to_string()),
             false,
         );
+        verify(&mut config_vec, "main_cryptde_key", None, true);
         verify(&mut config_vec, "mapping_protocol", None, false);
         verify(&mut config_vec, "max_block_count", None, false);
+        verify(&mut config_vec, "max_rate_pack", None, false);
         verify(&mut config_vec, "min_hops", Some("3"), false);
         verify(
             &mut config_vec,
@@ -943,6 +1127,7 @@ mod tests {
             false,
         );
         verify(&mut config_vec, "preexisting", Some("yes"), false); // making sure we opened the preexisting database
+        verify(&mut config_vec, "preferred_exits", None, false);
         verify(
             &mut config_vec,
             "rate_pack",
@@ -962,6 +1147,7 @@ mod tests {
             false,
         );
         verify(&mut config_vec, "start_block", None, false);
+        verify(&mut config_vec, "transaction_fees", None, false);
         assert_eq!(config_vec, vec![]);
     }
 
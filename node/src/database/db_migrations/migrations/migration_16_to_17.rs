@@ -0,0 +1,124 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::database::db_migrations::db_migrator::DatabaseMigration;
+use crate::database::db_migrations::migrator_utils::DBMigDeclarator;
+
+#[allow(non_camel_case_types)]
+pub struct Migrate_16_to_17;
+
+impl DatabaseMigration for Migrate_16_to_17 {
+    fn migrate<'a>(
+        &self,
+        declaration_utils: Box<dyn DBMigDeclarator + 'a>,
+    ) -> rusqlite::Result<()> {
+        // Bans recorded before now were all made by the delinquency scanner, which kept no facts
+        declaration_utils.execute_upon_transaction(&[
+            &"alter table banned add column banned_at integer",
+            &"alter table banned add column manual integer not null default 0",
+            &"alter table banned add column note text",
+            &"alter table banned add column balance_gwei integer",
+            &"alter table banned add column debt_age_sec integer",
+            &"create table if not exists ban_audit (
+                wallet_address text not null,
+                action text not null,
+                note text,
+                timestamp integer not null
+            )",
+        ])
+    }
+
+    fn old_version(&self) -> usize {
+        16
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::db_initializer::{
+        DbInitializationConfig, DbInitializer, DbInitializerReal, DATABASE_FILE,
+    };
+    use crate::test_utils::database_utils::{
+        assert_create_table_stm_contains_all_parts, assert_table_does_not_exist,
+        bring_db_0_back_to_life_and_return_connection, make_external_data, retrieve_config_row,
+    };
+    use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+    use std::fs::create_dir_all;
+
+    #[test]
+    fn migration_from_16_to_17_is_properly_set() {
+        init_test_logging();
+        let dir_path = ensure_node_home_directory_exists(
+            "db_migrations",
+            "migration_from_16_to_17_is_properly_set",
+        );
+        create_dir_all(&dir_path).unwrap();
+        let db_path = dir_path.join(DATABASE_FILE);
+        let _ = bring_db_0_back_to_life_and_return_connection(&db_path);
+        let subject = DbInitializerReal::default();
+        let connection = subject
+            .initialize_to_version(
+                &dir_path,
+                16,
+                DbInitializationConfig::create_or_migrate(make_external_data()),
+            )
+            .unwrap();
+        assert_table_does_not_exist(connection.as_ref(), "ban_audit");
+        connection
+            .prepare("insert into banned (wallet_address) values ('0x000000000000000000000000000000626f6f6761')")
+            .unwrap()
+            .execute([])
+            .unwrap();
+
+        let result = subject.initialize_to_version(
+            &dir_path,
+            17,
+            DbInitializationConfig::create_or_migrate(make_external_data()),
+        );
+
+        let connection = result.unwrap();
+        let (banned_at, manual, note, balance_gwei, debt_age_sec): (
+            Option<i64>,
+            i64,
+            Option<String>,
+            Option<i64>,
+            Option<i64>,
+        ) = connection
+            .prepare(
+                "select banned_at, manual, note, balance_gwei, debt_age_sec from banned \
+                 where wallet_address = '0x000000000000000000000000000000626f6f6761'",
+            )
+            .unwrap()
+            .query_row([], |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                ))
+            })
+            .unwrap();
+        assert_eq!(
+            (banned_at, manual, note, balance_gwei, debt_age_sec),
+            (None, 0, None, None, None)
+        );
+        let expected_key_words: &[&[&str]] = &[
+            &["wallet_address", "text", "not", "null"],
+            &["action", "text", "not", "null"],
+            &["note", "text"],
+            &["timestamp", "integer", "not", "null"],
+        ];
+        assert_create_table_stm_contains_all_parts(
+            connection.as_ref(),
+            "ban_audit",
+            expected_key_words,
+        );
+        let (cs_value, cs_encrypted) = retrieve_config_row(connection.as_ref(), "schema_version");
+        assert_eq!(cs_value, Some(17.to_string()));
+        assert_eq!(cs_encrypted, false);
+        TestLogHandler::new().assert_logs_contain_in_order(vec![
+            "DbMigrator: Database successfully migrated from version 16 to 17",
+        ]);
+    }
+}
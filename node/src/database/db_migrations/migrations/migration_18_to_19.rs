@@ -0,0 +1,124 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::database::db_migrations::db_migrator::DatabaseMigration;
+use crate::database::db_migrations::migrator_utils::DBMigDeclarator;
+
+#[allow(non_camel_case_types)]
+pub struct Migrate_18_to_19;
+
+impl DatabaseMigration for Migrate_18_to_19 {
+    fn migrate<'a>(
+        &self,
+        declaration_utils: Box<dyn DBMigDeclarator + 'a>,
+    ) -> rusqlite::Result<()> {
+        // Transactions sent before now kept no signing details and so cannot ever be replaced
+        declaration_utils.execute_upon_transaction(&[
+            &"alter table pending_payable add column recipient_wallet text",
+            &"alter table pending_payable add column nonce integer",
+            &"alter table pending_payable add column transaction_fees text",
+            &"alter table pending_payable add column replacement_transaction_hashes text",
+        ])
+    }
+
+    fn old_version(&self) -> usize {
+        18
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::db_initializer::{
+        DbInitializationConfig, DbInitializer, DbInitializerReal, DATABASE_FILE,
+    };
+    use crate::test_utils::database_utils::{
+        assert_create_table_stm_contains_all_parts, bring_db_0_back_to_life_and_return_connection,
+        make_external_data, retrieve_config_row,
+    };
+    use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+    use std::fs::create_dir_all;
+
+    #[test]
+    fn migration_from_18_to_19_is_properly_set() {
+        init_test_logging();
+        let dir_path = ensure_node_home_directory_exists(
+            "db_migrations",
+            "migration_from_18_to_19_is_properly_set",
+        );
+        create_dir_all(&dir_path).unwrap();
+        let db_path = dir_path.join(DATABASE_FILE);
+        let _ = bring_db_0_back_to_life_and_return_connection(&db_path);
+        let subject = DbInitializerReal::default();
+        let connection = subject
+            .initialize_to_version(
+                &dir_path,
+                18,
+                DbInitializationConfig::create_or_migrate(make_external_data()),
+            )
+            .unwrap();
+        connection
+            .prepare(
+                "insert into pending_payable (transaction_hash, amount_high_b, amount_low_b, \
+                 payable_timestamp, attempt, process_error) values ('0x0000000000000000000000000000000000000000000000000000000000000bc1', 0, 5000, 1000, 1, null)",
+            )
+            .unwrap()
+            .execute([])
+            .unwrap();
+
+        let result = subject.initialize_to_version(
+            &dir_path,
+            19,
+            DbInitializationConfig::create_or_migrate(make_external_data()),
+        );
+
+        let connection = result.unwrap();
+        let (recipient_wallet, nonce, transaction_fees, replacement_transaction_hashes): (
+            Option<String>,
+            Option<i64>,
+            Option<String>,
+            Option<String>,
+        ) = connection
+            .prepare(
+                "select recipient_wallet, nonce, transaction_fees, replacement_transaction_hashes \
+                 from pending_payable where amount_low_b = 5000",
+            )
+            .unwrap()
+            .query_row([], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })
+            .unwrap();
+        assert_eq!(
+            (
+                recipient_wallet,
+                nonce,
+                transaction_fees,
+                replacement_transaction_hashes
+            ),
+            (None, None, None, None)
+        );
+        let expected_key_words: &[&[&str]] = &[
+            &["rowid", "integer", "primary", "key"],
+            &["transaction_hash", "text", "not", "null"],
+            &["amount_high_b", "integer", "not", "null"],
+            &["amount_low_b", "integer", "not", "null"],
+            &["payable_timestamp", "integer", "not", "null"],
+            &["attempt", "integer", "not", "null"],
+            &["process_error", "text", "null"],
+            &["recipient_wallet", "text"],
+            &["nonce", "integer"],
+            &["transaction_fees", "text"],
+            &["replacement_transaction_hashes", "text"],
+        ];
+        assert_create_table_stm_contains_all_parts(
+            connection.as_ref(),
+            "pending_payable",
+            expected_key_words,
+        );
+        let (cs_value, cs_encrypted) = retrieve_config_row(connection.as_ref(), "schema_version");
+        assert_eq!(cs_value, Some(19.to_string()));
+        assert_eq!(cs_encrypted, false);
+        TestLogHandler::new().assert_logs_contain_in_order(vec![
+            "DbMigrator: Database successfully migrated from version 18 to 19",
+        ]);
+    }
+}
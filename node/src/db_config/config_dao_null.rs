@@ -142,6 +142,12 @@ impl Default for ConfigDaoNull {
             (Some(DEFAULT_SCAN_INTERVALS.to_string()), false),
         );
         data.insert("max_block_count".to_string(), (None, false));
+        data.insert("max_rate_pack".to_string(), (None, false));
+        data.insert("preferred_exits".to_string(), (None, false));
+        data.insert("excluded_nodes".to_string(), (None, false));
+        data.insert("main_cryptde_key".to_string(), (None, true));
+        data.insert("alias_cryptde_key".to_string(), (None, true));
+        data.insert("transaction_fees".to_string(), (None, false));
         Self { data }
     }
 }
@@ -287,6 +293,12 @@ mod tests {
                 Some(format!("{}", CURRENT_SCHEMA_VERSION).as_str()),
             ),
             ("max_block_count", None),
+            ("max_rate_pack", None),
+            ("preferred_exits", None),
+            ("excluded_nodes", None),
+            ("main_cryptde_key", None),
+            ("alias_cryptde_key", None),
+            ("transaction_fees", None),
         ]
         .into_iter()
         .map(|(k, v_opt)| (k.to_string(), v_opt.map(|v| v.to_string())))
@@ -11,7 +11,9 @@ use crate::db_config::typed_config_layer::{
     TypedConfigLayerError,
 };
 use crate::sub_lib::accountant::{PaymentThresholds, ScanIntervals};
-use crate::sub_lib::cryptde::PlainData;
+use crate::sub_lib::blockchain_bridge::TransactionFees;
+use crate::sub_lib::cryptde::{PlainData, PrivateKey, PublicKey};
+use crate::sub_lib::exit_location::PreferredExits;
 use crate::sub_lib::neighborhood::{Hops, NodeDescriptor, RatePack};
 use crate::sub_lib::wallet::Wallet;
 use masq_lib::constants::{HIGHEST_USABLE_PORT, LOWEST_USABLE_INSECURE_PORT};
@@ -131,6 +133,17 @@ pub trait PersistentConfiguration {
         node_descriptors_opt: Option<Vec<NodeDescriptor>>,
         db_password: &str,
     ) -> Result<(), PersistentConfigError>;
+    // The main and alias private keys, present only if both have been stored
+    fn cryptde_keys(
+        &self,
+        db_password: &str,
+    ) -> Result<Option<(PrivateKey, PrivateKey)>, PersistentConfigError>;
+    fn set_cryptde_keys(
+        &mut self,
+        main_key: &PrivateKey,
+        alias_key: &PrivateKey,
+        db_password: &str,
+    ) -> Result<(), PersistentConfigError>;
     fn start_block(&self) -> Result<Option<u64>, PersistentConfigError>;
     fn set_start_block(&mut self, value_opt: Option<u64>) -> Result<(), PersistentConfigError>;
     fn max_block_count(&self) -> Result<Option<u64>, PersistentConfigError>;
@@ -150,8 +163,28 @@ pub trait PersistentConfiguration {
     fn set_payment_thresholds(&mut self, curves: String) -> Result<(), PersistentConfigError>;
     fn rate_pack(&self) -> Result<RatePack, PersistentConfigError>;
     fn set_rate_pack(&mut self, rate_pack: String) -> Result<(), PersistentConfigError>;
+    fn max_rate_pack(&self) -> Result<Option<RatePack>, PersistentConfigError>;
+    fn set_max_rate_pack(
+        &mut self,
+        max_rate_pack_opt: Option<RatePack>,
+    ) -> Result<(), PersistentConfigError>;
+    fn preferred_exits(&self) -> Result<Option<PreferredExits>, PersistentConfigError>;
+    fn set_preferred_exits(
+        &mut self,
+        preferred_exits_opt: Option<PreferredExits>,
+    ) -> Result<(), PersistentConfigError>;
+    fn excluded_nodes(&self) -> Result<Vec<PublicKey>, PersistentConfigError>;
+    fn set_excluded_nodes(
+        &mut self,
+        excluded_nodes: Vec<PublicKey>,
+    ) -> Result<(), PersistentConfigError>;
     fn scan_intervals(&self) -> Result<ScanIntervals, PersistentConfigError>;
     fn set_scan_intervals(&mut self, intervals: String) -> Result<(), PersistentConfigError>;
+    fn transaction_fees(&self) -> Result<Option<TransactionFees>, PersistentConfigError>;
+    fn set_transaction_fees(
+        &mut self,
+        transaction_fees: TransactionFees,
+    ) -> Result<(), PersistentConfigError>;
 
     arbitrary_id_stamp_in_trait!();
 }
@@ -406,6 +439,28 @@ impl PersistentConfiguration for PersistentConfigurationReal {
         )?)
     }
 
+    fn cryptde_keys(
+        &self,
+        db_password: &str,
+    ) -> Result<Option<(PrivateKey, PrivateKey)>, PersistentConfigError> {
+        let main_key_opt = self.encrypted_private_key("main_cryptde_key", db_password)?;
+        let alias_key_opt = self.encrypted_private_key("alias_cryptde_key", db_password)?;
+        match (main_key_opt, alias_key_opt) {
+            (Some(main_key), Some(alias_key)) => Ok(Some((main_key, alias_key))),
+            _ => Ok(None),
+        }
+    }
+
+    fn set_cryptde_keys(
+        &mut self,
+        main_key: &PrivateKey,
+        alias_key: &PrivateKey,
+        db_password: &str,
+    ) -> Result<(), PersistentConfigError> {
+        self.set_encrypted_private_key("main_cryptde_key", main_key, db_password)?;
+        self.set_encrypted_private_key("alias_cryptde_key", alias_key, db_password)
+    }
+
     fn start_block(&self) -> Result<Option<u64>, PersistentConfigError> {
         Ok(decode_u64(self.get("start_block")?)?)
     }
@@ -497,6 +552,70 @@ impl PersistentConfiguration for PersistentConfigurationReal {
         self.simple_set_method("rate_pack", rate_pack)
     }
 
+    fn max_rate_pack(&self) -> Result<Option<RatePack>, PersistentConfigError> {
+        Ok(decode_combined_params(
+            |str: &str| RatePack::try_from(str),
+            self.get("max_rate_pack")?,
+        )?)
+    }
+
+    fn set_max_rate_pack(
+        &mut self,
+        max_rate_pack_opt: Option<RatePack>,
+    ) -> Result<(), PersistentConfigError> {
+        Ok(self.dao.set(
+            "max_rate_pack",
+            max_rate_pack_opt.map(|rate_pack| rate_pack.to_string()),
+        )?)
+    }
+
+    fn preferred_exits(&self) -> Result<Option<PreferredExits>, PersistentConfigError> {
+        Ok(decode_combined_params(
+            PreferredExits::from_str,
+            self.get("preferred_exits")?,
+        )?)
+    }
+
+    fn set_preferred_exits(
+        &mut self,
+        preferred_exits_opt: Option<PreferredExits>,
+    ) -> Result<(), PersistentConfigError> {
+        Ok(self.dao.set(
+            "preferred_exits",
+            preferred_exits_opt.map(|preferred_exits| preferred_exits.to_string()),
+        )?)
+    }
+
+    fn excluded_nodes(&self) -> Result<Vec<PublicKey>, PersistentConfigError> {
+        let excluded_nodes_opt = decode_combined_params(
+            |str: &str| {
+                str.split(',')
+                    .map(PublicKey::from_str)
+                    .collect::<Result<Vec<PublicKey>, String>>()
+            },
+            self.get("excluded_nodes")?,
+        )?;
+        Ok(excluded_nodes_opt.unwrap_or_default())
+    }
+
+    fn set_excluded_nodes(
+        &mut self,
+        excluded_nodes: Vec<PublicKey>,
+    ) -> Result<(), PersistentConfigError> {
+        let value_opt = if excluded_nodes.is_empty() {
+            None
+        } else {
+            Some(
+                excluded_nodes
+                    .iter()
+                    .map(|key| key.to_string())
+                    .collect::<Vec<String>>()
+                    .join(","),
+            )
+        };
+        Ok(self.dao.set("excluded_nodes", value_opt)?)
+    }
+
     fn scan_intervals(&self) -> Result<ScanIntervals, PersistentConfigError> {
         self.combined_params_get_method(|str: &str| ScanIntervals::try_from(str), "scan_intervals")
     }
@@ -504,6 +623,20 @@ impl PersistentConfiguration for PersistentConfigurationReal {
     fn set_scan_intervals(&mut self, intervals: String) -> Result<(), PersistentConfigError> {
         self.simple_set_method("scan_intervals", intervals)
     }
+
+    fn transaction_fees(&self) -> Result<Option<TransactionFees>, PersistentConfigError> {
+        Ok(decode_combined_params(
+            |str: &str| TransactionFees::try_from(str),
+            self.get("transaction_fees")?,
+        )?)
+    }
+
+    fn set_transaction_fees(
+        &mut self,
+        transaction_fees: TransactionFees,
+    ) -> Result<(), PersistentConfigError> {
+        self.simple_set_method("transaction_fees", transaction_fees.to_config_value())
+    }
 }
 
 impl From<Box<dyn ConnectionWrapper>> for PersistentConfigurationReal {
@@ -539,6 +672,36 @@ impl PersistentConfigurationReal {
             .map_err(|e| panic!("Failure to encrypt consuming private key: {:?}", e))
     }
 
+    fn encrypted_private_key(
+        &self,
+        name: &str,
+        db_password: &str,
+    ) -> Result<Option<PrivateKey>, PersistentConfigError> {
+        let bytes_opt = decode_bytes(self.scl.decrypt(
+            self.get_record(name)?,
+            Some(db_password.to_string()),
+            &self.dao,
+        )?)?;
+        Ok(bytes_opt.map(|bytes| PrivateKey::new(bytes.as_slice())))
+    }
+
+    fn set_encrypted_private_key(
+        &mut self,
+        name: &str,
+        private_key: &PrivateKey,
+        db_password: &str,
+    ) -> Result<(), PersistentConfigError> {
+        Ok(self.dao.set(
+            name,
+            self.scl.encrypt(
+                name,
+                encode_bytes(Some(PlainData::new(private_key.as_slice())))?,
+                Some(db_password.to_string()),
+                &self.dao,
+            )?,
+        )?)
+    }
+
     fn validate_wallet_address(address: &str) -> bool {
         Wallet::from_str(address).is_ok()
     }
@@ -1661,6 +1824,64 @@ mod tests {
         );
     }
 
+    #[test]
+    fn cryptde_keys_are_stored_encrypted_and_read_back() {
+        let home_dir = ensure_node_home_directory_exists(
+            "persistent_configuration",
+            "cryptde_keys_are_stored_encrypted_and_read_back",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let mut subject = PersistentConfigurationReal::from(conn);
+        subject.change_password(None, "password").unwrap();
+        let main_key = PrivateKey::new(&[1, 2, 3, 4]);
+        let alias_key = PrivateKey::new(&[5, 6, 7, 8]);
+        let before = subject.cryptde_keys("password").unwrap();
+
+        subject
+            .set_cryptde_keys(&main_key, &alias_key, "password")
+            .unwrap();
+
+        assert_eq!(before, None);
+        assert_eq!(
+            subject.cryptde_keys("password"),
+            Ok(Some((main_key.clone(), alias_key)))
+        );
+        assert_eq!(
+            subject.cryptde_keys("bad password"),
+            Err(PersistentConfigError::PasswordError)
+        );
+        let stored = subject.get_record("main_cryptde_key").unwrap();
+        assert_eq!(stored.encrypted, true);
+        assert_ne!(
+            stored.value_opt,
+            encode_bytes(Some(PlainData::new(main_key.as_slice()))).unwrap()
+        );
+    }
+
+    #[test]
+    fn set_cryptde_keys_requires_the_right_password() {
+        let home_dir = ensure_node_home_directory_exists(
+            "persistent_configuration",
+            "set_cryptde_keys_requires_the_right_password",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let mut subject = PersistentConfigurationReal::from(conn);
+        subject.change_password(None, "password").unwrap();
+
+        let result = subject.set_cryptde_keys(
+            &PrivateKey::new(&[1, 2, 3, 4]),
+            &PrivateKey::new(&[5, 6, 7, 8]),
+            "bad password",
+        );
+
+        assert_eq!(result, Err(PersistentConfigError::PasswordError));
+        assert_eq!(subject.cryptde_keys("password"), Ok(None));
+    }
+
     #[test]
     fn set_past_neighbors_success() {
         let example = "Aside from that, Mrs. Lincoln, how was the play?".as_bytes();
@@ -1945,6 +2166,237 @@ mod tests {
         getter_method_plain_data_does_not_tolerate_none_value!("rate_pack");
     }
 
+    #[test]
+    fn max_rate_pack_get_method_works() {
+        persistent_config_plain_data_assertions_for_simple_get_method!(
+            "max_rate_pack",
+            "100|200|300|400",
+            Some(RatePack {
+                routing_byte_rate: 100,
+                routing_service_rate: 200,
+                exit_byte_rate: 300,
+                exit_service_rate: 400,
+            })
+        );
+    }
+
+    #[test]
+    fn max_rate_pack_get_method_tolerates_none_value() {
+        let config_dao =
+            ConfigDaoMock::new().get_result(Ok(ConfigDaoRecord::new("max_rate_pack", None, false)));
+        let subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let result = subject.max_rate_pack();
+
+        assert_eq!(result, Ok(None));
+    }
+
+    #[test]
+    fn max_rate_pack_set_method_works_with_some() {
+        let set_params_arc = Arc::new(Mutex::new(Vec::new()));
+        let config_dao = ConfigDaoMock::new()
+            .set_params(&set_params_arc)
+            .set_result(Ok(()));
+        let mut subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let result = subject.set_max_rate_pack(Some(RatePack {
+            routing_byte_rate: 100,
+            routing_service_rate: 200,
+            exit_byte_rate: 300,
+            exit_service_rate: 400,
+        }));
+
+        assert!(result.is_ok());
+        let set_params = set_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_params,
+            vec![(
+                "max_rate_pack".to_string(),
+                Some("100|200|300|400".to_string())
+            )]
+        );
+    }
+
+    #[test]
+    fn max_rate_pack_set_method_works_with_none() {
+        let set_params_arc = Arc::new(Mutex::new(Vec::new()));
+        let config_dao = ConfigDaoMock::new()
+            .set_params(&set_params_arc)
+            .set_result(Ok(()));
+        let mut subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let result = subject.set_max_rate_pack(None);
+
+        assert!(result.is_ok());
+        let set_params = set_params_arc.lock().unwrap();
+        assert_eq!(*set_params, vec![("max_rate_pack".to_string(), None)]);
+    }
+
+    #[test]
+    fn preferred_exits_get_method_works() {
+        persistent_config_plain_data_assertions_for_simple_get_method!(
+            "preferred_exits",
+            "strict:AQID,BAUG",
+            Some(PreferredExits {
+                public_keys: vec![PublicKey::new(&[1, 2, 3]), PublicKey::new(&[4, 5, 6])],
+                strict: true,
+            })
+        );
+    }
+
+    #[test]
+    fn preferred_exits_get_method_tolerates_none_value() {
+        let config_dao = ConfigDaoMock::new().get_result(Ok(ConfigDaoRecord::new(
+            "preferred_exits",
+            None,
+            false,
+        )));
+        let subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let result = subject.preferred_exits();
+
+        assert_eq!(result, Ok(None));
+    }
+
+    #[test]
+    fn preferred_exits_set_method_works() {
+        let set_params_arc = Arc::new(Mutex::new(Vec::new()));
+        let config_dao = ConfigDaoMock::new()
+            .set_params(&set_params_arc)
+            .set_result(Ok(()))
+            .set_result(Ok(()));
+        let mut subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let some_result = subject.set_preferred_exits(Some(PreferredExits {
+            public_keys: vec![PublicKey::new(&[1, 2, 3])],
+            strict: false,
+        }));
+        let none_result = subject.set_preferred_exits(None);
+
+        assert_eq!(some_result, Ok(()));
+        assert_eq!(none_result, Ok(()));
+        let set_params = set_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_params,
+            vec![
+                (
+                    "preferred_exits".to_string(),
+                    Some("preferred:AQID".to_string())
+                ),
+                ("preferred_exits".to_string(), None)
+            ]
+        );
+    }
+
+    #[test]
+    fn transaction_fees_get_method_works() {
+        persistent_config_plain_data_assertions_for_simple_get_method!(
+            "transaction_fees",
+            "2500000000|100000000",
+            Some(TransactionFees::Eip1559 {
+                max_fee_per_gas_wei: 2_500_000_000,
+                max_priority_fee_per_gas_wei: 100_000_000,
+            })
+        );
+    }
+
+    #[test]
+    fn transaction_fees_get_method_tolerates_none_value() {
+        let config_dao = ConfigDaoMock::new().get_result(Ok(ConfigDaoRecord::new(
+            "transaction_fees",
+            None,
+            false,
+        )));
+        let subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let result = subject.transaction_fees();
+
+        assert_eq!(result, Ok(None));
+    }
+
+    #[test]
+    fn transaction_fees_set_method_works() {
+        let set_params_arc = Arc::new(Mutex::new(Vec::new()));
+        let config_dao = ConfigDaoMock::new()
+            .set_params(&set_params_arc)
+            .set_result(Ok(()))
+            .set_result(Ok(()));
+        let mut subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let legacy_result = subject.set_transaction_fees(TransactionFees::Legacy {
+            gas_price_wei: 123_000_000_000,
+        });
+        let eip1559_result = subject.set_transaction_fees(TransactionFees::Eip1559 {
+            max_fee_per_gas_wei: 2_500_000_000,
+            max_priority_fee_per_gas_wei: 100_000_000,
+        });
+
+        assert_eq!(legacy_result, Ok(()));
+        assert_eq!(eip1559_result, Ok(()));
+        let set_params = set_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_params,
+            vec![
+                (
+                    "transaction_fees".to_string(),
+                    Some("123000000000".to_string())
+                ),
+                (
+                    "transaction_fees".to_string(),
+                    Some("2500000000|100000000".to_string())
+                )
+            ]
+        );
+    }
+
+    #[test]
+    fn excluded_nodes_get_method_works() {
+        persistent_config_plain_data_assertions_for_simple_get_method!(
+            "excluded_nodes",
+            "AQID,BAUG",
+            vec![PublicKey::new(&[1, 2, 3]), PublicKey::new(&[4, 5, 6])]
+        );
+    }
+
+    #[test]
+    fn excluded_nodes_get_method_reads_none_as_empty() {
+        let config_dao = ConfigDaoMock::new().get_result(Ok(ConfigDaoRecord::new(
+            "excluded_nodes",
+            None,
+            false,
+        )));
+        let subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let result = subject.excluded_nodes();
+
+        assert_eq!(result, Ok(vec![]));
+    }
+
+    #[test]
+    fn excluded_nodes_set_method_works() {
+        let set_params_arc = Arc::new(Mutex::new(Vec::new()));
+        let config_dao = ConfigDaoMock::new()
+            .set_params(&set_params_arc)
+            .set_result(Ok(()))
+            .set_result(Ok(()));
+        let mut subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let some_result = subject
+            .set_excluded_nodes(vec![PublicKey::new(&[1, 2, 3]), PublicKey::new(&[4, 5, 6])]);
+        let none_result = subject.set_excluded_nodes(vec![]);
+
+        assert_eq!(some_result, Ok(()));
+        assert_eq!(none_result, Ok(()));
+        let set_params = set_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_params,
+            vec![
+                ("excluded_nodes".to_string(), Some("AQID,BAUG".to_string())),
+                ("excluded_nodes".to_string(), None)
+            ]
+        );
+    }
+
     #[test]
     fn scan_intervals_get_method_works() {
         persistent_config_plain_data_assertions_for_simple_get_method!(
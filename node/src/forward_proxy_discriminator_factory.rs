@@ -0,0 +1,56 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::discriminator::Discriminator;
+use crate::discriminator::DiscriminatorFactory;
+use crate::null_masquerader::NullMasquerader;
+use crate::sub_lib::forward_proxy_framer::ForwardProxyFramer;
+
+#[derive(Debug, Default)]
+pub struct ForwardProxyDiscriminatorFactory {}
+
+impl DiscriminatorFactory for ForwardProxyDiscriminatorFactory {
+    fn make(&self) -> Discriminator {
+        Discriminator::new(
+            Box::new(ForwardProxyFramer::new()),
+            vec![Box::new(NullMasquerader::new())],
+        )
+    }
+
+    fn duplicate(&self) -> Box<dyn DiscriminatorFactory> {
+        Box::new(ForwardProxyDiscriminatorFactory {})
+    }
+}
+
+impl ForwardProxyDiscriminatorFactory {
+    pub fn new() -> ForwardProxyDiscriminatorFactory {
+        Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discriminator::UnmaskedChunk;
+
+    #[test]
+    fn discriminator_factory_duplicate_works() {
+        let subject = ForwardProxyDiscriminatorFactory::new();
+
+        subject.duplicate();
+
+        // no panic; test passes
+    }
+
+    #[test]
+    fn factory_makes_discriminator_that_can_handle_absolute_form_request_for_proxy_server() {
+        let data: &[u8] = b"GET http://example.com/ HTTP/1.1\r\nHost: example.com\r\n\r\n";
+        let subject = ForwardProxyDiscriminatorFactory::new();
+
+        let mut result = subject.make();
+
+        result.add_data(data);
+        assert_eq!(
+            result.take_chunk(),
+            Some(UnmaskedChunk::new(Vec::from(data), true, true))
+        );
+    }
+}
@@ -1,8 +1,8 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 use super::live_cores_package::LiveCoresPackage;
-use crate::sub_lib::cryptde::CryptData;
-use crate::sub_lib::cryptde::{encodex, CryptDE};
+use crate::sub_lib::cryptde::{encodex, encodex_padded, CryptDE};
+use crate::sub_lib::cryptde::{CodexError, CryptData, PublicKey};
 use crate::sub_lib::dispatcher::{Endpoint, InboundClientData};
 use crate::sub_lib::hopper::{IncipientCoresPackage, NoLookupIncipientCoresPackage};
 use crate::sub_lib::stream_handler_pool::TransmitDataMsg;
@@ -16,6 +16,7 @@ pub struct ConsumingService {
     cryptde: &'static dyn CryptDE,
     to_dispatcher: Recipient<TransmitDataMsg>,
     to_hopper: Recipient<InboundClientData>,
+    packet_padding: bool,
     logger: Logger,
 }
 
@@ -24,11 +25,13 @@ impl ConsumingService {
         cryptde: &'static dyn CryptDE,
         to_dispatcher: Recipient<TransmitDataMsg>,
         to_hopper: Recipient<InboundClientData>,
+        packet_padding: bool,
     ) -> Self {
         Self {
             cryptde,
             to_dispatcher,
             to_hopper,
+            packet_padding,
             logger: Logger::new("ConsumingService"),
         }
     }
@@ -43,7 +46,7 @@ impl ConsumingService {
         let target_node_addr = incipient_cores_package.node_addr.clone();
         match LiveCoresPackage::from_no_lookup_incipient(incipient_cores_package, self.cryptde) {
             Ok((live_package, _)) => {
-                let encrypted_package = match encodex(self.cryptde, &target_key, &live_package) {
+                let encrypted_package = match self.encode(&target_key, &live_package) {
                     Ok(p) => p,
                     Err(e) => {
                         error!(
@@ -74,14 +77,13 @@ impl ConsumingService {
         );
         match LiveCoresPackage::from_incipient(incipient_cores_package, self.cryptde.borrow()) {
             Ok((live_package, next_hop)) => {
-                let encrypted_package =
-                    match encodex(self.cryptde, &next_hop.public_key, &live_package) {
-                        Ok(p) => p,
-                        Err(e) => {
-                            error!(self.logger, "Couldn't encode package: {:?}", e);
-                            return;
-                        }
-                    };
+                let encrypted_package = match self.encode(&next_hop.public_key, &live_package) {
+                    Ok(p) => p,
+                    Err(e) => {
+                        error!(self.logger, "Couldn't encode package: {:?}", e);
+                        return;
+                    }
+                };
                 if &next_hop.public_key == self.cryptde.public_key() {
                     self.zero_hop(encrypted_package);
                 } else {
@@ -92,6 +94,18 @@ impl ConsumingService {
         };
     }
 
+    fn encode(
+        &self,
+        public_key: &PublicKey,
+        live_package: &LiveCoresPackage,
+    ) -> Result<CryptData, CodexError> {
+        if self.packet_padding {
+            encodex_padded(self.cryptde, public_key, live_package)
+        } else {
+            encodex(self.cryptde, public_key, live_package)
+        }
+    }
+
     fn zero_hop(&self, encrypted_package: CryptData) {
         let ibcd = InboundClientData {
             timestamp: SystemTime::now(),
@@ -133,7 +147,8 @@ impl ConsumingService {
 mod tests {
     use super::*;
     use crate::node_test_utils::check_timestamp;
-    use crate::sub_lib::cryptde::PublicKey;
+    use crate::sub_lib::cryptde::PADDING_BUCKETS;
+    use crate::sub_lib::cryptde_null::CryptDENull;
     use crate::sub_lib::dispatcher::{Component, InboundClientData};
     use crate::sub_lib::node_addr::NodeAddr;
     use crate::sub_lib::route::Route;
@@ -167,6 +182,7 @@ mod tests {
             main_cryptde(),
             peer_actors.dispatcher.from_dispatcher_client,
             peer_actors.hopper.from_dispatcher,
+            false,
         );
 
         subject.consume_no_lookup(package.clone());
@@ -203,6 +219,7 @@ mod tests {
             main_cryptde(),
             peer_actors.dispatcher.from_dispatcher_client,
             peer_actors.hopper.from_dispatcher,
+            false,
         );
 
         subject.consume_no_lookup(package);
@@ -237,6 +254,7 @@ mod tests {
             cryptde,
             peer_actors.dispatcher.from_dispatcher_client,
             peer_actors.hopper.from_dispatcher,
+            false,
         );
 
         subject.consume(incipient_cores_package.clone());
@@ -259,6 +277,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn consume_pads_live_package_to_a_bucket_when_packet_padding_is_on() {
+        let cryptde = main_cryptde();
+        let paying_wallet = make_paying_wallet(b"wallet");
+        let (dispatcher, _, dispatcher_recording_arc) = make_recorder();
+        let destination_key = PublicKey::new(&[65, 65, 65]);
+        let route = Route::one_way(
+            RouteSegment::new(
+                vec![cryptde.public_key(), &destination_key.clone()],
+                Component::Neighborhood,
+            ),
+            cryptde,
+            Some(paying_wallet),
+            Some(TEST_DEFAULT_CHAIN.rec().contract),
+        )
+        .unwrap();
+        let payload = make_meaningless_message_type();
+        let incipient_cores_package =
+            IncipientCoresPackage::new(cryptde, route.clone(), payload, &destination_key).unwrap();
+        let system = System::new("consume_pads_live_package_to_a_bucket_when_packet_padding_is_on");
+        let peer_actors = peer_actors_builder().dispatcher(dispatcher).build();
+        let subject = ConsumingService::new(
+            cryptde,
+            peer_actors.dispatcher.from_dispatcher_client,
+            peer_actors.hopper.from_dispatcher,
+            true,
+        );
+
+        subject.consume(incipient_cores_package.clone());
+
+        System::current().stop();
+        system.run();
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        let record = dispatcher_recording.get_record::<TransmitDataMsg>(0);
+        let (expected_lcp, _) =
+            LiveCoresPackage::from_incipient(incipient_cores_package, cryptde).unwrap();
+        let expected_lcp_enc = encodex_padded(cryptde, &destination_key, &expected_lcp).unwrap();
+        assert_eq!(
+            TransmitDataMsg {
+                endpoint: Endpoint::Key(destination_key.clone()),
+                last_data: false,
+                sequence_number: None,
+                data: expected_lcp_enc.into(),
+            },
+            *record,
+        );
+        let decrypted = CryptDENull::from(&destination_key, TEST_DEFAULT_CHAIN)
+            .decode(&CryptData::new(&record.data))
+            .unwrap();
+        assert_eq!(decrypted.len(), PADDING_BUCKETS[0]);
+    }
+
     #[test]
     fn consume_sends_zero_hop_incipient_directly_to_hopper() {
         let cryptde = main_cryptde();
@@ -284,6 +354,7 @@ mod tests {
             cryptde,
             peer_actors.dispatcher.from_dispatcher_client,
             peer_actors.hopper.from_dispatcher,
+            false,
         );
         let before = SystemTime::now();
 
@@ -320,7 +391,7 @@ mod tests {
         let to_dispatcher = peer_actors.dispatcher.from_dispatcher_client;
         let to_hopper = peer_actors.hopper.from_dispatcher;
 
-        let subject = ConsumingService::new(main_cryptde(), to_dispatcher, to_hopper);
+        let subject = ConsumingService::new(main_cryptde(), to_dispatcher, to_hopper, false);
 
         subject.consume(
             IncipientCoresPackage::new(
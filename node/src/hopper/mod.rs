@@ -2,6 +2,7 @@
 
 mod consuming_service;
 pub mod live_cores_package;
+mod replay_cache;
 mod routing_service;
 
 use crate::bootstrapper::CryptDEPair;
@@ -30,6 +31,7 @@ pub struct Hopper {
     per_routing_service: u64,
     per_routing_byte: u64,
     is_decentralized: bool,
+    packet_padding: bool,
     logger: Logger,
     crashable: bool,
 }
@@ -47,6 +49,7 @@ impl Handler<BindMessage> for Hopper {
             self.cryptdes.main,
             msg.peer_actors.dispatcher.from_dispatcher_client.clone(),
             msg.peer_actors.hopper.from_dispatcher.clone(),
+            self.packet_padding,
         ));
         self.routing_service = Some(RoutingService::new(
             self.cryptdes,
@@ -57,10 +60,12 @@ impl Handler<BindMessage> for Hopper {
                 hopper_subs: msg.peer_actors.hopper,
                 to_dispatcher: msg.peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: msg.peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: msg.peer_actors.accountant.report_cover_traffic,
             },
             self.per_routing_service,
             self.per_routing_byte,
             self.is_decentralized,
+            self.packet_padding,
         ));
     }
 }
@@ -100,7 +105,7 @@ impl Handler<InboundClientData> for Hopper {
 
     fn handle(&mut self, msg: InboundClientData, _ctx: &mut Self::Context) -> Self::Result {
         self.routing_service
-            .as_ref()
+            .as_mut()
             .expect("Hopper unbound: no RoutingService")
             .route(msg);
     }
@@ -124,6 +129,7 @@ impl Hopper {
             per_routing_service: config.per_routing_service,
             per_routing_byte: config.per_routing_byte,
             is_decentralized: config.is_decentralized,
+            packet_padding: config.packet_padding,
             logger: Logger::new("Hopper"),
         }
     }
@@ -205,6 +211,7 @@ mod tests {
             per_routing_service: 100,
             per_routing_byte: 200,
             is_decentralized: false,
+            packet_padding: false,
             crashable: false,
         });
         let subject_addr = subject.start();
@@ -248,6 +255,7 @@ mod tests {
             per_routing_service: 100,
             per_routing_byte: 200,
             is_decentralized: false,
+            packet_padding: false,
             crashable: false,
         });
         let subject_addr = subject.start();
@@ -268,6 +276,7 @@ mod tests {
             per_routing_service: 100,
             per_routing_byte: 200,
             is_decentralized: false,
+            packet_padding: false,
             crashable: true,
         });
 
@@ -0,0 +1,122 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use ethsign_crypto::Keccak256;
+use std::collections::{HashSet, VecDeque};
+use std::time::{Duration, SystemTime};
+
+// A real CryptDE seals every CORES package to its recipient under a fresh ephemeral key, so two
+// honest packages never share ciphertext; the same bytes arriving twice can only be a replay.
+// CryptDENull (--fake-public-key) doesn't encrypt at all, so honest packages can repeat under it,
+// and the RoutingService keeps no ReplayCache in that case.
+pub const REPLAY_CACHE_CAPACITY: usize = 65_536;
+pub const REPLAY_CACHE_WINDOW: Duration = Duration::from_secs(600);
+
+pub struct ReplayCache {
+    capacity: usize,
+    window: Duration,
+    digests: HashSet<[u8; 32]>,
+    arrivals: VecDeque<([u8; 32], SystemTime)>,
+}
+
+impl Default for ReplayCache {
+    fn default() -> Self {
+        Self::new(REPLAY_CACHE_CAPACITY, REPLAY_CACHE_WINDOW)
+    }
+}
+
+impl ReplayCache {
+    pub fn new(capacity: usize, window: Duration) -> Self {
+        Self {
+            capacity,
+            window,
+            digests: HashSet::new(),
+            arrivals: VecDeque::new(),
+        }
+    }
+
+    // Returns true if the same bytes have already arrived within the window; otherwise
+    // remembers them and returns false.
+    pub fn is_replay(&mut self, data: &[u8], now: SystemTime) -> bool {
+        self.forget_older_than(now);
+        let digest = data.keccak256();
+        if self.digests.contains(&digest) {
+            return true;
+        }
+        if self.arrivals.len() >= self.capacity {
+            if let Some((oldest, _)) = self.arrivals.pop_front() {
+                self.digests.remove(&oldest);
+            }
+        }
+        self.digests.insert(digest);
+        self.arrivals.push_back((digest, now));
+        false
+    }
+
+    fn forget_older_than(&mut self, now: SystemTime) {
+        while let Some((digest, arrival)) = self.arrivals.front() {
+            match now.duration_since(*arrival) {
+                Ok(age) if age > self.window => {
+                    self.digests.remove(digest);
+                    self.arrivals.pop_front();
+                }
+                _ => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(REPLAY_CACHE_CAPACITY, 65_536);
+        assert_eq!(REPLAY_CACHE_WINDOW, Duration::from_secs(600));
+    }
+
+    #[test]
+    fn first_arrival_is_not_a_replay_but_second_is() {
+        let mut subject = ReplayCache::default();
+        let now = SystemTime::now();
+
+        let first = subject.is_replay(b"booga", now);
+        let second = subject.is_replay(b"booga", now + Duration::from_secs(1));
+        let other = subject.is_replay(b"agoob", now + Duration::from_secs(2));
+
+        assert_eq!(first, false);
+        assert_eq!(second, true);
+        assert_eq!(other, false);
+        assert_eq!(subject.arrivals.len(), 2);
+    }
+
+    #[test]
+    fn arrivals_older_than_the_window_are_forgotten() {
+        let mut subject = ReplayCache::new(10, Duration::from_secs(60));
+        let now = SystemTime::now();
+        subject.is_replay(b"booga", now);
+        subject.is_replay(b"agoob", now + Duration::from_secs(30));
+
+        let inside = subject.is_replay(b"agoob", now + Duration::from_secs(61));
+        let outside = subject.is_replay(b"booga", now + Duration::from_secs(61));
+
+        assert_eq!(inside, true);
+        assert_eq!(outside, false);
+        assert_eq!(subject.arrivals.len(), 2);
+    }
+
+    #[test]
+    fn oldest_arrival_is_forgotten_when_capacity_is_reached() {
+        let mut subject = ReplayCache::new(2, Duration::from_secs(60));
+        let now = SystemTime::now();
+        subject.is_replay(b"one", now);
+        subject.is_replay(b"two", now);
+        subject.is_replay(b"three", now);
+
+        let two = subject.is_replay(b"two", now);
+        let one = subject.is_replay(b"one", now);
+
+        assert_eq!(two, true);
+        assert_eq!(one, false);
+        assert_eq!(subject.arrivals.len(), 2);
+    }
+}
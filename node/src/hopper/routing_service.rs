@@ -1,13 +1,18 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 use super::live_cores_package::LiveCoresPackage;
+use super::replay_cache::ReplayCache;
 use crate::blockchain::payer::Payer;
 use crate::bootstrapper::CryptDEPair;
 use crate::neighborhood::gossip::Gossip_0v1;
-use crate::sub_lib::accountant::ReportRoutingServiceProvidedMessage;
-use crate::sub_lib::cryptde::{decodex, encodex, CryptData, CryptdecError};
+use crate::sub_lib::accountant::{ReportCoverTrafficMessage, ReportRoutingServiceProvidedMessage};
+use crate::sub_lib::cryptde::{decodex_padded, encodex, encodex_padded, CryptData, CryptdecError};
+use crate::sub_lib::cryptde_null::CryptDENull;
 use crate::sub_lib::dispatcher::{Component, Endpoint, InboundClientData};
 use crate::sub_lib::hop::LiveHop;
-use crate::sub_lib::hopper::{ExpiredCoresPackage, HopperSubs, MessageType};
+use crate::sub_lib::hopper::{
+    ExpiredCoresPackage, HopperSubs, IncipientCoresPackage, MessageType, ServiceRefusalReason,
+    ServiceRefused_0v1,
+};
 use crate::sub_lib::neighborhood::{GossipFailure_0v1, NeighborhoodSubs};
 use crate::sub_lib::proxy_client::{
     ClientResponsePayload_0v1, DnsResolveFailure_0v1, ProxyClientSubs,
@@ -28,6 +33,7 @@ pub struct RoutingServiceSubs {
     pub hopper_subs: HopperSubs,
     pub to_dispatcher: Recipient<TransmitDataMsg>,
     pub to_accountant_routing: Recipient<ReportRoutingServiceProvidedMessage>,
+    pub to_accountant_cover_traffic: Recipient<ReportCoverTrafficMessage>,
 }
 
 pub struct RoutingService {
@@ -37,6 +43,8 @@ pub struct RoutingService {
     per_routing_byte: u64,
     logger: Logger,
     is_decentralized: bool,
+    packet_padding: bool,
+    replay_cache_opt: Option<ReplayCache>,
 }
 
 impl RoutingService {
@@ -46,7 +54,13 @@ impl RoutingService {
         per_routing_service: u64,
         per_routing_byte: u64,
         is_decentralized: bool,
+        packet_padding: bool,
     ) -> RoutingService {
+        let replay_cache_opt = if cryptdes.main.as_any().is::<CryptDENull>() {
+            None
+        } else {
+            Some(ReplayCache::default())
+        };
         RoutingService {
             cryptdes,
             routing_service_subs,
@@ -54,10 +68,12 @@ impl RoutingService {
             per_routing_byte,
             logger: Logger::new("RoutingService"),
             is_decentralized,
+            packet_padding,
+            replay_cache_opt,
         }
     }
 
-    pub fn route(&self, ibcd: InboundClientData) {
+    pub fn route(&mut self, ibcd: InboundClientData) {
         let data_size = ibcd.data.len();
         debug!(
             self.logger,
@@ -69,7 +85,19 @@ impl RoutingService {
         let last_data = ibcd.last_data;
         let ibcd_but_data = ibcd.clone_but_data();
 
-        let live_package = match decodex::<LiveCoresPackage>(
+        if let Some(replay_cache) = self.replay_cache_opt.as_mut() {
+            if replay_cache.is_replay(&ibcd.data, ibcd.timestamp) {
+                warning!(
+                    self.logger,
+                    "Dropping replayed {}-byte CORES package from {}",
+                    data_size,
+                    peer_addr
+                );
+                return;
+            }
+        }
+
+        let live_package = match decodex_padded::<LiveCoresPackage>(
             self.cryptdes.main,
             &CryptData::new(&ibcd.data[..]),
         ) {
@@ -152,6 +180,9 @@ impl RoutingService {
                         payload_size,
                         next_hop.component,
                     );
+                        if next_hop.component == Component::ProxyClient {
+                            self.send_delinquency_notice(immediate_neighbor_addr, live_package);
+                        }
                         return;
                     }
                 }
@@ -165,6 +196,58 @@ impl RoutingService {
         }
     }
 
+    // Only the exit can read the return route, so only the exit can tell a delinquent consumer
+    // why its requests are going nowhere; a relay can do nothing but drop them.
+    fn send_delinquency_notice(
+        &self,
+        immediate_neighbor_addr: SocketAddr,
+        live_package: LiveCoresPackage,
+    ) {
+        let expired_package = match self.extract_expired_package(
+            immediate_neighbor_addr,
+            live_package,
+            Component::ProxyClient,
+        ) {
+            None => return,
+            Some(p) => p,
+        };
+        let client_request = match expired_package.payload {
+            MessageType::ClientRequest(vd) => match ClientRequestPayload_0v1::try_from(vd) {
+                Ok(crp) => crp,
+                Err(e) => {
+                    error!(
+                        self.logger,
+                        "Received unmigratable ClientRequestPayload: {:?}", e
+                    );
+                    return;
+                }
+            },
+            _ => return,
+        };
+        let notice = ServiceRefused_0v1::new(
+            client_request.stream_key,
+            ServiceRefusalReason::Delinquent,
+            self.cryptdes.main,
+        );
+        match IncipientCoresPackage::new(
+            self.cryptdes.main,
+            expired_package.remaining_route,
+            notice.into(),
+            &client_request.originator_public_key,
+        ) {
+            Ok(package) => self
+                .routing_service_subs
+                .hopper_subs
+                .from_hopper_client
+                .try_send(package)
+                .expect("Hopper is dead"),
+            Err(e) => error!(
+                self.logger,
+                "Could not tell delinquent consumer that its service was refused: {}", e
+            ),
+        }
+    }
+
     fn route_data_around_again(
         &self,
         live_package: LiveCoresPackage,
@@ -259,6 +342,21 @@ impl RoutingService {
     ) {
         let immediate_neighbor = expired_package.immediate_neighbor;
         match (component, expired_package.payload) {
+            (_, MessageType::CoverTraffic(_)) => {
+                trace!(
+                    self.logger,
+                    "Discarding {}-byte cover traffic from {}",
+                    expired_package.payload_len,
+                    immediate_neighbor
+                );
+                self.routing_service_subs
+                    .to_accountant_cover_traffic
+                    .try_send(ReportCoverTrafficMessage {
+                        bytes_sent: 0,
+                        bytes_received: expired_package.payload_len,
+                    })
+                    .expect("Accountant is dead")
+            }
             (Component::ProxyClient, MessageType::ClientRequest(vd)) => {
                 if !self.is_decentralized || payer_owns_secret_key {
                     let proxy_client_subs = match &self.routing_service_subs.proxy_client_subs_opt {
@@ -347,6 +445,26 @@ impl RoutingService {
                     ))
                     .expect("ProxyServer is dead")
             }
+            (Component::ProxyServer, MessageType::ServiceRefused(vd)) => {
+                let refusal = match ServiceRefused_0v1::try_from(vd) {
+                    Ok(r) => r,
+                    Err(e) => {
+                        error!(self.logger, "Received unmigratable ServiceRefused: {:?}", e);
+                        return;
+                    }
+                };
+                self.routing_service_subs
+                    .proxy_server_subs
+                    .service_refused_from_hopper
+                    .try_send(ExpiredCoresPackage::new(
+                        expired_package.immediate_neighbor,
+                        expired_package.paying_wallet,
+                        expired_package.remaining_route,
+                        refusal,
+                        expired_package.payload_len,
+                    ))
+                    .expect("ProxyServer is dead")
+            }
             (Component::Neighborhood, MessageType::Gossip(vd)) => {
                 let gossip = match Gossip_0v1::try_from(vd) {
                     Ok(g) => g,
@@ -479,15 +597,19 @@ impl RoutingService {
                 }
                 Ok(p) => p,
             };
-        let next_live_package_enc =
-            match encodex(self.cryptdes.main, &next_hop.public_key, &next_live_package) {
-                Ok(nlpe) => nlpe,
-                Err(e) => {
-                    let msg = format!("Couldn't serialize or encrypt outgoing LCP: {:?}", e);
-                    error!(self.logger, "{}", &msg);
-                    return Err(CryptdecError::OtherError(msg));
-                }
-            };
+        let encode_result = if self.packet_padding {
+            encodex_padded(self.cryptdes.main, &next_hop.public_key, &next_live_package)
+        } else {
+            encodex(self.cryptdes.main, &next_hop.public_key, &next_live_package)
+        };
+        let next_live_package_enc = match encode_result {
+            Ok(nlpe) => nlpe,
+            Err(e) => {
+                let msg = format!("Couldn't serialize or encrypt outgoing LCP: {:?}", e);
+                error!(self.logger, "{}", &msg);
+                return Err(CryptdecError::OtherError(msg));
+            }
+        };
         Ok(TransmitDataMsg {
             endpoint: Endpoint::Key(next_hop.public_key),
             last_data,
@@ -505,10 +627,13 @@ mod tests {
     use crate::neighborhood::gossip::{GossipBuilder, Gossip_0v1};
     use crate::node_test_utils::check_timestamp;
     use crate::sub_lib::accountant::ReportRoutingServiceProvidedMessage;
-    use crate::sub_lib::cryptde::{encodex, CryptDE, PlainData, PublicKey};
+    use crate::sub_lib::cryptde::{
+        decodex, encodex, CryptDE, PlainData, PublicKey, PADDING_BUCKETS,
+    };
     use crate::sub_lib::cryptde_null::CryptDENull;
     use crate::sub_lib::cryptde_real::CryptDEReal;
-    use crate::sub_lib::hopper::{IncipientCoresPackage, MessageType, MessageType::ClientRequest};
+    use crate::sub_lib::hopper::{CoverTraffic_0v1, IncipientCoresPackage};
+    use crate::sub_lib::hopper::{MessageType, MessageType::ClientRequest};
     use crate::sub_lib::neighborhood::GossipFailure_0v1;
     use crate::sub_lib::peer_actors::PeerActors;
     use crate::sub_lib::proxy_client::{ClientResponsePayload_0v1, DnsResolveFailure_0v1};
@@ -531,7 +656,7 @@ mod tests {
     use masq_lib::test_utils::utils::TEST_DEFAULT_CHAIN;
     use std::net::SocketAddr;
     use std::str::FromStr;
-    use std::time::SystemTime;
+    use std::time::{Duration, SystemTime};
 
     #[test]
     fn dns_resolution_failures_are_reported_to_the_proxy_server() {
@@ -565,7 +690,7 @@ mod tests {
 
         let system = System::new("dns_resolution_failures_are_reported_to_the_proxy_server");
         let peer_actors = peer_actors_builder().proxy_server(proxy_server).build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             cryptdes,
             RoutingServiceSubs {
                 proxy_client_subs_opt: peer_actors.proxy_client_opt,
@@ -574,10 +699,12 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
             },
             100,
             200,
             false,
+            false,
         );
 
         subject.route(inbound_client_data);
@@ -590,6 +717,150 @@ mod tests {
         assert_eq!(dns_resolve_failure, message.payload);
     }
 
+    #[test]
+    fn service_refusals_are_reported_to_the_proxy_server() {
+        let cryptdes = make_cryptde_pair();
+        let route = route_to_proxy_server(&cryptdes.main.public_key(), cryptdes.main);
+        let refusal = ServiceRefused_0v1::new(
+            StreamKey::make_meaningless_stream_key(),
+            ServiceRefusalReason::Delinquent,
+            cryptdes.main,
+        );
+        let lcp = LiveCoresPackage::new(
+            route,
+            encodex::<MessageType>(
+                cryptdes.alias,
+                &cryptdes.alias.public_key(),
+                &refusal.clone().into(),
+            )
+            .unwrap(),
+        );
+        let data_enc = encodex(cryptdes.main, &cryptdes.main.public_key(), &lcp).unwrap();
+        let inbound_client_data = InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: SocketAddr::from_str("1.2.3.4:5678").unwrap(),
+            reception_port: None,
+            sequence_number: None,
+            last_data: false,
+            is_clandestine: false,
+            data: data_enc.into(),
+        };
+        let (proxy_server, _, proxy_server_recording) = make_recorder();
+
+        let system = System::new("service_refusals_are_reported_to_the_proxy_server");
+        let peer_actors = peer_actors_builder().proxy_server(proxy_server).build();
+        let mut subject = RoutingService::new(
+            cryptdes,
+            RoutingServiceSubs {
+                proxy_client_subs_opt: peer_actors.proxy_client_opt,
+                proxy_server_subs: peer_actors.proxy_server,
+                neighborhood_subs: peer_actors.neighborhood,
+                hopper_subs: peer_actors.hopper,
+                to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
+                to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
+            },
+            100,
+            200,
+            false,
+            false,
+        );
+
+        subject.route(inbound_client_data);
+
+        System::current().stop();
+        system.run();
+
+        let recordings = proxy_server_recording.lock().unwrap();
+        let message = recordings.get_record::<ExpiredCoresPackage<ServiceRefused_0v1>>(0);
+        assert_eq!(refusal, message.payload);
+    }
+
+    #[test]
+    fn exit_tells_delinquent_consumer_why_its_request_goes_unserved() {
+        let _eg = EnvironmentGuard::new();
+        BAN_CACHE.clear();
+        init_test_logging();
+        let main_cryptde = main_cryptde();
+        let alias_cryptde = alias_cryptde();
+        let public_key = main_cryptde.public_key();
+        let paying_wallet = make_paying_wallet(b"delinquent");
+        BAN_CACHE.insert(paying_wallet.clone());
+        let mut route = Route::round_trip(
+            RouteSegment::new(vec![public_key, public_key], Component::ProxyClient),
+            RouteSegment::new(vec![public_key, public_key], Component::ProxyServer),
+            main_cryptde,
+            Some(paying_wallet),
+            1234,
+            Some(TEST_DEFAULT_CHAIN.rec().contract),
+        )
+        .unwrap();
+        route.shift(main_cryptde).unwrap();
+        let payload = make_request_payload(0, main_cryptde);
+        let lcp = LiveCoresPackage::new(
+            route,
+            encodex::<MessageType>(main_cryptde, public_key, &payload.clone().into()).unwrap(),
+        );
+        let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let expected_return_route = lcp
+            .clone()
+            .to_expired(peer_addr, main_cryptde, main_cryptde)
+            .unwrap()
+            .remaining_route;
+        let data_enc = encodex(main_cryptde, public_key, &lcp).unwrap();
+        let inbound_client_data = InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr,
+            reception_port: None,
+            last_data: false,
+            is_clandestine: true,
+            sequence_number: None,
+            data: data_enc.into(),
+        };
+        let (proxy_client, _, proxy_client_recording_arc) = make_recorder();
+        let (hopper, _, hopper_recording_arc) = make_recorder();
+        let system = System::new("exit_tells_delinquent_consumer_why_its_request_goes_unserved");
+        let peer_actors = peer_actors_builder()
+            .proxy_client(proxy_client)
+            .hopper(hopper)
+            .build();
+        let mut subject = RoutingService::new(
+            CryptDEPair {
+                main: main_cryptde,
+                alias: alias_cryptde,
+            },
+            RoutingServiceSubs {
+                proxy_client_subs_opt: peer_actors.proxy_client_opt,
+                proxy_server_subs: peer_actors.proxy_server,
+                neighborhood_subs: peer_actors.neighborhood,
+                hopper_subs: peer_actors.hopper,
+                to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
+                to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
+            },
+            100,
+            200,
+            true,
+            false,
+        );
+
+        subject.route(inbound_client_data);
+
+        System::current().stop();
+        system.run();
+        assert_eq!(proxy_client_recording_arc.lock().unwrap().len(), 0);
+        let hopper_recording = hopper_recording_arc.lock().unwrap();
+        let package = hopper_recording.get_record::<IncipientCoresPackage>(0);
+        assert_eq!(package.route, expected_return_route);
+        let notice = match decodex::<MessageType>(main_cryptde, &package.payload).unwrap() {
+            MessageType::ServiceRefused(vd) => ServiceRefused_0v1::try_from(vd).unwrap(),
+            other => panic!("Expected ServiceRefused, got {:?}", other),
+        };
+        assert_eq!(notice.stream_key, payload.stream_key);
+        assert_eq!(notice.reason, ServiceRefusalReason::Delinquent);
+        assert_eq!(notice.is_signed_by(public_key, main_cryptde), true);
+    }
+
     #[test]
     fn logs_and_ignores_message_that_cannot_be_deserialized() {
         init_test_logging();
@@ -610,7 +881,7 @@ mod tests {
             data: data_enc.into(),
         };
         let peer_actors = peer_actors_builder().build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             cryptdes,
             RoutingServiceSubs {
                 proxy_client_subs_opt: peer_actors.proxy_client_opt,
@@ -619,10 +890,12 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
             },
             100,
             200,
             false,
+            false,
         );
 
         subject.route(inbound_client_data);
@@ -657,7 +930,7 @@ mod tests {
             data: data_enc.into(),
         };
         let peer_actors = peer_actors_builder().build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: main_cryptde,
                 alias: alias_cryptde,
@@ -669,10 +942,12 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
             },
             100,
             200,
             false,
+            false,
         );
 
         subject.route(inbound_client_data);
@@ -709,7 +984,7 @@ mod tests {
             data: data_enc.into(),
         };
         let peer_actors = peer_actors_builder().build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: main_cryptde,
                 alias: alias_cryptde,
@@ -721,10 +996,12 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
             },
             100,
             200,
             false,
+            false,
         );
         subject.route(inbound_client_data);
         TestLogHandler::new().exists_log_matching("Attempt to send invalid combination .* to .*");
@@ -765,7 +1042,7 @@ mod tests {
 
         let system = System::new("converts_live_message_to_expired_for_proxy_client");
         let peer_actors = peer_actors_builder().proxy_client(component).build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: main_cryptde,
                 alias: alias_cryptde,
@@ -777,10 +1054,12 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
             },
             0,
             0,
             false,
+            false,
         );
 
         subject.route(inbound_client_data);
@@ -838,7 +1117,7 @@ mod tests {
 
         let system = System::new("converts_live_message_to_expired_for_proxy_client");
         let peer_actors = peer_actors_builder().build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: main_cryptde,
                 alias: alias_cryptde,
@@ -850,10 +1129,12 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
             },
             0,
             0,
             false,
+            false,
         );
 
         subject.route(inbound_client_data);
@@ -897,7 +1178,7 @@ mod tests {
 
         let system = System::new("converts_live_message_to_expired_for_proxy_server");
         let peer_actors = peer_actors_builder().proxy_server(proxy_server).build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: main_cryptde,
                 alias: alias_cryptde,
@@ -909,10 +1190,12 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
             },
             0,
             0,
             false,
+            false,
         );
 
         subject.route(inbound_client_data);
@@ -978,7 +1261,7 @@ mod tests {
 
         let system = System::new("converts_live_gossip_message_to_expired_for_neighborhood");
         let peer_actors = peer_actors_builder().neighborhood(component).build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: main_cryptde,
                 alias: alias_cryptde,
@@ -990,10 +1273,12 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
             },
             0,
             0,
             false,
+            false,
         );
 
         subject.route(inbound_client_data);
@@ -1055,7 +1340,7 @@ mod tests {
         let system =
             System::new("converts_live_gossip_failure_message_to_expired_for_neighborhood");
         let peer_actors = peer_actors_builder().neighborhood(component).build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: cryptde,
                 alias: alias_cryptde(),
@@ -1067,10 +1352,12 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
             },
             0,
             0,
             true,
+            false,
         );
 
         subject.route(inbound_client_data);
@@ -1093,6 +1380,161 @@ mod tests {
         assert_eq!(record.payload_len, expected_ecp.payload_len);
     }
 
+    #[test]
+    fn discards_padded_cover_traffic_and_reports_it_apart_from_routing_services() {
+        let _eg = EnvironmentGuard::new();
+        BAN_CACHE.clear();
+        let cryptde = main_cryptde();
+        let (neighborhood, _, neighborhood_recording_arc) = make_recorder();
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let mut route = Route::one_way(
+            RouteSegment::new(
+                vec![&cryptde.public_key(), &cryptde.public_key()],
+                Component::Neighborhood,
+            ),
+            cryptde,
+            None,
+            None,
+        )
+        .unwrap();
+        route.shift(cryptde).unwrap();
+        let payload: MessageType = CoverTraffic_0v1 {
+            filler: vec![0; 100],
+        }
+        .into();
+        let lcp = LiveCoresPackage::new(
+            route,
+            encodex::<MessageType>(cryptde, &cryptde.public_key(), &payload).unwrap(),
+        );
+        let data_enc = encodex_padded(cryptde, &cryptde.public_key(), &lcp).unwrap();
+        let inbound_client_data = InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: SocketAddr::from_str("1.3.2.4:5678").unwrap(),
+            reception_port: None,
+            last_data: false,
+            is_clandestine: true,
+            sequence_number: None,
+            data: data_enc.into(),
+        };
+        let system =
+            System::new("discards_padded_cover_traffic_and_reports_it_apart_from_routing_services");
+        let peer_actors = peer_actors_builder()
+            .neighborhood(neighborhood)
+            .accountant(accountant)
+            .build();
+        let mut subject = RoutingService::new(
+            CryptDEPair {
+                main: cryptde,
+                alias: alias_cryptde(),
+            },
+            RoutingServiceSubs {
+                proxy_client_subs_opt: peer_actors.proxy_client_opt,
+                proxy_server_subs: peer_actors.proxy_server,
+                neighborhood_subs: peer_actors.neighborhood,
+                hopper_subs: peer_actors.hopper,
+                to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
+                to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
+            },
+            100,
+            200,
+            true,
+            false,
+        );
+
+        subject.route(inbound_client_data);
+
+        System::current().stop();
+        system.run();
+        let neighborhood_recording = neighborhood_recording_arc.lock().unwrap();
+        assert_eq!(neighborhood_recording.len(), 0);
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        assert_eq!(
+            accountant_recording.get_record::<ReportCoverTrafficMessage>(0),
+            &ReportCoverTrafficMessage {
+                bytes_sent: 0,
+                bytes_received: lcp.payload.len(),
+            }
+        );
+        assert_eq!(accountant_recording.len(), 1);
+    }
+
+    #[test]
+    fn pads_relayed_live_package_when_packet_padding_is_on() {
+        let _eg = EnvironmentGuard::new();
+        BAN_CACHE.clear();
+        let main_cryptde = main_cryptde();
+        let paying_wallet = make_paying_wallet(b"wallet");
+        let (dispatcher, _, dispatcher_recording_arc) = make_recorder();
+        let next_key = PublicKey::new(&[65, 65, 65]);
+        let route = Route::one_way(
+            RouteSegment::new(
+                vec![&main_cryptde.public_key(), &next_key],
+                Component::Neighborhood,
+            ),
+            main_cryptde,
+            Some(paying_wallet.clone()),
+            Some(TEST_DEFAULT_CHAIN.rec().contract),
+        )
+        .unwrap();
+        let payload = PlainData::new(&b"abcd"[..]);
+        let lcp = LiveCoresPackage::new(route, main_cryptde.encode(&next_key, &payload).unwrap());
+        let data_enc = encodex(main_cryptde, &main_cryptde.public_key(), &lcp).unwrap();
+        let inbound_client_data = InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: SocketAddr::from_str("1.2.3.4:5678").unwrap(),
+            reception_port: None,
+            last_data: false,
+            is_clandestine: true,
+            sequence_number: None,
+            data: data_enc.into(),
+        };
+        let system = System::new("pads_relayed_live_package_when_packet_padding_is_on");
+        let peer_actors = peer_actors_builder().dispatcher(dispatcher).build();
+        let mut subject = RoutingService::new(
+            CryptDEPair {
+                main: main_cryptde,
+                alias: alias_cryptde(),
+            },
+            RoutingServiceSubs {
+                proxy_client_subs_opt: peer_actors.proxy_client_opt,
+                proxy_server_subs: peer_actors.proxy_server,
+                neighborhood_subs: peer_actors.neighborhood,
+                hopper_subs: peer_actors.hopper,
+                to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
+                to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
+            },
+            rate_pack_routing(103),
+            rate_pack_routing_byte(103),
+            false,
+            true,
+        );
+
+        subject.route(inbound_client_data);
+
+        System::current().stop();
+        system.run();
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        let record = dispatcher_recording.get_record::<TransmitDataMsg>(0);
+        let expected_lcp = lcp.into_next_live(main_cryptde).unwrap().1;
+        assert_eq!(
+            *record,
+            TransmitDataMsg {
+                endpoint: Endpoint::Key(next_key.clone()),
+                last_data: false,
+                sequence_number: None,
+                data: encodex_padded(main_cryptde, &next_key, &expected_lcp)
+                    .unwrap()
+                    .into(),
+            }
+        );
+        let decrypted = CryptDENull::from(&next_key, TEST_DEFAULT_CHAIN)
+            .decode(&CryptData::new(&record.data))
+            .unwrap();
+        assert_eq!(decrypted.len(), PADDING_BUCKETS[0]);
+    }
+
     #[test]
     fn passes_on_inbound_client_data_not_meant_for_this_node() {
         let _eg = EnvironmentGuard::new();
@@ -1137,7 +1579,7 @@ mod tests {
             .dispatcher(dispatcher)
             .accountant(accountant)
             .build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: main_cryptde,
                 alias: alias_cryptde,
@@ -1149,10 +1591,12 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
             },
             rate_pack_routing(103),
             rate_pack_routing_byte(103),
             false,
+            false,
         );
         let before = SystemTime::now();
 
@@ -1191,6 +1635,107 @@ mod tests {
         )
     }
 
+    #[test]
+    fn drops_replayed_package_without_routing_or_charging_for_it_again() {
+        init_test_logging();
+        let test_name = "drops_replayed_package_without_routing_or_charging_for_it_again";
+        BAN_CACHE.clear();
+        let main_cryptde = main_cryptde();
+        let alias_cryptde = alias_cryptde();
+        let paying_wallet = make_paying_wallet(b"wallet");
+        let (dispatcher, _, dispatcher_recording_arc) = make_recorder();
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let next_key = PublicKey::new(&[65, 65, 65]);
+        let contract_address = TEST_DEFAULT_CHAIN.rec().contract;
+        let route = Route::one_way(
+            RouteSegment::new(
+                vec![&main_cryptde.public_key(), &next_key],
+                Component::Neighborhood,
+            ),
+            main_cryptde,
+            Some(paying_wallet),
+            Some(contract_address),
+        )
+        .unwrap();
+        let payload = PlainData::new(&b"abcd"[..]);
+        let lcp = LiveCoresPackage::new(route, main_cryptde.encode(&next_key, &payload).unwrap());
+        let data_ser = PlainData::new(&serde_cbor::ser::to_vec(&lcp).unwrap()[..]);
+        let data_enc = main_cryptde
+            .encode(&main_cryptde.public_key(), &data_ser)
+            .unwrap();
+        let data_len = data_enc.len();
+        let inbound_client_data = InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: SocketAddr::from_str("1.2.3.4:5678").unwrap(),
+            reception_port: None,
+            last_data: true,
+            is_clandestine: true,
+            sequence_number: None,
+            data: data_enc.into(),
+        };
+        let replayed_inbound_client_data = InboundClientData {
+            timestamp: inbound_client_data.timestamp + Duration::from_secs(1),
+            peer_addr: SocketAddr::from_str("2.3.4.5:6789").unwrap(),
+            ..inbound_client_data.clone()
+        };
+        let system = System::new(test_name);
+        let peer_actors = peer_actors_builder()
+            .dispatcher(dispatcher)
+            .accountant(accountant)
+            .build();
+        let mut subject = RoutingService::new(
+            CryptDEPair {
+                main: main_cryptde,
+                alias: alias_cryptde,
+            },
+            make_routing_service_subs(peer_actors),
+            rate_pack_routing(103),
+            rate_pack_routing_byte(103),
+            false,
+            false,
+        );
+        subject.logger = Logger::new(test_name);
+        subject.replay_cache_opt = Some(ReplayCache::default());
+        subject.route(inbound_client_data);
+
+        subject.route(replayed_inbound_client_data);
+
+        System::current().stop();
+        system.run();
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        assert_eq!(dispatcher_recording.len(), 1);
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        assert_eq!(accountant_recording.len(), 1);
+        accountant_recording.get_record::<ReportRoutingServiceProvidedMessage>(0);
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {}: Dropping replayed {}-byte CORES package from 2.3.4.5:6789",
+            test_name, data_len
+        ));
+    }
+
+    #[test]
+    fn keeps_replay_cache_only_if_main_cryptde_really_encrypts() {
+        let _system = System::new("keeps_replay_cache_only_if_main_cryptde_really_encrypts");
+        let make_subject = |cryptdes: CryptDEPair| {
+            RoutingService::new(
+                cryptdes,
+                make_routing_service_subs(peer_actors_builder().build()),
+                100,
+                200,
+                false,
+                false,
+            )
+        };
+
+        let real_subject = make_subject(Bootstrapper::pub_initialize_cryptdes_for_testing(
+            &None, &None,
+        ));
+        let null_subject = make_subject(make_cryptde_pair());
+
+        assert!(real_subject.replay_cache_opt.is_some());
+        assert!(null_subject.replay_cache_opt.is_none());
+    }
+
     #[test]
     fn reprocesses_inbound_client_data_meant_for_this_node_and_destined_for_hopper() {
         let _eg = EnvironmentGuard::new();
@@ -1232,7 +1777,7 @@ mod tests {
             "reprocesses_inbound_client_data_meant_for_this_node_and_destined_for_hopper",
         );
         let peer_actors = peer_actors_builder().hopper(hopper).build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: main_cryptde,
                 alias: alias_cryptde,
@@ -1244,10 +1789,12 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
             },
             rate_pack_routing(103),
             rate_pack_routing_byte(103),
             false,
+            false,
         );
         let before = SystemTime::now();
 
@@ -1328,7 +1875,7 @@ mod tests {
             .dispatcher(dispatcher)
             .accountant(accountant)
             .build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: main_cryptde,
                 alias: alias_cryptde,
@@ -1340,10 +1887,12 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
             },
             100,
             200,
             true,
+            false,
         );
 
         subject.route(inbound_client_data);
@@ -1429,7 +1978,7 @@ mod tests {
             .dispatcher(dispatcher)
             .accountant(accountant)
             .build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: main_cryptde,
                 alias: alias_cryptde,
@@ -1441,10 +1990,12 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
             },
             100,
             200,
             true,
+            false,
         );
 
         subject.route(inbound_client_data);
@@ -1539,10 +2090,12 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
             },
             100,
             200,
             true,
+            false,
         );
 
         subject.route_data_externally(
@@ -1603,7 +2156,7 @@ mod tests {
             .dispatcher(dispatcher)
             .accountant(accountant)
             .build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: main_cryptde,
                 alias: alias_cryptde,
@@ -1615,10 +2168,12 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
             },
             rate_pack_routing(103),
             rate_pack_routing_byte(103),
             false,
+            false,
         );
 
         subject.route(inbound_client_data);
@@ -1677,7 +2232,7 @@ mod tests {
             .dispatcher(dispatcher)
             .accountant(accountant)
             .build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: main_cryptde,
                 alias: alias_cryptde,
@@ -1689,10 +2244,12 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
             },
             rate_pack_routing(103),
             rate_pack_routing_byte(103),
             false,
+            false,
         );
 
         subject.route(inbound_client_data);
@@ -1730,7 +2287,7 @@ mod tests {
             .neighborhood(neighborhood)
             .dispatcher(dispatcher)
             .build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             make_cryptde_pair(),
             RoutingServiceSubs {
                 proxy_client_subs_opt: peer_actors.proxy_client_opt,
@@ -1739,10 +2296,12 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
             },
             100,
             200,
             false,
+            false,
         );
 
         subject.route(inbound_client_data);
@@ -1788,7 +2347,7 @@ mod tests {
             .neighborhood(neighborhood)
             .dispatcher(dispatcher)
             .build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: main_cryptde,
                 alias: alias_cryptde,
@@ -1800,10 +2359,12 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
             },
             100,
             200,
             false,
+            false,
         );
 
         subject.route(inbound_client_data);
@@ -1832,10 +2393,12 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
             },
             100,
             200,
             false,
+            false,
         );
         let lcp = LiveCoresPackage::new(Route { hops: vec![] }, CryptData::new(&[]));
         let ibcd = InboundClientData {
@@ -1863,6 +2426,7 @@ mod tests {
             hopper_subs: peer_actors.hopper,
             to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
             to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+            to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
         }
     }
 
@@ -1879,6 +2443,7 @@ mod tests {
             100,
             200,
             true,
+            false,
         );
         let route = Route::single_hop(&PublicKey::new(b"1234"), subject.cryptdes.main).unwrap();
         let payload = payload_factory(&subject.cryptdes);
@@ -1996,10 +2561,12 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
             },
             100,
             200,
             false,
+            false,
         );
         let expired_package = ExpiredCoresPackage::new(
             SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -2035,10 +2602,12 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
             },
             100,
             200,
             false,
+            false,
         );
         let expired_package = ExpiredCoresPackage::new(
             SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -2074,10 +2643,12 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
             },
             100,
             200,
             false,
+            false,
         );
         let expired_package = ExpiredCoresPackage::new(
             SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -2113,10 +2684,12 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
             },
             100,
             200,
             false,
+            false,
         );
         let expired_package = ExpiredCoresPackage::new(
             SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -2152,10 +2725,12 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_cover_traffic: peer_actors.accountant.report_cover_traffic,
             },
             100,
             200,
             false,
+            false,
         );
         let expired_package = ExpiredCoresPackage::new(
             SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -0,0 +1,280 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::discriminator::UnmaskedChunk;
+use crate::masquerader::MasqueradeError;
+use crate::masquerader::Masquerader;
+use masq_lib::logger::Logger;
+use masq_lib::utils::index_of;
+
+pub const HTTP_MASQUERADE_REQUEST_LINE: &str = "POST /api/v1/sync HTTP/1.1";
+pub const HTTP_MASQUERADE_STATUS_LINE: &str = "HTTP/1.1 200 OK";
+const HEADER_TERMINATOR: &[u8] = b"\r\n\r\n";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HttpMasqueradeForm {
+    Request,
+    Response,
+}
+
+// Data is dressed as the body of an HTTP/1.1 POST request from the Node that opened the
+// connection, and as the body of a 200 response from the Node that accepted it. Either form
+// can be unmasked, no matter which form this masquerader produces.
+pub struct HttpMasquerader {
+    form: HttpMasqueradeForm,
+    logger: Logger,
+}
+
+impl Masquerader for HttpMasquerader {
+    fn try_unmask(&self, item: &[u8]) -> Result<UnmaskedChunk, MasqueradeError> {
+        if !item.starts_with(b"P") && !item.starts_with(b"H") {
+            return Err(MasqueradeError::NotThisMasquerader);
+        }
+        match Self::unmask(item) {
+            Ok(chunk) => Ok(chunk),
+            Err(err) => {
+                error!(self.logger, "{}", err);
+                Err(err)
+            }
+        }
+    }
+
+    fn mask(&self, data: &[u8]) -> Result<Vec<u8>, MasqueradeError> {
+        let start_line = match self.form {
+            HttpMasqueradeForm::Request => HTTP_MASQUERADE_REQUEST_LINE,
+            HttpMasqueradeForm::Response => HTTP_MASQUERADE_STATUS_LINE,
+        };
+        let mut masked = format!(
+            "{}\r\nContent-Type: application/octet-stream\r\nContent-Length: {}\r\n\r\n",
+            start_line,
+            data.len()
+        )
+        .into_bytes();
+        masked.extend(data);
+        Ok(masked)
+    }
+}
+
+impl HttpMasquerader {
+    pub fn new(form: HttpMasqueradeForm) -> HttpMasquerader {
+        HttpMasquerader {
+            form,
+            logger: Logger::new("HttpMasquerader"),
+        }
+    }
+
+    // None means more data is needed before the end of the masquerade can be found. A head with
+    // no usable Content-Length ends the masquerade right after the head; try_unmask will complain.
+    pub fn frame_length(data: &[u8]) -> Option<usize> {
+        let head_len = index_of(data, HEADER_TERMINATOR)? + HEADER_TERMINATOR.len();
+        let content_length = Self::content_length(&data[0..head_len]).unwrap_or(0);
+        let frame_length = head_len + content_length;
+        if frame_length <= data.len() {
+            Some(frame_length)
+        } else {
+            None
+        }
+    }
+
+    fn unmask(data: &[u8]) -> Result<UnmaskedChunk, MasqueradeError> {
+        let head_len = match index_of(data, HEADER_TERMINATOR) {
+            Some(index) => index + HEADER_TERMINATOR.len(),
+            None => {
+                return Err(MasqueradeError::LowLevelDataError(
+                    "HTTP head is not terminated".to_string(),
+                ))
+            }
+        };
+        let (head, body) = data.split_at(head_len);
+        let start_line = String::from_utf8_lossy(&head[0..(head_len - HEADER_TERMINATOR.len())])
+            .lines()
+            .next()
+            .unwrap_or("")
+            .to_string();
+        if start_line != HTTP_MASQUERADE_REQUEST_LINE && start_line != HTTP_MASQUERADE_STATUS_LINE {
+            return Err(MasqueradeError::MidLevelDataError(format!(
+                "Unexpected HTTP start line: '{}'",
+                start_line
+            )));
+        }
+        let content_length = match Self::content_length(head) {
+            Some(content_length) => content_length,
+            None => {
+                return Err(MasqueradeError::MidLevelDataError(
+                    "HTTP head has no valid Content-Length".to_string(),
+                ))
+            }
+        };
+        if content_length != body.len() {
+            return Err(MasqueradeError::HighLevelDataError(format!(
+                "Content-Length is {}, but body contains {} bytes",
+                content_length,
+                body.len()
+            )));
+        }
+        Ok(UnmaskedChunk::new(body.to_vec(), true, false))
+    }
+
+    fn content_length(head: &[u8]) -> Option<usize> {
+        String::from_utf8_lossy(head).lines().find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                value.trim().parse::<usize>().ok()
+            } else {
+                None
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(HTTP_MASQUERADE_REQUEST_LINE, "POST /api/v1/sync HTTP/1.1");
+        assert_eq!(HTTP_MASQUERADE_STATUS_LINE, "HTTP/1.1 200 OK");
+    }
+
+    #[test]
+    fn request_form_masks_data_as_post_request() {
+        let subject = HttpMasquerader::new(HttpMasqueradeForm::Request);
+
+        let result = subject.mask(&[0x00, 0xFF, b'x']).unwrap();
+
+        let mut expected = b"POST /api/v1/sync HTTP/1.1\r\n\
+            Content-Type: application/octet-stream\r\n\
+            Content-Length: 3\r\n\
+            \r\n"
+            .to_vec();
+        expected.extend(&[0x00, 0xFF, b'x']);
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn response_form_masks_data_as_ok_response() {
+        let subject = HttpMasquerader::new(HttpMasqueradeForm::Response);
+
+        let result = subject.mask(b"booga").unwrap();
+
+        assert_eq!(
+            result,
+            b"HTTP/1.1 200 OK\r\n\
+            Content-Type: application/octet-stream\r\n\
+            Content-Length: 5\r\n\
+            \r\n\
+            booga"
+                .to_vec()
+        );
+    }
+
+    #[test]
+    fn either_form_unmasks_both_forms() {
+        let request = HttpMasquerader::new(HttpMasqueradeForm::Request);
+        let response = HttpMasquerader::new(HttpMasqueradeForm::Response);
+        let masked_request = request.mask(b"upstream").unwrap();
+        let masked_response = response.mask(b"downstream").unwrap();
+
+        assert_eq!(
+            response.try_unmask(&masked_request),
+            Ok(UnmaskedChunk::new(b"upstream".to_vec(), true, false))
+        );
+        assert_eq!(
+            request.try_unmask(&masked_response),
+            Ok(UnmaskedChunk::new(b"downstream".to_vec(), true, false))
+        );
+    }
+
+    #[test]
+    fn frame_length_wants_more_data_until_body_is_complete() {
+        let masked = HttpMasquerader::new(HttpMasqueradeForm::Request)
+            .mask(b"booga")
+            .unwrap();
+        let len = masked.len();
+        let mut with_more = masked.clone();
+        with_more.extend(b"HTTP/1.1");
+
+        assert_eq!(HttpMasquerader::frame_length(&masked[0..20]), None);
+        assert_eq!(HttpMasquerader::frame_length(&masked[0..(len - 1)]), None);
+        assert_eq!(HttpMasquerader::frame_length(&masked), Some(len));
+        assert_eq!(HttpMasquerader::frame_length(&with_more), Some(len));
+    }
+
+    #[test]
+    fn frame_length_ends_frame_after_head_without_content_length() {
+        let data = b"POST / HTTP/1.1\r\nHost: booga\r\n\r\nextra";
+
+        let result = HttpMasquerader::frame_length(data);
+
+        assert_eq!(result, Some(data.len() - 5));
+    }
+
+    #[test]
+    fn try_unmask_rejects_data_for_other_masqueraders() {
+        let subject = HttpMasquerader::new(HttpMasqueradeForm::Request);
+
+        let result = subject.try_unmask(&[0x17, 0x03, 0x03, 0x00, 0x00]);
+
+        assert_eq!(result, Err(MasqueradeError::NotThisMasquerader));
+    }
+
+    #[test]
+    fn try_unmask_complains_about_unterminated_head() {
+        init_test_logging();
+        let subject = HttpMasquerader::new(HttpMasqueradeForm::Request);
+
+        let result = subject.try_unmask(b"POST /api/v1/sync HTTP/1.1\r\nContent-Length: 0\r\n");
+
+        assert_eq!(
+            result,
+            Err(MasqueradeError::LowLevelDataError(
+                "HTTP head is not terminated".to_string()
+            ))
+        );
+        TestLogHandler::new().exists_log_containing(
+            "ERROR: HttpMasquerader: Low-level data error: HTTP head is not terminated",
+        );
+    }
+
+    #[test]
+    fn try_unmask_complains_about_unexpected_start_line() {
+        let subject = HttpMasquerader::new(HttpMasqueradeForm::Request);
+
+        let result = subject.try_unmask(b"PUT /api/v1/sync HTTP/1.1\r\nContent-Length: 0\r\n\r\n");
+
+        assert_eq!(
+            result,
+            Err(MasqueradeError::MidLevelDataError(
+                "Unexpected HTTP start line: 'PUT /api/v1/sync HTTP/1.1'".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn try_unmask_complains_about_missing_content_length() {
+        let subject = HttpMasquerader::new(HttpMasqueradeForm::Response);
+
+        let result = subject.try_unmask(b"HTTP/1.1 200 OK\r\nContent-Length: lots\r\n\r\nbooga");
+
+        assert_eq!(
+            result,
+            Err(MasqueradeError::MidLevelDataError(
+                "HTTP head has no valid Content-Length".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn try_unmask_complains_about_body_of_wrong_length() {
+        let subject = HttpMasquerader::new(HttpMasqueradeForm::Response);
+
+        let result = subject.try_unmask(b"HTTP/1.1 200 OK\r\ncontent-length: 4\r\n\r\nbooga");
+
+        assert_eq!(
+            result,
+            Err(MasqueradeError::HighLevelDataError(
+                "Content-Length is 4, but body contains 5 bytes".to_string()
+            ))
+        );
+    }
+}
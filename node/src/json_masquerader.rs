@@ -11,6 +11,9 @@ pub struct JsonMasquerader {
 
 impl Masquerader for JsonMasquerader {
     fn try_unmask(&self, item: &[u8]) -> Result<UnmaskedChunk, MasqueradeError> {
+        if item.first() != Some(&b'{') {
+            return Err(MasqueradeError::NotThisMasquerader);
+        }
         match self.unmask(item) {
             Ok(chunk) => Ok(chunk),
             Err(err) => {
@@ -265,6 +268,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn json_masquerader_rejects_data_for_other_masqueraders() {
+        let subject = JsonMasquerader::new();
+
+        let result = subject.try_unmask(b"HTTP/1.1 200 OK\r\n\r\n");
+
+        assert_eq!(result, Err(MasqueradeError::NotThisMasquerader));
+    }
+
     fn verify_error(data: &[u8], msg_suffix: &str) {
         init_test_logging();
         let subject = JsonMasquerader::new();
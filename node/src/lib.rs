@@ -13,8 +13,12 @@ mod node_test_utils;
 pub mod accountant;
 mod actor_system_factory;
 pub mod apps;
+pub mod binary_framer;
+pub mod binary_masquerader;
 pub mod blockchain;
 mod bootstrapper;
+pub mod clandestine_discriminator_factory;
+pub mod clandestine_framer;
 mod crash_test_dummy;
 pub mod daemon;
 pub mod database;
@@ -22,11 +26,14 @@ pub mod db_config;
 pub mod discriminator;
 pub mod dispatcher;
 pub mod entry_dns;
+pub mod forward_proxy_discriminator_factory;
 pub mod hopper;
+pub mod http_masquerader;
 pub mod http_request_start_finder;
 pub mod json_discriminator_factory;
 pub mod json_framer;
 pub mod json_masquerader;
+mod link_session;
 mod listener_handler;
 pub mod masquerader;
 pub mod neighborhood;
@@ -38,6 +45,7 @@ pub mod proxy_server;
 pub mod run_modes;
 pub mod run_modes_factories;
 pub mod server_initializer;
+pub mod socks5_discriminator_factory;
 pub mod stream_handler_pool;
 mod stream_messages;
 mod stream_reader;
@@ -45,4 +53,5 @@ mod stream_writer_sorted;
 mod stream_writer_unsorted;
 pub mod test_utils; //TODO we should make some effort for collections of testing utils to be really test conditioned.
 pub mod tls_discriminator_factory;
+pub mod tls_masquerader;
 pub mod ui_gateway;
@@ -0,0 +1,677 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::sub_lib::cryptde::{CryptDE, CryptData, PlainData, PublicKey, SymmetricKey};
+use serde_derive::{Deserialize, Serialize};
+use sodiumoxide::crypto::kx;
+use std::time::{Duration, SystemTime};
+
+// How often the Node that opened a clandestine connection replaces the symmetric keys for that
+// link. Once a generation of keys is replaced, the ephemeral secrets that produced it are gone,
+// so recorded traffic can't be decrypted even if the Node's long-term keys later leak.
+pub const LINK_REKEY_INTERVAL: Duration = Duration::from_secs(600);
+// A Hello that hasn't been answered in this long is given up on.
+pub const LINK_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(30);
+
+// What actually crosses a clandestine connection between two neighbors, inside the masquerade.
+// The CORES package in a Data frame is exactly what it would have been without the link layer.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LinkFrame {
+    Hello(LinkHello),
+    Data { generation: u32, data: CryptData },
+}
+
+impl LinkFrame {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serde_cbor::ser::to_vec(self).expect("Serialization of LinkFrame failed")
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<LinkFrame, String> {
+        serde_cbor::de::from_slice::<LinkFrame>(bytes)
+            .map_err(|e| format!("Not a link frame: {:?}", e))
+    }
+}
+
+// One half of a key exchange, signed with the sender's main CryptDE so that nobody between the
+// neighbors can substitute an ephemeral key of their own. A reply names the ephemeral key it
+// answers, so it can't be spliced into some other exchange.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkHello {
+    pub generation: u32,
+    pub ephemeral_key: PublicKey,
+    pub reply_to_opt: Option<PublicKey>,
+    pub public_key: PublicKey,
+    pub signature: CryptData,
+}
+
+impl LinkHello {
+    fn new(
+        cryptde: &dyn CryptDE,
+        generation: u32,
+        ephemeral_key: PublicKey,
+        reply_to_opt: Option<PublicKey>,
+    ) -> Self {
+        let public_key = cryptde.public_key().clone();
+        let signature = cryptde
+            .sign(&Self::signed_data(
+                generation,
+                &ephemeral_key,
+                &reply_to_opt,
+                &public_key,
+            ))
+            .expect("Couldn't sign link Hello");
+        Self {
+            generation,
+            ephemeral_key,
+            reply_to_opt,
+            public_key,
+            signature,
+        }
+    }
+
+    fn has_valid_signature(&self, cryptde: &dyn CryptDE) -> bool {
+        cryptde.verify_signature(
+            &Self::signed_data(
+                self.generation,
+                &self.ephemeral_key,
+                &self.reply_to_opt,
+                &self.public_key,
+            ),
+            &self.signature,
+            &self.public_key,
+        )
+    }
+
+    fn signed_data(
+        generation: u32,
+        ephemeral_key: &PublicKey,
+        reply_to_opt: &Option<PublicKey>,
+        public_key: &PublicKey,
+    ) -> PlainData {
+        PlainData::from(
+            serde_cbor::ser::to_vec(&(generation, ephemeral_key, reply_to_opt, public_key))
+                .expect("Serialization of LinkHello failed"),
+        )
+    }
+}
+
+struct PendingHandshake {
+    generation: u32,
+    public_key: kx::PublicKey,
+    secret_key: kx::SecretKey,
+    started: SystemTime,
+}
+
+struct LinkKeys {
+    generation: u32,
+    rx: SymmetricKey,
+    tx: SymmetricKey,
+}
+
+// The link-layer keys this Node shares with the neighbor at the other end of one clandestine
+// connection. The Node that opened the connection opens every generation and plays client in
+// the key exchange; the other end only answers. Each end contributes a fresh ephemeral key to
+// every generation, and the secret half is dropped as soon as the session keys are derived.
+// The default session is the answering end's, before it knows who's calling.
+#[derive(Default)]
+pub struct LinkSession {
+    initiator: bool,
+    peer_key_opt: Option<PublicKey>,
+    pending_opt: Option<PendingHandshake>,
+    current_opt: Option<LinkKeys>,
+    previous_opt: Option<LinkKeys>,
+}
+
+impl LinkSession {
+    // For a connection this Node opened to the neighbor gossiping under peer_key
+    pub fn new_initiator(peer_key: PublicKey) -> Self {
+        Self {
+            initiator: true,
+            peer_key_opt: Some(peer_key),
+            ..Self::default()
+        }
+    }
+
+    #[cfg(test)]
+    pub fn new_established_for_test(
+        initiator: bool,
+        peer_key: PublicKey,
+        generation: u32,
+        rx: SymmetricKey,
+        tx: SymmetricKey,
+    ) -> Self {
+        Self {
+            initiator,
+            peer_key_opt: Some(peer_key),
+            pending_opt: None,
+            current_opt: Some(LinkKeys { generation, rx, tx }),
+            previous_opt: None,
+        }
+    }
+
+    pub fn is_initiator(&self) -> bool {
+        self.initiator
+    }
+
+    // The neighbor's main public key: gossiped for a connection this Node opened, proven by
+    // the neighbor's signed Hello otherwise.
+    pub fn peer_key_opt(&self) -> Option<&PublicKey> {
+        self.peer_key_opt.as_ref()
+    }
+
+    pub fn is_established(&self) -> bool {
+        self.current_opt.is_some()
+    }
+
+    pub fn is_rekeying(&self) -> bool {
+        self.pending_opt.is_some()
+    }
+
+    // Produces the Hello that opens a new generation: the first one for a new connection,
+    // or the next one when it's time to rekey. Only the initiator calls this.
+    pub fn start_handshake(&mut self, cryptde: &dyn CryptDE, now: SystemTime) -> LinkFrame {
+        let generation = match &self.current_opt {
+            None => 0,
+            Some(keys) => keys.generation.wrapping_add(1),
+        };
+        let (public_key, secret_key) = Self::gen_keypair();
+        let hello = LinkHello::new(
+            cryptde,
+            generation,
+            PublicKey::new(public_key.as_ref()),
+            None,
+        );
+        self.pending_opt = Some(PendingHandshake {
+            generation,
+            public_key,
+            secret_key,
+            started: now,
+        });
+        LinkFrame::Hello(hello)
+    }
+
+    // Completes a generation with the neighbor's ephemeral key. On the answering end, returns
+    // the Hello that carries our half of it back.
+    pub fn receive_hello(
+        &mut self,
+        cryptde: &dyn CryptDE,
+        hello: &LinkHello,
+    ) -> Result<Option<LinkFrame>, String> {
+        let their_key = kx::PublicKey::from_slice(hello.ephemeral_key.as_slice())
+            .ok_or_else(|| format!("Invalid ephemeral key: {}", hello.ephemeral_key))?;
+        if !hello.has_valid_signature(cryptde) {
+            return Err(format!(
+                "Bad signature on Hello for key generation {}",
+                hello.generation
+            ));
+        }
+        match &self.peer_key_opt {
+            Some(peer_key) if peer_key != &hello.public_key => {
+                return Err(format!(
+                    "Hello for key generation {} is from {}, not {}",
+                    hello.generation, hello.public_key, peer_key
+                ))
+            }
+            _ => (),
+        }
+        let (keys, reply_opt) = if self.initiator {
+            (self.receive_reply(hello, &their_key)?, None)
+        } else {
+            self.answer_hello(cryptde, hello, &their_key)?
+        };
+        self.previous_opt = self.current_opt.take();
+        self.current_opt = Some(keys);
+        Ok(reply_opt)
+    }
+
+    // Gives up on a Hello that has gone unanswered for longer than the timeout, so that it
+    // can be tried again. Returns true if there was one.
+    pub fn expire_handshake(&mut self, now: SystemTime, timeout: Duration) -> bool {
+        let expired = match &self.pending_opt {
+            Some(pending) => {
+                matches!(now.duration_since(pending.started), Ok(age) if age >= timeout)
+            }
+            None => false,
+        };
+        if expired {
+            self.pending_opt = None;
+        }
+        expired
+    }
+
+    pub fn seal(&self, cryptde: &dyn CryptDE, data: &[u8]) -> Result<LinkFrame, String> {
+        let keys = self
+            .current_opt
+            .as_ref()
+            .ok_or_else(|| "Link keys not yet established".to_string())?;
+        let data = cryptde
+            .encode_sym(&keys.tx, &PlainData::new(data))
+            .map_err(|e| format!("Couldn't encrypt for link: {:?}", e))?;
+        Ok(LinkFrame::Data {
+            generation: keys.generation,
+            data,
+        })
+    }
+
+    pub fn open(
+        &self,
+        cryptde: &dyn CryptDE,
+        generation: u32,
+        data: &CryptData,
+    ) -> Result<PlainData, String> {
+        let keys = [&self.current_opt, &self.previous_opt]
+            .into_iter()
+            .flatten()
+            .find(|keys| keys.generation == generation)
+            .ok_or_else(|| format!("No link keys for generation {}", generation))?;
+        cryptde
+            .decode_sym(&keys.rx, data)
+            .map_err(|e| format!("Couldn't decrypt from link: {:?}", e))
+    }
+
+    fn receive_reply(
+        &mut self,
+        hello: &LinkHello,
+        their_key: &kx::PublicKey,
+    ) -> Result<LinkKeys, String> {
+        let pending = match self.pending_opt.take() {
+            Some(pending)
+                if pending.generation == hello.generation
+                    && hello.reply_to_opt.as_ref().map(|key| key.as_slice())
+                        == Some(pending.public_key.as_ref()) =>
+            {
+                pending
+            }
+            pending_opt => {
+                self.pending_opt = pending_opt;
+                return Err(format!(
+                    "Unexpected Hello for key generation {}",
+                    hello.generation
+                ));
+            }
+        };
+        Self::derive_keys(
+            pending.generation,
+            kx::client_session_keys(&pending.public_key, &pending.secret_key, their_key),
+        )
+    }
+
+    fn answer_hello(
+        &mut self,
+        cryptde: &dyn CryptDE,
+        hello: &LinkHello,
+        their_key: &kx::PublicKey,
+    ) -> Result<(LinkKeys, Option<LinkFrame>), String> {
+        if hello.reply_to_opt.is_some() {
+            return Err(format!(
+                "Unexpected reply for key generation {}",
+                hello.generation
+            ));
+        }
+        let (public_key, secret_key) = Self::gen_keypair();
+        let keys = Self::derive_keys(
+            hello.generation,
+            kx::server_session_keys(&public_key, &secret_key, their_key),
+        )?;
+        self.peer_key_opt = Some(hello.public_key.clone());
+        let reply = LinkHello::new(
+            cryptde,
+            hello.generation,
+            PublicKey::new(public_key.as_ref()),
+            Some(hello.ephemeral_key.clone()),
+        );
+        Ok((keys, Some(LinkFrame::Hello(reply))))
+    }
+
+    fn gen_keypair() -> (kx::PublicKey, kx::SecretKey) {
+        sodiumoxide::init().expect("sodiumoxide initialization failed");
+        kx::gen_keypair()
+    }
+
+    fn derive_keys(
+        generation: u32,
+        session_keys: Result<(kx::SessionKey, kx::SessionKey), ()>,
+    ) -> Result<LinkKeys, String> {
+        match session_keys {
+            Ok((rx, tx)) => Ok(LinkKeys {
+                generation,
+                rx: SymmetricKey::new(rx.as_ref()),
+                tx: SymmetricKey::new(tx.as_ref()),
+            }),
+            Err(()) => Err(format!(
+                "Couldn't derive link keys for generation {}",
+                generation
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sub_lib::cryptde_real::CryptDEReal;
+    use crate::test_utils::main_cryptde;
+    use masq_lib::test_utils::utils::TEST_DEFAULT_CHAIN;
+
+    fn hello_of(frame: LinkFrame) -> LinkHello {
+        match frame {
+            LinkFrame::Hello(hello) => hello,
+            x => panic!("Expected Hello; got {:?}", x),
+        }
+    }
+
+    fn established_pair(
+        alice_cryptde: &dyn CryptDE,
+        bob_cryptde: &dyn CryptDE,
+    ) -> (LinkSession, LinkSession) {
+        let mut alice = LinkSession::new_initiator(bob_cryptde.public_key().clone());
+        let mut bob = LinkSession::default();
+        let hello = hello_of(alice.start_handshake(alice_cryptde, SystemTime::now()));
+        let reply = hello_of(bob.receive_hello(bob_cryptde, &hello).unwrap().unwrap());
+        assert_eq!(alice.receive_hello(alice_cryptde, &reply), Ok(None));
+        (alice, bob)
+    }
+
+    fn seal_parts(session: &LinkSession, cryptde: &dyn CryptDE, data: &[u8]) -> (u32, CryptData) {
+        match session.seal(cryptde, data).unwrap() {
+            LinkFrame::Data { generation, data } => (generation, data),
+            x => panic!("Expected Data; got {:?}", x),
+        }
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(LINK_REKEY_INTERVAL, Duration::from_secs(600));
+        assert_eq!(LINK_HANDSHAKE_TIMEOUT, Duration::from_secs(30));
+    }
+
+    #[test]
+    fn link_frames_survive_the_trip_through_bytes() {
+        let hello = LinkFrame::Hello(LinkHello {
+            generation: 3,
+            ephemeral_key: PublicKey::new(&[1, 2, 3, 4]),
+            reply_to_opt: Some(PublicKey::new(&[4, 3, 2, 1])),
+            public_key: PublicKey::new(&[5, 6, 7, 8]),
+            signature: CryptData::new(&[8, 7, 6, 5]),
+        });
+        let data = LinkFrame::Data {
+            generation: 4,
+            data: CryptData::new(b"booga"),
+        };
+
+        assert_eq!(LinkFrame::from_bytes(&hello.to_bytes()), Ok(hello));
+        assert_eq!(LinkFrame::from_bytes(&data.to_bytes()), Ok(data));
+    }
+
+    #[test]
+    fn bytes_that_are_not_a_link_frame_are_rejected() {
+        let result = LinkFrame::from_bytes(b"booga");
+
+        assert!(result.unwrap_err().starts_with("Not a link frame"));
+    }
+
+    #[test]
+    fn new_session_is_not_established_and_cannot_seal() {
+        let subject = LinkSession::new_initiator(PublicKey::new(&[1, 2, 3, 4]));
+
+        let result = subject.seal(main_cryptde(), b"booga");
+
+        assert_eq!(subject.is_initiator(), true);
+        assert_eq!(subject.peer_key_opt(), Some(&PublicKey::new(&[1, 2, 3, 4])));
+        assert_eq!(subject.is_established(), false);
+        assert_eq!(result, Err("Link keys not yet established".to_string()));
+    }
+
+    #[test]
+    fn hello_is_signed_by_the_main_cryptde() {
+        let cryptde = CryptDEReal::new(TEST_DEFAULT_CHAIN);
+        let mut subject = LinkSession::new_initiator(PublicKey::new(&[1, 2, 3, 4]));
+
+        let hello = hello_of(subject.start_handshake(&cryptde, SystemTime::now()));
+
+        assert_eq!(hello.generation, 0);
+        assert_eq!(hello.reply_to_opt, None);
+        assert_eq!(&hello.public_key, cryptde.public_key());
+        assert_eq!(hello.has_valid_signature(&cryptde), true);
+        assert_eq!(subject.is_rekeying(), true);
+    }
+
+    #[test]
+    fn answering_end_replies_to_a_hello_and_both_ends_share_keys() {
+        let alice_cryptde = CryptDEReal::new(TEST_DEFAULT_CHAIN);
+        let bob_cryptde = CryptDEReal::new(TEST_DEFAULT_CHAIN);
+        let mut alice = LinkSession::new_initiator(bob_cryptde.public_key().clone());
+        let mut bob = LinkSession::default();
+        let hello = hello_of(alice.start_handshake(&alice_cryptde, SystemTime::now()));
+
+        let reply = hello_of(bob.receive_hello(&bob_cryptde, &hello).unwrap().unwrap());
+
+        assert_eq!(reply.generation, 0);
+        assert_eq!(reply.reply_to_opt, Some(hello.ephemeral_key.clone()));
+        assert_eq!(&reply.public_key, bob_cryptde.public_key());
+        assert_eq!(bob.is_initiator(), false);
+        assert_eq!(bob.peer_key_opt(), Some(alice_cryptde.public_key()));
+        assert_eq!(alice.receive_hello(&alice_cryptde, &reply), Ok(None));
+        assert_eq!(alice.is_established(), true);
+        assert_eq!(alice.is_rekeying(), false);
+        assert_eq!(bob.is_established(), true);
+        let (generation, data) = seal_parts(&bob, &bob_cryptde, b"agoob");
+        assert_ne!(data.as_slice(), b"agoob");
+        assert_eq!(
+            alice.open(&alice_cryptde, generation, &data),
+            Ok(PlainData::new(b"agoob"))
+        );
+        let (generation, data) = seal_parts(&alice, &alice_cryptde, b"booga");
+        assert_eq!(
+            bob.open(&bob_cryptde, generation, &data),
+            Ok(PlainData::new(b"booga"))
+        );
+    }
+
+    #[test]
+    fn hello_with_substituted_ephemeral_key_is_rejected() {
+        let alice_cryptde = CryptDEReal::new(TEST_DEFAULT_CHAIN);
+        let bob_cryptde = CryptDEReal::new(TEST_DEFAULT_CHAIN);
+        let mut alice = LinkSession::new_initiator(bob_cryptde.public_key().clone());
+        let mut bob = LinkSession::default();
+        let mut hello = hello_of(alice.start_handshake(&alice_cryptde, SystemTime::now()));
+        let mallory_hello = hello_of(
+            LinkSession::new_initiator(bob_cryptde.public_key().clone())
+                .start_handshake(&alice_cryptde, SystemTime::now()),
+        );
+        hello.ephemeral_key = mallory_hello.ephemeral_key;
+
+        let result = bob.receive_hello(&bob_cryptde, &hello);
+
+        assert_eq!(
+            result,
+            Err("Bad signature on Hello for key generation 0".to_string())
+        );
+        assert_eq!(bob.is_established(), false);
+    }
+
+    #[test]
+    fn reply_from_someone_other_than_the_gossiped_neighbor_is_rejected() {
+        let alice_cryptde = CryptDEReal::new(TEST_DEFAULT_CHAIN);
+        let bob_cryptde = CryptDEReal::new(TEST_DEFAULT_CHAIN);
+        let mallory_cryptde = CryptDEReal::new(TEST_DEFAULT_CHAIN);
+        let mut alice = LinkSession::new_initiator(bob_cryptde.public_key().clone());
+        let hello = hello_of(alice.start_handshake(&alice_cryptde, SystemTime::now()));
+        let reply = hello_of(
+            LinkSession::default()
+                .receive_hello(&mallory_cryptde, &hello)
+                .unwrap()
+                .unwrap(),
+        );
+
+        let result = alice.receive_hello(&alice_cryptde, &reply);
+
+        assert_eq!(
+            result,
+            Err(format!(
+                "Hello for key generation 0 is from {}, not {}",
+                mallory_cryptde.public_key(),
+                bob_cryptde.public_key()
+            ))
+        );
+        assert_eq!(alice.is_established(), false);
+        assert_eq!(alice.is_rekeying(), true);
+    }
+
+    #[test]
+    fn reply_to_some_other_hello_is_rejected() {
+        let alice_cryptde = CryptDEReal::new(TEST_DEFAULT_CHAIN);
+        let bob_cryptde = CryptDEReal::new(TEST_DEFAULT_CHAIN);
+        let mut alice = LinkSession::new_initiator(bob_cryptde.public_key().clone());
+        let _ = alice.start_handshake(&alice_cryptde, SystemTime::now());
+        let other_hello = hello_of(
+            LinkSession::new_initiator(bob_cryptde.public_key().clone())
+                .start_handshake(&alice_cryptde, SystemTime::now()),
+        );
+        let reply = hello_of(
+            LinkSession::default()
+                .receive_hello(&bob_cryptde, &other_hello)
+                .unwrap()
+                .unwrap(),
+        );
+
+        let result = alice.receive_hello(&alice_cryptde, &reply);
+
+        assert_eq!(
+            result,
+            Err("Unexpected Hello for key generation 0".to_string())
+        );
+        assert_eq!(alice.is_established(), false);
+    }
+
+    #[test]
+    fn initiator_rejects_a_hello_that_opens_a_generation() {
+        let alice_cryptde = CryptDEReal::new(TEST_DEFAULT_CHAIN);
+        let bob_cryptde = CryptDEReal::new(TEST_DEFAULT_CHAIN);
+        let mut alice = LinkSession::new_initiator(bob_cryptde.public_key().clone());
+        let _ = alice.start_handshake(&alice_cryptde, SystemTime::now());
+        let bob_hello = hello_of(
+            LinkSession::new_initiator(alice_cryptde.public_key().clone())
+                .start_handshake(&bob_cryptde, SystemTime::now()),
+        );
+
+        let result = alice.receive_hello(&alice_cryptde, &bob_hello);
+
+        assert_eq!(
+            result,
+            Err("Unexpected Hello for key generation 0".to_string())
+        );
+        assert_eq!(alice.is_established(), false);
+    }
+
+    #[test]
+    fn answering_end_rejects_an_unexpected_reply() {
+        let alice_cryptde = CryptDEReal::new(TEST_DEFAULT_CHAIN);
+        let bob_cryptde = CryptDEReal::new(TEST_DEFAULT_CHAIN);
+        let hello = hello_of(
+            LinkSession::new_initiator(alice_cryptde.public_key().clone())
+                .start_handshake(&bob_cryptde, SystemTime::now()),
+        );
+        let reply = hello_of(
+            LinkSession::default()
+                .receive_hello(&alice_cryptde, &hello)
+                .unwrap()
+                .unwrap(),
+        );
+        let mut subject = LinkSession::default();
+
+        let result = subject.receive_hello(&bob_cryptde, &reply);
+
+        assert_eq!(
+            result,
+            Err("Unexpected reply for key generation 0".to_string())
+        );
+        assert_eq!(subject.is_established(), false);
+        assert_eq!(subject.peer_key_opt(), None);
+    }
+
+    #[test]
+    fn malformed_ephemeral_key_is_rejected() {
+        let mut subject = LinkSession::default();
+        let mut hello = hello_of(
+            LinkSession::new_initiator(PublicKey::new(&[1, 2, 3, 4]))
+                .start_handshake(main_cryptde(), SystemTime::now()),
+        );
+        hello.ephemeral_key = PublicKey::new(&[1, 2, 3]);
+
+        let result = subject.receive_hello(main_cryptde(), &hello);
+
+        assert_eq!(result, Err("Invalid ephemeral key: AQID".to_string()));
+    }
+
+    #[test]
+    fn rekeying_moves_to_the_next_generation_and_still_opens_frames_from_the_last_one() {
+        let alice_cryptde = CryptDEReal::new(TEST_DEFAULT_CHAIN);
+        let bob_cryptde = CryptDEReal::new(TEST_DEFAULT_CHAIN);
+        let (mut alice, mut bob) = established_pair(&alice_cryptde, &bob_cryptde);
+        let in_flight = seal_parts(&bob, &bob_cryptde, b"in flight");
+        let hello = hello_of(alice.start_handshake(&alice_cryptde, SystemTime::now()));
+        assert_eq!(hello.generation, 1);
+        assert_eq!(alice.is_rekeying(), true);
+
+        let reply = hello_of(bob.receive_hello(&bob_cryptde, &hello).unwrap().unwrap());
+        alice.receive_hello(&alice_cryptde, &reply).unwrap();
+
+        assert_eq!(alice.is_rekeying(), false);
+        assert_eq!(
+            alice.open(&alice_cryptde, in_flight.0, &in_flight.1),
+            Ok(PlainData::new(b"in flight"))
+        );
+        let (generation, data) = seal_parts(&alice, &alice_cryptde, b"fresh");
+        assert_eq!(generation, 1);
+        assert_eq!(
+            bob.open(&bob_cryptde, generation, &data),
+            Ok(PlainData::new(b"fresh"))
+        );
+        assert_eq!(
+            alice.open(&alice_cryptde, 7, &in_flight.1),
+            Err("No link keys for generation 7".to_string())
+        );
+    }
+
+    #[test]
+    fn unanswered_hello_expires_after_the_timeout() {
+        let alice_cryptde = CryptDEReal::new(TEST_DEFAULT_CHAIN);
+        let bob_cryptde = CryptDEReal::new(TEST_DEFAULT_CHAIN);
+        let (mut alice, _) = established_pair(&alice_cryptde, &bob_cryptde);
+        let started = SystemTime::now();
+        let _ = alice.start_handshake(&alice_cryptde, started);
+
+        let early =
+            alice.expire_handshake(started + Duration::from_secs(29), LINK_HANDSHAKE_TIMEOUT);
+        assert_eq!(early, false);
+        assert_eq!(alice.is_rekeying(), true);
+
+        let late =
+            alice.expire_handshake(started + Duration::from_secs(30), LINK_HANDSHAKE_TIMEOUT);
+
+        assert_eq!(late, true);
+        assert_eq!(alice.is_rekeying(), false);
+        assert_eq!(alice.is_established(), true);
+        assert_eq!(
+            alice.expire_handshake(started + Duration::from_secs(60), LINK_HANDSHAKE_TIMEOUT),
+            false
+        );
+        let hello = hello_of(alice.start_handshake(&alice_cryptde, started));
+        assert_eq!(hello.generation, 1);
+    }
+
+    #[test]
+    fn frame_sealed_with_other_keys_cannot_be_opened() {
+        let alice_cryptde = CryptDEReal::new(TEST_DEFAULT_CHAIN);
+        let bob_cryptde = CryptDEReal::new(TEST_DEFAULT_CHAIN);
+        let (alice, _) = established_pair(&alice_cryptde, &bob_cryptde);
+        let (_, carol) = established_pair(&alice_cryptde, &bob_cryptde);
+
+        let (generation, data) = seal_parts(&alice, &alice_cryptde, b"booga");
+        let result = carol.open(&bob_cryptde, generation, &data);
+
+        assert!(result
+            .unwrap_err()
+            .starts_with("Couldn't decrypt from link"));
+    }
+}
@@ -1,5 +1,13 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::binary_framer::BINARY_MASQUERADE_MARKER;
+use crate::binary_masquerader::BinaryMasquerader;
 use crate::discriminator::UnmaskedChunk;
+use crate::http_masquerader::{HttpMasqueradeForm, HttpMasquerader};
+use crate::json_masquerader::JsonMasquerader;
+use crate::tls_masquerader::{TlsMasquerader, TLS_APPLICATION_DATA};
+use serde::de::IgnoredAny;
+use serde::Deserializer;
+use serde_derive::{Deserialize, Serialize};
 use std::fmt;
 use std::fmt::Display;
 use std::fmt::Formatter;
@@ -29,6 +37,87 @@ pub trait Masquerader: Send {
     fn mask(&self, data: &[u8]) -> Result<Vec<u8>, MasqueradeError>;
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MasqueraderKind {
+    Json,
+    Http,
+    Tls,
+    Binary,
+}
+
+impl MasqueraderKind {
+    // In order of preference: two Nodes use the first kind in this list that both of them support.
+    // Binary doesn't disguise anything, so it comes after the lookalikes and is offered only on request.
+    pub const ALL: [MasqueraderKind; 4] = [
+        MasqueraderKind::Tls,
+        MasqueraderKind::Http,
+        MasqueraderKind::Binary,
+        MasqueraderKind::Json,
+    ];
+
+    pub fn supported(binary_masquerade: bool) -> Vec<MasqueraderKind> {
+        MasqueraderKind::ALL
+            .into_iter()
+            .filter(|kind| binary_masquerade || *kind != MasqueraderKind::Binary)
+            .collect()
+    }
+
+    // Every masquerade begins with a byte that none of the others can begin with.
+    pub fn recognize(first_byte: u8) -> Option<MasqueraderKind> {
+        match first_byte {
+            b'{' => Some(MasqueraderKind::Json),
+            b'P' | b'H' => Some(MasqueraderKind::Http),
+            TLS_APPLICATION_DATA => Some(MasqueraderKind::Tls),
+            BINARY_MASQUERADE_MARKER => Some(MasqueraderKind::Binary),
+            _ => None,
+        }
+    }
+
+    // A Node that advertises no kinds predates masquerader negotiation and understands only JSON.
+    pub fn negotiate(ours: &[MasqueraderKind], theirs: &[MasqueraderKind]) -> MasqueraderKind {
+        MasqueraderKind::ALL
+            .into_iter()
+            .find(|kind| ours.contains(kind) && theirs.contains(kind))
+            .unwrap_or(MasqueraderKind::Json)
+    }
+
+    // Kinds added by later releases are skipped instead of failing the whole list, so that a
+    // NodeRecord advertising one can still be decoded.
+    pub fn deserialize_known<'de, D>(deserializer: D) -> Result<Vec<MasqueraderKind>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum MaybeKnown {
+            Known(MasqueraderKind),
+            Unknown(IgnoredAny),
+        }
+        let kinds: Vec<MaybeKnown> = serde::Deserialize::deserialize(deserializer)?;
+        Ok(kinds
+            .into_iter()
+            .filter_map(|kind| match kind {
+                MaybeKnown::Known(kind) => Some(kind),
+                MaybeKnown::Unknown(_) => None,
+            })
+            .collect())
+    }
+
+    // The initiator of a connection is the one whose traffic should look like it came from a client.
+    pub fn make_masquerader(self, initiator: bool) -> Box<dyn Masquerader> {
+        match self {
+            MasqueraderKind::Json => Box::new(JsonMasquerader::new()),
+            MasqueraderKind::Http => Box::new(HttpMasquerader::new(if initiator {
+                HttpMasqueradeForm::Request
+            } else {
+                HttpMasqueradeForm::Response
+            })),
+            MasqueraderKind::Tls => Box::new(TlsMasquerader::new()),
+            MasqueraderKind::Binary => Box::new(BinaryMasquerader::new()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -61,4 +150,125 @@ mod tests {
             "Data not for this masquerader"
         );
     }
+
+    #[test]
+    fn masquerader_kinds_are_listed_in_order_of_preference() {
+        assert_eq!(
+            MasqueraderKind::supported(true),
+            vec![
+                MasqueraderKind::Tls,
+                MasqueraderKind::Http,
+                MasqueraderKind::Binary,
+                MasqueraderKind::Json
+            ]
+        );
+    }
+
+    #[test]
+    fn binary_masquerade_is_supported_only_on_request() {
+        assert_eq!(
+            MasqueraderKind::supported(false),
+            vec![
+                MasqueraderKind::Tls,
+                MasqueraderKind::Http,
+                MasqueraderKind::Json
+            ]
+        );
+    }
+
+    #[test]
+    fn masquerader_kinds_are_recognized_by_first_byte() {
+        assert_eq!(
+            MasqueraderKind::recognize(b'{'),
+            Some(MasqueraderKind::Json)
+        );
+        assert_eq!(
+            MasqueraderKind::recognize(b'P'),
+            Some(MasqueraderKind::Http)
+        );
+        assert_eq!(
+            MasqueraderKind::recognize(b'H'),
+            Some(MasqueraderKind::Http)
+        );
+        assert_eq!(MasqueraderKind::recognize(0x17), Some(MasqueraderKind::Tls));
+        assert_eq!(
+            MasqueraderKind::recognize(0xB1),
+            Some(MasqueraderKind::Binary)
+        );
+        assert_eq!(MasqueraderKind::recognize(b'G'), None);
+        assert_eq!(MasqueraderKind::recognize(0x16), None);
+    }
+
+    #[test]
+    fn negotiation_chooses_most_preferred_kind_both_ends_support() {
+        let all = MasqueraderKind::supported(true);
+
+        assert_eq!(MasqueraderKind::negotiate(&all, &all), MasqueraderKind::Tls);
+        assert_eq!(
+            MasqueraderKind::negotiate(&all, &[MasqueraderKind::Json, MasqueraderKind::Binary]),
+            MasqueraderKind::Binary
+        );
+        assert_eq!(
+            MasqueraderKind::negotiate(
+                &MasqueraderKind::supported(false),
+                &[MasqueraderKind::Binary]
+            ),
+            MasqueraderKind::Json
+        );
+        assert_eq!(
+            MasqueraderKind::negotiate(&all, &[MasqueraderKind::Json, MasqueraderKind::Tls]),
+            MasqueraderKind::Tls
+        );
+        assert_eq!(
+            MasqueraderKind::negotiate(&all, &[MasqueraderKind::Json, MasqueraderKind::Http]),
+            MasqueraderKind::Http
+        );
+        assert_eq!(
+            MasqueraderKind::negotiate(&[MasqueraderKind::Json, MasqueraderKind::Http], &all),
+            MasqueraderKind::Http
+        );
+    }
+
+    #[test]
+    fn negotiation_falls_back_to_json() {
+        let all = MasqueraderKind::supported(true);
+
+        assert_eq!(MasqueraderKind::negotiate(&all, &[]), MasqueraderKind::Json);
+        assert_eq!(
+            MasqueraderKind::negotiate(&[MasqueraderKind::Tls], &[MasqueraderKind::Http]),
+            MasqueraderKind::Json
+        );
+    }
+
+    #[test]
+    fn made_masqueraders_produce_masquerades_of_their_kind() {
+        vec![
+            (MasqueraderKind::Json, true, b'{'),
+            (MasqueraderKind::Http, true, b'P'),
+            (MasqueraderKind::Http, false, b'H'),
+            (MasqueraderKind::Tls, true, 0x17),
+            (MasqueraderKind::Tls, false, 0x17),
+            (MasqueraderKind::Binary, true, 0xB1),
+            (MasqueraderKind::Binary, false, 0xB1),
+        ]
+        .into_iter()
+        .for_each(|(kind, initiator, first_byte)| {
+            let subject = kind.make_masquerader(initiator);
+
+            let result = subject.mask(b"booga").unwrap();
+
+            assert_eq!(result[0], first_byte, "{:?}, {}", kind, initiator);
+            assert_eq!(
+                MasqueraderKind::recognize(result[0]),
+                Some(kind),
+                "{:?}, {}",
+                kind,
+                initiator
+            );
+            assert_eq!(
+                subject.try_unmask(&result),
+                Ok(UnmaskedChunk::new(b"booga".to_vec(), true, false))
+            );
+        });
+    }
 }
@@ -123,6 +123,40 @@ impl GossipNodeRecord {
                         .collect::<Vec<PublicKey>>()
                 );
                 let _ = write!(human_readable, "\n\t\tversion: {:?},", nri.version);
+                let _ = write!(
+                    human_readable,
+                    "\n\t\tmasquerader_kinds: {:?},",
+                    nri.masquerader_kinds
+                );
+                let _ = write!(
+                    human_readable,
+                    "\n\t\texit_policy: {:?},",
+                    nri.exit_policy
+                        .rules()
+                        .iter()
+                        .map(|rule| rule.to_string())
+                        .collect::<Vec<String>>()
+                );
+                let _ = write!(
+                    human_readable,
+                    "\n\t\tcountry_code_opt: {:?},",
+                    nri.country_code_opt
+                );
+                let _ = write!(
+                    human_readable,
+                    "\n\t\tneighbor_latencies: {:?},",
+                    nri.neighbor_latencies
+                );
+                let _ = write!(
+                    human_readable,
+                    "\n\t\tkey_transition_opt: {:?},",
+                    nri.key_transition_opt
+                );
+                let _ = write!(
+                    human_readable,
+                    "\n\t\tlink_encryption: {:?},",
+                    nri.link_encryption
+                );
                 let _ = write!(human_readable, "\n\t}},");
             }
             Err(_e) => {
@@ -632,11 +666,11 @@ mod tests {
         let result = format!("{:?}", gossip);
         let expected = format!(
             "\nGossipNodeRecord {{{}{}{}{}\n}}",
-            "\n\tinner: NodeRecordInner_0v1 {\n\t\tpublic_key: 0x01020304,\n\t\tnode_addr_opt: Some(1.2.3.4:[1234]),\n\t\tearning_wallet: Wallet { kind: Address(0x546900db8d6e0937497133d1ae6fdf5f4b75bcd0) },\n\t\trate_pack: RatePack { routing_byte_rate: 1235, routing_service_rate: 1434, exit_byte_rate: 1237, exit_service_rate: 1634 },\n\t\tneighbors: [],\n\t\tversion: 2,\n\t},",
+            "\n\tinner: NodeRecordInner_0v1 {\n\t\tpublic_key: 0x01020304,\n\t\tnode_addr_opt: Some(1.2.3.4:[1234]),\n\t\tearning_wallet: Wallet { kind: Address(0x546900db8d6e0937497133d1ae6fdf5f4b75bcd0) },\n\t\trate_pack: RatePack { routing_byte_rate: 1235, routing_service_rate: 1434, exit_byte_rate: 1237, exit_service_rate: 1634 },\n\t\tneighbors: [],\n\t\tversion: 2,\n\t\tmasquerader_kinds: [Tls, Http, Json],\n\t\texit_policy: [],\n\t\tcountry_code_opt: None,\n\t\tneighbor_latencies: {},\n\t\tkey_transition_opt: None,\n\t\tlink_encryption: true,\n\t},",
             "\n\tnode_addr_opt: Some(1.2.3.4:[1234]),",
             "\n\tsigned_data:
-Length: 229 (0xe5) bytes
-0000:   a7 6a 70 75  62 6c 69 63  5f 6b 65 79  44 01 02 03   .jpublic_keyD...
+Length: 279 (0x117) bytes
+0000:   a9 6a 70 75  62 6c 69 63  5f 6b 65 79  44 01 02 03   .jpublic_keyD...
 0010:   04 6e 65 61  72 6e 69 6e  67 5f 77 61  6c 6c 65 74   .nearning_wallet
 0020:   a1 67 61 64  64 72 65 73  73 94 18 54  18 69 00 18   .gaddress..T.i..
 0030:   db 18 8d 18  6e 09 18 37  18 49 18 71  18 33 18 d1   ....n..7.I.q.3..
@@ -650,11 +684,14 @@ Length: 229 (0xe5) bytes
 00b0:   6e 65 69 67  68 62 6f 72  73 80 73 61  63 63 65 70   neighbors.saccep
 00c0:   74 73 5f 63  6f 6e 6e 65  63 74 69 6f  6e 73 f5 6b   ts_connections.k
 00d0:   72 6f 75 74  65 73 5f 64  61 74 61 f5  67 76 65 72   routes_data.gver
-00e0:   73 69 6f 6e  02                                      sion.",
+00e0:   73 69 6f 6e  02 71 6d 61  73 71 75 65  72 61 64 65   sion.qmasquerade
+00f0:   72 5f 6b 69  6e 64 73 83  63 54 6c 73  64 48 74 74   r_kinds.cTlsdHtt
+0100:   70 64 4a 73  6f 6e 6f 6c  69 6e 6b 5f  65 6e 63 72   pdJsonolink_encr
+0110:   79 70 74 69  6f 6e f5                                yption.",
 	        "\n\tsignature:
 Length: 24 (0x18) bytes
-0000:   01 02 03 04  8a 7e b2 0e  c4 ea fe d8  ac 3c 89 2d   .....~.......<.-
-0010:   2d f1 e9 76  d1 76 db 67                             -..v.v.g"
+0000:   01 02 03 04  19 49 6f e2  1b 03 44 24  4b 62 67 90   .....Io...D$Kbg.
+0010:   60 d4 c2 25  c2 4c c3 b1                             `..%.L.."
         );
 
         assert_eq!(result, expected);
@@ -0,0 +1,111 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::sub_lib::cryptde::PublicKey;
+use crate::sub_lib::neighborhood::NeighborLatencyMessage;
+use actix::Recipient;
+use masq_lib::{as_any_ref_in_trait, as_any_ref_in_trait_impl};
+use std::net::{SocketAddr, TcpStream};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const LATENCY_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub trait LatencyProber {
+    fn probe(
+        &self,
+        neighbors: Vec<(PublicKey, SocketAddr)>,
+        recipient: Recipient<NeighborLatencyMessage>,
+    );
+    as_any_ref_in_trait!();
+}
+
+// A TCP handshake takes exactly one round trip, and every neighbor that accepts connections
+// is listening on its clandestine port anyway. Probing happens off the actor's thread, and each
+// result comes back to the Neighborhood as a NeighborLatencyMessage.
+pub struct LatencyProberReal {
+    timeout: Duration,
+}
+
+impl Default for LatencyProberReal {
+    fn default() -> Self {
+        Self {
+            timeout: LATENCY_PROBE_TIMEOUT,
+        }
+    }
+}
+
+impl LatencyProber for LatencyProberReal {
+    fn probe(
+        &self,
+        neighbors: Vec<(PublicKey, SocketAddr)>,
+        recipient: Recipient<NeighborLatencyMessage>,
+    ) {
+        let timeout = self.timeout;
+        thread::spawn(move || {
+            neighbors.into_iter().for_each(|(public_key, socket_addr)| {
+                let started = Instant::now();
+                let latency_opt = TcpStream::connect_timeout(&socket_addr, timeout)
+                    .ok()
+                    .map(|_| started.elapsed());
+                recipient
+                    .try_send(NeighborLatencyMessage {
+                        public_key,
+                        latency_opt,
+                    })
+                    .expect("Neighborhood is dead")
+            })
+        });
+    }
+    as_any_ref_in_trait_impl!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::recorder::make_recorder;
+    use actix::{Actor, System};
+    use masq_lib::utils::find_free_port;
+    use std::net::{IpAddr, Ipv4Addr, TcpListener};
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(LATENCY_PROBE_TIMEOUT, Duration::from_secs(5));
+    }
+
+    #[test]
+    fn probe_reports_latencies_of_reachable_neighbors_and_none_for_unreachable_ones() {
+        let localhost = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let listener = TcpListener::bind(SocketAddr::new(localhost, 0)).unwrap();
+        let reachable_addr = listener.local_addr().unwrap();
+        let unreachable_addr = SocketAddr::new(localhost, find_free_port());
+        let reachable_key = PublicKey::new(&[1, 2, 3, 4]);
+        let unreachable_key = PublicKey::new(&[2, 3, 4, 5]);
+        let (neighborhood, neighborhood_awaiter, neighborhood_recording_arc) = make_recorder();
+        let neighbors = vec![
+            (reachable_key.clone(), reachable_addr),
+            (unreachable_key.clone(), unreachable_addr),
+        ];
+        thread::spawn(move || {
+            let system = System::new("probe_reports_latencies");
+            let recipient = neighborhood.start().recipient();
+            let subject = LatencyProberReal::default();
+
+            subject.probe(neighbors, recipient);
+
+            system.run();
+        });
+
+        neighborhood_awaiter.await_message_count(2);
+        let recording = neighborhood_recording_arc.lock().unwrap();
+        let reachable = recording.get_record::<NeighborLatencyMessage>(0);
+        assert_eq!(reachable.public_key, reachable_key);
+        assert!(reachable.latency_opt.unwrap() < LATENCY_PROBE_TIMEOUT);
+        assert_eq!(
+            recording.get_record::<NeighborLatencyMessage>(1),
+            &NeighborLatencyMessage {
+                public_key: unreachable_key,
+                latency_opt: None
+            }
+        );
+    }
+}
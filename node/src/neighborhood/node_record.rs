@@ -1,9 +1,11 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+use crate::masquerader::MasqueraderKind;
 use crate::neighborhood::gossip::GossipNodeRecord;
 use crate::neighborhood::neighborhood_database::{NeighborhoodDatabase, NeighborhoodDatabaseError};
 use crate::neighborhood::{regenerate_signed_gossip, AccessibleGossipRecord};
 use crate::sub_lib::cryptde::{CryptDE, CryptData, PlainData, PublicKey};
+use crate::sub_lib::exit_policy::ExitPolicy;
 use crate::sub_lib::neighborhood::{NodeDescriptor, RatePack};
 use crate::sub_lib::node_addr::NodeAddr;
 use crate::sub_lib::utils::time_t_timestamp;
@@ -11,6 +13,7 @@ use crate::sub_lib::wallet::Wallet;
 use masq_lib::blockchains::chains::Chain;
 use serde_derive::{Deserialize, Serialize};
 use std::collections::btree_set::BTreeSet;
+use std::collections::BTreeMap;
 use std::collections::HashSet;
 use std::convert::TryFrom;
 
@@ -24,6 +27,60 @@ pub struct NodeRecordInner_0v1 {
     pub accepts_connections: bool,
     pub routes_data: bool,
     pub version: u32,
+    // Records from Nodes that predate masquerader negotiation have none; they speak only JSON.
+    #[serde(
+        default,
+        deserialize_with = "MasqueraderKind::deserialize_known",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub masquerader_kinds: Vec<MasqueraderKind>,
+    // Most exits refuse nothing, so an empty policy isn't gossiped at all.
+    #[serde(default, skip_serializing_if = "ExitPolicy::is_empty")]
+    pub exit_policy: ExitPolicy,
+    // Self-declared by the operator and unverified; consumers use it to choose exits by location.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country_code_opt: Option<String>,
+    // Round-trip times in milliseconds this Node has measured to its full neighbors. Nodes that
+    // don't probe, or haven't yet, leave it empty and it isn't gossiped.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub neighbor_latencies: BTreeMap<PublicKey, u32>,
+    // Present only after the operator has rotated this Node's keys; the new key goes into
+    // service when the Node next restarts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_transition_opt: Option<KeyTransition>,
+    // Nodes that predate link encryption don't gossip this; their neighbors must send them plain
+    // masked CORES packages instead of link frames.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub link_encryption: bool,
+}
+
+// The record carrying a KeyTransition is signed by the old key. The signature here is made with
+// the new key over the old public key, so nobody can claim a key that isn't theirs.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct KeyTransition {
+    pub new_public_key: PublicKey,
+    pub signature: CryptData,
+}
+
+impl KeyTransition {
+    pub fn new(old_public_key: &PublicKey, new_cryptde: &dyn CryptDE) -> Self {
+        let signature = new_cryptde
+            .sign(&PlainData::new(old_public_key.as_slice()))
+            .expect("Couldn't sign key transition");
+        Self {
+            new_public_key: new_cryptde.public_key().clone(),
+            signature,
+        }
+    }
+
+    pub fn is_valid(&self, old_public_key: &PublicKey, cryptde: &dyn CryptDE) -> bool {
+        &self.new_public_key != old_public_key
+            && cryptde.verify_signature(
+                &PlainData::new(old_public_key.as_slice()),
+                &self.signature,
+                &self.new_public_key,
+            )
+    }
 }
 
 impl TryFrom<GossipNodeRecord> for NodeRecordInner_0v1 {
@@ -78,6 +135,12 @@ impl NodeRecord {
                 routes_data,
                 neighbors: BTreeSet::new(),
                 version,
+                masquerader_kinds: MasqueraderKind::supported(false),
+                exit_policy: ExitPolicy::default(),
+                country_code_opt: None,
+                neighbor_latencies: BTreeMap::new(),
+                key_transition_opt: None,
+                link_encryption: true,
             },
             signed_gossip: PlainData::new(&[]),
             signature: CryptData::new(&[]),
@@ -244,6 +307,30 @@ impl NodeRecord {
         &self.inner.rate_pack
     }
 
+    pub fn masquerader_kinds(&self) -> &[MasqueraderKind] {
+        &self.inner.masquerader_kinds
+    }
+
+    pub fn exit_policy(&self) -> &ExitPolicy {
+        &self.inner.exit_policy
+    }
+
+    pub fn country_code_opt(&self) -> Option<&str> {
+        self.inner.country_code_opt.as_deref()
+    }
+
+    pub fn neighbor_latency_millis(&self, neighbor_key: &PublicKey) -> Option<u32> {
+        self.inner.neighbor_latencies.get(neighbor_key).copied()
+    }
+
+    pub fn key_transition_opt(&self) -> Option<&KeyTransition> {
+        self.inner.key_transition_opt.as_ref()
+    }
+
+    pub fn link_encryption(&self) -> bool {
+        self.inner.link_encryption
+    }
+
     pub fn update(&mut self, agr: AccessibleGossipRecord) -> Result<(), String> {
         if &agr.inner.public_key != self.public_key() {
             return Err(format!(
@@ -321,6 +408,8 @@ pub struct NodeRecordMetadata {
     pub last_update: u32,
     pub node_addr_opt: Option<NodeAddr>,
     pub unreachable_hosts: HashSet<String>,
+    // Measured by this Node, and only for its own full neighbors
+    pub latency_millis_opt: Option<u32>,
 }
 
 impl NodeRecordMetadata {
@@ -329,6 +418,7 @@ impl NodeRecordMetadata {
             last_update: time_t_timestamp(),
             node_addr_opt: None,
             unreachable_hosts: Default::default(),
+            latency_millis_opt: None,
         }
     }
 }
@@ -343,6 +433,7 @@ mod tests {
     use crate::test_utils::neighborhood_test_utils::{db_from_node, make_node_record};
     use crate::test_utils::{assert_contains, main_cryptde, rate_pack};
     use masq_lib::test_utils::utils::TEST_DEFAULT_CHAIN;
+    use masq_lib::utils::index_of;
     use std::net::IpAddr;
     use std::str::FromStr;
 
@@ -949,4 +1040,220 @@ mod tests {
         let final_serialized = serde_cbor::ser::to_vec(&subject.inner).unwrap();
         assert_eq!(&final_serialized[..], final_signed_gossip.as_slice());
     }
+
+    #[test]
+    fn new_node_record_advertises_every_supported_masquerader_kind() {
+        let subject = make_node_record(1234, true);
+
+        assert_eq!(
+            subject.masquerader_kinds(),
+            MasqueraderKind::supported(false)
+        );
+    }
+
+    #[test]
+    fn new_node_record_advertises_link_encryption() {
+        let subject = make_node_record(1234, true);
+
+        assert_eq!(subject.link_encryption(), true);
+    }
+
+    #[test]
+    fn inner_from_node_that_predates_masquerader_negotiation_has_no_masquerader_kinds() {
+        #[derive(Serialize)]
+        struct OldNodeRecordInner {
+            pub public_key: PublicKey,
+            pub earning_wallet: Wallet,
+            pub rate_pack: RatePack,
+            pub neighbors: BTreeSet<PublicKey>,
+            pub accepts_connections: bool,
+            pub routes_data: bool,
+            pub version: u32,
+        }
+        let old_inner = OldNodeRecordInner {
+            public_key: PublicKey::new(&[1, 2, 3, 4]),
+            earning_wallet: make_wallet("earning"),
+            rate_pack: rate_pack(100),
+            neighbors: BTreeSet::new(),
+            accepts_connections: true,
+            routes_data: true,
+            version: 3,
+        };
+        let serialized = serde_cbor::ser::to_vec(&old_inner).unwrap();
+
+        let result = serde_cbor::de::from_slice::<NodeRecordInner_0v1>(&serialized).unwrap();
+
+        assert_eq!(result.public_key, old_inner.public_key);
+        assert_eq!(result.version, 3);
+        assert_eq!(result.masquerader_kinds, vec![]);
+        assert_eq!(result.exit_policy, ExitPolicy::default());
+        assert_eq!(result.country_code_opt, None);
+        assert_eq!(result.neighbor_latencies, BTreeMap::new());
+        assert_eq!(result.link_encryption, false);
+    }
+
+    #[test]
+    fn inner_from_newer_node_decodes_with_unknown_masquerader_kinds_skipped() {
+        #[derive(Serialize)]
+        struct NewerNodeRecordInner {
+            pub public_key: PublicKey,
+            pub earning_wallet: Wallet,
+            pub rate_pack: RatePack,
+            pub neighbors: BTreeSet<PublicKey>,
+            pub accepts_connections: bool,
+            pub routes_data: bool,
+            pub version: u32,
+            pub masquerader_kinds: Vec<String>,
+        }
+        let newer_inner = NewerNodeRecordInner {
+            public_key: PublicKey::new(&[1, 2, 3, 4]),
+            earning_wallet: make_wallet("earning"),
+            rate_pack: rate_pack(100),
+            neighbors: BTreeSet::new(),
+            accepts_connections: true,
+            routes_data: true,
+            version: 3,
+            masquerader_kinds: vec!["Quic".to_string(), "Tls".to_string(), "Json".to_string()],
+        };
+        let serialized = serde_cbor::ser::to_vec(&newer_inner).unwrap();
+
+        let result = serde_cbor::de::from_slice::<NodeRecordInner_0v1>(&serialized).unwrap();
+
+        assert_eq!(result.public_key, newer_inner.public_key);
+        assert_eq!(
+            result.masquerader_kinds,
+            vec![MasqueraderKind::Tls, MasqueraderKind::Json]
+        );
+    }
+
+    #[test]
+    fn inner_without_masquerader_kinds_serializes_as_a_node_that_predates_them() {
+        #[derive(Serialize)]
+        struct OldNodeRecordInner {
+            pub public_key: PublicKey,
+            pub earning_wallet: Wallet,
+            pub rate_pack: RatePack,
+            pub neighbors: BTreeSet<PublicKey>,
+            pub accepts_connections: bool,
+            pub routes_data: bool,
+            pub version: u32,
+        }
+        let mut subject = make_node_record(1234, true).inner;
+        subject.masquerader_kinds = vec![];
+        subject.exit_policy = ExitPolicy::default();
+        subject.country_code_opt = None;
+        subject.neighbor_latencies = BTreeMap::new();
+        subject.key_transition_opt = None;
+        subject.link_encryption = false;
+        let old_inner = OldNodeRecordInner {
+            public_key: subject.public_key.clone(),
+            earning_wallet: subject.earning_wallet.clone(),
+            rate_pack: subject.rate_pack.clone(),
+            neighbors: subject.neighbors.clone(),
+            accepts_connections: subject.accepts_connections,
+            routes_data: subject.routes_data,
+            version: subject.version,
+        };
+
+        let result = serde_cbor::ser::to_vec(&subject).unwrap();
+
+        assert_eq!(result, serde_cbor::ser::to_vec(&old_inner).unwrap());
+    }
+
+    #[test]
+    fn exit_policy_is_gossiped_only_if_it_refuses_something() {
+        let mut subject = make_node_record(1234, true);
+        let empty_serialized = serde_cbor::ser::to_vec(&subject.inner).unwrap();
+        subject.inner.exit_policy = ExitPolicy::from_str("25,booga.com").unwrap();
+
+        let serialized = serde_cbor::ser::to_vec(&subject.inner).unwrap();
+
+        assert_eq!(index_of(&empty_serialized, b"exit_policy"), None);
+        assert!(index_of(&serialized, b"exit_policy").is_some());
+        let result = serde_cbor::de::from_slice::<NodeRecordInner_0v1>(&serialized).unwrap();
+        assert_eq!(
+            result.exit_policy,
+            ExitPolicy::from_str("25,booga.com").unwrap()
+        );
+        assert_eq!(subject.exit_policy(), &result.exit_policy);
+    }
+
+    #[test]
+    fn country_code_is_gossiped_only_if_declared() {
+        let mut subject = make_node_record(1234, true);
+        let undeclared_serialized = serde_cbor::ser::to_vec(&subject.inner).unwrap();
+        subject.inner.country_code_opt = Some("CZ".to_string());
+
+        let serialized = serde_cbor::ser::to_vec(&subject.inner).unwrap();
+
+        assert_eq!(index_of(&undeclared_serialized, b"country_code_opt"), None);
+        let result = serde_cbor::de::from_slice::<NodeRecordInner_0v1>(&serialized).unwrap();
+        assert_eq!(result.country_code_opt, Some("CZ".to_string()));
+        assert_eq!(subject.country_code_opt(), Some("CZ"));
+    }
+
+    #[test]
+    fn neighbor_latencies_are_gossiped_only_if_measured() {
+        let mut subject = make_node_record(1234, true);
+        let unmeasured_serialized = serde_cbor::ser::to_vec(&subject.inner).unwrap();
+        let neighbor_key = PublicKey::new(&[2, 3, 4, 5]);
+        subject
+            .inner
+            .neighbor_latencies
+            .insert(neighbor_key.clone(), 42);
+
+        let serialized = serde_cbor::ser::to_vec(&subject.inner).unwrap();
+
+        assert_eq!(
+            index_of(&unmeasured_serialized, b"neighbor_latencies"),
+            None
+        );
+        let result = serde_cbor::de::from_slice::<NodeRecordInner_0v1>(&serialized).unwrap();
+        assert_eq!(result.neighbor_latencies, subject.inner.neighbor_latencies);
+        assert_eq!(subject.neighbor_latency_millis(&neighbor_key), Some(42));
+        assert_eq!(
+            subject.neighbor_latency_millis(&PublicKey::new(&[3, 4, 5, 6])),
+            None
+        );
+    }
+
+    #[test]
+    fn key_transition_is_gossiped_only_if_keys_have_been_rotated() {
+        let mut subject = make_node_record(1234, true);
+        let unrotated_serialized = serde_cbor::ser::to_vec(&subject.inner).unwrap();
+        let new_cryptde = CryptDENull::new(TEST_DEFAULT_CHAIN);
+        let key_transition = KeyTransition::new(subject.public_key(), &new_cryptde);
+        subject.inner.key_transition_opt = Some(key_transition.clone());
+
+        let serialized = serde_cbor::ser::to_vec(&subject.inner).unwrap();
+
+        assert_eq!(index_of(&unrotated_serialized, b"key_transition_opt"), None);
+        let result = serde_cbor::de::from_slice::<NodeRecordInner_0v1>(&serialized).unwrap();
+        assert_eq!(result.key_transition_opt, Some(key_transition.clone()));
+        assert_eq!(subject.key_transition_opt(), Some(&key_transition));
+    }
+
+    #[test]
+    fn key_transition_is_valid_only_for_the_old_key_it_was_made_for() {
+        let old_public_key = PublicKey::new(&[1, 2, 3, 4]);
+        let new_cryptde = CryptDENull::new(TEST_DEFAULT_CHAIN);
+        let subject = KeyTransition::new(&old_public_key, &new_cryptde);
+        let forged = KeyTransition {
+            new_public_key: PublicKey::new(&[5, 6, 7, 8]),
+            signature: subject.signature.clone(),
+        };
+        let to_itself = KeyTransition::new(new_cryptde.public_key(), &new_cryptde);
+
+        assert_eq!(subject.new_public_key, new_cryptde.public_key().clone());
+        assert_eq!(subject.is_valid(&old_public_key, main_cryptde()), true);
+        assert_eq!(
+            subject.is_valid(&PublicKey::new(&[2, 3, 4, 5]), main_cryptde()),
+            false
+        );
+        assert_eq!(forged.is_valid(&old_public_key, main_cryptde()), false);
+        assert_eq!(
+            to_itself.is_valid(new_cryptde.public_key(), main_cryptde()),
+            false
+        );
+    }
 }
@@ -0,0 +1,219 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::accountant::db_access_objects::utils::VigilantRusqliteFlatten;
+use crate::database::rusqlite_wrappers::ConnectionWrapper;
+use crate::neighborhood::gossip::GossipNodeRecord;
+use crate::sub_lib::cryptde::{CryptData, PlainData, PublicKey};
+use crate::sub_lib::node_addr::NodeAddr;
+use rusqlite::ToSql;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NodeRecordDaoError {
+    DatabaseError(String),
+}
+
+// A NodeRecord as it travels in Gossip: the signed data and the signature are kept exactly as
+// the Node that owns them produced them, so they can be re-verified when they're loaded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PersistedNodeRecord {
+    pub public_key: PublicKey,
+    pub gossip_node_record: GossipNodeRecord,
+    pub last_update: u32,
+}
+
+pub trait NodeRecordDao: Send {
+    fn node_records(&self) -> Vec<PersistedNodeRecord>;
+    // Writes only what differs from what's stored: changed records are inserted or overwritten,
+    // and records of Nodes that are gone are deleted.
+    fn update_node_records(
+        &mut self,
+        changed: &[PersistedNodeRecord],
+        removed: &[PublicKey],
+    ) -> Result<(), NodeRecordDaoError>;
+}
+
+pub struct NodeRecordDaoReal {
+    conn: Box<dyn ConnectionWrapper>,
+}
+
+impl NodeRecordDaoReal {
+    pub fn new(conn: Box<dyn ConnectionWrapper>) -> Self {
+        Self { conn }
+    }
+}
+
+impl NodeRecordDao for NodeRecordDaoReal {
+    fn node_records(&self) -> Vec<PersistedNodeRecord> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "select public_key, signed_data, signature, node_addr, last_update from node_records",
+            )
+            .expect("Failed to prepare a statement");
+        stmt.query_map([], |row| {
+            let public_key: Vec<u8> = row.get(0)?;
+            let signed_data: Vec<u8> = row.get(1)?;
+            let signature: Vec<u8> = row.get(2)?;
+            let node_addr_opt: Option<String> = row.get(3)?;
+            let last_update: u32 = row.get(4)?;
+            Ok(PersistedNodeRecord {
+                public_key: PublicKey::new(&public_key),
+                gossip_node_record: GossipNodeRecord {
+                    signed_data: PlainData::new(&signed_data),
+                    signature: CryptData::new(&signature),
+                    node_addr_opt: node_addr_opt.map(|node_addr| {
+                        NodeAddr::from_str(&node_addr)
+                            .unwrap_or_else(|e| panic!("Node record database corrupt: {}", e))
+                    }),
+                },
+                last_update,
+            })
+        })
+        .expect("Couldn't retrieve persisted Node records: database corrupt")
+        .vigilant_flatten()
+        .collect()
+    }
+
+    fn update_node_records(
+        &mut self,
+        changed: &[PersistedNodeRecord],
+        removed: &[PublicKey],
+    ) -> Result<(), NodeRecordDaoError> {
+        let txn = self.conn.transaction()?;
+        for public_key in removed {
+            let params: &[&dyn ToSql] = &[&public_key.as_slice()];
+            txn.execute("delete from node_records where public_key = ?", params)?;
+        }
+        for node_record in changed {
+            let node_addr_opt = node_record
+                .gossip_node_record
+                .node_addr_opt
+                .as_ref()
+                .map(|node_addr| node_addr.to_string());
+            let params: &[&dyn ToSql] = &[
+                &node_record.public_key.as_slice(),
+                &node_record.gossip_node_record.signed_data.as_slice(),
+                &node_record.gossip_node_record.signature.as_slice(),
+                &node_addr_opt,
+                &node_record.last_update,
+            ];
+            txn.execute(
+                "insert or replace into node_records (public_key, signed_data, signature, node_addr, last_update) \
+                 values (?, ?, ?, ?, ?)",
+                params,
+            )?;
+        }
+        Ok(txn.commit()?)
+    }
+}
+
+impl From<rusqlite::Error> for NodeRecordDaoError {
+    fn from(e: rusqlite::Error) -> Self {
+        NodeRecordDaoError::DatabaseError(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::db_initializer::{
+        DbInitializationConfig, DbInitializer, DbInitializerReal,
+    };
+    use crate::test_utils::neighborhood_test_utils::make_node_record;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+
+    fn make_persisted_node_record(n: u16, has_ip: bool, last_update: u32) -> PersistedNodeRecord {
+        let node_record = make_node_record(n, has_ip);
+        PersistedNodeRecord {
+            public_key: node_record.public_key().clone(),
+            gossip_node_record: GossipNodeRecord::from(node_record),
+            last_update,
+        }
+    }
+
+    #[test]
+    fn node_records_are_empty_in_a_new_database() {
+        let home_dir = ensure_node_home_directory_exists(
+            "node_record_dao",
+            "node_records_are_empty_in_a_new_database",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let subject = NodeRecordDaoReal::new(conn);
+
+        let result = subject.node_records();
+
+        assert_eq!(result, vec![]);
+    }
+
+    #[test]
+    fn update_node_records_overwrites_changed_records_and_deletes_removed_ones() {
+        let home_dir = ensure_node_home_directory_exists(
+            "node_record_dao",
+            "update_node_records_overwrites_changed_records_and_deletes_removed_ones",
+        );
+        let db_initializer = DbInitializerReal::default();
+        let conn = db_initializer
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let mut subject = NodeRecordDaoReal::new(conn);
+        let removed_record = make_persisted_node_record(1234, true, 1_000_000);
+        let untouched_record = make_persisted_node_record(2345, true, 2_000_000);
+        let old_changed_record = make_persisted_node_record(3456, true, 3_000_000);
+        subject
+            .update_node_records(
+                &[
+                    removed_record.clone(),
+                    untouched_record.clone(),
+                    old_changed_record,
+                ],
+                &[],
+            )
+            .unwrap();
+        let new_changed_record = make_persisted_node_record(3456, false, 4_000_000);
+        let added_record = make_persisted_node_record(4567, true, 5_000_000);
+
+        let result = subject.update_node_records(
+            &[new_changed_record.clone(), added_record.clone()],
+            &[removed_record.public_key],
+        );
+
+        assert_eq!(result, Ok(()));
+        let conn = db_initializer
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let mut actual = NodeRecordDaoReal::new(conn).node_records();
+        actual.sort_by(|a, b| a.last_update.cmp(&b.last_update));
+        assert_eq!(
+            actual,
+            vec![untouched_record, new_changed_record, added_record]
+        );
+    }
+
+    #[test]
+    fn update_node_records_reports_database_errors() {
+        let home_dir = ensure_node_home_directory_exists(
+            "node_record_dao",
+            "update_node_records_reports_database_errors",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        conn.prepare("drop table node_records")
+            .unwrap()
+            .execute([])
+            .unwrap();
+        let mut subject = NodeRecordDaoReal::new(conn);
+
+        let result = subject.update_node_records(&[make_persisted_node_record(1234, true, 0)], &[]);
+
+        assert_eq!(
+            result,
+            Err(NodeRecordDaoError::DatabaseError(
+                "no such table: node_records".to_string()
+            ))
+        );
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+pub const REPUTATION_HALF_LIFE_SECS: u32 = 21_600; // a failure weighs half as much after six hours
+pub const FORGOTTEN_PENALTY: u64 = 10; // decayed below this, a failure record is dropped
+pub const REPUTATION_UNDESIRABILITY_PER_POINT: i64 = 1_000_000_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RelayFailure {
+    ConnectionFailed,
+    StreamDropped,
+    DnsResolveFailure,
+    ServiceRefused,
+}
+
+impl RelayFailure {
+    // A connection refused while we're routing through a Node is its fault for sure; a dropped
+    // stream may have been our network's fault, and a DNS failure may have been the target's.
+    // A Node that refuses us for delinquency will go on refusing until it's paid.
+    pub fn penalty(&self) -> u64 {
+        match self {
+            RelayFailure::ConnectionFailed => 1_000,
+            RelayFailure::StreamDropped => 500,
+            RelayFailure::DnsResolveFailure => 250,
+            RelayFailure::ServiceRefused => 4_000,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NodeReputation {
+    pub penalty: u64, // as of last_failure; decays from there
+    pub last_failure: u32,
+    pub connection_failures: u32,
+    pub stream_drops: u32,
+    pub dns_failures: u32,
+    pub service_refusals: u32,
+}
+
+impl NodeReputation {
+    pub fn record_failure(&mut self, failure: RelayFailure, now: u32) {
+        self.penalty = self.decayed_penalty(now).saturating_add(failure.penalty());
+        self.last_failure = now;
+        let counter = match failure {
+            RelayFailure::ConnectionFailed => &mut self.connection_failures,
+            RelayFailure::StreamDropped => &mut self.stream_drops,
+            RelayFailure::DnsResolveFailure => &mut self.dns_failures,
+            RelayFailure::ServiceRefused => &mut self.service_refusals,
+        };
+        *counter = counter.saturating_add(1);
+    }
+
+    pub fn decayed_penalty(&self, now: u32) -> u64 {
+        let elapsed = now.saturating_sub(self.last_failure);
+        let decay = 0.5_f64.powf(elapsed as f64 / REPUTATION_HALF_LIFE_SECS as f64);
+        (self.penalty as f64 * decay).round() as u64
+    }
+
+    pub fn is_forgotten(&self, now: u32) -> bool {
+        self.decayed_penalty(now) < FORGOTTEN_PENALTY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(REPUTATION_HALF_LIFE_SECS, 21_600);
+        assert_eq!(FORGOTTEN_PENALTY, 10);
+        assert_eq!(REPUTATION_UNDESIRABILITY_PER_POINT, 1_000_000_000);
+        assert_eq!(RelayFailure::ConnectionFailed.penalty(), 1_000);
+        assert_eq!(RelayFailure::StreamDropped.penalty(), 500);
+        assert_eq!(RelayFailure::DnsResolveFailure.penalty(), 250);
+        assert_eq!(RelayFailure::ServiceRefused.penalty(), 4_000);
+    }
+
+    #[test]
+    fn failures_accumulate_and_are_counted_by_kind() {
+        let mut subject = NodeReputation::default();
+
+        subject.record_failure(RelayFailure::ConnectionFailed, 1_000_000);
+        subject.record_failure(RelayFailure::StreamDropped, 1_000_000);
+        subject.record_failure(RelayFailure::DnsResolveFailure, 1_000_000);
+        subject.record_failure(RelayFailure::ConnectionFailed, 1_000_000);
+        subject.record_failure(RelayFailure::ServiceRefused, 1_000_000);
+
+        assert_eq!(
+            subject,
+            NodeReputation {
+                penalty: 6_750,
+                last_failure: 1_000_000,
+                connection_failures: 2,
+                stream_drops: 1,
+                dns_failures: 1,
+                service_refusals: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn penalty_halves_every_half_life_until_it_is_forgotten() {
+        let subject = NodeReputation {
+            penalty: 1_000,
+            last_failure: 1_000_000,
+            ..NodeReputation::default()
+        };
+
+        let half_lives = |n: u32| 1_000_000 + n * REPUTATION_HALF_LIFE_SECS;
+
+        assert_eq!(subject.decayed_penalty(1_000_000), 1_000);
+        assert_eq!(subject.decayed_penalty(half_lives(1)), 500);
+        assert_eq!(subject.decayed_penalty(half_lives(2)), 250);
+        assert_eq!(subject.is_forgotten(half_lives(6)), false); // 16
+        assert_eq!(subject.is_forgotten(half_lives(7)), true); // 8
+        assert_eq!(subject.decayed_penalty(999_999), 1_000); // clock went backward
+    }
+
+    #[test]
+    fn new_failure_adds_to_decayed_penalty() {
+        let mut subject = NodeReputation::default();
+        subject.record_failure(RelayFailure::ConnectionFailed, 1_000_000);
+
+        subject.record_failure(
+            RelayFailure::StreamDropped,
+            1_000_000 + REPUTATION_HALF_LIFE_SECS,
+        );
+
+        assert_eq!(subject.penalty, 1_000);
+        assert_eq!(subject.last_failure, 1_000_000 + REPUTATION_HALF_LIFE_SECS);
+    }
+}
@@ -0,0 +1,203 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::accountant::db_access_objects::utils::VigilantRusqliteFlatten;
+use crate::database::rusqlite_wrappers::ConnectionWrapper;
+use crate::neighborhood::reputation::NodeReputation;
+use crate::sub_lib::cryptde::PublicKey;
+use rusqlite::ToSql;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReputationDaoError {
+    DatabaseError(String),
+}
+
+pub trait ReputationDao: Send {
+    fn reputations(&self) -> Vec<(PublicKey, NodeReputation)>;
+    fn save_reputation(
+        &mut self,
+        public_key: &PublicKey,
+        reputation: &NodeReputation,
+    ) -> Result<(), ReputationDaoError>;
+    fn forget_reputation(&mut self, public_key: &PublicKey) -> Result<(), ReputationDaoError>;
+}
+
+pub struct ReputationDaoReal {
+    conn: Box<dyn ConnectionWrapper>,
+}
+
+impl ReputationDaoReal {
+    pub fn new(conn: Box<dyn ConnectionWrapper>) -> Self {
+        Self { conn }
+    }
+}
+
+impl ReputationDao for ReputationDaoReal {
+    fn reputations(&self) -> Vec<(PublicKey, NodeReputation)> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "select public_key, penalty, last_failure, connection_failures, stream_drops, \
+                 dns_failures, service_refusals from node_reputations",
+            )
+            .expect("Failed to prepare a statement");
+        stmt.query_map([], |row| {
+            let public_key: Vec<u8> = row.get(0)?;
+            let penalty: i64 = row.get(1)?;
+            Ok((
+                PublicKey::new(&public_key),
+                NodeReputation {
+                    penalty: penalty as u64,
+                    last_failure: row.get(2)?,
+                    connection_failures: row.get(3)?,
+                    stream_drops: row.get(4)?,
+                    dns_failures: row.get(5)?,
+                    service_refusals: row.get(6)?,
+                },
+            ))
+        })
+        .expect("Couldn't retrieve Node reputations: database corrupt")
+        .vigilant_flatten()
+        .collect()
+    }
+
+    fn save_reputation(
+        &mut self,
+        public_key: &PublicKey,
+        reputation: &NodeReputation,
+    ) -> Result<(), ReputationDaoError> {
+        let penalty = i64::try_from(reputation.penalty).unwrap_or(i64::MAX);
+        let params: &[&dyn ToSql] = &[
+            &public_key.as_slice(),
+            &penalty,
+            &reputation.last_failure,
+            &reputation.connection_failures,
+            &reputation.stream_drops,
+            &reputation.dns_failures,
+            &reputation.service_refusals,
+        ];
+        let mut stmt = self.conn.prepare(
+            "insert or replace into node_reputations (public_key, penalty, last_failure, \
+             connection_failures, stream_drops, dns_failures, service_refusals) \
+             values (?, ?, ?, ?, ?, ?, ?)",
+        )?;
+        stmt.execute(params)?;
+        Ok(())
+    }
+
+    fn forget_reputation(&mut self, public_key: &PublicKey) -> Result<(), ReputationDaoError> {
+        let mut stmt = self
+            .conn
+            .prepare("delete from node_reputations where public_key = ?")?;
+        stmt.execute([&public_key.as_slice() as &dyn ToSql])?;
+        Ok(())
+    }
+}
+
+impl From<rusqlite::Error> for ReputationDaoError {
+    fn from(e: rusqlite::Error) -> Self {
+        ReputationDaoError::DatabaseError(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::db_initializer::{
+        DbInitializationConfig, DbInitializer, DbInitializerReal,
+    };
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+
+    fn make_reputation(n: u32) -> NodeReputation {
+        NodeReputation {
+            penalty: n as u64 * 1000,
+            last_failure: n * 1_000_000,
+            connection_failures: n,
+            stream_drops: n + 1,
+            dns_failures: n + 2,
+            service_refusals: n + 3,
+        }
+    }
+
+    #[test]
+    fn reputations_are_empty_in_a_new_database() {
+        let home_dir = ensure_node_home_directory_exists(
+            "reputation_dao",
+            "reputations_are_empty_in_a_new_database",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let subject = ReputationDaoReal::new(conn);
+
+        let result = subject.reputations();
+
+        assert_eq!(result, vec![]);
+    }
+
+    #[test]
+    fn save_reputation_inserts_and_replaces_and_forget_reputation_deletes() {
+        let home_dir = ensure_node_home_directory_exists(
+            "reputation_dao",
+            "save_reputation_inserts_and_replaces_and_forget_reputation_deletes",
+        );
+        let db_initializer = DbInitializerReal::default();
+        let conn = db_initializer
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let mut subject = ReputationDaoReal::new(conn);
+        let replaced_key = PublicKey::new(&[1, 2, 3, 4]);
+        let forgotten_key = PublicKey::new(&[2, 3, 4, 5]);
+        let kept_key = PublicKey::new(&[3, 4, 5, 6]);
+        subject
+            .save_reputation(&replaced_key, &make_reputation(1))
+            .unwrap();
+        subject
+            .save_reputation(&forgotten_key, &make_reputation(2))
+            .unwrap();
+
+        let replace_result = subject.save_reputation(&replaced_key, &make_reputation(3));
+        let insert_result = subject.save_reputation(&kept_key, &make_reputation(4));
+        let forget_result = subject.forget_reputation(&forgotten_key);
+
+        assert_eq!(replace_result, Ok(()));
+        assert_eq!(insert_result, Ok(()));
+        assert_eq!(forget_result, Ok(()));
+        let conn = db_initializer
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let mut actual = ReputationDaoReal::new(conn).reputations();
+        actual.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            actual,
+            vec![
+                (replaced_key, make_reputation(3)),
+                (kept_key, make_reputation(4))
+            ]
+        );
+    }
+
+    #[test]
+    fn save_reputation_reports_database_errors() {
+        let home_dir = ensure_node_home_directory_exists(
+            "reputation_dao",
+            "save_reputation_reports_database_errors",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        conn.prepare("drop table node_reputations")
+            .unwrap()
+            .execute([])
+            .unwrap();
+        let mut subject = ReputationDaoReal::new(conn);
+
+        let result = subject.save_reputation(&PublicKey::new(&[1, 2, 3, 4]), &make_reputation(1));
+
+        assert_eq!(
+            result,
+            Err(ReputationDaoError::DatabaseError(
+                "no such table: node_reputations".to_string()
+            ))
+        );
+    }
+}
@@ -11,7 +11,8 @@ use masq_lib::messages::{
     UiConfigurationResponse, UiGenerateSeedSpec, UiGenerateWalletsRequest,
     UiGenerateWalletsResponse, UiNewPasswordBroadcast, UiPaymentThresholds, UiRatePack,
     UiRecoverWalletsRequest, UiRecoverWalletsResponse, UiScanIntervals, UiSetConfigurationRequest,
-    UiSetConfigurationResponse, UiWalletAddressesRequest, UiWalletAddressesResponse,
+    UiSetConfigurationResponse, UiTransactionFees, UiWalletAddressesRequest,
+    UiWalletAddressesResponse,
 };
 use masq_lib::ui_gateway::MessageTarget::ClientId;
 use masq_lib::ui_gateway::{
@@ -26,7 +27,8 @@ use crate::db_config::config_dao::ConfigDaoReal;
 use crate::db_config::persistent_configuration::{
     PersistentConfigError, PersistentConfiguration, PersistentConfigurationReal,
 };
-use crate::sub_lib::neighborhood::{ConfigChange, ConfigChangeMsg, Hops, WalletPair};
+use crate::sub_lib::blockchain_bridge::TransactionFees;
+use crate::sub_lib::neighborhood::{ConfigChange, ConfigChangeMsg, Hops, RatePack, WalletPair};
 use crate::sub_lib::peer_actors::{BindMessage, ConfigChangeSubs};
 use crate::sub_lib::utils::{db_connection_launch_panic, handle_ui_crash_request};
 use crate::sub_lib::wallet::Wallet;
@@ -610,6 +612,9 @@ impl Configurator {
             Self::value_required(persistent_config.scan_intervals(), "scanIntervals")?;
         let payment_thresholds =
             Self::value_required(persistent_config.payment_thresholds(), "paymentThresholds")?;
+        let transaction_fees_opt =
+            Self::value_not_required(persistent_config.transaction_fees(), "transactionFeesOpt")?
+                .map(Self::ui_transaction_fees);
         let routing_byte_rate = rate_pack.routing_byte_rate;
         let routing_service_rate = rate_pack.routing_service_rate;
         let exit_byte_rate = rate_pack.exit_byte_rate;
@@ -656,10 +661,28 @@ impl Configurator {
                 payable_sec,
                 receivable_sec,
             },
+            transaction_fees_opt,
         };
         Ok(response.tmb(context_id))
     }
 
+    fn ui_transaction_fees(transaction_fees: TransactionFees) -> UiTransactionFees {
+        let to_u64 = |wei: u128| u64::try_from(wei).unwrap_or(u64::MAX);
+        match transaction_fees {
+            TransactionFees::Legacy { gas_price_wei } => UiTransactionFees {
+                max_fee_per_gas_wei: to_u64(gas_price_wei),
+                max_priority_fee_per_gas_wei_opt: None,
+            },
+            TransactionFees::Eip1559 {
+                max_fee_per_gas_wei,
+                max_priority_fee_per_gas_wei,
+            } => UiTransactionFees {
+                max_fee_per_gas_wei: to_u64(max_fee_per_gas_wei),
+                max_priority_fee_per_gas_wei_opt: Some(to_u64(max_priority_fee_per_gas_wei)),
+            },
+        }
+    }
+
     fn value_required<T>(
         result: Result<T, PersistentConfigError>,
         field_name: &str,
@@ -735,6 +758,7 @@ impl Configurator {
         match password {
             None => match msg.name.as_str() {
                 "gas-price" => self.set_gas_price(msg.value)?,
+                "max-rate-pack" => self.set_max_rate_pack(msg.value)?,
                 "min-hops" => self.set_min_hops(msg.value)?,
                 "start-block" => self.set_start_block(msg.value)?,
                 _ => {
@@ -763,6 +787,34 @@ impl Configurator {
         }
     }
 
+    fn set_max_rate_pack(&mut self, max_rate_pack_value: String) -> Result<(), (u64, String)> {
+        let max_rate_pack_opt = if "none".eq_ignore_ascii_case(&max_rate_pack_value) {
+            None
+        } else {
+            match RatePack::try_from(max_rate_pack_value.as_str()) {
+                Ok(rate_pack) => Some(rate_pack),
+                Err(e) => return Err((NON_PARSABLE_VALUE, format!("max rate pack: {}", e))),
+            }
+        };
+        match self.persistent_config.set_max_rate_pack(max_rate_pack_opt) {
+            Ok(_) => {
+                debug!(
+                    self.logger,
+                    "The value of max-rate-pack has been changed to {} inside the database",
+                    match max_rate_pack_opt {
+                        Some(rate_pack) => rate_pack.to_string(),
+                        None => "none".to_string(),
+                    }
+                );
+                self.send_config_change_msg(ConfigChangeMsg {
+                    change: ConfigChange::UpdateMaxRatePack(max_rate_pack_opt),
+                });
+                Ok(())
+            }
+            Err(e) => Err((CONFIGURATOR_WRITE_ERROR, format!("max rate pack: {:?}", e))),
+        }
+    }
+
     fn set_min_hops(&mut self, min_hops_value: String) -> Result<(), (u64, String)> {
         let min_hops = match Hops::from_str(&min_hops_value) {
             Ok(min_hops) => min_hops,
@@ -2308,6 +2360,155 @@ mod tests {
         );
     }
 
+    #[test]
+    fn handle_set_configuration_works_for_max_rate_pack() {
+        init_test_logging();
+        let test_name = "handle_set_configuration_works_for_max_rate_pack";
+        let set_max_rate_pack_params_arc = Arc::new(Mutex::new(vec![]));
+        let persistent_config = PersistentConfigurationMock::new()
+            .set_max_rate_pack_params(&set_max_rate_pack_params_arc)
+            .set_max_rate_pack_result(Ok(()));
+        let system = System::new(test_name);
+        let (neighborhood, _, neighborhood_recording_arc) = make_recorder();
+        let peer_actors = peer_actors_builder().neighborhood(neighborhood).build();
+        let mut subject = make_subject(Some(persistent_config));
+        subject.logger = Logger::new(test_name);
+        subject.config_change_subs_opt = Some(peer_actors.config_change_subs());
+
+        let result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "max-rate-pack".to_string(),
+                value: "10|100|20|200".to_string(),
+            },
+            4000,
+        );
+
+        System::current().stop();
+        system.run();
+        let expected_max_rate_pack = RatePack {
+            routing_byte_rate: 10,
+            routing_service_rate: 100,
+            exit_byte_rate: 20,
+            exit_service_rate: 200,
+        };
+        let neighborhood_recording = neighborhood_recording_arc.lock().unwrap();
+        let message_to_neighborhood = neighborhood_recording.get_record::<ConfigChangeMsg>(0);
+        let set_max_rate_pack_params = set_max_rate_pack_params_arc.lock().unwrap();
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "setConfiguration".to_string(),
+                path: MessagePath::Conversation(4000),
+                payload: Ok(r#"{}"#.to_string())
+            }
+        );
+        assert_eq!(
+            message_to_neighborhood,
+            &ConfigChangeMsg {
+                change: ConfigChange::UpdateMaxRatePack(Some(expected_max_rate_pack))
+            }
+        );
+        assert_eq!(
+            *set_max_rate_pack_params,
+            vec![Some(expected_max_rate_pack)]
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "DEBUG: {test_name}: The value of max-rate-pack has been changed to 10|100|20|200 inside the database"
+        ));
+    }
+
+    #[test]
+    fn handle_set_configuration_clears_max_rate_pack_with_none() {
+        let set_max_rate_pack_params_arc = Arc::new(Mutex::new(vec![]));
+        let persistent_config = PersistentConfigurationMock::new()
+            .set_max_rate_pack_params(&set_max_rate_pack_params_arc)
+            .set_max_rate_pack_result(Ok(()));
+        let system = System::new("handle_set_configuration_clears_max_rate_pack_with_none");
+        let (neighborhood, _, neighborhood_recording_arc) = make_recorder();
+        let peer_actors = peer_actors_builder().neighborhood(neighborhood).build();
+        let mut subject = make_subject(Some(persistent_config));
+        subject.config_change_subs_opt = Some(peer_actors.config_change_subs());
+
+        let result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "max-rate-pack".to_string(),
+                value: "None".to_string(),
+            },
+            4000,
+        );
+
+        System::current().stop();
+        system.run();
+        let neighborhood_recording = neighborhood_recording_arc.lock().unwrap();
+        let message_to_neighborhood = neighborhood_recording.get_record::<ConfigChangeMsg>(0);
+        let set_max_rate_pack_params = set_max_rate_pack_params_arc.lock().unwrap();
+        assert_eq!(result.payload, Ok(r#"{}"#.to_string()));
+        assert_eq!(
+            message_to_neighborhood,
+            &ConfigChangeMsg {
+                change: ConfigChange::UpdateMaxRatePack(None)
+            }
+        );
+        assert_eq!(*set_max_rate_pack_params, vec![None]);
+    }
+
+    #[test]
+    fn handle_set_configuration_throws_err_for_invalid_max_rate_pack() {
+        let mut subject = make_subject(None);
+
+        let result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "max-rate-pack".to_string(),
+                value: "10|100|20".to_string(),
+            },
+            4000,
+        );
+
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "setConfiguration".to_string(),
+                path: MessagePath::Conversation(4000),
+                payload: Err((
+                    NON_PARSABLE_VALUE,
+                    "max rate pack: Wrong number of values: expected 4 but 3 supplied".to_string()
+                ))
+            }
+        );
+    }
+
+    #[test]
+    fn handle_set_configuration_handles_failure_on_max_rate_pack_database_issue() {
+        let persistent_config = PersistentConfigurationMock::new()
+            .set_max_rate_pack_result(Err(PersistentConfigError::TransactionError));
+        let system =
+            System::new("handle_set_configuration_handles_failure_on_max_rate_pack_database_issue");
+        let (neighborhood, _, neighborhood_recording_arc) = make_recorder();
+        let peer_actors = peer_actors_builder().neighborhood(neighborhood).build();
+        let mut subject = make_subject(Some(persistent_config));
+        subject.config_change_subs_opt = Some(peer_actors.config_change_subs());
+
+        let result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "max-rate-pack".to_string(),
+                value: "10|100|20|200".to_string(),
+            },
+            4000,
+        );
+
+        System::current().stop();
+        system.run();
+        let recording = neighborhood_recording_arc.lock().unwrap();
+        assert!(recording.is_empty());
+        assert_eq!(
+            result.payload,
+            Err((
+                CONFIGURATOR_WRITE_ERROR,
+                "max rate pack: TransactionError".to_string()
+            ))
+        );
+    }
+
     #[test]
     fn handle_set_configuration_works_for_min_hops() {
         init_test_logging();
@@ -2319,7 +2520,11 @@ mod tests {
             .set_min_hops_result(Ok(()));
         let system = System::new("handle_set_configuration_works_for_min_hops");
         let (neighborhood, _, neighborhood_recording_arc) = make_recorder();
-        let peer_actors = peer_actors_builder().neighborhood(neighborhood).build();
+        let (proxy_server, _, proxy_server_recording_arc) = make_recorder();
+        let peer_actors = peer_actors_builder()
+            .neighborhood(neighborhood)
+            .proxy_server(proxy_server)
+            .build();
         let mut subject = make_subject(Some(persistent_config));
         subject.logger = Logger::new(test_name);
         subject.config_change_subs_opt = Some(peer_actors.config_change_subs());
@@ -2352,6 +2557,13 @@ mod tests {
                 change: ConfigChange::UpdateMinHops(new_min_hops)
             }
         );
+        let proxy_server_recording = proxy_server_recording_arc.lock().unwrap();
+        assert_eq!(
+            proxy_server_recording.get_record::<ConfigChangeMsg>(0),
+            &ConfigChangeMsg {
+                change: ConfigChange::UpdateMinHops(new_min_hops)
+            }
+        );
         assert_eq!(*min_hops_in_db, new_min_hops);
         TestLogHandler::new().exists_log_containing(&format!(
            "DEBUG: {test_name}: The value of min-hops has been changed to {new_min_hops}-hop inside the database"
@@ -2546,7 +2758,11 @@ mod tests {
             .neighborhood_mode_result(Ok(NeighborhoodModeLight::Standard))
             .past_neighbors_result(Ok(Some(vec![node_descriptor.clone()])))
             .earning_wallet_address_result(Ok(Some(earning_wallet_address.clone())))
-            .start_block_result(Ok(Some(3456)));
+            .start_block_result(Ok(Some(3456)))
+            .transaction_fees_result(Ok(Some(TransactionFees::Eip1559 {
+                max_fee_per_gas_wei: 2_500_000_000,
+                max_priority_fee_per_gas_wei: 100_000_000,
+            })));
         let persistent_config = payment_thresholds_scan_intervals_rate_pack(persistent_config);
         let mut subject = make_subject(Some(persistent_config));
 
@@ -2594,7 +2810,11 @@ mod tests {
                     pending_payable_sec: 122,
                     payable_sec: 125,
                     receivable_sec: 128
-                }
+                },
+                transaction_fees_opt: Some(UiTransactionFees {
+                    max_fee_per_gas_wei: 2_500_000_000,
+                    max_priority_fee_per_gas_wei_opt: Some(100_000_000),
+                })
             }
         );
     }
@@ -2677,7 +2897,10 @@ mod tests {
             .past_neighbors_params(&past_neighbors_params_arc)
             .past_neighbors_result(Ok(Some(vec![node_descriptor.clone()])))
             .earning_wallet_address_result(Ok(Some(earning_wallet_address.clone())))
-            .start_block_result(Ok(Some(3456)));
+            .start_block_result(Ok(Some(3456)))
+            .transaction_fees_result(Ok(Some(TransactionFees::Legacy {
+                gas_price_wei: 2_345_000_000_000,
+            })));
         let persistent_config = payment_thresholds_scan_intervals_rate_pack(persistent_config);
         let mut subject = make_subject(Some(persistent_config));
 
@@ -2725,7 +2948,11 @@ mod tests {
                     pending_payable_sec: 122,
                     payable_sec: 125,
                     receivable_sec: 128
-                }
+                },
+                transaction_fees_opt: Some(UiTransactionFees {
+                    max_fee_per_gas_wei: 2_345_000_000_000,
+                    max_priority_fee_per_gas_wei_opt: None,
+                })
             }
         );
         let consuming_wallet_private_key_params =
@@ -2771,7 +2998,8 @@ mod tests {
                 permanent_debt_allowed_gwei: 0,
                 threshold_interval_sec: 0,
                 unban_below_gwei: 0,
-            }));
+            }))
+            .transaction_fees_result(Ok(None));
         let mut subject = make_subject(Some(persistent_config));
 
         let (configuration, context_id) =
@@ -2818,11 +3046,62 @@ mod tests {
                     pending_payable_sec: 0,
                     payable_sec: 0,
                     receivable_sec: 0
-                }
+                },
+                transaction_fees_opt: None
+            }
+        );
+    }
+
+    #[test]
+    fn configuration_handles_error_retrieving_transaction_fees() {
+        let persistent_config = PersistentConfigurationMock::new()
+            .blockchain_service_url_result(Ok(None))
+            .current_schema_version_result("3")
+            .clandestine_port_result(Ok(1234))
+            .chain_name_result("ropsten".to_string())
+            .gas_price_result(Ok(2345))
+            .earning_wallet_address_result(Ok(None))
+            .start_block_result(Ok(Some(3456)))
+            .max_block_count_result(Ok(None))
+            .neighborhood_mode_result(Ok(NeighborhoodModeLight::Standard))
+            .mapping_protocol_result(Ok(None))
+            .transaction_fees_result(Err(PersistentConfigError::NotPresent));
+        let persistent_config = payment_thresholds_scan_intervals_rate_pack(persistent_config);
+        let mut subject = make_subject(Some(persistent_config));
+
+        let result = subject.handle_configuration(
+            UiConfigurationRequest {
+                db_password_opt: None,
+            },
+            4321,
+        );
+
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "configuration".to_string(),
+                path: MessagePath::Conversation(4321),
+                payload: Err((CONFIGURATOR_READ_ERROR, "transactionFeesOpt".to_string()))
             }
         );
     }
 
+    #[test]
+    fn ui_transaction_fees_saturate_at_the_largest_number_the_ui_can_take() {
+        let result = Configurator::ui_transaction_fees(TransactionFees::Eip1559 {
+            max_fee_per_gas_wei: u64::MAX as u128 + 1,
+            max_priority_fee_per_gas_wei: 100_000_000,
+        });
+
+        assert_eq!(
+            result,
+            UiTransactionFees {
+                max_fee_per_gas_wei: u64::MAX,
+                max_priority_fee_per_gas_wei_opt: Some(100_000_000),
+            }
+        )
+    }
+
     #[test]
     #[should_panic(
         expected = "Database corruption: Could not read max block count: DatabaseError(\"Corruption\")"
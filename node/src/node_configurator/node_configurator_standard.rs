@@ -18,7 +18,9 @@ use crate::apps::app_node;
 use crate::bootstrapper::PortConfiguration;
 use crate::database::db_initializer::{DbInitializationConfig, ExternalData};
 use crate::db_config::persistent_configuration::PersistentConfiguration;
+use crate::forward_proxy_discriminator_factory::ForwardProxyDiscriminatorFactory;
 use crate::http_request_start_finder::HttpRequestDiscriminatorFactory;
+use crate::neighborhood::DEFAULT_LATENCY_WEIGHT;
 use crate::node_configurator::unprivileged_parse_args_configuration::{
     UnprivilegedParseArgsConfiguration, UnprivilegedParseArgsConfigurationDaoReal,
 };
@@ -26,13 +28,19 @@ use crate::node_configurator::{
     data_directory_from_context, determine_user_specific_data,
     real_user_data_directory_path_and_chain,
 };
+use crate::proxy_server::{DEFAULT_MULTIPATH_ROUTES, DEFAULT_STICKY_ROUTE_TTL};
+use crate::socks5_discriminator_factory::Socks5DiscriminatorFactory;
 use crate::sub_lib::cryptde::PublicKey;
 use crate::sub_lib::cryptde_null::CryptDENull;
+use crate::sub_lib::exit_location::{normalize_country_code, ExitLocation};
+use crate::sub_lib::exit_policy::ExitPolicy;
+use crate::sub_lib::proxy_server::ForwardProxyConfig;
 use crate::sub_lib::utils::make_new_multi_config;
 use crate::tls_discriminator_factory::TlsDiscriminatorFactory;
 use masq_lib::constants::{DEFAULT_UI_PORT, HTTP_PORT, TLS_PORT};
 use masq_lib::multi_config::{CommandLineVcl, ConfigFileVcl, EnvironmentVcl};
 use std::str::FromStr;
+use std::time::Duration;
 
 pub struct NodeConfiguratorStandardPrivileged {
     dirs_wrapper: Box<dyn DirsWrapper>,
@@ -44,12 +52,12 @@ impl NodeConfigurator<BootstrapperConfig> for NodeConfiguratorStandardPrivileged
         multi_config: &MultiConfig,
     ) -> Result<BootstrapperConfig, ConfiguratorError> {
         let mut bootstrapper_config = BootstrapperConfig::new();
-        establish_port_configurations(&mut bootstrapper_config);
         privileged_parse_args(
             self.dirs_wrapper.as_ref(),
             multi_config,
             &mut bootstrapper_config,
         )?;
+        establish_port_configurations(&mut bootstrapper_config);
         Ok(bootstrapper_config)
     }
 }
@@ -256,6 +264,21 @@ pub fn establish_port_configurations(config: &mut BootstrapperConfig) {
             false,
         ),
     );
+    if let Some(socks_port) = config.socks_port_opt {
+        config.port_configurations.insert(
+            socks_port,
+            PortConfiguration::new(vec![Box::new(Socks5DiscriminatorFactory::new())], false),
+        );
+    }
+    if let Some(forward_proxy_config) = &config.forward_proxy_config_opt {
+        config.port_configurations.insert(
+            forward_proxy_config.port,
+            PortConfiguration::new(
+                vec![Box::new(ForwardProxyDiscriminatorFactory::new())],
+                false,
+            ),
+        );
+    }
 }
 
 // All initialization that doesn't specifically require lack of privilege should be done here.
@@ -297,6 +320,54 @@ pub fn privileged_parse_args(
     privileged_config.crash_point =
         value_m!(multi_config, "crash-point", CrashPoint).unwrap_or(CrashPoint::None);
 
+    privileged_config.socks_port_opt = value_m!(multi_config, "socks-port", u16);
+
+    privileged_config.forward_proxy_config_opt = value_m!(multi_config, "forward-proxy-port", u16)
+        .map(|port| ForwardProxyConfig {
+            port,
+            connect_ports: match value_m!(multi_config, "connect-ports", String) {
+                Some(connect_ports) => connect_ports
+                    .split(',')
+                    .map(|port| {
+                        port.parse::<u16>()
+                            .expect("Bad clap validation for connect-ports")
+                    })
+                    .collect(),
+                None => vec![TLS_PORT],
+            },
+        });
+
+    privileged_config.sticky_route_ttl = value_m!(multi_config, "sticky-route-ttl", u64)
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_STICKY_ROUTE_TTL);
+    privileged_config.multipath_routes =
+        value_m!(multi_config, "multipath-routes", usize).unwrap_or(DEFAULT_MULTIPATH_ROUTES);
+    privileged_config.packet_padding = value_m!(multi_config, "packet-padding", String)
+        .unwrap_or_else(|| "off".to_string())
+        == *"on";
+    privileged_config.binary_masquerade = value_m!(multi_config, "binary-masquerade", String)
+        .unwrap_or_else(|| "off".to_string())
+        == *"on";
+    privileged_config.cover_traffic_interval_opt =
+        value_m!(multi_config, "cover-traffic-interval", u64).map(Duration::from_millis);
+
+    privileged_config.exit_policy = value_m!(multi_config, "exit-policy", String)
+        .map(|policy| ExitPolicy::from_str(&policy).expect("Bad clap validation for exit-policy"))
+        .unwrap_or_default();
+
+    privileged_config.country_code_opt =
+        value_m!(multi_config, "country-code", String).map(|country_code| {
+            normalize_country_code(&country_code).expect("Bad clap validation for country-code")
+        });
+
+    privileged_config.exit_location_opt =
+        value_m!(multi_config, "exit-location", String).map(|exit_location| {
+            ExitLocation::from_str(&exit_location).expect("Bad clap validation for exit-location")
+        });
+
+    privileged_config.latency_weight =
+        value_m!(multi_config, "latency-weight", u64).unwrap_or(DEFAULT_LATENCY_WEIGHT);
+
     if let Some(public_key_str) = value_m!(multi_config, "fake-public-key", String) {
         let (main_public_key, alias_public_key) = match base64::decode(&public_key_str) {
             Ok(mut key) => {
@@ -649,6 +720,18 @@ mod tests {
             .param("--ip", "34.56.78.90")
             .param("--clandestine-port", "1234")
             .param("--ui-port", "5335")
+            .param("--socks-port", "1080")
+            .param("--forward-proxy-port", "8080")
+            .param("--connect-ports", "443,22")
+            .param("--sticky-route-ttl", "60")
+            .param("--multipath-routes", "3")
+            .param("--packet-padding", "on")
+            .param("--binary-masquerade", "on")
+            .param("--cover-traffic-interval", "250")
+            .param("--exit-policy", "25,booga.com")
+            .param("--country-code", "cz")
+            .param("--exit-location", "strict:CZ,SK")
+            .param("--latency-weight", "5000000000")
             .param("--data-directory", home_dir.to_str().unwrap())
             .param("--blockchain-service-url", "http://127.0.0.1:8545")
             .param("--log-level", "trace")
@@ -683,6 +766,35 @@ mod tests {
             ),
         );
         assert_eq!(config.ui_gateway_config.ui_port, 5335);
+        assert_eq!(config.socks_port_opt, Some(1080));
+        assert_eq!(
+            config.forward_proxy_config_opt,
+            Some(ForwardProxyConfig {
+                port: 8080,
+                connect_ports: vec![443, 22],
+            })
+        );
+        assert_eq!(config.sticky_route_ttl, Duration::from_secs(60));
+        assert_eq!(config.multipath_routes, 3);
+        assert_eq!(config.packet_padding, true);
+        assert_eq!(config.binary_masquerade, true);
+        assert_eq!(
+            config.cover_traffic_interval_opt,
+            Some(Duration::from_millis(250))
+        );
+        assert_eq!(
+            config.exit_policy,
+            ExitPolicy::from_str("25,booga.com").unwrap()
+        );
+        assert_eq!(config.country_code_opt, Some("CZ".to_string()));
+        assert_eq!(
+            config.exit_location_opt,
+            Some(ExitLocation {
+                country_codes: vec!["CZ".to_string(), "SK".to_string()],
+                strict: true,
+            })
+        );
+        assert_eq!(config.latency_weight, 5_000_000_000);
         assert_eq!(
             config.neighborhood_config,
             NeighborhoodConfig {
@@ -723,6 +835,17 @@ mod tests {
         );
         assert_eq!(config.crash_point, CrashPoint::None);
         assert_eq!(config.ui_gateway_config.ui_port, DEFAULT_UI_PORT);
+        assert_eq!(config.socks_port_opt, None);
+        assert_eq!(config.forward_proxy_config_opt, None);
+        assert_eq!(config.sticky_route_ttl, DEFAULT_STICKY_ROUTE_TTL);
+        assert_eq!(config.multipath_routes, DEFAULT_MULTIPATH_ROUTES);
+        assert_eq!(config.packet_padding, false);
+        assert_eq!(config.binary_masquerade, false);
+        assert_eq!(config.cover_traffic_interval_opt, None);
+        assert_eq!(config.exit_policy, ExitPolicy::default());
+        assert_eq!(config.country_code_opt, None);
+        assert_eq!(config.exit_location_opt, None);
+        assert_eq!(config.latency_weight, DEFAULT_LATENCY_WEIGHT);
         assert!(config.main_cryptde_null_opt.is_none());
         assert_eq!(
             config.real_user,
@@ -810,6 +933,90 @@ mod tests {
         assert_eq!(config.crash_point, CrashPoint::Panic);
     }
 
+    #[test]
+    fn establish_port_configurations_adds_socks_listener_only_when_socks_port_is_specified() {
+        running_test();
+        let mut without_socks = BootstrapperConfig::new();
+        let mut with_socks = BootstrapperConfig::new();
+        with_socks.socks_port_opt = Some(2080);
+
+        establish_port_configurations(&mut without_socks);
+        establish_port_configurations(&mut with_socks);
+
+        let mut without_socks_ports = without_socks
+            .port_configurations
+            .keys()
+            .cloned()
+            .collect::<Vec<u16>>();
+        without_socks_ports.sort();
+        assert_eq!(without_socks_ports, vec![HTTP_PORT, TLS_PORT]);
+        let mut with_socks_ports = with_socks
+            .port_configurations
+            .keys()
+            .cloned()
+            .collect::<Vec<u16>>();
+        with_socks_ports.sort();
+        assert_eq!(with_socks_ports, vec![HTTP_PORT, TLS_PORT, 2080]);
+        let socks_configuration = with_socks.port_configurations.get(&2080).unwrap();
+        assert!(!socks_configuration.is_clandestine);
+        assert_eq!(socks_configuration.discriminator_factories.len(), 1);
+        let mut discriminator = socks_configuration.discriminator_factories[0].make();
+        discriminator.add_data(&[0x05, 0x01, 0x00]);
+        assert_eq!(
+            discriminator.take_chunk().unwrap().chunk,
+            vec![0x05, 0x01, 0x00]
+        );
+    }
+
+    #[test]
+    fn privileged_parse_args_defaults_connect_ports_to_tls_port() {
+        running_test();
+        let args = ArgsBuilder::new()
+            .param("--ip", "1.2.3.4")
+            .param("--forward-proxy-port", "8080");
+        let mut config = BootstrapperConfig::new();
+        let vcls: Vec<Box<dyn VirtualCommandLine>> =
+            vec![Box::new(CommandLineVcl::new(args.into()))];
+        let multi_config = make_new_multi_config(&app_node(), vcls).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(
+            config.forward_proxy_config_opt,
+            Some(ForwardProxyConfig {
+                port: 8080,
+                connect_ports: vec![TLS_PORT],
+            })
+        );
+    }
+
+    #[test]
+    fn establish_port_configurations_adds_forward_proxy_listener_when_configured() {
+        running_test();
+        let mut config = BootstrapperConfig::new();
+        config.forward_proxy_config_opt = Some(ForwardProxyConfig {
+            port: 8080,
+            connect_ports: vec![TLS_PORT],
+        });
+
+        establish_port_configurations(&mut config);
+
+        let mut ports = config
+            .port_configurations
+            .keys()
+            .cloned()
+            .collect::<Vec<u16>>();
+        ports.sort();
+        assert_eq!(ports, vec![HTTP_PORT, TLS_PORT, 8080]);
+        let forward_proxy_configuration = config.port_configurations.get(&8080).unwrap();
+        assert!(!forward_proxy_configuration.is_clandestine);
+        assert_eq!(forward_proxy_configuration.discriminator_factories.len(), 1);
+        let mut discriminator = forward_proxy_configuration.discriminator_factories[0].make();
+        let request = b"GET http://example.com/ HTTP/1.1\r\nHost: example.com\r\n\r\n";
+        discriminator.add_data(request);
+        assert_eq!(discriminator.take_chunk().unwrap().chunk, request.to_vec());
+    }
+
     fn fill_up_config_file(mut config_file: File) {
         {
             config_file
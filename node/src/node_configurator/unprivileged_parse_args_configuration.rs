@@ -1,6 +1,6 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
-use crate::accountant::DEFAULT_PENDING_TOO_LONG_SEC;
+use crate::accountant::{DEFAULT_PENDING_SCANS_BEFORE_REPLACEMENT, DEFAULT_PENDING_TOO_LONG_SEC};
 use crate::blockchain::bip32::Bip32EncryptionKeyProvider;
 use crate::bootstrapper::BootstrapperConfig;
 use crate::db_config::persistent_configuration::{PersistentConfigError, PersistentConfiguration};
@@ -511,6 +511,9 @@ fn configure_accountant_config(
     config.scan_intervals_opt = Some(scan_intervals);
     config.suppress_initial_scans = suppress_initial_scans;
     config.when_pending_too_long_sec = DEFAULT_PENDING_TOO_LONG_SEC;
+    config.pending_scans_before_replacement =
+        value_m!(multi_config, "pending-scans-before-replacement", u16)
+            .unwrap_or(DEFAULT_PENDING_SCANS_BEFORE_REPLACEMENT);
     Ok(())
 }
 
@@ -1821,6 +1824,8 @@ mod tests {
             "180|150|130",
             "--payment-thresholds",
             "100000|10000|1000|20000|1000|20000",
+            "--pending-scans-before-replacement",
+            "3",
         ];
         let mut config = BootstrapperConfig::new();
         let multi_config = make_simplified_multi_config(args);
@@ -1877,6 +1882,7 @@ mod tests {
             config.when_pending_too_long_sec,
             DEFAULT_PENDING_TOO_LONG_SEC
         );
+        assert_eq!(config.pending_scans_before_replacement, 3);
         let set_scan_intervals_params = set_scan_intervals_params_arc.lock().unwrap();
         assert_eq!(*set_scan_intervals_params, vec!["180|150|130".to_string()]);
         let set_payment_thresholds_params = set_payment_thresholds_params_arc.lock().unwrap();
@@ -1954,6 +1960,10 @@ mod tests {
             config.when_pending_too_long_sec,
             expected_when_pending_too_long_sec
         );
+        assert_eq!(
+            config.pending_scans_before_replacement,
+            DEFAULT_PENDING_SCANS_BEFORE_REPLACEMENT
+        );
         //no prepared results for the setter methods, that is they were uncalled
     }
 
@@ -11,6 +11,7 @@ use crate::null_masquerader::NullMasquerader;
 use crate::privilege_drop::IdWrapper;
 use crate::stream_handler_pool::StreamHandlerPoolSubs;
 use crate::stream_messages::*;
+use crate::sub_lib::dispatcher::InboundClientData;
 use crate::sub_lib::framer::FramedChunk;
 use crate::sub_lib::framer::Framer;
 use crate::sub_lib::stream_handler_pool::DispatcherNodeQueryResponse;
@@ -301,6 +302,7 @@ pub fn make_stream_handler_pool_subs_from_recorder(addr: &Addr<Recorder>) -> Str
             addr,
             MessageScheduler<DispatcherNodeQueryResponse>
         ),
+        link_data_sub: recipient!(addr, InboundClientData),
     }
 }
 
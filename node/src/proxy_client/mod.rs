@@ -224,7 +224,9 @@ impl ProxyClient {
         ProxyClient {
             dns_servers: config.dns_servers,
             resolver_wrapper_factory: Box::new(ResolverWrapperFactoryReal {}),
-            stream_handler_pool_factory: Box::new(StreamHandlerPoolFactoryReal {}),
+            stream_handler_pool_factory: Box::new(StreamHandlerPoolFactoryReal::new(
+                config.exit_policy,
+            )),
             cryptde: config.cryptde,
             to_hopper: None,
             to_accountant: None,
@@ -341,6 +343,7 @@ mod tests {
     use crate::sub_lib::cryptde::CryptData;
     use crate::sub_lib::cryptde::PublicKey;
     use crate::sub_lib::dispatcher::Component;
+    use crate::sub_lib::exit_policy::ExitPolicy;
     use crate::sub_lib::hopper::MessageType;
     use crate::sub_lib::proxy_client::ClientResponsePayload_0v1;
     use crate::sub_lib::proxy_server::ClientRequestPayload_0v1;
@@ -494,6 +497,7 @@ mod tests {
             exit_byte_rate: 200,
             is_decentralized,
             crashable: false,
+            exit_policy: ExitPolicy::default(),
         };
 
         let zero_hop = ProxyClient::new(config_factory(false));
@@ -517,6 +521,7 @@ mod tests {
             exit_byte_rate: 200,
             is_decentralized: true,
             crashable: true,
+            exit_policy: ExitPolicy::default(),
         });
 
         prove_that_crash_request_handler_is_hooked_up(proxy_client, CRASH_KEY);
@@ -534,6 +539,7 @@ mod tests {
             exit_byte_rate: 200,
             is_decentralized: true,
             crashable: false,
+            exit_policy: ExitPolicy::default(),
         });
     }
 
@@ -563,6 +569,7 @@ mod tests {
             exit_byte_rate: 200,
             is_decentralized: true,
             crashable: false,
+            exit_policy: ExitPolicy::default(),
         });
         subject.resolver_wrapper_factory = Box::new(resolver_wrapper_factory);
         subject.stream_handler_pool_factory = Box::new(pool_factory);
@@ -595,6 +602,11 @@ mod tests {
         );
         assert_eq!(opts, ResolverOpts::default());
         assert_eq!(resolver_wrapper_new_parameters.is_empty(), true);
+        let mut pool_factory_make_parameters = pool_factory_make_parameters.lock().unwrap();
+        let (_, _, _, _, exit_service_rate, exit_byte_rate) =
+            pool_factory_make_parameters.remove(0);
+        assert_eq!(exit_service_rate, 100);
+        assert_eq!(exit_byte_rate, 200);
     }
 
     #[test]
@@ -628,6 +640,7 @@ mod tests {
             exit_byte_rate: 200,
             is_decentralized: true,
             crashable: false,
+            exit_policy: ExitPolicy::default(),
         });
         let subject_addr: Addr<ProxyClient> = subject.start();
 
@@ -652,6 +665,7 @@ mod tests {
                 exit_byte_rate: 0,
                 is_decentralized: true,
                 crashable: false,
+                exit_policy: ExitPolicy::default(),
             });
             let subject_addr = subject.start();
             let subject_subs = ProxyClient::make_subs_from(&subject_addr);
@@ -693,6 +707,7 @@ mod tests {
                 exit_byte_rate: 0,
                 is_decentralized: true,
                 crashable: false,
+                exit_policy: ExitPolicy::default(),
             });
             subject.stream_contexts.insert(
                 stream_key_inner,
@@ -785,6 +800,7 @@ mod tests {
             exit_byte_rate: 200,
             is_decentralized: true,
             crashable: false,
+            exit_policy: ExitPolicy::default(),
         });
         subject.resolver_wrapper_factory = Box::new(resolver_factory);
         subject.stream_handler_pool_factory = Box::new(pool_factory);
@@ -842,6 +858,7 @@ mod tests {
             exit_byte_rate: rate_pack_exit_byte(100),
             is_decentralized: true,
             crashable: false,
+            exit_policy: ExitPolicy::default(),
         });
         subject.resolver_wrapper_factory = Box::new(resolver_factory);
         subject.stream_handler_pool_factory = Box::new(pool_factory);
@@ -909,6 +926,7 @@ mod tests {
             exit_byte_rate: rate_pack_exit_byte(100),
             is_decentralized: false,
             crashable: false,
+            exit_policy: ExitPolicy::default(),
         });
         subject.resolver_wrapper_factory = Box::new(resolver_factory);
         subject.stream_handler_pool_factory = Box::new(pool_factory);
@@ -940,6 +958,7 @@ mod tests {
             exit_byte_rate: 200,
             is_decentralized: true,
             crashable: false,
+            exit_policy: ExitPolicy::default(),
         });
         subject.stream_contexts.insert(
             stream_key.clone(),
@@ -1089,6 +1108,7 @@ mod tests {
             exit_byte_rate: 200,
             is_decentralized: true,
             crashable: false,
+            exit_policy: ExitPolicy::default(),
         });
         subject.stream_contexts.insert(
             stream_key.clone(),
@@ -1141,6 +1161,7 @@ mod tests {
             exit_byte_rate: 200,
             is_decentralized: true,
             crashable: false,
+            exit_policy: ExitPolicy::default(),
         });
         subject.stream_contexts.insert(
             stream_key.clone(),
@@ -1191,6 +1212,7 @@ mod tests {
             exit_byte_rate: 200,
             is_decentralized: true,
             crashable: false,
+            exit_policy: ExitPolicy::default(),
         });
         let mut process_package_params_arc = Arc::new(Mutex::new(vec![]));
         let pool = StreamHandlerPoolMock::new()
@@ -7,6 +7,7 @@ use crate::proxy_client::stream_establisher::{StreamEstablisher, StreamEstablish
 use crate::sub_lib::accountant::ReportExitServiceProvidedMessage;
 use crate::sub_lib::channel_wrappers::SenderWrapper;
 use crate::sub_lib::cryptde::CryptDE;
+use crate::sub_lib::exit_policy::ExitPolicy;
 use crate::sub_lib::proxy_client::{error_socket_addr, ProxyClientSubs};
 use crate::sub_lib::proxy_client::{DnsResolveFailure_0v1, InboundServerData};
 use crate::sub_lib::proxy_server::ClientRequestPayload_0v1;
@@ -56,6 +57,7 @@ struct StreamHandlerPoolRealInner {
     establisher_factory: Box<dyn StreamEstablisherFactory>,
     exit_service_rate: u64,
     exit_byte_rate: u64,
+    exit_policy: ExitPolicy,
 }
 
 impl StreamHandlerPool for StreamHandlerPoolReal {
@@ -80,6 +82,7 @@ impl StreamHandlerPoolReal {
         proxy_client_subs: ProxyClientSubs,
         exit_service_rate: u64,
         exit_byte_rate: u64,
+        exit_policy: ExitPolicy,
     ) -> StreamHandlerPoolReal {
         let (stream_killer_tx, stream_killer_rx) = unbounded();
         let (stream_adder_tx, stream_adder_rx) = unbounded();
@@ -99,6 +102,7 @@ impl StreamHandlerPoolReal {
                 logger: Logger::new("ProxyClient"),
                 exit_service_rate,
                 exit_byte_rate,
+                exit_policy,
             })),
             stream_adder_rx,
             stream_killer_rx,
@@ -283,15 +287,27 @@ impl StreamHandlerPoolReal {
         );
 
         match payload.target_hostname {
-            Some(ref target_hostname) => match Self::parse_ip(target_hostname) {
-                Ok(socket_addr) => Self::handle_ip(
-                    payload.clone(),
-                    socket_addr,
-                    inner_arc,
-                    target_hostname.to_string(),
-                ),
-                Err(_) => Self::lookup_dns(inner_arc, target_hostname.to_string(), payload.clone()),
-            },
+            Some(ref target_hostname) => {
+                if let Some(error) =
+                    Self::check_exit_policy(&inner_arc, payload, target_hostname, &logger)
+                {
+                    return Box::new(err::<
+                        Box<dyn SenderWrapper<SequencedPacket> + 'static>,
+                        String,
+                    >(error));
+                }
+                match Self::parse_ip(target_hostname) {
+                    Ok(socket_addr) => Self::handle_ip(
+                        payload.clone(),
+                        socket_addr,
+                        inner_arc,
+                        target_hostname.to_string(),
+                    ),
+                    Err(_) => {
+                        Self::lookup_dns(inner_arc, target_hostname.to_string(), payload.clone())
+                    }
+                }
+            }
             None => {
                 error!(
                     logger,
@@ -306,6 +322,41 @@ impl StreamHandlerPoolReal {
         }
     }
 
+    // A refused destination gets the same answer as one that can't be resolved, so that the
+    // originating Node will look for another exit next time.
+    fn check_exit_policy(
+        inner_arc: &Arc<Mutex<StreamHandlerPoolRealInner>>,
+        payload: &ClientRequestPayload_0v1,
+        target_hostname: &str,
+        logger: &Logger,
+    ) -> Option<String> {
+        let inner = inner_arc.lock().expect("Stream handler pool is poisoned");
+        let hostname = match Self::parse_ip(target_hostname) {
+            Ok(ip_addr) => ip_addr.to_string(),
+            Err(_) => target_hostname.to_string(),
+        };
+        let rule = inner
+            .exit_policy
+            .refusing_rule(&hostname, payload.target_port)?;
+        warning!(
+            logger,
+            "Exit policy rule '{}' refuses stream {:?} to {}:{}",
+            rule,
+            payload.stream_key,
+            target_hostname,
+            payload.target_port
+        );
+        inner
+            .proxy_client_subs
+            .dns_resolve_failed
+            .try_send(DnsResolveFailure_0v1::new(payload.stream_key))
+            .expect("ProxyClient is dead");
+        Some(format!(
+            "Exit policy refuses {}:{}",
+            target_hostname, payload.target_port
+        ))
+    }
+
     fn parse_ip(hostname: &str) -> Result<IpAddr, AddrParseError> {
         let socket_ip = SocketAddr::from_str(hostname).map(|sa| sa.ip());
         if socket_ip.is_ok() {
@@ -350,6 +401,11 @@ impl StreamHandlerPoolReal {
             .dns_resolve_failed
             .clone();
         let mut establisher = StreamHandlerPoolReal::make_establisher(inner_arc.clone());
+        let exit_policy = inner_arc
+            .lock()
+            .expect("Stream handler pool is poisoned")
+            .exit_policy
+            .clone();
         let stream_key = payload.stream_key;
         let logger = StreamHandlerPoolReal::make_logger_copy(&inner_arc);
         Box::new(
@@ -363,6 +419,7 @@ impl StreamHandlerPoolReal {
                         target_hostname.to_string(),
                         &payload,
                         lookup_result,
+                        &exit_policy,
                         logger,
                         &mut establisher,
                     )
@@ -391,6 +448,7 @@ impl StreamHandlerPoolReal {
         target_hostname: String,
         payload: &ClientRequestPayload_0v1,
         lookup_result: Result<LookupIp, ResolveError>,
+        exit_policy: &ExitPolicy,
         logger: Logger,
         establisher: &mut StreamEstablisher,
     ) -> io::Result<Box<dyn SenderWrapper<SequencedPacket>>> {
@@ -415,11 +473,27 @@ impl StreamHandlerPoolReal {
             return Err(io::Error::from(io::ErrorKind::NotFound));
         }
 
+        let permitted_ip_addrs: Vec<IpAddr> = filtered_ip_addrs
+            .iter()
+            .filter(|ip_addr| exit_policy.refusing_rule_for_ip(**ip_addr).is_none())
+            .copied()
+            .collect();
+
+        if permitted_ip_addrs.is_empty() {
+            warning!(
+                logger,
+                "Exit policy refuses every IP address for host {}: {:?}",
+                target_hostname,
+                &filtered_ip_addrs
+            );
+            return Err(io::Error::from(io::ErrorKind::PermissionDenied));
+        }
+
         debug!(
             logger,
-            "Found IP addresses for {}: {:?}", target_hostname, &filtered_ip_addrs
+            "Found IP addresses for {}: {:?}", target_hostname, &permitted_ip_addrs
         );
-        establisher.establish_stream(payload, filtered_ip_addrs, target_hostname)
+        establisher.establish_stream(payload, permitted_ip_addrs, target_hostname)
     }
 
     fn make_fqdn(target_hostname: &str) -> String {
@@ -543,7 +617,9 @@ pub trait StreamHandlerPoolFactory {
     ) -> Box<dyn StreamHandlerPool>;
 }
 
-pub struct StreamHandlerPoolFactoryReal {}
+pub struct StreamHandlerPoolFactoryReal {
+    exit_policy: ExitPolicy,
+}
 
 impl StreamHandlerPoolFactory for StreamHandlerPoolFactoryReal {
     fn make(
@@ -562,10 +638,17 @@ impl StreamHandlerPoolFactory for StreamHandlerPoolFactoryReal {
             proxy_client_subs,
             exit_service_rate,
             exit_byte_rate,
+            self.exit_policy.clone(),
         ))
     }
 }
 
+impl StreamHandlerPoolFactoryReal {
+    pub fn new(exit_policy: ExitPolicy) -> StreamHandlerPoolFactoryReal {
+        StreamHandlerPoolFactoryReal { exit_policy }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -587,11 +670,12 @@ mod tests {
     use crate::test_utils::make_wallet;
     use crate::test_utils::recorder::make_recorder;
     use crate::test_utils::recorder::peer_actors_builder;
+    use crate::test_utils::recorder::Recorder;
     use crate::test_utils::stream_connector_mock::StreamConnectorMock;
     use crate::test_utils::tokio_wrapper_mocks::ReadHalfWrapperMock;
     use crate::test_utils::tokio_wrapper_mocks::WriteHalfWrapperMock;
     use actix::System;
-    use masq_lib::constants::HTTP_PORT;
+    use masq_lib::constants::{HTTP_PORT, TLS_PORT};
     use masq_lib::test_utils::logging::init_test_logging;
     use masq_lib::test_utils::logging::TestLogHandler;
     use std::cell::RefCell;
@@ -669,6 +753,7 @@ mod tests {
                 }),
                 exit_service_rate: Default::default(),
                 exit_byte_rate: Default::default(),
+                exit_policy: ExitPolicy::default(),
             };
             let payload = ClientRequestPayload_0v1 {
                 stream_key,
@@ -695,6 +780,133 @@ mod tests {
         );
     }
 
+    fn process_package_under_exit_policy(
+        test_name: &'static str,
+        resolver_mock: ResolverWrapperMock,
+        exit_policy: &str,
+        target_hostname: &str,
+        target_port: u16,
+        proxy_client: Recorder,
+        stream_key: StreamKey,
+    ) {
+        let exit_policy = ExitPolicy::from_str(exit_policy).unwrap();
+        let target_hostname = target_hostname.to_string();
+        thread::spawn(move || {
+            let system = System::new(test_name);
+            let peer_actors = peer_actors_builder().proxy_client(proxy_client).build();
+            let cryptde = main_cryptde();
+            let logger = Logger::new(test_name);
+            let establisher = StreamEstablisher {
+                cryptde,
+                stream_adder_tx: unbounded().0,
+                stream_killer_tx: unbounded().0,
+                stream_connector: Box::new(StreamConnectorMock::new()),
+                proxy_client_sub: peer_actors
+                    .proxy_client_opt
+                    .clone()
+                    .unwrap()
+                    .inbound_server_data,
+                logger: logger.clone(),
+                channel_factory: Box::new(FuturesChannelFactoryMock::default()),
+            };
+            let inner = StreamHandlerPoolRealInner {
+                accountant_sub: peer_actors.accountant.report_exit_service_provided.clone(),
+                proxy_client_subs: peer_actors.proxy_client_opt.clone().unwrap(),
+                stream_writer_channels: HashMap::new(),
+                resolver: Box::new(resolver_mock),
+                logger,
+                establisher_factory: Box::new(StreamEstablisherFactoryMock {
+                    make_results: RefCell::new(vec![establisher]),
+                }),
+                exit_service_rate: Default::default(),
+                exit_byte_rate: Default::default(),
+                exit_policy,
+            };
+            let payload = ClientRequestPayload_0v1 {
+                stream_key,
+                sequenced_packet: SequencedPacket::new(b"booga".to_vec(), 0, false),
+                target_hostname: Some(target_hostname),
+                target_port,
+                protocol: ProxyProtocol::TLS,
+                originator_public_key: cryptde.public_key().clone(),
+            };
+
+            StreamHandlerPoolReal::process_package(payload, None, Arc::new(Mutex::new(inner)));
+
+            system.run();
+        });
+    }
+
+    #[test]
+    fn exit_policy_refuses_destination_before_resolving_it() {
+        init_test_logging();
+        let test_name = "exit_policy_refuses_destination_before_resolving_it";
+        let (proxy_client, proxy_client_awaiter, proxy_client_recording) = make_recorder();
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let lookup_ip_parameters = Arc::new(Mutex::new(vec![]));
+        let resolver_mock = ResolverWrapperMock::new().lookup_ip_parameters(&lookup_ip_parameters);
+
+        process_package_under_exit_policy(
+            test_name,
+            resolver_mock,
+            "25,tracker.example.com",
+            "udp.tracker.example.com",
+            TLS_PORT,
+            proxy_client,
+            stream_key,
+        );
+
+        proxy_client_awaiter.await_message_count(2);
+        let proxy_client_recording = proxy_client_recording.lock().unwrap();
+        assert_eq!(
+            proxy_client_recording.get_record::<DnsResolveFailure_0v1>(0),
+            &DnsResolveFailure_0v1::new(stream_key)
+        );
+        let terminating_data = proxy_client_recording.get_record::<InboundServerData>(1);
+        assert!(terminating_data.last_data);
+        assert_eq!(terminating_data.data, Vec::<u8>::new());
+        assert!(lookup_ip_parameters.lock().unwrap().is_empty());
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {}: Exit policy rule 'tracker.example.com' refuses stream {:?} to udp.tracker.example.com:443",
+            test_name, stream_key
+        ));
+    }
+
+    #[test]
+    fn exit_policy_refuses_destination_that_resolves_only_to_refused_addresses() {
+        init_test_logging();
+        let test_name = "exit_policy_refuses_destination_that_resolves_only_to_refused_addresses";
+        let (proxy_client, proxy_client_awaiter, proxy_client_recording) = make_recorder();
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let resolver_mock = ResolverWrapperMock::new().lookup_ip_success(vec![
+            IpAddr::from_str("192.168.1.1").unwrap(),
+            IpAddr::from_str("10.0.0.1").unwrap(),
+        ]);
+
+        process_package_under_exit_policy(
+            test_name,
+            resolver_mock,
+            "private",
+            "intranet.example.com",
+            TLS_PORT,
+            proxy_client,
+            stream_key,
+        );
+
+        proxy_client_awaiter.await_message_count(1);
+        assert_eq!(
+            proxy_client_recording
+                .lock()
+                .unwrap()
+                .get_record::<DnsResolveFailure_0v1>(0),
+            &DnsResolveFailure_0v1::new(stream_key)
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {}: Exit policy refuses every IP address for host intranet.example.com: [192.168.1.1, 10.0.0.1]",
+            test_name
+        ));
+    }
+
     #[test]
     fn non_terminal_payload_can_be_sent_over_existing_connection() {
         let cryptde = main_cryptde();
@@ -736,6 +948,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                ExitPolicy::default(),
             );
             subject.inner.lock().unwrap().stream_writer_channels.insert(
                 stream_key,
@@ -798,6 +1011,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                ExitPolicy::default(),
             );
             {
                 let mut inner = subject.inner.lock().unwrap();
@@ -883,6 +1097,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                ExitPolicy::default(),
             );
             let (stream_killer_tx, stream_killer_rx) = unbounded();
             subject.stream_killer_rx = stream_killer_rx;
@@ -965,6 +1180,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                ExitPolicy::default(),
             );
             {
                 let mut inner = subject.inner.lock().unwrap();
@@ -1026,6 +1242,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                ExitPolicy::default(),
             );
             {
                 let mut inner = subject.inner.lock().unwrap();
@@ -1108,6 +1325,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                ExitPolicy::default(),
             );
             let (stream_killer_tx, stream_killer_rx) = unbounded();
             subject.stream_killer_rx = stream_killer_rx;
@@ -1198,6 +1416,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                ExitPolicy::default(),
             );
 
             run_process_package_in_actix(subject, package);
@@ -1287,6 +1506,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                ExitPolicy::default(),
             );
             let (stream_killer_tx, stream_killer_rx) = unbounded();
             subject.stream_killer_rx = stream_killer_rx;
@@ -1389,6 +1609,7 @@ mod tests {
                 peer_actors.proxy_client_opt.clone().unwrap(),
                 100,
                 200,
+                ExitPolicy::default(),
             );
             let (stream_killer_tx, stream_killer_rx) = unbounded();
             subject.stream_killer_rx = stream_killer_rx;
@@ -1508,6 +1729,7 @@ mod tests {
                 peer_actors.proxy_client_opt.clone().unwrap(),
                 100,
                 200,
+                ExitPolicy::default(),
             );
             let (stream_killer_tx, stream_killer_rx) = unbounded();
             subject.stream_killer_rx = stream_killer_rx;
@@ -1618,6 +1840,7 @@ mod tests {
                 peer_actors.proxy_client_opt.clone().unwrap(),
                 100,
                 200,
+                ExitPolicy::default(),
             );
 
             let peer_addr = SocketAddr::from_str("3.4.5.6:80").unwrap();
@@ -1715,6 +1938,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                ExitPolicy::default(),
             );
             subject.inner.lock().unwrap().logger =
                 Logger::new("bad_dns_lookup_produces_log_and_sends_error_response");
@@ -1784,6 +2008,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                ExitPolicy::default(),
             );
             subject.inner.lock().unwrap().stream_writer_channels.insert(
                 stream_key,
@@ -1844,6 +2069,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                ExitPolicy::default(),
             );
 
             subject.inner.lock().unwrap().establisher_factory =
@@ -1880,6 +2106,7 @@ mod tests {
             peer_actors.proxy_client_opt.unwrap(),
             0,
             0,
+            ExitPolicy::default(),
         );
         let (stream_killer_tx, stream_killer_rx) = unbounded();
         subject.stream_killer_rx = stream_killer_rx;
@@ -1931,6 +2158,7 @@ mod tests {
             peer_actors.proxy_client_opt.unwrap(),
             0,
             0,
+            ExitPolicy::default(),
         );
         let (stream_killer_tx, stream_killer_rx) = unbounded();
         subject.stream_killer_rx = stream_killer_rx;
@@ -1973,6 +2201,7 @@ mod tests {
             peer_actors.proxy_client_opt.unwrap(),
             0,
             0,
+            ExitPolicy::default(),
         );
         let (stream_killer_tx, stream_killer_rx) = unbounded();
         subject.stream_killer_rx = stream_killer_rx;
@@ -2000,6 +2229,7 @@ mod tests {
             peer_actors.proxy_client_opt.unwrap(),
             0,
             0,
+            ExitPolicy::default(),
         );
         subject.stream_adder_rx = stream_adder_rx;
         {
@@ -2059,4 +2289,13 @@ mod tests {
             "DEBUG: {test_name}: Persisting StreamWriter to 2.3.4.5:6789 under key 1Kbv+3/MIN4/1hLQXLeNPgdDM58"
         ));
     }
+
+    #[test]
+    fn stream_handler_pool_factory_real_keeps_exit_policy_for_the_pools_it_makes() {
+        let exit_policy = ExitPolicy::from_str("25,private").unwrap();
+
+        let subject = StreamHandlerPoolFactoryReal::new(exit_policy.clone());
+
+        assert_eq!(subject.exit_policy, exit_policy);
+    }
 }
@@ -91,6 +91,55 @@ impl HttpProtocolPack {
         )
     }
 
+    // Turns a request meant for an explicit proxy ("GET http://host/path HTTP/1.1") into the
+    // request the origin server expects ("GET /path HTTP/1.1"). Proxy-only headers are dropped,
+    // and the origin server is asked to close the connection after responding, because a
+    // browser will reuse its connection to the proxy for other hosts, and our stream can't
+    // follow it there.
+    pub fn absolute_to_origin_form(data: &[u8]) -> Option<Vec<u8>> {
+        let headers_end = index_of(data, &b"\r\n\r\n"[..])?;
+        let head = std::str::from_utf8(&data[0..headers_end]).ok()?;
+        let body = &data[(headers_end + 4)..];
+        let mut lines = head.split("\r\n");
+        let mut request_line = lines.next()?.split(' ');
+        let (method, target, version) = match (
+            request_line.next(),
+            request_line.next(),
+            request_line.next(),
+        ) {
+            (Some(method), Some(target), Some(version)) => (method, target, version),
+            _ => return None,
+        };
+        let authority_and_path = target.strip_prefix("http://")?;
+        let (authority, path) = match authority_and_path.find('/') {
+            Some(idx) => authority_and_path.split_at(idx),
+            None => (authority_and_path, "/"),
+        };
+        if authority.is_empty() {
+            return None;
+        }
+        let mut head_lines: Vec<String> = lines
+            .filter(|line| {
+                let lower = line.to_ascii_lowercase();
+                !lower.starts_with("proxy-connection:")
+                    && !lower.starts_with("proxy-authorization:")
+                    && !lower.starts_with("connection:")
+            })
+            .map(|line| line.to_string())
+            .collect();
+        if !head_lines
+            .iter()
+            .any(|line| line.to_ascii_lowercase().starts_with("host:"))
+        {
+            head_lines.insert(0, format!("Host: {}", authority));
+        }
+        head_lines.insert(0, format!("{} {} {}", method, path, version));
+        head_lines.push("Connection: close".to_string());
+        let mut result = format!("{}\r\n\r\n", head_lines.join("\r\n")).into_bytes();
+        result.extend(body);
+        Some(result)
+    }
+
     fn port_from_string(port_str: String) -> Option<u16> {
         match port_str.parse::<u16>() {
             Err(_) => None,
@@ -333,6 +382,65 @@ mod tests {
         assert_eq!(None, host.port);
     }
 
+    #[test]
+    fn absolute_to_origin_form_rewrites_request_line_and_proxy_headers() {
+        let data = b"POST http://example.com:8080/path/page.html?q=1 HTTP/1.1\r\n\
+Host: example.com:8080\r\n\
+Proxy-Connection: keep-alive\r\n\
+proxy-authorization: Basic Ym9vZ2E=\r\n\
+Connection: keep-alive\r\n\
+Content-Length: 5\r\n\
+\r\n\
+booga";
+
+        let result = HttpProtocolPack::absolute_to_origin_form(data);
+
+        assert_eq!(
+            result,
+            Some(
+                b"POST /path/page.html?q=1 HTTP/1.1\r\n\
+Host: example.com:8080\r\n\
+Content-Length: 5\r\n\
+Connection: close\r\n\
+\r\n\
+booga"
+                    .to_vec()
+            )
+        );
+    }
+
+    #[test]
+    fn absolute_to_origin_form_supplies_missing_path_and_host_header() {
+        let data = b"GET http://example.com HTTP/1.0\r\nAccept: */*\r\n\r\n";
+
+        let result = HttpProtocolPack::absolute_to_origin_form(data);
+
+        assert_eq!(
+            result,
+            Some(
+                b"GET / HTTP/1.0\r\nHost: example.com\r\nAccept: */*\r\nConnection: close\r\n\r\n"
+                    .to_vec()
+            )
+        );
+    }
+
+    #[test]
+    fn absolute_to_origin_form_rejects_requests_that_are_not_absolute_http() {
+        vec![
+            &b"GET /index.html HTTP/1.1\r\nHost: example.com\r\n\r\n"[..],
+            &b"GET https://example.com/ HTTP/1.1\r\n\r\n"[..],
+            &b"GET http:/// HTTP/1.1\r\n\r\n"[..],
+            &b"GET http://example.com/\r\n\r\n"[..],
+            &b"GET http://example.com/ HTTP/1.1\r\n"[..],
+        ]
+        .into_iter()
+        .for_each(|data| {
+            let result = HttpProtocolPack::absolute_to_origin_form(data);
+
+            assert_eq!(result, None, "{:?}", String::from_utf8_lossy(data));
+        });
+    }
+
     #[test]
     fn is_connect_true_when_method_is_connect() {
         let data = b"CONNECT server.example.com:80 HTTP/1.1\r\nHost: server.example.com:80\r\nProxy-Authorization: basic aGVsbG86d29ybGQ=\r\n\r\n";
@@ -2,53 +2,71 @@
 
 pub mod client_request_payload_factory;
 pub mod http_protocol_pack;
+pub mod pac_file;
 pub mod protocol_pack;
 pub mod server_impersonator_http;
+pub mod server_impersonator_socks5;
 pub mod server_impersonator_tls;
+pub mod socks5_protocol_pack;
 pub mod tls_protocol_pack;
 
 use crate::proxy_server::client_request_payload_factory::{
     ClientRequestPayloadFactory, ClientRequestPayloadFactoryReal,
 };
 use crate::proxy_server::http_protocol_pack::HttpProtocolPack;
-use crate::proxy_server::protocol_pack::{from_ibcd, from_protocol, ProtocolPack};
+use crate::proxy_server::pac_file::{pac_file_response, PAC_FILE_PATH};
+use crate::proxy_server::protocol_pack::{
+    from_ibcd, from_protocol, Host, ProtocolPack, ServerImpersonator,
+};
+use crate::proxy_server::server_impersonator_socks5::{
+    ServerImpersonatorSocks5, SOCKS5_REPLY_SUCCEEDED,
+};
+use crate::proxy_server::socks5_protocol_pack::Socks5ProtocolPack;
 use crate::proxy_server::ExitServiceSearch::{Definite, ZeroHop};
 use crate::stream_messages::NonClandestineAttributes;
 use crate::stream_messages::RemovedStreamType;
 use crate::sub_lib::accountant::RoutingServiceConsumed;
-use crate::sub_lib::accountant::{ExitServiceConsumed, ReportServicesConsumedMessage};
+use crate::sub_lib::accountant::{
+    ExitServiceConsumed, PrioritizeCreditorMessage, ReportServicesConsumedMessage,
+};
 use crate::sub_lib::bidi_hashmap::BidiHashMap;
 use crate::sub_lib::cryptde::CryptDE;
 use crate::sub_lib::cryptde::PublicKey;
 use crate::sub_lib::dispatcher::InboundClientData;
 use crate::sub_lib::dispatcher::{Endpoint, StreamShutdownMsg};
-use crate::sub_lib::hopper::{ExpiredCoresPackage, IncipientCoresPackage};
+use crate::sub_lib::exit_location::PreferredExits;
+use crate::sub_lib::hopper::{ExpiredCoresPackage, IncipientCoresPackage, ServiceRefused_0v1};
+use crate::sub_lib::http_packet_framer::summarize_http_packet;
 use crate::sub_lib::neighborhood::RouteQueryResponse;
+use crate::sub_lib::neighborhood::{ConfigChange, ConfigChangeMsg, ExpectedServices, RatePack};
 use crate::sub_lib::neighborhood::{ExpectedService, UpdateNodeRecordMetadataMessage};
-use crate::sub_lib::neighborhood::{ExpectedServices, RatePack};
 use crate::sub_lib::neighborhood::{NRMetadataChange, RouteQueryMessage};
 use crate::sub_lib::peer_actors::BindMessage;
 use crate::sub_lib::proxy_client::{ClientResponsePayload_0v1, DnsResolveFailure_0v1};
 use crate::sub_lib::proxy_server::ProxyServerSubs;
 use crate::sub_lib::proxy_server::{AddReturnRouteMessage, StreamKeyPurge};
 use crate::sub_lib::proxy_server::{
-    AddRouteResultMessage, ClientRequestPayload_0v1, ProxyProtocol,
+    AddRouteResultMessage, ClientRequestPayload_0v1, ForwardProxyConfig, ProxyProtocol,
 };
+use crate::sub_lib::proxy_server::{AddStripeRouteMessage, NodesLostMsg, SweepSilentStreams};
 use crate::sub_lib::route::Route;
 use crate::sub_lib::stream_handler_pool::TransmitDataMsg;
 use crate::sub_lib::stream_key::StreamKey;
 use crate::sub_lib::ttl_hashmap::TtlHashMap;
 use crate::sub_lib::utils::{handle_ui_crash_request, MessageScheduler, NODE_MAILBOX_CAPACITY};
+use crate::sub_lib::utils::{NotifyLaterHandle, NotifyLaterHandleReal};
 use crate::sub_lib::wallet::Wallet;
 use actix::Context;
 use actix::Handler;
 use actix::Recipient;
 use actix::{Actor, MailboxError};
 use actix::{Addr, AsyncContext};
+use masq_lib::constants::{HTTP_PORT, SOCKS_PORT, TLS_PORT};
 use masq_lib::logger::Logger;
 use masq_lib::ui_gateway::NodeFromUiMessage;
 use masq_lib::utils::MutabilityConflictHelper;
 use regex::Regex;
+use std::cmp::max;
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::rc::Rc;
@@ -60,15 +78,36 @@ pub const RETURN_ROUTE_TTL: Duration = Duration::from_secs(120);
 
 pub const STREAM_KEY_PURGE_DELAY: Duration = Duration::from_secs(30);
 
+pub const DEFAULT_STICKY_ROUTE_TTL: Duration = Duration::from_secs(300);
+
+// One route per stream unless the user asks for more
+pub const DEFAULT_MULTIPATH_ROUTES: usize = 1;
+
+// A stream whose request has gone this long without a single response packet is presumed to have
+// lost a relay, and its request is replayed over a new route if that can be done safely.
+pub const RELAY_SILENCE_WINDOW: Duration = Duration::from_secs(30);
+pub const SILENT_STREAM_SWEEP_INTERVAL: Duration = Duration::from_secs(5);
+// Keeps a server that is merely slow from being chased around the network forever
+pub const MAX_SILENT_REROUTES: usize = 2;
+
+// Requests with these methods may be sent to the server again without changing what they do
+const IDEMPOTENT_HTTP_METHODS: [&str; 6] = ["GET", "HEAD", "OPTIONS", "TRACE", "PUT", "DELETE"];
+
+// The SOCKS5 greeting and CONNECT request are answered locally and never reach the exit Node,
+// so sequence numbers on a SOCKS5 stream are shifted by this much in each direction.
+pub const SOCKS5_HANDSHAKE_PACKETS: u64 = 2;
+
 struct ProxyServerOutSubs {
     dispatcher: Recipient<TransmitDataMsg>,
     hopper: Recipient<IncipientCoresPackage>,
     accountant: Recipient<ReportServicesConsumedMessage>,
+    prioritize_creditor: Recipient<PrioritizeCreditorMessage>,
     route_source: Recipient<RouteQueryMessage>,
     update_node_record_metadata: Recipient<UpdateNodeRecordMetadataMessage>,
     add_return_route: Recipient<AddReturnRouteMessage>,
     stream_shutdown_sub: Recipient<StreamShutdownMsg>,
     route_result_sub: Recipient<AddRouteResultMessage>,
+    stripe_route_sub: Recipient<AddStripeRouteMessage>,
     schedule_stream_key_purge: Recipient<MessageScheduler<StreamKeyPurge>>,
 }
 
@@ -78,11 +117,19 @@ pub struct ProxyServer {
     stream_key_factory: Box<dyn StreamKeyFactory>,
     keys_and_addrs: BidiHashMap<StreamKey, SocketAddr>,
     tunneled_hosts: HashMap<StreamKey, String>,
+    tunneled_ports: HashMap<StreamKey, u16>,
+    socks_tunnels: HashMap<StreamKey, SocksTunnel>,
     dns_failure_retries: HashMap<StreamKey, DNSFailureRetry>,
     stream_key_routes: HashMap<StreamKey, RouteQueryResponse>,
+    stream_stripes: HashMap<StreamKey, StreamStripes>,
+    multipath_routes: usize,
+    stream_progress: HashMap<StreamKey, StreamProgress>,
+    sticky_routes_opt: Option<TtlHashMap<String, RouteQueryResponse>>,
     stream_key_ttl: HashMap<StreamKey, SystemTime>,
     is_decentralized: bool,
     consuming_wallet_balance: Option<i64>,
+    socks_port_opt: Option<u16>,
+    forward_proxy_config_opt: Option<ForwardProxyConfig>,
     main_cryptde: &'static dyn CryptDE,
     alias_cryptde: &'static dyn CryptDE,
     crashable: bool,
@@ -91,6 +138,9 @@ pub struct ProxyServer {
     browser_proxy_sequence_offset: bool,
     inbound_client_data_helper_opt: Option<Box<dyn IBCDHelper>>,
     stream_key_purge_delay: Duration,
+    relay_silence_window: Duration,
+    notify_later_sweep: Box<dyn NotifyLaterHandle<SweepSilentStreams, ProxyServer>>,
+    preferred_exits_opt: Option<PreferredExits>,
 }
 
 impl Actor for ProxyServer {
@@ -106,14 +156,19 @@ impl Handler<BindMessage> for ProxyServer {
             dispatcher: msg.peer_actors.dispatcher.from_dispatcher_client,
             hopper: msg.peer_actors.hopper.from_hopper_client,
             accountant: msg.peer_actors.accountant.report_services_consumed,
+            prioritize_creditor: msg.peer_actors.accountant.prioritize_creditor,
             route_source: msg.peer_actors.neighborhood.route_query,
             update_node_record_metadata: msg.peer_actors.neighborhood.update_node_record_metadata,
             add_return_route: msg.peer_actors.proxy_server.add_return_route,
             stream_shutdown_sub: msg.peer_actors.proxy_server.stream_shutdown_sub,
             route_result_sub: msg.peer_actors.proxy_server.route_result_sub,
+            stripe_route_sub: msg.peer_actors.proxy_server.stripe_route_sub,
             schedule_stream_key_purge: msg.peer_actors.proxy_server.schedule_stream_key_purge,
         };
         self.subs = Some(subs);
+        if self.is_decentralized {
+            self.schedule_silent_stream_sweep(ctx);
+        }
     }
 }
 
@@ -121,8 +176,14 @@ impl Handler<InboundClientData> for ProxyServer {
     type Result = ();
 
     fn handle(&mut self, msg: InboundClientData, _ctx: &mut Self::Context) -> Self::Result {
-        if msg.is_connect() {
-            self.tls_connect(&msg);
+        if msg.reception_port.is_some() && msg.reception_port == self.socks_port_opt {
+            self.handle_socks_client_data(msg);
+        } else if msg.reception_port.is_some()
+            && msg.reception_port == self.forward_proxy_port_opt()
+        {
+            self.handle_forward_proxy_client_data(msg);
+        } else if msg.is_connect() {
+            self.tls_connect(&msg, &[TLS_PORT]);
             self.browser_proxy_sequence_offset = true;
         } else if let Err(e) =
             self.help(|helper, proxy| helper.handle_normal_client_data(proxy, msg, false))
@@ -136,11 +197,20 @@ impl Handler<AddReturnRouteMessage> for ProxyServer {
     type Result = ();
 
     fn handle(&mut self, msg: AddReturnRouteMessage, _ctx: &mut Self::Context) -> Self::Result {
+        self.report_unpinned_exit(&msg);
         self.route_ids_to_return_routes
             .insert(msg.return_route_id, msg);
     }
 }
 
+impl Handler<ConfigChangeMsg> for ProxyServer {
+    type Result = ();
+
+    fn handle(&mut self, msg: ConfigChangeMsg, _ctx: &mut Self::Context) -> Self::Result {
+        self.handle_config_change_msg(msg)
+    }
+}
+
 impl AddReturnRouteMessage {
     pub fn find_exit_node_key(&self) -> Option<&PublicKey> {
         self.expected_services
@@ -169,23 +239,48 @@ impl Handler<AddRouteResultMessage> for ProxyServer {
 
         match msg.result {
             Ok(route_query_response) => {
+                let hostname_opt = dns_failure.unsuccessful_request.target_hostname.clone();
                 debug!(
                     self.logger,
                     "Found a new route for hostname: {:?} - stream key: {}  retries left: {}",
-                    dns_failure.unsuccessful_request.target_hostname,
+                    hostname_opt,
                     msg.stream_key,
                     dns_failure.retries_left
                 );
+                self.remember_sticky_route(hostname_opt, &route_query_response);
                 self.stream_key_routes
                     .insert(msg.stream_key, route_query_response);
+                self.request_stripe_route(msg.stream_key);
             }
             Err(e) => {
-                warning!(self.logger, "No route found for hostname: {:?} - stream key {} - retries left: {} - AddRouteResultMessage Error: {}",dns_failure.unsuccessful_request.target_hostname, msg.stream_key, dns_failure.retries_left, e);
+                let hostname_opt = dns_failure.unsuccessful_request.target_hostname.clone();
+                warning!(self.logger, "No route found for hostname: {:?} - stream key {} - retries left: {} - AddRouteResultMessage Error: {}",hostname_opt, msg.stream_key, dns_failure.retries_left, e);
+                if let Some(preferred_exits) = self.preferred_exits_opt.as_ref() {
+                    if preferred_exits.strict {
+                        warning!(
+                            self.logger,
+                            "No pinned exit is available for hostname: {:?}; strict pinning to {} refuses every other exit",
+                            hostname_opt,
+                            ProxyServer::pinned_exits_string(preferred_exits)
+                        );
+                    }
+                }
+                if let Some(hostname) = hostname_opt {
+                    self.forget_sticky_route(&hostname, "a route failure");
+                }
             }
         }
     }
 }
 
+impl Handler<AddStripeRouteMessage> for ProxyServer {
+    type Result = ();
+
+    fn handle(&mut self, msg: AddStripeRouteMessage, _ctx: &mut Self::Context) -> Self::Result {
+        self.handle_add_stripe_route_message(msg)
+    }
+}
+
 impl Handler<ExpiredCoresPackage<DnsResolveFailure_0v1>> for ProxyServer {
     type Result = ();
 
@@ -198,6 +293,18 @@ impl Handler<ExpiredCoresPackage<DnsResolveFailure_0v1>> for ProxyServer {
     }
 }
 
+impl Handler<ExpiredCoresPackage<ServiceRefused_0v1>> for ProxyServer {
+    type Result = ();
+
+    fn handle(
+        &mut self,
+        msg: ExpiredCoresPackage<ServiceRefused_0v1>,
+        _ctx: &mut Self::Context,
+    ) -> Self::Result {
+        self.handle_service_refused(msg)
+    }
+}
+
 impl Handler<ExpiredCoresPackage<ClientResponsePayload_0v1>> for ProxyServer {
     type Result = ();
 
@@ -218,6 +325,23 @@ impl Handler<StreamShutdownMsg> for ProxyServer {
     }
 }
 
+impl Handler<NodesLostMsg> for ProxyServer {
+    type Result = ();
+
+    fn handle(&mut self, msg: NodesLostMsg, _ctx: &mut Self::Context) -> Self::Result {
+        self.handle_nodes_lost_msg(msg)
+    }
+}
+
+impl Handler<SweepSilentStreams> for ProxyServer {
+    type Result = ();
+
+    fn handle(&mut self, _msg: SweepSilentStreams, ctx: &mut Self::Context) -> Self::Result {
+        self.sweep_silent_streams();
+        self.schedule_silent_stream_sweep(ctx);
+    }
+}
+
 impl Handler<NodeFromUiMessage> for ProxyServer {
     type Result = ();
 
@@ -246,11 +370,16 @@ where
 }
 
 impl ProxyServer {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         main_cryptde: &'static dyn CryptDE,
         alias_cryptde: &'static dyn CryptDE,
         is_decentralized: bool,
         consuming_wallet_balance: Option<i64>,
+        socks_port_opt: Option<u16>,
+        forward_proxy_config_opt: Option<ForwardProxyConfig>,
+        sticky_route_ttl: Duration,
+        multipath_routes: usize,
         crashable: bool,
     ) -> ProxyServer {
         ProxyServer {
@@ -259,11 +388,24 @@ impl ProxyServer {
             stream_key_factory: Box::new(StreamKeyFactoryReal {}),
             keys_and_addrs: BidiHashMap::new(),
             tunneled_hosts: HashMap::new(),
+            tunneled_ports: HashMap::new(),
+            socks_tunnels: HashMap::new(),
             dns_failure_retries: HashMap::new(),
             stream_key_routes: HashMap::new(),
+            stream_stripes: HashMap::new(),
+            multipath_routes,
+            stream_progress: HashMap::new(),
+            // Zero-hop routes never leave this Node, so there's no exit to keep
+            sticky_routes_opt: if is_decentralized && sticky_route_ttl > Duration::ZERO {
+                Some(TtlHashMap::new(sticky_route_ttl))
+            } else {
+                None
+            },
             stream_key_ttl: HashMap::new(),
             is_decentralized,
             consuming_wallet_balance,
+            socks_port_opt,
+            forward_proxy_config_opt,
             main_cryptde,
             alias_cryptde,
             crashable,
@@ -272,6 +414,9 @@ impl ProxyServer {
             browser_proxy_sequence_offset: false,
             inbound_client_data_helper_opt: Some(Box::new(IBCDHelperReal::new())),
             stream_key_purge_delay: STREAM_KEY_PURGE_DELAY,
+            relay_silence_window: RELAY_SILENCE_WINDOW,
+            notify_later_sweep: Box::new(NotifyLaterHandleReal::new()),
+            preferred_exits_opt: None,
         }
     }
 
@@ -281,14 +426,543 @@ impl ProxyServer {
             from_dispatcher: recipient!(addr, InboundClientData),
             from_hopper: recipient!(addr, ExpiredCoresPackage<ClientResponsePayload_0v1>),
             dns_failure_from_hopper: recipient!(addr, ExpiredCoresPackage<DnsResolveFailure_0v1>),
+            service_refused_from_hopper: recipient!(addr, ExpiredCoresPackage<ServiceRefused_0v1>),
             add_return_route: recipient!(addr, AddReturnRouteMessage),
             stream_shutdown_sub: recipient!(addr, StreamShutdownMsg),
             node_from_ui: recipient!(addr, NodeFromUiMessage),
             route_result_sub: recipient!(addr, AddRouteResultMessage),
             schedule_stream_key_purge: recipient!(addr, MessageScheduler<StreamKeyPurge>),
+            config_change_msg_sub: recipient!(addr, ConfigChangeMsg),
+            nodes_lost_sub: recipient!(addr, NodesLostMsg),
+            stripe_route_sub: recipient!(addr, AddStripeRouteMessage),
+        }
+    }
+
+    fn handle_config_change_msg(&mut self, msg: ConfigChangeMsg) {
+        match msg.change {
+            ConfigChange::UpdatePreferredExits(preferred_exits_opt) => {
+                debug!(
+                    self.logger,
+                    "Pinned exits changed to {:?}",
+                    preferred_exits_opt.as_ref().map(|pe| pe.to_string())
+                );
+                self.preferred_exits_opt = preferred_exits_opt;
+                self.forget_sticky_routes("pinned exits changed");
+            }
+            ConfigChange::UpdateExcludedNodes(_) => {
+                self.forget_sticky_routes("excluded Nodes changed")
+            }
+            ConfigChange::UpdateMaxRatePack(_) => {
+                self.forget_sticky_routes("max rate pack changed")
+            }
+            ConfigChange::UpdateMinHops(_) => self.forget_sticky_routes("min hops changed"),
+            ConfigChange::UpdatePassword(_) | ConfigChange::UpdateWallets(_) => (),
+        }
+    }
+
+    fn sticky_route(&self, payload: &ClientRequestPayload_0v1) -> Option<RouteQueryResponse> {
+        match (
+            self.sticky_routes_opt.as_ref(),
+            payload.target_hostname.as_ref(),
+        ) {
+            (Some(sticky_routes), Some(hostname)) => sticky_routes
+                .get(hostname)
+                .map(|route_query_response| route_query_response.as_ref().clone()),
+            _ => None,
+        }
+    }
+
+    fn remember_sticky_route(
+        &mut self,
+        hostname_opt: Option<String>,
+        route_query_response: &RouteQueryResponse,
+    ) {
+        if let (Some(sticky_routes), Some(hostname)) =
+            (self.sticky_routes_opt.as_mut(), hostname_opt)
+        {
+            debug!(
+                self.logger,
+                "New streams to {} will reuse this route until it goes unused for {}s",
+                hostname,
+                sticky_routes.ttl().as_secs()
+            );
+            sticky_routes.insert(hostname, route_query_response.clone());
+        }
+    }
+
+    fn forget_sticky_route(&mut self, hostname: &str, reason: &str) {
+        if let Some(sticky_routes) = self.sticky_routes_opt.as_mut() {
+            if sticky_routes.remove(&hostname.to_string()).is_some() {
+                debug!(
+                    self.logger,
+                    "Forgot the route to {} after {}", hostname, reason
+                );
+            }
+        }
+    }
+
+    fn forget_sticky_routes(&mut self, reason: &str) {
+        if let Some(sticky_routes) = self.sticky_routes_opt.as_mut() {
+            sticky_routes.clear();
+            debug!(self.logger, "Forgot all sticky routes: {}", reason);
+        }
+    }
+
+    // A stream's first route stays in stream_key_routes; routes found later to the same exit through
+    // other relays take turns with it, packet by packet.
+    fn next_stream_route(&mut self, stream_key: &StreamKey) -> Option<RouteQueryResponse> {
+        let route_query_response = self.stream_key_routes.get(stream_key)?;
+        match self.stream_stripes.get_mut(stream_key) {
+            Some(stripes) if !stripes.routes.is_empty() => {
+                let index = stripes.next_route % (stripes.routes.len() + 1);
+                stripes.next_route = index + 1;
+                match index {
+                    0 => Some(route_query_response.clone()),
+                    _ => Some(stripes.routes[index - 1].clone()),
+                }
+            }
+            _ => Some(route_query_response.clone()),
+        }
+    }
+
+    fn request_stripe_route(&self, stream_key: StreamKey) {
+        if !self.is_decentralized || self.stream_route_count(&stream_key) >= self.multipath_routes {
+            return;
+        }
+        let exit_key = match self
+            .stream_key_routes
+            .get(&stream_key)
+            .and_then(ProxyServer::exit_key)
+        {
+            Some(exit_key) => exit_key,
+            None => return,
+        };
+        let (hostname_opt, target_port_opt, payload_size) =
+            match self.dns_failure_retries.get(&stream_key) {
+                Some(retry) => (
+                    retry.unsuccessful_request.target_hostname.clone(),
+                    Some(retry.unsuccessful_request.target_port),
+                    retry.unsuccessful_request.sequenced_packet.data.len(),
+                ),
+                None => (None, None, 0),
+            };
+        let route_query = RouteQueryMessage::data_disjoint_route_request(
+            exit_key,
+            self.stream_relay_keys(&stream_key),
+            hostname_opt,
+            target_port_opt,
+            payload_size,
+        );
+        let stripe_route_sub = self.out_subs("ProxyServer").stripe_route_sub.clone();
+        tokio::spawn(
+            self.out_subs("Neighborhood")
+                .route_source
+                .send(route_query)
+                .then(move |route_result| {
+                    stripe_route_sub
+                        .try_send(AddStripeRouteMessage {
+                            stream_key,
+                            route_query_response_opt: route_result.unwrap_or(None),
+                        })
+                        .expect("ProxyServer is dead");
+                    Ok(())
+                }),
+        );
+    }
+
+    fn handle_add_stripe_route_message(&mut self, msg: AddStripeRouteMessage) {
+        let stream_key = msg.stream_key;
+        let route_query_response = match msg.route_query_response_opt {
+            Some(route_query_response) => route_query_response,
+            None => {
+                debug!(
+                    self.logger,
+                    "Found no more routes for stream {} that avoid the relays it uses; it stays on {}",
+                    stream_key,
+                    self.stream_route_count(&stream_key)
+                );
+                return;
+            }
+        };
+        // While the route was being found, the stream may have closed, moved to another exit, or
+        // picked up a stripe through the same relays.
+        let exit_key_opt = self
+            .stream_key_routes
+            .get(&stream_key)
+            .and_then(ProxyServer::exit_key);
+        let stream_relay_keys = self.stream_relay_keys(&stream_key);
+        if exit_key_opt.is_none()
+            || ProxyServer::exit_key(&route_query_response) != exit_key_opt
+            || self.stream_route_count(&stream_key) >= self.multipath_routes
+            || ProxyServer::relay_keys(&route_query_response)
+                .iter()
+                .any(|relay_key| stream_relay_keys.contains(relay_key))
+        {
+            debug!(
+                self.logger,
+                "Discarding a stripe route that no longer suits stream {}", stream_key
+            );
+            return;
+        }
+        let stripes = self.stream_stripes.entry(stream_key).or_default();
+        stripes.routes.push(route_query_response);
+        debug!(
+            self.logger,
+            "Striping stream {} across {} routes",
+            stream_key,
+            stripes.routes.len() + 1
+        );
+        self.request_stripe_route(stream_key);
+    }
+
+    fn stream_route_count(&self, stream_key: &StreamKey) -> usize {
+        match self.stream_key_routes.get(stream_key) {
+            Some(_) => {
+                1 + self
+                    .stream_stripes
+                    .get(stream_key)
+                    .map_or(0, |stripes| stripes.routes.len())
+            }
+            None => 0,
+        }
+    }
+
+    fn stream_relay_keys(&self, stream_key: &StreamKey) -> Vec<PublicKey> {
+        let mut relay_keys = self
+            .stream_key_routes
+            .get(stream_key)
+            .into_iter()
+            .chain(
+                self.stream_stripes
+                    .get(stream_key)
+                    .into_iter()
+                    .flat_map(|stripes| stripes.routes.iter()),
+            )
+            .flat_map(ProxyServer::relay_keys)
+            .collect::<Vec<PublicKey>>();
+        relay_keys.sort();
+        relay_keys.dedup();
+        relay_keys
+    }
+
+    fn exit_key(route_query_response: &RouteQueryResponse) -> Option<PublicKey> {
+        match &route_query_response.expected_services {
+            ExpectedServices::RoundTrip(over, _, _) => {
+                over.iter().find_map(|service| match service {
+                    ExpectedService::Exit(public_key, _, _) => Some(public_key.clone()),
+                    _ => None,
+                })
+            }
+            ExpectedServices::OneWay(_) => None,
+        }
+    }
+
+    fn relay_keys(route_query_response: &RouteQueryResponse) -> Vec<PublicKey> {
+        let services = match &route_query_response.expected_services {
+            ExpectedServices::OneWay(services) => services.iter().collect::<Vec<_>>(),
+            ExpectedServices::RoundTrip(over, back, _) => over.iter().chain(back.iter()).collect(),
+        };
+        services
+            .into_iter()
+            .filter_map(|service| match service {
+                ExpectedService::Routing(public_key, _, _) => Some(public_key.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn return_route_id(route_query_response: &RouteQueryResponse) -> Option<u32> {
+        match route_query_response.expected_services {
+            ExpectedServices::RoundTrip(_, _, return_route_id) => Some(return_route_id),
+            ExpectedServices::OneWay(_) => None,
+        }
+    }
+
+    fn schedule_silent_stream_sweep(&self, ctx: &mut Context<ProxyServer>) {
+        let _ = self.notify_later_sweep.notify_later(
+            SweepSilentStreams {},
+            SILENT_STREAM_SWEEP_INTERVAL,
+            ctx,
+        );
+    }
+
+    fn handle_nodes_lost_msg(&mut self, msg: NodesLostMsg) {
+        let lost_keys = msg.public_keys;
+        if let Some(sticky_routes) = self.sticky_routes_opt.as_mut() {
+            sticky_routes.retain(|_, route_query_response| {
+                !ProxyServer::route_passes_through(route_query_response, &lost_keys)
+            });
+        }
+        // A stream striped across several routes moves as a whole, so that its exit never sends
+        // responses down a route that has lost a Node.
+        let stream_keys = self
+            .stream_key_routes
+            .iter()
+            .filter(|(stream_key, route_query_response)| {
+                ProxyServer::route_passes_through(route_query_response, &lost_keys)
+                    || self.stream_stripes.get(stream_key).is_some_and(|stripes| {
+                        stripes.routes.iter().any(|stripe_route| {
+                            ProxyServer::route_passes_through(stripe_route, &lost_keys)
+                        })
+                    })
+            })
+            .map(|(stream_key, _)| *stream_key)
+            .collect::<Vec<StreamKey>>();
+        stream_keys.iter().for_each(|stream_key| {
+            self.reroute_stream(*stream_key, "a Node on its route dropped out")
+        });
+    }
+
+    fn route_passes_through(
+        route_query_response: &RouteQueryResponse,
+        public_keys: &[PublicKey],
+    ) -> bool {
+        let services = match &route_query_response.expected_services {
+            ExpectedServices::OneWay(services) => services.iter().collect::<Vec<_>>(),
+            ExpectedServices::RoundTrip(over, back, _) => over.iter().chain(back.iter()).collect(),
+        };
+        services.into_iter().any(|service| match service {
+            ExpectedService::Routing(public_key, _, _)
+            | ExpectedService::Exit(public_key, _, _) => public_keys.contains(public_key),
+            ExpectedService::Nothing => false,
+        })
+    }
+
+    fn sweep_silent_streams(&mut self) {
+        let now = SystemTime::now();
+        let silent_stream_keys = self
+            .stream_progress
+            .iter()
+            .filter(|(stream_key, progress)| {
+                self.stream_key_routes.contains_key(stream_key)
+                    && progress.silent_reroutes < MAX_SILENT_REROUTES
+                    && progress.can_replay()
+                    && progress.unanswered_since_opt.is_some_and(|since| {
+                        now.duration_since(since).unwrap_or_default() >= self.relay_silence_window
+                    })
+            })
+            .map(|(stream_key, _)| *stream_key)
+            .collect::<Vec<StreamKey>>();
+        let reason = format!(
+            "{}s without a response",
+            self.relay_silence_window.as_secs()
+        );
+        silent_stream_keys.iter().for_each(|stream_key| {
+            if let Some(progress) = self.stream_progress.get_mut(stream_key) {
+                progress.silent_reroutes += 1;
+            }
+            self.reroute_stream(*stream_key, &reason)
+        });
+    }
+
+    fn reroute_stream(&mut self, stream_key: StreamKey, reason: &str) {
+        let client_addr = match self.keys_and_addrs.a_to_b(&stream_key) {
+            Some(client_addr) => client_addr,
+            None => return,
+        };
+        // No route means a new one is already being looked up
+        let route_query_response = match self.stream_key_routes.remove(&stream_key) {
+            Some(route_query_response) => route_query_response,
+            None => return,
+        };
+        let progress = match self.stream_progress.get_mut(&stream_key) {
+            Some(progress) => progress,
+            None => return,
+        };
+        let stripe_routes = self
+            .stream_stripes
+            .remove(&stream_key)
+            .map_or(vec![], |stripes| stripes.routes);
+        progress.abandoned_return_route_ids.extend(
+            std::iter::once(&route_query_response)
+                .chain(stripe_routes.iter())
+                .filter_map(ProxyServer::return_route_id),
+        );
+        let hostname_opt = progress.hostname_opt.clone();
+        let fresh = progress.next_response_sequence_number == 0;
+        let replay_opt = if progress.can_replay() {
+            progress.unanswered_request_opt.take()
+        } else {
+            None
+        };
+        // An unanswered request we can't send again is lost, and so is any session state the
+        // old exit was keeping for an encrypted or tunneled stream.
+        let request_lost = progress.unanswered_packets > 0 && replay_opt.is_none();
+        let session_lost = !fresh && progress.protocol != ProxyProtocol::HTTP;
+        if !request_lost && !session_lost {
+            if !fresh {
+                progress.sequence_rebase_opt = Some(SequenceRebase {
+                    request_base_opt: None,
+                    response_base: progress.next_response_sequence_number,
+                });
+            }
+            progress.unanswered_since_opt = None;
+            progress.unanswered_packets = 0;
+        }
+        if let Some(hostname) = hostname_opt.as_ref() {
+            self.forget_sticky_route(hostname, reason);
+        }
+        let hostname = hostname_opt.unwrap_or_else(|| "<unknown>".to_string());
+        if request_lost || session_lost {
+            warning!(
+                self.logger,
+                "Can't move stream {} to {} onto a new route after {}; closing it",
+                stream_key,
+                hostname,
+                reason
+            );
+            self.close_client_stream(&stream_key, client_addr);
+            return;
+        }
+        match replay_opt {
+            Some(request) => {
+                info!(
+                    self.logger,
+                    "Rerouting stream {} to {} after {}; replaying its unanswered request",
+                    stream_key,
+                    hostname,
+                    reason
+                );
+                self.replay_request(request, client_addr);
+            }
+            None => info!(
+                self.logger,
+                "Rerouting stream {} to {} after {}; its next data will take a new route",
+                stream_key,
+                hostname,
+                reason
+            ),
+        }
+    }
+
+    fn replay_request(&mut self, request: ClientRequestPayload_0v1, client_addr: SocketAddr) {
+        let request = self.rebase_request(request);
+        self.note_request(&request);
+        self.dns_failure_retries
+            .entry(request.stream_key)
+            .or_insert_with(|| DNSFailureRetry {
+                unsuccessful_request: request.clone(),
+                retries_left: 3,
+            });
+        self.request_new_route(request, client_addr);
+    }
+
+    fn close_client_stream(&mut self, stream_key: &StreamKey, client_addr: SocketAddr) {
+        let next_response_sequence_number = self
+            .stream_progress
+            .get(stream_key)
+            .map_or(0, |progress| progress.next_response_sequence_number);
+        self.out_subs("Dispatcher")
+            .dispatcher
+            .try_send(TransmitDataMsg {
+                endpoint: Endpoint::Socket(client_addr),
+                last_data: true,
+                sequence_number: Some(
+                    next_response_sequence_number + self.response_sequence_offset(stream_key),
+                ),
+                data: vec![],
+            })
+            .expect("Dispatcher is dead");
+        self.purge_stream_key(stream_key, "losing its route");
+    }
+
+    fn note_request(&mut self, payload: &ClientRequestPayload_0v1) {
+        if !self.is_decentralized || payload.sequenced_packet.data.is_empty() {
+            return;
+        }
+        let progress = self
+            .stream_progress
+            .entry(payload.stream_key)
+            .or_insert_with(|| StreamProgress::new(payload.protocol));
+        progress.hostname_opt = payload.target_hostname.clone();
+        if progress.unanswered_since_opt.is_none() {
+            progress.unanswered_since_opt = Some(SystemTime::now());
+            progress.unanswered_request_opt = Some(payload.clone());
+        }
+        progress.unanswered_packets += 1;
+    }
+
+    // Returns the sequence number the response would have had if the stream had never changed
+    // routes: a new exit Node numbers its responses from zero.
+    fn note_response(&mut self, stream_key: &StreamKey, sequence_number: u64) -> u64 {
+        match self.stream_progress.get_mut(stream_key) {
+            None => sequence_number,
+            Some(progress) => {
+                let sequence_number = sequence_number
+                    + progress
+                        .sequence_rebase_opt
+                        .map_or(0, |rebase| rebase.response_base);
+                progress.unanswered_since_opt = None;
+                progress.unanswered_request_opt = None;
+                progress.unanswered_packets = 0;
+                progress.next_response_sequence_number =
+                    max(progress.next_response_sequence_number, sequence_number + 1);
+                sequence_number
+            }
+        }
+    }
+
+    // A new exit Node expects a stream's requests to be numbered from zero.
+    fn rebase_request(
+        &mut self,
+        mut payload: ClientRequestPayload_0v1,
+    ) -> ClientRequestPayload_0v1 {
+        if let Some(rebase) = self
+            .stream_progress
+            .get_mut(&payload.stream_key)
+            .and_then(|progress| progress.sequence_rebase_opt.as_mut())
+        {
+            let sequence_number = payload.sequenced_packet.sequence_number;
+            let request_base = *rebase.request_base_opt.get_or_insert(sequence_number);
+            payload.sequenced_packet.sequence_number = sequence_number.saturating_sub(request_base);
+        }
+        payload
+    }
+
+    fn is_awaiting_new_route(&self, stream_key: &StreamKey) -> bool {
+        self.stream_progress
+            .get(stream_key)
+            .and_then(|progress| progress.sequence_rebase_opt)
+            .is_some_and(|rebase| rebase.request_base_opt.is_none())
+    }
+
+    fn is_from_abandoned_route(&self, stream_key: &StreamKey, return_route_id: u32) -> bool {
+        self.stream_progress
+            .get(stream_key)
+            .is_some_and(|progress| {
+                progress
+                    .abandoned_return_route_ids
+                    .contains(&return_route_id)
+            })
+    }
+
+    // A strict pin never yields another exit, so only preferred pins can end up here.
+    fn report_unpinned_exit(&self, return_route_info: &AddReturnRouteMessage) {
+        if let (Some(preferred_exits), Some(exit_key)) = (
+            self.preferred_exits_opt.as_ref(),
+            return_route_info.find_exit_node_key(),
+        ) {
+            if !preferred_exits.admits(exit_key) {
+                warning!(
+                    self.logger,
+                    "Pinned exit {} unavailable for hostname: {:?}; routing through exit {} instead",
+                    ProxyServer::pinned_exits_string(preferred_exits),
+                    return_route_info.hostname_opt,
+                    exit_key
+                );
+            }
         }
     }
 
+    fn pinned_exits_string(preferred_exits: &PreferredExits) -> String {
+        preferred_exits
+            .public_keys
+            .iter()
+            .map(|key| key.to_string())
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
     fn remove_dns_failure_retry(
         &mut self,
         stream_key: &StreamKey,
@@ -307,13 +981,13 @@ impl ProxyServer {
         retry: DNSFailureRetry,
         client_addr: SocketAddr,
     ) -> DNSFailureRetry {
-        let args = TryTransmitToHopperArgs::new(
-            self,
-            retry.unsuccessful_request.clone(),
-            client_addr,
-            SystemTime::now(),
-            false,
-        );
+        self.request_new_route(retry.unsuccessful_request.clone(), client_addr);
+        retry
+    }
+
+    fn request_new_route(&self, payload: ClientRequestPayload_0v1, client_addr: SocketAddr) {
+        let args =
+            TryTransmitToHopperArgs::new(self, payload, client_addr, SystemTime::now(), false);
         let route_source = self.out_subs("Neighborhood").route_source.clone();
         let proxy_server_sub = self.out_subs("ProxyServer").route_result_sub.clone();
         let inbound_client_data_helper = self
@@ -322,7 +996,6 @@ impl ProxyServer {
             .expect("IBCDHelper uninitialized");
 
         inbound_client_data_helper.request_route_and_transmit(args, route_source, proxy_server_sub);
-        retry
     }
 
     fn retire_stream_key(&mut self, stream_key: &StreamKey) {
@@ -380,6 +1053,7 @@ impl ProxyServer {
         match self.keys_and_addrs.a_to_b(&response.stream_key) {
             Some(client_addr) => {
                 if let Some(server_name) = hostname_opt.clone() {
+                    self.forget_sticky_route(&server_name, "a DNS resolution failure");
                     self.subs
                         .as_ref()
                         .expect("Neighborhood unbound in ProxyServer")
@@ -433,12 +1107,93 @@ impl ProxyServer {
         }
     }
 
-    fn schedule_stream_key_purge(&mut self, stream_key: StreamKey) {
-        let host_info = match self.tunneled_hosts.get(&stream_key) {
-            None => String::from(""),
-            Some(hostname) => format!(", which was tunneling to the host {:?}", hostname),
+    // An exit that refuses us for delinquency will go on refusing until it's paid, so we tell the
+    // browser why, steer new routes away from the exit, and pay it on the next payable scan.
+    fn handle_service_refused(&mut self, msg: ExpiredCoresPackage<ServiceRefused_0v1>) {
+        let return_route_info =
+            match self.get_return_route_info(&msg.remaining_route, "service refusal") {
+                Some(rri) => rri,
+                None => return,
+            };
+        let notice = msg.payload;
+        let (exit_public_key, exit_wallet) = match return_route_info
+            .expected_services
+            .iter()
+            .find_map(|service| match service {
+                ExpectedService::Exit(public_key, wallet, _) => {
+                    Some((public_key.clone(), wallet.clone()))
+                }
+                _ => None,
+            }) {
+            Some(exit) => exit,
+            None => {
+                warning!(
+                    self.logger,
+                    "Ignoring refusal of service on stream {} from a route with no exit Node",
+                    notice.stream_key
+                );
+                return;
+            }
         };
-        debug!(
+        if !notice.is_signed_by(&exit_public_key, self.main_cryptde) {
+            warning!(
+                self.logger,
+                "Ignoring refusal of service on stream {} that was not signed by exit Node {}",
+                notice.stream_key,
+                exit_public_key
+            );
+            return;
+        }
+        warning!(
+            self.logger,
+            "Exit Node {} refused service on stream {}: {:?}",
+            exit_public_key,
+            notice.stream_key,
+            notice.reason
+        );
+        self.report_response_services_consumed(&return_route_info, 0, msg.payload_len);
+        if let Some(hostname) = return_route_info.hostname_opt.as_ref() {
+            self.forget_sticky_route(hostname, "a refusal of service");
+        }
+        let subs = self.out_subs("Neighborhood");
+        subs.update_node_record_metadata
+            .try_send(UpdateNodeRecordMetadataMessage {
+                public_key: exit_public_key,
+                metadata_change: NRMetadataChange::ServiceRefused,
+            })
+            .expect("Neighborhood is dead");
+        subs.prioritize_creditor
+            .try_send(PrioritizeCreditorMessage {
+                wallet: exit_wallet,
+            })
+            .expect("Accountant is dead");
+        if let Some(client_addr) = self.keys_and_addrs.a_to_b(&notice.stream_key) {
+            let sequence_number = self
+                .stream_progress
+                .get(&notice.stream_key)
+                .map_or(0, |progress| progress.next_response_sequence_number)
+                + self.response_sequence_offset(&notice.stream_key);
+            self.out_subs("Dispatcher")
+                .dispatcher
+                .try_send(TransmitDataMsg {
+                    endpoint: Endpoint::Socket(client_addr),
+                    last_data: true,
+                    sequence_number: Some(sequence_number),
+                    data: from_protocol(return_route_info.protocol)
+                        .server_impersonator()
+                        .service_refused_response(),
+                })
+                .expect("Dispatcher is dead");
+            self.purge_stream_key(&notice.stream_key, "service refusal");
+        }
+    }
+
+    fn schedule_stream_key_purge(&mut self, stream_key: StreamKey) {
+        let host_info = match self.tunneled_hosts.get(&stream_key) {
+            None => String::from(""),
+            Some(hostname) => format!(", which was tunneling to the host {:?}", hostname),
+        };
+        debug!(
             self.logger,
             "Client closed stream referenced by stream key {:?}{}. It will be purged after {:?}.",
             &stream_key,
@@ -503,6 +1258,15 @@ impl ProxyServer {
             payload_data_len,
         );
         let stream_key = response.stream_key;
+        if self.is_from_abandoned_route(&stream_key, return_route_info.return_route_id) {
+            debug!(
+                self.logger,
+                "Discarding late response {} on stream {} from the route it was moved off",
+                response.sequenced_packet.sequence_number,
+                stream_key
+            );
+            return;
+        }
         match self.remove_dns_failure_retry(&stream_key) {
             Ok(_) => {
                 debug!(self.logger, "Successful attempt of DNS resolution, removing DNS retry entry for stream key: {}", &response.stream_key)
@@ -521,9 +1285,10 @@ impl ProxyServer {
         match self.keys_and_addrs.a_to_b(&stream_key) {
             Some(socket_addr) => {
                 let last_data = response.sequenced_packet.last_data;
+                let sequence_offset = self.response_sequence_offset(&stream_key);
                 let sequence_number = Some(
-                    response.sequenced_packet.sequence_number
-                        + self.browser_proxy_sequence_offset as u64,
+                    self.note_response(&stream_key, response.sequenced_packet.sequence_number)
+                        + sequence_offset,
                 );
                 self.subs
                     .as_ref()
@@ -557,12 +1322,26 @@ impl ProxyServer {
         }
     }
 
-    fn tls_connect(&mut self, msg: &InboundClientData) {
+    fn response_sequence_offset(&self, stream_key: &StreamKey) -> u64 {
+        if self.socks_tunnels.contains_key(stream_key) {
+            SOCKS5_HANDSHAKE_PACKETS
+        } else if self.tunneled_hosts.contains_key(stream_key) {
+            1
+        } else {
+            self.browser_proxy_sequence_offset as u64
+        }
+    }
+
+    fn tls_connect(&mut self, msg: &InboundClientData, allowed_ports: &[u16]) {
         let http_data = HttpProtocolPack {}.find_host(&msg.data.clone().into());
         match http_data {
-            Some(ref host) if host.port == Some(443) => {
+            Some(Host {
+                name,
+                port: Some(port),
+            }) if allowed_ports.contains(&port) => {
                 let stream_key = self.find_or_generate_stream_key(msg);
-                self.tunneled_hosts.insert(stream_key, host.name.clone());
+                self.tunneled_hosts.insert(stream_key, name);
+                self.tunneled_ports.insert(stream_key, port);
                 self.subs
                     .as_ref()
                     .expect("Dispatcher unbound in ProxyServer")
@@ -591,6 +1370,184 @@ impl ProxyServer {
         }
     }
 
+    fn forward_proxy_port_opt(&self) -> Option<u16> {
+        self.forward_proxy_config_opt
+            .as_ref()
+            .map(|forward_proxy_config| forward_proxy_config.port)
+    }
+
+    fn handle_forward_proxy_client_data(&mut self, msg: InboundClientData) {
+        let is_tunneled = match self.keys_and_addrs.b_to_a(&msg.peer_addr) {
+            Some(stream_key) => self.tunneled_hosts.contains_key(&stream_key),
+            None => false,
+        };
+        let ibcd = if is_tunneled {
+            InboundClientData {
+                reception_port: Some(TLS_PORT),
+                ..msg
+            }
+        } else if msg.is_connect() {
+            let connect_ports = self
+                .forward_proxy_config_opt
+                .as_ref()
+                .expect("Forward proxy is not configured")
+                .connect_ports
+                .clone();
+            self.tls_connect(&msg, &connect_ports);
+            return;
+        } else {
+            match HttpProtocolPack::absolute_to_origin_form(&msg.data) {
+                Some(data) => InboundClientData {
+                    reception_port: Some(HTTP_PORT),
+                    data,
+                    ..msg
+                },
+                None => {
+                    self.answer_origin_form_request(&msg);
+                    return;
+                }
+            }
+        };
+        if let Err(e) =
+            self.help(|helper, proxy| helper.handle_normal_client_data(proxy, ibcd, false))
+        {
+            error!(self.logger, "{}", e)
+        }
+    }
+
+    // A request that doesn't name a target server is meant for us, not for the MASQ Network.
+    fn answer_origin_form_request(&self, msg: &InboundClientData) {
+        let pac_request_line = format!("GET {} HTTP/", PAC_FILE_PATH);
+        let data = if msg.data.starts_with(pac_request_line.as_bytes()) {
+            debug!(self.logger, "Serving PAC file to {}", msg.peer_addr);
+            pac_file_response(
+                self.forward_proxy_port_opt()
+                    .expect("Forward proxy is not configured"),
+                self.socks_port_opt,
+            )
+        } else {
+            warning!(
+                self.logger,
+                "Refusing forward-proxy request from {} with no absolute URI: {}",
+                msg.peer_addr,
+                summarize_http_packet(&msg.data)
+            );
+            b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_vec()
+        };
+        self.out_subs("Dispatcher")
+            .dispatcher
+            .try_send(TransmitDataMsg {
+                endpoint: Endpoint::Socket(msg.peer_addr),
+                last_data: true,
+                sequence_number: msg.sequence_number,
+                data,
+            })
+            .expect("Dispatcher is dead");
+    }
+
+    fn handle_socks_client_data(&mut self, msg: InboundClientData) {
+        let stream_key = self.find_or_generate_stream_key(&msg);
+        match self.socks_tunnels.get(&stream_key) {
+            None => self.socks_greeting(&msg, stream_key),
+            Some(SocksTunnel::AwaitingRequest) => self.socks_connect(&msg, stream_key),
+            Some(SocksTunnel::Established { .. }) => {
+                let ibcd = InboundClientData {
+                    reception_port: Some(SOCKS_PORT),
+                    ..msg
+                };
+                if let Err(e) =
+                    self.help(|helper, proxy| helper.handle_normal_client_data(proxy, ibcd, false))
+                {
+                    error!(self.logger, "{}", e)
+                }
+            }
+            Some(SocksTunnel::Refused) => debug!(
+                self.logger,
+                "Ignoring {} bytes from {} on refused SOCKS5 stream {}",
+                msg.data.len(),
+                msg.peer_addr,
+                stream_key
+            ),
+        }
+    }
+
+    fn socks_greeting(&mut self, msg: &InboundClientData, stream_key: StreamKey) {
+        let (tunnel, reply, last_data) = match Socks5ProtocolPack::method_selection_reply(&msg.data)
+        {
+            Ok(reply) => (SocksTunnel::AwaitingRequest, reply, false),
+            Err(reply) => {
+                warning!(
+                    self.logger,
+                    "Refusing SOCKS5 client at {}: no acceptable authentication method in {:?}",
+                    msg.peer_addr,
+                    msg.data
+                );
+                (SocksTunnel::Refused, reply, true)
+            }
+        };
+        self.socks_tunnels.insert(stream_key, tunnel);
+        self.send_socks_reply(msg, reply, last_data);
+    }
+
+    fn socks_connect(&mut self, msg: &InboundClientData, stream_key: StreamKey) {
+        let (tunnel, reply, last_data) =
+            if self.consuming_wallet_balance.is_none() && self.is_decentralized {
+                warning!(
+                    self.logger,
+                    "Refusing SOCKS5 CONNECT from {} due to missing consuming wallet",
+                    msg.peer_addr
+                );
+                let reply = ServerImpersonatorSocks5 {}.consuming_wallet_absent();
+                (SocksTunnel::Refused, reply, true)
+            } else {
+                match Socks5ProtocolPack::parse_connect_request(&msg.data) {
+                    Ok(Host {
+                        name: hostname,
+                        port,
+                    }) => {
+                        debug!(
+                            self.logger,
+                            "Opening SOCKS5 tunnel to {}:{:?} for stream key {}",
+                            hostname,
+                            port,
+                            stream_key
+                        );
+                        let tunnel = SocksTunnel::Established {
+                            hostname,
+                            port: port.expect("SOCKS5 CONNECT requests always carry a port"),
+                        };
+                        let reply = ServerImpersonatorSocks5::reply(SOCKS5_REPLY_SUCCEEDED);
+                        (tunnel, reply, false)
+                    }
+                    Err(reply_code) => {
+                        warning!(
+                            self.logger,
+                            "Refusing SOCKS5 request from {} with reply code {}: {:?}",
+                            msg.peer_addr,
+                            reply_code,
+                            msg.data
+                        );
+                        let reply = ServerImpersonatorSocks5::reply(reply_code);
+                        (SocksTunnel::Refused, reply, true)
+                    }
+                }
+            };
+        self.socks_tunnels.insert(stream_key, tunnel);
+        self.send_socks_reply(msg, reply, last_data);
+    }
+
+    fn send_socks_reply(&self, msg: &InboundClientData, data: Vec<u8>, last_data: bool) {
+        self.out_subs("Dispatcher")
+            .dispatcher
+            .try_send(TransmitDataMsg {
+                endpoint: Endpoint::Socket(msg.peer_addr),
+                last_data,
+                sequence_number: msg.sequence_number,
+                data,
+            })
+            .expect("Dispatcher is dead");
+    }
+
     fn out_subs(&self, actor_name: &str) -> &ProxyServerOutSubs {
         self.subs
             .as_ref()
@@ -616,7 +1573,32 @@ impl ProxyServer {
             Some(sk) => sk,
         };
         self.schedule_stream_key_purge(stream_key);
-        if msg.report_to_counterpart {
+        if msg.report_to_counterpart && self.is_awaiting_new_route(&stream_key) {
+            debug!(
+                self.logger,
+                "Stream {} was closed before it reached its new route; no counterpart to report to",
+                &stream_key
+            );
+        } else if msg.report_to_counterpart {
+            let reception_port = match self.socks_tunnels.get(&stream_key) {
+                None if Some(nca.reception_port) == self.forward_proxy_port_opt() => {
+                    if self.tunneled_hosts.contains_key(&stream_key) {
+                        TLS_PORT
+                    } else {
+                        HTTP_PORT
+                    }
+                }
+                None => nca.reception_port,
+                Some(SocksTunnel::Established { .. }) => SOCKS_PORT,
+                Some(_) => {
+                    debug!(
+                        self.logger,
+                        "SOCKS5 stream {} never reached an exit Node; no counterpart to report to",
+                        &stream_key
+                    );
+                    return;
+                }
+            };
             debug!(
                 self.logger,
                 "Reporting shutdown of {} to counterpart", &stream_key
@@ -624,7 +1606,7 @@ impl ProxyServer {
             let ibcd = InboundClientData {
                 timestamp: SystemTime::now(),
                 peer_addr: msg.peer_addr,
-                reception_port: Some(nca.reception_port),
+                reception_port: Some(reception_port),
                 last_data: true,
                 is_clandestine: false,
                 sequence_number: Some(nca.sequence_number),
@@ -668,7 +1650,11 @@ impl ProxyServer {
         );
         let _ = self.keys_and_addrs.remove_a(stream_key);
         let _ = self.stream_key_routes.remove(stream_key);
+        let _ = self.stream_stripes.remove(stream_key);
+        let _ = self.stream_progress.remove(stream_key);
         let _ = self.tunneled_hosts.remove(stream_key);
+        let _ = self.tunneled_ports.remove(stream_key);
+        let _ = self.socks_tunnels.remove(stream_key);
         let _ = self.stream_key_ttl.remove(stream_key);
     }
 
@@ -678,12 +1664,23 @@ impl ProxyServer {
         stream_key: &StreamKey,
     ) -> Result<ClientRequestPayload_0v1, String> {
         let tunnelled_host = self.tunneled_hosts.get(stream_key);
-        let new_ibcd = match tunnelled_host {
-            Some(_) => InboundClientData {
+        let socks_target_opt = match self.socks_tunnels.get(stream_key) {
+            Some(SocksTunnel::Established { hostname, port }) => Some((hostname.clone(), *port)),
+            _ => None,
+        };
+        let new_ibcd = match (tunnelled_host, &socks_target_opt) {
+            (_, Some(_)) => InboundClientData {
+                reception_port: Some(SOCKS_PORT),
+                sequence_number: ibcd
+                    .sequence_number
+                    .map(|sn| sn.saturating_sub(SOCKS5_HANDSHAKE_PACKETS)),
+                ..ibcd
+            },
+            (Some(_), None) => InboundClientData {
                 reception_port: Some(443),
                 ..ibcd
             },
-            None => ibcd,
+            (None, None) => ibcd,
         };
         match self.client_request_payload_factory.make(
             &new_ibcd,
@@ -692,12 +1689,21 @@ impl ProxyServer {
             &self.logger,
         ) {
             None => Err("Couldn't create ClientRequestPayload".to_string()),
-            Some(payload) => match tunnelled_host {
-                Some(hostname) => Ok(ClientRequestPayload_0v1 {
+            Some(payload) => match (tunnelled_host, socks_target_opt) {
+                (_, Some((hostname, port))) => Ok(ClientRequestPayload_0v1 {
+                    target_hostname: Some(hostname),
+                    target_port: port,
+                    ..payload
+                }),
+                (Some(hostname), None) => Ok(ClientRequestPayload_0v1 {
                     target_hostname: Some(hostname.clone()),
+                    target_port: match self.tunneled_ports.get(stream_key) {
+                        Some(port) => *port,
+                        None => payload.target_port,
+                    },
                     ..payload
                 }),
-                None => Ok(payload),
+                (None, None) => Ok(payload),
             },
         }
     }
@@ -1130,9 +2136,10 @@ impl IBCDHelper for IBCDHelperReal {
         let stream_key = proxy.find_or_generate_stream_key(&msg);
         let timestamp = msg.timestamp;
         let payload = match proxy.make_payload(msg, &stream_key) {
-            Ok(payload) => payload,
+            Ok(payload) => proxy.rebase_request(payload),
             Err(e) => return Err(e),
         };
+        proxy.note_request(&payload);
 
         if proxy.dns_failure_retries.get(&stream_key).is_none() {
             let dns_failure_retry = DNSFailureRetry {
@@ -1146,7 +2153,7 @@ impl IBCDHelper for IBCDHelperReal {
         let tth_args =
             TryTransmitToHopperArgs::new(proxy, payload, source_addr, timestamp, retire_stream_key);
         let pld = &tth_args.payload;
-        if let Some(route_query_response) = proxy.stream_key_routes.get(&pld.stream_key) {
+        if let Some(route_query_response) = proxy.next_stream_route(&pld.stream_key) {
             debug!(
                 proxy.logger,
                 "Transmitting down existing stream {}: sequence {}, length {}",
@@ -1154,7 +2161,18 @@ impl IBCDHelper for IBCDHelperReal {
                 pld.sequenced_packet.sequence_number,
                 pld.sequenced_packet.data.len()
             );
-            let route_query_response = route_query_response.clone();
+            ProxyServer::try_transmit_to_hopper(tth_args, route_query_response)
+        } else if let Some(route_query_response) = proxy.sticky_route(pld) {
+            debug!(
+                proxy.logger,
+                "Reusing the route to {} for new stream {}",
+                ProxyServer::hostname(pld),
+                pld.stream_key
+            );
+            proxy
+                .stream_key_routes
+                .insert(pld.stream_key, route_query_response.clone());
+            proxy.request_stripe_route(pld.stream_key);
             ProxyServer::try_transmit_to_hopper(tth_args, route_query_response)
         } else {
             let route_source = proxy.out_subs("Neighborhood").route_source.clone();
@@ -1172,6 +2190,7 @@ impl IBCDHelper for IBCDHelperReal {
     ) {
         let pld = &tth_args.payload;
         let hostname_opt = pld.target_hostname.clone();
+        let target_port = pld.target_port;
         let logger = tth_args.logger.clone();
         debug!(
             logger,
@@ -1187,6 +2206,7 @@ impl IBCDHelper for IBCDHelperReal {
             neighborhood_sub
                 .send(RouteQueryMessage::data_indefinite_route_request(
                     hostname_opt,
+                    Some(target_port),
                     payload_size,
                 ))
                 .then(move |route_result| {
@@ -1265,12 +2285,83 @@ impl StreamKeyFactory for StreamKeyFactoryReal {
     }
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum SocksTunnel {
+    AwaitingRequest,
+    Established { hostname: String, port: u16 },
+    Refused,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 struct DNSFailureRetry {
     unsuccessful_request: ClientRequestPayload_0v1,
     retries_left: usize,
 }
 
+// How far a stream's exchange with its exit Node has got, so that a route that goes quiet or
+// loses a Node can be swapped for another without the browser noticing.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct StreamProgress {
+    protocol: ProxyProtocol,
+    hostname_opt: Option<String>,
+    unanswered_since_opt: Option<SystemTime>,
+    unanswered_request_opt: Option<ClientRequestPayload_0v1>,
+    unanswered_packets: usize,
+    next_response_sequence_number: u64,
+    sequence_rebase_opt: Option<SequenceRebase>,
+    abandoned_return_route_ids: Vec<u32>,
+    silent_reroutes: usize,
+}
+
+impl StreamProgress {
+    fn new(protocol: ProxyProtocol) -> Self {
+        Self {
+            protocol,
+            hostname_opt: None,
+            unanswered_since_opt: None,
+            unanswered_request_opt: None,
+            unanswered_packets: 0,
+            next_response_sequence_number: 0,
+            sequence_rebase_opt: None,
+            abandoned_return_route_ids: vec![],
+            silent_reroutes: 0,
+        }
+    }
+
+    // Only a request that went out whole in one packet, and that the server may safely see twice,
+    // is sent again. A TLS stream qualifies only until the server first answers it, since up to
+    // then it has carried nothing but the ClientHello.
+    fn can_replay(&self) -> bool {
+        if self.unanswered_packets != 1 {
+            return false;
+        }
+        match (&self.unanswered_request_opt, self.protocol) {
+            (Some(request), ProxyProtocol::HTTP) => {
+                let data = &request.sequenced_packet.data;
+                IDEMPOTENT_HTTP_METHODS.iter().any(|method| {
+                    data.starts_with(method.as_bytes()) && data.get(method.len()) == Some(&b' ')
+                })
+            }
+            (Some(_), ProxyProtocol::TLS) => self.next_response_sequence_number == 0,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct SequenceRebase {
+    request_base_opt: Option<u64>,
+    response_base: u64,
+}
+
+// The routes besides its first that a stream is striped across, all to the same exit and none
+// sharing a relay, and where the stream's next packet goes among them.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+struct StreamStripes {
+    routes: Vec<RouteQueryResponse>,
+    next_route: usize,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 struct Hostname {
     hostname: String,
@@ -1308,9 +2399,9 @@ mod tests {
     use crate::sub_lib::cryptde_null::CryptDENull;
     use crate::sub_lib::dispatcher::Component;
     use crate::sub_lib::hop::LiveHop;
-    use crate::sub_lib::hopper::MessageType;
+    use crate::sub_lib::hopper::{MessageType, ServiceRefusalReason};
     use crate::sub_lib::neighborhood::ExpectedServices;
-    use crate::sub_lib::neighborhood::{ExpectedService, DEFAULT_RATE_PACK};
+    use crate::sub_lib::neighborhood::{ExpectedService, Hops, DEFAULT_RATE_PACK};
     use crate::sub_lib::proxy_client::{ClientResponsePayload_0v1, DnsResolveFailure_0v1};
     use crate::sub_lib::proxy_server::ClientRequestPayload_0v1;
     use crate::sub_lib::proxy_server::ProxyProtocol;
@@ -1326,6 +2417,7 @@ mod tests {
     use crate::test_utils::recorder::peer_actors_builder;
     use crate::test_utils::recorder::Recorder;
     use crate::test_utils::recorder_stop_conditions::{StopCondition, StopConditions};
+    use crate::test_utils::unshared_test_utils::notify_handlers::NotifyLaterHandleMock;
     use crate::test_utils::unshared_test_utils::{
         prove_that_crash_request_handler_is_hooked_up, AssertionsMessage,
     };
@@ -1340,7 +2432,7 @@ mod tests {
     use masq_lib::test_utils::utils::TEST_DEFAULT_CHAIN;
     use std::any::TypeId;
     use std::cell::RefCell;
-    use std::net::SocketAddr;
+    use std::net::{IpAddr, SocketAddr};
     use std::str::FromStr;
     use std::sync::{Arc, Mutex};
     use std::thread;
@@ -1428,6 +2520,14 @@ mod tests {
         assert_eq!(CRASH_KEY, "PROXYSERVER");
         assert_eq!(RETURN_ROUTE_TTL, Duration::from_secs(120));
         assert_eq!(STREAM_KEY_PURGE_DELAY, Duration::from_secs(30));
+        assert_eq!(DEFAULT_STICKY_ROUTE_TTL, Duration::from_secs(300));
+        assert_eq!(RELAY_SILENCE_WINDOW, Duration::from_secs(30));
+        assert_eq!(SILENT_STREAM_SWEEP_INTERVAL, Duration::from_secs(5));
+        assert_eq!(MAX_SILENT_REROUTES, 2);
+        assert_eq!(
+            IDEMPOTENT_HTTP_METHODS,
+            ["GET", "HEAD", "OPTIONS", "TRACE", "PUT", "DELETE"]
+        );
     }
 
     const STANDARD_CONSUMING_WALLET_BALANCE: i64 = 0;
@@ -1439,11 +2539,13 @@ mod tests {
             dispatcher: recipient!(addr, TransmitDataMsg),
             hopper: recipient!(addr, IncipientCoresPackage),
             accountant: recipient!(addr, ReportServicesConsumedMessage),
+            prioritize_creditor: recipient!(addr, PrioritizeCreditorMessage),
             route_source: recipient!(addr, RouteQueryMessage),
             update_node_record_metadata: recipient!(addr, UpdateNodeRecordMetadataMessage),
             add_return_route: recipient!(addr, AddReturnRouteMessage),
             stream_shutdown_sub: recipient!(addr, StreamShutdownMsg),
             route_result_sub: recipient!(addr, AddRouteResultMessage),
+            stripe_route_sub: recipient!(addr, AddStripeRouteMessage),
             schedule_stream_key_purge: recipient!(addr, MessageScheduler<StreamKeyPurge>),
         }
     }
@@ -1619,6 +2721,10 @@ mod tests {
                 alias_cryptde,
                 true,
                 Some(STANDARD_CONSUMING_WALLET_BALANCE),
+                None,
+                None,
+                DEFAULT_STICKY_ROUTE_TTL,
+                DEFAULT_MULTIPATH_ROUTES,
                 false,
             );
             subject.logger = Logger::new(test_name);
@@ -1647,7 +2753,11 @@ mod tests {
         let record = recording.get_record::<RouteQueryMessage>(0);
         assert_eq!(
             record,
-            &RouteQueryMessage::data_indefinite_route_request(Some("nowhere.com".to_string()), 47)
+            &RouteQueryMessage::data_indefinite_route_request(
+                Some("nowhere.com".to_string()),
+                Some(80),
+                47
+            )
         );
         let recording = proxy_server_recording_arc.lock().unwrap();
         assert_eq!(recording.len(), 0);
@@ -1736,6 +2846,10 @@ mod tests {
                 alias_cryptde,
                 true,
                 Some(STANDARD_CONSUMING_WALLET_BALANCE),
+                None,
+                None,
+                DEFAULT_STICKY_ROUTE_TTL,
+                DEFAULT_MULTIPATH_ROUTES,
                 false,
             );
             subject.stream_key_factory = Box::new(stream_key_factory);
@@ -1769,6 +2883,7 @@ mod tests {
             neighborhood_record,
             &RouteQueryMessage::data_indefinite_route_request(
                 Some("realdomain.nu".to_string()),
+                Some(443),
                 12
             )
         );
@@ -1785,6 +2900,10 @@ mod tests {
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
@@ -1891,6 +3010,10 @@ mod tests {
                 alias_cryptde(),
                 true,
                 Some(STANDARD_CONSUMING_WALLET_BALANCE),
+                None,
+                None,
+                DEFAULT_STICKY_ROUTE_TTL,
+                DEFAULT_MULTIPATH_ROUTES,
                 false,
             );
             subject.stream_key_factory = Box::new(stream_key_factory);
@@ -1962,6 +3085,10 @@ mod tests {
                 alias_cryptde(),
                 true,
                 Some(STANDARD_CONSUMING_WALLET_BALANCE),
+                None,
+                None,
+                DEFAULT_STICKY_ROUTE_TTL,
+                DEFAULT_MULTIPATH_ROUTES,
                 false,
             );
             subject.stream_key_factory = Box::new(stream_key_factory);
@@ -1992,689 +3119,2652 @@ mod tests {
         assert_eq!(&expected_transmit_data_msg, record);
     }
 
-    #[test]
-    fn proxy_server_receives_http_request_with_no_consuming_wallet_and_sends_impersonated_response()
-    {
-        init_test_logging();
-        let cryptde = main_cryptde();
-        let http_request = b"GET /index.html HTTP/1.1\r\nHost: nowhere.com\r\n\r\n";
-        let (hopper, _, hopper_log_arc) = make_recorder();
-        let (neighborhood, _, neighborhood_log_arc) = make_recorder();
-        let (dispatcher, _, dispatcher_log_arc) = make_recorder();
-        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
-        let stream_key = StreamKey::make_meaningless_stream_key();
-        let expected_data = http_request.to_vec();
-        let msg_from_dispatcher = InboundClientData {
+    fn make_socks_ibcd(
+        socket_addr: SocketAddr,
+        sequence_number: u64,
+        data: Vec<u8>,
+    ) -> InboundClientData {
+        InboundClientData {
             timestamp: SystemTime::now(),
-            peer_addr: socket_addr.clone(),
-            reception_port: Some(HTTP_PORT),
-            sequence_number: Some(0),
-            last_data: true,
+            peer_addr: socket_addr,
+            reception_port: Some(2080),
+            sequence_number: Some(sequence_number),
+            last_data: false,
             is_clandestine: false,
-            data: expected_data.clone(),
-        };
-        let stream_key_factory = StreamKeyFactoryMock::new(); // can't make any stream keys; shouldn't have to
-        let system = System::new("proxy_server_receives_http_request_with_no_consuming_wallet_and_sends_impersonated_response");
-        let mut subject = ProxyServer::new(cryptde, alias_cryptde(), true, None, false);
-        subject.stream_key_factory = Box::new(stream_key_factory);
-        subject.keys_and_addrs.insert(stream_key, socket_addr);
-        let subject_addr: Addr<ProxyServer> = subject.start();
-        let peer_actors = peer_actors_builder()
-            .dispatcher(dispatcher)
-            .hopper(hopper)
-            .neighborhood(neighborhood)
-            .build();
-        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
-
-        subject_addr.try_send(msg_from_dispatcher).unwrap();
+            data,
+        }
+    }
 
-        System::current().stop();
-        system.run();
-        let neighborhood_recording = neighborhood_log_arc.lock().unwrap();
-        assert!(neighborhood_recording.is_empty());
-        let hopper_recording = hopper_log_arc.lock().unwrap();
-        assert!(hopper_recording.is_empty());
-        let dispatcher_recording = dispatcher_log_arc.lock().unwrap();
-        let record = dispatcher_recording.get_record::<TransmitDataMsg>(0);
-        let server_impersonator = ServerImpersonatorHttp {};
-        assert_eq!(
-            record,
-            &TransmitDataMsg {
-                endpoint: Endpoint::Socket(socket_addr),
-                last_data: true,
-                sequence_number: Some(0),
-                data: server_impersonator.consuming_wallet_absent(),
-            }
-        );
-        TestLogHandler::new().exists_log_containing(
-            "ERROR: ProxyServer: Browser request rejected due to missing consuming wallet",
-        );
+    fn socks_connect_request(hostname: &str, port: u16) -> Vec<u8> {
+        let mut request = vec![0x05, 0x01, 0x00, 0x03, hostname.len() as u8];
+        request.extend(hostname.as_bytes());
+        request.extend(&port.to_be_bytes());
+        request
     }
 
     #[test]
-    fn proxy_server_receives_tls_request_with_no_consuming_wallet_and_sends_impersonated_response()
-    {
-        init_test_logging();
-        let cryptde = main_cryptde();
-        let tls_request = b"Fake TLS request";
-        let (hopper, _, hopper_log_arc) = make_recorder();
-        let (neighborhood, _, neighborhood_log_arc) = make_recorder();
-        let (dispatcher, _, dispatcher_log_arc) = make_recorder();
+    fn proxy_server_negotiates_socks5_tunnel_and_sends_tunneled_data_to_target_host() {
+        let main_cryptde = main_cryptde();
+        let alias_cryptde = alias_cryptde();
+        let (hopper_mock, hopper_awaiter, hopper_recording_arc) = make_recorder();
+        let (neighborhood_mock, _, neighborhood_recording_arc) = make_recorder();
+        let destination_key = PublicKey::from(&b"our destination"[..]);
+        let neighborhood_mock = neighborhood_mock.route_query_response(Some(RouteQueryResponse {
+            route: Route { hops: vec![] },
+            expected_services: ExpectedServices::RoundTrip(
+                vec![make_exit_service_from_key(destination_key.clone())],
+                vec![],
+                1234,
+            ),
+        }));
+        let route = Route { hops: vec![] };
+        let (dispatcher_mock, _, dispatcher_recording_arc) = make_recorder();
         let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
         let stream_key = StreamKey::make_meaningless_stream_key();
-        let expected_data = tls_request.to_vec();
-        let msg_from_dispatcher = InboundClientData {
-            timestamp: SystemTime::now(),
-            peer_addr: socket_addr.clone(),
-            reception_port: Some(TLS_PORT),
-            sequence_number: Some(0),
-            last_data: true,
-            is_clandestine: false,
-            data: expected_data.clone(),
+        let greeting = make_socks_ibcd(socket_addr, 0, vec![0x05, 0x01, 0x00]);
+        let connect_request =
+            make_socks_ibcd(socket_addr, 1, socks_connect_request("example.com", 8080));
+        let tunneled_data = make_socks_ibcd(socket_addr, 2, b"tunneled bytes".to_vec());
+        let expected_payload = ClientRequestPayload_0v1 {
+            stream_key,
+            sequenced_packet: SequencedPacket {
+                data: b"tunneled bytes".to_vec(),
+                sequence_number: 0,
+                last_data: false,
+            },
+            target_hostname: Some(String::from("example.com")),
+            target_port: 8080,
+            protocol: ProxyProtocol::SOCKS5,
+            originator_public_key: alias_cryptde.public_key().clone(),
         };
-        let stream_key_factory = StreamKeyFactoryMock::new(); // can't make any stream keys; shouldn't have to
-        let system = System::new("proxy_server_receives_tls_request_with_no_consuming_wallet_and_sends_impersonated_response");
-        let mut subject = ProxyServer::new(cryptde, alias_cryptde(), true, None, false);
-        subject.stream_key_factory = Box::new(stream_key_factory);
-        subject.keys_and_addrs.insert(stream_key, socket_addr);
-        let subject_addr: Addr<ProxyServer> = subject.start();
-        let peer_actors = peer_actors_builder()
-            .dispatcher(dispatcher)
-            .hopper(hopper)
-            .neighborhood(neighborhood)
-            .build();
-        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
-
-        subject_addr.try_send(msg_from_dispatcher).unwrap();
-
-        System::current().stop();
-        system.run();
-        let neighborhood_recording = neighborhood_log_arc.lock().unwrap();
-        assert!(neighborhood_recording.is_empty());
-        let hopper_recording = hopper_log_arc.lock().unwrap();
-        assert!(hopper_recording.is_empty());
-        let dispatcher_recording = dispatcher_log_arc.lock().unwrap();
-        let record = dispatcher_recording.get_record::<TransmitDataMsg>(0);
-        let server_impersonator = ServerImpersonatorTls {};
-        assert_eq!(
-            record,
-            &TransmitDataMsg {
-                endpoint: Endpoint::Socket(socket_addr),
-                last_data: true,
-                sequence_number: Some(0),
-                data: server_impersonator.consuming_wallet_absent(),
-            }
-        );
-        TestLogHandler::new().exists_log_containing(
-            "ERROR: ProxyServer: Browser request rejected due to missing consuming wallet",
-        );
-    }
+        let expected_pkg = IncipientCoresPackage::new(
+            main_cryptde,
+            route,
+            expected_payload.into(),
+            &destination_key,
+        )
+        .unwrap();
 
-    #[test]
-    fn proxy_server_receives_http_request_with_no_consuming_wallet_in_zero_hop_mode_and_handles_normally(
-    ) {
-        init_test_logging();
-        let main_cryptde = main_cryptde();
-        let alias_cryptde = alias_cryptde();
-        let expected_data = b"GET /index.html HTTP/1.1\r\nHost: nowhere.com\r\n\r\n".to_vec();
-        let expected_data_inner = expected_data.clone();
-        let expected_route = zero_hop_route_response(main_cryptde.public_key(), main_cryptde);
-        let stream_key = StreamKey::make_meaningless_stream_key();
-        let (hopper, hopper_awaiter, hopper_log_arc) = make_recorder();
-        let neighborhood = Recorder::new().route_query_response(Some(expected_route.clone()));
-        let neighborhood_log_arc = neighborhood.get_recording();
-        let (dispatcher, _, dispatcher_log_arc) = make_recorder();
         thread::spawn(move || {
-            let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
-            let msg_from_dispatcher = InboundClientData {
-                timestamp: SystemTime::now(),
-                peer_addr: socket_addr.clone(),
-                reception_port: Some(HTTP_PORT),
-                sequence_number: Some(0),
-                last_data: true,
-                is_clandestine: false,
-                data: expected_data_inner,
-            };
-            let stream_key_factory = StreamKeyFactoryMock::new(); // can't make any stream keys; shouldn't have to
-            let system = System::new("proxy_server_receives_http_request_with_no_consuming_wallet_in_zero_hop_mode_and_handles_normally");
-            let mut subject = ProxyServer::new(main_cryptde, alias_cryptde, false, None, false);
+            let stream_key_factory = StreamKeyFactoryMock::new().make_result(stream_key);
+            let system = System::new(
+                "proxy_server_negotiates_socks5_tunnel_and_sends_tunneled_data_to_target_host",
+            );
+            let mut subject = ProxyServer::new(
+                main_cryptde,
+                alias_cryptde,
+                true,
+                Some(STANDARD_CONSUMING_WALLET_BALANCE),
+                Some(2080),
+                None,
+                DEFAULT_STICKY_ROUTE_TTL,
+                DEFAULT_MULTIPATH_ROUTES,
+                false,
+            );
             subject.stream_key_factory = Box::new(stream_key_factory);
-            subject.keys_and_addrs.insert(stream_key, socket_addr);
             let subject_addr: Addr<ProxyServer> = subject.start();
             let peer_actors = peer_actors_builder()
-                .dispatcher(dispatcher)
-                .hopper(hopper)
-                .neighborhood(neighborhood)
+                .dispatcher(dispatcher_mock)
+                .hopper(hopper_mock)
+                .neighborhood(neighborhood_mock)
                 .build();
             subject_addr.try_send(BindMessage { peer_actors }).unwrap();
 
-            subject_addr.try_send(msg_from_dispatcher).unwrap();
-
+            subject_addr.try_send(greeting).unwrap();
+            subject_addr.try_send(connect_request).unwrap();
+            subject_addr.try_send(tunneled_data).unwrap();
             system.run();
         });
+
         hopper_awaiter.await_message_count(1);
-        let neighborhood_recording = neighborhood_log_arc.lock().unwrap();
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
         assert_eq!(
-            neighborhood_recording.get_record::<RouteQueryMessage>(0),
-            &RouteQueryMessage {
-                target_key_opt: None,
-                target_component: Component::ProxyClient,
-                return_component_opt: Some(Component::ProxyServer),
-                payload_size: 47,
-                hostname_opt: Some("nowhere.com".to_string())
+            dispatcher_recording.get_record::<TransmitDataMsg>(0),
+            &TransmitDataMsg {
+                endpoint: Endpoint::Socket(socket_addr),
+                last_data: false,
+                sequence_number: Some(0),
+                data: vec![0x05, 0x00],
             }
         );
-        let dispatcher_recording = dispatcher_log_arc.lock().unwrap();
-        assert!(dispatcher_recording.is_empty());
-        let hopper_recording = hopper_log_arc.lock().unwrap();
         assert_eq!(
-            hopper_recording.get_record::<IncipientCoresPackage>(0),
-            &IncipientCoresPackage::new(
-                main_cryptde,
-                expected_route.route,
-                MessageType::ClientRequest(VersionedData::new(
-                    &crate::sub_lib::migrations::client_request_payload::MIGRATIONS,
-                    &ClientRequestPayload_0v1 {
-                        stream_key,
-                        sequenced_packet: SequencedPacket::new(expected_data, 0, true),
-                        target_hostname: Some("nowhere.com".to_string()),
-                        target_port: 80,
-                        protocol: ProxyProtocol::HTTP,
-                        originator_public_key: alias_cryptde.public_key().clone(),
-                    }
-                )),
-                main_cryptde.public_key()
+            dispatcher_recording.get_record::<TransmitDataMsg>(1),
+            &TransmitDataMsg {
+                endpoint: Endpoint::Socket(socket_addr),
+                last_data: false,
+                sequence_number: Some(1),
+                data: vec![0x05, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            }
+        );
+        let hopper_recording = hopper_recording_arc.lock().unwrap();
+        let hopper_record = hopper_recording.get_record::<IncipientCoresPackage>(0);
+        assert_eq!(hopper_record, &expected_pkg);
+        let neighborhood_recording = neighborhood_recording_arc.lock().unwrap();
+        let neighborhood_record = neighborhood_recording.get_record::<RouteQueryMessage>(0);
+        assert_eq!(
+            neighborhood_record,
+            &RouteQueryMessage::data_indefinite_route_request(
+                Some("example.com".to_string()),
+                Some(8080),
+                14
             )
-            .unwrap()
         );
     }
 
     #[test]
-    fn proxy_server_receives_tls_request_with_no_consuming_wallet_in_zero_hop_mode_and_handles_normally(
-    ) {
+    fn proxy_server_refuses_socks5_greeting_without_acceptable_authentication_method() {
         init_test_logging();
-        let main_cryptde = main_cryptde();
-        let alias_cryptde = alias_cryptde();
-        let expected_data = b"Fake TLS request".to_vec();
-        let expected_data_inner = expected_data.clone();
-        let expected_route = zero_hop_route_response(main_cryptde.public_key(), main_cryptde);
+        let system = System::new(
+            "proxy_server_refuses_socks5_greeting_without_acceptable_authentication_method",
+        );
+        let (dispatcher_mock, _, dispatcher_recording_arc) = make_recorder();
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
         let stream_key = StreamKey::make_meaningless_stream_key();
-        let (hopper, hopper_awaiter, hopper_log_arc) = make_recorder();
-        let neighborhood = Recorder::new().route_query_response(Some(expected_route.clone()));
-        let neighborhood_log_arc = neighborhood.get_recording();
-        let (dispatcher, _, dispatcher_log_arc) = make_recorder();
-        thread::spawn(move || {
-            let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
-            let msg_from_dispatcher = InboundClientData {
-                timestamp: SystemTime::now(),
-                peer_addr: socket_addr.clone(),
-                reception_port: Some(TLS_PORT),
-                sequence_number: Some(0),
-                last_data: true,
-                is_clandestine: false,
-                data: expected_data_inner,
-            };
-            let stream_key_factory = StreamKeyFactoryMock::new(); // can't make any stream keys; shouldn't have to
-            let system = System::new("proxy_server_receives_tls_request_with_no_consuming_wallet_in_zero_hop_mode_and_handles_normally");
-            let mut subject = ProxyServer::new(main_cryptde, alias_cryptde, false, None, false);
-            subject.stream_key_factory = Box::new(stream_key_factory);
-            subject.keys_and_addrs.insert(stream_key, socket_addr);
-            let subject_addr: Addr<ProxyServer> = subject.start();
-            let peer_actors = peer_actors_builder()
-                .dispatcher(dispatcher)
-                .hopper(hopper)
-                .neighborhood(neighborhood)
-                .build();
-            subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let mut subject = ProxyServer::new(
+            main_cryptde(),
+            alias_cryptde(),
+            true,
+            Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            Some(2080),
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
+            false,
+        );
+        subject.stream_key_factory = Box::new(StreamKeyFactoryMock::new().make_result(stream_key));
+        subject.logger = Logger::new(
+            "proxy_server_refuses_socks5_greeting_without_acceptable_authentication_method",
+        );
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let peer_actors = peer_actors_builder().dispatcher(dispatcher_mock).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
 
-            subject_addr.try_send(msg_from_dispatcher).unwrap();
+        subject_addr
+            .try_send(make_socks_ibcd(socket_addr, 0, vec![0x05, 0x01, 0x02]))
+            .unwrap();
+        subject_addr
+            .try_send(make_socks_ibcd(
+                socket_addr,
+                1,
+                socks_connect_request("example.com", 443),
+            ))
+            .unwrap();
 
-            system.run();
-        });
-        hopper_awaiter.await_message_count(1);
-        let neighborhood_recording = neighborhood_log_arc.lock().unwrap();
+        System::current().stop();
+        system.run();
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        assert_eq!(dispatcher_recording.len(), 1);
         assert_eq!(
-            neighborhood_recording.get_record::<RouteQueryMessage>(0),
-            &RouteQueryMessage {
-                target_key_opt: None,
-                target_component: Component::ProxyClient,
-                return_component_opt: Some(Component::ProxyServer),
-                payload_size: 16,
-                hostname_opt: None
+            dispatcher_recording.get_record::<TransmitDataMsg>(0),
+            &TransmitDataMsg {
+                endpoint: Endpoint::Socket(socket_addr),
+                last_data: true,
+                sequence_number: Some(0),
+                data: vec![0x05, 0xFF],
             }
         );
-        let dispatcher_recording = dispatcher_log_arc.lock().unwrap();
-        assert!(dispatcher_recording.is_empty());
-        let hopper_recording = hopper_log_arc.lock().unwrap();
-        assert_eq!(
-            hopper_recording.get_record::<IncipientCoresPackage>(0),
-            &IncipientCoresPackage::new(
-                main_cryptde,
-                expected_route.route,
-                MessageType::ClientRequest(VersionedData::new(
-                    &crate::sub_lib::migrations::client_request_payload::MIGRATIONS,
-                    &ClientRequestPayload_0v1 {
-                        stream_key,
-                        sequenced_packet: SequencedPacket::new(expected_data, 0, true),
-                        target_hostname: None,
-                        target_port: 443,
-                        protocol: ProxyProtocol::TLS,
-                        originator_public_key: alias_cryptde.public_key().clone(),
-                    }
-                ),),
-                main_cryptde.public_key()
-            )
-            .unwrap()
+        TestLogHandler::new().exists_log_containing(
+            "WARN: proxy_server_refuses_socks5_greeting_without_acceptable_authentication_method: \
+            Refusing SOCKS5 client at 1.2.3.4:5678: no acceptable authentication method in [5, 1, 2]",
         );
     }
 
     #[test]
-    fn proxy_server_receives_http_request_with_existing_stream_key_from_dispatcher_then_sends_cores_package_to_hopper(
-    ) {
-        let main_cryptde = main_cryptde();
-        let alias_cryptde = alias_cryptde();
-        let http_request = b"GET /index.html HTTP/1.1\r\nHost: nowhere.com\r\n\r\n";
-        let hopper_mock = Recorder::new();
-        let hopper_log_arc = hopper_mock.get_recording();
-        let hopper_awaiter = hopper_mock.get_awaiter();
-        let destination_key = PublicKey::from(&b"our destination"[..]);
-        let neighborhood_mock = Recorder::new().route_query_response(Some(RouteQueryResponse {
-            route: Route { hops: vec![] },
-            expected_services: ExpectedServices::RoundTrip(
-                vec![make_exit_service_from_key(destination_key.clone())],
-                vec![],
-                1234,
-            ),
-        }));
+    fn proxy_server_refuses_socks5_request_for_unsupported_command() {
+        let system = System::new("proxy_server_refuses_socks5_request_for_unsupported_command");
+        let (dispatcher_mock, _, dispatcher_recording_arc) = make_recorder();
+        let (neighborhood_mock, _, neighborhood_recording_arc) = make_recorder();
         let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
-        let stream_key = StreamKey::make_meaningless_stream_key();
-        let expected_data = http_request.to_vec();
-        let msg_from_dispatcher = InboundClientData {
-            timestamp: SystemTime::now(),
-            peer_addr: socket_addr.clone(),
-            reception_port: Some(HTTP_PORT),
-            sequence_number: Some(0),
-            last_data: true,
-            is_clandestine: false,
-            data: expected_data.clone(),
+        let mut subject = ProxyServer::new(
+            main_cryptde(),
+            alias_cryptde(),
+            true,
+            Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            Some(2080),
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
+            false,
+        );
+        subject.stream_key_factory = Box::new(
+            StreamKeyFactoryMock::new().make_result(StreamKey::make_meaningless_stream_key()),
+        );
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let peer_actors = peer_actors_builder()
+            .dispatcher(dispatcher_mock)
+            .neighborhood(neighborhood_mock)
+            .build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let udp_associate = vec![0x05, 0x03, 0x00, 0x01, 0, 0, 0, 0, 0, 0];
+
+        subject_addr
+            .try_send(make_socks_ibcd(socket_addr, 0, vec![0x05, 0x01, 0x00]))
+            .unwrap();
+        subject_addr
+            .try_send(make_socks_ibcd(socket_addr, 1, udp_associate))
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        assert_eq!(
+            dispatcher_recording.get_record::<TransmitDataMsg>(1),
+            &TransmitDataMsg {
+                endpoint: Endpoint::Socket(socket_addr),
+                last_data: true,
+                sequence_number: Some(1),
+                data: vec![0x05, 0x07, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            }
+        );
+        let neighborhood_recording = neighborhood_recording_arc.lock().unwrap();
+        assert_eq!(neighborhood_recording.len(), 0);
+    }
+
+    #[test]
+    fn proxy_server_refuses_socks5_request_when_consuming_wallet_is_absent() {
+        let system =
+            System::new("proxy_server_refuses_socks5_request_when_consuming_wallet_is_absent");
+        let (dispatcher_mock, _, dispatcher_recording_arc) = make_recorder();
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let mut subject = ProxyServer::new(
+            main_cryptde(),
+            alias_cryptde(),
+            true,
+            None,
+            Some(2080),
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
+            false,
+        );
+        subject.stream_key_factory = Box::new(StreamKeyFactoryMock::new().make_result(stream_key));
+        subject
+            .socks_tunnels
+            .insert(stream_key, SocksTunnel::AwaitingRequest);
+        subject.keys_and_addrs.insert(stream_key, socket_addr);
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let peer_actors = peer_actors_builder().dispatcher(dispatcher_mock).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr
+            .try_send(make_socks_ibcd(
+                socket_addr,
+                1,
+                socks_connect_request("example.com", 443),
+            ))
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        assert_eq!(
+            dispatcher_recording.get_record::<TransmitDataMsg>(0),
+            &TransmitDataMsg {
+                endpoint: Endpoint::Socket(socket_addr),
+                last_data: true,
+                sequence_number: Some(1),
+                data: vec![0x05, 0x02, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            }
+        );
+    }
+
+    #[test]
+    fn handle_client_response_payload_skips_socks5_handshake_in_sequence_numbers() {
+        let system = System::new(
+            "handle_client_response_payload_skips_socks5_handshake_in_sequence_numbers",
+        );
+        let (dispatcher_mock, _, dispatcher_recording_arc) = make_recorder();
+        let cryptde = main_cryptde();
+        let mut subject = ProxyServer::new(
+            cryptde,
+            alias_cryptde(),
+            true,
+            Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            Some(2080),
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
+            false,
+        );
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        subject.keys_and_addrs.insert(stream_key, socket_addr);
+        subject.socks_tunnels.insert(
+            stream_key,
+            SocksTunnel::Established {
+                hostname: "example.com".to_string(),
+                port: 8080,
+            },
+        );
+        subject.route_ids_to_return_routes.insert(
+            1234,
+            AddReturnRouteMessage {
+                return_route_id: 1234,
+                expected_services: vec![ExpectedService::Nothing],
+                protocol: ProxyProtocol::SOCKS5,
+                hostname_opt: Some("example.com".to_string()),
+            },
+        );
+        let client_response_payload = ClientResponsePayload_0v1 {
+            stream_key,
+            sequenced_packet: SequencedPacket {
+                data: b"some data".to_vec(),
+                sequence_number: 0,
+                last_data: false,
+            },
         };
-        let expected_http_request = PlainData::new(http_request);
-        let route = Route { hops: vec![] };
+        let expired_cores_package: ExpiredCoresPackage<ClientResponsePayload_0v1> =
+            ExpiredCoresPackage::new(
+                SocketAddr::from_str("1.2.3.4:1234").unwrap(),
+                Some(make_wallet("irrelevant")),
+                return_route_with_id(cryptde, 1234),
+                client_response_payload,
+                0,
+            );
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let peer_actors = peer_actors_builder().dispatcher(dispatcher_mock).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr.try_send(expired_cores_package).unwrap();
+
+        System::current().stop();
+        system.run();
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        let record = dispatcher_recording.get_record::<TransmitDataMsg>(0);
+        assert_eq!(record.sequence_number, Some(2));
+        assert_eq!(record.data, b"some data".to_vec());
+    }
+
+    #[test]
+    fn handle_stream_shutdown_msg_does_not_report_socks5_stream_that_never_got_a_tunnel() {
+        let system = System::new(
+            "handle_stream_shutdown_msg_does_not_report_socks5_stream_that_never_got_a_tunnel",
+        );
+        let (neighborhood_mock, _, neighborhood_recording_arc) = make_recorder();
+        let (hopper_mock, _, hopper_recording_arc) = make_recorder();
+        let mut subject = ProxyServer::new(
+            main_cryptde(),
+            alias_cryptde(),
+            true,
+            Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            Some(2080),
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
+            false,
+        );
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        subject.keys_and_addrs.insert(stream_key, socket_addr);
+        subject
+            .socks_tunnels
+            .insert(stream_key, SocksTunnel::AwaitingRequest);
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let peer_actors = peer_actors_builder()
+            .neighborhood(neighborhood_mock)
+            .hopper(hopper_mock)
+            .build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr
+            .try_send(StreamShutdownMsg {
+                peer_addr: socket_addr,
+                stream_type: RemovedStreamType::NonClandestine(NonClandestineAttributes {
+                    reception_port: 2080,
+                    sequence_number: 1,
+                }),
+                report_to_counterpart: true,
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        assert_eq!(neighborhood_recording_arc.lock().unwrap().len(), 0);
+        assert_eq!(hopper_recording_arc.lock().unwrap().len(), 0);
+    }
+
+    fn make_forward_proxy_config() -> ForwardProxyConfig {
+        ForwardProxyConfig {
+            port: 8080,
+            connect_ports: vec![TLS_PORT, 22],
+        }
+    }
+
+    fn make_forward_proxy_ibcd(
+        socket_addr: SocketAddr,
+        sequence_number: u64,
+        data: &[u8],
+    ) -> InboundClientData {
+        InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: socket_addr,
+            reception_port: Some(8080),
+            sequence_number: Some(sequence_number),
+            last_data: false,
+            is_clandestine: false,
+            data: data.to_vec(),
+        }
+    }
+
+    #[test]
+    fn proxy_server_opens_forward_proxy_tunnel_to_allowed_connect_port() {
+        let main_cryptde = main_cryptde();
+        let alias_cryptde = alias_cryptde();
+        let (hopper_mock, hopper_awaiter, hopper_recording_arc) = make_recorder();
+        let (neighborhood_mock, _, _) = make_recorder();
+        let destination_key = PublicKey::from(&b"our destination"[..]);
+        let neighborhood_mock = neighborhood_mock.route_query_response(Some(RouteQueryResponse {
+            route: Route { hops: vec![] },
+            expected_services: ExpectedServices::RoundTrip(
+                vec![make_exit_service_from_key(destination_key.clone())],
+                vec![],
+                1234,
+            ),
+        }));
+        let (dispatcher_mock, _, dispatcher_recording_arc) = make_recorder();
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let connect = make_forward_proxy_ibcd(
+            socket_addr,
+            0,
+            b"CONNECT example.com:22 HTTP/1.1\r\nHost: example.com:22\r\n\r\n",
+        );
+        let tunneled_data = make_forward_proxy_ibcd(socket_addr, 0, b"SSH-2.0-OpenSSH\r\n");
         let expected_payload = ClientRequestPayload_0v1 {
-            stream_key: stream_key.clone(),
+            stream_key,
             sequenced_packet: SequencedPacket {
-                data: expected_http_request.into(),
+                data: b"SSH-2.0-OpenSSH\r\n".to_vec(),
                 sequence_number: 0,
-                last_data: true,
+                last_data: false,
             },
-            target_hostname: Some(String::from("nowhere.com")),
-            target_port: HTTP_PORT,
-            protocol: ProxyProtocol::HTTP,
+            target_hostname: Some(String::from("example.com")),
+            target_port: 22,
+            protocol: ProxyProtocol::TLS,
             originator_public_key: alias_cryptde.public_key().clone(),
         };
         let expected_pkg = IncipientCoresPackage::new(
             main_cryptde,
-            route.clone(),
+            Route { hops: vec![] },
             expected_payload.into(),
             &destination_key,
         )
         .unwrap();
+
         thread::spawn(move || {
-            let stream_key_factory = StreamKeyFactoryMock::new(); // can't make any stream keys; shouldn't have to
-            let system = System::new("proxy_server_receives_http_request_from_dispatcher_then_sends_cores_package_to_hopper");
+            let system =
+                System::new("proxy_server_opens_forward_proxy_tunnel_to_allowed_connect_port");
             let mut subject = ProxyServer::new(
                 main_cryptde,
                 alias_cryptde,
                 true,
                 Some(STANDARD_CONSUMING_WALLET_BALANCE),
+                None,
+                Some(make_forward_proxy_config()),
+                DEFAULT_STICKY_ROUTE_TTL,
+                DEFAULT_MULTIPATH_ROUTES,
                 false,
             );
-            subject.stream_key_factory = Box::new(stream_key_factory);
-            subject.keys_and_addrs.insert(stream_key, socket_addr);
+            subject.stream_key_factory =
+                Box::new(StreamKeyFactoryMock::new().make_result(stream_key));
             let subject_addr: Addr<ProxyServer> = subject.start();
             let peer_actors = peer_actors_builder()
+                .dispatcher(dispatcher_mock)
                 .hopper(hopper_mock)
                 .neighborhood(neighborhood_mock)
                 .build();
             subject_addr.try_send(BindMessage { peer_actors }).unwrap();
 
-            subject_addr.try_send(msg_from_dispatcher).unwrap();
-
+            subject_addr.try_send(connect).unwrap();
+            subject_addr.try_send(tunneled_data).unwrap();
             system.run();
         });
 
         hopper_awaiter.await_message_count(1);
-        let recording = hopper_log_arc.lock().unwrap();
-        let record = recording.get_record::<IncipientCoresPackage>(0);
-        assert_eq!(record, &expected_pkg);
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        assert_eq!(
+            dispatcher_recording.get_record::<TransmitDataMsg>(0),
+            &TransmitDataMsg {
+                endpoint: Endpoint::Socket(socket_addr),
+                last_data: false,
+                sequence_number: Some(0),
+                data: b"HTTP/1.1 200 OK\r\n\r\n".to_vec(),
+            }
+        );
+        let hopper_recording = hopper_recording_arc.lock().unwrap();
+        assert_eq!(
+            hopper_recording.get_record::<IncipientCoresPackage>(0),
+            &expected_pkg
+        );
     }
 
     #[test]
-    fn proxy_server_receives_http_request_from_dispatcher_then_sends_multihop_cores_package_to_hopper(
-    ) {
-        let main_cryptde = main_cryptde();
-        let alias_cryptde = alias_cryptde();
-        let consuming_wallet = make_paying_wallet(b"paying wallet");
-        let earning_wallet = make_wallet("earning wallet");
-        let http_request = b"GET /index.html HTTP/1.1\r\nHost: nowhere.com\r\n\r\n";
-        let hopper_mock = Recorder::new();
-        let hopper_log_arc = hopper_mock.get_recording();
-        let hopper_awaiter = hopper_mock.get_awaiter();
-        let payload_destination_key = PublicKey::new(&[3]);
-        let route = Route::round_trip(
-            RouteSegment::new(
-                vec![
-                    &main_cryptde.public_key(),
-                    &PublicKey::new(&[1]),
-                    &PublicKey::new(&[2]),
-                    &payload_destination_key,
-                ],
-                Component::ProxyClient,
-            ),
-            RouteSegment::new(
-                vec![
-                    &payload_destination_key,
-                    &PublicKey::new(&[2]),
-                    &PublicKey::new(&[1]),
-                    &main_cryptde.public_key(),
-                ],
-                Component::ProxyServer,
-            ),
-            main_cryptde,
-            Some(consuming_wallet),
-            1234,
-            Some(TEST_DEFAULT_CHAIN.rec().contract),
-        )
-        .unwrap();
+    fn proxy_server_refuses_forward_proxy_connect_to_port_not_in_allowlist() {
+        let system =
+            System::new("proxy_server_refuses_forward_proxy_connect_to_port_not_in_allowlist");
+        let (dispatcher_mock, _, dispatcher_recording_arc) = make_recorder();
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let subject = ProxyServer::new(
+            main_cryptde(),
+            alias_cryptde(),
+            true,
+            Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            Some(make_forward_proxy_config()),
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
+            false,
+        );
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let peer_actors = peer_actors_builder().dispatcher(dispatcher_mock).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr
+            .try_send(make_forward_proxy_ibcd(
+                socket_addr,
+                0,
+                b"CONNECT example.com:25 HTTP/1.1\r\nHost: example.com:25\r\n\r\n",
+            ))
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        assert_eq!(
+            dispatcher_recording.get_record::<TransmitDataMsg>(0),
+            &TransmitDataMsg {
+                endpoint: Endpoint::Socket(socket_addr),
+                last_data: true,
+                sequence_number: Some(0),
+                data: b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n".to_vec(),
+            }
+        );
+    }
+
+    #[test]
+    fn proxy_server_rewrites_absolute_form_forward_proxy_request_and_sends_it_to_hopper() {
+        let main_cryptde = main_cryptde();
+        let alias_cryptde = alias_cryptde();
+        let (hopper_mock, hopper_awaiter, hopper_recording_arc) = make_recorder();
         let (neighborhood_mock, _, neighborhood_recording_arc) = make_recorder();
+        let destination_key = PublicKey::from(&b"our destination"[..]);
         let neighborhood_mock = neighborhood_mock.route_query_response(Some(RouteQueryResponse {
-            route: route.clone(),
+            route: Route { hops: vec![] },
             expected_services: ExpectedServices::RoundTrip(
-                vec![
-                    ExpectedService::Exit(
-                        PublicKey::new(&[3]),
-                        earning_wallet.clone(),
-                        rate_pack(101),
-                    ),
-                    ExpectedService::Nothing,
-                ],
-                vec![
-                    ExpectedService::Nothing,
-                    ExpectedService::Exit(PublicKey::new(&[3]), earning_wallet, rate_pack(102)),
-                ],
+                vec![make_exit_service_from_key(destination_key.clone())],
+                vec![],
                 1234,
             ),
         }));
+        let (dispatcher_mock, _, _) = make_recorder();
         let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
         let stream_key = StreamKey::make_meaningless_stream_key();
-        let expected_data = http_request.to_vec();
-        let msg_from_dispatcher = InboundClientData {
-            timestamp: SystemTime::now(),
-            peer_addr: socket_addr.clone(),
-            reception_port: Some(HTTP_PORT),
-            sequence_number: Some(0),
-            last_data: true,
-            is_clandestine: false,
-            data: expected_data.clone(),
-        };
-        let expected_http_request = PlainData::new(http_request);
+        let request = make_forward_proxy_ibcd(
+            socket_addr,
+            0,
+            b"GET http://example.com/index.html HTTP/1.1\r\n\
+              Host: example.com\r\n\
+              Proxy-Connection: keep-alive\r\n\r\n",
+        );
         let expected_payload = ClientRequestPayload_0v1 {
-            stream_key: stream_key.clone(),
+            stream_key,
             sequenced_packet: SequencedPacket {
-                data: expected_http_request.into(),
+                data: b"GET /index.html HTTP/1.1\r\nHost: example.com\r\nConnection: close\r\n\r\n"
+                    .to_vec(),
                 sequence_number: 0,
-                last_data: true,
+                last_data: false,
             },
-            target_hostname: Some(String::from("nowhere.com")),
+            target_hostname: Some(String::from("example.com")),
             target_port: HTTP_PORT,
             protocol: ProxyProtocol::HTTP,
             originator_public_key: alias_cryptde.public_key().clone(),
         };
         let expected_pkg = IncipientCoresPackage::new(
             main_cryptde,
-            route.clone(),
+            Route { hops: vec![] },
             expected_payload.into(),
-            &payload_destination_key,
+            &destination_key,
         )
         .unwrap();
+
         thread::spawn(move || {
-            let stream_key_factory = StreamKeyFactoryMock::new().make_result(stream_key);
-            let system = System::new("proxy_server_receives_http_request_from_dispatcher_then_sends_multihop_cores_package_to_hopper");
+            let system = System::new(
+                "proxy_server_rewrites_absolute_form_forward_proxy_request_and_sends_it_to_hopper",
+            );
             let mut subject = ProxyServer::new(
                 main_cryptde,
                 alias_cryptde,
                 true,
                 Some(STANDARD_CONSUMING_WALLET_BALANCE),
+                None,
+                Some(make_forward_proxy_config()),
+                DEFAULT_STICKY_ROUTE_TTL,
+                DEFAULT_MULTIPATH_ROUTES,
                 false,
             );
-            subject.stream_key_factory = Box::new(stream_key_factory);
+            subject.stream_key_factory =
+                Box::new(StreamKeyFactoryMock::new().make_result(stream_key));
             let subject_addr: Addr<ProxyServer> = subject.start();
             let peer_actors = peer_actors_builder()
+                .dispatcher(dispatcher_mock)
                 .hopper(hopper_mock)
                 .neighborhood(neighborhood_mock)
                 .build();
             subject_addr.try_send(BindMessage { peer_actors }).unwrap();
 
-            subject_addr.try_send(msg_from_dispatcher).unwrap();
-
+            subject_addr.try_send(request).unwrap();
             system.run();
         });
 
         hopper_awaiter.await_message_count(1);
-        let recording = hopper_log_arc.lock().unwrap();
-        let record = recording.get_record::<IncipientCoresPackage>(0);
-        assert_eq!(record, &expected_pkg);
-        let recording = neighborhood_recording_arc.lock().unwrap();
-        let record = recording.get_record::<RouteQueryMessage>(0);
+        let hopper_recording = hopper_recording_arc.lock().unwrap();
         assert_eq!(
-            record,
-            &RouteQueryMessage::data_indefinite_route_request(Some("nowhere.com".to_string()), 47)
+            hopper_recording.get_record::<IncipientCoresPackage>(0),
+            &expected_pkg
+        );
+        let neighborhood_recording = neighborhood_recording_arc.lock().unwrap();
+        assert_eq!(
+            neighborhood_recording.get_record::<RouteQueryMessage>(0),
+            &RouteQueryMessage::data_indefinite_route_request(
+                Some("example.com".to_string()),
+                Some(80),
+                66
+            )
         );
     }
 
     #[test]
-    fn proxy_server_sends_a_message_when_dns_retry_found_a_route() {
-        let cryptde = main_cryptde();
-        let http_request = b"GET /index.html HTTP/1.1\r\nHost: nowhere.com\r\n\r\n";
-        let (proxy_server_mock, proxy_server_awaiter, proxy_server_recording_arc) = make_recorder();
-        let expected_service = ExpectedService::Exit(
-            main_cryptde().public_key().clone(),
-            make_wallet("walletAddress"),
-            DEFAULT_RATE_PACK,
+    fn proxy_server_serves_pac_file_on_forward_proxy_port() {
+        let system = System::new("proxy_server_serves_pac_file_on_forward_proxy_port");
+        let (dispatcher_mock, _, dispatcher_recording_arc) = make_recorder();
+        let (neighborhood_mock, _, neighborhood_recording_arc) = make_recorder();
+        let socket_addr = SocketAddr::from_str("127.0.0.1:5678").unwrap();
+        let subject = ProxyServer::new(
+            main_cryptde(),
+            alias_cryptde(),
+            true,
+            Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            Some(1080),
+            Some(make_forward_proxy_config()),
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
+            false,
         );
-        let route_query_response = Some(RouteQueryResponse {
-            route: Route { hops: vec![] },
-            expected_services: ExpectedServices::RoundTrip(
-                vec![expected_service.clone()],
-                vec![expected_service],
-                123,
-            ),
-        });
-        let (neighborhood_mock, _, _) = make_recorder();
-        let neighborhood_mock =
-            neighborhood_mock.route_query_response(route_query_response.clone());
-        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
-        let stream_key = StreamKey::make_meaningless_stream_key();
-        let expected_data = http_request.to_vec();
-        let msg_from_dispatcher = InboundClientData {
-            timestamp: SystemTime::now(),
-            peer_addr: socket_addr.clone(),
-            reception_port: Some(HTTP_PORT),
-            sequence_number: Some(0),
-            last_data: true,
-            is_clandestine: false,
-            data: expected_data.clone(),
-        };
-
-        thread::spawn(move || {
-            let stream_key_factory = StreamKeyFactoryMock::new().make_result(stream_key);
-            let system = System::new("proxy_server_sends_a_message_when_dns_retry_found_a_route");
-            let mut subject = ProxyServer::new(
-                cryptde,
-                alias_cryptde(),
-                true,
-                Some(STANDARD_CONSUMING_WALLET_BALANCE),
-                false,
-            );
-            subject.stream_key_factory = Box::new(stream_key_factory);
-            let subject_addr: Addr<ProxyServer> = subject.start();
-            let mut peer_actors = peer_actors_builder()
-                .proxy_server(proxy_server_mock)
-                .neighborhood(neighborhood_mock)
-                .build();
-            // Get the dns_retry_result recipient so we can partially mock it...
-            let dns_retry_result_recipient = peer_actors.proxy_server.route_result_sub;
-            peer_actors.proxy_server = ProxyServer::make_subs_from(&subject_addr);
-            peer_actors.proxy_server.route_result_sub = dns_retry_result_recipient; //Partial mocking
-            subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let peer_actors = peer_actors_builder()
+            .dispatcher(dispatcher_mock)
+            .neighborhood(neighborhood_mock)
+            .build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
 
-            subject_addr.try_send(msg_from_dispatcher).unwrap();
+        subject_addr
+            .try_send(make_forward_proxy_ibcd(
+                socket_addr,
+                0,
+                b"GET /proxy.pac HTTP/1.1\r\nHost: 127.0.0.1:8080\r\n\r\n",
+            ))
+            .unwrap();
 
-            system.run();
-        });
-        let expected_route_result_message = AddRouteResultMessage {
-            stream_key,
-            result: Ok(route_query_response.unwrap()),
-        };
-        proxy_server_awaiter.await_message_count(1);
-        let recording = proxy_server_recording_arc.lock().unwrap();
-        let message = recording.get_record::<AddRouteResultMessage>(0);
-        assert_eq!(message, &expected_route_result_message);
+        System::current().stop();
+        system.run();
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        assert_eq!(
+            dispatcher_recording.get_record::<TransmitDataMsg>(0),
+            &TransmitDataMsg {
+                endpoint: Endpoint::Socket(socket_addr),
+                last_data: true,
+                sequence_number: Some(0),
+                data: pac_file_response(8080, Some(1080)),
+            }
+        );
+        assert_eq!(neighborhood_recording_arc.lock().unwrap().len(), 0);
     }
 
     #[test]
-    fn proxy_server_sends_a_message_when_dns_retry_cannot_find_a_route() {
-        let test_name = "proxy_server_sends_a_message_when_dns_retry_cannot_find_a_route";
-        let cryptde = main_cryptde();
-        let http_request = b"GET /index.html HTTP/1.1\r\nHost: nowhere.com\r\n\r\n";
-        let (proxy_server_mock, _, proxy_server_recording_arc) = make_recorder();
-        let proxy_server_mock =
-            proxy_server_mock.system_stop_conditions(match_every_type_id!(AddRouteResultMessage));
-        let route_query_response = None;
-        let (neighborhood_mock, _, _) = make_recorder();
-        let neighborhood_mock =
-            neighborhood_mock.route_query_response(route_query_response.clone());
-        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
-        let stream_key = StreamKey::make_meaningless_stream_key();
-        let expected_data = http_request.to_vec();
-        let msg_from_dispatcher = InboundClientData {
-            timestamp: SystemTime::now(),
-            peer_addr: socket_addr.clone(),
-            reception_port: Some(HTTP_PORT),
-            sequence_number: Some(0),
-            last_data: true,
-            is_clandestine: false,
-            data: expected_data.clone(),
-        };
-        let stream_key_factory = StreamKeyFactoryMock::new().make_result(stream_key);
+    fn proxy_server_refuses_other_origin_form_requests_on_forward_proxy_port() {
+        init_test_logging();
+        let test_name = "proxy_server_refuses_other_origin_form_requests_on_forward_proxy_port";
         let system = System::new(test_name);
+        let (dispatcher_mock, _, dispatcher_recording_arc) = make_recorder();
+        let (neighborhood_mock, _, neighborhood_recording_arc) = make_recorder();
+        let socket_addr = SocketAddr::from_str("127.0.0.1:5678").unwrap();
         let mut subject = ProxyServer::new(
-            cryptde,
+            main_cryptde(),
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            Some(make_forward_proxy_config()),
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         subject.logger = Logger::new(test_name);
-        subject.stream_key_factory = Box::new(stream_key_factory);
         let subject_addr: Addr<ProxyServer> = subject.start();
-        let mut peer_actors = peer_actors_builder()
-            .proxy_server(proxy_server_mock)
+        let peer_actors = peer_actors_builder()
+            .dispatcher(dispatcher_mock)
             .neighborhood(neighborhood_mock)
             .build();
-        // Get the dns_retry_result recipient so we can partially mock it...
-        let dns_retry_result_recipient = peer_actors.proxy_server.route_result_sub;
-        peer_actors.proxy_server.route_result_sub = dns_retry_result_recipient; //Partial mocking
         subject_addr.try_send(BindMessage { peer_actors }).unwrap();
 
-        subject_addr.try_send(msg_from_dispatcher).unwrap();
+        subject_addr
+            .try_send(make_forward_proxy_ibcd(
+                socket_addr,
+                0,
+                b"GET /index.html HTTP/1.1\r\nHost: example.com\r\n\r\n",
+            ))
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        assert_eq!(
+            dispatcher_recording.get_record::<TransmitDataMsg>(0),
+            &TransmitDataMsg {
+                endpoint: Endpoint::Socket(socket_addr),
+                last_data: true,
+                sequence_number: Some(0),
+                data: b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    .to_vec(),
+            }
+        );
+        assert_eq!(neighborhood_recording_arc.lock().unwrap().len(), 0);
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {}: Refusing forward-proxy request from 127.0.0.1:5678 with no absolute URI",
+            test_name
+        ));
+    }
+
+    #[test]
+    fn handle_client_response_payload_skips_connect_response_for_tunneled_stream() {
+        let system = System::new(
+            "handle_client_response_payload_skips_connect_response_for_tunneled_stream",
+        );
+        let (dispatcher_mock, _, dispatcher_recording_arc) = make_recorder();
+        let cryptde = main_cryptde();
+        let mut subject = ProxyServer::new(
+            cryptde,
+            alias_cryptde(),
+            true,
+            Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            Some(make_forward_proxy_config()),
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
+            false,
+        );
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        subject.keys_and_addrs.insert(stream_key, socket_addr);
+        subject
+            .tunneled_hosts
+            .insert(stream_key, "example.com".to_string());
+        subject.tunneled_ports.insert(stream_key, 22);
+        subject.route_ids_to_return_routes.insert(
+            1234,
+            AddReturnRouteMessage {
+                return_route_id: 1234,
+                expected_services: vec![ExpectedService::Nothing],
+                protocol: ProxyProtocol::TLS,
+                hostname_opt: Some("example.com".to_string()),
+            },
+        );
+        let client_response_payload = ClientResponsePayload_0v1 {
+            stream_key,
+            sequenced_packet: SequencedPacket {
+                data: b"some data".to_vec(),
+                sequence_number: 0,
+                last_data: false,
+            },
+        };
+        let expired_cores_package: ExpiredCoresPackage<ClientResponsePayload_0v1> =
+            ExpiredCoresPackage::new(
+                SocketAddr::from_str("1.2.3.4:1234").unwrap(),
+                Some(make_wallet("irrelevant")),
+                return_route_with_id(cryptde, 1234),
+                client_response_payload,
+                0,
+            );
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let peer_actors = peer_actors_builder().dispatcher(dispatcher_mock).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr.try_send(expired_cores_package).unwrap();
+
+        System::current().stop();
+        system.run();
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        let record = dispatcher_recording.get_record::<TransmitDataMsg>(0);
+        assert_eq!(record.sequence_number, Some(1));
+    }
+
+    #[test]
+    fn proxy_server_receives_http_request_with_no_consuming_wallet_and_sends_impersonated_response()
+    {
+        init_test_logging();
+        let cryptde = main_cryptde();
+        let http_request = b"GET /index.html HTTP/1.1\r\nHost: nowhere.com\r\n\r\n";
+        let (hopper, _, hopper_log_arc) = make_recorder();
+        let (neighborhood, _, neighborhood_log_arc) = make_recorder();
+        let (dispatcher, _, dispatcher_log_arc) = make_recorder();
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let expected_data = http_request.to_vec();
+        let msg_from_dispatcher = InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: socket_addr.clone(),
+            reception_port: Some(HTTP_PORT),
+            sequence_number: Some(0),
+            last_data: true,
+            is_clandestine: false,
+            data: expected_data.clone(),
+        };
+        let stream_key_factory = StreamKeyFactoryMock::new(); // can't make any stream keys; shouldn't have to
+        let system = System::new("proxy_server_receives_http_request_with_no_consuming_wallet_and_sends_impersonated_response");
+        let mut subject = ProxyServer::new(
+            cryptde,
+            alias_cryptde(),
+            true,
+            None,
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
+            false,
+        );
+        subject.stream_key_factory = Box::new(stream_key_factory);
+        subject.keys_and_addrs.insert(stream_key, socket_addr);
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let peer_actors = peer_actors_builder()
+            .dispatcher(dispatcher)
+            .hopper(hopper)
+            .neighborhood(neighborhood)
+            .build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr.try_send(msg_from_dispatcher).unwrap();
+
+        System::current().stop();
+        system.run();
+        let neighborhood_recording = neighborhood_log_arc.lock().unwrap();
+        assert!(neighborhood_recording.is_empty());
+        let hopper_recording = hopper_log_arc.lock().unwrap();
+        assert!(hopper_recording.is_empty());
+        let dispatcher_recording = dispatcher_log_arc.lock().unwrap();
+        let record = dispatcher_recording.get_record::<TransmitDataMsg>(0);
+        let server_impersonator = ServerImpersonatorHttp {};
+        assert_eq!(
+            record,
+            &TransmitDataMsg {
+                endpoint: Endpoint::Socket(socket_addr),
+                last_data: true,
+                sequence_number: Some(0),
+                data: server_impersonator.consuming_wallet_absent(),
+            }
+        );
+        TestLogHandler::new().exists_log_containing(
+            "ERROR: ProxyServer: Browser request rejected due to missing consuming wallet",
+        );
+    }
+
+    #[test]
+    fn proxy_server_receives_tls_request_with_no_consuming_wallet_and_sends_impersonated_response()
+    {
+        init_test_logging();
+        let cryptde = main_cryptde();
+        let tls_request = b"Fake TLS request";
+        let (hopper, _, hopper_log_arc) = make_recorder();
+        let (neighborhood, _, neighborhood_log_arc) = make_recorder();
+        let (dispatcher, _, dispatcher_log_arc) = make_recorder();
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let expected_data = tls_request.to_vec();
+        let msg_from_dispatcher = InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: socket_addr.clone(),
+            reception_port: Some(TLS_PORT),
+            sequence_number: Some(0),
+            last_data: true,
+            is_clandestine: false,
+            data: expected_data.clone(),
+        };
+        let stream_key_factory = StreamKeyFactoryMock::new(); // can't make any stream keys; shouldn't have to
+        let system = System::new("proxy_server_receives_tls_request_with_no_consuming_wallet_and_sends_impersonated_response");
+        let mut subject = ProxyServer::new(
+            cryptde,
+            alias_cryptde(),
+            true,
+            None,
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
+            false,
+        );
+        subject.stream_key_factory = Box::new(stream_key_factory);
+        subject.keys_and_addrs.insert(stream_key, socket_addr);
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let peer_actors = peer_actors_builder()
+            .dispatcher(dispatcher)
+            .hopper(hopper)
+            .neighborhood(neighborhood)
+            .build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr.try_send(msg_from_dispatcher).unwrap();
+
+        System::current().stop();
+        system.run();
+        let neighborhood_recording = neighborhood_log_arc.lock().unwrap();
+        assert!(neighborhood_recording.is_empty());
+        let hopper_recording = hopper_log_arc.lock().unwrap();
+        assert!(hopper_recording.is_empty());
+        let dispatcher_recording = dispatcher_log_arc.lock().unwrap();
+        let record = dispatcher_recording.get_record::<TransmitDataMsg>(0);
+        let server_impersonator = ServerImpersonatorTls {};
+        assert_eq!(
+            record,
+            &TransmitDataMsg {
+                endpoint: Endpoint::Socket(socket_addr),
+                last_data: true,
+                sequence_number: Some(0),
+                data: server_impersonator.consuming_wallet_absent(),
+            }
+        );
+        TestLogHandler::new().exists_log_containing(
+            "ERROR: ProxyServer: Browser request rejected due to missing consuming wallet",
+        );
+    }
+
+    #[test]
+    fn proxy_server_receives_http_request_with_no_consuming_wallet_in_zero_hop_mode_and_handles_normally(
+    ) {
+        init_test_logging();
+        let main_cryptde = main_cryptde();
+        let alias_cryptde = alias_cryptde();
+        let expected_data = b"GET /index.html HTTP/1.1\r\nHost: nowhere.com\r\n\r\n".to_vec();
+        let expected_data_inner = expected_data.clone();
+        let expected_route = zero_hop_route_response(main_cryptde.public_key(), main_cryptde);
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let (hopper, hopper_awaiter, hopper_log_arc) = make_recorder();
+        let neighborhood = Recorder::new().route_query_response(Some(expected_route.clone()));
+        let neighborhood_log_arc = neighborhood.get_recording();
+        let (dispatcher, _, dispatcher_log_arc) = make_recorder();
+        thread::spawn(move || {
+            let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+            let msg_from_dispatcher = InboundClientData {
+                timestamp: SystemTime::now(),
+                peer_addr: socket_addr.clone(),
+                reception_port: Some(HTTP_PORT),
+                sequence_number: Some(0),
+                last_data: true,
+                is_clandestine: false,
+                data: expected_data_inner,
+            };
+            let stream_key_factory = StreamKeyFactoryMock::new(); // can't make any stream keys; shouldn't have to
+            let system = System::new("proxy_server_receives_http_request_with_no_consuming_wallet_in_zero_hop_mode_and_handles_normally");
+            let mut subject = ProxyServer::new(
+                main_cryptde,
+                alias_cryptde,
+                false,
+                None,
+                None,
+                None,
+                DEFAULT_STICKY_ROUTE_TTL,
+                DEFAULT_MULTIPATH_ROUTES,
+                false,
+            );
+            subject.stream_key_factory = Box::new(stream_key_factory);
+            subject.keys_and_addrs.insert(stream_key, socket_addr);
+            let subject_addr: Addr<ProxyServer> = subject.start();
+            let peer_actors = peer_actors_builder()
+                .dispatcher(dispatcher)
+                .hopper(hopper)
+                .neighborhood(neighborhood)
+                .build();
+            subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+            subject_addr.try_send(msg_from_dispatcher).unwrap();
+
+            system.run();
+        });
+        hopper_awaiter.await_message_count(1);
+        let neighborhood_recording = neighborhood_log_arc.lock().unwrap();
+        assert_eq!(
+            neighborhood_recording.get_record::<RouteQueryMessage>(0),
+            &RouteQueryMessage {
+                target_key_opt: None,
+                target_component: Component::ProxyClient,
+                return_component_opt: Some(Component::ProxyServer),
+                payload_size: 47,
+                hostname_opt: Some("nowhere.com".to_string()),
+                target_port_opt: Some(80),
+                avoided_keys: vec![],
+            }
+        );
+        let dispatcher_recording = dispatcher_log_arc.lock().unwrap();
+        assert!(dispatcher_recording.is_empty());
+        let hopper_recording = hopper_log_arc.lock().unwrap();
+        assert_eq!(
+            hopper_recording.get_record::<IncipientCoresPackage>(0),
+            &IncipientCoresPackage::new(
+                main_cryptde,
+                expected_route.route,
+                MessageType::ClientRequest(VersionedData::new(
+                    &crate::sub_lib::migrations::client_request_payload::MIGRATIONS,
+                    &ClientRequestPayload_0v1 {
+                        stream_key,
+                        sequenced_packet: SequencedPacket::new(expected_data, 0, true),
+                        target_hostname: Some("nowhere.com".to_string()),
+                        target_port: 80,
+                        protocol: ProxyProtocol::HTTP,
+                        originator_public_key: alias_cryptde.public_key().clone(),
+                    }
+                )),
+                main_cryptde.public_key()
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn proxy_server_receives_tls_request_with_no_consuming_wallet_in_zero_hop_mode_and_handles_normally(
+    ) {
+        init_test_logging();
+        let main_cryptde = main_cryptde();
+        let alias_cryptde = alias_cryptde();
+        let expected_data = b"Fake TLS request".to_vec();
+        let expected_data_inner = expected_data.clone();
+        let expected_route = zero_hop_route_response(main_cryptde.public_key(), main_cryptde);
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let (hopper, hopper_awaiter, hopper_log_arc) = make_recorder();
+        let neighborhood = Recorder::new().route_query_response(Some(expected_route.clone()));
+        let neighborhood_log_arc = neighborhood.get_recording();
+        let (dispatcher, _, dispatcher_log_arc) = make_recorder();
+        thread::spawn(move || {
+            let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+            let msg_from_dispatcher = InboundClientData {
+                timestamp: SystemTime::now(),
+                peer_addr: socket_addr.clone(),
+                reception_port: Some(TLS_PORT),
+                sequence_number: Some(0),
+                last_data: true,
+                is_clandestine: false,
+                data: expected_data_inner,
+            };
+            let stream_key_factory = StreamKeyFactoryMock::new(); // can't make any stream keys; shouldn't have to
+            let system = System::new("proxy_server_receives_tls_request_with_no_consuming_wallet_in_zero_hop_mode_and_handles_normally");
+            let mut subject = ProxyServer::new(
+                main_cryptde,
+                alias_cryptde,
+                false,
+                None,
+                None,
+                None,
+                DEFAULT_STICKY_ROUTE_TTL,
+                DEFAULT_MULTIPATH_ROUTES,
+                false,
+            );
+            subject.stream_key_factory = Box::new(stream_key_factory);
+            subject.keys_and_addrs.insert(stream_key, socket_addr);
+            let subject_addr: Addr<ProxyServer> = subject.start();
+            let peer_actors = peer_actors_builder()
+                .dispatcher(dispatcher)
+                .hopper(hopper)
+                .neighborhood(neighborhood)
+                .build();
+            subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+            subject_addr.try_send(msg_from_dispatcher).unwrap();
+
+            system.run();
+        });
+        hopper_awaiter.await_message_count(1);
+        let neighborhood_recording = neighborhood_log_arc.lock().unwrap();
+        assert_eq!(
+            neighborhood_recording.get_record::<RouteQueryMessage>(0),
+            &RouteQueryMessage {
+                target_key_opt: None,
+                target_component: Component::ProxyClient,
+                return_component_opt: Some(Component::ProxyServer),
+                payload_size: 16,
+                hostname_opt: None,
+                target_port_opt: Some(443),
+                avoided_keys: vec![],
+            }
+        );
+        let dispatcher_recording = dispatcher_log_arc.lock().unwrap();
+        assert!(dispatcher_recording.is_empty());
+        let hopper_recording = hopper_log_arc.lock().unwrap();
+        assert_eq!(
+            hopper_recording.get_record::<IncipientCoresPackage>(0),
+            &IncipientCoresPackage::new(
+                main_cryptde,
+                expected_route.route,
+                MessageType::ClientRequest(VersionedData::new(
+                    &crate::sub_lib::migrations::client_request_payload::MIGRATIONS,
+                    &ClientRequestPayload_0v1 {
+                        stream_key,
+                        sequenced_packet: SequencedPacket::new(expected_data, 0, true),
+                        target_hostname: None,
+                        target_port: 443,
+                        protocol: ProxyProtocol::TLS,
+                        originator_public_key: alias_cryptde.public_key().clone(),
+                    }
+                ),),
+                main_cryptde.public_key()
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn proxy_server_receives_http_request_with_existing_stream_key_from_dispatcher_then_sends_cores_package_to_hopper(
+    ) {
+        let main_cryptde = main_cryptde();
+        let alias_cryptde = alias_cryptde();
+        let http_request = b"GET /index.html HTTP/1.1\r\nHost: nowhere.com\r\n\r\n";
+        let hopper_mock = Recorder::new();
+        let hopper_log_arc = hopper_mock.get_recording();
+        let hopper_awaiter = hopper_mock.get_awaiter();
+        let destination_key = PublicKey::from(&b"our destination"[..]);
+        let neighborhood_mock = Recorder::new().route_query_response(Some(RouteQueryResponse {
+            route: Route { hops: vec![] },
+            expected_services: ExpectedServices::RoundTrip(
+                vec![make_exit_service_from_key(destination_key.clone())],
+                vec![],
+                1234,
+            ),
+        }));
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let expected_data = http_request.to_vec();
+        let msg_from_dispatcher = InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: socket_addr.clone(),
+            reception_port: Some(HTTP_PORT),
+            sequence_number: Some(0),
+            last_data: true,
+            is_clandestine: false,
+            data: expected_data.clone(),
+        };
+        let expected_http_request = PlainData::new(http_request);
+        let route = Route { hops: vec![] };
+        let expected_payload = ClientRequestPayload_0v1 {
+            stream_key: stream_key.clone(),
+            sequenced_packet: SequencedPacket {
+                data: expected_http_request.into(),
+                sequence_number: 0,
+                last_data: true,
+            },
+            target_hostname: Some(String::from("nowhere.com")),
+            target_port: HTTP_PORT,
+            protocol: ProxyProtocol::HTTP,
+            originator_public_key: alias_cryptde.public_key().clone(),
+        };
+        let expected_pkg = IncipientCoresPackage::new(
+            main_cryptde,
+            route.clone(),
+            expected_payload.into(),
+            &destination_key,
+        )
+        .unwrap();
+        thread::spawn(move || {
+            let stream_key_factory = StreamKeyFactoryMock::new(); // can't make any stream keys; shouldn't have to
+            let system = System::new("proxy_server_receives_http_request_from_dispatcher_then_sends_cores_package_to_hopper");
+            let mut subject = ProxyServer::new(
+                main_cryptde,
+                alias_cryptde,
+                true,
+                Some(STANDARD_CONSUMING_WALLET_BALANCE),
+                None,
+                None,
+                DEFAULT_STICKY_ROUTE_TTL,
+                DEFAULT_MULTIPATH_ROUTES,
+                false,
+            );
+            subject.stream_key_factory = Box::new(stream_key_factory);
+            subject.keys_and_addrs.insert(stream_key, socket_addr);
+            let subject_addr: Addr<ProxyServer> = subject.start();
+            let peer_actors = peer_actors_builder()
+                .hopper(hopper_mock)
+                .neighborhood(neighborhood_mock)
+                .build();
+            subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+            subject_addr.try_send(msg_from_dispatcher).unwrap();
+
+            system.run();
+        });
+
+        hopper_awaiter.await_message_count(1);
+        let recording = hopper_log_arc.lock().unwrap();
+        let record = recording.get_record::<IncipientCoresPackage>(0);
+        assert_eq!(record, &expected_pkg);
+    }
+
+    #[test]
+    fn proxy_server_receives_http_request_from_dispatcher_then_sends_multihop_cores_package_to_hopper(
+    ) {
+        let main_cryptde = main_cryptde();
+        let alias_cryptde = alias_cryptde();
+        let consuming_wallet = make_paying_wallet(b"paying wallet");
+        let earning_wallet = make_wallet("earning wallet");
+        let http_request = b"GET /index.html HTTP/1.1\r\nHost: nowhere.com\r\n\r\n";
+        let hopper_mock = Recorder::new();
+        let hopper_log_arc = hopper_mock.get_recording();
+        let hopper_awaiter = hopper_mock.get_awaiter();
+        let payload_destination_key = PublicKey::new(&[3]);
+        let route = Route::round_trip(
+            RouteSegment::new(
+                vec![
+                    &main_cryptde.public_key(),
+                    &PublicKey::new(&[1]),
+                    &PublicKey::new(&[2]),
+                    &payload_destination_key,
+                ],
+                Component::ProxyClient,
+            ),
+            RouteSegment::new(
+                vec![
+                    &payload_destination_key,
+                    &PublicKey::new(&[2]),
+                    &PublicKey::new(&[1]),
+                    &main_cryptde.public_key(),
+                ],
+                Component::ProxyServer,
+            ),
+            main_cryptde,
+            Some(consuming_wallet),
+            1234,
+            Some(TEST_DEFAULT_CHAIN.rec().contract),
+        )
+        .unwrap();
+        let (neighborhood_mock, _, neighborhood_recording_arc) = make_recorder();
+        let neighborhood_mock = neighborhood_mock.route_query_response(Some(RouteQueryResponse {
+            route: route.clone(),
+            expected_services: ExpectedServices::RoundTrip(
+                vec![
+                    ExpectedService::Exit(
+                        PublicKey::new(&[3]),
+                        earning_wallet.clone(),
+                        rate_pack(101),
+                    ),
+                    ExpectedService::Nothing,
+                ],
+                vec![
+                    ExpectedService::Nothing,
+                    ExpectedService::Exit(PublicKey::new(&[3]), earning_wallet, rate_pack(102)),
+                ],
+                1234,
+            ),
+        }));
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let expected_data = http_request.to_vec();
+        let msg_from_dispatcher = InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: socket_addr.clone(),
+            reception_port: Some(HTTP_PORT),
+            sequence_number: Some(0),
+            last_data: true,
+            is_clandestine: false,
+            data: expected_data.clone(),
+        };
+        let expected_http_request = PlainData::new(http_request);
+        let expected_payload = ClientRequestPayload_0v1 {
+            stream_key: stream_key.clone(),
+            sequenced_packet: SequencedPacket {
+                data: expected_http_request.into(),
+                sequence_number: 0,
+                last_data: true,
+            },
+            target_hostname: Some(String::from("nowhere.com")),
+            target_port: HTTP_PORT,
+            protocol: ProxyProtocol::HTTP,
+            originator_public_key: alias_cryptde.public_key().clone(),
+        };
+        let expected_pkg = IncipientCoresPackage::new(
+            main_cryptde,
+            route.clone(),
+            expected_payload.into(),
+            &payload_destination_key,
+        )
+        .unwrap();
+        thread::spawn(move || {
+            let stream_key_factory = StreamKeyFactoryMock::new().make_result(stream_key);
+            let system = System::new("proxy_server_receives_http_request_from_dispatcher_then_sends_multihop_cores_package_to_hopper");
+            let mut subject = ProxyServer::new(
+                main_cryptde,
+                alias_cryptde,
+                true,
+                Some(STANDARD_CONSUMING_WALLET_BALANCE),
+                None,
+                None,
+                DEFAULT_STICKY_ROUTE_TTL,
+                DEFAULT_MULTIPATH_ROUTES,
+                false,
+            );
+            subject.stream_key_factory = Box::new(stream_key_factory);
+            let subject_addr: Addr<ProxyServer> = subject.start();
+            let peer_actors = peer_actors_builder()
+                .hopper(hopper_mock)
+                .neighborhood(neighborhood_mock)
+                .build();
+            subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+            subject_addr.try_send(msg_from_dispatcher).unwrap();
+
+            system.run();
+        });
+
+        hopper_awaiter.await_message_count(1);
+        let recording = hopper_log_arc.lock().unwrap();
+        let record = recording.get_record::<IncipientCoresPackage>(0);
+        assert_eq!(record, &expected_pkg);
+        let recording = neighborhood_recording_arc.lock().unwrap();
+        let record = recording.get_record::<RouteQueryMessage>(0);
+        assert_eq!(
+            record,
+            &RouteQueryMessage::data_indefinite_route_request(
+                Some("nowhere.com".to_string()),
+                Some(80),
+                47
+            )
+        );
+    }
+
+    #[test]
+    fn proxy_server_sends_a_message_when_dns_retry_found_a_route() {
+        let cryptde = main_cryptde();
+        let http_request = b"GET /index.html HTTP/1.1\r\nHost: nowhere.com\r\n\r\n";
+        let (proxy_server_mock, proxy_server_awaiter, proxy_server_recording_arc) = make_recorder();
+        let expected_service = ExpectedService::Exit(
+            main_cryptde().public_key().clone(),
+            make_wallet("walletAddress"),
+            DEFAULT_RATE_PACK,
+        );
+        let route_query_response = Some(RouteQueryResponse {
+            route: Route { hops: vec![] },
+            expected_services: ExpectedServices::RoundTrip(
+                vec![expected_service.clone()],
+                vec![expected_service],
+                123,
+            ),
+        });
+        let (neighborhood_mock, _, _) = make_recorder();
+        let neighborhood_mock =
+            neighborhood_mock.route_query_response(route_query_response.clone());
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let expected_data = http_request.to_vec();
+        let msg_from_dispatcher = InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: socket_addr.clone(),
+            reception_port: Some(HTTP_PORT),
+            sequence_number: Some(0),
+            last_data: true,
+            is_clandestine: false,
+            data: expected_data.clone(),
+        };
+
+        thread::spawn(move || {
+            let stream_key_factory = StreamKeyFactoryMock::new().make_result(stream_key);
+            let system = System::new("proxy_server_sends_a_message_when_dns_retry_found_a_route");
+            let mut subject = ProxyServer::new(
+                cryptde,
+                alias_cryptde(),
+                true,
+                Some(STANDARD_CONSUMING_WALLET_BALANCE),
+                None,
+                None,
+                DEFAULT_STICKY_ROUTE_TTL,
+                DEFAULT_MULTIPATH_ROUTES,
+                false,
+            );
+            subject.stream_key_factory = Box::new(stream_key_factory);
+            let subject_addr: Addr<ProxyServer> = subject.start();
+            let mut peer_actors = peer_actors_builder()
+                .proxy_server(proxy_server_mock)
+                .neighborhood(neighborhood_mock)
+                .build();
+            // Get the dns_retry_result recipient so we can partially mock it...
+            let dns_retry_result_recipient = peer_actors.proxy_server.route_result_sub;
+            peer_actors.proxy_server = ProxyServer::make_subs_from(&subject_addr);
+            peer_actors.proxy_server.route_result_sub = dns_retry_result_recipient; //Partial mocking
+            subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+            subject_addr.try_send(msg_from_dispatcher).unwrap();
+
+            system.run();
+        });
+        let expected_route_result_message = AddRouteResultMessage {
+            stream_key,
+            result: Ok(route_query_response.unwrap()),
+        };
+        proxy_server_awaiter.await_message_count(1);
+        let recording = proxy_server_recording_arc.lock().unwrap();
+        let message = recording.get_record::<AddRouteResultMessage>(0);
+        assert_eq!(message, &expected_route_result_message);
+    }
+
+    #[test]
+    fn proxy_server_sends_a_message_when_dns_retry_cannot_find_a_route() {
+        let test_name = "proxy_server_sends_a_message_when_dns_retry_cannot_find_a_route";
+        let cryptde = main_cryptde();
+        let http_request = b"GET /index.html HTTP/1.1\r\nHost: nowhere.com\r\n\r\n";
+        let (proxy_server_mock, _, proxy_server_recording_arc) = make_recorder();
+        let proxy_server_mock =
+            proxy_server_mock.system_stop_conditions(match_every_type_id!(AddRouteResultMessage));
+        let route_query_response = None;
+        let (neighborhood_mock, _, _) = make_recorder();
+        let neighborhood_mock =
+            neighborhood_mock.route_query_response(route_query_response.clone());
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let expected_data = http_request.to_vec();
+        let msg_from_dispatcher = InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: socket_addr.clone(),
+            reception_port: Some(HTTP_PORT),
+            sequence_number: Some(0),
+            last_data: true,
+            is_clandestine: false,
+            data: expected_data.clone(),
+        };
+        let stream_key_factory = StreamKeyFactoryMock::new().make_result(stream_key);
+        let system = System::new(test_name);
+        let mut subject = ProxyServer::new(
+            cryptde,
+            alias_cryptde(),
+            true,
+            Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
+            false,
+        );
+        subject.logger = Logger::new(test_name);
+        subject.stream_key_factory = Box::new(stream_key_factory);
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let mut peer_actors = peer_actors_builder()
+            .proxy_server(proxy_server_mock)
+            .neighborhood(neighborhood_mock)
+            .build();
+        // Get the dns_retry_result recipient so we can partially mock it...
+        let dns_retry_result_recipient = peer_actors.proxy_server.route_result_sub;
+        peer_actors.proxy_server.route_result_sub = dns_retry_result_recipient; //Partial mocking
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr.try_send(msg_from_dispatcher).unwrap();
+
+        system.run();
+        let recording = proxy_server_recording_arc.lock().unwrap();
+        let message = recording.get_record::<AddRouteResultMessage>(0);
+        assert_eq!(message.stream_key, stream_key);
+        assert_eq!(
+            message.result,
+            Err(format!(
+                "Failed to find route to nowhere.com for stream key: {stream_key}"
+            ))
+        );
+    }
+
+    #[test]
+    fn proxy_server_uses_existing_route() {
+        let main_cryptde = main_cryptde();
+        let alias_cryptde = alias_cryptde();
+        let http_request = b"GET /index.html HTTP/1.1\r\nHost: nowhere.com\r\n\r\n";
+        let destination_key = PublicKey::from(&b"our destination"[..]);
+        let route_query_response = RouteQueryResponse {
+            route: Route { hops: vec![] },
+            expected_services: ExpectedServices::RoundTrip(
+                vec![make_exit_service_from_key(destination_key.clone())],
+                vec![],
+                1234,
+            ),
+        };
+        let (hopper_mock, hopper_awaiter, hopper_recording_arc) = make_recorder();
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let expected_data = http_request.to_vec();
+        let msg_from_dispatcher = InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: socket_addr.clone(),
+            reception_port: Some(HTTP_PORT),
+            sequence_number: Some(0),
+            last_data: true,
+            is_clandestine: false,
+            data: expected_data.clone(),
+        };
+        let expected_payload = ClientRequestPayload_0v1 {
+            stream_key: stream_key.clone(),
+            sequenced_packet: SequencedPacket {
+                data: PlainData::new(http_request).into(),
+                sequence_number: 0,
+                last_data: true,
+            },
+            target_hostname: Some(String::from("nowhere.com")),
+            target_port: HTTP_PORT,
+            protocol: ProxyProtocol::HTTP,
+            originator_public_key: alias_cryptde.public_key().clone(),
+        };
+        let expected_pkg = IncipientCoresPackage::new(
+            main_cryptde,
+            Route { hops: vec![] },
+            expected_payload.into(),
+            &destination_key,
+        )
+        .unwrap();
+
+        thread::spawn(move || {
+            let stream_key_factory = StreamKeyFactoryMock::new().make_result(stream_key);
+            let system = System::new("proxy_server_uses_existing_route");
+            let mut subject = ProxyServer::new(
+                main_cryptde,
+                alias_cryptde,
+                true,
+                Some(STANDARD_CONSUMING_WALLET_BALANCE),
+                None,
+                None,
+                DEFAULT_STICKY_ROUTE_TTL,
+                DEFAULT_MULTIPATH_ROUTES,
+                false,
+            );
+            subject.stream_key_factory = Box::new(stream_key_factory);
+            subject
+                .stream_key_routes
+                .insert(stream_key, route_query_response);
+            let subject_addr: Addr<ProxyServer> = subject.start();
+            let peer_actors = peer_actors_builder().hopper(hopper_mock).build();
+            subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+            subject_addr.try_send(msg_from_dispatcher).unwrap();
+
+            System::current().stop();
+            system.run();
+        });
+
+        hopper_awaiter.await_message_count(1);
+        let recording = hopper_recording_arc.lock().unwrap();
+        let record = recording.get_record::<IncipientCoresPackage>(0);
+        assert_eq!(record, &expected_pkg);
+    }
+
+    #[test]
+    fn proxy_server_reuses_sticky_route_for_new_stream_to_the_same_hostname() {
+        init_test_logging();
+        let test_name = "proxy_server_reuses_sticky_route_for_new_stream_to_the_same_hostname";
+        let main_cryptde = main_cryptde();
+        let alias_cryptde = alias_cryptde();
+        let http_request = b"GET /index.html HTTP/1.1\r\nHost: nowhere.com\r\n\r\n";
+        let destination_key = PublicKey::from(&b"our destination"[..]);
+        let route_query_response = RouteQueryResponse {
+            route: Route { hops: vec![] },
+            expected_services: ExpectedServices::RoundTrip(
+                vec![make_exit_service_from_key(destination_key.clone())],
+                vec![],
+                1234,
+            ),
+        };
+        let (hopper_mock, hopper_awaiter, hopper_recording_arc) = make_recorder();
+        let (neighborhood_mock, _, neighborhood_recording_arc) = make_recorder();
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let msg_from_dispatcher = InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: socket_addr,
+            reception_port: Some(HTTP_PORT),
+            sequence_number: Some(0),
+            last_data: true,
+            is_clandestine: false,
+            data: http_request.to_vec(),
+        };
+        let expected_payload = ClientRequestPayload_0v1 {
+            stream_key,
+            sequenced_packet: SequencedPacket {
+                data: PlainData::new(http_request).into(),
+                sequence_number: 0,
+                last_data: true,
+            },
+            target_hostname: Some(String::from("nowhere.com")),
+            target_port: HTTP_PORT,
+            protocol: ProxyProtocol::HTTP,
+            originator_public_key: alias_cryptde.public_key().clone(),
+        };
+        let expected_pkg = IncipientCoresPackage::new(
+            main_cryptde,
+            Route { hops: vec![] },
+            expected_payload.into(),
+            &destination_key,
+        )
+        .unwrap();
+
+        thread::spawn(move || {
+            let stream_key_factory = StreamKeyFactoryMock::new().make_result(stream_key);
+            let system = System::new(test_name);
+            let mut subject = ProxyServer::new(
+                main_cryptde,
+                alias_cryptde,
+                true,
+                Some(STANDARD_CONSUMING_WALLET_BALANCE),
+                None,
+                None,
+                DEFAULT_STICKY_ROUTE_TTL,
+                DEFAULT_MULTIPATH_ROUTES,
+                false,
+            );
+            subject.logger = Logger::new(test_name);
+            subject.stream_key_factory = Box::new(stream_key_factory);
+            subject
+                .sticky_routes_opt
+                .as_mut()
+                .unwrap()
+                .insert("nowhere.com".to_string(), route_query_response.clone());
+            let subject_addr: Addr<ProxyServer> = subject.start();
+            let peer_actors = peer_actors_builder()
+                .hopper(hopper_mock)
+                .neighborhood(neighborhood_mock)
+                .build();
+            subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+            subject_addr.try_send(msg_from_dispatcher).unwrap();
+            subject_addr
+                .try_send(AssertionsMessage {
+                    assertions: Box::new(move |proxy_server: &mut ProxyServer| {
+                        assert_eq!(
+                            proxy_server.stream_key_routes.get(&stream_key),
+                            Some(&route_query_response)
+                        );
+                    }),
+                })
+                .unwrap();
+
+            System::current().stop();
+            system.run();
+        });
+
+        hopper_awaiter.await_message_count(1);
+        let recording = hopper_recording_arc.lock().unwrap();
+        let record = recording.get_record::<IncipientCoresPackage>(0);
+        assert_eq!(record, &expected_pkg);
+        let neighborhood_recording = neighborhood_recording_arc.lock().unwrap();
+        assert_eq!(neighborhood_recording.len(), 0);
+        TestLogHandler::new().exists_log_containing(&format!(
+            "DEBUG: {test_name}: Reusing the route to nowhere.com for new stream {stream_key}"
+        ));
+    }
+
+    #[test]
+    fn sticky_routes_are_disabled_by_a_zero_ttl_and_in_zero_hop_mode() {
+        let make_subject = |is_decentralized: bool, sticky_route_ttl: Duration| {
+            ProxyServer::new(
+                main_cryptde(),
+                alias_cryptde(),
+                is_decentralized,
+                Some(STANDARD_CONSUMING_WALLET_BALANCE),
+                None,
+                None,
+                sticky_route_ttl,
+                DEFAULT_MULTIPATH_ROUTES,
+                false,
+            )
+        };
+
+        let zero_ttl = make_subject(true, Duration::ZERO);
+        let zero_hop = make_subject(false, DEFAULT_STICKY_ROUTE_TTL);
+        let standard = make_subject(true, Duration::from_secs(60));
+
+        assert!(zero_ttl.sticky_routes_opt.is_none());
+        assert!(zero_hop.sticky_routes_opt.is_none());
+        assert_eq!(
+            standard.sticky_routes_opt.unwrap().ttl(),
+            Duration::from_secs(60)
+        );
+    }
+
+    #[test]
+    fn proxy_server_remembers_a_new_route_for_its_hostname() {
+        let system = System::new("proxy_server_remembers_a_new_route_for_its_hostname");
+        let mut subject = ProxyServer::new(
+            main_cryptde(),
+            alias_cryptde(),
+            true,
+            Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
+            false,
+        );
+        let payload = make_request_payload(0, main_cryptde());
+        let stream_key = payload.stream_key;
+        subject.dns_failure_retries.insert(
+            stream_key,
+            DNSFailureRetry {
+                unsuccessful_request: payload,
+                retries_left: 3,
+            },
+        );
+        let route_query_response = RouteQueryResponse {
+            route: Route { hops: vec![] },
+            expected_services: ExpectedServices::RoundTrip(
+                vec![make_exit_service_from_key(PublicKey::new(&[1, 2, 3]))],
+                vec![],
+                1234,
+            ),
+        };
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let peer_actors = peer_actors_builder().build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr
+            .try_send(AddRouteResultMessage {
+                stream_key,
+                result: Ok(route_query_response.clone()),
+            })
+            .unwrap();
+
+        subject_addr
+            .try_send(AssertionsMessage {
+                assertions: Box::new(move |proxy_server: &mut ProxyServer| {
+                    let sticky_routes = proxy_server.sticky_routes_opt.as_ref().unwrap();
+                    assert_eq!(
+                        sticky_routes
+                            .get(&"example.com".to_string())
+                            .map(|route| route.as_ref().clone()),
+                        Some(route_query_response)
+                    );
+                }),
+            })
+            .unwrap();
+        System::current().stop();
+        system.run();
+    }
+
+    #[test]
+    fn proxy_server_forgets_the_sticky_route_to_a_hostname_it_found_no_route_to() {
+        let system =
+            System::new("proxy_server_forgets_the_sticky_route_to_a_hostname_it_found_no_route_to");
+        let mut subject = ProxyServer::new(
+            main_cryptde(),
+            alias_cryptde(),
+            true,
+            Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
+            false,
+        );
+        let payload = make_request_payload(0, main_cryptde());
+        let stream_key = payload.stream_key;
+        subject.dns_failure_retries.insert(
+            stream_key,
+            DNSFailureRetry {
+                unsuccessful_request: payload,
+                retries_left: 3,
+            },
+        );
+        let sticky_routes = subject.sticky_routes_opt.as_mut().unwrap();
+        sticky_routes.insert("example.com".to_string(), make_sticky_route());
+        sticky_routes.insert("elsewhere.com".to_string(), make_sticky_route());
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let peer_actors = peer_actors_builder().build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr
+            .try_send(AddRouteResultMessage {
+                stream_key,
+                result: Err("Failed to find route to example.com".to_string()),
+            })
+            .unwrap();
+
+        subject_addr
+            .try_send(AssertionsMessage {
+                assertions: Box::new(move |proxy_server: &mut ProxyServer| {
+                    let sticky_routes = proxy_server.sticky_routes_opt.as_ref().unwrap();
+                    assert_eq!(sticky_routes.get(&"example.com".to_string()), None);
+                    assert!(sticky_routes.get(&"elsewhere.com".to_string()).is_some());
+                }),
+            })
+            .unwrap();
+        System::current().stop();
+        system.run();
+    }
+
+    #[test]
+    fn proxy_server_forgets_sticky_routes_when_routing_preferences_change() {
+        let mut subject = ProxyServer::new(
+            main_cryptde(),
+            alias_cryptde(),
+            true,
+            Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
+            false,
+        );
+        let mut forgets = |change: ConfigChange| {
+            subject
+                .sticky_routes_opt
+                .as_mut()
+                .unwrap()
+                .insert("example.com".to_string(), make_sticky_route());
+            subject.handle_config_change_msg(ConfigChangeMsg { change });
+            subject
+                .sticky_routes_opt
+                .as_ref()
+                .unwrap()
+                .get(&"example.com".to_string())
+                .is_none()
+        };
+
+        assert!(forgets(ConfigChange::UpdatePreferredExits(None)));
+        assert!(forgets(ConfigChange::UpdateExcludedNodes(vec![
+            PublicKey::new(&[1, 2, 3])
+        ])));
+        assert!(forgets(ConfigChange::UpdateMaxRatePack(Some(rate_pack(
+            100
+        )))));
+        assert!(forgets(ConfigChange::UpdateMinHops(Hops::TwoHops)));
+        assert!(!forgets(ConfigChange::UpdatePassword("booga".to_string())));
+    }
+
+    fn make_sticky_route() -> RouteQueryResponse {
+        RouteQueryResponse {
+            route: Route { hops: vec![] },
+            expected_services: ExpectedServices::RoundTrip(
+                vec![make_exit_service_from_key(PublicKey::new(&[9, 9, 9]))],
+                vec![],
+                4321,
+            ),
+        }
+    }
+
+    fn make_route_through(
+        relay_key: &[u8],
+        exit_key: &[u8],
+        return_route_id: u32,
+    ) -> RouteQueryResponse {
+        let relay = ExpectedService::Routing(
+            PublicKey::new(relay_key),
+            make_wallet(&format!("relay wallet {:?}", relay_key)),
+            rate_pack(101),
+        );
+        let exit = make_exit_service_from_key(PublicKey::new(exit_key));
+        RouteQueryResponse {
+            route: Route { hops: vec![] },
+            expected_services: ExpectedServices::RoundTrip(
+                vec![ExpectedService::Nothing, relay.clone(), exit.clone()],
+                vec![exit, relay, ExpectedService::Nothing],
+                return_route_id,
+            ),
+        }
+    }
+
+    fn make_rerouting_request(
+        stream_key: StreamKey,
+        protocol: ProxyProtocol,
+        data: &[u8],
+        sequence_number: u64,
+    ) -> ClientRequestPayload_0v1 {
+        ClientRequestPayload_0v1 {
+            stream_key,
+            sequenced_packet: SequencedPacket::new(data.to_vec(), sequence_number, false),
+            target_hostname: Some("example.com".to_string()),
+            target_port: if protocol == ProxyProtocol::TLS {
+                TLS_PORT
+            } else {
+                HTTP_PORT
+            },
+            protocol,
+            originator_public_key: alias_cryptde().public_key().clone(),
+        }
+    }
+
+    fn make_decentralized_proxy_server() -> ProxyServer {
+        ProxyServer::new(
+            main_cryptde(),
+            alias_cryptde(),
+            true,
+            Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
+            false,
+        )
+    }
+
+    #[test]
+    fn stream_progress_replays_only_whole_requests_the_server_may_safely_see_twice() {
+        let stream_key = StreamKey::make_meaningful_stream_key("replays");
+        let progress = |protocol: ProxyProtocol,
+                        data: &[u8],
+                        unanswered_packets: usize,
+                        next_response_sequence_number: u64| {
+            let mut progress = StreamProgress::new(protocol);
+            progress.unanswered_request_opt =
+                Some(make_rerouting_request(stream_key, protocol, data, 0));
+            progress.unanswered_packets = unanswered_packets;
+            progress.next_response_sequence_number = next_response_sequence_number;
+            progress.can_replay()
+        };
+
+        assert!(progress(
+            ProxyProtocol::HTTP,
+            b"GET / HTTP/1.1\r\n\r\n",
+            1,
+            0
+        ));
+        assert!(progress(
+            ProxyProtocol::HTTP,
+            b"DELETE /x HTTP/1.1\r\n\r\n",
+            1,
+            4
+        ));
+        assert!(!progress(
+            ProxyProtocol::HTTP,
+            b"POST / HTTP/1.1\r\n\r\n",
+            1,
+            0
+        ));
+        assert!(!progress(
+            ProxyProtocol::HTTP,
+            b"GETAWAY / HTTP/1.1\r\n\r\n",
+            1,
+            0
+        ));
+        assert!(!progress(
+            ProxyProtocol::HTTP,
+            b"GET / HTTP/1.1\r\n\r\n",
+            2,
+            0
+        ));
+        assert!(progress(ProxyProtocol::TLS, b"client hello", 1, 0));
+        assert!(!progress(ProxyProtocol::TLS, b"application data", 1, 3));
+        assert!(!progress(
+            ProxyProtocol::SOCKS5,
+            b"GET / HTTP/1.1\r\n\r\n",
+            1,
+            0
+        ));
+        assert!(!StreamProgress::new(ProxyProtocol::HTTP).can_replay());
+    }
+
+    #[test]
+    fn proxy_server_replays_unanswered_request_over_a_new_route_when_a_node_on_its_route_is_lost() {
+        init_test_logging();
+        let test_name = "proxy_server_replays_unanswered_request_over_a_new_route_when_a_node_on_its_route_is_lost";
+        let system = System::new(test_name);
+        let (neighborhood, _, neighborhood_recording_arc) = make_recorder();
+        let neighborhood = neighborhood
+            .route_query_response(None)
+            .system_stop_conditions(match_every_type_id!(RouteQueryMessage));
+        let mut subject = make_decentralized_proxy_server();
+        subject.logger = Logger::new(test_name);
+        let stream_key = StreamKey::make_meaningful_stream_key(test_name);
+        let bystander_stream_key = StreamKey::make_meaningful_stream_key("bystander");
+        let request = make_rerouting_request(
+            stream_key,
+            ProxyProtocol::HTTP,
+            b"GET /index.html HTTP/1.1\r\nHost: example.com\r\n\r\n",
+            0,
+        );
+        let expected_request = request.clone();
+        subject
+            .keys_and_addrs
+            .insert(stream_key, SocketAddr::from_str("1.2.3.4:5678").unwrap());
+        subject.keys_and_addrs.insert(
+            bystander_stream_key,
+            SocketAddr::from_str("1.2.3.4:5679").unwrap(),
+        );
+        subject
+            .stream_key_routes
+            .insert(stream_key, make_route_through(&[1, 1, 1], &[2, 2, 2], 1234));
+        subject.stream_key_routes.insert(
+            bystander_stream_key,
+            make_route_through(&[3, 3, 3], &[4, 4, 4], 4321),
+        );
+        let sticky_routes = subject.sticky_routes_opt.as_mut().unwrap();
+        sticky_routes.insert(
+            "example.com".to_string(),
+            make_route_through(&[1, 1, 1], &[2, 2, 2], 1234),
+        );
+        sticky_routes.insert(
+            "elsewhere.com".to_string(),
+            make_route_through(&[3, 3, 3], &[4, 4, 4], 4321),
+        );
+        subject.note_request(&request);
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let peer_actors = peer_actors_builder().neighborhood(neighborhood).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr
+            .try_send(NodesLostMsg {
+                public_keys: vec![PublicKey::new(&[1, 1, 1])],
+            })
+            .unwrap();
+
+        subject_addr
+            .try_send(AssertionsMessage {
+                assertions: Box::new(move |proxy_server: &mut ProxyServer| {
+                    assert_eq!(proxy_server.stream_key_routes.get(&stream_key), None);
+                    assert_eq!(
+                        proxy_server.stream_key_routes.get(&bystander_stream_key),
+                        Some(&make_route_through(&[3, 3, 3], &[4, 4, 4], 4321))
+                    );
+                    let sticky_routes = proxy_server.sticky_routes_opt.as_ref().unwrap();
+                    assert_eq!(sticky_routes.get(&"example.com".to_string()), None);
+                    assert!(sticky_routes.get(&"elsewhere.com".to_string()).is_some());
+                    let progress = proxy_server.stream_progress.get(&stream_key).unwrap();
+                    assert_eq!(progress.abandoned_return_route_ids, vec![1234]);
+                    assert_eq!(progress.unanswered_request_opt, Some(expected_request));
+                    assert_eq!(progress.unanswered_packets, 1);
+                    assert_eq!(progress.sequence_rebase_opt, None);
+                }),
+            })
+            .unwrap();
+        system.run();
+        let neighborhood_recording = neighborhood_recording_arc.lock().unwrap();
+        assert_eq!(
+            neighborhood_recording.get_record::<RouteQueryMessage>(0),
+            &RouteQueryMessage::data_indefinite_route_request(
+                Some("example.com".to_string()),
+                Some(HTTP_PORT),
+                request.sequenced_packet.data.len(),
+            )
+        );
+        assert_eq!(neighborhood_recording.len(), 1);
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: {test_name}: Rerouting stream {stream_key} to example.com after a Node on its route dropped out; replaying its unanswered request"
+        ));
+    }
+
+    #[test]
+    fn proxy_server_closes_a_tls_stream_it_cannot_move_when_a_node_on_its_route_is_lost() {
+        init_test_logging();
+        let test_name =
+            "proxy_server_closes_a_tls_stream_it_cannot_move_when_a_node_on_its_route_is_lost";
+        let system = System::new(test_name);
+        let (dispatcher, _, dispatcher_recording_arc) = make_recorder();
+        let mut subject = make_decentralized_proxy_server();
+        subject.logger = Logger::new(test_name);
+        let stream_key = StreamKey::make_meaningful_stream_key(test_name);
+        let client_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        subject.keys_and_addrs.insert(stream_key, client_addr);
+        subject
+            .stream_key_routes
+            .insert(stream_key, make_route_through(&[1, 1, 1], &[2, 2, 2], 1234));
+        subject.note_request(&make_rerouting_request(
+            stream_key,
+            ProxyProtocol::TLS,
+            b"client hello",
+            0,
+        ));
+        subject.note_response(&stream_key, 0);
+        subject.note_response(&stream_key, 1);
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let peer_actors = peer_actors_builder().dispatcher(dispatcher).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr
+            .try_send(NodesLostMsg {
+                public_keys: vec![PublicKey::new(&[2, 2, 2])],
+            })
+            .unwrap();
+
+        subject_addr
+            .try_send(AssertionsMessage {
+                assertions: Box::new(move |proxy_server: &mut ProxyServer| {
+                    assert_eq!(proxy_server.keys_and_addrs.a_to_b(&stream_key), None);
+                    assert_eq!(proxy_server.stream_progress.get(&stream_key), None);
+                }),
+            })
+            .unwrap();
+        System::current().stop();
+        system.run();
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        assert_eq!(
+            dispatcher_recording.get_record::<TransmitDataMsg>(0),
+            &TransmitDataMsg {
+                endpoint: Endpoint::Socket(client_addr),
+                last_data: true,
+                sequence_number: Some(2),
+                data: vec![],
+            }
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {test_name}: Can't move stream {stream_key} to example.com onto a new route after a Node on its route dropped out; closing it"
+        ));
+    }
+
+    #[test]
+    fn rerouted_http_stream_numbers_its_packets_afresh_for_the_new_exit() {
+        let mut subject = make_decentralized_proxy_server();
+        let stream_key = StreamKey::make_meaningful_stream_key("afresh");
+        subject
+            .keys_and_addrs
+            .insert(stream_key, SocketAddr::from_str("1.2.3.4:5678").unwrap());
+        subject
+            .stream_key_routes
+            .insert(stream_key, make_route_through(&[1, 1, 1], &[2, 2, 2], 1234));
+        subject.note_request(&make_rerouting_request(
+            stream_key,
+            ProxyProtocol::HTTP,
+            b"GET / HTTP/1.1\r\n\r\n",
+            0,
+        ));
+        (0..3).for_each(|sequence_number| {
+            subject.note_response(&stream_key, sequence_number);
+        });
+
+        subject.reroute_stream(stream_key, "testing");
+
+        assert_eq!(subject.stream_key_routes.get(&stream_key), None);
+        assert_eq!(subject.is_awaiting_new_route(&stream_key), true);
+        let second_request = subject.rebase_request(make_rerouting_request(
+            stream_key,
+            ProxyProtocol::HTTP,
+            b"GET /again HTTP/1.1\r\n\r\n",
+            5,
+        ));
+        let third_request = subject.rebase_request(make_rerouting_request(
+            stream_key,
+            ProxyProtocol::HTTP,
+            b"more",
+            6,
+        ));
+        let first_response_sequence_number = subject.note_response(&stream_key, 0);
+        assert_eq!(second_request.sequenced_packet.sequence_number, 0);
+        assert_eq!(third_request.sequenced_packet.sequence_number, 1);
+        assert_eq!(first_response_sequence_number, 3);
+        assert_eq!(subject.is_awaiting_new_route(&stream_key), false);
+    }
+
+    #[test]
+    fn proxy_server_replays_only_silent_requests_it_safely_can() {
+        let system = System::new("proxy_server_replays_only_silent_requests_it_safely_can");
+        let (neighborhood, _, neighborhood_recording_arc) = make_recorder();
+        let neighborhood = neighborhood
+            .route_query_response(None)
+            .system_stop_conditions(match_every_type_id!(RouteQueryMessage));
+        let notify_later_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut subject = make_decentralized_proxy_server();
+        subject.relay_silence_window = Duration::ZERO;
+        subject.notify_later_sweep = Box::new(
+            NotifyLaterHandleMock::default().notify_later_params(&notify_later_params_arc),
+        );
+        let get_stream_key = StreamKey::make_meaningful_stream_key("get");
+        let post_stream_key = StreamKey::make_meaningful_stream_key("post");
+        let exhausted_stream_key = StreamKey::make_meaningful_stream_key("exhausted");
+        let get_request = make_rerouting_request(
+            get_stream_key,
+            ProxyProtocol::HTTP,
+            b"GET / HTTP/1.1\r\n\r\n",
+            0,
+        );
+        vec![
+            (get_stream_key, b"GET / HTTP/1.1\r\n\r\n".to_vec(), 5678),
+            (post_stream_key, b"POST / HTTP/1.1\r\n\r\n".to_vec(), 5679),
+            (
+                exhausted_stream_key,
+                b"GET / HTTP/1.1\r\n\r\n".to_vec(),
+                5680,
+            ),
+        ]
+        .into_iter()
+        .for_each(|(stream_key, data, port)| {
+            subject.keys_and_addrs.insert(
+                stream_key,
+                SocketAddr::new(IpAddr::from_str("1.2.3.4").unwrap(), port),
+            );
+            subject
+                .stream_key_routes
+                .insert(stream_key, make_route_through(&[1, 1, 1], &[2, 2, 2], 1234));
+            subject.note_request(&make_rerouting_request(
+                stream_key,
+                ProxyProtocol::HTTP,
+                &data,
+                0,
+            ));
+        });
+        subject
+            .stream_progress
+            .get_mut(&exhausted_stream_key)
+            .unwrap()
+            .silent_reroutes = MAX_SILENT_REROUTES;
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let peer_actors = peer_actors_builder().neighborhood(neighborhood).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr.try_send(SweepSilentStreams {}).unwrap();
+
+        subject_addr
+            .try_send(AssertionsMessage {
+                assertions: Box::new(move |proxy_server: &mut ProxyServer| {
+                    assert_eq!(proxy_server.stream_key_routes.get(&get_stream_key), None);
+                    assert_eq!(
+                        proxy_server
+                            .stream_progress
+                            .get(&get_stream_key)
+                            .unwrap()
+                            .silent_reroutes,
+                        1
+                    );
+                    assert!(proxy_server
+                        .stream_key_routes
+                        .contains_key(&post_stream_key));
+                    assert!(proxy_server
+                        .stream_key_routes
+                        .contains_key(&exhausted_stream_key));
+                }),
+            })
+            .unwrap();
+        system.run();
+        let neighborhood_recording = neighborhood_recording_arc.lock().unwrap();
+        assert_eq!(
+            neighborhood_recording.get_record::<RouteQueryMessage>(0),
+            &RouteQueryMessage::data_indefinite_route_request(
+                Some("example.com".to_string()),
+                Some(HTTP_PORT),
+                get_request.sequenced_packet.data.len(),
+            )
+        );
+        assert_eq!(neighborhood_recording.len(), 1);
+        let notify_later_params = notify_later_params_arc.lock().unwrap();
+        assert_eq!(
+            *notify_later_params,
+            vec![
+                (SweepSilentStreams {}, SILENT_STREAM_SWEEP_INTERVAL),
+                (SweepSilentStreams {}, SILENT_STREAM_SWEEP_INTERVAL)
+            ]
+        );
+    }
+
+    #[test]
+    fn proxy_server_discards_late_responses_from_the_route_a_stream_was_moved_off() {
+        init_test_logging();
+        let test_name =
+            "proxy_server_discards_late_responses_from_the_route_a_stream_was_moved_off";
+        let system = System::new(test_name);
+        let (dispatcher, _, dispatcher_recording_arc) = make_recorder();
+        let cryptde = main_cryptde();
+        let mut subject = make_decentralized_proxy_server();
+        subject.logger = Logger::new(test_name);
+        let stream_key = StreamKey::make_meaningful_stream_key(test_name);
+        subject
+            .keys_and_addrs
+            .insert(stream_key, SocketAddr::from_str("1.2.3.4:5678").unwrap());
+        subject.route_ids_to_return_routes.insert(
+            1234,
+            AddReturnRouteMessage {
+                return_route_id: 1234,
+                expected_services: vec![ExpectedService::Nothing],
+                protocol: ProxyProtocol::HTTP,
+                hostname_opt: Some("example.com".to_string()),
+            },
+        );
+        let mut progress = StreamProgress::new(ProxyProtocol::HTTP);
+        progress.abandoned_return_route_ids = vec![1234];
+        subject.stream_progress.insert(stream_key, progress);
+        let expired_cores_package: ExpiredCoresPackage<ClientResponsePayload_0v1> =
+            ExpiredCoresPackage::new(
+                SocketAddr::from_str("1.2.3.4:1234").unwrap(),
+                Some(make_wallet("irrelevant")),
+                return_route_with_id(cryptde, 1234),
+                ClientResponsePayload_0v1 {
+                    stream_key,
+                    sequenced_packet: SequencedPacket::new(b"stale".to_vec(), 0, false),
+                },
+                0,
+            );
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let peer_actors = peer_actors_builder().dispatcher(dispatcher).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr.try_send(expired_cores_package).unwrap();
+
+        System::current().stop();
+        system.run();
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        assert_eq!(dispatcher_recording.len(), 0);
+        TestLogHandler::new().exists_log_containing(&format!(
+            "DEBUG: {test_name}: Discarding late response 0 on stream {stream_key} from the route it was moved off"
+        ));
+    }
+
+    #[test]
+    fn next_stream_route_takes_turns_among_the_routes_a_stream_is_striped_across() {
+        let mut subject = make_decentralized_proxy_server();
+        let striped_stream_key = StreamKey::make_meaningful_stream_key("striped");
+        let plain_stream_key = StreamKey::make_meaningful_stream_key("plain");
+        let first_route = make_route_through(&[1, 1, 1], &[9, 9, 9], 1001);
+        let second_route = make_route_through(&[2, 2, 2], &[9, 9, 9], 1002);
+        let third_route = make_route_through(&[3, 3, 3], &[9, 9, 9], 1003);
+        subject
+            .stream_key_routes
+            .insert(striped_stream_key, first_route.clone());
+        subject.stream_stripes.insert(
+            striped_stream_key,
+            StreamStripes {
+                routes: vec![second_route.clone(), third_route.clone()],
+                next_route: 0,
+            },
+        );
+        subject
+            .stream_key_routes
+            .insert(plain_stream_key, first_route.clone());
+
+        let striped_routes = (0..4)
+            .map(|_| subject.next_stream_route(&striped_stream_key))
+            .collect::<Vec<_>>();
+        let plain_routes = (0..2)
+            .map(|_| subject.next_stream_route(&plain_stream_key))
+            .collect::<Vec<_>>();
+        let unknown_route =
+            subject.next_stream_route(&StreamKey::make_meaningful_stream_key("unknown"));
+
+        assert_eq!(
+            striped_routes,
+            vec![
+                Some(first_route.clone()),
+                Some(second_route),
+                Some(third_route),
+                Some(first_route.clone())
+            ]
+        );
+        assert_eq!(
+            plain_routes,
+            vec![Some(first_route.clone()), Some(first_route)]
+        );
+        assert_eq!(unknown_route, None);
+    }
+
+    #[test]
+    fn proxy_server_sends_and_accounts_for_striped_packets_route_by_route() {
+        let test_name = "proxy_server_sends_and_accounts_for_striped_packets_route_by_route";
+        let system = System::new(test_name);
+        let (hopper, _, hopper_recording_arc) = make_recorder();
+        let (proxy_server, _, proxy_server_recording_arc) = make_recorder();
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let accountant =
+            accountant.system_stop_conditions(match_every_type_id!(ReportServicesConsumedMessage));
+        let mut subject = make_decentralized_proxy_server();
+        let stream_key = StreamKey::make_meaningful_stream_key(test_name);
+        let client_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        subject.keys_and_addrs.insert(stream_key, client_addr);
+        subject
+            .tunneled_hosts
+            .insert(stream_key, "example.com".to_string());
+        subject
+            .stream_key_routes
+            .insert(stream_key, make_route_through(&[1, 1, 1], &[9, 9, 9], 1001));
+        subject.stream_stripes.insert(
+            stream_key,
+            StreamStripes {
+                routes: vec![make_route_through(&[2, 2, 2], &[9, 9, 9], 1002)],
+                next_route: 0,
+            },
+        );
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let peer_actors = peer_actors_builder()
+            .hopper(hopper)
+            .proxy_server(proxy_server)
+            .accountant(accountant)
+            .build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        (0..3).for_each(|sequence_number| {
+            subject_addr
+                .try_send(InboundClientData {
+                    timestamp: SystemTime::now(),
+                    peer_addr: client_addr,
+                    reception_port: Some(TLS_PORT),
+                    sequence_number: Some(sequence_number),
+                    last_data: false,
+                    is_clandestine: false,
+                    data: format!("packet {}", sequence_number).into_bytes(),
+                })
+                .unwrap()
+        });
+
+        system.run();
+        let hopper_recording = hopper_recording_arc.lock().unwrap();
+        assert_eq!(hopper_recording.len(), 3);
+        let proxy_server_recording = proxy_server_recording_arc.lock().unwrap();
+        let return_route_ids = (0..3)
+            .map(|index| {
+                proxy_server_recording
+                    .get_record::<AddReturnRouteMessage>(index)
+                    .return_route_id
+            })
+            .collect::<Vec<u32>>();
+        assert_eq!(return_route_ids, vec![1001, 1002, 1001]);
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        let routing_wallets = (0..3)
+            .map(|index| {
+                let report =
+                    accountant_recording.get_record::<ReportServicesConsumedMessage>(index);
+                assert_eq!(report.exit.earning_wallet, make_wallet("exit wallet"));
+                report
+                    .routing
+                    .iter()
+                    .map(|service| service.earning_wallet.clone())
+                    .collect::<Vec<Wallet>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            routing_wallets,
+            vec![
+                vec![make_wallet("relay wallet [1, 1, 1]")],
+                vec![make_wallet("relay wallet [2, 2, 2]")],
+                vec![make_wallet("relay wallet [1, 1, 1]")],
+            ]
+        );
+    }
+
+    #[test]
+    fn proxy_server_looks_for_disjoint_routes_to_the_exit_of_a_new_stream_when_striping() {
+        init_test_logging();
+        let test_name =
+            "proxy_server_looks_for_disjoint_routes_to_the_exit_of_a_new_stream_when_striping";
+        let system = System::new(test_name);
+        let stream_key = StreamKey::make_meaningful_stream_key(test_name);
+        let request = make_rerouting_request(
+            stream_key,
+            ProxyProtocol::HTTP,
+            b"GET /index.html HTTP/1.1\r\nHost: example.com\r\n\r\n",
+            0,
+        );
+        let disjoint_route_request = |avoided_keys: Vec<PublicKey>| {
+            RouteQueryMessage::data_disjoint_route_request(
+                PublicKey::new(&[9, 9, 9]),
+                avoided_keys,
+                Some("example.com".to_string()),
+                Some(HTTP_PORT),
+                request.sequenced_packet.data.len(),
+            )
+        };
+        let (neighborhood, _, neighborhood_recording_arc) = make_recorder();
+        let neighborhood = neighborhood
+            .route_query_response(Some(make_route_through(&[2, 2, 2], &[9, 9, 9], 1002)))
+            .route_query_response(None)
+            .system_stop_conditions(StopConditions::All(vec![StopCondition::StopOnMatch {
+                exemplar: Box::new(disjoint_route_request(vec![
+                    PublicKey::new(&[1, 1, 1]),
+                    PublicKey::new(&[2, 2, 2]),
+                ])),
+            }]));
+        let mut subject = make_decentralized_proxy_server();
+        subject.multipath_routes = 3;
+        subject.logger = Logger::new(test_name);
+        subject.dns_failure_retries.insert(
+            stream_key,
+            DNSFailureRetry {
+                unsuccessful_request: request.clone(),
+                retries_left: 3,
+            },
+        );
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let mut peer_actors = peer_actors_builder().neighborhood(neighborhood).build();
+        peer_actors.proxy_server = ProxyServer::make_subs_from(&subject_addr);
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr
+            .try_send(AddRouteResultMessage {
+                stream_key,
+                result: Ok(make_route_through(&[1, 1, 1], &[9, 9, 9], 1001)),
+            })
+            .unwrap();
 
         system.run();
-        let recording = proxy_server_recording_arc.lock().unwrap();
-        let message = recording.get_record::<AddRouteResultMessage>(0);
-        assert_eq!(message.stream_key, stream_key);
+        let neighborhood_recording = neighborhood_recording_arc.lock().unwrap();
         assert_eq!(
-            message.result,
-            Err(format!(
-                "Failed to find route to nowhere.com for stream key: {stream_key}"
-            ))
+            neighborhood_recording.get_record::<RouteQueryMessage>(0),
+            &disjoint_route_request(vec![PublicKey::new(&[1, 1, 1])])
+        );
+        assert_eq!(
+            neighborhood_recording.get_record::<RouteQueryMessage>(1),
+            &disjoint_route_request(vec![PublicKey::new(&[1, 1, 1]), PublicKey::new(&[2, 2, 2])])
         );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "DEBUG: {test_name}: Striping stream {stream_key} across 2 routes"
+        ));
     }
 
     #[test]
-    fn proxy_server_uses_existing_route() {
-        let main_cryptde = main_cryptde();
-        let alias_cryptde = alias_cryptde();
-        let http_request = b"GET /index.html HTTP/1.1\r\nHost: nowhere.com\r\n\r\n";
-        let destination_key = PublicKey::from(&b"our destination"[..]);
-        let route_query_response = RouteQueryResponse {
-            route: Route { hops: vec![] },
-            expected_services: ExpectedServices::RoundTrip(
-                vec![make_exit_service_from_key(destination_key.clone())],
-                vec![],
-                1234,
-            ),
-        };
-        let (hopper_mock, hopper_awaiter, hopper_recording_arc) = make_recorder();
-        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
-        let stream_key = StreamKey::make_meaningless_stream_key();
-        let expected_data = http_request.to_vec();
-        let msg_from_dispatcher = InboundClientData {
-            timestamp: SystemTime::now(),
-            peer_addr: socket_addr.clone(),
-            reception_port: Some(HTTP_PORT),
-            sequence_number: Some(0),
-            last_data: true,
-            is_clandestine: false,
-            data: expected_data.clone(),
-        };
-        let expected_payload = ClientRequestPayload_0v1 {
-            stream_key: stream_key.clone(),
-            sequenced_packet: SequencedPacket {
-                data: PlainData::new(http_request).into(),
-                sequence_number: 0,
-                last_data: true,
-            },
-            target_hostname: Some(String::from("nowhere.com")),
-            target_port: HTTP_PORT,
-            protocol: ProxyProtocol::HTTP,
-            originator_public_key: alias_cryptde.public_key().clone(),
+    fn proxy_server_keeps_only_stripe_routes_that_still_suit_their_stream() {
+        init_test_logging();
+        let test_name = "proxy_server_keeps_only_stripe_routes_that_still_suit_their_stream";
+        let mut subject = make_decentralized_proxy_server();
+        subject.multipath_routes = 2;
+        subject.logger = Logger::new(test_name);
+        let stream_key = StreamKey::make_meaningful_stream_key(test_name);
+        let closed_stream_key = StreamKey::make_meaningful_stream_key("closed");
+        subject
+            .stream_key_routes
+            .insert(stream_key, make_route_through(&[1, 1, 1], &[9, 9, 9], 1001));
+        let mut offer = |stream_key: StreamKey, route_query_response_opt| {
+            subject.handle_add_stripe_route_message(AddStripeRouteMessage {
+                stream_key,
+                route_query_response_opt,
+            })
         };
-        let expected_pkg = IncipientCoresPackage::new(
-            main_cryptde,
-            Route { hops: vec![] },
-            expected_payload.into(),
-            &destination_key,
-        )
-        .unwrap();
 
-        thread::spawn(move || {
-            let stream_key_factory = StreamKeyFactoryMock::new().make_result(stream_key);
-            let system = System::new("proxy_server_uses_existing_route");
-            let mut subject = ProxyServer::new(
-                main_cryptde,
-                alias_cryptde,
-                true,
-                Some(STANDARD_CONSUMING_WALLET_BALANCE),
-                false,
-            );
-            subject.stream_key_factory = Box::new(stream_key_factory);
-            subject
-                .stream_key_routes
-                .insert(stream_key, route_query_response);
-            let subject_addr: Addr<ProxyServer> = subject.start();
-            let peer_actors = peer_actors_builder().hopper(hopper_mock).build();
-            subject_addr.try_send(BindMessage { peer_actors }).unwrap();
-            subject_addr.try_send(msg_from_dispatcher).unwrap();
+        offer(stream_key, None);
+        offer(
+            closed_stream_key,
+            Some(make_route_through(&[2, 2, 2], &[9, 9, 9], 1002)),
+        );
+        offer(
+            stream_key,
+            Some(make_route_through(&[1, 1, 1], &[9, 9, 9], 1003)),
+        );
+        offer(
+            stream_key,
+            Some(make_route_through(&[2, 2, 2], &[8, 8, 8], 1004)),
+        );
+        offer(
+            stream_key,
+            Some(make_route_through(&[2, 2, 2], &[9, 9, 9], 1005)),
+        );
+        offer(
+            stream_key,
+            Some(make_route_through(&[3, 3, 3], &[9, 9, 9], 1006)),
+        );
 
-            System::current().stop();
-            system.run();
-        });
+        assert_eq!(
+            subject.stream_stripes.get(&stream_key),
+            Some(&StreamStripes {
+                routes: vec![make_route_through(&[2, 2, 2], &[9, 9, 9], 1005)],
+                next_route: 0,
+            })
+        );
+        assert_eq!(subject.stream_stripes.get(&closed_stream_key), None);
+        let tlh = TestLogHandler::new();
+        tlh.exists_log_containing(&format!(
+            "DEBUG: {test_name}: Found no more routes for stream {stream_key} that avoid the relays it uses; it stays on 1"
+        ));
+        tlh.exists_log_containing(&format!(
+            "DEBUG: {test_name}: Discarding a stripe route that no longer suits stream {closed_stream_key}"
+        ));
+        tlh.exists_log_containing(&format!(
+            "DEBUG: {test_name}: Discarding a stripe route that no longer suits stream {stream_key}"
+        ));
+        tlh.exists_log_containing(&format!(
+            "DEBUG: {test_name}: Striping stream {stream_key} across 2 routes"
+        ));
+    }
 
-        hopper_awaiter.await_message_count(1);
-        let recording = hopper_recording_arc.lock().unwrap();
-        let record = recording.get_record::<IncipientCoresPackage>(0);
-        assert_eq!(record, &expected_pkg);
+    #[test]
+    fn proxy_server_moves_a_whole_striped_stream_when_a_node_on_one_of_its_routes_is_lost() {
+        init_test_logging();
+        let test_name =
+            "proxy_server_moves_a_whole_striped_stream_when_a_node_on_one_of_its_routes_is_lost";
+        let system = System::new(test_name);
+        let (neighborhood, _, neighborhood_recording_arc) = make_recorder();
+        let neighborhood = neighborhood
+            .route_query_response(None)
+            .system_stop_conditions(match_every_type_id!(RouteQueryMessage));
+        let mut subject = make_decentralized_proxy_server();
+        subject.multipath_routes = 2;
+        subject.logger = Logger::new(test_name);
+        let stream_key = StreamKey::make_meaningful_stream_key(test_name);
+        let request = make_rerouting_request(
+            stream_key,
+            ProxyProtocol::HTTP,
+            b"GET /index.html HTTP/1.1\r\nHost: example.com\r\n\r\n",
+            0,
+        );
+        subject
+            .keys_and_addrs
+            .insert(stream_key, SocketAddr::from_str("1.2.3.4:5678").unwrap());
+        subject
+            .stream_key_routes
+            .insert(stream_key, make_route_through(&[1, 1, 1], &[9, 9, 9], 1001));
+        subject.stream_stripes.insert(
+            stream_key,
+            StreamStripes {
+                routes: vec![make_route_through(&[2, 2, 2], &[9, 9, 9], 1002)],
+                next_route: 1,
+            },
+        );
+        subject.note_request(&request);
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let peer_actors = peer_actors_builder().neighborhood(neighborhood).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr
+            .try_send(NodesLostMsg {
+                public_keys: vec![PublicKey::new(&[2, 2, 2])],
+            })
+            .unwrap();
+
+        subject_addr
+            .try_send(AssertionsMessage {
+                assertions: Box::new(move |proxy_server: &mut ProxyServer| {
+                    assert_eq!(proxy_server.stream_key_routes.get(&stream_key), None);
+                    assert_eq!(proxy_server.stream_stripes.get(&stream_key), None);
+                    let progress = proxy_server.stream_progress.get(&stream_key).unwrap();
+                    assert_eq!(progress.abandoned_return_route_ids, vec![1001, 1002]);
+                }),
+            })
+            .unwrap();
+        system.run();
+        let neighborhood_recording = neighborhood_recording_arc.lock().unwrap();
+        assert_eq!(
+            neighborhood_recording.get_record::<RouteQueryMessage>(0),
+            &RouteQueryMessage::data_indefinite_route_request(
+                Some("example.com".to_string()),
+                Some(HTTP_PORT),
+                request.sequenced_packet.data.len(),
+            )
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: {test_name}: Rerouting stream {stream_key} to example.com after a Node on its route dropped out; replaying its unanswered request"
+        ));
     }
 
     #[test]
@@ -2899,46 +5989,159 @@ mod tests {
             vec![],
             0,
         );
-        let neighborhood_mock =
-            neighborhood_mock.route_query_response(Some(route_query_response.clone()));
-        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
-        let stream_key = StreamKey::make_meaningless_stream_key();
-        let expected_data = http_request.to_vec();
-        let msg_from_dispatcher = InboundClientData {
-            timestamp: SystemTime::now(),
-            peer_addr: socket_addr.clone(),
-            reception_port: Some(HTTP_PORT),
-            sequence_number: Some(0),
-            last_data: true,
-            is_clandestine: false,
-            data: expected_data.clone(),
-        };
-        thread::spawn(move || {
-            let stream_key_factory = StreamKeyFactoryMock::new().make_result(stream_key);
-            let system =
-                System::new("proxy_server_logs_messages_when_routing_services_are_not_requested");
-            let mut subject = ProxyServer::new(
-                cryptde,
-                alias_cryptde(),
-                true,
-                Some(STANDARD_CONSUMING_WALLET_BALANCE),
-                false,
-            );
-            subject.stream_key_factory = Box::new(stream_key_factory);
-            let subject_addr: Addr<ProxyServer> = subject.start();
-            let peer_actors = peer_actors_builder()
-                .accountant(accountant_mock)
-                .neighborhood(neighborhood_mock)
-                .build();
-            subject_addr.try_send(BindMessage { peer_actors }).unwrap();
-            subject_addr.try_send(msg_from_dispatcher).unwrap();
-            system.run();
+        let neighborhood_mock =
+            neighborhood_mock.route_query_response(Some(route_query_response.clone()));
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let expected_data = http_request.to_vec();
+        let msg_from_dispatcher = InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: socket_addr.clone(),
+            reception_port: Some(HTTP_PORT),
+            sequence_number: Some(0),
+            last_data: true,
+            is_clandestine: false,
+            data: expected_data.clone(),
+        };
+        thread::spawn(move || {
+            let stream_key_factory = StreamKeyFactoryMock::new().make_result(stream_key);
+            let system =
+                System::new("proxy_server_logs_messages_when_routing_services_are_not_requested");
+            let mut subject = ProxyServer::new(
+                cryptde,
+                alias_cryptde(),
+                true,
+                Some(STANDARD_CONSUMING_WALLET_BALANCE),
+                None,
+                None,
+                DEFAULT_STICKY_ROUTE_TTL,
+                DEFAULT_MULTIPATH_ROUTES,
+                false,
+            );
+            subject.stream_key_factory = Box::new(stream_key_factory);
+            let subject_addr: Addr<ProxyServer> = subject.start();
+            let peer_actors = peer_actors_builder()
+                .accountant(accountant_mock)
+                .neighborhood(neighborhood_mock)
+                .build();
+            subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+            subject_addr.try_send(msg_from_dispatcher).unwrap();
+            system.run();
+        });
+
+        TestLogHandler::new()
+            .await_log_containing("DEBUG: ProxyServer: No routing services requested.", 1000);
+        //report about consumed services is sent anyway, exit service is mandatory ever
+        accountant_awaiter.await_message_count(1)
+    }
+
+    #[test]
+    fn proxy_server_warns_when_a_route_does_not_exit_through_a_preferred_pin() {
+        init_test_logging();
+        let test_name = "proxy_server_warns_when_a_route_does_not_exit_through_a_preferred_pin";
+        let pinned_key = PublicKey::new(&[1, 2, 3]);
+        let other_key = PublicKey::new(&[4, 5, 6]);
+        let system = System::new(test_name);
+        let mut subject = ProxyServer::new(
+            main_cryptde(),
+            alias_cryptde(),
+            true,
+            Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
+            false,
+        );
+        subject.logger = Logger::new(test_name);
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let peer_actors = peer_actors_builder().build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let return_route_through =
+            |exit_key: &PublicKey, hostname: &str, return_route_id: u32| AddReturnRouteMessage {
+                return_route_id,
+                expected_services: vec![
+                    ExpectedService::Exit(exit_key.clone(), make_wallet("exit"), rate_pack(10)),
+                    ExpectedService::Nothing,
+                ],
+                protocol: ProxyProtocol::HTTP,
+                hostname_opt: Some(hostname.to_string()),
+            };
+
+        subject_addr
+            .try_send(ConfigChangeMsg {
+                change: ConfigChange::UpdatePreferredExits(Some(PreferredExits {
+                    public_keys: vec![pinned_key.clone()],
+                    strict: false,
+                })),
+            })
+            .unwrap();
+        subject_addr
+            .try_send(return_route_through(&other_key, "unpinned.com", 1))
+            .unwrap();
+        subject_addr
+            .try_send(return_route_through(&pinned_key, "pinned.com", 2))
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let tlh = TestLogHandler::new();
+        tlh.exists_log_containing(&format!(
+            "WARN: {test_name}: Pinned exit AQID unavailable for hostname: Some(\"unpinned.com\"); \
+             routing through exit BAUG instead"
+        ));
+        tlh.exists_no_log_containing(&format!(
+            "WARN: {test_name}: Pinned exit AQID unavailable for hostname: Some(\"pinned.com\")"
+        ));
+    }
+
+    #[test]
+    fn proxy_server_warns_when_strict_pins_leave_no_route() {
+        init_test_logging();
+        let test_name = "proxy_server_warns_when_strict_pins_leave_no_route";
+        let system = System::new(test_name);
+        let mut subject = ProxyServer::new(
+            main_cryptde(),
+            alias_cryptde(),
+            true,
+            Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
+            false,
+        );
+        subject.logger = Logger::new(test_name);
+        subject.preferred_exits_opt = Some(PreferredExits {
+            public_keys: vec![PublicKey::new(&[1, 2, 3]), PublicKey::new(&[4, 5, 6])],
+            strict: true,
         });
+        let payload = make_request_payload(0, main_cryptde());
+        let stream_key = payload.stream_key;
+        subject.dns_failure_retries.insert(
+            stream_key,
+            DNSFailureRetry {
+                unsuccessful_request: payload,
+                retries_left: 3,
+            },
+        );
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let peer_actors = peer_actors_builder().build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
 
-        TestLogHandler::new()
-            .await_log_containing("DEBUG: ProxyServer: No routing services requested.", 1000);
-        //report about consumed services is sent anyway, exit service is mandatory ever
-        accountant_awaiter.await_message_count(1)
+        subject_addr
+            .try_send(AddRouteResultMessage {
+                stream_key,
+                result: Err("Failed to find route to example.com".to_string()),
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {test_name}: No pinned exit is available for hostname: Some(\"example.com\"); \
+             strict pinning to AQID, BAUG refuses every other exit"
+        ));
     }
 
     #[test]
@@ -2952,6 +6155,10 @@ mod tests {
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         let subject_addr: Addr<ProxyServer> = subject.start();
@@ -3040,6 +6247,10 @@ mod tests {
                 alias_cryptde(),
                 true,
                 Some(STANDARD_CONSUMING_WALLET_BALANCE),
+                None,
+                None,
+                DEFAULT_STICKY_ROUTE_TTL,
+                DEFAULT_MULTIPATH_ROUTES,
                 false,
             );
             subject.stream_key_factory =
@@ -3072,7 +6283,11 @@ mod tests {
         let record = recording.get_record::<RouteQueryMessage>(0);
         assert_eq!(
             record,
-            &RouteQueryMessage::data_indefinite_route_request(Some("nowhere.com".to_string()), 47)
+            &RouteQueryMessage::data_indefinite_route_request(
+                Some("nowhere.com".to_string()),
+                Some(80),
+                47
+            )
         );
         TestLogHandler::new().exists_log_containing(&format!(
             "WARN: {test_name}: No route found for hostname: Some(\"nowhere.com\") - stream key {stream_key} - retries left: 3 - AddRouteResultMessage Error: Failed to find route to nowhere.com"
@@ -3149,6 +6364,10 @@ mod tests {
             alias_cryptde,
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         let add_return_route_message = AddReturnRouteMessage {
@@ -3216,6 +6435,10 @@ mod tests {
                 alias_cryptde(),
                 true,
                 Some(STANDARD_CONSUMING_WALLET_BALANCE),
+                None,
+                None,
+                DEFAULT_STICKY_ROUTE_TTL,
+                DEFAULT_MULTIPATH_ROUTES,
                 false,
             );
             subject.logger = Logger::new(test_name);
@@ -3248,7 +6471,11 @@ mod tests {
         let record = recording.get_record::<RouteQueryMessage>(0);
         assert_eq!(
             record,
-            &RouteQueryMessage::data_indefinite_route_request(Some("nowhere.com".to_string()), 47)
+            &RouteQueryMessage::data_indefinite_route_request(
+                Some("nowhere.com".to_string()),
+                Some(80),
+                47
+            )
         );
         TestLogHandler::new().exists_log_containing(&format!(
             "WARN: {test_name}: No route found for hostname: Some(\"nowhere.com\") - stream key {stream_key} - retries left: 3 - AddRouteResultMessage Error: Failed to find route to nowhere.com"
@@ -3330,6 +6557,10 @@ mod tests {
                 alias_cryptde,
                 true,
                 Some(STANDARD_CONSUMING_WALLET_BALANCE),
+                None,
+                None,
+                DEFAULT_STICKY_ROUTE_TTL,
+                DEFAULT_MULTIPATH_ROUTES,
                 false,
             );
             subject.stream_key_factory =
@@ -3415,6 +6646,10 @@ mod tests {
                 alias_cryptde,
                 true,
                 Some(STANDARD_CONSUMING_WALLET_BALANCE),
+                None,
+                None,
+                DEFAULT_STICKY_ROUTE_TTL,
+                DEFAULT_MULTIPATH_ROUTES,
                 false,
             );
             subject.stream_key_factory =
@@ -3499,6 +6734,10 @@ mod tests {
                 alias_cryptde,
                 true,
                 Some(STANDARD_CONSUMING_WALLET_BALANCE),
+                None,
+                None,
+                DEFAULT_STICKY_ROUTE_TTL,
+                DEFAULT_MULTIPATH_ROUTES,
                 false,
             );
             subject.keys_and_addrs.insert(stream_key, client_addr);
@@ -3569,6 +6808,10 @@ mod tests {
                 alias_cryptde(),
                 true,
                 Some(STANDARD_CONSUMING_WALLET_BALANCE),
+                None,
+                None,
+                DEFAULT_STICKY_ROUTE_TTL,
+                DEFAULT_MULTIPATH_ROUTES,
                 false,
             );
             subject.logger = Logger::new(test_name);
@@ -3609,6 +6852,10 @@ mod tests {
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         subject.logger = Logger::new(test_name);
@@ -3676,6 +6923,10 @@ mod tests {
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         let stream_key = StreamKey::make_meaningless_stream_key();
@@ -3693,6 +6944,10 @@ mod tests {
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         subject.subs = Some(make_proxy_server_out_subs());
@@ -3801,6 +7056,10 @@ mod tests {
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         subject.stream_key_purge_delay = Duration::from_millis(stream_key_purge_delay_in_millis);
@@ -3897,12 +7156,17 @@ mod tests {
     fn straggling_packets_are_logged() {
         init_test_logging();
         let test_name = "straggling_packets_are_logged";
+        let _system = System::new(test_name);
         let cryptde = main_cryptde();
         let mut subject = ProxyServer::new(
             cryptde,
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         subject.logger = Logger::new(test_name);
@@ -3983,6 +7247,10 @@ mod tests {
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
@@ -4189,6 +7457,10 @@ mod tests {
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
@@ -4256,32 +7528,214 @@ mod tests {
                 SocketAddr::from_str("1.2.3.4:1234").unwrap(),
                 Some(make_wallet("irrelevant")),
                 return_route_with_id(cryptde, 1234),
-                first_client_response_payload.into(),
+                first_client_response_payload.into(),
+                0,
+            );
+        let peer_actors = peer_actors_builder().build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr.try_send(first_expired_cores_package).unwrap();
+
+        subject_addr
+            .try_send(AssertionsMessage {
+                assertions: Box::new(move |proxy_server: &mut ProxyServer| {
+                    let retry_opt = proxy_server.dns_failure_retries.get(&stream_key);
+                    assert_eq!(retry_opt, None);
+                }),
+            })
+            .unwrap();
+        System::current().stop();
+        system.run();
+        TestLogHandler::new().exists_log_containing(&format!("DEBUG: {test_name}: Successful attempt of DNS resolution, removing DNS retry entry for stream key: {stream_key_clone}"));
+    }
+
+    #[test]
+    fn proxy_server_records_services_consumed_even_after_browser_stream_is_gone() {
+        let system =
+            System::new("proxy_server_records_services_consumed_even_after_browser_stream_is_gone");
+        let (dispatcher_mock, _, dispatcher_log_arc) = make_recorder();
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let cryptde = main_cryptde();
+        let mut subject = ProxyServer::new(
+            cryptde,
+            alias_cryptde(),
+            true,
+            Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
+            false,
+        );
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let irrelevant_public_key = PublicKey::from(&b"irrelevant"[..]);
+        // subject.keys_and_addrs contains no browser stream
+        let incoming_route_d_wallet = make_wallet("D Earning");
+        let incoming_route_e_wallet = make_wallet("E Earning");
+        let rate_pack_d = rate_pack(101);
+        let rate_pack_e = rate_pack(102);
+        subject.route_ids_to_return_routes.insert(
+            1234,
+            AddReturnRouteMessage {
+                return_route_id: 1234,
+                expected_services: vec![
+                    ExpectedService::Exit(
+                        irrelevant_public_key.clone(),
+                        incoming_route_d_wallet.clone(),
+                        rate_pack_d,
+                    ),
+                    ExpectedService::Routing(
+                        irrelevant_public_key.clone(),
+                        incoming_route_e_wallet.clone(),
+                        rate_pack_e,
+                    ),
+                ],
+                protocol: ProxyProtocol::TLS,
+                hostname_opt: None,
+            },
+        );
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let client_response_payload = ClientResponsePayload_0v1 {
+            stream_key,
+            sequenced_packet: SequencedPacket {
+                data: b"some data".to_vec(),
+                sequence_number: 4321,
+                last_data: false,
+            },
+        };
+        let exit_size = client_response_payload.sequenced_packet.data.len();
+        let expired_cores_package: ExpiredCoresPackage<ClientResponsePayload_0v1> =
+            ExpiredCoresPackage::new(
+                SocketAddr::from_str("1.2.3.4:1234").unwrap(),
+                Some(make_wallet("irrelevant")),
+                return_route_with_id(cryptde, 1234),
+                client_response_payload.into(),
+                0,
+            );
+        let routing_size = expired_cores_package.payload_len;
+        let peer_actors = peer_actors_builder()
+            .dispatcher(dispatcher_mock)
+            .accountant(accountant)
+            .build();
+        let before = SystemTime::now();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr
+            .try_send(expired_cores_package.clone())
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let after = SystemTime::now();
+        let dispatcher_recording = dispatcher_log_arc.lock().unwrap();
+        assert_eq!(dispatcher_recording.len(), 0);
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        let services_consumed_report =
+            accountant_recording.get_record::<ReportServicesConsumedMessage>(0);
+        let returned_timestamp = services_consumed_report.timestamp;
+        assert_eq!(
+            services_consumed_report,
+            &ReportServicesConsumedMessage {
+                timestamp: returned_timestamp,
+                exit: ExitServiceConsumed {
+                    earning_wallet: incoming_route_d_wallet,
+                    payload_size: exit_size,
+                    service_rate: rate_pack_d.exit_service_rate,
+                    byte_rate: rate_pack_d.exit_byte_rate
+                },
+                routing_payload_size: routing_size,
+                routing: vec![RoutingServiceConsumed {
+                    earning_wallet: incoming_route_e_wallet,
+                    service_rate: rate_pack_e.routing_service_rate,
+                    byte_rate: rate_pack_e.routing_byte_rate
+                }]
+            }
+        );
+        assert!(before <= returned_timestamp && returned_timestamp <= after);
+        assert_eq!(accountant_recording.len(), 1);
+    }
+
+    #[test]
+    fn handle_dns_resolve_failure_sends_message_to_dispatcher() {
+        let system = System::new("proxy_server_receives_response_from_routing_services");
+        let (dispatcher_mock, _, dispatcher_log_arc) = make_recorder();
+        let cryptde = main_cryptde();
+        let mut subject = ProxyServer::new(
+            cryptde,
+            alias_cryptde(),
+            true,
+            Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
+            false,
+        );
+
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let mut dns_failure_retries_hash_map = HashMap::new();
+        let client_payload = make_request_payload(111, cryptde);
+        dns_failure_retries_hash_map.insert(
+            stream_key,
+            DNSFailureRetry {
+                unsuccessful_request: client_payload,
+                retries_left: 0,
+            },
+        );
+        subject.dns_failure_retries = dns_failure_retries_hash_map;
+        subject
+            .keys_and_addrs
+            .insert(stream_key.clone(), socket_addr.clone());
+        let exit_public_key = PublicKey::from(&b"exit_key"[..]);
+        let exit_wallet = make_wallet("exit wallet");
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let dns_resolve_failure = DnsResolveFailure_0v1::new(stream_key);
+        let expired_cores_package: ExpiredCoresPackage<DnsResolveFailure_0v1> =
+            ExpiredCoresPackage::new(
+                SocketAddr::from_str("1.2.3.4:1234").unwrap(),
+                Some(make_wallet("irrelevant")),
+                return_route_with_id(cryptde, 1234),
+                dns_resolve_failure.into(),
                 0,
             );
-        let peer_actors = peer_actors_builder().build();
-        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
-
-        subject_addr.try_send(first_expired_cores_package).unwrap();
 
+        let peer_actors = peer_actors_builder().dispatcher(dispatcher_mock).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
         subject_addr
-            .try_send(AssertionsMessage {
-                assertions: Box::new(move |proxy_server: &mut ProxyServer| {
-                    let retry_opt = proxy_server.dns_failure_retries.get(&stream_key);
-                    assert_eq!(retry_opt, None);
-                }),
+            .try_send(AddReturnRouteMessage {
+                return_route_id: 1234,
+                expected_services: vec![ExpectedService::Exit(
+                    exit_public_key.clone(),
+                    exit_wallet,
+                    rate_pack(10),
+                )],
+                protocol: ProxyProtocol::HTTP,
+                hostname_opt: Some("server.com".to_string()),
             })
             .unwrap();
+        subject_addr.try_send(expired_cores_package).unwrap();
+
         System::current().stop();
         system.run();
-        TestLogHandler::new().exists_log_containing(&format!("DEBUG: {test_name}: Successful attempt of DNS resolution, removing DNS retry entry for stream key: {stream_key_clone}"));
+
+        let dispatcher_recording = dispatcher_log_arc.lock().unwrap();
+        let record = dispatcher_recording.get_record::<TransmitDataMsg>(0);
+        assert_eq!(
+            TransmitDataMsg {
+                endpoint: Endpoint::Socket(socket_addr),
+                last_data: true,
+                sequence_number: Some(0),
+                data: ServerImpersonatorHttp {}
+                    .dns_resolution_failure_response(Some("server.com".to_string()),),
+            },
+            *record
+        );
     }
 
     #[test]
-    fn proxy_server_records_services_consumed_even_after_browser_stream_is_gone() {
-        let system =
-            System::new("proxy_server_records_services_consumed_even_after_browser_stream_is_gone");
-        let (dispatcher_mock, _, dispatcher_log_arc) = make_recorder();
+    fn handle_dns_resolve_failure_reports_services_consumed() {
+        let system = System::new("proxy_server_records_accounting");
         let (accountant, _, accountant_recording_arc) = make_recorder();
         let cryptde = main_cryptde();
         let mut subject = ProxyServer::new(
@@ -4289,15 +7743,34 @@ mod tests {
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
         let stream_key = StreamKey::make_meaningless_stream_key();
         let irrelevant_public_key = PublicKey::from(&b"irrelevant"[..]);
-        // subject.keys_and_addrs contains no browser stream
+        let mut dns_failure_retries_hash_map = HashMap::new();
+        let client_payload = make_request_payload(111, cryptde);
+        dns_failure_retries_hash_map.insert(
+            stream_key,
+            DNSFailureRetry {
+                unsuccessful_request: client_payload,
+                retries_left: 0,
+            },
+        );
+        subject.dns_failure_retries = dns_failure_retries_hash_map;
+        subject
+            .keys_and_addrs
+            .insert(stream_key.clone(), socket_addr.clone());
         let incoming_route_d_wallet = make_wallet("D Earning");
         let incoming_route_e_wallet = make_wallet("E Earning");
+        let incoming_route_f_wallet = make_wallet("F Earning");
         let rate_pack_d = rate_pack(101);
         let rate_pack_e = rate_pack(102);
+        let rate_pack_f = rate_pack(103);
         subject.route_ids_to_return_routes.insert(
             1234,
             AddReturnRouteMessage {
@@ -4313,36 +7786,31 @@ mod tests {
                         incoming_route_e_wallet.clone(),
                         rate_pack_e,
                     ),
+                    ExpectedService::Routing(
+                        irrelevant_public_key.clone(),
+                        incoming_route_f_wallet.clone(),
+                        rate_pack_f,
+                    ),
+                    ExpectedService::Nothing,
                 ],
                 protocol: ProxyProtocol::TLS,
-                hostname_opt: None,
+                hostname_opt: Some("server.com".to_string()),
             },
         );
         let subject_addr: Addr<ProxyServer> = subject.start();
-        let client_response_payload = ClientResponsePayload_0v1 {
-            stream_key,
-            sequenced_packet: SequencedPacket {
-                data: b"some data".to_vec(),
-                sequence_number: 4321,
-                last_data: false,
-            },
-        };
-        let exit_size = client_response_payload.sequenced_packet.data.len();
-        let expired_cores_package: ExpiredCoresPackage<ClientResponsePayload_0v1> =
+        let dns_resolve_failure_payload = DnsResolveFailure_0v1::new(stream_key);
+        let expired_cores_package: ExpiredCoresPackage<DnsResolveFailure_0v1> =
             ExpiredCoresPackage::new(
                 SocketAddr::from_str("1.2.3.4:1234").unwrap(),
                 Some(make_wallet("irrelevant")),
                 return_route_with_id(cryptde, 1234),
-                client_response_payload.into(),
+                dns_resolve_failure_payload.into(),
                 0,
             );
         let routing_size = expired_cores_package.payload_len;
-        let peer_actors = peer_actors_builder()
-            .dispatcher(dispatcher_mock)
-            .accountant(accountant)
-            .build();
-        let before = SystemTime::now();
+        let peer_actors = peer_actors_builder().accountant(accountant).build();
         subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let before = SystemTime::now();
 
         subject_addr
             .try_send(expired_cores_package.clone())
@@ -4351,28 +7819,33 @@ mod tests {
         System::current().stop();
         system.run();
         let after = SystemTime::now();
-        let dispatcher_recording = dispatcher_log_arc.lock().unwrap();
-        assert_eq!(dispatcher_recording.len(), 0);
         let accountant_recording = accountant_recording_arc.lock().unwrap();
-        let services_consumed_report =
+        let services_consumed_message =
             accountant_recording.get_record::<ReportServicesConsumedMessage>(0);
-        let returned_timestamp = services_consumed_report.timestamp;
+        let returned_timestamp = services_consumed_message.timestamp;
         assert_eq!(
-            services_consumed_report,
+            services_consumed_message,
             &ReportServicesConsumedMessage {
                 timestamp: returned_timestamp,
                 exit: ExitServiceConsumed {
                     earning_wallet: incoming_route_d_wallet,
-                    payload_size: exit_size,
+                    payload_size: 0,
                     service_rate: rate_pack_d.exit_service_rate,
                     byte_rate: rate_pack_d.exit_byte_rate
                 },
                 routing_payload_size: routing_size,
-                routing: vec![RoutingServiceConsumed {
-                    earning_wallet: incoming_route_e_wallet,
-                    service_rate: rate_pack_e.routing_service_rate,
-                    byte_rate: rate_pack_e.routing_byte_rate
-                }]
+                routing: vec![
+                    RoutingServiceConsumed {
+                        earning_wallet: incoming_route_e_wallet,
+                        service_rate: rate_pack_e.routing_service_rate,
+                        byte_rate: rate_pack_e.routing_byte_rate
+                    },
+                    RoutingServiceConsumed {
+                        earning_wallet: incoming_route_f_wallet,
+                        service_rate: rate_pack_f.routing_service_rate,
+                        byte_rate: rate_pack_f.routing_byte_rate
+                    }
+                ]
             }
         );
         assert!(before <= returned_timestamp && returned_timestamp <= after);
@@ -4380,18 +7853,23 @@ mod tests {
     }
 
     #[test]
-    fn handle_dns_resolve_failure_sends_message_to_dispatcher() {
-        let system = System::new("proxy_server_receives_response_from_routing_services");
-        let (dispatcher_mock, _, dispatcher_log_arc) = make_recorder();
+    fn handle_dns_resolve_failure_sends_message_to_neighborhood() {
+        init_test_logging();
+        let test_name = "handle_dns_resolve_failure_sends_message_to_neighborhood";
+        let system = System::new(test_name);
+        let (neighborhood_mock, _, neighborhood_log_arc) = make_recorder();
         let cryptde = main_cryptde();
         let mut subject = ProxyServer::new(
             cryptde,
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
-
         let stream_key = StreamKey::make_meaningless_stream_key();
         let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
         let mut dns_failure_retries_hash_map = HashMap::new();
@@ -4403,12 +7881,26 @@ mod tests {
                 retries_left: 0,
             },
         );
+        subject.logger = Logger::new(test_name);
         subject.dns_failure_retries = dns_failure_retries_hash_map;
         subject
             .keys_and_addrs
             .insert(stream_key.clone(), socket_addr.clone());
         let exit_public_key = PublicKey::from(&b"exit_key"[..]);
         let exit_wallet = make_wallet("exit wallet");
+        subject.route_ids_to_return_routes.insert(
+            1234,
+            AddReturnRouteMessage {
+                return_route_id: 1234,
+                expected_services: vec![ExpectedService::Exit(
+                    exit_public_key.clone(),
+                    exit_wallet,
+                    rate_pack(10),
+                )],
+                protocol: ProxyProtocol::HTTP,
+                hostname_opt: Some("server.com".to_string()),
+            },
+        );
         let subject_addr: Addr<ProxyServer> = subject.start();
         let dns_resolve_failure = DnsResolveFailure_0v1::new(stream_key);
         let expired_cores_package: ExpiredCoresPackage<DnsResolveFailure_0v1> =
@@ -4419,233 +7911,280 @@ mod tests {
                 dns_resolve_failure.into(),
                 0,
             );
+        let peer_actors = peer_actors_builder()
+            .neighborhood(neighborhood_mock)
+            .build();
 
-        let peer_actors = peer_actors_builder().dispatcher(dispatcher_mock).build();
         subject_addr.try_send(BindMessage { peer_actors }).unwrap();
-        subject_addr
-            .try_send(AddReturnRouteMessage {
+        subject_addr.try_send(expired_cores_package).unwrap();
+
+        System::current().stop();
+        system.run();
+        let neighborhood_recording = neighborhood_log_arc.lock().unwrap();
+        let record = neighborhood_recording.get_record::<UpdateNodeRecordMetadataMessage>(0);
+        assert_eq!(
+            record,
+            &UpdateNodeRecordMetadataMessage {
+                public_key: exit_public_key.clone(),
+                metadata_change: NRMetadataChange::AddUnreachableHost {
+                    hostname: "server.com".to_string()
+                }
+            }
+        );
+        TestLogHandler::new().exists_no_log_containing(&format!(
+            "ERROR: {test_name}: Exit node {exit_public_key} complained of DNS failure, but was given no hostname to resolve."
+        ));
+    }
+
+    #[test]
+    fn handle_service_refused_tells_the_browser_and_steers_away_from_and_pays_the_exit() {
+        init_test_logging();
+        let test_name =
+            "handle_service_refused_tells_the_browser_and_steers_away_from_and_pays_the_exit";
+        let system = System::new(test_name);
+        let (neighborhood, _, neighborhood_recording_arc) = make_recorder();
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let (dispatcher, _, dispatcher_recording_arc) = make_recorder();
+        let cryptde = main_cryptde();
+        let exit_cryptde = CryptDENull::new(TEST_DEFAULT_CHAIN);
+        let exit_public_key = exit_cryptde.public_key().clone();
+        let exit_wallet = make_wallet("exit wallet");
+        let mut subject = ProxyServer::new(
+            cryptde,
+            alias_cryptde(),
+            true,
+            Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
+            false,
+        );
+        subject.logger = Logger::new(test_name);
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        subject.keys_and_addrs.insert(stream_key, socket_addr);
+        subject.route_ids_to_return_routes.insert(
+            1234,
+            AddReturnRouteMessage {
                 return_route_id: 1234,
                 expected_services: vec![ExpectedService::Exit(
                     exit_public_key.clone(),
-                    exit_wallet,
+                    exit_wallet.clone(),
                     rate_pack(10),
                 )],
                 protocol: ProxyProtocol::HTTP,
                 hostname_opt: Some("server.com".to_string()),
-            })
-            .unwrap();
+            },
+        );
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let notice =
+            ServiceRefused_0v1::new(stream_key, ServiceRefusalReason::Delinquent, &exit_cryptde);
+        let expired_cores_package = ExpiredCoresPackage::new(
+            SocketAddr::from_str("1.2.3.4:1234").unwrap(),
+            Some(make_wallet("irrelevant")),
+            return_route_with_id(cryptde, 1234),
+            notice,
+            0,
+        );
+        let peer_actors = peer_actors_builder()
+            .neighborhood(neighborhood)
+            .accountant(accountant)
+            .dispatcher(dispatcher)
+            .build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
         subject_addr.try_send(expired_cores_package).unwrap();
 
+        subject_addr
+            .try_send(AssertionsMessage {
+                assertions: Box::new(move |proxy_server: &mut ProxyServer| {
+                    assert_eq!(proxy_server.keys_and_addrs.a_to_b(&stream_key), None);
+                }),
+            })
+            .unwrap();
         System::current().stop();
         system.run();
-
-        let dispatcher_recording = dispatcher_log_arc.lock().unwrap();
-        let record = dispatcher_recording.get_record::<TransmitDataMsg>(0);
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
         assert_eq!(
-            TransmitDataMsg {
+            dispatcher_recording.get_record::<TransmitDataMsg>(0),
+            &TransmitDataMsg {
                 endpoint: Endpoint::Socket(socket_addr),
                 last_data: true,
                 sequence_number: Some(0),
-                data: ServerImpersonatorHttp {}
-                    .dns_resolution_failure_response(Some("server.com".to_string()),),
-            },
-            *record
+                data: ServerImpersonatorHttp {}.service_refused_response(),
+            }
+        );
+        let neighborhood_recording = neighborhood_recording_arc.lock().unwrap();
+        assert_eq!(
+            neighborhood_recording.get_record::<UpdateNodeRecordMetadataMessage>(0),
+            &UpdateNodeRecordMetadataMessage {
+                public_key: exit_public_key.clone(),
+                metadata_change: NRMetadataChange::ServiceRefused,
+            }
+        );
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        assert_eq!(
+            accountant_recording.get_record::<PrioritizeCreditorMessage>(1),
+            &PrioritizeCreditorMessage {
+                wallet: exit_wallet
+            }
         );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {test_name}: Exit Node {exit_public_key} refused service on stream {stream_key}: Delinquent"
+        ));
     }
 
     #[test]
-    fn handle_dns_resolve_failure_reports_services_consumed() {
-        let system = System::new("proxy_server_records_accounting");
+    fn handle_service_refused_ignores_refusal_not_signed_by_the_exit() {
+        init_test_logging();
+        let test_name = "handle_service_refused_ignores_refusal_not_signed_by_the_exit";
+        let system = System::new(test_name);
+        let (neighborhood, _, neighborhood_recording_arc) = make_recorder();
         let (accountant, _, accountant_recording_arc) = make_recorder();
+        let (dispatcher, _, dispatcher_recording_arc) = make_recorder();
         let cryptde = main_cryptde();
+        let exit_public_key = PublicKey::from(&b"exit_key"[..]);
+        let impostor_cryptde = CryptDENull::new(TEST_DEFAULT_CHAIN);
         let mut subject = ProxyServer::new(
             cryptde,
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
-        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        subject.logger = Logger::new(test_name);
         let stream_key = StreamKey::make_meaningless_stream_key();
-        let irrelevant_public_key = PublicKey::from(&b"irrelevant"[..]);
-        let mut dns_failure_retries_hash_map = HashMap::new();
-        let client_payload = make_request_payload(111, cryptde);
-        dns_failure_retries_hash_map.insert(
-            stream_key,
-            DNSFailureRetry {
-                unsuccessful_request: client_payload,
-                retries_left: 0,
-            },
-        );
-        subject.dns_failure_retries = dns_failure_retries_hash_map;
-        subject
-            .keys_and_addrs
-            .insert(stream_key.clone(), socket_addr.clone());
-        let incoming_route_d_wallet = make_wallet("D Earning");
-        let incoming_route_e_wallet = make_wallet("E Earning");
-        let incoming_route_f_wallet = make_wallet("F Earning");
-        let rate_pack_d = rate_pack(101);
-        let rate_pack_e = rate_pack(102);
-        let rate_pack_f = rate_pack(103);
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        subject.keys_and_addrs.insert(stream_key, socket_addr);
         subject.route_ids_to_return_routes.insert(
             1234,
             AddReturnRouteMessage {
                 return_route_id: 1234,
-                expected_services: vec![
-                    ExpectedService::Exit(
-                        irrelevant_public_key.clone(),
-                        incoming_route_d_wallet.clone(),
-                        rate_pack_d,
-                    ),
-                    ExpectedService::Routing(
-                        irrelevant_public_key.clone(),
-                        incoming_route_e_wallet.clone(),
-                        rate_pack_e,
-                    ),
-                    ExpectedService::Routing(
-                        irrelevant_public_key.clone(),
-                        incoming_route_f_wallet.clone(),
-                        rate_pack_f,
-                    ),
-                    ExpectedService::Nothing,
-                ],
-                protocol: ProxyProtocol::TLS,
+                expected_services: vec![ExpectedService::Exit(
+                    exit_public_key.clone(),
+                    make_wallet("exit wallet"),
+                    rate_pack(10),
+                )],
+                protocol: ProxyProtocol::HTTP,
                 hostname_opt: Some("server.com".to_string()),
             },
         );
         let subject_addr: Addr<ProxyServer> = subject.start();
-        let dns_resolve_failure_payload = DnsResolveFailure_0v1::new(stream_key);
-        let expired_cores_package: ExpiredCoresPackage<DnsResolveFailure_0v1> =
-            ExpiredCoresPackage::new(
-                SocketAddr::from_str("1.2.3.4:1234").unwrap(),
-                Some(make_wallet("irrelevant")),
-                return_route_with_id(cryptde, 1234),
-                dns_resolve_failure_payload.into(),
-                0,
-            );
-        let routing_size = expired_cores_package.payload_len;
-        let peer_actors = peer_actors_builder().accountant(accountant).build();
+        let notice = ServiceRefused_0v1::new(
+            stream_key,
+            ServiceRefusalReason::Delinquent,
+            &impostor_cryptde,
+        );
+        let expired_cores_package = ExpiredCoresPackage::new(
+            SocketAddr::from_str("1.2.3.4:1234").unwrap(),
+            Some(make_wallet("irrelevant")),
+            return_route_with_id(cryptde, 1234),
+            notice,
+            0,
+        );
+        let peer_actors = peer_actors_builder()
+            .neighborhood(neighborhood)
+            .accountant(accountant)
+            .dispatcher(dispatcher)
+            .build();
         subject_addr.try_send(BindMessage { peer_actors }).unwrap();
-        let before = SystemTime::now();
+
+        subject_addr.try_send(expired_cores_package).unwrap();
 
         subject_addr
-            .try_send(expired_cores_package.clone())
+            .try_send(AssertionsMessage {
+                assertions: Box::new(move |proxy_server: &mut ProxyServer| {
+                    assert_eq!(
+                        proxy_server.keys_and_addrs.a_to_b(&stream_key),
+                        Some(socket_addr)
+                    );
+                }),
+            })
             .unwrap();
-
         System::current().stop();
         system.run();
-        let after = SystemTime::now();
-        let accountant_recording = accountant_recording_arc.lock().unwrap();
-        let services_consumed_message =
-            accountant_recording.get_record::<ReportServicesConsumedMessage>(0);
-        let returned_timestamp = services_consumed_message.timestamp;
-        assert_eq!(
-            services_consumed_message,
-            &ReportServicesConsumedMessage {
-                timestamp: returned_timestamp,
-                exit: ExitServiceConsumed {
-                    earning_wallet: incoming_route_d_wallet,
-                    payload_size: 0,
-                    service_rate: rate_pack_d.exit_service_rate,
-                    byte_rate: rate_pack_d.exit_byte_rate
-                },
-                routing_payload_size: routing_size,
-                routing: vec![
-                    RoutingServiceConsumed {
-                        earning_wallet: incoming_route_e_wallet,
-                        service_rate: rate_pack_e.routing_service_rate,
-                        byte_rate: rate_pack_e.routing_byte_rate
-                    },
-                    RoutingServiceConsumed {
-                        earning_wallet: incoming_route_f_wallet,
-                        service_rate: rate_pack_f.routing_service_rate,
-                        byte_rate: rate_pack_f.routing_byte_rate
-                    }
-                ]
-            }
-        );
-        assert!(before <= returned_timestamp && returned_timestamp <= after);
-        assert_eq!(accountant_recording.len(), 1);
+        assert_eq!(dispatcher_recording_arc.lock().unwrap().len(), 0);
+        assert_eq!(neighborhood_recording_arc.lock().unwrap().len(), 0);
+        assert_eq!(accountant_recording_arc.lock().unwrap().len(), 0);
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {test_name}: Ignoring refusal of service on stream {stream_key} that was not \
+             signed by exit Node {exit_public_key}"
+        ));
     }
 
     #[test]
-    fn handle_dns_resolve_failure_sends_message_to_neighborhood() {
-        init_test_logging();
-        let test_name = "handle_dns_resolve_failure_sends_message_to_neighborhood";
-        let system = System::new(test_name);
-        let (neighborhood_mock, _, neighborhood_log_arc) = make_recorder();
+    fn handle_dns_resolve_failure_forgets_the_sticky_route_to_the_hostname() {
+        let system =
+            System::new("handle_dns_resolve_failure_forgets_the_sticky_route_to_the_hostname");
         let cryptde = main_cryptde();
         let mut subject = ProxyServer::new(
             cryptde,
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         let stream_key = StreamKey::make_meaningless_stream_key();
-        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
-        let mut dns_failure_retries_hash_map = HashMap::new();
-        let client_payload = make_request_payload(111, cryptde);
-        dns_failure_retries_hash_map.insert(
+        subject.dns_failure_retries.insert(
             stream_key,
             DNSFailureRetry {
-                unsuccessful_request: client_payload,
+                unsuccessful_request: make_request_payload(111, cryptde),
                 retries_left: 0,
             },
         );
-        subject.logger = Logger::new(test_name);
-        subject.dns_failure_retries = dns_failure_retries_hash_map;
         subject
             .keys_and_addrs
-            .insert(stream_key.clone(), socket_addr.clone());
-        let exit_public_key = PublicKey::from(&b"exit_key"[..]);
-        let exit_wallet = make_wallet("exit wallet");
+            .insert(stream_key, SocketAddr::from_str("1.2.3.4:5678").unwrap());
         subject.route_ids_to_return_routes.insert(
             1234,
             AddReturnRouteMessage {
                 return_route_id: 1234,
                 expected_services: vec![ExpectedService::Exit(
-                    exit_public_key.clone(),
-                    exit_wallet,
+                    PublicKey::from(&b"exit_key"[..]),
+                    make_wallet("exit wallet"),
                     rate_pack(10),
                 )],
                 protocol: ProxyProtocol::HTTP,
                 hostname_opt: Some("server.com".to_string()),
             },
         );
+        let sticky_routes = subject.sticky_routes_opt.as_mut().unwrap();
+        sticky_routes.insert("server.com".to_string(), make_sticky_route());
+        sticky_routes.insert("elsewhere.com".to_string(), make_sticky_route());
         let subject_addr: Addr<ProxyServer> = subject.start();
-        let dns_resolve_failure = DnsResolveFailure_0v1::new(stream_key);
         let expired_cores_package: ExpiredCoresPackage<DnsResolveFailure_0v1> =
             ExpiredCoresPackage::new(
                 SocketAddr::from_str("1.2.3.4:1234").unwrap(),
                 Some(make_wallet("irrelevant")),
                 return_route_with_id(cryptde, 1234),
-                dns_resolve_failure.into(),
+                DnsResolveFailure_0v1::new(stream_key).into(),
                 0,
             );
-        let peer_actors = peer_actors_builder()
-            .neighborhood(neighborhood_mock)
-            .build();
-
+        let peer_actors = peer_actors_builder().build();
         subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
         subject_addr.try_send(expired_cores_package).unwrap();
 
+        subject_addr
+            .try_send(AssertionsMessage {
+                assertions: Box::new(move |proxy_server: &mut ProxyServer| {
+                    let sticky_routes = proxy_server.sticky_routes_opt.as_ref().unwrap();
+                    assert_eq!(sticky_routes.get(&"server.com".to_string()), None);
+                    assert!(sticky_routes.get(&"elsewhere.com".to_string()).is_some());
+                }),
+            })
+            .unwrap();
         System::current().stop();
         system.run();
-        let neighborhood_recording = neighborhood_log_arc.lock().unwrap();
-        let record = neighborhood_recording.get_record::<UpdateNodeRecordMetadataMessage>(0);
-        assert_eq!(
-            record,
-            &UpdateNodeRecordMetadataMessage {
-                public_key: exit_public_key.clone(),
-                metadata_change: NRMetadataChange::AddUnreachableHost {
-                    hostname: "server.com".to_string()
-                }
-            }
-        );
-        TestLogHandler::new().exists_no_log_containing(&format!(
-            "ERROR: {test_name}: Exit node {exit_public_key} complained of DNS failure, but was given no hostname to resolve."
-        ));
     }
 
     #[test]
@@ -4661,6 +8200,10 @@ mod tests {
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         let stream_key = StreamKey::make_meaningless_stream_key();
@@ -4731,6 +8274,10 @@ mod tests {
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         let stream_key = StreamKey::make_meaningless_stream_key();
@@ -4807,6 +8354,10 @@ mod tests {
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         let stream_key = StreamKey::make_meaningless_stream_key();
@@ -4881,6 +8432,10 @@ mod tests {
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         subject.subs = Some(make_proxy_server_out_subs());
@@ -4957,6 +8512,10 @@ mod tests {
             alias_cryptde(),
             false, //meaning ZeroHop
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         let stream_key = StreamKey::make_meaningless_stream_key();
@@ -5057,6 +8616,10 @@ mod tests {
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
@@ -5153,6 +8716,10 @@ mod tests {
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         let stream_key = StreamKey::make_meaningless_stream_key();
@@ -5225,6 +8792,10 @@ mod tests {
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         subject.logger = Logger::new(test_name);
@@ -5318,6 +8889,10 @@ mod tests {
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         subject
@@ -5367,6 +8942,10 @@ mod tests {
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
@@ -5401,6 +8980,10 @@ mod tests {
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         let stream_key = StreamKey::make_meaningless_stream_key();
@@ -5451,6 +9034,10 @@ mod tests {
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         let stream_key = StreamKey::make_meaningless_stream_key();
@@ -5506,6 +9093,10 @@ mod tests {
                 alias_cryptde(),
                 true,
                 Some(STANDARD_CONSUMING_WALLET_BALANCE),
+                None,
+                None,
+                DEFAULT_STICKY_ROUTE_TTL,
+                DEFAULT_MULTIPATH_ROUTES,
                 false,
             );
             subject.route_ids_to_return_routes = TtlHashMap::new(Duration::from_millis(250));
@@ -5555,7 +9146,17 @@ mod tests {
 
     #[test]
     fn handle_stream_shutdown_msg_handles_unknown_peer_addr() {
-        let mut subject = ProxyServer::new(main_cryptde(), alias_cryptde(), true, None, false);
+        let mut subject = ProxyServer::new(
+            main_cryptde(),
+            alias_cryptde(),
+            true,
+            None,
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
+            false,
+        );
         let unaffected_socket_addr = SocketAddr::from_str("2.3.4.5:6789").unwrap();
         let unaffected_stream_key = StreamKey::make_meaningful_stream_key("unaffected");
         subject
@@ -5600,6 +9201,10 @@ mod tests {
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         let unaffected_socket_addr = SocketAddr::from_str("2.3.4.5:6789").unwrap();
@@ -5724,6 +9329,10 @@ mod tests {
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         let unaffected_socket_addr = SocketAddr::from_str("2.3.4.5:6789").unwrap();
@@ -5840,7 +9449,17 @@ mod tests {
     #[test]
     fn handle_stream_shutdown_msg_logs_errors_from_handling_normal_client_data() {
         init_test_logging();
-        let mut subject = ProxyServer::new(main_cryptde(), alias_cryptde(), true, Some(0), false);
+        let mut subject = ProxyServer::new(
+            main_cryptde(),
+            alias_cryptde(),
+            true,
+            Some(0),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
+            false,
+        );
         subject.subs = Some(make_proxy_server_out_subs());
         let helper = IBCDHelperMock::default()
             .handle_normal_client_data_result(Err("Our help is not welcome".to_string()));
@@ -5864,8 +9483,19 @@ mod tests {
 
     #[test]
     fn stream_shutdown_msg_populates_correct_inbound_client_data_msg() {
+        let _system = System::new("stream_shutdown_msg_populates_correct_inbound_client_data_msg");
         let help_to_handle_normal_client_data_params_arc = Arc::new(Mutex::new(vec![]));
-        let mut subject = ProxyServer::new(main_cryptde(), alias_cryptde(), true, Some(0), false);
+        let mut subject = ProxyServer::new(
+            main_cryptde(),
+            alias_cryptde(),
+            true,
+            Some(0),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
+            false,
+        );
         subject.subs = Some(make_proxy_server_out_subs());
         let icd_helper = IBCDHelperMock::default()
             .handle_normal_client_data_params(&help_to_handle_normal_client_data_params_arc)
@@ -5911,7 +9541,17 @@ mod tests {
 
     #[test]
     fn help_to_handle_normal_client_data_missing_consuming_wallet_and_protocol_pack_not_found() {
-        let mut proxy_server = ProxyServer::new(main_cryptde(), alias_cryptde(), true, None, false);
+        let mut proxy_server = ProxyServer::new(
+            main_cryptde(),
+            alias_cryptde(),
+            true,
+            None,
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
+            false,
+        );
         proxy_server.subs = Some(make_proxy_server_out_subs());
         let inbound_client_data_msg = InboundClientData {
             timestamp: SystemTime::now(),
@@ -6006,6 +9646,10 @@ mod tests {
             alias_cryptde(),
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         proxy_server.subs = Some(make_proxy_server_out_subs());
@@ -6077,6 +9721,10 @@ mod tests {
             alias_cryptde,
             true,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         subject.stream_key_factory = Box::new(stream_key_factory);
@@ -6145,6 +9793,10 @@ mod tests {
             alias_cryptde,
             false,
             Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
             false,
         );
         subject.stream_key_factory = Box::new(stream_key_factory);
@@ -6197,7 +9849,17 @@ mod tests {
     )]
     fn handle_stream_shutdown_complains_about_clandestine_message() {
         let system = System::new("test");
-        let subject = ProxyServer::new(main_cryptde(), alias_cryptde(), true, None, false);
+        let subject = ProxyServer::new(
+            main_cryptde(),
+            alias_cryptde(),
+            true,
+            None,
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
+            false,
+        );
         let subject_addr = subject.start();
 
         subject_addr
@@ -6217,7 +9879,17 @@ mod tests {
         expected = "panic message (processed with: node_lib::sub_lib::utils::crash_request_analyzer)"
     )]
     fn proxy_server_can_be_crashed_properly_but_not_improperly() {
-        let proxy_server = ProxyServer::new(main_cryptde(), alias_cryptde(), true, None, true);
+        let proxy_server = ProxyServer::new(
+            main_cryptde(),
+            alias_cryptde(),
+            true,
+            None,
+            None,
+            None,
+            DEFAULT_STICKY_ROUTE_TTL,
+            DEFAULT_MULTIPATH_ROUTES,
+            true,
+        );
 
         prove_that_crash_request_handler_is_hooked_up(proxy_server, CRASH_KEY);
     }
@@ -0,0 +1,93 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+pub const PAC_FILE_PATH: &str = "/proxy.pac";
+pub const PAC_FILE_CONTENT_TYPE: &str = "application/x-ns-proxy-autoconfig";
+
+// Local names stay off the MASQ Network: an exit Node couldn't reach them anyway.
+pub fn generate_pac_file(forward_proxy_port: u16, socks_port_opt: Option<u16>) -> String {
+    let proxies = match socks_port_opt {
+        Some(socks_port) => format!(
+            "PROXY 127.0.0.1:{}; SOCKS5 127.0.0.1:{}",
+            forward_proxy_port, socks_port
+        ),
+        None => format!("PROXY 127.0.0.1:{}", forward_proxy_port),
+    };
+    format!(
+        "function FindProxyForURL(url, host) {{\n\
+         \x20   if (isPlainHostName(host) || host === \"localhost\" || host === \"127.0.0.1\") {{\n\
+         \x20       return \"DIRECT\";\n\
+         \x20   }}\n\
+         \x20   return \"{}\";\n\
+         }}\n",
+        proxies
+    )
+}
+
+pub fn pac_file_response(forward_proxy_port: u16, socks_port_opt: Option<u16>) -> Vec<u8> {
+    let pac_file = generate_pac_file(forward_proxy_port, socks_port_opt);
+    format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        PAC_FILE_CONTENT_TYPE,
+        pac_file.len(),
+        pac_file
+    )
+    .into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(PAC_FILE_PATH, "/proxy.pac");
+        assert_eq!(PAC_FILE_CONTENT_TYPE, "application/x-ns-proxy-autoconfig");
+    }
+
+    #[test]
+    fn generates_pac_file_for_forward_proxy_alone() {
+        let result = generate_pac_file(8080, None);
+
+        assert_eq!(
+            result,
+            "function FindProxyForURL(url, host) {\n    \
+                if (isPlainHostName(host) || host === \"localhost\" || host === \"127.0.0.1\") {\n        \
+                    return \"DIRECT\";\n    \
+                }\n    \
+                return \"PROXY 127.0.0.1:8080\";\n\
+            }\n"
+        );
+    }
+
+    #[test]
+    fn generates_pac_file_that_falls_back_to_socks_listener() {
+        let result = generate_pac_file(8080, Some(1080));
+
+        assert!(
+            result.contains("    return \"PROXY 127.0.0.1:8080; SOCKS5 127.0.0.1:1080\";\n"),
+            "{}",
+            result
+        );
+    }
+
+    #[test]
+    fn pac_file_response_is_complete_http_response() {
+        let pac_file = generate_pac_file(8080, None);
+
+        let result = pac_file_response(8080, None);
+
+        assert_eq!(
+            String::from_utf8(result).unwrap(),
+            format!(
+                "HTTP/1.1 200 OK\r\n\
+                 Content-Type: application/x-ns-proxy-autoconfig\r\n\
+                 Content-Length: {}\r\n\
+                 Connection: close\r\n\
+                 \r\n\
+                 {}",
+                pac_file.len(),
+                pac_file
+            )
+        );
+    }
+}
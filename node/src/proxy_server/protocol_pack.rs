@@ -1,10 +1,11 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 use crate::proxy_server::http_protocol_pack::HttpProtocolPack;
+use crate::proxy_server::socks5_protocol_pack::Socks5ProtocolPack;
 use crate::proxy_server::tls_protocol_pack::TlsProtocolPack;
 use crate::sub_lib::cryptde::PlainData;
 use crate::sub_lib::dispatcher::InboundClientData;
 use crate::sub_lib::proxy_server::ProxyProtocol;
-use masq_lib::constants::{HTTP_PORT, TLS_PORT};
+use masq_lib::constants::{HTTP_PORT, SOCKS_PORT, TLS_PORT};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Host {
@@ -23,6 +24,7 @@ pub fn from_protocol(protocol: ProxyProtocol) -> Box<dyn ProtocolPack> {
     match protocol {
         ProxyProtocol::HTTP => Box::new(HttpProtocolPack {}),
         ProxyProtocol::TLS => Box::new(TlsProtocolPack {}),
+        ProxyProtocol::SOCKS5 => Box::new(Socks5ProtocolPack {}),
     }
 }
 
@@ -30,6 +32,7 @@ pub fn from_standard_port(standard_port: u16) -> Option<Box<dyn ProtocolPack>> {
     match standard_port {
         HTTP_PORT => Some(Box::new(HttpProtocolPack {})),
         TLS_PORT => Some(Box::new(TlsProtocolPack {})),
+        SOCKS_PORT => Some(Box::new(Socks5ProtocolPack {})),
         _ => None,
     }
 }
@@ -60,4 +63,5 @@ pub trait ServerImpersonator {
     fn route_query_failure_response(&self, server_name: &str) -> Vec<u8>;
     fn dns_resolution_failure_response(&self, server_name_opt: Option<String>) -> Vec<u8>;
     fn consuming_wallet_absent(&self) -> Vec<u8>;
+    fn service_refused_response(&self) -> Vec<u8>;
 }
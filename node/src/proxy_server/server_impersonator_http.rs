@@ -45,6 +45,18 @@ impl ServerImpersonator for ServerImpersonatorHttp {
             Set up a funded consuming wallet and try again.",
         )
     }
+
+    fn service_refused_response(&self) -> Vec<u8> {
+        ServerImpersonatorHttp::make_error_response(
+            402,
+            "Service Refused",
+            "An exit Node won't serve you until you pay it",
+            "The exit Node for this request says your Node owes it too much for too long, so it's \
+            refusing to carry your traffic. Your Node will steer around it for a while and will pay \
+            it first the next time it pays its debts; if this keeps happening, make sure your \
+            consuming wallet has enough funds to pay for what you use.",
+        )
+    }
 }
 
 impl ServerImpersonatorHttp {
@@ -239,4 +251,22 @@ mod tests {
         );
         assert_eq!(expected, result);
     }
+
+    #[test]
+    fn service_refused_response_produces_expected_error_page() {
+        let subject = ServerImpersonatorHttp {};
+
+        let result = subject.service_refused_response();
+
+        let expected = ServerImpersonatorHttp::make_error_response(
+            402,
+            "Service Refused",
+            "An exit Node won't serve you until you pay it",
+            "The exit Node for this request says your Node owes it too much for too long, so it's \
+            refusing to carry your traffic. Your Node will steer around it for a while and will pay \
+            it first the next time it pays its debts; if this keeps happening, make sure your \
+            consuming wallet has enough funds to pay for what you use.",
+        );
+        assert_eq!(expected, result);
+    }
 }
@@ -0,0 +1,122 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::proxy_server::protocol_pack::ServerImpersonator;
+use crate::sub_lib::socks5_framer::{SOCKS5_ATYP_IPV4, SOCKS5_VERSION};
+
+pub const SOCKS5_REPLY_SUCCEEDED: u8 = 0x00;
+pub const SOCKS5_REPLY_GENERAL_FAILURE: u8 = 0x01;
+pub const SOCKS5_REPLY_CONNECTION_NOT_ALLOWED: u8 = 0x02;
+pub const SOCKS5_REPLY_HOST_UNREACHABLE: u8 = 0x04;
+pub const SOCKS5_REPLY_COMMAND_NOT_SUPPORTED: u8 = 0x07;
+pub const SOCKS5_REPLY_ADDRESS_TYPE_NOT_SUPPORTED: u8 = 0x08;
+
+pub struct ServerImpersonatorSocks5 {}
+
+impl ServerImpersonator for ServerImpersonatorSocks5 {
+    fn route_query_failure_response(&self, _server_name: &str) -> Vec<u8> {
+        ServerImpersonatorSocks5::reply(SOCKS5_REPLY_HOST_UNREACHABLE)
+    }
+
+    fn dns_resolution_failure_response(&self, _server_name: Option<String>) -> Vec<u8> {
+        ServerImpersonatorSocks5::reply(SOCKS5_REPLY_HOST_UNREACHABLE)
+    }
+
+    fn consuming_wallet_absent(&self) -> Vec<u8> {
+        ServerImpersonatorSocks5::reply(SOCKS5_REPLY_CONNECTION_NOT_ALLOWED)
+    }
+
+    fn service_refused_response(&self) -> Vec<u8> {
+        ServerImpersonatorSocks5::reply(SOCKS5_REPLY_CONNECTION_NOT_ALLOWED)
+    }
+}
+
+impl ServerImpersonatorSocks5 {
+    // The bound address is meaningless to the client, since its data will leave the MASQ
+    // Network from an exit Node it doesn't know about; so we always report 0.0.0.0:0.
+    pub fn reply(reply_code: u8) -> Vec<u8> {
+        vec![
+            SOCKS5_VERSION,
+            reply_code,
+            0x00, // reserved
+            SOCKS5_ATYP_IPV4,
+            0x00,
+            0x00,
+            0x00,
+            0x00, // BND.ADDR
+            0x00,
+            0x00, // BND.PORT
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(SOCKS5_REPLY_SUCCEEDED, 0x00);
+        assert_eq!(SOCKS5_REPLY_GENERAL_FAILURE, 0x01);
+        assert_eq!(SOCKS5_REPLY_CONNECTION_NOT_ALLOWED, 0x02);
+        assert_eq!(SOCKS5_REPLY_HOST_UNREACHABLE, 0x04);
+        assert_eq!(SOCKS5_REPLY_COMMAND_NOT_SUPPORTED, 0x07);
+        assert_eq!(SOCKS5_REPLY_ADDRESS_TYPE_NOT_SUPPORTED, 0x08);
+    }
+
+    #[test]
+    fn reply_produces_reply_with_unspecified_bound_address() {
+        let result = ServerImpersonatorSocks5::reply(SOCKS5_REPLY_SUCCEEDED);
+
+        assert_eq!(
+            result,
+            vec![0x05, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn route_query_failure_response_produces_host_unreachable_reply() {
+        let subject = ServerImpersonatorSocks5 {};
+
+        let result = subject.route_query_failure_response("ignored");
+
+        assert_eq!(
+            result,
+            ServerImpersonatorSocks5::reply(SOCKS5_REPLY_HOST_UNREACHABLE)
+        );
+    }
+
+    #[test]
+    fn dns_resolution_failure_response_produces_host_unreachable_reply() {
+        let subject = ServerImpersonatorSocks5 {};
+
+        let result = subject.dns_resolution_failure_response(None);
+
+        assert_eq!(
+            result,
+            ServerImpersonatorSocks5::reply(SOCKS5_REPLY_HOST_UNREACHABLE)
+        );
+    }
+
+    #[test]
+    fn consuming_wallet_absent_produces_connection_not_allowed_reply() {
+        let subject = ServerImpersonatorSocks5 {};
+
+        let result = subject.consuming_wallet_absent();
+
+        assert_eq!(
+            result,
+            ServerImpersonatorSocks5::reply(SOCKS5_REPLY_CONNECTION_NOT_ALLOWED)
+        );
+    }
+
+    #[test]
+    fn service_refused_response_produces_connection_not_allowed_reply() {
+        let subject = ServerImpersonatorSocks5 {};
+
+        let result = subject.service_refused_response();
+
+        assert_eq!(
+            result,
+            ServerImpersonatorSocks5::reply(SOCKS5_REPLY_CONNECTION_NOT_ALLOWED)
+        );
+    }
+}
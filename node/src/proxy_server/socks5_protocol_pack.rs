@@ -0,0 +1,255 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::proxy_server::protocol_pack::{Host, ProtocolPack, ServerImpersonator};
+use crate::proxy_server::server_impersonator_socks5::{
+    ServerImpersonatorSocks5, SOCKS5_REPLY_ADDRESS_TYPE_NOT_SUPPORTED,
+    SOCKS5_REPLY_COMMAND_NOT_SUPPORTED, SOCKS5_REPLY_GENERAL_FAILURE,
+};
+use crate::sub_lib::cryptde::PlainData;
+use crate::sub_lib::proxy_server::ProxyProtocol;
+use crate::sub_lib::socks5_framer::{
+    SOCKS5_ATYP_DOMAIN_NAME, SOCKS5_ATYP_IPV4, SOCKS5_ATYP_IPV6, SOCKS5_VERSION,
+};
+use masq_lib::constants::SOCKS_PORT;
+use std::convert::TryFrom;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+pub const SOCKS5_METHOD_NO_AUTHENTICATION_REQUIRED: u8 = 0x00;
+pub const SOCKS5_METHOD_NO_ACCEPTABLE_METHODS: u8 = 0xFF;
+pub const SOCKS5_COMMAND_CONNECT: u8 = 0x01;
+
+pub struct Socks5ProtocolPack {}
+
+impl ProtocolPack for Socks5ProtocolPack {
+    fn proxy_protocol(&self) -> ProxyProtocol {
+        ProxyProtocol::SOCKS5
+    }
+
+    fn standard_port(&self) -> u16 {
+        SOCKS_PORT
+    }
+
+    fn find_host(&self, data: &PlainData) -> Option<Host> {
+        Socks5ProtocolPack::parse_connect_request(data.as_slice()).ok()
+    }
+
+    fn server_impersonator(&self) -> Box<dyn ServerImpersonator> {
+        Box::new(ServerImpersonatorSocks5 {})
+    }
+}
+
+impl Socks5ProtocolPack {
+    // Ok contains the method-selection reply for a greeting we can accept; Err contains the
+    // reply for one we can't. We don't do authentication: the listener is only for local apps.
+    pub fn method_selection_reply(greeting: &[u8]) -> Result<Vec<u8>, Vec<u8>> {
+        let acceptable = match greeting {
+            [SOCKS5_VERSION, method_count, methods @ ..] => {
+                methods.len() == *method_count as usize
+                    && methods.contains(&SOCKS5_METHOD_NO_AUTHENTICATION_REQUIRED)
+            }
+            _ => false,
+        };
+        if acceptable {
+            Ok(vec![
+                SOCKS5_VERSION,
+                SOCKS5_METHOD_NO_AUTHENTICATION_REQUIRED,
+            ])
+        } else {
+            Err(vec![SOCKS5_VERSION, SOCKS5_METHOD_NO_ACCEPTABLE_METHODS])
+        }
+    }
+
+    // Err contains the SOCKS5 reply code that explains why the request can't be honored.
+    pub fn parse_connect_request(data: &[u8]) -> Result<Host, u8> {
+        let (command, address_type, address_and_port) = match data {
+            [SOCKS5_VERSION, command, 0x00, address_type, rest @ ..] => {
+                (*command, *address_type, rest)
+            }
+            _ => return Err(SOCKS5_REPLY_GENERAL_FAILURE),
+        };
+        if command != SOCKS5_COMMAND_CONNECT {
+            return Err(SOCKS5_REPLY_COMMAND_NOT_SUPPORTED);
+        }
+        let (name, port_bytes) = match address_type {
+            SOCKS5_ATYP_IPV4 if address_and_port.len() == 4 + 2 => {
+                let octets = <[u8; 4]>::try_from(&address_and_port[0..4]).expect("Length checked");
+                (Ipv4Addr::from(octets).to_string(), &address_and_port[4..])
+            }
+            SOCKS5_ATYP_IPV6 if address_and_port.len() == 16 + 2 => {
+                let octets =
+                    <[u8; 16]>::try_from(&address_and_port[0..16]).expect("Length checked");
+                (Ipv6Addr::from(octets).to_string(), &address_and_port[16..])
+            }
+            SOCKS5_ATYP_DOMAIN_NAME
+                if !address_and_port.is_empty()
+                    && address_and_port.len() == 1 + address_and_port[0] as usize + 2 =>
+            {
+                let name_end = 1 + address_and_port[0] as usize;
+                match String::from_utf8(address_and_port[1..name_end].to_vec()) {
+                    Ok(name) if !name.is_empty() => (name, &address_and_port[name_end..]),
+                    _ => return Err(SOCKS5_REPLY_GENERAL_FAILURE),
+                }
+            }
+            SOCKS5_ATYP_IPV4 | SOCKS5_ATYP_IPV6 | SOCKS5_ATYP_DOMAIN_NAME => {
+                return Err(SOCKS5_REPLY_GENERAL_FAILURE)
+            }
+            _ => return Err(SOCKS5_REPLY_ADDRESS_TYPE_NOT_SUPPORTED),
+        };
+        let port = ((port_bytes[0] as u16) << 8) | (port_bytes[1] as u16);
+        Ok(Host {
+            name,
+            port: Some(port),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(SOCKS5_METHOD_NO_AUTHENTICATION_REQUIRED, 0x00);
+        assert_eq!(SOCKS5_METHOD_NO_ACCEPTABLE_METHODS, 0xFF);
+        assert_eq!(SOCKS5_COMMAND_CONNECT, 0x01);
+    }
+
+    #[test]
+    fn knows_its_protocol() {
+        let subject = Socks5ProtocolPack {};
+
+        let result = subject.proxy_protocol();
+
+        assert_eq!(result, ProxyProtocol::SOCKS5);
+    }
+
+    #[test]
+    fn knows_its_standard_port() {
+        let subject = Socks5ProtocolPack {};
+
+        let result = subject.standard_port();
+
+        assert_eq!(result, SOCKS_PORT);
+    }
+
+    #[test]
+    fn accepts_greeting_offering_no_authentication() {
+        let result = Socks5ProtocolPack::method_selection_reply(&[0x05, 0x02, 0x02, 0x00]);
+
+        assert_eq!(result, Ok(vec![0x05, 0x00]));
+    }
+
+    #[test]
+    fn rejects_greeting_demanding_authentication() {
+        let result = Socks5ProtocolPack::method_selection_reply(&[0x05, 0x01, 0x02]);
+
+        assert_eq!(result, Err(vec![0x05, 0xFF]));
+    }
+
+    #[test]
+    fn rejects_greeting_from_wrong_protocol_version() {
+        let result = Socks5ProtocolPack::method_selection_reply(&[0x04, 0x01, 0x00]);
+
+        assert_eq!(result, Err(vec![0x05, 0xFF]));
+    }
+
+    #[test]
+    fn rejects_greeting_with_wrong_method_count() {
+        let result = Socks5ProtocolPack::method_selection_reply(&[0x05, 0x03, 0x00]);
+
+        assert_eq!(result, Err(vec![0x05, 0xFF]));
+    }
+
+    #[test]
+    fn finds_domain_name_host_with_nonstandard_port() {
+        let mut data = vec![0x05, 0x01, 0x00, 0x03, 0x0B];
+        data.extend(b"example.com");
+        data.extend(&[0x1F, 0x90]);
+        let subject = Socks5ProtocolPack {};
+
+        let result = subject.find_host(&PlainData::from(data));
+
+        assert_eq!(
+            result,
+            Some(Host {
+                name: "example.com".to_string(),
+                port: Some(8080)
+            })
+        );
+    }
+
+    #[test]
+    fn finds_ipv4_host() {
+        let result = Socks5ProtocolPack::parse_connect_request(&[
+            0x05, 0x01, 0x00, 0x01, 1, 2, 3, 4, 0x00, 0x19,
+        ]);
+
+        assert_eq!(
+            result,
+            Ok(Host {
+                name: "1.2.3.4".to_string(),
+                port: Some(25)
+            })
+        );
+    }
+
+    #[test]
+    fn finds_ipv6_host() {
+        let mut data = vec![0x05, 0x01, 0x00, 0x04];
+        data.extend(&[0x20, 0x01, 0x0D, 0xB8]);
+        data.extend(&[0x00; 11]);
+        data.extend(&[0x01, 0x01, 0xBB]);
+
+        let result = Socks5ProtocolPack::parse_connect_request(&data);
+
+        assert_eq!(
+            result,
+            Ok(Host {
+                name: "2001:db8::1".to_string(),
+                port: Some(443)
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_commands_other_than_connect() {
+        let result = Socks5ProtocolPack::parse_connect_request(&[
+            0x05, 0x03, 0x00, 0x01, 1, 2, 3, 4, 0x00, 0x35,
+        ]);
+
+        assert_eq!(result, Err(SOCKS5_REPLY_COMMAND_NOT_SUPPORTED));
+    }
+
+    #[test]
+    fn rejects_unknown_address_types() {
+        let result =
+            Socks5ProtocolPack::parse_connect_request(&[0x05, 0x01, 0x00, 0x09, 1, 2, 0x00, 0x50]);
+
+        assert_eq!(result, Err(SOCKS5_REPLY_ADDRESS_TYPE_NOT_SUPPORTED));
+    }
+
+    #[test]
+    fn rejects_truncated_and_malformed_requests() {
+        vec![
+            vec![0x05, 0x01],
+            vec![0x04, 0x01, 0x00, 0x01, 1, 2, 3, 4, 0x00, 0x50],
+            vec![0x05, 0x01, 0x00, 0x01, 1, 2, 3, 4, 0x00],
+            vec![0x05, 0x01, 0x00, 0x03, 0x05, b'a', b'b', 0x00, 0x50],
+            vec![0x05, 0x01, 0x00, 0x03, 0x00, 0x00, 0x50],
+        ]
+        .into_iter()
+        .for_each(|data| {
+            let result = Socks5ProtocolPack::parse_connect_request(&data);
+
+            assert_eq!(result, Err(SOCKS5_REPLY_GENERAL_FAILURE), "{:?}", data);
+        });
+    }
+
+    #[test]
+    fn tunneled_data_has_no_host() {
+        let subject = Socks5ProtocolPack {};
+
+        let result = subject.find_host(&PlainData::from(b"GET / HTTP/1.1\r\n\r\n".to_vec()));
+
+        assert_eq!(result, None);
+    }
+}
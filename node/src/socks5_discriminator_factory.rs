@@ -0,0 +1,56 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::discriminator::Discriminator;
+use crate::discriminator::DiscriminatorFactory;
+use crate::null_masquerader::NullMasquerader;
+use crate::sub_lib::socks5_framer::Socks5Framer;
+
+#[derive(Debug, Default)]
+pub struct Socks5DiscriminatorFactory {}
+
+impl DiscriminatorFactory for Socks5DiscriminatorFactory {
+    fn make(&self) -> Discriminator {
+        Discriminator::new(
+            Box::new(Socks5Framer::new()),
+            vec![Box::new(NullMasquerader::new())],
+        )
+    }
+
+    fn duplicate(&self) -> Box<dyn DiscriminatorFactory> {
+        Box::new(Socks5DiscriminatorFactory {})
+    }
+}
+
+impl Socks5DiscriminatorFactory {
+    pub fn new() -> Socks5DiscriminatorFactory {
+        Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discriminator::UnmaskedChunk;
+
+    #[test]
+    fn discriminator_factory_duplicate_works() {
+        let subject = Socks5DiscriminatorFactory::new();
+
+        subject.duplicate();
+
+        // no panic; test passes
+    }
+
+    #[test]
+    fn factory_makes_discriminator_that_can_handle_socks5_greeting_for_proxy_server() {
+        let data: &[u8] = &[0x05, 0x01, 0x00];
+        let subject = Socks5DiscriminatorFactory::new();
+
+        let mut result = subject.make();
+
+        result.add_data(data);
+        assert_eq!(
+            result.take_chunk(),
+            Some(UnmaskedChunk::new(Vec::from(data), true, true))
+        );
+    }
+}
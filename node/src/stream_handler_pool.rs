@@ -2,8 +2,8 @@
 
 use crate::bootstrapper::PortConfiguration;
 use crate::discriminator::DiscriminatorFactory;
-use crate::json_masquerader::JsonMasquerader;
-use crate::masquerader::Masquerader;
+use crate::link_session::{LinkFrame, LinkSession, LINK_HANDSHAKE_TIMEOUT, LINK_REKEY_INTERVAL};
+use crate::masquerader::{Masquerader, MasqueraderKind};
 use crate::stream_messages::*;
 use crate::stream_reader::StreamReaderReal;
 use crate::stream_writer_sorted::StreamWriterSorted;
@@ -11,7 +11,7 @@ use crate::stream_writer_unsorted::StreamWriterUnsorted;
 use crate::sub_lib::channel_wrappers::FuturesChannelFactory;
 use crate::sub_lib::channel_wrappers::FuturesChannelFactoryReal;
 use crate::sub_lib::channel_wrappers::SenderWrapper;
-use crate::sub_lib::cryptde::PublicKey;
+use crate::sub_lib::cryptde::{CryptDE, PublicKey};
 use crate::sub_lib::dispatcher;
 use crate::sub_lib::dispatcher::Endpoint;
 use crate::sub_lib::dispatcher::{DispatcherSubs, StreamShutdownMsg};
@@ -39,11 +39,11 @@ use actix::{Actor, AsyncContext};
 use masq_lib::logger::Logger;
 use masq_lib::ui_gateway::NodeFromUiMessage;
 use masq_lib::utils::localhost;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Display, Formatter};
 use std::io;
 use std::net::SocketAddr;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 use tokio::prelude::Future;
 
 // IMPORTANT: Nothing at or below the level of StreamHandlerPool should know about StreamKeys.
@@ -62,6 +62,7 @@ pub struct StreamHandlerPoolSubs {
     pub node_query_response: Recipient<DispatcherNodeQueryResponse>,
     pub node_from_ui_sub: Recipient<NodeFromUiMessage>,
     pub scheduled_node_query_response_sub: Recipient<MessageScheduler<DispatcherNodeQueryResponse>>,
+    pub link_data_sub: Recipient<dispatcher::InboundClientData>,
 }
 
 impl Clone for StreamHandlerPoolSubs {
@@ -74,6 +75,7 @@ impl Clone for StreamHandlerPoolSubs {
             node_query_response: self.node_query_response.clone(),
             node_from_ui_sub: self.node_from_ui_sub.clone(),
             scheduled_node_query_response_sub: self.scheduled_node_query_response_sub.clone(),
+            link_data_sub: self.link_data_sub.clone(),
         }
     }
 }
@@ -109,6 +111,13 @@ impl Display for StreamWriterKey {
 // It is used to store streams for both neighbors and browser.
 pub struct StreamHandlerPool {
     stream_writers: HashMap<StreamWriterKey, Option<Box<dyn SenderWrapper<SequencedPacket>>>>,
+    // Streams this Node opened itself, rather than accepted from a neighbor
+    outbound_stream_writer_keys: HashSet<StreamWriterKey>,
+    // Forward-secret keys for the link layer of each clandestine stream
+    link_sessions: HashMap<StreamWriterKey, LinkSession>,
+    link_rekey_interval: Duration,
+    link_handshake_timeout: Duration,
+    cryptde: &'static dyn CryptDE,
     dispatcher_subs_opt: Option<DispatcherSubs>,
     self_subs_opt: Option<StreamHandlerPoolSubs>,
     ask_neighborhood_opt: Option<Recipient<DispatcherNodeQueryMessage>>,
@@ -150,6 +159,14 @@ impl Handler<TransmitDataMsg> for StreamHandlerPool {
     }
 }
 
+impl Handler<dispatcher::InboundClientData> for StreamHandlerPool {
+    type Result = ();
+
+    fn handle(&mut self, msg: dispatcher::InboundClientData, _ctx: &mut Self::Context) {
+        self.handle_link_data(msg)
+    }
+}
+
 impl Handler<DispatcherNodeQueryResponse> for StreamHandlerPool {
     type Result = ();
     fn handle(&mut self, msg: DispatcherNodeQueryResponse, _ctx: &mut Self::Context) {
@@ -179,6 +196,10 @@ impl Handler<PoolBindMessage> for StreamHandlerPool {
         self.ask_neighborhood_opt = Some(msg.neighborhood_subs.dispatcher_node_query);
         self.remove_neighbor_sub_opt = Some(msg.neighborhood_subs.remove_neighbor);
         self.connection_progress_sub_opt = Some(msg.neighborhood_subs.connection_progress_sub);
+        ctx.run_interval(self.link_rekey_interval, |pool, _| pool.rekey_links());
+        ctx.run_interval(self.link_handshake_timeout, |pool, _| {
+            pool.expire_link_handshakes()
+        });
     }
 }
 
@@ -193,10 +214,17 @@ impl Handler<NodeFromUiMessage> for StreamHandlerPool {
 impl StreamHandlerPool {
     pub fn new(
         clandestine_discriminator_factories: Vec<Box<dyn DiscriminatorFactory>>,
+        masquerader_kinds: Vec<MasqueraderKind>,
         crashable: bool,
+        cryptde: &'static dyn CryptDE,
     ) -> StreamHandlerPool {
         StreamHandlerPool {
             stream_writers: HashMap::new(),
+            outbound_stream_writer_keys: HashSet::new(),
+            link_sessions: HashMap::new(),
+            link_rekey_interval: LINK_REKEY_INTERVAL,
+            link_handshake_timeout: LINK_HANDSHAKE_TIMEOUT,
+            cryptde,
             dispatcher_subs_opt: None,
             self_subs_opt: None,
             ask_neighborhood_opt: None,
@@ -207,7 +235,7 @@ impl StreamHandlerPool {
             stream_connector: Box::new(StreamConnectorReal {}),
             channel_factory: Box::new(FuturesChannelFactoryReal {}),
             clandestine_discriminator_factories,
-            traffic_analyzer: Box::new(TrafficAnalyzerReal {}),
+            traffic_analyzer: Box::new(TrafficAnalyzerReal::new(masquerader_kinds)),
         }
     }
 
@@ -223,6 +251,7 @@ impl StreamHandlerPool {
                 pool_addr,
                 MessageScheduler<DispatcherNodeQueryResponse>
             ),
+            link_data_sub: recipient!(pool_addr, dispatcher::InboundClientData),
         }
     }
 
@@ -234,12 +263,20 @@ impl StreamHandlerPool {
         peer_addr: SocketAddr,
         local_addr: SocketAddr,
     ) {
-        let ibcd_sub: Recipient<dispatcher::InboundClientData> = self
-            .dispatcher_subs_opt
-            .as_ref()
-            .expect("Dispatcher is unbound")
-            .ibcd_sub
-            .clone();
+        let ibcd_sub: Recipient<dispatcher::InboundClientData> =
+            if port_configuration.is_clandestine {
+                self.self_subs_opt
+                    .as_ref()
+                    .expect("StreamHandlerPool is unbound")
+                    .link_data_sub
+                    .clone()
+            } else {
+                self.dispatcher_subs_opt
+                    .as_ref()
+                    .expect("Dispatcher is unbound")
+                    .ibcd_sub
+                    .clone()
+            };
         let remove_sub: Recipient<RemoveStreamMsg> = self
             .self_subs_opt
             .as_ref()
@@ -338,6 +375,8 @@ impl StreamHandlerPool {
                             PublicKey::new(&[]),
                             Some(NodeAddr::from(&socket_addr)),
                             ZERO_RATE_PACK,
+                            vec![],
+                            false,
                         )),
                         context: msg,
                     })
@@ -374,6 +413,8 @@ impl StreamHandlerPool {
             msg.peer_addr,
             stream_writer_key
         );
+        self.outbound_stream_writer_keys.remove(&stream_writer_key);
+        self.link_sessions.remove(&stream_writer_key);
         let report_to_counterpart = match self.stream_writers.remove(&stream_writer_key) {
             None => {
                 trace!(
@@ -472,6 +513,13 @@ impl StreamHandlerPool {
         peer_addr: SocketAddr,
         sw_key: StreamWriterKey,
     ) -> Result<(), String> {
+        if msg.context.sequence_number.is_none()
+            && matches!(self.stream_writers.get(&sw_key), Some(Some(_)))
+            && !self.is_link_ready(&msg, peer_addr, sw_key)
+        {
+            self.delay_packet_for_opening_stream(msg, peer_addr, sw_key);
+            return Ok(());
+        }
         let tx_box_opt_opt = self.stream_writers.get(&sw_key);
         match tx_box_opt_opt {
             Some(Some(tx_box)) => {
@@ -480,6 +528,9 @@ impl StreamHandlerPool {
                 if remove_stream_writer {
                     self.stream_writers
                         .remove(&StreamWriterKey::from(peer_addr));
+                    self.outbound_stream_writer_keys
+                        .remove(&StreamWriterKey::from(peer_addr));
+                    self.link_sessions.remove(&StreamWriterKey::from(peer_addr));
                 }
             }
             Some(None) => self.delay_packet_for_opening_stream(msg, peer_addr, sw_key),
@@ -494,6 +545,8 @@ impl StreamHandlerPool {
 
                 self.stream_writers
                     .insert(StreamWriterKey::from(peer_addr), None);
+                self.outbound_stream_writer_keys
+                    .insert(StreamWriterKey::from(peer_addr));
 
                 self.open_new_stream_and_recycle_message(msg, peer_addr, sw_key);
             }
@@ -517,8 +570,26 @@ impl StreamHandlerPool {
         );
         debug!(self.logger, "Masking {} bytes", msg.context.data.len());
         let packet = if msg.context.sequence_number.is_none() {
-            let masquerader = self.traffic_analyzer.get_masquerader();
-            match masquerader.mask(msg.context.data.as_slice()) {
+            let neighbor_masquerader_kinds = msg
+                .result
+                .as_ref()
+                .map(|metadata| metadata.masquerader_kinds.as_slice())
+                .unwrap_or(&[]);
+            let data = match self.link_sessions.get(&sw_key) {
+                Some(session) => self
+                    .seal_for_link(session, &msg)
+                    .map_err(|e| {
+                        format!(
+                            "{} for {}. Discarding {} bytes.",
+                            e,
+                            peer_addr,
+                            msg.context.data.len()
+                        )
+                    })?
+                    .to_bytes(),
+                None => msg.context.data.clone(),
+            };
+            match self.mask(&data, neighbor_masquerader_kinds, sw_key) {
                 Ok(masked_data) => SequencedPacket::new(masked_data, 0, false),
                 Err(e) => {
                     return Err(format!(
@@ -562,6 +633,253 @@ impl StreamHandlerPool {
         Ok(false)
     }
 
+    fn mask(
+        &self,
+        data: &[u8],
+        neighbor_masquerader_kinds: &[MasqueraderKind],
+        sw_key: StreamWriterKey,
+    ) -> Result<Vec<u8>, String> {
+        let masquerader = self.traffic_analyzer.get_masquerader(
+            neighbor_masquerader_kinds,
+            self.outbound_stream_writer_keys.contains(&sw_key),
+        );
+        masquerader.mask(data).map_err(|e| format!("{}", e))
+    }
+
+    fn seal_for_link(
+        &self,
+        session: &LinkSession,
+        msg: &DispatcherNodeQueryResponse,
+    ) -> Result<LinkFrame, String> {
+        let intended_key_opt = msg
+            .result
+            .as_ref()
+            .map(|metadata| &metadata.public_key)
+            .filter(|public_key| !public_key.is_empty());
+        match (intended_key_opt, session.peer_key_opt()) {
+            (Some(intended_key), Some(peer_key)) if intended_key != peer_key => Err(format!(
+                "Link keys are shared with {}, not {}",
+                peer_key, intended_key
+            )),
+            _ => session.seal(self.cryptde, msg.context.data.as_slice()),
+        }
+    }
+
+    // Clandestine data can go out once the link has its keys. A link is opened only by the Node
+    // that opened the connection, and only to a neighbor that gossips support for it; the
+    // others get plain masked CORES packages, as they always have.
+    fn is_link_ready(
+        &mut self,
+        msg: &DispatcherNodeQueryResponse,
+        peer_addr: SocketAddr,
+        sw_key: StreamWriterKey,
+    ) -> bool {
+        if let Some(session) = self.link_sessions.get(&sw_key) {
+            return session.is_established();
+        }
+        let neighbor_key_opt = msg
+            .result
+            .as_ref()
+            .filter(|metadata| metadata.link_encryption)
+            .map(|metadata| metadata.public_key.clone());
+        match neighbor_key_opt {
+            Some(neighbor_key) if self.outbound_stream_writer_keys.contains(&sw_key) => {
+                self.start_link_session(peer_addr, neighbor_key);
+                false
+            }
+            _ => true,
+        }
+    }
+
+    fn is_link_established(&self, sw_key: &StreamWriterKey) -> bool {
+        self.link_sessions
+            .get(sw_key)
+            .map(|session| session.is_established())
+            .unwrap_or(false)
+    }
+
+    fn start_link_session(&mut self, peer_addr: SocketAddr, neighbor_key: PublicKey) {
+        let sw_key = StreamWriterKey::from(peer_addr);
+        let mut session = LinkSession::new_initiator(neighbor_key);
+        let hello = session.start_handshake(self.cryptde, SystemTime::now());
+        self.link_sessions.insert(sw_key, session);
+        debug!(self.logger, "Opening link key exchange with {}", peer_addr);
+        self.send_link_frame(&hello, peer_addr);
+    }
+
+    fn rekey_links(&mut self) {
+        let sw_keys = self
+            .link_sessions
+            .iter()
+            .filter(|(_, session)| {
+                session.is_initiator() && session.is_established() && !session.is_rekeying()
+            })
+            .map(|(sw_key, _)| *sw_key)
+            .collect::<Vec<StreamWriterKey>>();
+        for sw_key in sw_keys {
+            let hello = self
+                .link_sessions
+                .get_mut(&sw_key)
+                .expect("Link session disappeared")
+                .start_handshake(self.cryptde, SystemTime::now());
+            debug!(self.logger, "Rekeying link to {}", sw_key.socket_addr);
+            self.send_link_frame(&hello, sw_key.socket_addr)
+        }
+    }
+
+    // An unanswered rekey leaves the link on its current keys until the next rekey. A link that
+    // never got keys at all is dropped, so that the next packet for it opens a new connection.
+    fn expire_link_handshakes(&mut self) {
+        let now = SystemTime::now();
+        let timeout = self.link_handshake_timeout;
+        let expired = self
+            .link_sessions
+            .iter_mut()
+            .filter_map(|(sw_key, session)| {
+                session
+                    .expire_handshake(now, timeout)
+                    .then(|| (*sw_key, session.is_established()))
+            })
+            .collect::<Vec<(StreamWriterKey, bool)>>();
+        for (sw_key, established) in expired {
+            if established {
+                warning!(
+                    self.logger,
+                    "Link to {} didn't answer rekey in time; keeping current keys",
+                    sw_key.socket_addr
+                );
+            } else {
+                warning!(
+                    self.logger,
+                    "Link to {} didn't complete its key exchange in time; dropping it",
+                    sw_key.socket_addr
+                );
+                self.link_sessions.remove(&sw_key);
+                self.stream_writers.remove(&sw_key);
+                self.outbound_stream_writer_keys.remove(&sw_key);
+            }
+        }
+    }
+
+    fn send_link_frame(&self, frame: &LinkFrame, peer_addr: SocketAddr) {
+        let sw_key = StreamWriterKey::from(peer_addr);
+        let tx_box = match self.stream_writers.get(&sw_key) {
+            Some(Some(tx_box)) => tx_box,
+            _ => {
+                warning!(
+                    self.logger,
+                    "No stream to {} for link key exchange",
+                    peer_addr
+                );
+                return;
+            }
+        };
+        let masked_data = match self.mask(&frame.to_bytes(), &[], sw_key) {
+            Ok(masked_data) => masked_data,
+            Err(e) => {
+                error!(
+                    self.logger,
+                    "Masking failed for link key exchange with {}: {}", peer_addr, e
+                );
+                return;
+            }
+        };
+        if let Err(e) = tx_box.unbounded_send(SequencedPacket::new(masked_data, 0, false)) {
+            error!(
+                self.logger,
+                "Couldn't send link key exchange to {}: {}", peer_addr, e
+            );
+        }
+    }
+
+    fn handle_link_data(&mut self, msg: dispatcher::InboundClientData) {
+        let sw_key = StreamWriterKey::from(msg.peer_addr);
+        let frame = match LinkFrame::from_bytes(&msg.data) {
+            Ok(frame) => frame,
+            // Neighbors that haven't set up a link with us send plain CORES packages
+            Err(_) if !self.is_link_established(&sw_key) => {
+                self.dispatcher_subs_opt
+                    .as_ref()
+                    .expect("Dispatcher is unbound")
+                    .ibcd_sub
+                    .try_send(msg)
+                    .expect("Dispatcher is dead");
+                return;
+            }
+            Err(e) => {
+                warning!(
+                    self.logger,
+                    "Discarding {} bytes from {}: {}",
+                    msg.data.len(),
+                    msg.peer_addr,
+                    e
+                );
+                return;
+            }
+        };
+        match frame {
+            LinkFrame::Hello(hello) => {
+                let result = self
+                    .link_sessions
+                    .entry(sw_key)
+                    .or_default()
+                    .receive_hello(self.cryptde, &hello);
+                match result {
+                    Ok(reply_opt) => {
+                        debug!(
+                            self.logger,
+                            "Link keys for generation {} established with {}",
+                            hello.generation,
+                            msg.peer_addr
+                        );
+                        if let Some(reply) = reply_opt {
+                            self.send_link_frame(&reply, msg.peer_addr)
+                        }
+                    }
+                    Err(e) => {
+                        warning!(
+                            self.logger,
+                            "Link key exchange with {} failed: {}",
+                            msg.peer_addr,
+                            e
+                        );
+                        // Don't let a bad Hello hold up plain CORES packages on this stream
+                        if !self.is_link_established(&sw_key)
+                            && !self.link_sessions[&sw_key].is_rekeying()
+                        {
+                            self.link_sessions.remove(&sw_key);
+                        }
+                    }
+                }
+            }
+            LinkFrame::Data { generation, data } => {
+                let opened = match self.link_sessions.get(&sw_key) {
+                    Some(session) => session.open(self.cryptde, generation, &data),
+                    None => Err("No link session".to_string()),
+                };
+                match opened {
+                    Ok(plain_data) => self
+                        .dispatcher_subs_opt
+                        .as_ref()
+                        .expect("Dispatcher is unbound")
+                        .ibcd_sub
+                        .try_send(dispatcher::InboundClientData {
+                            data: plain_data.into(),
+                            ..msg
+                        })
+                        .expect("Dispatcher is dead"),
+                    Err(e) => warning!(
+                        self.logger,
+                        "Discarding {} bytes from {}: {}",
+                        data.len(),
+                        msg.peer_addr,
+                        e
+                    ),
+                }
+            }
+        }
+    }
+
     fn delay_packet_for_opening_stream(
         &self,
         msg: DispatcherNodeQueryResponse,
@@ -767,27 +1085,47 @@ impl StreamStartSuccessHandler {
 }
 
 trait TrafficAnalyzer {
-    fn get_masquerader(&self) -> Box<dyn Masquerader>;
+    fn get_masquerader(
+        &self,
+        neighbor_masquerader_kinds: &[MasqueraderKind],
+        initiator: bool,
+    ) -> Box<dyn Masquerader>;
 }
 
-struct TrafficAnalyzerReal {}
+struct TrafficAnalyzerReal {
+    masquerader_kinds: Vec<MasqueraderKind>,
+}
 
 impl TrafficAnalyzer for TrafficAnalyzerReal {
-    fn get_masquerader(&self) -> Box<dyn Masquerader> {
-        Box::new(JsonMasquerader::new())
+    fn get_masquerader(
+        &self,
+        neighbor_masquerader_kinds: &[MasqueraderKind],
+        initiator: bool,
+    ) -> Box<dyn Masquerader> {
+        MasqueraderKind::negotiate(&self.masquerader_kinds, neighbor_masquerader_kinds)
+            .make_masquerader(initiator)
     }
 }
 
-impl TrafficAnalyzerReal {}
+impl TrafficAnalyzerReal {
+    fn new(masquerader_kinds: Vec<MasqueraderKind>) -> TrafficAnalyzerReal {
+        TrafficAnalyzerReal { masquerader_kinds }
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::http_masquerader::{HttpMasqueradeForm, HttpMasquerader};
     use crate::http_request_start_finder::HttpRequestDiscriminatorFactory;
     use crate::json_discriminator_factory::JsonDiscriminatorFactory;
     use crate::json_masquerader::JsonMasquerader;
+    use crate::link_session::LinkHello;
     use crate::masquerader::Masquerader;
-    use crate::node_test_utils::{check_timestamp, FailingMasquerader};
+    use crate::node_test_utils::{
+        check_timestamp, make_stream_handler_pool_subs_from_recorder, FailingMasquerader,
+    };
+    use crate::sub_lib::cryptde::{CryptData, PlainData, SymmetricKey};
     use crate::sub_lib::dispatcher::InboundClientData;
     use crate::sub_lib::neighborhood::{
         ConnectionProgressEvent, ConnectionProgressMessage, NodeQueryResponseMetadata,
@@ -832,11 +1170,89 @@ mod tests {
     struct TrafficAnalyzerMock {}
 
     impl TrafficAnalyzer for TrafficAnalyzerMock {
-        fn get_masquerader(&self) -> Box<dyn Masquerader> {
+        fn get_masquerader(
+            &self,
+            _neighbor_masquerader_kinds: &[MasqueraderKind],
+            _initiator: bool,
+        ) -> Box<dyn Masquerader> {
             Box::new(FailingMasquerader {})
         }
     }
 
+    fn link_key() -> SymmetricKey {
+        SymmetricKey::new(b"link key")
+    }
+
+    fn established_link_session() -> LinkSession {
+        LinkSession::new_established_for_test(
+            true,
+            main_cryptde().public_key().clone(),
+            0,
+            link_key(),
+            link_key(),
+        )
+    }
+
+    fn link_data_frame(data: &[u8]) -> Vec<u8> {
+        LinkFrame::Data {
+            generation: 0,
+            data: main_cryptde()
+                .encode_sym(&link_key(), &PlainData::new(data))
+                .unwrap(),
+        }
+        .to_bytes()
+    }
+
+    fn unmask_link_frame(masked: &[u8]) -> LinkFrame {
+        let unmasked = JsonMasquerader::new().try_unmask(masked).unwrap();
+        LinkFrame::from_bytes(&unmasked.chunk).unwrap()
+    }
+
+    // Session keys from libsodium's key exchange are 32 bytes long, and CryptDENull puts the
+    // key in front of the data, so the lengths of masked frames are predictable.
+    fn masked_link_frame_len(frame: LinkFrame) -> usize {
+        JsonMasquerader::new()
+            .mask(&frame.to_bytes())
+            .unwrap()
+            .len()
+    }
+
+    fn masked_hello_len() -> usize {
+        masked_link_frame_len(
+            LinkSession::new_initiator(PublicKey::new(&[]))
+                .start_handshake(main_cryptde(), SystemTime::now()),
+        )
+    }
+
+    fn masked_data_len(data_len: usize) -> usize {
+        masked_link_frame_len(LinkFrame::Data {
+            generation: 0,
+            data: CryptData::new(&vec![0; 32 + data_len]),
+        })
+    }
+
+    fn answer_hello(peer: &mut LinkSession, masked_hello: &[u8]) -> LinkFrame {
+        match unmask_link_frame(masked_hello) {
+            LinkFrame::Hello(hello) => {
+                assert_eq!(hello.generation, 0);
+                assert_eq!(hello.reply_to_opt, None);
+                peer.receive_hello(main_cryptde(), &hello)
+                    .unwrap()
+                    .expect("No reply to Hello")
+            }
+            x => panic!("Expected Hello; got {:?}", x),
+        }
+    }
+
+    fn open_link_data(peer: &LinkSession, masked_data: &[u8]) -> Vec<u8> {
+        match unmask_link_frame(masked_data) {
+            LinkFrame::Data { generation, data } => {
+                peer.open(main_cryptde(), generation, &data).unwrap().into()
+            }
+            x => panic!("Expected Data; got {:?}", x),
+        }
+    }
+
     #[test]
     fn a_newly_added_stream_produces_stream_handler_that_sends_received_data_to_dispatcher() {
         let dispatcher = Recorder::new();
@@ -861,7 +1277,12 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let mut subject = StreamHandlerPool::new(vec![], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![],
+                MasqueraderKind::supported(false),
+                false,
+                main_cryptde(),
+            );
             subject.stream_connector = Box::new(StreamConnectorMock::new());
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -982,7 +1403,12 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let subject = StreamHandlerPool::new(vec![], false);
+            let subject = StreamHandlerPool::new(
+                vec![],
+                MasqueraderKind::supported(false),
+                false,
+                main_cryptde(),
+            );
 
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -1046,7 +1472,12 @@ mod tests {
         thread::spawn(move || {
             let system = System::new("test");
 
-            let mut subject = StreamHandlerPool::new(vec![], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![],
+                MasqueraderKind::supported(false),
+                false,
+                main_cryptde(),
+            );
             subject.stream_connector = Box::new(
                 StreamConnectorMock::new()
                     .connect_pair_result(Err(Error::from(ErrorKind::ConnectionRefused))),
@@ -1150,8 +1581,12 @@ mod tests {
         thread::spawn(move || {
             let system = System::new("test");
 
-            let mut subject =
-                StreamHandlerPool::new(vec![Box::new(JsonDiscriminatorFactory {})], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![Box::new(JsonDiscriminatorFactory {})],
+                MasqueraderKind::supported(false),
+                false,
+                main_cryptde(),
+            );
             subject.stream_connector = Box::new(StreamConnectorMock::new().connection(
                 local_addr,
                 peer_addr,
@@ -1223,7 +1658,12 @@ mod tests {
         let (recorder, _, recording_arc) = make_recorder();
         let system = System::new("test");
         let sub = recorder.start().recipient::<StreamShutdownMsg>();
-        let mut subject = StreamHandlerPool::new(vec![], false);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::supported(false),
+            false,
+            main_cryptde(),
+        );
         let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
         let local_addr = SocketAddr::from_str("127.0.0.1:0").unwrap();
         let sw_key = StreamWriterKey::from(peer_addr);
@@ -1231,6 +1671,10 @@ mod tests {
         subject
             .stream_writers
             .insert(sw_key.clone(), Some(Box::new(sender_wrapper)));
+        subject.outbound_stream_writer_keys.insert(sw_key);
+        subject
+            .link_sessions
+            .insert(sw_key, established_link_session());
 
         subject.handle_remove_stream_msg(RemoveStreamMsg {
             peer_addr,
@@ -1242,6 +1686,8 @@ mod tests {
         System::current().stop_with_code(0);
         system.run();
         assert_eq!(subject.stream_writers.contains_key(&sw_key), false);
+        assert!(!subject.outbound_stream_writer_keys.contains(&sw_key));
+        assert!(!subject.link_sessions.contains_key(&sw_key));
         let recording = recording_arc.lock().unwrap();
         let record = recording.get_record::<StreamShutdownMsg>(0);
         assert_eq!(
@@ -1259,7 +1705,12 @@ mod tests {
         let (recorder, _, recording_arc) = make_recorder();
         let system = System::new("test");
         let sub = recorder.start().recipient::<StreamShutdownMsg>();
-        let mut subject = StreamHandlerPool::new(vec![], false);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::supported(false),
+            false,
+            main_cryptde(),
+        );
         let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
         let local_addr = SocketAddr::from_str("127.0.0.1:0").unwrap();
         let sw_key = StreamWriterKey::from(peer_addr);
@@ -1299,7 +1750,12 @@ mod tests {
         let (recorder, _, recording_arc) = make_recorder();
         let system = System::new(test_name);
         let sub = recorder.start().recipient::<StreamShutdownMsg>();
-        let mut subject = StreamHandlerPool::new(vec![], false);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::supported(false),
+            false,
+            main_cryptde(),
+        );
         subject.logger = Logger::new(test_name);
         let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
         let local_addr = SocketAddr::from_str("127.0.0.1:0").unwrap();
@@ -1344,7 +1800,12 @@ mod tests {
         let (neighborhood, neighborhood_awaiter, neighborhood_recording_arc) = make_recorder();
         thread::spawn(move || {
             let system = System::new("when_stream_handler_pool_fails_to_create_nonexistent_stream_for_write_then_it_logs_and_notifies_neighborhood");
-            let mut subject = StreamHandlerPool::new(vec![], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![],
+                MasqueraderKind::supported(false),
+                false,
+                main_cryptde(),
+            );
             subject.stream_connector = Box::new(
                 StreamConnectorMock::new()
                     .connect_pair_result(Err(Error::from(ErrorKind::Other)))
@@ -1372,6 +1833,8 @@ mod tests {
                             &[7000],
                         )),
                         rate_pack(100),
+                        vec![],
+                        false,
                     )),
                     context: TransmitDataMsg {
                         endpoint: Endpoint::Key(public_key),
@@ -1419,7 +1882,12 @@ mod tests {
                 "stream_handler_pool_creates_nonexistent_stream_for_reading_and_writing",
             );
             let discriminator_factory = JsonDiscriminatorFactory::new();
-            let mut subject = StreamHandlerPool::new(vec![Box::new(discriminator_factory)], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![Box::new(discriminator_factory)],
+                MasqueraderKind::supported(false),
+                false,
+                main_cryptde(),
+            );
             subject.stream_connector = Box::new(
                 StreamConnectorMock::new().connect_pair_result(Ok(ConnectionInfo {
                     reader: Box::new(
@@ -1481,6 +1949,8 @@ mod tests {
                     public_key.clone(),
                     Some(NodeAddr::new(&target_ip_addr, &[7000])),
                     rate_pack(100),
+                    vec![],
+                    false,
                 )),
                 context: node_query_msg.context,
             })
@@ -1520,6 +1990,115 @@ mod tests {
         );
     }
 
+    #[test]
+    fn stream_handler_pool_opens_link_on_nonexistent_stream_to_neighbor_that_gossips_link_encryption(
+    ) {
+        use crossbeam_channel::unbounded;
+        let public_key = main_cryptde().public_key().clone();
+        let peer_addr = SocketAddr::from_str("1.2.3.5:7000").unwrap();
+        let mut peer_session = LinkSession::default();
+        let outgoing_unmasked = b"Outgoing data".to_vec();
+        let outgoing_masked_len = masked_data_len(outgoing_unmasked.len());
+        let (dispatcher, _, dispatcher_recording_arc) = make_recorder();
+        let (neighborhood, neighborhood_awaiter, neighborhood_recording_arc) = make_recorder();
+        let poll_write_params_arc = Arc::new(Mutex::new(vec![]));
+        let poll_write_params_arc_a = poll_write_params_arc.clone();
+        let (tx, rx) = unbounded();
+        thread::spawn(move || {
+            let system = System::new(
+                "stream_handler_pool_opens_link_on_nonexistent_stream_to_neighbor_that_gossips_link_encryption",
+            );
+            let discriminator_factory = JsonDiscriminatorFactory::new();
+            let mut subject = StreamHandlerPool::new(
+                vec![Box::new(discriminator_factory)],
+                MasqueraderKind::supported(false),
+                false,
+                main_cryptde(),
+            );
+            subject.stream_connector = Box::new(
+                StreamConnectorMock::new().connect_pair_result(Ok(ConnectionInfo {
+                    reader: Box::new(
+                        ReadHalfWrapperMock::new().poll_read_result(vec![], Ok(Async::NotReady)),
+                    ),
+                    writer: Box::new(
+                        WriteHalfWrapperMock::new()
+                            .poll_write_ok(masked_hello_len())
+                            .poll_write_ok(outgoing_masked_len)
+                            .poll_write_result(Ok(Async::NotReady))
+                            .poll_write_params(&poll_write_params_arc),
+                    ),
+                    local_addr: SocketAddr::from_str("127.0.0.1:54321").unwrap(),
+                    peer_addr,
+                })),
+            );
+            let subject_addr: Addr<StreamHandlerPool> = subject.start();
+            let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
+            let peer_actors = peer_actors_builder()
+                .dispatcher(dispatcher)
+                .neighborhood(neighborhood)
+                .build();
+            subject_subs
+                .bind
+                .try_send(PoolBindMessage {
+                    dispatcher_subs: peer_actors.dispatcher,
+                    stream_handler_pool_subs: subject_subs.clone(),
+                    neighborhood_subs: peer_actors.neighborhood,
+                })
+                .unwrap();
+
+            tx.send(subject_subs).unwrap();
+
+            system.run();
+        });
+
+        let subject_subs = rx.recv().unwrap();
+
+        subject_subs
+            .transmit_sub
+            .try_send(TransmitDataMsg {
+                endpoint: Endpoint::Key(public_key.clone()),
+                last_data: false,
+                sequence_number: None,
+                data: outgoing_unmasked.clone(),
+            })
+            .unwrap();
+
+        neighborhood_awaiter.await_message_count(1);
+        let node_query_msg =
+            Recording::get::<DispatcherNodeQueryMessage>(&neighborhood_recording_arc, 0);
+        subject_subs
+            .node_query_response
+            .try_send(DispatcherNodeQueryResponse {
+                result: Some(NodeQueryResponseMetadata::new(
+                    public_key.clone(),
+                    Some(NodeAddr::from(&peer_addr)),
+                    rate_pack(100),
+                    vec![],
+                    true,
+                )),
+                context: node_query_msg.context,
+            })
+            .unwrap();
+
+        await_messages(1, &poll_write_params_arc_a);
+        let reply = answer_hello(
+            &mut peer_session,
+            &poll_write_params_arc_a.lock().unwrap()[0],
+        );
+        subject_subs
+            .link_data_sub
+            .try_send(make_link_ibcd(peer_addr, reply.to_bytes()))
+            .unwrap();
+        await_messages(2, &poll_write_params_arc_a);
+        let poll_write_params = poll_write_params_arc_a.lock().unwrap();
+        assert_eq!(
+            open_link_data(&peer_session, &poll_write_params[1]),
+            outgoing_unmasked
+        );
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        assert_eq!(dispatcher_recording.len(), 0);
+    }
+
     #[test]
     fn transmit_data_msg_handler_finds_ip_from_neighborhood_and_transmits_message() {
         init_test_logging();
@@ -1539,7 +2118,12 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let subject = StreamHandlerPool::new(vec![], false);
+            let subject = StreamHandlerPool::new(
+                vec![],
+                MasqueraderKind::supported(false),
+                false,
+                main_cryptde(),
+            );
 
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -1598,6 +2182,8 @@ mod tests {
                         &[6789],
                     )),
                     rate_pack(100),
+                    vec![],
+                    false,
                 )),
                 context: node_query_msg.context,
             })
@@ -1620,7 +2206,12 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let subject = StreamHandlerPool::new(vec![], false);
+            let subject = StreamHandlerPool::new(
+                vec![],
+                MasqueraderKind::supported(false),
+                false,
+                main_cryptde(),
+            );
 
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -1668,7 +2259,12 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let subject = StreamHandlerPool::new(vec![], false);
+            let subject = StreamHandlerPool::new(
+                vec![],
+                MasqueraderKind::supported(false),
+                false,
+                main_cryptde(),
+            );
 
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -1689,6 +2285,8 @@ mod tests {
                         key.clone(),
                         None,
                         rate_pack(100),
+                        vec![],
+                        false,
                     )),
                     context: TransmitDataMsg {
                         endpoint: Endpoint::Key(key.clone()),
@@ -1732,7 +2330,12 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let mut subject = StreamHandlerPool::new(vec![], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![],
+                MasqueraderKind::supported(false),
+                false,
+                main_cryptde(),
+            );
             subject
                 .stream_writers
                 .insert(StreamWriterKey::from(peer_addr), None);
@@ -1755,6 +2358,8 @@ mod tests {
                         key.clone(),
                         Some(NodeAddr::new(&peer_addr.ip(), &[peer_addr.port()])),
                         rate_pack(100),
+                        vec![],
+                        false,
                     )),
                     context: msg,
                 })
@@ -1822,7 +2427,12 @@ mod tests {
         let sender_wrapper = SenderWrapperMock::new(peer_addr)
             .unbounded_send_params(&sender_wrapper_unbounded_send_params_arc)
             .unbounded_send_result(Err(send_error));
-        let mut subject = StreamHandlerPool::new(vec![], false);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::supported(false),
+            false,
+            main_cryptde(),
+        );
         subject
             .stream_writers
             .insert(sw_key, Some(Box::new(sender_wrapper)));
@@ -1831,6 +2441,8 @@ mod tests {
                 public_key: key,
                 node_addr_opt: Some(NodeAddr::new(&peer_addr.ip(), &[peer_addr.port()])),
                 rate_pack: ZERO_RATE_PACK.clone(),
+                masquerader_kinds: vec![],
+                link_encryption: false,
             }),
             context: TransmitDataMsg {
                 endpoint: Endpoint::Socket(peer_addr.clone()),
@@ -1852,6 +2464,139 @@ mod tests {
         );
     }
 
+    #[test]
+    fn clandestine_data_is_masked_with_masquerader_negotiated_with_neighbor() {
+        let key = PublicKey::new(&[1, 2, 3, 4]);
+        let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let sw_key = StreamWriterKey::from(peer_addr);
+        let unbounded_send_params_arc = Arc::new(Mutex::new(vec![]));
+        let sender_wrapper =
+            SenderWrapperMock::new(peer_addr).unbounded_send_params(&unbounded_send_params_arc);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::supported(false),
+            false,
+            main_cryptde(),
+        );
+        subject
+            .stream_writers
+            .insert(sw_key, Some(Box::new(sender_wrapper)));
+        let make_msg = |masquerader_kinds: Vec<MasqueraderKind>| DispatcherNodeQueryResponse {
+            result: Some(NodeQueryResponseMetadata::new(
+                key.clone(),
+                Some(NodeAddr::new(&peer_addr.ip(), &[peer_addr.port()])),
+                rate_pack(100),
+                masquerader_kinds,
+                false,
+            )),
+            context: TransmitDataMsg {
+                endpoint: Endpoint::Key(key.clone()),
+                last_data: false,
+                sequence_number: None,
+                data: b"booga".to_vec(),
+            },
+        };
+
+        subject.handle_dispatcher_node_query_response(make_msg(vec![
+            MasqueraderKind::Http,
+            MasqueraderKind::Json,
+        ]));
+        subject.outbound_stream_writer_keys.insert(sw_key);
+        subject.handle_dispatcher_node_query_response(make_msg(vec![
+            MasqueraderKind::Http,
+            MasqueraderKind::Json,
+        ]));
+        subject.handle_dispatcher_node_query_response(make_msg(vec![]));
+
+        let masked = unbounded_send_params_arc
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|packet: &SequencedPacket| packet.data.clone())
+            .collect::<Vec<Vec<u8>>>();
+        assert_eq!(
+            masked,
+            vec![
+                HttpMasquerader::new(HttpMasqueradeForm::Response)
+                    .mask(b"booga")
+                    .unwrap(),
+                HttpMasquerader::new(HttpMasqueradeForm::Request)
+                    .mask(b"booga")
+                    .unwrap(),
+                JsonMasquerader::new().mask(b"booga").unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn link_frames_are_masked_with_masquerader_negotiated_with_neighbor() {
+        let key = main_cryptde().public_key().clone();
+        let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let sw_key = StreamWriterKey::from(peer_addr);
+        let unbounded_send_params_arc = Arc::new(Mutex::new(vec![]));
+        let sender_wrapper =
+            SenderWrapperMock::new(peer_addr).unbounded_send_params(&unbounded_send_params_arc);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::supported(false),
+            false,
+            main_cryptde(),
+        );
+        subject
+            .stream_writers
+            .insert(sw_key, Some(Box::new(sender_wrapper)));
+        subject
+            .link_sessions
+            .insert(sw_key, established_link_session());
+        let make_msg = |masquerader_kinds: Vec<MasqueraderKind>| DispatcherNodeQueryResponse {
+            result: Some(NodeQueryResponseMetadata::new(
+                key.clone(),
+                Some(NodeAddr::new(&peer_addr.ip(), &[peer_addr.port()])),
+                rate_pack(100),
+                masquerader_kinds,
+                true,
+            )),
+            context: TransmitDataMsg {
+                endpoint: Endpoint::Key(key.clone()),
+                last_data: false,
+                sequence_number: None,
+                data: b"booga".to_vec(),
+            },
+        };
+
+        subject.handle_dispatcher_node_query_response(make_msg(vec![
+            MasqueraderKind::Http,
+            MasqueraderKind::Json,
+        ]));
+        subject.outbound_stream_writer_keys.insert(sw_key);
+        subject.handle_dispatcher_node_query_response(make_msg(vec![
+            MasqueraderKind::Http,
+            MasqueraderKind::Json,
+        ]));
+        subject.handle_dispatcher_node_query_response(make_msg(vec![]));
+
+        let masked = unbounded_send_params_arc
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|packet: &SequencedPacket| packet.data.clone())
+            .collect::<Vec<Vec<u8>>>();
+        assert_eq!(
+            masked,
+            vec![
+                HttpMasquerader::new(HttpMasqueradeForm::Response)
+                    .mask(&link_data_frame(b"booga"))
+                    .unwrap(),
+                HttpMasquerader::new(HttpMasqueradeForm::Request)
+                    .mask(&link_data_frame(b"booga"))
+                    .unwrap(),
+                JsonMasquerader::new()
+                    .mask(&link_data_frame(b"booga"))
+                    .unwrap(),
+            ]
+        );
+    }
+
     #[test]
     fn when_a_new_connection_fails_the_stream_writer_flag_is_removed_and_another_connection_is_attempted_for_the_next_message_with_the_same_stream_key(
     ) {
@@ -1895,7 +2640,12 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let mut subject = StreamHandlerPool::new(vec![], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![],
+                MasqueraderKind::supported(false),
+                false,
+                main_cryptde(),
+            );
             subject.stream_connector = Box::new(
                 StreamConnectorMock::new()
                     .connect_pair_result(Err(Error::from(ErrorKind::Other)))
@@ -1922,6 +2672,8 @@ mod tests {
                         key_bg,
                         Some(NodeAddr::new(&peer_addr.ip(), &[peer_addr.port()])),
                         rate_pack(100),
+                        vec![],
+                        false,
                     )),
                     context: msg,
                 })
@@ -1941,6 +2693,8 @@ mod tests {
                     cryptde.public_key().clone(),
                     Some(NodeAddr::new(&peer_addr.ip(), &[peer_addr.port()])),
                     rate_pack(100),
+                    vec![],
+                    false,
                 )),
                 context: msg_a,
             })
@@ -1975,7 +2729,12 @@ mod tests {
         let sender_wrapper = SenderWrapperMock::new(peer_addr)
             .unbounded_send_params(&sender_wrapper_unbounded_send_params_arc)
             .unbounded_send_result(Ok(()));
-        let mut subject = StreamHandlerPool::new(vec![], false);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::supported(false),
+            false,
+            main_cryptde(),
+        );
         subject.stream_writers.insert(
             StreamWriterKey::from(peer_addr),
             Some(Box::new(sender_wrapper)),
@@ -1986,6 +2745,8 @@ mod tests {
                 public_key: key,
                 node_addr_opt: Some(NodeAddr::new(&peer_addr.ip(), &[peer_addr.port()])),
                 rate_pack: ZERO_RATE_PACK.clone(),
+                masquerader_kinds: vec![],
+                link_encryption: false,
             }),
             context: TransmitDataMsg {
                 endpoint: Endpoint::Socket(peer_addr.clone()),
@@ -2035,7 +2796,12 @@ mod tests {
         };
 
         let system = System::new("test");
-        let subject = StreamHandlerPool::new(vec![], false);
+        let subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::supported(false),
+            false,
+            main_cryptde(),
+        );
         let subject_addr: Addr<StreamHandlerPool> = subject.start();
         let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
         let peer_actors = peer_actors_builder().build();
@@ -2055,6 +2821,8 @@ mod tests {
                     key.clone(),
                     Some(NodeAddr::new(&peer_addr.ip(), &[])),
                     rate_pack(100),
+                    vec![],
+                    false,
                 )),
                 context: msg,
             })
@@ -2083,7 +2851,12 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let subject = StreamHandlerPool::new(vec![], false);
+            let subject = StreamHandlerPool::new(
+                vec![],
+                MasqueraderKind::supported(false),
+                false,
+                main_cryptde(),
+            );
 
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -2156,7 +2929,12 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let mut subject = StreamHandlerPool::new(vec![], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![],
+                MasqueraderKind::supported(false),
+                false,
+                main_cryptde(),
+            );
             subject.traffic_analyzer = Box::new(TrafficAnalyzerMock {});
 
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
@@ -2206,6 +2984,48 @@ mod tests {
         TestLogHandler::new().await_log_containing("Masking failed for 1.2.3.5:6789: Low-level data error: don't care. Discarding 5 bytes.", 1000);
     }
 
+    #[test]
+    fn stream_handler_pool_drops_link_data_when_masking_fails() {
+        init_test_logging();
+        let peer_addr = SocketAddr::from_str("1.2.3.5:6789").unwrap();
+        let sw_key = StreamWriterKey::from(peer_addr);
+        let unbounded_send_params_arc = Arc::new(Mutex::new(vec![]));
+        let sender_wrapper =
+            SenderWrapperMock::new(peer_addr).unbounded_send_params(&unbounded_send_params_arc);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::supported(false),
+            false,
+            main_cryptde(),
+        );
+        subject.traffic_analyzer = Box::new(TrafficAnalyzerMock {});
+        subject
+            .stream_writers
+            .insert(sw_key, Some(Box::new(sender_wrapper)));
+        subject
+            .link_sessions
+            .insert(sw_key, established_link_session());
+
+        subject.handle_dispatcher_node_query_response(DispatcherNodeQueryResponse {
+            result: Some(NodeQueryResponseMetadata::new(
+                PublicKey::new(&[]),
+                Some(NodeAddr::from(&peer_addr)),
+                ZERO_RATE_PACK,
+                vec![],
+                false,
+            )),
+            context: TransmitDataMsg {
+                endpoint: Endpoint::Socket(peer_addr),
+                last_data: false,
+                sequence_number: None,
+                data: b"hello".to_vec(),
+            },
+        });
+
+        assert!(unbounded_send_params_arc.lock().unwrap().is_empty());
+        TestLogHandler::new().exists_log_containing("Masking failed for 1.2.3.5:6789: Low-level data error: don't care. Discarding 5 bytes.");
+    }
+
     #[test]
     fn stream_handler_pool_logs_error_and_returns_when_local_connection_is_gone() {
         init_test_logging();
@@ -2217,7 +3037,12 @@ mod tests {
                 "stream_handler_pool_creates_nonexistent_stream_for_reading_and_writing",
             );
             let discriminator_factory = JsonDiscriminatorFactory::new();
-            let mut subject = StreamHandlerPool::new(vec![Box::new(discriminator_factory)], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![Box::new(discriminator_factory)],
+                MasqueraderKind::supported(false),
+                false,
+                main_cryptde(),
+            );
             subject.stream_connector = Box::new(StreamConnectorMock::new()); // this will panic if a connection is attempted
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -2259,12 +3084,511 @@ mod tests {
         );
     }
 
+    fn make_link_ibcd(peer_addr: SocketAddr, data: Vec<u8>) -> InboundClientData {
+        InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr,
+            reception_port: Some(1234),
+            last_data: false,
+            is_clandestine: true,
+            sequence_number: None,
+            data,
+        }
+    }
+
+    fn make_clandestine_msg(
+        public_key: PublicKey,
+        peer_addr: SocketAddr,
+        link_encryption: bool,
+    ) -> DispatcherNodeQueryResponse {
+        DispatcherNodeQueryResponse {
+            result: Some(NodeQueryResponseMetadata::new(
+                public_key.clone(),
+                Some(NodeAddr::from(&peer_addr)),
+                rate_pack(100),
+                vec![],
+                link_encryption,
+            )),
+            context: TransmitDataMsg {
+                endpoint: Endpoint::Key(public_key),
+                last_data: false,
+                sequence_number: None,
+                data: b"hello".to_vec(),
+            },
+        }
+    }
+
+    #[test]
+    fn clandestine_data_waits_for_link_keys_before_it_is_sent() {
+        let system = System::new("clandestine_data_waits_for_link_keys_before_it_is_sent");
+        let (pool, _, pool_recording_arc) = make_recorder();
+        let peer_addr = SocketAddr::from_str("1.2.3.5:6789").unwrap();
+        let sw_key = StreamWriterKey::from(peer_addr);
+        let unbounded_send_params_arc = Arc::new(Mutex::new(vec![]));
+        let sender_wrapper =
+            SenderWrapperMock::new(peer_addr).unbounded_send_params(&unbounded_send_params_arc);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::supported(false),
+            false,
+            main_cryptde(),
+        );
+        subject.self_subs_opt = Some(make_stream_handler_pool_subs_from_recorder(&pool.start()));
+        subject
+            .stream_writers
+            .insert(sw_key, Some(Box::new(sender_wrapper)));
+        let mut session = LinkSession::new_initiator(main_cryptde().public_key().clone());
+        session.start_handshake(main_cryptde(), SystemTime::now());
+        subject.link_sessions.insert(sw_key, session);
+        let msg = make_clandestine_msg(main_cryptde().public_key().clone(), peer_addr, true);
+
+        subject.handle_dispatcher_node_query_response(msg.clone());
+
+        System::current().stop();
+        system.run();
+        assert!(unbounded_send_params_arc.lock().unwrap().is_empty());
+        let pool_recording = pool_recording_arc.lock().unwrap();
+        let scheduled =
+            pool_recording.get_record::<MessageScheduler<DispatcherNodeQueryResponse>>(0);
+        assert!(scheduled.scheduled_msg == msg);
+        assert_eq!(scheduled.delay, Duration::from_millis(100));
+    }
+
+    #[test]
+    fn clandestine_data_opens_link_on_stream_this_node_opened_to_neighbor_that_gossips_link_encryption(
+    ) {
+        let system = System::new(
+            "clandestine_data_opens_link_on_stream_this_node_opened_to_neighbor_that_gossips_link_encryption",
+        );
+        let (pool, _, pool_recording_arc) = make_recorder();
+        let peer_addr = SocketAddr::from_str("1.2.3.5:6789").unwrap();
+        let sw_key = StreamWriterKey::from(peer_addr);
+        let unbounded_send_params_arc = Arc::new(Mutex::new(vec![]));
+        let sender_wrapper =
+            SenderWrapperMock::new(peer_addr).unbounded_send_params(&unbounded_send_params_arc);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::supported(false),
+            false,
+            main_cryptde(),
+        );
+        subject.self_subs_opt = Some(make_stream_handler_pool_subs_from_recorder(&pool.start()));
+        subject
+            .stream_writers
+            .insert(sw_key, Some(Box::new(sender_wrapper)));
+        subject.outbound_stream_writer_keys.insert(sw_key);
+        let mut peer_session = LinkSession::default();
+        let msg = make_clandestine_msg(main_cryptde().public_key().clone(), peer_addr, true);
+
+        subject.handle_dispatcher_node_query_response(msg.clone());
+        let reply = answer_hello(
+            &mut peer_session,
+            &unbounded_send_params_arc.lock().unwrap()[0].data,
+        );
+        subject.handle_link_data(make_link_ibcd(peer_addr, reply.to_bytes()));
+        subject.handle_dispatcher_node_query_response(msg.clone());
+
+        System::current().stop();
+        system.run();
+        assert_eq!(subject.link_sessions[&sw_key].is_initiator(), true);
+        assert_eq!(subject.is_link_established(&sw_key), true);
+        let unbounded_send_params = unbounded_send_params_arc.lock().unwrap();
+        assert_eq!(unbounded_send_params.len(), 2);
+        assert_eq!(
+            open_link_data(&peer_session, &unbounded_send_params[1].data),
+            b"hello".to_vec()
+        );
+        let pool_recording = pool_recording_arc.lock().unwrap();
+        let scheduled =
+            pool_recording.get_record::<MessageScheduler<DispatcherNodeQueryResponse>>(0);
+        assert!(scheduled.scheduled_msg == msg);
+        assert_eq!(pool_recording.len(), 1);
+    }
+
+    #[test]
+    fn clandestine_data_is_sent_without_link_on_stream_this_node_did_not_open() {
+        let peer_addr = SocketAddr::from_str("1.2.3.5:6789").unwrap();
+        let sw_key = StreamWriterKey::from(peer_addr);
+        let unbounded_send_params_arc = Arc::new(Mutex::new(vec![]));
+        let sender_wrapper =
+            SenderWrapperMock::new(peer_addr).unbounded_send_params(&unbounded_send_params_arc);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::supported(false),
+            false,
+            main_cryptde(),
+        );
+        subject
+            .stream_writers
+            .insert(sw_key, Some(Box::new(sender_wrapper)));
+
+        subject.handle_dispatcher_node_query_response(make_clandestine_msg(
+            main_cryptde().public_key().clone(),
+            peer_addr,
+            true,
+        ));
+
+        assert!(subject.link_sessions.is_empty());
+        let unbounded_send_params = unbounded_send_params_arc.lock().unwrap();
+        assert_eq!(
+            unbounded_send_params[0].data,
+            JsonMasquerader::new().mask(b"hello").unwrap()
+        );
+        assert_eq!(unbounded_send_params.len(), 1);
+    }
+
+    #[test]
+    fn link_carries_no_data_for_nodes_other_than_the_one_it_was_keyed_with() {
+        init_test_logging();
+        let peer_addr = SocketAddr::from_str("1.2.3.7:6789").unwrap();
+        let sw_key = StreamWriterKey::from(peer_addr);
+        let unbounded_send_params_arc = Arc::new(Mutex::new(vec![]));
+        let sender_wrapper =
+            SenderWrapperMock::new(peer_addr).unbounded_send_params(&unbounded_send_params_arc);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::supported(false),
+            false,
+            main_cryptde(),
+        );
+        subject
+            .stream_writers
+            .insert(sw_key, Some(Box::new(sender_wrapper)));
+        subject
+            .link_sessions
+            .insert(sw_key, established_link_session());
+
+        subject.handle_dispatcher_node_query_response(make_clandestine_msg(
+            PublicKey::new(&[9, 9, 9, 9]),
+            peer_addr,
+            true,
+        ));
+
+        assert!(unbounded_send_params_arc.lock().unwrap().is_empty());
+        TestLogHandler::new().exists_log_containing(&format!(
+            "ERROR: Dispatcher: Link keys are shared with {}, not CQkJCQ for 1.2.3.7:6789. Discarding 5 bytes.",
+            main_cryptde().public_key()
+        ));
+    }
+
+    #[test]
+    fn link_data_is_decrypted_and_passed_to_dispatcher() {
+        let system = System::new("link_data_is_decrypted_and_passed_to_dispatcher");
+        let (dispatcher, _, dispatcher_recording_arc) = make_recorder();
+        let peer_addr = SocketAddr::from_str("1.2.3.5:6789").unwrap();
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::supported(false),
+            false,
+            main_cryptde(),
+        );
+        subject.dispatcher_subs_opt = Some(
+            peer_actors_builder()
+                .dispatcher(dispatcher)
+                .build()
+                .dispatcher,
+        );
+        subject
+            .link_sessions
+            .insert(StreamWriterKey::from(peer_addr), established_link_session());
+        let ibcd = make_link_ibcd(peer_addr, link_data_frame(b"booga"));
+
+        subject.handle_link_data(ibcd.clone());
+
+        System::current().stop();
+        system.run();
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        assert_eq!(
+            dispatcher_recording.get_record::<InboundClientData>(0),
+            &InboundClientData {
+                data: b"booga".to_vec(),
+                ..ibcd
+            }
+        );
+        assert_eq!(dispatcher_recording.len(), 1);
+    }
+
+    #[test]
+    fn clandestine_data_that_is_not_a_link_frame_is_passed_to_dispatcher_when_there_is_no_link() {
+        let system = System::new(
+            "clandestine_data_that_is_not_a_link_frame_is_passed_to_dispatcher_when_there_is_no_link",
+        );
+        let (dispatcher, _, dispatcher_recording_arc) = make_recorder();
+        let peer_addr = SocketAddr::from_str("1.2.3.5:6789").unwrap();
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::supported(false),
+            false,
+            main_cryptde(),
+        );
+        subject.dispatcher_subs_opt = Some(
+            peer_actors_builder()
+                .dispatcher(dispatcher)
+                .build()
+                .dispatcher,
+        );
+        let ibcd = make_link_ibcd(peer_addr, b"booga".to_vec());
+
+        subject.handle_link_data(ibcd.clone());
+
+        System::current().stop();
+        system.run();
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        assert_eq!(
+            dispatcher_recording.get_record::<InboundClientData>(0),
+            &ibcd
+        );
+        assert_eq!(dispatcher_recording.len(), 1);
+    }
+
+    #[test]
+    fn link_data_that_is_not_a_link_frame_is_discarded() {
+        init_test_logging();
+        let system = System::new("link_data_that_is_not_a_link_frame_is_discarded");
+        let (dispatcher, _, dispatcher_recording_arc) = make_recorder();
+        let peer_addr = SocketAddr::from_str("1.2.3.6:6789").unwrap();
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::supported(false),
+            false,
+            main_cryptde(),
+        );
+        subject.dispatcher_subs_opt = Some(
+            peer_actors_builder()
+                .dispatcher(dispatcher)
+                .build()
+                .dispatcher,
+        );
+        subject
+            .link_sessions
+            .insert(StreamWriterKey::from(peer_addr), established_link_session());
+
+        subject.handle_link_data(make_link_ibcd(peer_addr, b"booga".to_vec()));
+
+        System::current().stop();
+        system.run();
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        assert_eq!(dispatcher_recording.len(), 0);
+        TestLogHandler::new().exists_log_containing(
+            "WARN: Dispatcher: Discarding 5 bytes from 1.2.3.6:6789: Not a link frame",
+        );
+    }
+
+    #[test]
+    fn opening_hello_is_answered_and_establishes_the_link() {
+        let peer_addr = SocketAddr::from_str("1.2.3.5:6789").unwrap();
+        let sw_key = StreamWriterKey::from(peer_addr);
+        let unbounded_send_params_arc = Arc::new(Mutex::new(vec![]));
+        let sender_wrapper =
+            SenderWrapperMock::new(peer_addr).unbounded_send_params(&unbounded_send_params_arc);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::supported(false),
+            false,
+            main_cryptde(),
+        );
+        subject
+            .stream_writers
+            .insert(sw_key, Some(Box::new(sender_wrapper)));
+        let mut peer_session = LinkSession::new_initiator(main_cryptde().public_key().clone());
+        let hello = peer_session.start_handshake(main_cryptde(), SystemTime::now());
+
+        subject.handle_link_data(make_link_ibcd(peer_addr, hello.to_bytes()));
+
+        assert_eq!(subject.is_link_established(&sw_key), true);
+        assert_eq!(subject.link_sessions[&sw_key].is_initiator(), false);
+        assert_eq!(
+            subject.link_sessions[&sw_key].peer_key_opt(),
+            Some(main_cryptde().public_key())
+        );
+        let unbounded_send_params = unbounded_send_params_arc.lock().unwrap();
+        assert_eq!(unbounded_send_params.len(), 1);
+        match unmask_link_frame(&unbounded_send_params[0].data) {
+            LinkFrame::Hello(reply) => {
+                assert_eq!(reply.generation, 0);
+                assert_eq!(peer_session.receive_hello(main_cryptde(), &reply), Ok(None));
+            }
+            x => panic!("Expected Hello; got {:?}", x),
+        }
+        let frame = match peer_session.seal(main_cryptde(), b"booga").unwrap() {
+            LinkFrame::Data { generation, data } => (generation, data),
+            x => panic!("Expected Data; got {:?}", x),
+        };
+        assert_eq!(
+            subject.link_sessions[&sw_key].open(main_cryptde(), frame.0, &frame.1),
+            Ok(PlainData::new(b"booga"))
+        );
+    }
+
+    #[test]
+    fn hello_with_bad_signature_is_refused_and_leaves_no_link() {
+        init_test_logging();
+        let peer_addr = SocketAddr::from_str("1.2.3.8:6789").unwrap();
+        let sw_key = StreamWriterKey::from(peer_addr);
+        let unbounded_send_params_arc = Arc::new(Mutex::new(vec![]));
+        let sender_wrapper =
+            SenderWrapperMock::new(peer_addr).unbounded_send_params(&unbounded_send_params_arc);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::supported(false),
+            false,
+            main_cryptde(),
+        );
+        subject
+            .stream_writers
+            .insert(sw_key, Some(Box::new(sender_wrapper)));
+        let mut hello = match LinkSession::new_initiator(main_cryptde().public_key().clone())
+            .start_handshake(main_cryptde(), SystemTime::now())
+        {
+            LinkFrame::Hello(hello) => hello,
+            x => panic!("Expected Hello; got {:?}", x),
+        };
+        hello.generation = 1;
+
+        subject.handle_link_data(make_link_ibcd(
+            peer_addr,
+            LinkFrame::Hello(hello).to_bytes(),
+        ));
+
+        assert!(subject.link_sessions.is_empty());
+        assert!(unbounded_send_params_arc.lock().unwrap().is_empty());
+        TestLogHandler::new().exists_log_containing(
+            "WARN: Dispatcher: Link key exchange with 1.2.3.8:6789 failed: Bad signature on Hello for key generation 1",
+        );
+    }
+
+    #[test]
+    fn rekey_links_opens_the_next_key_generation_on_established_links_this_node_opened() {
+        let peer_addr = SocketAddr::from_str("1.2.3.5:6789").unwrap();
+        let sw_key = StreamWriterKey::from(peer_addr);
+        let unbounded_send_params_arc = Arc::new(Mutex::new(vec![]));
+        let sender_wrapper =
+            SenderWrapperMock::new(peer_addr).unbounded_send_params(&unbounded_send_params_arc);
+        let answering_addr = SocketAddr::from_str("1.2.3.6:6789").unwrap();
+        let answering_sw_key = StreamWriterKey::from(answering_addr);
+        let answering_send_params_arc = Arc::new(Mutex::new(vec![]));
+        let answering_sender_wrapper = SenderWrapperMock::new(answering_addr)
+            .unbounded_send_params(&answering_send_params_arc);
+        let unestablished_addr = SocketAddr::from_str("1.2.3.7:6789").unwrap();
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::supported(false),
+            false,
+            main_cryptde(),
+        );
+        subject
+            .stream_writers
+            .insert(sw_key, Some(Box::new(sender_wrapper)));
+        subject
+            .link_sessions
+            .insert(sw_key, established_link_session());
+        subject
+            .stream_writers
+            .insert(answering_sw_key, Some(Box::new(answering_sender_wrapper)));
+        subject.link_sessions.insert(
+            answering_sw_key,
+            LinkSession::new_established_for_test(
+                false,
+                main_cryptde().public_key().clone(),
+                0,
+                link_key(),
+                link_key(),
+            ),
+        );
+        subject.link_sessions.insert(
+            StreamWriterKey::from(unestablished_addr),
+            LinkSession::new_initiator(main_cryptde().public_key().clone()),
+        );
+
+        subject.rekey_links();
+
+        let unbounded_send_params = unbounded_send_params_arc.lock().unwrap();
+        assert_eq!(unbounded_send_params.len(), 1);
+        match unmask_link_frame(&unbounded_send_params[0].data) {
+            LinkFrame::Hello(LinkHello {
+                generation: 1,
+                reply_to_opt: None,
+                ..
+            }) => (),
+            x => panic!("Expected Hello for generation 1; got {:?}", x),
+        }
+        assert_eq!(subject.link_sessions[&sw_key].is_rekeying(), true);
+        assert_eq!(subject.is_link_established(&sw_key), true);
+        assert!(answering_send_params_arc.lock().unwrap().is_empty());
+        assert_eq!(
+            subject.link_sessions[&answering_sw_key].is_rekeying(),
+            false
+        );
+    }
+
+    #[test]
+    fn expire_link_handshakes_drops_links_that_never_got_keys_and_keeps_the_others() {
+        init_test_logging();
+        let unanswered_addr = SocketAddr::from_str("1.2.3.5:6789").unwrap();
+        let unanswered_sw_key = StreamWriterKey::from(unanswered_addr);
+        let rekeying_addr = SocketAddr::from_str("1.2.3.6:6789").unwrap();
+        let rekeying_sw_key = StreamWriterKey::from(rekeying_addr);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::supported(false),
+            false,
+            main_cryptde(),
+        );
+        subject.link_handshake_timeout = Duration::from_secs(0);
+        subject.stream_writers.insert(
+            unanswered_sw_key,
+            Some(Box::new(SenderWrapperMock::new(unanswered_addr))),
+        );
+        subject
+            .outbound_stream_writer_keys
+            .insert(unanswered_sw_key);
+        let mut unanswered_session =
+            LinkSession::new_initiator(main_cryptde().public_key().clone());
+        unanswered_session.start_handshake(main_cryptde(), SystemTime::now());
+        subject
+            .link_sessions
+            .insert(unanswered_sw_key, unanswered_session);
+        subject.stream_writers.insert(
+            rekeying_sw_key,
+            Some(Box::new(SenderWrapperMock::new(rekeying_addr))),
+        );
+        subject.outbound_stream_writer_keys.insert(rekeying_sw_key);
+        let mut rekeying_session = established_link_session();
+        rekeying_session.start_handshake(main_cryptde(), SystemTime::now());
+        subject
+            .link_sessions
+            .insert(rekeying_sw_key, rekeying_session);
+
+        subject.expire_link_handshakes();
+
+        assert!(!subject.link_sessions.contains_key(&unanswered_sw_key));
+        assert!(!subject.stream_writers.contains_key(&unanswered_sw_key));
+        assert!(!subject
+            .outbound_stream_writer_keys
+            .contains(&unanswered_sw_key));
+        assert_eq!(subject.is_link_established(&rekeying_sw_key), true);
+        assert_eq!(subject.link_sessions[&rekeying_sw_key].is_rekeying(), false);
+        assert!(subject.stream_writers.contains_key(&rekeying_sw_key));
+        let tlh = TestLogHandler::new();
+        tlh.exists_log_containing(
+            "WARN: Dispatcher: Link to 1.2.3.5:0 didn't complete its key exchange in time; dropping it",
+        );
+        tlh.exists_log_containing(
+            "WARN: Dispatcher: Link to 1.2.3.6:0 didn't answer rekey in time; keeping current keys",
+        );
+    }
+
     #[test]
     #[should_panic(
         expected = "panic message (processed with: node_lib::sub_lib::utils::crash_request_analyzer)"
     )]
     fn stream_handler_can_be_crashed_properly_but_not_improperly() {
-        let stream_handler_pool = StreamHandlerPool::new(vec![], true);
+        let stream_handler_pool = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::supported(false),
+            true,
+            main_cryptde(),
+        );
 
         prove_that_crash_request_handler_is_hooked_up(stream_handler_pool, CRASH_KEY);
     }
@@ -98,6 +98,8 @@ pub struct AccountantSubs {
     pub report_routing_service_provided: Recipient<ReportRoutingServiceProvidedMessage>,
     pub report_exit_service_provided: Recipient<ReportExitServiceProvidedMessage>,
     pub report_services_consumed: Recipient<ReportServicesConsumedMessage>,
+    pub report_cover_traffic: Recipient<ReportCoverTrafficMessage>,
+    pub prioritize_creditor: Recipient<PrioritizeCreditorMessage>,
     pub report_payable_payments_setup: Recipient<BlockchainAgentWithContextMessage>,
     pub report_inbound_payments: Recipient<ReceivedPayments>,
     pub init_pending_payable_fingerprints: Recipient<PendingPayableFingerprintSeeds>,
@@ -140,6 +142,19 @@ pub struct ReportExitServiceProvidedMessage {
     pub byte_rate: u64,
 }
 
+// Cover traffic is nobody's service: it's counted here, apart from the debts, and never charged
+#[derive(Clone, PartialEq, Eq, Debug, Message)]
+pub struct ReportCoverTrafficMessage {
+    pub bytes_sent: usize,
+    pub bytes_received: usize,
+}
+
+// Sent when a creditor refuses us service until we pay it
+#[derive(Clone, PartialEq, Eq, Debug, Message)]
+pub struct PrioritizeCreditorMessage {
+    pub wallet: Wallet,
+}
+
 #[derive(Clone, PartialEq, Eq, Debug, Message)]
 pub struct ReportServicesConsumedMessage {
     pub timestamp: SystemTime,
@@ -169,6 +184,12 @@ pub struct FinancialStatistics {
     pub total_paid_receivable_wei: u128,
 }
 
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct CoverTrafficStatistics {
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+}
+
 #[derive(PartialEq, Eq, Debug)]
 pub enum SignConversionError {
     U64(String),
@@ -11,7 +11,8 @@ use actix::Recipient;
 use masq_lib::blockchains::chains::Chain;
 use masq_lib::ui_gateway::NodeFromUiMessage;
 use std::fmt;
-use std::fmt::{Debug, Formatter};
+use std::fmt::{Debug, Display, Formatter};
+use thousands::Separable;
 use web3::types::U256;
 
 #[derive(Clone, PartialEq, Eq, Debug, Default)]
@@ -81,11 +82,135 @@ impl ConsumingWalletBalances {
     }
 }
 
+// Prices of a unit of gas in wei, as agreed upon for a batch of payments
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionFees {
+    Legacy {
+        gas_price_wei: u128,
+    },
+    Eip1559 {
+        max_fee_per_gas_wei: u128,
+        max_priority_fee_per_gas_wei: u128,
+    },
+}
+
+impl TransactionFees {
+    // The consuming wallet has to be prepared for the worst case
+    pub fn max_fee_per_gas_wei(&self) -> u128 {
+        match self {
+            TransactionFees::Legacy { gas_price_wei } => *gas_price_wei,
+            TransactionFees::Eip1559 {
+                max_fee_per_gas_wei,
+                ..
+            } => *max_fee_per_gas_wei,
+        }
+    }
+
+    // A transaction is let in over one pending under the same nonce only if it offers at least 10%
+    // more in every fee; we add an eighth, or as much as the market asks now if that is more
+    pub fn outbidding(&self, current_fees: TransactionFees) -> TransactionFees {
+        fn bump(wei: u128) -> u128 {
+            wei.saturating_add(wei.div_ceil(8))
+        }
+        match (*self, current_fees) {
+            (TransactionFees::Legacy { gas_price_wei }, current_fees) => TransactionFees::Legacy {
+                gas_price_wei: bump(gas_price_wei).max(current_fees.max_fee_per_gas_wei()),
+            },
+            (
+                TransactionFees::Eip1559 {
+                    max_fee_per_gas_wei,
+                    max_priority_fee_per_gas_wei,
+                },
+                TransactionFees::Eip1559 {
+                    max_fee_per_gas_wei: current_max_fee_per_gas_wei,
+                    max_priority_fee_per_gas_wei: current_max_priority_fee_per_gas_wei,
+                },
+            ) => {
+                let max_priority_fee_per_gas_wei =
+                    bump(max_priority_fee_per_gas_wei).max(current_max_priority_fee_per_gas_wei);
+                TransactionFees::Eip1559 {
+                    max_fee_per_gas_wei: bump(max_fee_per_gas_wei)
+                        .max(current_max_fee_per_gas_wei)
+                        .max(max_priority_fee_per_gas_wei),
+                    max_priority_fee_per_gas_wei,
+                }
+            }
+            (
+                TransactionFees::Eip1559 {
+                    max_fee_per_gas_wei,
+                    max_priority_fee_per_gas_wei,
+                },
+                TransactionFees::Legacy { .. },
+            ) => TransactionFees::Eip1559 {
+                max_fee_per_gas_wei: bump(max_fee_per_gas_wei),
+                max_priority_fee_per_gas_wei: bump(max_priority_fee_per_gas_wei),
+            },
+        }
+    }
+
+    // The database keeps "<gas price>" or "<max fee per gas>|<max priority fee per gas>", in wei
+    pub fn to_config_value(&self) -> String {
+        match self {
+            TransactionFees::Legacy { gas_price_wei } => gas_price_wei.to_string(),
+            TransactionFees::Eip1559 {
+                max_fee_per_gas_wei,
+                max_priority_fee_per_gas_wei,
+            } => format!("{}|{}", max_fee_per_gas_wei, max_priority_fee_per_gas_wei),
+        }
+    }
+}
+
+impl TryFrom<&str> for TransactionFees {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let parse = |number: &str| {
+            number
+                .parse::<u128>()
+                .map_err(|e| format!("Transaction fees '{}' are malformed: {}", value, e))
+        };
+        match value.split('|').collect::<Vec<&str>>().as_slice() {
+            [gas_price] => Ok(TransactionFees::Legacy {
+                gas_price_wei: parse(gas_price)?,
+            }),
+            [max_fee_per_gas, max_priority_fee_per_gas] => Ok(TransactionFees::Eip1559 {
+                max_fee_per_gas_wei: parse(max_fee_per_gas)?,
+                max_priority_fee_per_gas_wei: parse(max_priority_fee_per_gas)?,
+            }),
+            _ => Err(format!(
+                "Transaction fees '{}' must have one or two parts",
+                value
+            )),
+        }
+    }
+}
+
+impl Display for TransactionFees {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TransactionFees::Legacy { gas_price_wei } => {
+                write!(f, "gas price {} wei", gas_price_wei.separate_with_commas())
+            }
+            TransactionFees::Eip1559 {
+                max_fee_per_gas_wei,
+                max_priority_fee_per_gas_wei,
+            } => write!(
+                f,
+                "max fee per gas {} wei, max priority fee per gas {} wei",
+                max_fee_per_gas_wei.separate_with_commas(),
+                max_priority_fee_per_gas_wei.separate_with_commas()
+            ),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::accountant::test_utils::PendingPayableDaoMock;
     use crate::actor_system_factory::SubsFactory;
     use crate::blockchain::blockchain_bridge::{BlockchainBridge, BlockchainBridgeSubsFactoryReal};
     use crate::blockchain::test_utils::BlockchainInterfaceMock;
+    use crate::sub_lib::blockchain_bridge::TransactionFees;
     use crate::test_utils::persistent_configuration_mock::PersistentConfigurationMock;
     use crate::test_utils::recorder::{make_blockchain_bridge_subs_from_recorder, Recorder};
     use actix::Actor;
@@ -107,6 +232,7 @@ mod tests {
         let accountant = BlockchainBridge::new(
             Box::new(blockchain_interface),
             Box::new(persistent_config),
+            Box::new(PendingPayableDaoMock::new()),
             false,
         );
         let addr = accountant.start();
@@ -115,4 +241,128 @@ mod tests {
 
         assert_eq!(subs, BlockchainBridge::make_subs_from(&addr))
     }
+
+    #[test]
+    fn transaction_fees_know_the_most_a_unit_of_gas_can_cost() {
+        let legacy = TransactionFees::Legacy {
+            gas_price_wei: 123_000_000_000,
+        };
+        let eip1559 = TransactionFees::Eip1559 {
+            max_fee_per_gas_wei: 2_500_000_000,
+            max_priority_fee_per_gas_wei: 100_000_000,
+        };
+
+        assert_eq!(legacy.max_fee_per_gas_wei(), 123_000_000_000);
+        assert_eq!(eip1559.max_fee_per_gas_wei(), 2_500_000_000);
+    }
+
+    #[test]
+    fn transaction_fees_outbid_by_an_eighth_unless_the_market_asks_for_more() {
+        let legacy = TransactionFees::Legacy {
+            gas_price_wei: 1_000_000_001,
+        };
+        let eip1559 = TransactionFees::Eip1559 {
+            max_fee_per_gas_wei: 2_400_000_000,
+            max_priority_fee_per_gas_wei: 100_000_000,
+        };
+        let calm_market = TransactionFees::Eip1559 {
+            max_fee_per_gas_wei: 1_000_000_000,
+            max_priority_fee_per_gas_wei: 50_000_000,
+        };
+        let busy_market = TransactionFees::Eip1559 {
+            max_fee_per_gas_wei: 4_000_000_000,
+            max_priority_fee_per_gas_wei: 300_000_000,
+        };
+        let legacy_market = TransactionFees::Legacy {
+            gas_price_wei: 9_000_000_000,
+        };
+
+        assert_eq!(
+            legacy.outbidding(calm_market),
+            TransactionFees::Legacy {
+                gas_price_wei: 1_125_000_002
+            }
+        );
+        assert_eq!(
+            legacy.outbidding(busy_market),
+            TransactionFees::Legacy {
+                gas_price_wei: 4_000_000_000
+            }
+        );
+        assert_eq!(
+            eip1559.outbidding(calm_market),
+            TransactionFees::Eip1559 {
+                max_fee_per_gas_wei: 2_700_000_000,
+                max_priority_fee_per_gas_wei: 112_500_000,
+            }
+        );
+        assert_eq!(
+            eip1559.outbidding(busy_market),
+            TransactionFees::Eip1559 {
+                max_fee_per_gas_wei: 4_000_000_000,
+                max_priority_fee_per_gas_wei: 300_000_000,
+            }
+        );
+        assert_eq!(
+            eip1559.outbidding(legacy_market),
+            TransactionFees::Eip1559 {
+                max_fee_per_gas_wei: 2_700_000_000,
+                max_priority_fee_per_gas_wei: 112_500_000,
+            }
+        );
+    }
+
+    #[test]
+    fn transaction_fees_are_displayed_properly() {
+        let legacy = TransactionFees::Legacy {
+            gas_price_wei: 123_000_000_000,
+        };
+        let eip1559 = TransactionFees::Eip1559 {
+            max_fee_per_gas_wei: 2_500_000_000,
+            max_priority_fee_per_gas_wei: 100_000_000,
+        };
+
+        assert_eq!(legacy.to_string(), "gas price 123,000,000,000 wei");
+        assert_eq!(
+            eip1559.to_string(),
+            "max fee per gas 2,500,000,000 wei, max priority fee per gas 100,000,000 wei"
+        );
+    }
+
+    #[test]
+    fn transaction_fees_round_trip_through_their_config_value() {
+        let legacy = TransactionFees::Legacy {
+            gas_price_wei: 123_000_000_000,
+        };
+        let eip1559 = TransactionFees::Eip1559 {
+            max_fee_per_gas_wei: 2_500_000_000,
+            max_priority_fee_per_gas_wei: 100_000_000,
+        };
+
+        let legacy_value = legacy.to_config_value();
+        let eip1559_value = eip1559.to_config_value();
+
+        assert_eq!(legacy_value, "123000000000");
+        assert_eq!(eip1559_value, "2500000000|100000000");
+        assert_eq!(TransactionFees::try_from(legacy_value.as_str()), Ok(legacy));
+        assert_eq!(
+            TransactionFees::try_from(eip1559_value.as_str()),
+            Ok(eip1559)
+        );
+    }
+
+    #[test]
+    fn transaction_fees_reject_malformed_config_values() {
+        assert_eq!(
+            TransactionFees::try_from("12|booga"),
+            Err(
+                "Transaction fees '12|booga' are malformed: invalid digit found in string"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            TransactionFees::try_from("1|2|3"),
+            Err("Transaction fees '1|2|3' must have one or two parts".to_string())
+        );
+    }
 }
@@ -110,6 +110,23 @@ impl fmt::Display for PublicKey {
     }
 }
 
+// Accepts the standard alphabet used by Display as well as the URL-safe one used in descriptors.
+impl FromStr for PublicKey {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let trimmed = value.trim().trim_end_matches('=');
+        let data = base64::decode_config(trimmed, base64::STANDARD_NO_PAD)
+            .or_else(|_| base64::decode_config(trimmed, base64::URL_SAFE_NO_PAD))
+            .map_err(|_| format!("Bad public key: '{}'", value))?;
+        if data.is_empty() {
+            Err(format!("Bad public key: '{}'", value))
+        } else {
+            Ok(PublicKey::from(data))
+        }
+    }
+}
+
 impl fmt::Debug for PublicKey {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         write!(
@@ -541,6 +558,8 @@ pub trait CryptDE: Send + Sync {
     fn public_key(&self) -> &PublicKey;
     // This is dup instead of clone because making a trait Clone has unpleasant consequences.
     fn dup(&self) -> Box<dyn CryptDE>;
+    // A CryptDE of the same kind, for the same chain, with freshly generated keys.
+    fn gen_successor(&self) -> Box<dyn CryptDE>;
     fn sign(&self, data: &PlainData) -> Result<CryptData, CryptdecError>;
     fn verify_signature(
         &self,
@@ -621,6 +640,56 @@ where
     }
 }
 
+// Plaintext sizes that padded packages are rounded up to before encryption, so that the size of
+// the ciphertext says little about the size of what's inside. Anything bigger than the largest
+// bucket is rounded up to a multiple of it.
+pub const PADDING_BUCKETS: [usize; 4] = [1_024, 4_096, 16_384, 65_536];
+
+pub fn padded_len(len: usize) -> usize {
+    match PADDING_BUCKETS.iter().find(|bucket| **bucket >= len) {
+        Some(bucket) => *bucket,
+        None => {
+            let largest = PADDING_BUCKETS[PADDING_BUCKETS.len() - 1];
+            len.div_ceil(largest) * largest
+        }
+    }
+}
+
+pub fn encodex_padded<T>(
+    cryptde: &dyn CryptDE,
+    public_key: &PublicKey,
+    item: &T,
+) -> Result<CryptData, CodexError>
+where
+    T: Serialize,
+{
+    let mut serialized = match serde_cbor::ser::to_vec(item) {
+        Ok(s) => s,
+        Err(e) => return Err(CodexError::SerializationError(SerdeCborError::new(e))),
+    };
+    serialized.resize(padded_len(serialized.len()), 0);
+    match cryptde.encode(public_key, &PlainData::from(serialized)) {
+        Ok(c) => Ok(c),
+        Err(e) => Err(CodexError::EncryptionError(e)),
+    }
+}
+
+// Like decodex, but ignores whatever follows the item, so it reads padded and unpadded data alike.
+pub fn decodex_padded<T>(cryptde: &dyn CryptDE, data: &CryptData) -> Result<T, CodexError>
+where
+    for<'de> T: Deserialize<'de>,
+{
+    let decrypted = match cryptde.decode(data) {
+        Ok(d) => d,
+        Err(e) => return Err(CodexError::DecryptionError(e)),
+    };
+    let mut deserializer = serde_cbor::de::Deserializer::from_slice(decrypted.as_slice());
+    match T::deserialize(&mut deserializer) {
+        Ok(t) => Ok(t),
+        Err(e) => Err(CodexError::DeserializationError(SerdeCborError::new(e))),
+    }
+}
+
 pub fn create_digest(msg: &dyn AsRef<[u8]>, address: &dyn AsRef<[u8]>) -> [u8; 32] {
     [msg.as_ref(), address.as_ref()].concat().keccak256()
 }
@@ -990,6 +1059,26 @@ mod tests {
         assert_eq!(result, String::from ("Tm93IGlzIHRoZSB0aW1lIGZvciBhbGwgZ29vZCBtZW4 0x4E6F77206973207468652074696D6520666F7220616C6C20676F6F64206D656E"));
     }
 
+    #[test]
+    fn public_key_is_parsed_from_either_base64_alphabet() {
+        let subject = PublicKey::new(&[0xFB, 0xFF, 0x01, 0x02]);
+
+        assert_eq!(
+            PublicKey::from_str(&subject.to_string()),
+            Ok(subject.clone())
+        );
+        assert_eq!(PublicKey::from_str(" -_8BAg "), Ok(subject.clone()));
+        assert_eq!(PublicKey::from_str("+/8BAg=="), Ok(subject));
+        assert_eq!(
+            PublicKey::from_str("not a key"),
+            Err("Bad public key: 'not a key'".to_string())
+        );
+        assert_eq!(
+            PublicKey::from_str(""),
+            Err("Bad public key: ''".to_string())
+        );
+    }
+
     #[derive(PartialEq, Eq, Debug, Serialize, Deserialize)]
     struct TestStruct {
         string: String,
@@ -1070,6 +1159,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn padded_len_rounds_up_to_the_next_bucket() {
+        assert_eq!(padded_len(0), 1_024);
+        assert_eq!(padded_len(1), 1_024);
+        assert_eq!(padded_len(1_024), 1_024);
+        assert_eq!(padded_len(1_025), 4_096);
+        assert_eq!(padded_len(16_385), 65_536);
+        assert_eq!(padded_len(65_536), 65_536);
+        assert_eq!(padded_len(65_537), 131_072);
+        assert_eq!(padded_len(200_000), 262_144);
+    }
+
+    #[test]
+    fn encodex_padded_pads_the_plaintext_to_a_bucket() {
+        let cryptde = main_cryptde();
+        let start = TestStruct::make();
+        let unpadded_len = serde_cbor::ser::to_vec(&start).unwrap().len();
+
+        let intermediate = encodex_padded(cryptde, &cryptde.public_key(), &start).unwrap();
+
+        let decrypted = cryptde.decode(&intermediate).unwrap();
+        assert_eq!(decrypted.len(), PADDING_BUCKETS[0]);
+        assert!(decrypted.as_slice()[unpadded_len..]
+            .iter()
+            .all(|byte| *byte == 0));
+    }
+
+    #[test]
+    fn encodex_padded_and_decodex_padded_communicate() {
+        let cryptde = main_cryptde();
+        let start = TestStruct::make();
+
+        let intermediate = encodex_padded(cryptde, &cryptde.public_key(), &start).unwrap();
+        let end = decodex_padded::<TestStruct>(cryptde, &intermediate).unwrap();
+
+        assert_eq!(end, start);
+    }
+
+    #[test]
+    fn decodex_padded_reads_unpadded_data_too() {
+        let cryptde = main_cryptde();
+        let start = TestStruct::make();
+
+        let intermediate = encodex(cryptde, &cryptde.public_key(), &start).unwrap();
+        let end = decodex_padded::<TestStruct>(cryptde, &intermediate).unwrap();
+
+        assert_eq!(end, start);
+    }
+
+    #[test]
+    fn decodex_cannot_read_padded_data() {
+        let cryptde = main_cryptde();
+        let start = TestStruct::make();
+
+        let intermediate = encodex_padded(cryptde, &cryptde.public_key(), &start).unwrap();
+        let result = decodex::<TestStruct>(cryptde, &intermediate);
+
+        match result {
+            Err(CodexError::DeserializationError(_)) => (),
+            x => panic!("Expected DeserializationError; got {:?}", x),
+        }
+    }
+
     #[derive(PartialEq, Eq, Debug)]
     struct BadSerStruct {
         flag: bool,
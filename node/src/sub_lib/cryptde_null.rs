@@ -17,6 +17,7 @@ use std::sync::{Arc, Mutex};
 #[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone)]
 pub struct CryptDENull {
+    chain: Chain,
     private_key: PrivateKey,
     public_key: PublicKey,
     digest: [u8; 32],
@@ -82,6 +83,7 @@ impl CryptDE for CryptDENull {
     // This is dup instead of clone because it returns a Box<CryptDE> instead of a CryptDENull.
     fn dup(&self) -> Box<dyn CryptDE> {
         Box::new(CryptDENull {
+            chain: self.chain,
             private_key: self.private_key.clone(),
             public_key: self.public_key.clone(),
             digest: self.digest,
@@ -89,6 +91,10 @@ impl CryptDE for CryptDENull {
         })
     }
 
+    fn gen_successor(&self) -> Box<dyn CryptDE> {
+        Box::new(CryptDENull::new(self.chain))
+    }
+
     fn sign(&self, data: &PlainData) -> Result<CryptData, CryptdecError> {
         let hash = self.hash(data);
         Self::encode_with_key_data(
@@ -173,6 +179,7 @@ impl CryptDENull {
         let public_key = Self::public_from_private(&private_key);
         let digest = cryptde::create_digest(&public_key, &chain.rec().contract);
         Self {
+            chain,
             private_key,
             public_key,
             digest,
@@ -187,6 +194,7 @@ impl CryptDENull {
     }
 
     pub fn set_key_pair(&mut self, public_key: &PublicKey, chain: Chain) {
+        self.chain = chain;
         self.public_key = public_key.clone();
         self.private_key = CryptDENull::private_from_public(public_key);
         self.digest = cryptde::create_digest(public_key, &chain.rec().contract);
@@ -584,6 +592,19 @@ mod tests {
         assert_eq!(result.private_key(), subject.private_key());
     }
 
+    #[test]
+    fn gen_successor_produces_different_keys_for_the_same_chain() {
+        let subject = CryptDENull::from(&PublicKey::new(&[1, 2, 3, 4]), TEST_DEFAULT_CHAIN);
+
+        let result = subject.gen_successor();
+
+        assert_ne!(result.public_key(), subject.public_key());
+        assert_eq!(
+            result.digest(),
+            cryptde::create_digest(result.public_key(), &TEST_DEFAULT_CHAIN.rec().contract)
+        );
+    }
+
     #[test]
     fn stringifies_public_key_properly() {
         let subject = main_cryptde();
@@ -25,6 +25,8 @@ lazy_static! {
 
 #[allow(clippy::upper_case_acronyms)]
 pub struct CryptDEReal {
+    chain: Chain,
+    private_key: PrivateKey,
     public_key: PublicKey,
     encryption_secret_key: encryption::SecretKey,
     signing_secret_key: signing::SecretKey,
@@ -97,9 +99,9 @@ impl CryptDE for CryptDEReal {
         randombytes_into(dest);
     }
 
+    // The encryption secret key followed by the signing secret key; see from_private_key().
     fn private_key(&self) -> &PrivateKey {
-        // Hypothesis: private_key() is unused and unnecessary in production code. Consider making it #[cfg(test)].
-        unimplemented!()
+        &self.private_key
     }
 
     fn public_key(&self) -> &PublicKey {
@@ -108,6 +110,8 @@ impl CryptDE for CryptDEReal {
 
     fn dup(&self) -> Box<dyn CryptDE> {
         Box::new(CryptDEReal {
+            chain: self.chain,
+            private_key: self.private_key.clone(),
             public_key: self.public_key.clone(),
             encryption_secret_key: encryption::SecretKey(self.encryption_secret_key.0),
             signing_secret_key: signing::SecretKey(self.signing_secret_key.0),
@@ -116,6 +120,10 @@ impl CryptDE for CryptDEReal {
         })
     }
 
+    fn gen_successor(&self) -> Box<dyn CryptDE> {
+        Box::new(CryptDEReal::new(self.chain))
+    }
+
     fn sign(&self, data: &PlainData) -> Result<CryptData, CryptdecError> {
         let data_to_sign = [data.as_slice(), &self.pre_shared_data[..]].concat();
         Ok(CryptData::new(
@@ -191,16 +199,45 @@ impl CryptDE for CryptDEReal {
 
 impl CryptDEReal {
     pub fn new(chain: Chain) -> Self {
-        let (e_public, e_secret) = encryption::gen_keypair();
-        let (s_public, s_secret) = signing::gen_keypair();
-        let public_key = Self::local_public_key_from(&e_public, &s_public);
+        let (_, e_secret) = encryption::gen_keypair();
+        let (_, s_secret) = signing::gen_keypair();
+        Self::from_secret_keys(e_secret, s_secret, chain)
+    }
+
+    pub fn from_private_key(private_key: &PrivateKey, chain: Chain) -> Result<Self, String> {
+        if private_key.len() != encryption::SECRETKEYBYTES + signing::SECRETKEYBYTES {
+            return Err(format!(
+                "Private key must be {} bytes long, not {}",
+                encryption::SECRETKEYBYTES + signing::SECRETKEYBYTES,
+                private_key.len()
+            ));
+        }
+        let (e_part, s_part) = private_key.as_slice().split_at(encryption::SECRETKEYBYTES);
+        let e_secret = encryption::SecretKey::from_slice(e_part).expect("Length was checked");
+        let s_secret = signing::SecretKey::from_slice(s_part).expect("Length was checked");
+        Ok(Self::from_secret_keys(e_secret, s_secret, chain))
+    }
+
+    fn from_secret_keys(
+        encryption_secret_key: encryption::SecretKey,
+        signing_secret_key: signing::SecretKey,
+        chain: Chain,
+    ) -> Self {
+        let public_key = Self::local_public_key_from(
+            &encryption_secret_key.public_key(),
+            &signing_secret_key.public_key(),
+        );
+        let private_key =
+            PrivateKey::from([&encryption_secret_key.0[..], &signing_secret_key.0[..]].concat());
         let digest = cryptde::create_digest(&public_key, &chain.rec().contract);
         let pre_shared_data = chain.rec().contract.0;
 
         Self {
+            chain,
+            private_key,
             public_key,
-            encryption_secret_key: e_secret,
-            signing_secret_key: s_secret,
+            encryption_secret_key,
+            signing_secret_key,
             digest,
             pre_shared_data,
         }
@@ -261,6 +298,47 @@ mod tests {
         assert_eq!(subject.public_key(), dup.public_key());
     }
 
+    #[test]
+    fn private_key_restores_identical_keys() {
+        let subject = CryptDEReal::default();
+        let data = PlainData::new(b"These are the times that try men's souls");
+
+        let result =
+            CryptDEReal::from_private_key(subject.private_key(), TEST_DEFAULT_CHAIN).unwrap();
+
+        assert_eq!(result.public_key(), subject.public_key());
+        assert_eq!(result.private_key(), subject.private_key());
+        assert_eq!(result.digest(), subject.digest());
+        let encoded = subject.encode(subject.public_key(), &data).unwrap();
+        assert_eq!(result.decode(&encoded).unwrap(), data);
+        let signature = result.sign(&data).unwrap();
+        assert!(subject.verify_signature(&data, &signature, subject.public_key()));
+    }
+
+    #[test]
+    fn from_private_key_rejects_key_of_wrong_length() {
+        let result =
+            CryptDEReal::from_private_key(&PrivateKey::new(&[1, 2, 3, 4]), TEST_DEFAULT_CHAIN);
+
+        assert_eq!(
+            result.err(),
+            Some("Private key must be 96 bytes long, not 4".to_string())
+        );
+    }
+
+    #[test]
+    fn gen_successor_produces_different_keys() {
+        let subject = CryptDEReal::default();
+
+        let result = subject.gen_successor();
+
+        assert_ne!(result.public_key(), subject.public_key());
+        assert_ne!(result.private_key(), subject.private_key());
+        let data = PlainData::new(b"booga");
+        let encoded = subject.encode(result.public_key(), &data).unwrap();
+        assert_eq!(result.decode(&encoded).unwrap(), data);
+    }
+
     #[test]
     fn random_produces_different_fields_of_data() {
         let subject = CryptDEReal::default();
@@ -0,0 +1,262 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::sub_lib::cryptde::PublicKey;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+pub const STRICT_EXIT_LOCATION_PREFIX: &str = "strict:";
+pub const PREFERRED_EXIT_LOCATION_PREFIX: &str = "preferred:";
+
+// Country codes are ISO 3166-1 alpha-2, self-declared by the exit operator and not verified.
+pub fn normalize_country_code(country_code: &str) -> Result<String, String> {
+    let trimmed = country_code.trim();
+    if trimmed.len() == 2 && trimmed.chars().all(|c| c.is_ascii_alphabetic()) {
+        Ok(trimmed.to_ascii_uppercase())
+    } else {
+        Err(format!("Bad country code: '{}'", country_code))
+    }
+}
+
+// A consumer's wishes about where its traffic leaves the MASQ Network. A strict location
+// refuses every exit outside the listed countries; a preferred one only makes them less desirable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExitLocation {
+    pub country_codes: Vec<String>,
+    pub strict: bool,
+}
+
+impl Display for ExitLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            if self.strict {
+                STRICT_EXIT_LOCATION_PREFIX
+            } else {
+                PREFERRED_EXIT_LOCATION_PREFIX
+            },
+            self.country_codes.join(",")
+        )
+    }
+}
+
+impl FromStr for ExitLocation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (strict, country_codes_str) =
+            if let Some(rest) = s.strip_prefix(STRICT_EXIT_LOCATION_PREFIX) {
+                (true, rest)
+            } else if let Some(rest) = s.strip_prefix(PREFERRED_EXIT_LOCATION_PREFIX) {
+                (false, rest)
+            } else {
+                (false, s)
+            };
+        let country_codes = country_codes_str
+            .split(',')
+            .map(normalize_country_code)
+            .collect::<Result<Vec<String>, String>>()?;
+        Ok(ExitLocation {
+            country_codes,
+            strict,
+        })
+    }
+}
+
+impl ExitLocation {
+    pub fn admits(&self, country_code_opt: Option<&str>) -> bool {
+        match country_code_opt {
+            Some(country_code) => self.country_codes.iter().any(|code| code == country_code),
+            None => false,
+        }
+    }
+}
+
+// Exit Nodes a consumer has pinned by public key. Strict pins refuse every other exit;
+// preferred ones are chosen whenever a route through them can be built.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PreferredExits {
+    pub public_keys: Vec<PublicKey>,
+    pub strict: bool,
+}
+
+impl Display for PreferredExits {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            if self.strict {
+                STRICT_EXIT_LOCATION_PREFIX
+            } else {
+                PREFERRED_EXIT_LOCATION_PREFIX
+            },
+            self.public_keys
+                .iter()
+                .map(|key| key.to_string())
+                .collect::<Vec<String>>()
+                .join(",")
+        )
+    }
+}
+
+impl FromStr for PreferredExits {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (strict, public_keys_str) =
+            if let Some(rest) = s.strip_prefix(STRICT_EXIT_LOCATION_PREFIX) {
+                (true, rest)
+            } else if let Some(rest) = s.strip_prefix(PREFERRED_EXIT_LOCATION_PREFIX) {
+                (false, rest)
+            } else {
+                (false, s)
+            };
+        let public_keys = public_keys_str
+            .split(',')
+            .map(PublicKey::from_str)
+            .collect::<Result<Vec<PublicKey>, String>>()?;
+        Ok(PreferredExits {
+            public_keys,
+            strict,
+        })
+    }
+}
+
+impl PreferredExits {
+    pub fn admits(&self, public_key: &PublicKey) -> bool {
+        self.public_keys.contains(public_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(STRICT_EXIT_LOCATION_PREFIX, "strict:");
+        assert_eq!(PREFERRED_EXIT_LOCATION_PREFIX, "preferred:");
+    }
+
+    #[test]
+    fn country_codes_are_normalized_or_rejected() {
+        assert_eq!(normalize_country_code(" cz "), Ok("CZ".to_string()));
+        assert_eq!(normalize_country_code("Us"), Ok("US".to_string()));
+        vec!["", "C", "CZE", "C1", "ČZ"]
+            .into_iter()
+            .for_each(|input| {
+                assert_eq!(
+                    normalize_country_code(input),
+                    Err(format!("Bad country code: '{}'", input))
+                )
+            });
+    }
+
+    #[test]
+    fn exit_locations_are_parsed_and_displayed() {
+        vec![
+            ("cz,sk", vec!["CZ", "SK"], false, "preferred:CZ,SK"),
+            ("preferred:DE", vec!["DE"], false, "preferred:DE"),
+            ("strict:ch, is", vec!["CH", "IS"], true, "strict:CH,IS"),
+        ]
+        .into_iter()
+        .for_each(|(input, country_codes, strict, expected_display)| {
+            let exit_location = ExitLocation::from_str(input).unwrap();
+
+            assert_eq!(
+                exit_location,
+                ExitLocation {
+                    country_codes: country_codes.into_iter().map(|s| s.to_string()).collect(),
+                    strict,
+                },
+                "{}",
+                input
+            );
+            assert_eq!(exit_location.to_string(), expected_display, "{}", input);
+        })
+    }
+
+    #[test]
+    fn bad_exit_locations_are_rejected() {
+        assert_eq!(
+            ExitLocation::from_str("strict:"),
+            Err("Bad country code: ''".to_string())
+        );
+        assert_eq!(
+            ExitLocation::from_str("CZ,Czechia"),
+            Err("Bad country code: 'Czechia'".to_string())
+        );
+        assert_eq!(
+            ExitLocation::from_str("sometimes:CZ"),
+            Err("Bad country code: 'sometimes:CZ'".to_string())
+        );
+    }
+
+    #[test]
+    fn admits_only_listed_countries() {
+        let subject = ExitLocation::from_str("CZ,SK").unwrap();
+
+        assert!(subject.admits(Some("CZ")));
+        assert!(subject.admits(Some("SK")));
+        assert!(!subject.admits(Some("DE")));
+        assert!(!subject.admits(None));
+    }
+
+    #[test]
+    fn preferred_exits_are_parsed_and_displayed() {
+        let one = PublicKey::new(&[1, 2, 3]);
+        let two = PublicKey::new(&[4, 5, 6]);
+        vec![
+            (
+                "AQID,BAUG",
+                vec![one.clone(), two.clone()],
+                false,
+                "preferred:AQID,BAUG",
+            ),
+            ("preferred:BAUG", vec![two.clone()], false, "preferred:BAUG"),
+            (
+                "strict:AQID, BAUG",
+                vec![one, two],
+                true,
+                "strict:AQID,BAUG",
+            ),
+        ]
+        .into_iter()
+        .for_each(|(input, public_keys, strict, expected_display)| {
+            let preferred_exits = PreferredExits::from_str(input).unwrap();
+
+            assert_eq!(
+                preferred_exits,
+                PreferredExits {
+                    public_keys,
+                    strict
+                },
+                "{}",
+                input
+            );
+            assert_eq!(preferred_exits.to_string(), expected_display, "{}", input);
+        })
+    }
+
+    #[test]
+    fn bad_preferred_exits_are_rejected() {
+        assert_eq!(
+            PreferredExits::from_str("strict:"),
+            Err("Bad public key: ''".to_string())
+        );
+        assert_eq!(
+            PreferredExits::from_str("AQID,not a key"),
+            Err("Bad public key: 'not a key'".to_string())
+        );
+    }
+
+    #[test]
+    fn preferred_exits_admit_only_listed_keys() {
+        let subject = PreferredExits::from_str("strict:AQID").unwrap();
+
+        assert!(subject.admits(&PublicKey::new(&[1, 2, 3])));
+        assert!(!subject.admits(&PublicKey::new(&[4, 5, 6])));
+    }
+}
@@ -0,0 +1,362 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use serde_derive::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt::{Display, Formatter};
+use std::net::IpAddr;
+use std::str::FromStr;
+
+pub const PRIVATE_NETWORKS_KEYWORD: &str = "private";
+pub const PRIVATE_NETWORKS: [&str; 7] = [
+    "10.0.0.0/8",
+    "172.16.0.0/12",
+    "192.168.0.0/16",
+    "127.0.0.0/8",
+    "169.254.0.0/16",
+    "::1/128",
+    "fc00::/7",
+];
+
+// Each rule is gossiped as the same short string the operator would write on the command line.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum ExitPolicyRule {
+    Ports(u16, u16),
+    Network(IpAddr, u8),
+    Host(String),
+}
+
+impl Display for ExitPolicyRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExitPolicyRule::Ports(low, high) if low == high => write!(f, "{}", low),
+            ExitPolicyRule::Ports(low, high) => write!(f, "{}-{}", low, high),
+            ExitPolicyRule::Network(address, prefix_len) => {
+                write!(f, "{}/{}", address, prefix_len)
+            }
+            ExitPolicyRule::Host(host) => write!(f, "{}", host),
+        }
+    }
+}
+
+impl FromStr for ExitPolicyRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let bad_rule = || format!("Bad exit-policy rule: '{}'", s);
+        if s.is_empty() {
+            return Err(bad_rule());
+        }
+        if s.chars().all(|c| c.is_ascii_digit() || c == '-') {
+            let (low, high) = s.split_once('-').unwrap_or((s, s));
+            return match (low.parse::<u16>(), high.parse::<u16>()) {
+                (Ok(low), Ok(high)) if low > 0 && low <= high => {
+                    Ok(ExitPolicyRule::Ports(low, high))
+                }
+                _ => Err(bad_rule()),
+            };
+        }
+        let (address, prefix_len_opt) = match s.split_once('/') {
+            Some((address, prefix_len)) => (address, Some(prefix_len)),
+            None => (s, None),
+        };
+        if let Ok(address) = IpAddr::from_str(address) {
+            let max_prefix_len = if address.is_ipv4() { 32 } else { 128 };
+            return match prefix_len_opt.map(|prefix_len| prefix_len.parse::<u8>()) {
+                None => Ok(ExitPolicyRule::Network(address, max_prefix_len)),
+                Some(Ok(prefix_len)) if prefix_len <= max_prefix_len => {
+                    Ok(ExitPolicyRule::Network(address, prefix_len))
+                }
+                Some(_) => Err(bad_rule()),
+            };
+        }
+        if prefix_len_opt.is_none()
+            && s.chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+            && !s.starts_with('.')
+            && !s.ends_with('.')
+        {
+            Ok(ExitPolicyRule::Host(s.to_lowercase()))
+        } else {
+            Err(bad_rule())
+        }
+    }
+}
+
+impl From<ExitPolicyRule> for String {
+    fn from(rule: ExitPolicyRule) -> Self {
+        rule.to_string()
+    }
+}
+
+impl TryFrom<String> for ExitPolicyRule {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        ExitPolicyRule::from_str(&s)
+    }
+}
+
+impl ExitPolicyRule {
+    pub fn refuses_port(&self, port: u16) -> bool {
+        matches!(self, ExitPolicyRule::Ports(low, high) if (*low..=*high).contains(&port))
+    }
+
+    // A host rule covers the host itself and every subdomain of it.
+    pub fn refuses_hostname(&self, hostname: &str) -> bool {
+        match self {
+            ExitPolicyRule::Host(host) => {
+                let hostname = hostname.to_lowercase();
+                hostname == *host || hostname.ends_with(&format!(".{}", host))
+            }
+            _ => false,
+        }
+    }
+
+    pub fn refuses_ip(&self, ip_addr: IpAddr) -> bool {
+        match (self, ip_addr) {
+            (ExitPolicyRule::Network(IpAddr::V4(network), prefix_len), IpAddr::V4(ip_addr)) => {
+                let mask = u32::MAX.checked_shl(32 - *prefix_len as u32).unwrap_or(0);
+                u32::from(*network) & mask == u32::from(ip_addr) & mask
+            }
+            (ExitPolicyRule::Network(IpAddr::V6(network), prefix_len), IpAddr::V6(ip_addr)) => {
+                let mask = u128::MAX.checked_shl(128 - *prefix_len as u32).unwrap_or(0);
+                u128::from(*network) & mask == u128::from(ip_addr) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+// The destinations this Node refuses to connect to as an exit. Everything else is allowed.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ExitPolicy {
+    rules: Vec<ExitPolicyRule>,
+}
+
+impl FromStr for ExitPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rules = vec![];
+        for rule_str in s.split(',') {
+            if rule_str.trim() == PRIVATE_NETWORKS_KEYWORD {
+                rules.extend(
+                    PRIVATE_NETWORKS.iter().map(|network| {
+                        ExitPolicyRule::from_str(network).expect("Bad private network")
+                    }),
+                )
+            } else {
+                rules.push(ExitPolicyRule::from_str(rule_str)?)
+            }
+        }
+        Ok(ExitPolicy::new(rules))
+    }
+}
+
+impl ExitPolicy {
+    pub fn new(rules: Vec<ExitPolicyRule>) -> Self {
+        Self { rules }
+    }
+
+    pub fn rules(&self) -> &[ExitPolicyRule] {
+        &self.rules
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    // The hostname may be a DNS name or an IP address; addresses a name resolves to must be
+    // checked separately with refusing_rule_for_ip.
+    pub fn refusing_rule(&self, hostname: &str, port: u16) -> Option<&ExitPolicyRule> {
+        let ip_addr_opt = IpAddr::from_str(hostname).ok();
+        self.rules.iter().find(|rule| {
+            rule.refuses_port(port)
+                || rule.refuses_hostname(hostname)
+                || matches!(ip_addr_opt, Some(ip_addr) if rule.refuses_ip(ip_addr))
+        })
+    }
+
+    pub fn refusing_rule_for_ip(&self, ip_addr: IpAddr) -> Option<&ExitPolicyRule> {
+        self.rules.iter().find(|rule| rule.refuses_ip(ip_addr))
+    }
+
+    // For a consumer choosing an exit: whatever isn't known about the destination can't be refused.
+    pub fn refuses(&self, hostname_opt: Option<&str>, port_opt: Option<u16>) -> bool {
+        match (hostname_opt, port_opt) {
+            (Some(hostname), Some(port)) => self.refusing_rule(hostname, port).is_some(),
+            (Some(hostname), None) => {
+                let ip_addr_opt = IpAddr::from_str(hostname).ok();
+                self.rules.iter().any(|rule| {
+                    rule.refuses_hostname(hostname)
+                        || matches!(ip_addr_opt, Some(ip_addr) if rule.refuses_ip(ip_addr))
+                })
+            }
+            (None, Some(port)) => self.rules.iter().any(|rule| rule.refuses_port(port)),
+            (None, None) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_are_parsed_and_displayed() {
+        vec![
+            ("25", ExitPolicyRule::Ports(25, 25), "25"),
+            ("6881-6889", ExitPolicyRule::Ports(6881, 6889), "6881-6889"),
+            (
+                "10.0.0.0/8",
+                ExitPolicyRule::Network(IpAddr::from_str("10.0.0.0").unwrap(), 8),
+                "10.0.0.0/8",
+            ),
+            (
+                "1.2.3.4",
+                ExitPolicyRule::Network(IpAddr::from_str("1.2.3.4").unwrap(), 32),
+                "1.2.3.4/32",
+            ),
+            (
+                "fc00::/7",
+                ExitPolicyRule::Network(IpAddr::from_str("fc00::").unwrap(), 7),
+                "fc00::/7",
+            ),
+            (
+                " Tracker.Example.com ",
+                ExitPolicyRule::Host("tracker.example.com".to_string()),
+                "tracker.example.com",
+            ),
+        ]
+        .into_iter()
+        .for_each(|(input, expected_rule, expected_display)| {
+            let rule = ExitPolicyRule::from_str(input).unwrap();
+
+            assert_eq!(rule, expected_rule, "{}", input);
+            assert_eq!(rule.to_string(), expected_display, "{}", input);
+        })
+    }
+
+    #[test]
+    fn bad_rules_are_rejected() {
+        vec![
+            "",
+            "0",
+            "25-24",
+            "65536",
+            "1-2-3",
+            "10.0.0.0/33",
+            "::1/129",
+            "a/8",
+            "*.com",
+            ".com",
+            "booga.",
+        ]
+        .into_iter()
+        .for_each(|input| {
+            let result = ExitPolicyRule::from_str(input);
+
+            assert_eq!(
+                result,
+                Err(format!("Bad exit-policy rule: '{}'", input.trim())),
+                "{}",
+                input
+            );
+        })
+    }
+
+    #[test]
+    fn policy_expands_private_keyword() {
+        let subject = ExitPolicy::from_str("25,private").unwrap();
+
+        assert_eq!(subject.rules().len(), 8);
+        [
+            "10.1.2.3",
+            "172.31.255.255",
+            "192.168.0.1",
+            "127.0.0.1",
+            "169.254.1.1",
+            "::1",
+            "fd12::1",
+        ]
+        .iter()
+        .for_each(|ip| {
+            assert!(
+                subject
+                    .refusing_rule_for_ip(IpAddr::from_str(ip).unwrap())
+                    .is_some(),
+                "{}",
+                ip
+            )
+        });
+        ["172.32.0.1", "8.8.8.8", "2001:db8::1"]
+            .iter()
+            .for_each(|ip| {
+                assert_eq!(
+                    subject.refusing_rule_for_ip(IpAddr::from_str(ip).unwrap()),
+                    None,
+                    "{}",
+                    ip
+                )
+            });
+    }
+
+    #[test]
+    fn policy_reports_rule_that_refuses_destination() {
+        let subject = ExitPolicy::from_str("25,6881-6889,tracker.example.com,10.0.0.0/8").unwrap();
+
+        assert_eq!(
+            subject.refusing_rule("smtp.example.com", 25),
+            Some(&ExitPolicyRule::Ports(25, 25))
+        );
+        assert_eq!(
+            subject.refusing_rule("example.com", 6885),
+            Some(&ExitPolicyRule::Ports(6881, 6889))
+        );
+        assert_eq!(
+            subject.refusing_rule("UDP.Tracker.Example.COM", 443),
+            Some(&ExitPolicyRule::Host("tracker.example.com".to_string()))
+        );
+        assert_eq!(
+            subject.refusing_rule("10.9.8.7", 443),
+            Some(&ExitPolicyRule::Network(
+                IpAddr::from_str("10.0.0.0").unwrap(),
+                8
+            ))
+        );
+        assert_eq!(subject.refusing_rule("example.com", 443), None);
+        assert_eq!(subject.refusing_rule("nottracker.example.com", 443), None);
+        assert_eq!(subject.refusing_rule("11.0.0.1", 443), None);
+    }
+
+    #[test]
+    fn refuses_considers_only_what_is_known_about_destination() {
+        let subject = ExitPolicy::from_str("25,tracker.example.com").unwrap();
+
+        assert!(subject.refuses(Some("tracker.example.com"), Some(443)));
+        assert!(subject.refuses(Some("example.com"), Some(25)));
+        assert!(subject.refuses(Some("tracker.example.com"), None));
+        assert!(subject.refuses(None, Some(25)));
+        assert!(!subject.refuses(Some("example.com"), None));
+        assert!(!subject.refuses(None, Some(443)));
+        assert!(!subject.refuses(None, None));
+        assert!(!ExitPolicy::default().refuses(Some("tracker.example.com"), Some(25)));
+    }
+
+    #[test]
+    fn policy_is_serialized_as_list_of_rule_strings() {
+        let subject = ExitPolicy::from_str("25,6881-6889,booga.com,10.0.0.0/8").unwrap();
+
+        let serialized = serde_cbor::ser::to_vec(&subject).unwrap();
+        let deserialized = serde_cbor::de::from_slice::<ExitPolicy>(&serialized).unwrap();
+
+        assert_eq!(
+            serialized,
+            serde_cbor::ser::to_vec(&vec!["25", "6881-6889", "booga.com", "10.0.0.0/8"]).unwrap()
+        );
+        assert_eq!(deserialized, subject);
+    }
+}
@@ -0,0 +1,133 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::http_request_start_finder::HttpRequestStartFinder;
+use crate::proxy_server::http_protocol_pack::HttpProtocolPack;
+use crate::sub_lib::framer::FramedChunk;
+use crate::sub_lib::framer::Framer;
+use crate::sub_lib::http_packet_framer::HttpPacketFramer;
+
+// A browser talking to an explicit proxy sends HTTP requests until it sends a CONNECT; after
+// that, the stream is an opaque tunnel whose data is framed exactly as it arrives.
+pub struct ForwardProxyFramer {
+    http_framer: HttpPacketFramer,
+    tunnel_data_opt: Option<Vec<u8>>,
+}
+
+impl Default for ForwardProxyFramer {
+    fn default() -> Self {
+        Self {
+            http_framer: HttpPacketFramer::new(Box::new(HttpRequestStartFinder {})),
+            tunnel_data_opt: None,
+        }
+    }
+}
+
+impl Framer for ForwardProxyFramer {
+    fn add_data(&mut self, data: &[u8]) {
+        match self.tunnel_data_opt.as_mut() {
+            Some(tunnel_data) => tunnel_data.extend(data),
+            None => self.http_framer.add_data(data),
+        }
+    }
+
+    fn take_frame(&mut self) -> Option<FramedChunk> {
+        if let Some(tunnel_data) = self.tunnel_data_opt.as_mut() {
+            if tunnel_data.is_empty() {
+                return None;
+            }
+            return Some(FramedChunk {
+                chunk: std::mem::take(tunnel_data),
+                last_chunk: false,
+            });
+        }
+        let frame = self.http_framer.take_frame()?;
+        if HttpProtocolPack::is_connect(&frame.chunk) {
+            self.tunnel_data_opt = Some(self.http_framer.take_leftovers());
+        }
+        Some(frame)
+    }
+}
+
+impl ForwardProxyFramer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forward_proxy_framer_frames_absolute_form_requests_one_at_a_time() {
+        let first = b"GET http://example.com/ HTTP/1.1\r\nHost: example.com\r\n\r\n".to_vec();
+        let second =
+            b"POST http://example.com/form HTTP/1.1\r\nHost: example.com\r\nContent-Length: 5\r\n\r\nbooga"
+                .to_vec();
+        let mut all_data = first.clone();
+        all_data.extend(second.clone());
+        let mut subject = ForwardProxyFramer::new();
+
+        subject.add_data(&all_data);
+        let first_frame = subject.take_frame();
+        let second_frame = subject.take_frame();
+        let nothing = subject.take_frame();
+
+        assert_eq!(
+            first_frame,
+            Some(FramedChunk {
+                chunk: first,
+                last_chunk: false
+            })
+        );
+        assert_eq!(
+            second_frame,
+            Some(FramedChunk {
+                chunk: second,
+                last_chunk: false
+            })
+        );
+        assert_eq!(nothing, None);
+        assert_eq!(subject.tunnel_data_opt, None);
+    }
+
+    #[test]
+    fn forward_proxy_framer_passes_everything_after_connect_through_unframed() {
+        let connect =
+            b"CONNECT example.com:8443 HTTP/1.1\r\nHost: example.com:8443\r\n\r\n".to_vec();
+        let early_tunnel_data = b"\x16\x03\x01 not HTTP".to_vec();
+        let later_tunnel_data = b"GET / HTTP/1.1\r\n".to_vec();
+        let mut first_read = connect.clone();
+        first_read.extend(early_tunnel_data.clone());
+        let mut subject = ForwardProxyFramer::new();
+
+        subject.add_data(&first_read);
+        let connect_frame = subject.take_frame();
+        let early_frame = subject.take_frame();
+        let nothing = subject.take_frame();
+        subject.add_data(&later_tunnel_data);
+        let later_frame = subject.take_frame();
+
+        assert_eq!(
+            connect_frame,
+            Some(FramedChunk {
+                chunk: connect,
+                last_chunk: false
+            })
+        );
+        assert_eq!(
+            early_frame,
+            Some(FramedChunk {
+                chunk: early_tunnel_data,
+                last_chunk: false
+            })
+        );
+        assert_eq!(nothing, None);
+        assert_eq!(
+            later_frame,
+            Some(FramedChunk {
+                chunk: later_tunnel_data,
+                last_chunk: false
+            })
+        );
+    }
+}
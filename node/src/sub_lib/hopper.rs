@@ -4,6 +4,7 @@ use crate::neighborhood::gossip::Gossip_0v1;
 use crate::sub_lib::cryptde::encodex;
 use crate::sub_lib::cryptde::CryptDE;
 use crate::sub_lib::cryptde::CryptData;
+use crate::sub_lib::cryptde::PlainData;
 use crate::sub_lib::cryptde::PublicKey;
 use crate::sub_lib::dispatcher::InboundClientData;
 use crate::sub_lib::neighborhood::GossipFailure_0v1;
@@ -12,6 +13,7 @@ use crate::sub_lib::peer_actors::BindMessage;
 use crate::sub_lib::proxy_client::{ClientResponsePayload_0v1, DnsResolveFailure_0v1};
 use crate::sub_lib::proxy_server::ClientRequestPayload_0v1;
 use crate::sub_lib::route::Route;
+use crate::sub_lib::stream_key::StreamKey;
 use crate::sub_lib::versioned_data::VersionedData;
 use crate::sub_lib::wallet::Wallet;
 use actix::Message;
@@ -72,6 +74,8 @@ pub enum MessageType {
     Gossip(VersionedData<Gossip_0v1>),
     GossipFailure(VersionedData<GossipFailure_0v1>),
     DnsResolveFailed(VersionedData<DnsResolveFailure_0v1>),
+    CoverTraffic(VersionedData<CoverTraffic_0v1>),
+    ServiceRefused(VersionedData<ServiceRefused_0v1>),
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -81,6 +85,8 @@ pub enum MessageTypeLite {
     Gossip,
     GossipFailure,
     DnsResolveFailed,
+    CoverTraffic,
+    ServiceRefused,
 }
 
 #[allow(clippy::from_over_into)]
@@ -92,6 +98,8 @@ impl Into<MessageTypeLite> for MessageType {
             MessageType::Gossip(_) => MessageTypeLite::Gossip,
             MessageType::GossipFailure(_) => MessageTypeLite::GossipFailure,
             MessageType::DnsResolveFailed(_) => MessageTypeLite::DnsResolveFailed,
+            MessageType::CoverTraffic(_) => MessageTypeLite::CoverTraffic,
+            MessageType::ServiceRefused(_) => MessageTypeLite::ServiceRefused,
         }
     }
 }
@@ -115,6 +123,83 @@ impl IncipientCoresPackage {
     }
 }
 
+/// Filler that a Node sends its neighbors so that its links look busy whether or not they're
+/// carrying anything real. The receiving Hopper throws it away.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(non_camel_case_types)]
+pub struct CoverTraffic_0v1 {
+    pub filler: Vec<u8>,
+}
+
+impl From<CoverTraffic_0v1> for MessageType {
+    fn from(data: CoverTraffic_0v1) -> Self {
+        MessageType::CoverTraffic(VersionedData::new(
+            &crate::sub_lib::migrations::cover_traffic::MIGRATIONS,
+            &data,
+        ))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ServiceRefusalReason {
+    Delinquent,
+}
+
+/// Notice that a Node sends back along the return route when it refuses to serve a consumer,
+/// instead of leaving the consumer to wonder why its connections hang. It's signed by the
+/// refusing Node, so that the consumer can be sure whom it has to pay.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(non_camel_case_types)]
+pub struct ServiceRefused_0v1 {
+    pub stream_key: StreamKey,
+    pub reason: ServiceRefusalReason,
+    pub refuser_public_key: PublicKey,
+    pub signature: CryptData,
+}
+
+impl ServiceRefused_0v1 {
+    pub fn new(
+        stream_key: StreamKey,
+        reason: ServiceRefusalReason,
+        cryptde: &dyn CryptDE, // must be the main CryptDE of the refusing Node
+    ) -> Self {
+        let signature = cryptde
+            .sign(&Self::signed_data(&stream_key, reason))
+            .expect("Couldn't sign service refusal");
+        Self {
+            stream_key,
+            reason,
+            refuser_public_key: cryptde.public_key().clone(),
+            signature,
+        }
+    }
+
+    pub fn is_signed_by(&self, public_key: &PublicKey, cryptde: &dyn CryptDE) -> bool {
+        &self.refuser_public_key == public_key
+            && cryptde.verify_signature(
+                &Self::signed_data(&self.stream_key, self.reason),
+                &self.signature,
+                public_key,
+            )
+    }
+
+    fn signed_data(stream_key: &StreamKey, reason: ServiceRefusalReason) -> PlainData {
+        PlainData::from(
+            serde_cbor::ser::to_vec(&(stream_key, reason))
+                .expect("Couldn't serialize service refusal"),
+        )
+    }
+}
+
+impl From<ServiceRefused_0v1> for MessageType {
+    fn from(data: ServiceRefused_0v1) -> Self {
+        MessageType::ServiceRefused(VersionedData::new(
+            &crate::sub_lib::migrations::service_refused::MIGRATIONS,
+            &data,
+        ))
+    }
+}
+
 /// CORES package that has traversed the MASQ Network and is arriving at its destination
 #[derive(Clone, Debug, PartialEq, Eq, Message)]
 pub struct ExpiredCoresPackage<T> {
@@ -149,6 +234,7 @@ pub struct HopperConfig {
     pub per_routing_service: u64,
     pub per_routing_byte: u64,
     pub is_decentralized: bool,
+    pub packet_padding: bool,
     pub crashable: bool,
 }
 
@@ -170,7 +256,7 @@ impl Debug for HopperSubs {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::sub_lib::cryptde::PlainData;
+    use crate::sub_lib::cryptde_null::CryptDENull;
     use crate::sub_lib::dispatcher::Component;
     use crate::sub_lib::route::RouteSegment;
     use crate::test_utils::recorder::Recorder;
@@ -325,17 +411,44 @@ mod tests {
         let client_request = MessageType::ClientRequest(VersionedData::test_new(dv!(0, 0), vec![]));
         let gossip_failure = MessageType::GossipFailure(VersionedData::test_new(dv!(0, 0), vec![]));
         let gossip = MessageType::Gossip(VersionedData::test_new(dv!(0, 0), vec![]));
+        let cover_traffic = MessageType::CoverTraffic(VersionedData::test_new(dv!(0, 0), vec![]));
+        let service_refused =
+            MessageType::ServiceRefused(VersionedData::test_new(dv!(0, 0), vec![]));
 
         let dns_resolve_failed_result: MessageTypeLite = dns_resolve_failed.into();
         let client_response_result: MessageTypeLite = client_response.into();
         let client_request_result: MessageTypeLite = client_request.into();
         let gossip_failure_result: MessageTypeLite = gossip_failure.into();
         let gossip_result: MessageTypeLite = gossip.into();
+        let cover_traffic_result: MessageTypeLite = cover_traffic.into();
+        let service_refused_result: MessageTypeLite = service_refused.into();
 
         assert_eq!(dns_resolve_failed_result, MessageTypeLite::DnsResolveFailed);
         assert_eq!(client_response_result, MessageTypeLite::ClientResponse);
         assert_eq!(client_request_result, MessageTypeLite::ClientRequest);
         assert_eq!(gossip_failure_result, MessageTypeLite::GossipFailure);
         assert_eq!(gossip_result, MessageTypeLite::Gossip);
+        assert_eq!(cover_traffic_result, MessageTypeLite::CoverTraffic);
+        assert_eq!(service_refused_result, MessageTypeLite::ServiceRefused);
+    }
+
+    #[test]
+    fn service_refused_is_signed_by_the_refusing_node_only() {
+        let refuser = CryptDENull::new(TEST_DEFAULT_CHAIN);
+        let impostor = CryptDENull::new(TEST_DEFAULT_CHAIN);
+        let stream_key = StreamKey::make_meaningless_stream_key();
+
+        let subject =
+            ServiceRefused_0v1::new(stream_key, ServiceRefusalReason::Delinquent, &refuser);
+
+        assert_eq!(subject.refuser_public_key, refuser.public_key().clone());
+        assert!(subject.is_signed_by(refuser.public_key(), main_cryptde()));
+        assert!(!subject.is_signed_by(impostor.public_key(), main_cryptde()));
+        let mut forged = subject.clone();
+        forged.stream_key = StreamKey::make_meaningful_stream_key("other stream");
+        assert!(!forged.is_signed_by(refuser.public_key(), main_cryptde()));
+        let mut disowned = subject;
+        disowned.refuser_public_key = impostor.public_key().clone();
+        assert!(!disowned.is_signed_by(impostor.public_key(), main_cryptde()));
     }
 }
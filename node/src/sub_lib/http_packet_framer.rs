@@ -108,6 +108,10 @@ impl HttpPacketFramer {
         }
     }
 
+    pub fn take_leftovers(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.framer_state.data_so_far)
+    }
+
     fn take_packet_frame(&mut self) -> Option<FramedChunk> {
         if self.framer_state.packet_progress_state == PacketProgressState::SeekingPacketStart
             && !self.start_finder.seek_packet_start(&mut self.framer_state)
@@ -98,7 +98,10 @@ impl TryFrom<&Value> for ClientRequestPayload_0v1 {
                     &originator_public_key_opt,
                 );
                 if !missing_fields.is_empty() {
-                    unimplemented!("{:?}", missing_fields.clone())
+                    return Err(StepError::SemanticError(format!(
+                        "Missing or undecodable fields: {:?}",
+                        missing_fields
+                    )));
                 }
                 Ok(ClientRequestPayload_0v1 {
                     stream_key: stream_key_opt.expect("stream_key disappeared"),
@@ -168,6 +171,63 @@ mod tests {
         assert_eq!(actual_crp, expected_crp);
     }
 
+    #[test]
+    fn socks5_payload_is_decoded_as_tls_by_the_0v1_decoder() {
+        let socks5_crp = ClientRequestPayload_0v1 {
+            stream_key: StreamKey::make_meaningful_stream_key("Here Comes the Sun"),
+            sequenced_packet: SequencedPacket::new(vec![1, 2, 3, 4], 0, false),
+            target_hostname: Some("target.hostname.com".to_string()),
+            target_port: 8080,
+            protocol: ProxyProtocol::SOCKS5,
+            originator_public_key: PublicKey::new(&[2, 3, 4, 5]),
+        };
+        let serialized =
+            serde_cbor::ser::to_vec(&VersionedData::new(&MIGRATIONS, &socks5_crp)).unwrap();
+        let vd = serde_cbor::de::from_slice::<VersionedData<ClientRequestPayload_0v1>>(&serialized)
+            .unwrap();
+
+        let result = ClientRequestPayload_0v1::try_from(vd).unwrap();
+
+        assert_eq!(
+            result,
+            ClientRequestPayload_0v1 {
+                protocol: ProxyProtocol::TLS,
+                ..socks5_crp
+            }
+        );
+    }
+
+    #[test]
+    fn unknown_protocol_from_the_future_is_an_error_rather_than_a_panic() {
+        #[derive(Serialize, Deserialize)]
+        struct ExampleFutureCRP {
+            pub stream_key: StreamKey,
+            pub sequenced_packet: SequencedPacket,
+            pub target_hostname: Option<String>,
+            pub target_port: u16,
+            pub protocol: String,
+            pub originator_public_key: PublicKey,
+        }
+        let future_crp = ExampleFutureCRP {
+            stream_key: StreamKey::make_meaningful_stream_key("Something"),
+            sequenced_packet: SequencedPacket::new(vec![4, 3, 2, 1], 4321, false),
+            target_hostname: Some("target.hostname.com".to_string()),
+            target_port: 1234,
+            protocol: "QUIC".to_string(),
+            originator_public_key: PublicKey::new(&[2, 3, 4, 5]),
+        };
+        let value = serde_cbor::value::to_value(&future_crp).unwrap();
+
+        let result = ClientRequestPayload_0v1::try_from(&value);
+
+        assert_eq!(
+            result,
+            Err(StepError::SemanticError(
+                "Missing or undecodable fields: [\"protocol\"]".to_string()
+            ))
+        )
+    }
+
     #[test]
     fn cannot_migrate_from_value_other_than_map() {
         let value = Value::Bool(true);
@@ -0,0 +1,128 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::sub_lib::hopper::CoverTraffic_0v1;
+use crate::sub_lib::migrations::utils::value_to_type;
+use crate::sub_lib::versioned_data::Migrations;
+use crate::sub_lib::versioned_data::{MigrationError, StepError, VersionedData};
+use lazy_static::lazy_static;
+use serde_cbor::Value;
+use std::convert::TryFrom;
+
+lazy_static! {
+    pub static ref MIGRATIONS: Migrations = {
+        let current_version = masq_lib::constants::COVER_TRAFFIC_CURRENT_VERSION;
+        let mut migrations = Migrations::new(current_version);
+
+        migrate_value!(dv!(0, 1), CoverTraffic_0v1, CoverTrafficMF_0v1, {|value: serde_cbor::Value| {
+            CoverTraffic_0v1::try_from (&value)
+        }});
+        migrations.add_step (masq_lib::data_version::FUTURE_VERSION, dv!(0, 1), Box::new (CoverTrafficMF_0v1{}));
+
+        // add more steps here
+
+        migrations
+    };
+}
+
+impl From<CoverTraffic_0v1> for VersionedData<CoverTraffic_0v1> {
+    fn from(data: CoverTraffic_0v1) -> Self {
+        VersionedData::new(&MIGRATIONS, &data)
+    }
+}
+
+impl TryFrom<VersionedData<CoverTraffic_0v1>> for CoverTraffic_0v1 {
+    type Error = MigrationError;
+
+    fn try_from(vd: VersionedData<CoverTraffic_0v1>) -> Result<Self, Self::Error> {
+        vd.extract(&MIGRATIONS)
+    }
+}
+
+impl TryFrom<&Value> for CoverTraffic_0v1 {
+    type Error = StepError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            // Cover traffic is thrown away on arrival, so a missing or mangled filler costs nothing
+            Value::Map(map) => {
+                let filler = map
+                    .get(&Value::Text("filler".to_string()))
+                    .and_then(value_to_type::<Vec<u8>>)
+                    .unwrap_or_default();
+                Ok(CoverTraffic_0v1 { filler })
+            }
+            _ => Err(StepError::SemanticError(format!(
+                "Expected Value::Map; found {:?}",
+                value
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use masq_lib::data_version::DataVersion;
+    use serde_derive::{Deserialize, Serialize};
+
+    #[test]
+    fn can_migrate_from_the_future() {
+        #[derive(Serialize, Deserialize)]
+        struct ExampleFutureCT {
+            pub filler: Vec<u8>,
+            pub another_field: String,
+        }
+        let expected_ct = CoverTraffic_0v1 {
+            filler: vec![0, 0, 0, 0],
+        };
+        let future_ct = ExampleFutureCT {
+            filler: expected_ct.filler.clone(),
+            another_field: "Nothing to see here".to_string(),
+        };
+        let future_migrations = Migrations::new(DataVersion::new(4095, 4095));
+        let serialized =
+            serde_cbor::ser::to_vec(&VersionedData::new(&future_migrations, &future_ct)).unwrap();
+        let future_vd =
+            serde_cbor::de::from_slice::<VersionedData<CoverTraffic_0v1>>(&serialized).unwrap();
+
+        let actual_ct = CoverTraffic_0v1::try_from(future_vd).unwrap();
+
+        assert_eq!(actual_ct, expected_ct);
+    }
+
+    #[test]
+    fn migrates_a_future_version_without_filler_to_empty_filler() {
+        #[derive(Serialize, Deserialize)]
+        struct ExampleFutureCT {
+            pub padding_length: u32,
+        }
+        let future_migrations = Migrations::new(DataVersion::new(4095, 4095));
+        let serialized = serde_cbor::ser::to_vec(&VersionedData::new(
+            &future_migrations,
+            &ExampleFutureCT {
+                padding_length: 1234,
+            },
+        ))
+        .unwrap();
+        let future_vd =
+            serde_cbor::de::from_slice::<VersionedData<CoverTraffic_0v1>>(&serialized).unwrap();
+
+        let actual_ct = CoverTraffic_0v1::try_from(future_vd).unwrap();
+
+        assert_eq!(actual_ct, CoverTraffic_0v1 { filler: vec![] });
+    }
+
+    #[test]
+    fn cannot_migrate_from_value_other_than_map() {
+        let value = Value::Bool(true);
+
+        let result = CoverTraffic_0v1::try_from(&value);
+
+        assert_eq!(
+            result,
+            Err(StepError::SemanticError(
+                "Expected Value::Map; found Bool(true)".to_string()
+            ))
+        )
+    }
+}
@@ -2,8 +2,10 @@
 
 pub mod client_request_payload;
 pub mod client_response_payload;
+pub mod cover_traffic;
 pub mod dns_resolve_failure;
 pub mod gossip;
 pub mod gossip_failure;
 pub mod node_record_inner;
+pub mod service_refused;
 pub mod utils;
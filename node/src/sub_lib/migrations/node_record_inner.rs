@@ -1,14 +1,17 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
-use crate::neighborhood::node_record::NodeRecordInner_0v1;
+use crate::masquerader::MasqueraderKind;
+use crate::neighborhood::node_record::{KeyTransition, NodeRecordInner_0v1};
 use crate::sub_lib::cryptde::PublicKey;
+use crate::sub_lib::exit_location::normalize_country_code;
+use crate::sub_lib::exit_policy::{ExitPolicy, ExitPolicyRule};
 use crate::sub_lib::migrations::utils::value_to_type;
 use crate::sub_lib::neighborhood::RatePack;
 use crate::sub_lib::versioned_data::{MigrationError, Migrations, StepError, VersionedData};
 use crate::sub_lib::wallet::Wallet;
 use lazy_static::lazy_static;
 use serde_cbor::Value;
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::convert::TryFrom;
 
 lazy_static! {
@@ -55,12 +58,24 @@ impl TryFrom<&Value> for NodeRecordInner_0v1 {
                 let mut accepts_connections_opt: Option<bool> = None;
                 let mut routes_data_opt: Option<bool> = None;
                 let mut version_opt: Option<u32> = None;
+                let mut masquerader_kinds: Vec<MasqueraderKind> = vec![];
+                let mut exit_policy = ExitPolicy::default();
+                let mut country_code_opt: Option<String> = None;
+                let mut neighbor_latencies: BTreeMap<PublicKey, u32> = BTreeMap::new();
+                let mut key_transition_opt: Option<KeyTransition> = None;
+                let mut link_encryption = false;
                 map.keys().for_each(|k| {
                     let v = map.get(k).expect("Disappeared");
                     match (k, v) {
                         (Value::Text(field_name), Value::Map(_)) => match field_name.as_str() {
                             "earning_wallet" => earning_wallet_opt = value_to_type::<Wallet>(v),
                             "rate_pack" => rate_pack_opt = value_to_type::<RatePack>(v),
+                            "neighbor_latencies" => {
+                                neighbor_latencies = Self::values_to_neighbor_latencies(v)
+                            }
+                            "key_transition_opt" => {
+                                key_transition_opt = value_to_type::<KeyTransition>(v)
+                            }
                             _ => (),
                         },
                         (Value::Text(field_name), Value::Array(field_value)) => {
@@ -68,6 +83,22 @@ impl TryFrom<&Value> for NodeRecordInner_0v1 {
                                 "neighbors" => {
                                     neighbors_opt = Self::public_keys_to_btree_set(field_value)
                                 }
+                                // Kinds this Node doesn't know about are of no use to it
+                                "masquerader_kinds" => {
+                                    masquerader_kinds = field_value
+                                        .iter()
+                                        .filter_map(value_to_type::<MasqueraderKind>)
+                                        .collect()
+                                }
+                                // The exit will still refuse what it refuses, even if we can't tell
+                                "exit_policy" => {
+                                    exit_policy = ExitPolicy::new(
+                                        field_value
+                                            .iter()
+                                            .filter_map(value_to_type::<ExitPolicyRule>)
+                                            .collect(),
+                                    )
+                                }
                                 _ => (),
                             }
                         }
@@ -83,6 +114,15 @@ impl TryFrom<&Value> for NodeRecordInner_0v1 {
                                     accepts_connections_opt = Some(*field_value)
                                 }
                                 "routes_data" => routes_data_opt = Some(*field_value),
+                                "link_encryption" => link_encryption = *field_value,
+                                _ => (),
+                            }
+                        }
+                        (Value::Text(field_name), Value::Text(field_value)) => {
+                            match field_name.as_str() {
+                                "country_code_opt" => {
+                                    country_code_opt = normalize_country_code(field_value).ok()
+                                }
                                 _ => (),
                             }
                         }
@@ -131,6 +171,12 @@ impl TryFrom<&Value> for NodeRecordInner_0v1 {
                     accepts_connections: accepts_connections_opt.expect("public_key disappeared"),
                     routes_data: routes_data_opt.expect("public_key disappeared"),
                     version: version_opt.expect("public_key disappeared"),
+                    masquerader_kinds,
+                    exit_policy,
+                    country_code_opt,
+                    neighbor_latencies,
+                    key_transition_opt,
+                    link_encryption,
                 })
             }
             _ => Err(StepError::SemanticError(format!(
@@ -152,11 +198,30 @@ impl NodeRecordInner_0v1 {
         }
         Some(output)
     }
+
+    // A latency this Node can't make sense of is just left unknown
+    fn values_to_neighbor_latencies(value: &Value) -> BTreeMap<PublicKey, u32> {
+        match value {
+            Value::Map(map) => map
+                .iter()
+                .filter_map(|(k, v)| match (value_to_type::<PublicKey>(k), v) {
+                    (Some(public_key), Value::Integer(millis))
+                        if (0..=(u32::MAX as i128)).contains(millis) =>
+                    {
+                        Some((public_key, *millis as u32))
+                    }
+                    _ => None,
+                })
+                .collect(),
+            _ => BTreeMap::new(),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sub_lib::cryptde::CryptData;
     use crate::sub_lib::neighborhood::DEFAULT_RATE_PACK;
     use masq_lib::data_version::DataVersion;
     use serde_derive::{Deserialize, Serialize};
@@ -173,6 +238,12 @@ mod tests {
             pub accepts_connections: bool,
             pub routes_data: bool,
             pub version: u32,
+            pub masquerader_kinds: Vec<String>,
+            pub exit_policy: Vec<String>,
+            pub country_code_opt: Option<String>,
+            pub neighbor_latencies: BTreeMap<PublicKey, i64>,
+            pub key_transition_opt: Option<KeyTransition>,
+            pub link_encryption: bool,
             pub another_field: String,
             pub yet_another_field: u64,
         }
@@ -186,6 +257,20 @@ mod tests {
             accepts_connections: false,
             routes_data: true,
             version: 42,
+            masquerader_kinds: vec![MasqueraderKind::Http, MasqueraderKind::Json],
+            exit_policy: ExitPolicy::new(vec![
+                ExitPolicyRule::Ports(25, 25),
+                ExitPolicyRule::Host("booga.com".to_string()),
+            ]),
+            country_code_opt: Some("CZ".to_string()),
+            neighbor_latencies: BTreeMap::from_iter(
+                vec![(PublicKey::new(&[2, 3, 4, 5]), 42)].into_iter(),
+            ),
+            key_transition_opt: Some(KeyTransition {
+                new_public_key: PublicKey::new(&[9, 8, 7, 6]),
+                signature: CryptData::new(&[5, 4, 3, 2]),
+            }),
+            link_encryption: true,
         };
         let future_nri = ExampleFutureNRI {
             public_key: expected_nri.public_key.clone(),
@@ -195,6 +280,26 @@ mod tests {
             accepts_connections: expected_nri.accepts_connections,
             routes_data: expected_nri.routes_data,
             version: expected_nri.version,
+            masquerader_kinds: vec![
+                "Http".to_string(),
+                "Carrier Pigeon".to_string(),
+                "Json".to_string(),
+            ],
+            exit_policy: vec![
+                "25".to_string(),
+                "geo:XX".to_string(),
+                "booga.com".to_string(),
+            ],
+            country_code_opt: Some("cz".to_string()),
+            neighbor_latencies: BTreeMap::from_iter(
+                vec![
+                    (PublicKey::new(&[2, 3, 4, 5]), 42),
+                    (PublicKey::new(&[3, 4, 5, 6]), -1),
+                ]
+                .into_iter(),
+            ),
+            key_transition_opt: expected_nri.key_transition_opt.clone(),
+            link_encryption: expected_nri.link_encryption,
             another_field: "These are the times that try men's souls".to_string(),
             yet_another_field: 1234567890,
         };
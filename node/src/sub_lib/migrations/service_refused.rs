@@ -0,0 +1,165 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::sub_lib::cryptde::{CryptData, PublicKey};
+use crate::sub_lib::hopper::{ServiceRefusalReason, ServiceRefused_0v1};
+use crate::sub_lib::migrations::utils::value_to_type;
+use crate::sub_lib::stream_key::StreamKey;
+use crate::sub_lib::versioned_data::Migrations;
+use crate::sub_lib::versioned_data::{MigrationError, StepError, VersionedData};
+use lazy_static::lazy_static;
+use serde_cbor::Value;
+use std::convert::TryFrom;
+
+lazy_static! {
+    pub static ref MIGRATIONS: Migrations = {
+        let current_version = masq_lib::constants::SERVICE_REFUSED_CURRENT_VERSION;
+        let mut migrations = Migrations::new(current_version);
+
+        migrate_value!(dv!(0, 1), ServiceRefused_0v1, ServiceRefusedMF_0v1, {|value: serde_cbor::Value| {
+            ServiceRefused_0v1::try_from (&value)
+        }});
+        migrations.add_step (masq_lib::data_version::FUTURE_VERSION, dv!(0, 1), Box::new (ServiceRefusedMF_0v1{}));
+
+        // add more steps here
+
+        migrations
+    };
+}
+
+impl From<ServiceRefused_0v1> for VersionedData<ServiceRefused_0v1> {
+    fn from(data: ServiceRefused_0v1) -> Self {
+        VersionedData::new(&MIGRATIONS, &data)
+    }
+}
+
+impl TryFrom<VersionedData<ServiceRefused_0v1>> for ServiceRefused_0v1 {
+    type Error = MigrationError;
+
+    fn try_from(vd: VersionedData<ServiceRefused_0v1>) -> Result<Self, Self::Error> {
+        vd.extract(&MIGRATIONS)
+    }
+}
+
+impl TryFrom<&Value> for ServiceRefused_0v1 {
+    type Error = StepError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Map(map) => {
+                let field = |name: &str| map.get(&Value::Text(name.to_string()));
+                let stream_key_opt = field("stream_key").and_then(value_to_type::<StreamKey>);
+                let reason_opt = field("reason").and_then(value_to_type::<ServiceRefusalReason>);
+                let refuser_public_key_opt =
+                    field("refuser_public_key").and_then(value_to_type::<PublicKey>);
+                let signature_opt = field("signature").and_then(value_to_type::<CryptData>);
+                match (
+                    stream_key_opt,
+                    reason_opt,
+                    refuser_public_key_opt,
+                    signature_opt,
+                ) {
+                    (
+                        Some(stream_key),
+                        Some(reason),
+                        Some(refuser_public_key),
+                        Some(signature),
+                    ) => Ok(ServiceRefused_0v1 {
+                        stream_key,
+                        reason,
+                        refuser_public_key,
+                        signature,
+                    }),
+                    _ => Err(StepError::SemanticError(format!(
+                        "Expected stream_key, reason, refuser_public_key, and signature; found {:?}",
+                        value
+                    ))),
+                }
+            }
+            _ => Err(StepError::SemanticError(format!(
+                "Expected Value::Map; found {:?}",
+                value
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::main_cryptde;
+    use masq_lib::data_version::DataVersion;
+    use serde_derive::{Deserialize, Serialize};
+
+    #[test]
+    fn can_migrate_from_the_future() {
+        #[derive(Serialize, Deserialize)]
+        struct ExampleFutureSR {
+            pub stream_key: StreamKey,
+            pub reason: ServiceRefusalReason,
+            pub refuser_public_key: PublicKey,
+            pub signature: CryptData,
+            pub another_field: String,
+        }
+        let expected_sr = ServiceRefused_0v1::new(
+            StreamKey::make_meaningful_stream_key("All Things Must Pass"),
+            ServiceRefusalReason::Delinquent,
+            main_cryptde(),
+        );
+        let future_sr = ExampleFutureSR {
+            stream_key: expected_sr.stream_key,
+            reason: expected_sr.reason,
+            refuser_public_key: expected_sr.refuser_public_key.clone(),
+            signature: expected_sr.signature.clone(),
+            another_field: "Pay up".to_string(),
+        };
+        let future_migrations = Migrations::new(DataVersion::new(4095, 4095));
+        let serialized =
+            serde_cbor::ser::to_vec(&VersionedData::new(&future_migrations, &future_sr)).unwrap();
+        let future_vd =
+            serde_cbor::de::from_slice::<VersionedData<ServiceRefused_0v1>>(&serialized).unwrap();
+
+        let actual_sr = ServiceRefused_0v1::try_from(future_vd).unwrap();
+
+        assert_eq!(actual_sr, expected_sr);
+    }
+
+    #[test]
+    fn cannot_migrate_from_a_future_version_without_a_signature() {
+        #[derive(Serialize, Deserialize)]
+        struct ExampleFutureSR {
+            pub stream_key: StreamKey,
+            pub reason: ServiceRefusalReason,
+            pub refuser_public_key: PublicKey,
+        }
+        let future_migrations = Migrations::new(DataVersion::new(4095, 4095));
+        let serialized = serde_cbor::ser::to_vec(&VersionedData::new(
+            &future_migrations,
+            &ExampleFutureSR {
+                stream_key: StreamKey::make_meaningless_stream_key(),
+                reason: ServiceRefusalReason::Delinquent,
+                refuser_public_key: PublicKey::new(&[1, 2, 3, 4]),
+            },
+        ))
+        .unwrap();
+        let future_vd =
+            serde_cbor::de::from_slice::<VersionedData<ServiceRefused_0v1>>(&serialized).unwrap();
+
+        let result = ServiceRefused_0v1::try_from(future_vd);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn cannot_migrate_from_value_other_than_map() {
+        let value = Value::Bool(true);
+
+        let result = ServiceRefused_0v1::try_from(&value);
+
+        assert_eq!(
+            result,
+            Err(StepError::SemanticError(
+                "Expected Value::Map; found Bool(true)".to_string()
+            ))
+        )
+    }
+}
@@ -17,6 +17,9 @@ pub mod cryptde_null;
 pub mod cryptde_real;
 pub mod data_version;
 pub mod dispatcher;
+pub mod exit_location;
+pub mod exit_policy;
+pub mod forward_proxy_framer;
 pub mod framer;
 pub mod framer_utils;
 pub mod hop;
@@ -35,6 +38,7 @@ pub mod route;
 pub mod sequence_buffer;
 pub mod sequencer;
 pub mod socket_server;
+pub mod socks5_framer;
 pub mod stream_connector;
 pub mod stream_handler_pool;
 pub mod stream_key;
@@ -1,12 +1,15 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+use crate::masquerader::MasqueraderKind;
 use crate::neighborhood::gossip::Gossip_0v1;
+use crate::neighborhood::latency_prober::{LatencyProber, LatencyProberReal};
 use crate::neighborhood::node_record::NodeRecord;
 use crate::neighborhood::overall_connection_status::ConnectionProgress;
 use crate::neighborhood::Neighborhood;
 use crate::sub_lib::cryptde::{CryptDE, PublicKey};
 use crate::sub_lib::cryptde_real::CryptDEReal;
 use crate::sub_lib::dispatcher::{Component, StreamShutdownMsg};
+use crate::sub_lib::exit_location::PreferredExits;
 use crate::sub_lib::hopper::ExpiredCoresPackage;
 use crate::sub_lib::node_addr::NodeAddr;
 use crate::sub_lib::peer_actors::{BindMessage, NewPublicIp, StartMessage};
@@ -33,6 +36,7 @@ use std::str::FromStr;
 use std::time::Duration;
 
 const ASK_ABOUT_GOSSIP_INTERVAL: Duration = Duration::from_secs(10);
+const LATENCY_PROBE_INTERVAL: Duration = Duration::from_secs(60);
 
 pub const DEFAULT_RATE_PACK: RatePack = RatePack {
     routing_byte_rate: 172_300_000,
@@ -64,6 +68,16 @@ impl RatePack {
     pub fn exit_charge(&self, payload_size: u64) -> u64 {
         self.exit_service_rate + (self.exit_byte_rate * payload_size)
     }
+
+    pub fn routing_rates_exceed(&self, ceiling: &RatePack) -> bool {
+        self.routing_byte_rate > ceiling.routing_byte_rate
+            || self.routing_service_rate > ceiling.routing_service_rate
+    }
+
+    pub fn exit_rates_exceed(&self, ceiling: &RatePack) -> bool {
+        self.exit_byte_rate > ceiling.exit_byte_rate
+            || self.exit_service_rate > ceiling.exit_service_rate
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -438,6 +452,8 @@ pub struct NodeQueryResponseMetadata {
     pub public_key: PublicKey,
     pub node_addr_opt: Option<NodeAddr>,
     pub rate_pack: RatePack,
+    pub masquerader_kinds: Vec<MasqueraderKind>,
+    pub link_encryption: bool,
 }
 
 impl NodeQueryResponseMetadata {
@@ -445,11 +461,15 @@ impl NodeQueryResponseMetadata {
         public_key: PublicKey,
         node_addr_opt: Option<NodeAddr>,
         rate_pack: RatePack,
+        masquerader_kinds: Vec<MasqueraderKind>,
+        link_encryption: bool,
     ) -> NodeQueryResponseMetadata {
         NodeQueryResponseMetadata {
             public_key,
             node_addr_opt,
             rate_pack,
+            masquerader_kinds,
+            link_encryption,
         }
     }
 }
@@ -474,6 +494,9 @@ pub struct RouteQueryMessage {
     pub return_component_opt: Option<Component>,
     pub payload_size: usize,
     pub hostname_opt: Option<String>,
+    pub target_port_opt: Option<u16>,
+    // Relays the route must not pass through, so that it shares none with routes already in use
+    pub avoided_keys: Vec<PublicKey>,
 }
 
 impl Message for RouteQueryMessage {
@@ -483,6 +506,7 @@ impl Message for RouteQueryMessage {
 impl RouteQueryMessage {
     pub fn data_indefinite_route_request(
         hostname_opt: Option<String>,
+        target_port_opt: Option<u16>,
         payload_size: usize,
     ) -> RouteQueryMessage {
         RouteQueryMessage {
@@ -491,6 +515,26 @@ impl RouteQueryMessage {
             return_component_opt: Some(Component::ProxyServer),
             payload_size,
             hostname_opt,
+            target_port_opt,
+            avoided_keys: vec![],
+        }
+    }
+
+    pub fn data_disjoint_route_request(
+        exit_key: PublicKey,
+        avoided_keys: Vec<PublicKey>,
+        hostname_opt: Option<String>,
+        target_port_opt: Option<u16>,
+        payload_size: usize,
+    ) -> RouteQueryMessage {
+        RouteQueryMessage {
+            target_key_opt: Some(exit_key),
+            target_component: Component::ProxyClient,
+            return_component_opt: Some(Component::ProxyServer),
+            payload_size,
+            hostname_opt,
+            target_port_opt,
+            avoided_keys,
         }
     }
 }
@@ -541,6 +585,18 @@ pub struct AskAboutDebutGossipMessage {
     pub prev_connection_progress: ConnectionProgress,
 }
 
+#[derive(Clone, Debug, Message, PartialEq, Eq)]
+pub struct ProbeNeighborLatenciesMessage {}
+
+#[derive(Clone, Debug, Message, PartialEq, Eq)]
+pub struct SendCoverTrafficMessage {}
+
+#[derive(Clone, Debug, Message, PartialEq, Eq)]
+pub struct NeighborLatencyMessage {
+    pub public_key: PublicKey,
+    pub latency_opt: Option<Duration>,
+}
+
 #[derive(Clone, Debug, Message, PartialEq, Eq)]
 pub struct UpdateNodeRecordMetadataMessage {
     pub public_key: PublicKey,
@@ -550,6 +606,7 @@ pub struct UpdateNodeRecordMetadataMessage {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum NRMetadataChange {
     AddUnreachableHost { hostname: String },
+    ServiceRefused,
 }
 
 #[derive(Clone, Debug, Message, PartialEq, Eq)]
@@ -565,8 +622,11 @@ pub struct WalletPair {
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ConfigChange {
+    UpdateExcludedNodes(Vec<PublicKey>),
+    UpdateMaxRatePack(Option<RatePack>),
     UpdateMinHops(Hops),
     UpdatePassword(String),
+    UpdatePreferredExits(Option<PreferredExits>),
     UpdateWallets(WalletPair),
 }
 
@@ -604,6 +664,12 @@ pub struct NeighborhoodTools {
     pub notify_later_ask_about_gossip:
         Box<dyn NotifyLaterHandle<AskAboutDebutGossipMessage, Neighborhood>>,
     pub ask_about_gossip_interval: Duration,
+    pub notify_later_probe_latencies:
+        Box<dyn NotifyLaterHandle<ProbeNeighborLatenciesMessage, Neighborhood>>,
+    pub latency_probe_interval: Duration,
+    pub latency_prober: Box<dyn LatencyProber>,
+    pub notify_later_send_cover_traffic:
+        Box<dyn NotifyLaterHandle<SendCoverTrafficMessage, Neighborhood>>,
 }
 
 impl Default for NeighborhoodTools {
@@ -611,6 +677,10 @@ impl Default for NeighborhoodTools {
         Self {
             notify_later_ask_about_gossip: Box::new(NotifyLaterHandleReal::new()),
             ask_about_gossip_interval: ASK_ABOUT_GOSSIP_INTERVAL,
+            notify_later_probe_latencies: Box::new(NotifyLaterHandleReal::new()),
+            latency_probe_interval: LATENCY_PROBE_INTERVAL,
+            latency_prober: Box::new(LatencyProberReal::default()),
+            notify_later_send_cover_traffic: Box::new(NotifyLaterHandleReal::new()),
         }
     }
 }
@@ -649,6 +719,7 @@ mod tests {
             }
         );
         assert_eq!(ASK_ABOUT_GOSSIP_INTERVAL, Duration::from_secs(10));
+        assert_eq!(LATENCY_PROBE_INTERVAL, Duration::from_secs(60));
     }
 
     pub fn rate_pack(base_rate: u64) -> RatePack {
@@ -976,6 +1047,44 @@ mod tests {
         assert_eq!(result, 1_000_000);
     }
 
+    #[test]
+    fn rate_pack_ceilings_are_checked_rate_by_rate() {
+        let ceiling = RatePack {
+            routing_byte_rate: 10,
+            routing_service_rate: 100,
+            exit_byte_rate: 20,
+            exit_service_rate: 200,
+        };
+        let at_ceiling = ceiling;
+        let routing_byte_too_high = RatePack {
+            routing_byte_rate: 11,
+            ..ceiling
+        };
+        let routing_service_too_high = RatePack {
+            routing_service_rate: 101,
+            ..ceiling
+        };
+        let exit_byte_too_high = RatePack {
+            exit_byte_rate: 21,
+            ..ceiling
+        };
+        let exit_service_too_high = RatePack {
+            exit_service_rate: 201,
+            ..ceiling
+        };
+
+        assert!(!at_ceiling.routing_rates_exceed(&ceiling));
+        assert!(!at_ceiling.exit_rates_exceed(&ceiling));
+        assert!(routing_byte_too_high.routing_rates_exceed(&ceiling));
+        assert!(!routing_byte_too_high.exit_rates_exceed(&ceiling));
+        assert!(routing_service_too_high.routing_rates_exceed(&ceiling));
+        assert!(!routing_service_too_high.exit_rates_exceed(&ceiling));
+        assert!(!exit_byte_too_high.routing_rates_exceed(&ceiling));
+        assert!(exit_byte_too_high.exit_rates_exceed(&ceiling));
+        assert!(!exit_service_too_high.routing_rates_exceed(&ceiling));
+        assert!(exit_service_too_high.exit_rates_exceed(&ceiling));
+    }
+
     #[test]
     fn node_descriptor_from_key_node_addr_and_mainnet_flag_works() {
         let cryptde: &dyn CryptDE = main_cryptde();
@@ -1051,7 +1160,11 @@ mod tests {
 
     #[test]
     fn data_indefinite_route_request() {
-        let result = RouteQueryMessage::data_indefinite_route_request(None, 7500);
+        let result = RouteQueryMessage::data_indefinite_route_request(
+            Some("booga.com".to_string()),
+            Some(443),
+            7500,
+        );
 
         assert_eq!(
             result,
@@ -1060,7 +1173,33 @@ mod tests {
                 target_component: Component::ProxyClient,
                 return_component_opt: Some(Component::ProxyServer),
                 payload_size: 7500,
-                hostname_opt: None
+                hostname_opt: Some("booga.com".to_string()),
+                target_port_opt: Some(443),
+                avoided_keys: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn data_disjoint_route_request() {
+        let result = RouteQueryMessage::data_disjoint_route_request(
+            PublicKey::new(b"exit"),
+            vec![PublicKey::new(b"relay")],
+            Some("booga.com".to_string()),
+            Some(443),
+            7500,
+        );
+
+        assert_eq!(
+            result,
+            RouteQueryMessage {
+                target_key_opt: Some(PublicKey::new(b"exit")),
+                target_component: Component::ProxyClient,
+                return_component_opt: Some(Component::ProxyServer),
+                payload_size: 7500,
+                hostname_opt: Some("booga.com".to_string()),
+                target_port_opt: Some(443),
+                avoided_keys: vec![PublicKey::new(b"relay")],
             }
         );
     }
@@ -1265,6 +1404,22 @@ mod tests {
             .downcast_ref::<NotifyLaterHandleReal<AskAboutDebutGossipMessage>>()
             .unwrap();
         assert_eq!(subject.ask_about_gossip_interval, Duration::from_secs(10));
+        subject
+            .notify_later_probe_latencies
+            .as_any()
+            .downcast_ref::<NotifyLaterHandleReal<ProbeNeighborLatenciesMessage>>()
+            .unwrap();
+        assert_eq!(subject.latency_probe_interval, Duration::from_secs(60));
+        subject
+            .latency_prober
+            .as_any()
+            .downcast_ref::<LatencyProberReal>()
+            .unwrap();
+        subject
+            .notify_later_send_cover_traffic
+            .as_any()
+            .downcast_ref::<NotifyLaterHandleReal<SendCoverTrafficMessage>>()
+            .unwrap();
     }
 
     #[test]
@@ -39,6 +39,7 @@ impl PeerActors {
         vec![
             self.accountant.config_change_msg_sub.clone(),
             self.neighborhood.config_change_msg_sub.clone(),
+            self.proxy_server.config_change_msg_sub.clone(),
         ]
     }
 }
@@ -1,5 +1,6 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 use crate::sub_lib::cryptde::CryptDE;
+use crate::sub_lib::exit_policy::ExitPolicy;
 use crate::sub_lib::hopper::{ExpiredCoresPackage, MessageType};
 use crate::sub_lib::peer_actors::BindMessage;
 use crate::sub_lib::proxy_server::ClientRequestPayload_0v1;
@@ -27,6 +28,7 @@ pub struct ProxyClientConfig {
     pub exit_byte_rate: u64,
     pub is_decentralized: bool,
     pub crashable: bool,
+    pub exit_policy: ExitPolicy,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
@@ -3,8 +3,8 @@ use crate::sub_lib::cryptde::PublicKey;
 use crate::sub_lib::data_version::DataVersion;
 use crate::sub_lib::dispatcher::InboundClientData;
 use crate::sub_lib::dispatcher::StreamShutdownMsg;
-use crate::sub_lib::hopper::{ExpiredCoresPackage, MessageType};
-use crate::sub_lib::neighborhood::{ExpectedService, RouteQueryResponse};
+use crate::sub_lib::hopper::{ExpiredCoresPackage, MessageType, ServiceRefused_0v1};
+use crate::sub_lib::neighborhood::{ConfigChangeMsg, ExpectedService, RouteQueryResponse};
 use crate::sub_lib::peer_actors::BindMessage;
 use crate::sub_lib::proxy_client::{ClientResponsePayload_0v1, DnsResolveFailure_0v1};
 use crate::sub_lib::sequence_buffer::SequencedPacket;
@@ -14,6 +14,7 @@ use crate::sub_lib::versioned_data::VersionedData;
 use actix::Message;
 use actix::Recipient;
 use masq_lib::ui_gateway::NodeFromUiMessage;
+use serde::Serializer;
 use serde_derive::{Deserialize, Serialize};
 use std::fmt::Debug;
 
@@ -24,6 +25,13 @@ pub const DEFAULT_MINIMUM_HOP_COUNT: usize = 3;
 pub enum ProxyProtocol {
     HTTP,
     TLS,
+    SOCKS5,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForwardProxyConfig {
+    pub port: u16,
+    pub connect_ports: Vec<u16>,
 }
 
 // TODO: Based on the way it's used, this struct should comprise two elements: one, a nested
@@ -36,10 +44,24 @@ pub struct ClientRequestPayload_0v1 {
     pub sequenced_packet: SequencedPacket,
     pub target_hostname: Option<String>,
     pub target_port: u16,
+    #[serde(serialize_with = "serialize_wire_protocol")]
     pub protocol: ProxyProtocol,
     pub originator_public_key: PublicKey,
 }
 
+// Exit Nodes of earlier releases can't decode SOCKS5, and to an exit a SOCKS5 tunnel is nothing
+// but a TLS one: opaque bytes to a hostname and port. So SOCKS5 never leaves the ProxyServer.
+fn serialize_wire_protocol<S>(protocol: &ProxyProtocol, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let wire_protocol = match protocol {
+        ProxyProtocol::SOCKS5 => ProxyProtocol::TLS,
+        protocol => *protocol,
+    };
+    serde::Serialize::serialize(&wire_protocol, serializer)
+}
+
 impl From<ClientRequestPayload_0v1> for MessageType {
     fn from(payload: ClientRequestPayload_0v1) -> Self {
         MessageType::ClientRequest(VersionedData::new(
@@ -69,11 +91,29 @@ pub struct AddRouteResultMessage {
     pub result: Result<RouteQueryResponse, String>,
 }
 
+// The answer to a request for one more route to a stream's exit that avoids its other routes'
+// relays; None if there is no such route.
+#[derive(Message, Debug, PartialEq, Eq)]
+pub struct AddStripeRouteMessage {
+    pub stream_key: StreamKey,
+    pub route_query_response_opt: Option<RouteQueryResponse>,
+}
+
 #[derive(Message, Debug, PartialEq, Eq)]
 pub struct StreamKeyPurge {
     pub stream_key: StreamKey,
 }
 
+// Sent by the Neighborhood when Nodes vanish from its database or stop being its neighbors, so
+// that streams routed through them can find new routes before the browser notices.
+#[derive(Message, Clone, Debug, PartialEq, Eq)]
+pub struct NodesLostMsg {
+    pub public_keys: Vec<PublicKey>,
+}
+
+#[derive(Message, Clone, Debug, PartialEq, Eq)]
+pub struct SweepSilentStreams {}
+
 #[derive(Clone, PartialEq, Eq)]
 pub struct ProxyServerSubs {
     // ProxyServer will handle these messages:
@@ -81,11 +121,15 @@ pub struct ProxyServerSubs {
     pub from_dispatcher: Recipient<InboundClientData>,
     pub from_hopper: Recipient<ExpiredCoresPackage<ClientResponsePayload_0v1>>,
     pub dns_failure_from_hopper: Recipient<ExpiredCoresPackage<DnsResolveFailure_0v1>>,
+    pub service_refused_from_hopper: Recipient<ExpiredCoresPackage<ServiceRefused_0v1>>,
     pub add_return_route: Recipient<AddReturnRouteMessage>,
     pub stream_shutdown_sub: Recipient<StreamShutdownMsg>,
     pub node_from_ui: Recipient<NodeFromUiMessage>,
     pub route_result_sub: Recipient<AddRouteResultMessage>,
     pub schedule_stream_key_purge: Recipient<MessageScheduler<StreamKeyPurge>>,
+    pub config_change_msg_sub: Recipient<ConfigChangeMsg>,
+    pub nodes_lost_sub: Recipient<NodesLostMsg>,
+    pub stripe_route_sub: Recipient<AddStripeRouteMessage>,
 }
 
 impl Debug for ProxyServerSubs {
@@ -113,11 +157,18 @@ mod tests {
                 recorder,
                 ExpiredCoresPackage<DnsResolveFailure_0v1>
             ),
+            service_refused_from_hopper: recipient!(
+                recorder,
+                ExpiredCoresPackage<ServiceRefused_0v1>
+            ),
             add_return_route: recipient!(recorder, AddReturnRouteMessage),
             stream_shutdown_sub: recipient!(recorder, StreamShutdownMsg),
             node_from_ui: recipient!(recorder, NodeFromUiMessage),
             route_result_sub: recipient!(recorder, AddRouteResultMessage),
             schedule_stream_key_purge: recipient!(recorder, MessageScheduler<StreamKeyPurge>),
+            config_change_msg_sub: recipient!(recorder, ConfigChangeMsg),
+            nodes_lost_sub: recipient!(recorder, NodesLostMsg),
+            stripe_route_sub: recipient!(recorder, AddStripeRouteMessage),
         };
 
         assert_eq!(format!("{:?}", subject), "ProxyServerSubs");
@@ -0,0 +1,201 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::sub_lib::framer::FramedChunk;
+use crate::sub_lib::framer::Framer;
+
+pub const SOCKS5_VERSION: u8 = 0x05;
+pub const SOCKS5_ATYP_IPV4: u8 = 0x01;
+pub const SOCKS5_ATYP_DOMAIN_NAME: u8 = 0x03;
+pub const SOCKS5_ATYP_IPV6: u8 = 0x04;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Socks5FramerState {
+    SeekingGreeting,
+    SeekingRequest,
+    Tunneling,
+}
+
+// A SOCKS5 stream starts with two handshake messages from the client (the method-selection
+// greeting and the CONNECT request); after that, it's an opaque tunnel whose data is framed
+// exactly as it arrives.
+pub struct Socks5Framer {
+    state: Socks5FramerState,
+    data_so_far: Vec<u8>,
+}
+
+impl Default for Socks5Framer {
+    fn default() -> Self {
+        Self {
+            state: Socks5FramerState::SeekingGreeting,
+            data_so_far: vec![],
+        }
+    }
+}
+
+impl Framer for Socks5Framer {
+    fn add_data(&mut self, data: &[u8]) {
+        self.data_so_far.extend(data);
+    }
+
+    fn take_frame(&mut self) -> Option<FramedChunk> {
+        let frame_len = match self.state {
+            Socks5FramerState::SeekingGreeting => Self::greeting_len(&self.data_so_far)?,
+            Socks5FramerState::SeekingRequest => Self::request_len(&self.data_so_far)?,
+            Socks5FramerState::Tunneling if self.data_so_far.is_empty() => return None,
+            Socks5FramerState::Tunneling => self.data_so_far.len(),
+        };
+        if self.data_so_far.len() < frame_len {
+            return None;
+        }
+        let leftovers = self.data_so_far.split_off(frame_len);
+        let chunk = std::mem::replace(&mut self.data_so_far, leftovers);
+        self.state = match self.state {
+            Socks5FramerState::SeekingGreeting => Socks5FramerState::SeekingRequest,
+            _ => Socks5FramerState::Tunneling,
+        };
+        Some(FramedChunk {
+            chunk,
+            last_chunk: false,
+        })
+    }
+}
+
+impl Socks5Framer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn greeting_len(data: &[u8]) -> Option<usize> {
+        // VER NMETHODS METHODS...
+        let method_count = *data.get(1)? as usize;
+        Some(2 + method_count)
+    }
+
+    fn request_len(data: &[u8]) -> Option<usize> {
+        // VER CMD RSV ATYP DST.ADDR DST.PORT
+        let address_len = match *data.get(3)? {
+            SOCKS5_ATYP_IPV4 => 4,
+            SOCKS5_ATYP_DOMAIN_NAME => 1 + *data.get(4)? as usize,
+            SOCKS5_ATYP_IPV6 => 16,
+            // Unknown address type: hand over what we have and let the ProxyServer complain
+            _ => return Some(data.len()),
+        };
+        Some(4 + address_len + 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(SOCKS5_VERSION, 0x05);
+        assert_eq!(SOCKS5_ATYP_IPV4, 0x01);
+        assert_eq!(SOCKS5_ATYP_DOMAIN_NAME, 0x03);
+        assert_eq!(SOCKS5_ATYP_IPV6, 0x04);
+    }
+
+    #[test]
+    fn socks5_framer_waits_for_complete_greeting() {
+        let mut subject = Socks5Framer::new();
+
+        subject.add_data(&[0x05, 0x02, 0x00][..]);
+        let result = subject.take_frame();
+
+        assert_eq!(result, None);
+        assert_eq!(subject.data_so_far, vec![0x05, 0x02, 0x00]);
+        assert_eq!(subject.state, Socks5FramerState::SeekingGreeting);
+    }
+
+    #[test]
+    fn socks5_framer_frames_greeting_request_and_tunneled_data_separately() {
+        let mut subject = Socks5Framer::new();
+        let greeting = vec![0x05, 0x02, 0x00, 0x02];
+        let request = vec![
+            0x05, 0x01, 0x00, 0x03, 0x0B, b'e', b'x', b'a', b'm', b'p', b'l', b'e', b'.', b'c',
+            b'o', b'm', 0x1F, 0x90,
+        ];
+        let tunneled_data = b"booga".to_vec();
+        let mut all_data = greeting.clone();
+        all_data.extend(request.clone());
+        all_data.extend(tunneled_data.clone());
+
+        subject.add_data(&all_data);
+        let greeting_frame = subject.take_frame();
+        let request_frame = subject.take_frame();
+        let tunneled_frame = subject.take_frame();
+        let nothing = subject.take_frame();
+
+        assert_eq!(
+            greeting_frame,
+            Some(FramedChunk {
+                chunk: greeting,
+                last_chunk: false
+            })
+        );
+        assert_eq!(
+            request_frame,
+            Some(FramedChunk {
+                chunk: request,
+                last_chunk: false
+            })
+        );
+        assert_eq!(
+            tunneled_frame,
+            Some(FramedChunk {
+                chunk: tunneled_data,
+                last_chunk: false
+            })
+        );
+        assert_eq!(nothing, None);
+        assert_eq!(subject.state, Socks5FramerState::Tunneling);
+    }
+
+    #[test]
+    fn socks5_framer_waits_for_complete_ipv4_and_ipv6_requests() {
+        vec![
+            (SOCKS5_ATYP_IPV4, 4 + 4 + 2),
+            (SOCKS5_ATYP_IPV6, 4 + 16 + 2),
+        ]
+        .into_iter()
+        .for_each(|(atyp, request_len)| {
+            let mut subject = Socks5Framer::new();
+            subject.add_data(&[0x05, 0x01, 0x00]);
+            let _ = subject.take_frame();
+            let mut request = vec![0x05, 0x01, 0x00, atyp];
+            request.extend(vec![0x01; request_len - 4]);
+
+            subject.add_data(&request[..request_len - 1]);
+            let incomplete = subject.take_frame();
+            subject.add_data(&request[request_len - 1..]);
+            let complete = subject.take_frame();
+
+            assert_eq!(incomplete, None);
+            assert_eq!(
+                complete,
+                Some(FramedChunk {
+                    chunk: request,
+                    last_chunk: false
+                })
+            );
+        });
+    }
+
+    #[test]
+    fn socks5_framer_hands_over_request_with_unknown_address_type_as_is() {
+        let mut subject = Socks5Framer::new();
+        subject.add_data(&[0x05, 0x01, 0x00]);
+        let _ = subject.take_frame();
+
+        subject.add_data(&[0x05, 0x01, 0x00, 0x09, 0x01, 0x02]);
+        let result = subject.take_frame();
+
+        assert_eq!(
+            result,
+            Some(FramedChunk {
+                chunk: vec![0x05, 0x01, 0x00, 0x09, 0x01, 0x02],
+                last_chunk: false
+            })
+        );
+    }
+}
@@ -54,6 +54,25 @@ where
         }
     }
 
+    pub fn remove(&mut self, key: &K) -> Option<Rc<V>> {
+        self.remove_expired_entries();
+
+        self.data.borrow_mut().remove(key).map(|(result, _)| result)
+    }
+
+    pub fn clear(&mut self) {
+        self.data.borrow_mut().clear();
+    }
+
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        self.data
+            .borrow_mut()
+            .retain(|key, (value, _)| keep(key, value));
+    }
+
     fn remove_expired_entries(&self) {
         let now = Instant::now();
 
@@ -139,6 +158,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn ttl_hashmap_remove_returns_and_forgets_entry() {
+        let mut subject = TtlHashMap::new(Duration::from_millis(1000));
+        subject.insert(42u32, "Hello");
+        subject.insert(24u32, "World");
+
+        let result = subject.remove(&42u32);
+
+        assert_eq!(result.unwrap().as_ref(), &"Hello");
+        assert_eq!(subject.get(&42u32), None);
+        assert_eq!(subject.get(&24u32).unwrap().as_ref(), &"World");
+        assert_eq!(subject.remove(&42u32), None);
+    }
+
+    #[test]
+    fn ttl_hashmap_clear_forgets_every_entry() {
+        let mut subject = TtlHashMap::new(Duration::from_millis(1000));
+        subject.insert(42u32, "Hello");
+        subject.insert(24u32, "World");
+
+        subject.clear();
+
+        assert_eq!(subject.get(&42u32), None);
+        assert_eq!(subject.get(&24u32), None);
+    }
+
+    #[test]
+    fn ttl_hashmap_retain_forgets_entries_that_fail_the_test() {
+        let mut subject = TtlHashMap::new(Duration::from_millis(1000));
+        subject.insert(42u32, "Hello");
+        subject.insert(24u32, "World");
+
+        subject.retain(|key, value| *key == 42u32 && *value == "Hello");
+
+        assert_eq!(subject.get(&42u32), Some(Rc::new("Hello")));
+        assert_eq!(subject.get(&24u32), None);
+    }
+
     #[test]
     fn ttl_hashmap_get_preserves_otherwise_expired_entry() {
         // Note: You may think that these delays are far too long for unit tests, and that you can
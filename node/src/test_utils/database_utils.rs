@@ -25,40 +25,19 @@ pub fn bring_db_0_back_to_life_and_return_connection(db_path: &Path) -> Connecti
         Err(e) => panic!("Unexpected but serious error: {}", e),
         _ => (),
     };
-    let connection = Connection::open(&db_path).unwrap();
-    [
-        "create table config (
-            name text not null,
-            value text,
-            encrypted integer not null )",
-        "create unique index idx_config_name on config (name)",
-        "insert into config (name, value, encrypted) values ('example_encrypted', null, 1)",
-        "insert into config (name, value, encrypted) values ('clandestine_port', '2897', 0)",
-        "insert into config (name, value, encrypted) values ('consuming_wallet_derivation_path', null, 0)",
-        "insert into config (name, value, encrypted) values ('consuming_wallet_public_key', null, 0)",
-        "insert into config (name, value, encrypted) values ('earning_wallet_address', null, 0)",
-        "insert into config (name, value, encrypted) values ('schema_version', '0', 0)",
-        "insert into config (name, value, encrypted) values ('seed', null, 0)",
-        "insert into config (name, value, encrypted) values ('start_block', 8688171, 0)",
-        "insert into config (name, value, encrypted) values ('gas_price', '1', 0)",
-        "insert into config (name, value, encrypted) values ('past_neighbors', null, 1)",
-        "create table payable (
-                wallet_address text primary key,
-                balance integer not null,
-                last_paid_timestamp integer not null,
-                pending_payment_transaction text null
-            )",
-        "create unique index idx_payable_wallet_address on payable (wallet_address)",
-        "create table receivable (
-                wallet_address text primary key,
-                balance integer not null,
-                last_received_timestamp integer not null
-            )",
-        "create unique index idx_receivable_wallet_address on receivable (wallet_address)",
-        "create table banned ( wallet_address text primary key )",
-        "create unique index idx_banned_wallet_address on banned (wallet_address)"
-    ].iter().for_each(|statement|{connection.execute(statement,NO_PARAMS).unwrap();});
-    connection
+    let conn = Connection::open(&db_path).unwrap();
+    let file_path = current_dir()
+        .unwrap()
+        .join(Path::new("src"))
+        .join(Path::new("test_utils"))
+        .join(Path::new("database_version_0_sql.txt"));
+    let mut file = File::open(file_path).unwrap();
+    let mut buffer = String::new();
+    file.read_to_string(&mut buffer).unwrap();
+    buffer.lines().for_each(|stm| {
+        conn.execute(stm, []).unwrap();
+    });
+    conn
 }
 
 #[derive(Default)]
@@ -541,7 +541,9 @@ macro_rules! arbitrary_id_stamp_in_trait {
 
 #[cfg(test)]
 pub mod unshared_test_utils {
-    use crate::accountant::DEFAULT_PENDING_TOO_LONG_SEC;
+    use crate::accountant::{
+        DEFAULT_PENDING_SCANS_BEFORE_REPLACEMENT, DEFAULT_PENDING_TOO_LONG_SEC,
+    };
     use crate::apps::app_node;
     use crate::bootstrapper::BootstrapperConfig;
     use crate::daemon::{ChannelFactory, DaemonBindMessage};
@@ -702,6 +704,7 @@ pub mod unshared_test_utils {
         config.scan_intervals_opt = Some(ScanIntervals::default());
         config.suppress_initial_scans = false;
         config.when_pending_too_long_sec = DEFAULT_PENDING_TOO_LONG_SEC;
+        config.pending_scans_before_replacement = DEFAULT_PENDING_SCANS_BEFORE_REPLACEMENT;
         config.payment_thresholds_opt = Some(PaymentThresholds::default());
         config
     }
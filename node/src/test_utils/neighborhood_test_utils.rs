@@ -1,22 +1,35 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 use crate::bootstrapper::BootstrapperConfig;
 use crate::neighborhood::gossip::{GossipBuilder, GossipNodeRecord, Gossip_0v1};
+use crate::neighborhood::latency_prober::LatencyProber;
 use crate::neighborhood::neighborhood_database::NeighborhoodDatabase;
 use crate::neighborhood::node_record::{NodeRecord, NodeRecordInner_0v1};
+use crate::neighborhood::node_record_dao::{
+    NodeRecordDao, NodeRecordDaoError, PersistedNodeRecord,
+};
+use crate::neighborhood::reputation::NodeReputation;
+use crate::neighborhood::reputation_dao::{ReputationDao, ReputationDaoError};
 use crate::neighborhood::{AccessibleGossipRecord, Neighborhood, DEFAULT_MIN_HOPS};
 use crate::sub_lib::cryptde::PublicKey;
 use crate::sub_lib::cryptde::{CryptDE, PlainData};
 use crate::sub_lib::cryptde_null::CryptDENull;
-use crate::sub_lib::neighborhood::{Hops, NeighborhoodConfig, NeighborhoodMode, NodeDescriptor};
+use crate::sub_lib::neighborhood::{
+    Hops, NeighborLatencyMessage, NeighborhoodConfig, NeighborhoodMode, NodeDescriptor,
+};
 use crate::sub_lib::node_addr::NodeAddr;
 use crate::sub_lib::wallet::Wallet;
 use crate::test_utils::*;
+use actix::Recipient;
 use ethereum_types::H160;
+use masq_lib::as_any_ref_in_trait_impl;
 use masq_lib::blockchains::chains::Chain;
 use masq_lib::test_utils::utils::TEST_DEFAULT_CHAIN;
+use std::cell::RefCell;
 use std::convert::TryFrom;
 use std::net::IpAddr;
 use std::net::Ipv4Addr;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
 
 pub const MIN_HOPS_FOR_TEST: Hops = DEFAULT_MIN_HOPS;
 pub const DB_PATCH_SIZE_FOR_TEST: u8 = DEFAULT_MIN_HOPS as u8;
@@ -343,3 +356,149 @@ pub fn cryptdes_from_node_records(nodes: &[NodeRecord]) -> Vec<CryptDENull> {
         .map(|node| CryptDENull::from(node.public_key(), TEST_DEFAULT_CHAIN))
         .collect::<Vec<CryptDENull>>()
 }
+
+type UpdateNodeRecordsParams = Arc<Mutex<Vec<(Vec<PersistedNodeRecord>, Vec<PublicKey>)>>>;
+
+#[derive(Default)]
+pub struct NodeRecordDaoMock {
+    node_records_results: RefCell<Vec<Vec<PersistedNodeRecord>>>,
+    update_node_records_params: UpdateNodeRecordsParams,
+    update_node_records_results: RefCell<Vec<Result<(), NodeRecordDaoError>>>,
+}
+
+impl NodeRecordDao for NodeRecordDaoMock {
+    fn node_records(&self) -> Vec<PersistedNodeRecord> {
+        self.node_records_results.borrow_mut().remove(0)
+    }
+
+    fn update_node_records(
+        &mut self,
+        changed: &[PersistedNodeRecord],
+        removed: &[PublicKey],
+    ) -> Result<(), NodeRecordDaoError> {
+        self.update_node_records_params
+            .lock()
+            .unwrap()
+            .push((changed.to_vec(), removed.to_vec()));
+        self.update_node_records_results.borrow_mut().remove(0)
+    }
+}
+
+impl NodeRecordDaoMock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn node_records_result(self, result: Vec<PersistedNodeRecord>) -> Self {
+        self.node_records_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn update_node_records_params(mut self, params: &UpdateNodeRecordsParams) -> Self {
+        self.update_node_records_params = params.clone();
+        self
+    }
+
+    pub fn update_node_records_result(self, result: Result<(), NodeRecordDaoError>) -> Self {
+        self.update_node_records_results.borrow_mut().push(result);
+        self
+    }
+}
+
+type ProbeParams = Arc<Mutex<Vec<Vec<(PublicKey, SocketAddr)>>>>;
+
+#[derive(Default)]
+pub struct LatencyProberMock {
+    probe_params: ProbeParams,
+}
+
+impl LatencyProber for LatencyProberMock {
+    fn probe(
+        &self,
+        neighbors: Vec<(PublicKey, SocketAddr)>,
+        _recipient: Recipient<NeighborLatencyMessage>,
+    ) {
+        self.probe_params.lock().unwrap().push(neighbors);
+    }
+    as_any_ref_in_trait_impl!();
+}
+
+impl LatencyProberMock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn probe_params(mut self, params: &ProbeParams) -> Self {
+        self.probe_params = params.clone();
+        self
+    }
+}
+
+#[derive(Default)]
+pub struct ReputationDaoMock {
+    reputations_results: RefCell<Vec<Vec<(PublicKey, NodeReputation)>>>,
+    save_reputation_params: Arc<Mutex<Vec<(PublicKey, NodeReputation)>>>,
+    save_reputation_results: RefCell<Vec<Result<(), ReputationDaoError>>>,
+    forget_reputation_params: Arc<Mutex<Vec<PublicKey>>>,
+    forget_reputation_results: RefCell<Vec<Result<(), ReputationDaoError>>>,
+}
+
+impl ReputationDao for ReputationDaoMock {
+    fn reputations(&self) -> Vec<(PublicKey, NodeReputation)> {
+        self.reputations_results.borrow_mut().remove(0)
+    }
+
+    fn save_reputation(
+        &mut self,
+        public_key: &PublicKey,
+        reputation: &NodeReputation,
+    ) -> Result<(), ReputationDaoError> {
+        self.save_reputation_params
+            .lock()
+            .unwrap()
+            .push((public_key.clone(), reputation.clone()));
+        self.save_reputation_results.borrow_mut().remove(0)
+    }
+
+    fn forget_reputation(&mut self, public_key: &PublicKey) -> Result<(), ReputationDaoError> {
+        self.forget_reputation_params
+            .lock()
+            .unwrap()
+            .push(public_key.clone());
+        self.forget_reputation_results.borrow_mut().remove(0)
+    }
+}
+
+impl ReputationDaoMock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn reputations_result(self, result: Vec<(PublicKey, NodeReputation)>) -> Self {
+        self.reputations_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn save_reputation_params(
+        mut self,
+        params: &Arc<Mutex<Vec<(PublicKey, NodeReputation)>>>,
+    ) -> Self {
+        self.save_reputation_params = params.clone();
+        self
+    }
+
+    pub fn save_reputation_result(self, result: Result<(), ReputationDaoError>) -> Self {
+        self.save_reputation_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn forget_reputation_params(mut self, params: &Arc<Mutex<Vec<PublicKey>>>) -> Self {
+        self.forget_reputation_params = params.clone();
+        self
+    }
+
+    pub fn forget_reputation_result(self, result: Result<(), ReputationDaoError>) -> Self {
+        self.forget_reputation_results.borrow_mut().push(result);
+        self
+    }
+}
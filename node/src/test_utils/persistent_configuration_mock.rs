@@ -5,6 +5,9 @@
 use crate::database::rusqlite_wrappers::TransactionSafeWrapper;
 use crate::db_config::persistent_configuration::{PersistentConfigError, PersistentConfiguration};
 use crate::sub_lib::accountant::{PaymentThresholds, ScanIntervals};
+use crate::sub_lib::blockchain_bridge::TransactionFees;
+use crate::sub_lib::cryptde::{PrivateKey, PublicKey};
+use crate::sub_lib::exit_location::PreferredExits;
 use crate::sub_lib::neighborhood::{Hops, NodeDescriptor, RatePack};
 use crate::sub_lib::wallet::Wallet;
 use crate::test_utils::unshared_test_utils::arbitrary_id_stamp::ArbitraryIdStamp;
@@ -57,6 +60,11 @@ pub struct PersistentConfigurationMock {
         RefCell<Vec<Result<Option<Vec<NodeDescriptor>>, PersistentConfigError>>>,
     set_past_neighbors_params: Arc<Mutex<Vec<(Option<Vec<NodeDescriptor>>, String)>>>,
     set_past_neighbors_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
+    cryptde_keys_params: Arc<Mutex<Vec<String>>>,
+    cryptde_keys_results:
+        RefCell<Vec<Result<Option<(PrivateKey, PrivateKey)>, PersistentConfigError>>>,
+    set_cryptde_keys_params: Arc<Mutex<Vec<(PrivateKey, PrivateKey, String)>>>,
+    set_cryptde_keys_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
     start_block_params: Arc<Mutex<Vec<()>>>,
     start_block_results: RefCell<Vec<Result<Option<u64>, PersistentConfigError>>>,
     set_start_block_params: Arc<Mutex<Vec<Option<u64>>>>,
@@ -73,9 +81,21 @@ pub struct PersistentConfigurationMock {
     rate_pack_results: RefCell<Vec<Result<RatePack, PersistentConfigError>>>,
     set_rate_pack_params: Arc<Mutex<Vec<String>>>,
     set_rate_pack_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
+    max_rate_pack_results: RefCell<Vec<Result<Option<RatePack>, PersistentConfigError>>>,
+    set_max_rate_pack_params: Arc<Mutex<Vec<Option<RatePack>>>>,
+    set_max_rate_pack_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
+    preferred_exits_results: RefCell<Vec<Result<Option<PreferredExits>, PersistentConfigError>>>,
+    set_preferred_exits_params: Arc<Mutex<Vec<Option<PreferredExits>>>>,
+    set_preferred_exits_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
+    excluded_nodes_results: RefCell<Vec<Result<Vec<PublicKey>, PersistentConfigError>>>,
+    set_excluded_nodes_params: Arc<Mutex<Vec<Vec<PublicKey>>>>,
+    set_excluded_nodes_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
     scan_intervals_results: RefCell<Vec<Result<ScanIntervals, PersistentConfigError>>>,
     set_scan_intervals_params: Arc<Mutex<Vec<String>>>,
     set_scan_intervals_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
+    transaction_fees_results: RefCell<Vec<Result<Option<TransactionFees>, PersistentConfigError>>>,
+    set_transaction_fees_params: Arc<Mutex<Vec<TransactionFees>>>,
+    set_transaction_fees_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
     arbitrary_id_stamp_opt: Option<ArbitraryIdStamp>,
 }
 
@@ -230,6 +250,31 @@ impl PersistentConfiguration for PersistentConfigurationMock {
         self.set_past_neighbors_results.borrow_mut().remove(0)
     }
 
+    fn cryptde_keys(
+        &self,
+        db_password: &str,
+    ) -> Result<Option<(PrivateKey, PrivateKey)>, PersistentConfigError> {
+        self.cryptde_keys_params
+            .lock()
+            .unwrap()
+            .push(db_password.to_string());
+        self.cryptde_keys_results.borrow_mut().remove(0)
+    }
+
+    fn set_cryptde_keys(
+        &mut self,
+        main_key: &PrivateKey,
+        alias_key: &PrivateKey,
+        db_password: &str,
+    ) -> Result<(), PersistentConfigError> {
+        self.set_cryptde_keys_params.lock().unwrap().push((
+            main_key.clone(),
+            alias_key.clone(),
+            db_password.to_string(),
+        ));
+        self.set_cryptde_keys_results.borrow_mut().remove(0)
+    }
+
     fn start_block(&self) -> Result<Option<u64>, PersistentConfigError> {
         self.start_block_params.lock().unwrap().push(());
         Self::result_from(&self.start_block_results)
@@ -296,6 +341,51 @@ impl PersistentConfiguration for PersistentConfigurationMock {
         self.set_rate_pack_results.borrow_mut().remove(0)
     }
 
+    fn max_rate_pack(&self) -> Result<Option<RatePack>, PersistentConfigError> {
+        self.max_rate_pack_results.borrow_mut().remove(0)
+    }
+
+    fn set_max_rate_pack(
+        &mut self,
+        max_rate_pack_opt: Option<RatePack>,
+    ) -> Result<(), PersistentConfigError> {
+        self.set_max_rate_pack_params
+            .lock()
+            .unwrap()
+            .push(max_rate_pack_opt);
+        self.set_max_rate_pack_results.borrow_mut().remove(0)
+    }
+
+    fn preferred_exits(&self) -> Result<Option<PreferredExits>, PersistentConfigError> {
+        self.preferred_exits_results.borrow_mut().remove(0)
+    }
+
+    fn set_preferred_exits(
+        &mut self,
+        preferred_exits_opt: Option<PreferredExits>,
+    ) -> Result<(), PersistentConfigError> {
+        self.set_preferred_exits_params
+            .lock()
+            .unwrap()
+            .push(preferred_exits_opt);
+        self.set_preferred_exits_results.borrow_mut().remove(0)
+    }
+
+    fn excluded_nodes(&self) -> Result<Vec<PublicKey>, PersistentConfigError> {
+        self.excluded_nodes_results.borrow_mut().remove(0)
+    }
+
+    fn set_excluded_nodes(
+        &mut self,
+        excluded_nodes: Vec<PublicKey>,
+    ) -> Result<(), PersistentConfigError> {
+        self.set_excluded_nodes_params
+            .lock()
+            .unwrap()
+            .push(excluded_nodes);
+        self.set_excluded_nodes_results.borrow_mut().remove(0)
+    }
+
     fn scan_intervals(&self) -> Result<ScanIntervals, PersistentConfigError> {
         self.scan_intervals_results.borrow_mut().remove(0)
     }
@@ -308,6 +398,21 @@ impl PersistentConfiguration for PersistentConfigurationMock {
         self.set_scan_intervals_results.borrow_mut().remove(0)
     }
 
+    fn transaction_fees(&self) -> Result<Option<TransactionFees>, PersistentConfigError> {
+        self.transaction_fees_results.borrow_mut().remove(0)
+    }
+
+    fn set_transaction_fees(
+        &mut self,
+        transaction_fees: TransactionFees,
+    ) -> Result<(), PersistentConfigError> {
+        self.set_transaction_fees_params
+            .lock()
+            .unwrap()
+            .push(transaction_fees);
+        self.set_transaction_fees_results.borrow_mut().remove(0)
+    }
+
     arbitrary_id_stamp_in_trait_impl!();
 }
 
@@ -523,6 +628,32 @@ impl PersistentConfigurationMock {
         self
     }
 
+    pub fn cryptde_keys_params(mut self, params: &Arc<Mutex<Vec<String>>>) -> Self {
+        self.cryptde_keys_params = params.clone();
+        self
+    }
+
+    pub fn cryptde_keys_result(
+        self,
+        result: Result<Option<(PrivateKey, PrivateKey)>, PersistentConfigError>,
+    ) -> Self {
+        self.cryptde_keys_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn set_cryptde_keys_params(
+        mut self,
+        params: &Arc<Mutex<Vec<(PrivateKey, PrivateKey, String)>>>,
+    ) -> Self {
+        self.set_cryptde_keys_params = params.clone();
+        self
+    }
+
+    pub fn set_cryptde_keys_result(self, result: Result<(), PersistentConfigError>) -> Self {
+        self.set_cryptde_keys_results.borrow_mut().push(result);
+        self
+    }
+
     pub fn earning_wallet_result(
         self,
         result: Result<Option<Wallet>, PersistentConfigError>,
@@ -637,6 +768,63 @@ impl PersistentConfigurationMock {
         self
     }
 
+    pub fn max_rate_pack_result(
+        self,
+        result: Result<Option<RatePack>, PersistentConfigError>,
+    ) -> Self {
+        self.max_rate_pack_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn set_max_rate_pack_params(mut self, params: &Arc<Mutex<Vec<Option<RatePack>>>>) -> Self {
+        self.set_max_rate_pack_params = params.clone();
+        self
+    }
+
+    pub fn set_max_rate_pack_result(self, result: Result<(), PersistentConfigError>) -> Self {
+        self.set_max_rate_pack_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn preferred_exits_result(
+        self,
+        result: Result<Option<PreferredExits>, PersistentConfigError>,
+    ) -> Self {
+        self.preferred_exits_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn set_preferred_exits_params(
+        mut self,
+        params: &Arc<Mutex<Vec<Option<PreferredExits>>>>,
+    ) -> Self {
+        self.set_preferred_exits_params = params.clone();
+        self
+    }
+
+    pub fn set_preferred_exits_result(self, result: Result<(), PersistentConfigError>) -> Self {
+        self.set_preferred_exits_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn excluded_nodes_result(
+        self,
+        result: Result<Vec<PublicKey>, PersistentConfigError>,
+    ) -> Self {
+        self.excluded_nodes_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn set_excluded_nodes_params(mut self, params: &Arc<Mutex<Vec<Vec<PublicKey>>>>) -> Self {
+        self.set_excluded_nodes_params = params.clone();
+        self
+    }
+
+    pub fn set_excluded_nodes_result(self, result: Result<(), PersistentConfigError>) -> Self {
+        self.set_excluded_nodes_results.borrow_mut().push(result);
+        self
+    }
+
     pub fn scan_intervals_result(
         self,
         result: Result<ScanIntervals, PersistentConfigError>,
@@ -655,6 +843,27 @@ impl PersistentConfigurationMock {
         self
     }
 
+    pub fn transaction_fees_result(
+        self,
+        result: Result<Option<TransactionFees>, PersistentConfigError>,
+    ) -> Self {
+        self.transaction_fees_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn set_transaction_fees_params(
+        mut self,
+        params: &Arc<Mutex<Vec<TransactionFees>>>,
+    ) -> Self {
+        self.set_transaction_fees_params = params.clone();
+        self
+    }
+
+    pub fn set_transaction_fees_result(self, result: Result<(), PersistentConfigError>) -> Self {
+        self.set_transaction_fees_results.borrow_mut().push(result);
+        self
+    }
+
     pub fn mapping_protocol_result(
         self,
         result: Result<Option<AutomapProtocol>, PersistentConfigError>,
@@ -18,13 +18,15 @@ use crate::sub_lib::accountant::AccountantSubs;
 use crate::sub_lib::accountant::ReportExitServiceProvidedMessage;
 use crate::sub_lib::accountant::ReportRoutingServiceProvidedMessage;
 use crate::sub_lib::accountant::ReportServicesConsumedMessage;
+use crate::sub_lib::accountant::{PrioritizeCreditorMessage, ReportCoverTrafficMessage};
 use crate::sub_lib::blockchain_bridge::BlockchainBridgeSubs;
 use crate::sub_lib::blockchain_bridge::OutboundPaymentsInstructions;
 use crate::sub_lib::dispatcher::InboundClientData;
 use crate::sub_lib::dispatcher::{DispatcherSubs, StreamShutdownMsg};
 use crate::sub_lib::hopper::IncipientCoresPackage;
 use crate::sub_lib::hopper::{ExpiredCoresPackage, NoLookupIncipientCoresPackage};
-use crate::sub_lib::hopper::{HopperSubs, MessageType};
+use crate::sub_lib::hopper::{HopperSubs, MessageType, ServiceRefused_0v1};
+use crate::sub_lib::neighborhood::NeighborLatencyMessage;
 use crate::sub_lib::neighborhood::NeighborhoodSubs;
 use crate::sub_lib::neighborhood::{ConfigChangeMsg, ConnectionProgressMessage};
 
@@ -40,7 +42,8 @@ use crate::sub_lib::peer_actors::{BindMessage, NewPublicIp, StartMessage};
 use crate::sub_lib::proxy_client::{ClientResponsePayload_0v1, InboundServerData};
 use crate::sub_lib::proxy_client::{DnsResolveFailure_0v1, ProxyClientSubs};
 use crate::sub_lib::proxy_server::{
-    AddReturnRouteMessage, ClientRequestPayload_0v1, StreamKeyPurge,
+    AddReturnRouteMessage, AddStripeRouteMessage, ClientRequestPayload_0v1, NodesLostMsg,
+    StreamKeyPurge,
 };
 use crate::sub_lib::proxy_server::{AddRouteResultMessage, ProxyServerSubs};
 use crate::sub_lib::stream_handler_pool::DispatcherNodeQueryResponse;
@@ -141,12 +144,16 @@ recorder_message_handler_t_m_p!(ExpiredCoresPackage<DnsResolveFailure_0v1>);
 recorder_message_handler_t_m_p!(ExpiredCoresPackage<Gossip_0v1>);
 recorder_message_handler_t_m_p!(ExpiredCoresPackage<GossipFailure_0v1>);
 recorder_message_handler_t_m_p!(ExpiredCoresPackage<MessageType>);
+recorder_message_handler_t_m_p!(ExpiredCoresPackage<ServiceRefused_0v1>);
 recorder_message_handler_t_m_p!(InboundClientData);
 recorder_message_handler_t_m_p!(InboundServerData);
 recorder_message_handler_t_m_p!(IncipientCoresPackage);
+recorder_message_handler_t_m_p!(NeighborLatencyMessage);
 recorder_message_handler_t_m_p!(NewPublicIp);
 recorder_message_handler_t_m_p!(NodeFromUiMessage);
 recorder_message_handler_t_m_p!(NodeToUiMessage);
+recorder_message_handler_t_m_p!(NodesLostMsg);
+recorder_message_handler_t_m_p!(AddStripeRouteMessage);
 recorder_message_handler_t_m_p!(NoLookupIncipientCoresPackage);
 recorder_message_handler_t_p!(OutboundPaymentsInstructions);
 recorder_message_handler_t_m_p!(PendingPayableFingerprintSeeds);
@@ -158,6 +165,8 @@ recorder_message_handler_t_m_p!(RemoveStreamMsg);
 recorder_message_handler_t_m_p!(ReportExitServiceProvidedMessage);
 recorder_message_handler_t_m_p!(ReportRoutingServiceProvidedMessage);
 recorder_message_handler_t_m_p!(ReportServicesConsumedMessage);
+recorder_message_handler_t_m_p!(ReportCoverTrafficMessage);
+recorder_message_handler_t_m_p!(PrioritizeCreditorMessage);
 recorder_message_handler_t_m_p!(ReportTransactionReceipts);
 recorder_message_handler_t_m_p!(RequestTransactionReceipts);
 recorder_message_handler_t_m_p!(RetrieveTransactions);
@@ -200,14 +209,17 @@ impl Handler<RouteQueryMessage> for Recorder {
         msg: RouteQueryMessage,
         _ctx: &mut Self::Context,
     ) -> <Self as Handler<RouteQueryMessage>>::Result {
-        self.record(msg);
-        MessageResult(extract_response(
+        let response = extract_response(
             &mut self.route_query_responses,
             "No RouteQueryResponses prepared for RouteQueryMessage",
-        ))
+        );
+        self.handle_msg_t_m_p(msg);
+        MessageResult(response)
     }
 }
 
+matchable!(RouteQueryMessage);
+
 fn extract_response<T>(responses: &mut Vec<T>, err_msg: &str) -> T
 where
     T: Clone,
@@ -396,11 +408,15 @@ pub fn make_proxy_server_subs_from_recorder(addr: &Addr<Recorder>) -> ProxyServe
         from_dispatcher: recipient!(addr, InboundClientData),
         from_hopper: recipient!(addr, ExpiredCoresPackage<ClientResponsePayload_0v1>),
         dns_failure_from_hopper: recipient!(addr, ExpiredCoresPackage<DnsResolveFailure_0v1>),
+        service_refused_from_hopper: recipient!(addr, ExpiredCoresPackage<ServiceRefused_0v1>),
         add_return_route: recipient!(addr, AddReturnRouteMessage),
         stream_shutdown_sub: recipient!(addr, StreamShutdownMsg),
         node_from_ui: recipient!(addr, NodeFromUiMessage),
         route_result_sub: recipient!(addr, AddRouteResultMessage),
         schedule_stream_key_purge: recipient!(addr, MessageScheduler<StreamKeyPurge>),
+        config_change_msg_sub: recipient!(addr, ConfigChangeMsg),
+        nodes_lost_sub: recipient!(addr, NodesLostMsg),
+        stripe_route_sub: recipient!(addr, AddStripeRouteMessage),
     }
 }
 
@@ -461,6 +477,8 @@ pub fn make_accountant_subs_from_recorder(addr: &Addr<Recorder>) -> AccountantSu
         report_routing_service_provided: recipient!(addr, ReportRoutingServiceProvidedMessage),
         report_exit_service_provided: recipient!(addr, ReportExitServiceProvidedMessage),
         report_services_consumed: recipient!(addr, ReportServicesConsumedMessage),
+        report_cover_traffic: recipient!(addr, ReportCoverTrafficMessage),
+        prioritize_creditor: recipient!(addr, PrioritizeCreditorMessage),
         report_payable_payments_setup: recipient!(addr, BlockchainAgentWithContextMessage),
         report_inbound_payments: recipient!(addr, ReceivedPayments),
         init_pending_payable_fingerprints: recipient!(addr, PendingPayableFingerprintSeeds),
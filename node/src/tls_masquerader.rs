@@ -0,0 +1,302 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::discriminator::UnmaskedChunk;
+use crate::masquerader::MasqueradeError;
+use crate::masquerader::Masquerader;
+use masq_lib::logger::Logger;
+
+pub const TLS_APPLICATION_DATA: u8 = 0x17;
+pub const TLS_1_2_VERSION: [u8; 2] = [0x03, 0x03];
+pub const TLS_RECORD_HEADER_LEN: usize = 5;
+pub const TLS_MAX_RECORD_PAYLOAD_LEN: usize = 16384;
+
+// Data is dressed as a run of TLS 1.2 application-data records. Every record but the last is
+// full-sized; the last is short (possibly empty), and that's how the far end knows where the
+// masqueraded data ends.
+pub struct TlsMasquerader {
+    logger: Logger,
+}
+
+impl Masquerader for TlsMasquerader {
+    fn try_unmask(&self, item: &[u8]) -> Result<UnmaskedChunk, MasqueradeError> {
+        if item.first() != Some(&TLS_APPLICATION_DATA) {
+            return Err(MasqueradeError::NotThisMasquerader);
+        }
+        match Self::unmask(item) {
+            Ok(chunk) => Ok(chunk),
+            Err(err) => {
+                error!(self.logger, "{}", err);
+                Err(err)
+            }
+        }
+    }
+
+    fn mask(&self, data: &[u8]) -> Result<Vec<u8>, MasqueradeError> {
+        let record_count = data.len() / TLS_MAX_RECORD_PAYLOAD_LEN + 1;
+        let mut masked = Vec::with_capacity(data.len() + record_count * TLS_RECORD_HEADER_LEN);
+        let mut records = data.chunks(TLS_MAX_RECORD_PAYLOAD_LEN).peekable();
+        let mut last_record_len = 0;
+        while let Some(payload) = records.next() {
+            if records.peek().is_none() {
+                last_record_len = payload.len();
+            }
+            Self::append_record(&mut masked, payload);
+        }
+        if last_record_len == TLS_MAX_RECORD_PAYLOAD_LEN || data.is_empty() {
+            Self::append_record(&mut masked, &[]);
+        }
+        Ok(masked)
+    }
+}
+
+impl Default for TlsMasquerader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TlsMasquerader {
+    pub fn new() -> TlsMasquerader {
+        TlsMasquerader {
+            logger: Logger::new("TlsMasquerader"),
+        }
+    }
+
+    // None means more data is needed before the end of the masquerade can be found. A malformed
+    // header ends the masquerade early; try_unmask will complain about what's left.
+    pub fn frame_length(data: &[u8]) -> Option<usize> {
+        let mut offset = 0;
+        loop {
+            let header = data.get(offset..(offset + TLS_RECORD_HEADER_LEN))?;
+            if !Self::is_valid_header(header) {
+                return Some(offset);
+            }
+            let payload_len = Self::payload_len(header);
+            offset += TLS_RECORD_HEADER_LEN + payload_len;
+            if payload_len < TLS_MAX_RECORD_PAYLOAD_LEN {
+                return if offset <= data.len() {
+                    Some(offset)
+                } else {
+                    None
+                };
+            }
+        }
+    }
+
+    fn unmask(data: &[u8]) -> Result<UnmaskedChunk, MasqueradeError> {
+        let mut unmasked = Vec::with_capacity(data.len());
+        let mut remainder = data;
+        loop {
+            if remainder.len() < TLS_RECORD_HEADER_LEN {
+                return Err(MasqueradeError::LowLevelDataError(format!(
+                    "TLS record header truncated after {} bytes",
+                    data.len() - remainder.len()
+                )));
+            }
+            let (header, rest) = remainder.split_at(TLS_RECORD_HEADER_LEN);
+            if !Self::is_valid_header(header) {
+                return Err(MasqueradeError::MidLevelDataError(format!(
+                    "Not a TLS 1.2 application-data record header: {:?}",
+                    header
+                )));
+            }
+            let payload_len = Self::payload_len(header);
+            if payload_len > rest.len() {
+                return Err(MasqueradeError::LowLevelDataError(format!(
+                    "TLS record claims {} bytes but contains only {}",
+                    payload_len,
+                    rest.len()
+                )));
+            }
+            let (payload, rest) = rest.split_at(payload_len);
+            unmasked.extend(payload);
+            if payload_len < TLS_MAX_RECORD_PAYLOAD_LEN {
+                if !rest.is_empty() {
+                    return Err(MasqueradeError::MidLevelDataError(format!(
+                        "{} extra bytes after final TLS record",
+                        rest.len()
+                    )));
+                }
+                return Ok(UnmaskedChunk::new(unmasked, true, false));
+            }
+            remainder = rest;
+        }
+    }
+
+    fn append_record(masked: &mut Vec<u8>, payload: &[u8]) {
+        masked.push(TLS_APPLICATION_DATA);
+        masked.extend(TLS_1_2_VERSION);
+        masked.extend((payload.len() as u16).to_be_bytes());
+        masked.extend(payload);
+    }
+
+    fn is_valid_header(header: &[u8]) -> bool {
+        header[0] == TLS_APPLICATION_DATA && header[1..3] == TLS_1_2_VERSION
+    }
+
+    fn payload_len(header: &[u8]) -> usize {
+        ((header[3] as usize) << 8) | (header[4] as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(TLS_APPLICATION_DATA, 0x17);
+        assert_eq!(TLS_1_2_VERSION, [0x03, 0x03]);
+        assert_eq!(TLS_RECORD_HEADER_LEN, 5);
+        assert_eq!(TLS_MAX_RECORD_PAYLOAD_LEN, 16384);
+    }
+
+    #[test]
+    fn short_data_is_masked_as_single_record() {
+        let subject = TlsMasquerader::new();
+
+        let result = subject.mask(b"booga").unwrap();
+
+        assert_eq!(
+            result,
+            vec![0x17, 0x03, 0x03, 0x00, 0x05, b'b', b'o', b'o', b'g', b'a']
+        );
+    }
+
+    #[test]
+    fn empty_data_is_masked_as_empty_record() {
+        let subject = TlsMasquerader::new();
+
+        let result = subject.mask(&[]).unwrap();
+
+        assert_eq!(result, vec![0x17, 0x03, 0x03, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn long_data_is_masked_as_full_records_followed_by_short_record() {
+        let data = vec![0xA5; TLS_MAX_RECORD_PAYLOAD_LEN * 2 + 3];
+        let subject = TlsMasquerader::new();
+
+        let result = subject.mask(&data).unwrap();
+
+        assert_eq!(result.len(), data.len() + 3 * TLS_RECORD_HEADER_LEN);
+        assert_eq!(&result[0..5], &[0x17, 0x03, 0x03, 0x40, 0x00]);
+        assert_eq!(&result[16389..16394], &[0x17, 0x03, 0x03, 0x40, 0x00]);
+        assert_eq!(&result[32778..32783], &[0x17, 0x03, 0x03, 0x00, 0x03]);
+        assert_eq!(TlsMasquerader::frame_length(&result), Some(result.len()));
+        assert_eq!(
+            subject.try_unmask(&result),
+            Ok(UnmaskedChunk::new(data, true, false))
+        );
+    }
+
+    #[test]
+    fn data_that_exactly_fills_records_is_terminated_by_empty_record() {
+        let data = vec![0x5A; TLS_MAX_RECORD_PAYLOAD_LEN];
+        let subject = TlsMasquerader::new();
+
+        let result = subject.mask(&data).unwrap();
+
+        assert_eq!(result.len(), data.len() + 2 * TLS_RECORD_HEADER_LEN);
+        assert_eq!(&result[16389..], &[0x17, 0x03, 0x03, 0x00, 0x00]);
+        assert_eq!(TlsMasquerader::frame_length(&result), Some(result.len()));
+        assert_eq!(
+            subject.try_unmask(&result),
+            Ok(UnmaskedChunk::new(data, true, false))
+        );
+    }
+
+    #[test]
+    fn frame_length_wants_more_data_until_final_record_is_complete() {
+        let masked = TlsMasquerader::new().mask(b"booga").unwrap();
+
+        assert_eq!(TlsMasquerader::frame_length(&masked[0..4]), None);
+        assert_eq!(TlsMasquerader::frame_length(&masked[0..9]), None);
+        assert_eq!(TlsMasquerader::frame_length(&masked), Some(10));
+        let mut with_more = masked.clone();
+        with_more.extend(&masked);
+        assert_eq!(TlsMasquerader::frame_length(&with_more), Some(10));
+    }
+
+    #[test]
+    fn frame_length_stops_at_malformed_header() {
+        let mut data = vec![0x17, 0x03, 0x03, 0x40, 0x00];
+        data.extend(vec![0x00; TLS_MAX_RECORD_PAYLOAD_LEN]);
+        data.extend(&[0x16, 0x03, 0x01, 0x00, 0x00]);
+
+        let result = TlsMasquerader::frame_length(&data);
+
+        assert_eq!(result, Some(TLS_MAX_RECORD_PAYLOAD_LEN + 5));
+    }
+
+    #[test]
+    fn try_unmask_rejects_data_for_other_masqueraders() {
+        let subject = TlsMasquerader::new();
+
+        let result = subject.try_unmask(b"{\"bodyText\": \"booga\"}");
+
+        assert_eq!(result, Err(MasqueradeError::NotThisMasquerader));
+    }
+
+    #[test]
+    fn try_unmask_complains_about_truncated_record() {
+        init_test_logging();
+        let subject = TlsMasquerader::new();
+
+        let result = subject.try_unmask(&[0x17, 0x03, 0x03, 0x00, 0x05, b'b', b'o']);
+
+        assert_eq!(
+            result,
+            Err(MasqueradeError::LowLevelDataError(
+                "TLS record claims 5 bytes but contains only 2".to_string()
+            ))
+        );
+        TestLogHandler::new().exists_log_containing(
+            "ERROR: TlsMasquerader: Low-level data error: TLS record claims 5 bytes but contains only 2",
+        );
+    }
+
+    #[test]
+    fn try_unmask_complains_about_missing_final_record() {
+        let mut data = vec![0x17, 0x03, 0x03, 0x40, 0x00];
+        data.extend(vec![0x00; TLS_MAX_RECORD_PAYLOAD_LEN]);
+        let subject = TlsMasquerader::new();
+
+        let result = subject.try_unmask(&data);
+
+        assert_eq!(
+            result,
+            Err(MasqueradeError::LowLevelDataError(
+                "TLS record header truncated after 16389 bytes".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn try_unmask_complains_about_bad_record_header() {
+        let subject = TlsMasquerader::new();
+
+        let result = subject.try_unmask(&[0x17, 0x03, 0x01, 0x00, 0x00]);
+
+        assert_eq!(
+            result,
+            Err(MasqueradeError::MidLevelDataError(
+                "Not a TLS 1.2 application-data record header: [23, 3, 1, 0, 0]".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn try_unmask_complains_about_data_after_final_record() {
+        let subject = TlsMasquerader::new();
+
+        let result = subject.try_unmask(&[0x17, 0x03, 0x03, 0x00, 0x01, b'b', b'x', b'y']);
+
+        assert_eq!(
+            result,
+            Err(MasqueradeError::MidLevelDataError(
+                "2 extra bytes after final TLS record".to_string()
+            ))
+        );
+    }
+}